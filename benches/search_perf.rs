@@ -125,6 +125,9 @@ fn make_bench_hit(id: &str, score: f32) -> SearchHit {
         source_id: "local".to_string(),
         origin_kind: "local".to_string(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     }
 }
 
@@ -1,6 +1,7 @@
 //! Connectors for agent histories.
 
 use crate::sources::config::{PathMapping, Platform};
+use crate::sources::filter::FileFilter;
 use crate::sources::provenance::Origin;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
@@ -224,6 +225,9 @@ pub struct ScanRoot {
     /// Applied at ingest time so filters work across sources.
     pub workspace_rewrites: Vec<PathMapping>,
 
+    /// The source's file selection rules, re-checked on what its mirror holds.
+    pub filter: FileFilter,
+
     /// Cached trie for fast workspace rewriting (Opt 1.5).
     /// Lazily initialized on first use of rewrite_workspace.
     rewrite_trie: OnceCell<Arc<PathTrie>>,
@@ -236,6 +240,7 @@ impl Clone for ScanRoot {
             origin: self.origin.clone(),
             platform: self.platform,
             workspace_rewrites: self.workspace_rewrites.clone(),
+            filter: self.filter.clone(),
             // Each clone gets its own lazy trie cell
             rewrite_trie: OnceCell::new(),
         }
@@ -250,6 +255,7 @@ impl ScanRoot {
             origin: Origin::local(),
             platform: None,
             workspace_rewrites: Vec::new(),
+            filter: FileFilter::default(),
             rewrite_trie: OnceCell::new(),
         }
     }
@@ -261,6 +267,7 @@ impl ScanRoot {
             origin,
             platform,
             workspace_rewrites: Vec::new(),
+            filter: FileFilter::default(),
             rewrite_trie: OnceCell::new(),
         }
     }
@@ -593,6 +600,7 @@ mod tests {
             source_id: "work-laptop".to_string(),
             kind: crate::sources::provenance::SourceKind::Ssh,
             host: Some("work.local".to_string()),
+            owner: None,
         };
         let root = ScanRoot::remote(
             PathBuf::from("/data/remotes/work"),
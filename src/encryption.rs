@@ -87,3 +87,26 @@ pub fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
     let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), ikm);
     prk.to_vec()
 }
+
+pub fn x25519_public_key(secret: &[u8]) -> Result<Vec<u8>, String> {
+    let secret: [u8; 32] = secret
+        .try_into()
+        .map_err(|_| "x25519 secret must be 32 bytes".to_string())?;
+    let secret = x25519_dalek::StaticSecret::from(secret);
+    Ok(x25519_dalek::PublicKey::from(&secret).as_bytes().to_vec())
+}
+
+pub fn x25519_shared_secret(secret: &[u8], peer_public: &[u8]) -> Result<Vec<u8>, String> {
+    let secret: [u8; 32] = secret
+        .try_into()
+        .map_err(|_| "x25519 secret must be 32 bytes".to_string())?;
+    let peer_public: [u8; 32] = peer_public
+        .try_into()
+        .map_err(|_| "x25519 public key must be 32 bytes".to_string())?;
+    let shared = x25519_dalek::StaticSecret::from(secret)
+        .diffie_hellman(&x25519_dalek::PublicKey::from(peer_public));
+    if !shared.was_contributory() {
+        return Err("x25519 shared secret is all zeros".to_string());
+    }
+    Ok(shared.as_bytes().to_vec())
+}
@@ -0,0 +1,605 @@
+//! Offline search-quality evaluation (`cass eval`).
+//!
+//! Runs a judgments file (query → relevant `source_path`/line pairs) through
+//! each search mode and TUI ranking mode, scores the ranked lists with nDCG@k,
+//! MRR and recall@k, and diffs two reports so ranking changes can be checked
+//! against a baseline instead of by eye.
+
+use anyhow::{Context, Result, anyhow, bail};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::search::canonicalize::{canonicalize_for_embedding, content_hash};
+use crate::search::embedder::Embedder;
+use crate::search::hash_embedder::HashEmbedder;
+use crate::search::query::{FusionConfig, SearchClient, SearchFilters, SearchHit, SearchMode};
+use crate::search::ranking::{RankingMode, apply_ranking_mode};
+use crate::search::vector_index::{
+    Quantization, SemanticFilterMaps, VectorEntry, VectorIndex, role_code_from_str,
+};
+use crate::sources::provenance::LOCAL_SOURCE_ID;
+
+pub const DEFAULT_K: usize = 10;
+/// Candidates fetched per query before a ranking mode re-orders them.
+const MIN_CANDIDATE_DEPTH: usize = 50;
+/// Same threshold `cass search` uses before retrying with wildcards.
+const SPARSE_THRESHOLD: usize = 3;
+
+fn default_k() -> usize {
+    DEFAULT_K
+}
+
+fn default_grade() -> u32 {
+    1
+}
+
+/// Relevance judgments loaded from JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Judgments {
+    /// Cutoff for nDCG@k and recall@k
+    #[serde(default = "default_k")]
+    pub k: usize,
+    pub queries: Vec<JudgedQuery>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JudgedQuery {
+    pub query: String,
+    /// Optional agent filter applied when running this query
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    pub relevant: Vec<Relevant>,
+}
+
+/// One relevant result. `source_path` matches a hit's path exactly or as a
+/// trailing path suffix, so judgments stay valid when the corpus is indexed
+/// from a temporary directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relevant {
+    pub source_path: String,
+    /// 1-based message line (`SearchHit::line_number`); `None` = any hit in the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Graded relevance used as nDCG gain (default 1)
+    #[serde(default = "default_grade")]
+    pub grade: u32,
+}
+
+impl Relevant {
+    fn matches(&self, hit: &SearchHit) -> bool {
+        let path_matches = hit.source_path == self.source_path
+            || Path::new(&hit.source_path).ends_with(&self.source_path);
+        path_matches && self.line.is_none_or(|line| hit.line_number == Some(line))
+    }
+}
+
+impl Judgments {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("reading judgments {}", path.display()))?;
+        let judgments: Judgments = serde_json::from_str(&raw)
+            .with_context(|| format!("parsing judgments {}", path.display()))?;
+        if judgments.queries.is_empty() {
+            bail!("judgments file {} has no queries", path.display());
+        }
+        if judgments.k == 0 {
+            bail!("judgments k must be at least 1");
+        }
+        Ok(judgments)
+    }
+}
+
+/// Which embedder backs the semantic and hybrid runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalEmbedder {
+    /// The installed ML model and its vector index (skips semantic runs if missing)
+    #[default]
+    Model,
+    /// Deterministic hash embedder over the database; needs no model (for CI)
+    Hash,
+}
+
+#[derive(Debug, Clone)]
+pub struct EvalOptions {
+    pub modes: Vec<SearchMode>,
+    pub rankings: Vec<RankingMode>,
+    pub fusion: FusionConfig,
+    /// Overrides the judgments file's `k`
+    pub k: Option<usize>,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        Self {
+            modes: vec![
+                SearchMode::Lexical,
+                SearchMode::Semantic,
+                SearchMode::Hybrid,
+            ],
+            rankings: RankingMode::ALL.to_vec(),
+            fusion: FusionConfig::default(),
+            k: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryScore {
+    pub query: String,
+    pub ndcg: f64,
+    pub reciprocal_rank: f64,
+    pub recall: f64,
+}
+
+/// Metrics for one search mode × ranking mode combination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    /// `<mode>/<ranking>`, e.g. `hybrid/balanced`
+    pub run: String,
+    pub mode: String,
+    pub ranking: String,
+    pub ndcg: f64,
+    pub mrr: f64,
+    pub recall: f64,
+    pub per_query: Vec<QueryScore>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedMode {
+    pub mode: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub k: usize,
+    pub queries: usize,
+    pub runs: Vec<RunReport>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedMode>,
+}
+
+impl EvalReport {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("reading eval report {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("parsing eval report {}", path.display()))
+    }
+}
+
+pub fn mode_name(mode: SearchMode) -> &'static str {
+    match mode {
+        SearchMode::Lexical => "lexical",
+        SearchMode::Semantic => "semantic",
+        SearchMode::Hybrid => "hybrid",
+    }
+}
+
+/// Gain at each ranked position. A judgment is credited once, at the first hit
+/// that matches it, so duplicate hits from the same session don't inflate scores.
+pub fn judge(hits: &[SearchHit], relevant: &[Relevant]) -> Vec<u32> {
+    let mut credited = vec![false; relevant.len()];
+    hits.iter()
+        .map(|hit| {
+            relevant
+                .iter()
+                .enumerate()
+                .find(|(i, r)| !credited[*i] && r.matches(hit))
+                .map_or(0, |(i, r)| {
+                    credited[i] = true;
+                    r.grade
+                })
+        })
+        .collect()
+}
+
+fn dcg(gains: impl IntoIterator<Item = u32>) -> f64 {
+    gains
+        .into_iter()
+        .enumerate()
+        .map(|(i, g)| (2f64.powi(g as i32) - 1.0) / ((i + 2) as f64).log2())
+        .sum()
+}
+
+/// nDCG@k with exponential gain; 0 when nothing is relevant.
+pub fn ndcg_at_k(gains: &[u32], relevant: &[Relevant], k: usize) -> f64 {
+    let mut ideal: Vec<u32> = relevant.iter().map(|r| r.grade).collect();
+    ideal.sort_unstable_by(|a, b| b.cmp(a));
+    let ideal_dcg = dcg(ideal.into_iter().take(k));
+    if ideal_dcg <= 0.0 {
+        return 0.0;
+    }
+    dcg(gains.iter().copied().take(k)) / ideal_dcg
+}
+
+/// 1 / rank of the first relevant hit (0 if none was found).
+pub fn reciprocal_rank(gains: &[u32]) -> f64 {
+    gains
+        .iter()
+        .position(|g| *g > 0)
+        .map_or(0.0, |pos| 1.0 / (pos + 1) as f64)
+}
+
+/// Fraction of judgments found in the top `k`.
+pub fn recall_at_k(gains: &[u32], relevant: &[Relevant], k: usize) -> f64 {
+    if relevant.is_empty() {
+        return 0.0;
+    }
+    let found = gains.iter().take(k).filter(|g| **g > 0).count();
+    found as f64 / relevant.len() as f64
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(s, c), v| (s + v, c + 1));
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+/// Score every query under each requested mode and ranking.
+///
+/// `semantic` is the outcome of attaching a semantic context to `client`;
+/// when it failed, semantic and hybrid runs are reported as skipped.
+pub fn run_eval(
+    client: &SearchClient,
+    judgments: &Judgments,
+    options: &EvalOptions,
+    semantic: Result<(), String>,
+) -> Result<EvalReport> {
+    let k = options.k.unwrap_or(judgments.k).max(1);
+    let depth = k.max(MIN_CANDIDATE_DEPTH);
+    let mut runs = Vec::new();
+    let mut skipped = Vec::new();
+
+    for &mode in &options.modes {
+        if mode != SearchMode::Lexical
+            && let Err(reason) = &semantic
+        {
+            skipped.push(SkippedMode {
+                mode: mode_name(mode).to_string(),
+                reason: reason.clone(),
+            });
+            continue;
+        }
+
+        let mut candidates = Vec::with_capacity(judgments.queries.len());
+        for judged in &judgments.queries {
+            let filters = SearchFilters {
+                agents: judged.agents.iter().cloned().collect(),
+                ..SearchFilters::default()
+            };
+            let hits = match mode {
+                SearchMode::Lexical => {
+                    client
+                        .search_with_fallback(&judged.query, filters, depth, 0, SPARSE_THRESHOLD)?
+                        .hits
+                }
+                SearchMode::Semantic => client.search_semantic(&judged.query, filters, depth, 0)?,
+                SearchMode::Hybrid => {
+                    client
+                        .search_hybrid(
+                            &judged.query,
+                            &judged.query,
+                            filters,
+                            depth,
+                            0,
+                            SPARSE_THRESHOLD,
+                            &options.fusion,
+                        )?
+                        .hits
+                }
+            };
+            candidates.push(hits);
+        }
+
+        for &ranking in &options.rankings {
+            let per_query: Vec<QueryScore> = judgments
+                .queries
+                .iter()
+                .zip(&candidates)
+                .map(|(judged, hits)| {
+                    let mut ranked = hits.clone();
+                    apply_ranking_mode(&mut ranked, mode, ranking);
+                    let gains = judge(&ranked, &judged.relevant);
+                    QueryScore {
+                        query: judged.query.clone(),
+                        ndcg: ndcg_at_k(&gains, &judged.relevant, k),
+                        reciprocal_rank: reciprocal_rank(&gains),
+                        recall: recall_at_k(&gains, &judged.relevant, k),
+                    }
+                })
+                .collect();
+            runs.push(RunReport {
+                run: format!("{}/{}", mode_name(mode), ranking.as_str()),
+                mode: mode_name(mode).to_string(),
+                ranking: ranking.as_str().to_string(),
+                ndcg: mean(per_query.iter().map(|q| q.ndcg)),
+                mrr: mean(per_query.iter().map(|q| q.reciprocal_rank)),
+                recall: mean(per_query.iter().map(|q| q.recall)),
+                per_query,
+            });
+        }
+    }
+
+    Ok(EvalReport {
+        k,
+        queries: judgments.queries.len(),
+        runs,
+        skipped,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub current: f64,
+    pub delta: f64,
+}
+
+impl MetricDelta {
+    fn new(baseline: f64, current: f64) -> Self {
+        Self {
+            baseline,
+            current,
+            delta: current - baseline,
+        }
+    }
+}
+
+/// A query whose nDCG moved between the two reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryDelta {
+    pub query: String,
+    pub ndcg: MetricDelta,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunDiff {
+    pub run: String,
+    pub ndcg: MetricDelta,
+    pub mrr: MetricDelta,
+    pub recall: MetricDelta,
+    /// Per-query nDCG changes, largest drop first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_queries: Vec<QueryDelta>,
+}
+
+impl RunDiff {
+    /// Largest drop across nDCG, MRR and recall (0 if nothing got worse).
+    pub fn worst_drop(&self) -> f64 {
+        [self.ndcg.delta, self.mrr.delta, self.recall.delta]
+            .into_iter()
+            .fold(0.0, |worst, d| worst.max(-d))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportDiff {
+    pub runs: Vec<RunDiff>,
+    /// Runs in the baseline that the current report lacks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_runs: Vec<String>,
+}
+
+impl ReportDiff {
+    pub fn worst_drop(&self) -> f64 {
+        self.runs
+            .iter()
+            .map(RunDiff::worst_drop)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Compare `current` against `baseline`, run by run.
+pub fn diff_reports(baseline: &EvalReport, current: &EvalReport) -> ReportDiff {
+    const EPSILON: f64 = 1e-9;
+    let current_runs: HashMap<&str, &RunReport> =
+        current.runs.iter().map(|r| (r.run.as_str(), r)).collect();
+    let mut diff = ReportDiff::default();
+
+    for before in &baseline.runs {
+        let Some(after) = current_runs.get(before.run.as_str()) else {
+            diff.missing_runs.push(before.run.clone());
+            continue;
+        };
+        let after_scores: HashMap<&str, f64> = after
+            .per_query
+            .iter()
+            .map(|q| (q.query.as_str(), q.ndcg))
+            .collect();
+        let mut changed_queries: Vec<QueryDelta> = before
+            .per_query
+            .iter()
+            .filter_map(|q| {
+                let now = *after_scores.get(q.query.as_str())?;
+                ((now - q.ndcg).abs() > EPSILON).then(|| QueryDelta {
+                    query: q.query.clone(),
+                    ndcg: MetricDelta::new(q.ndcg, now),
+                })
+            })
+            .collect();
+        changed_queries.sort_by(|a, b| a.ndcg.delta.total_cmp(&b.ndcg.delta));
+
+        diff.runs.push(RunDiff {
+            run: before.run.clone(),
+            ndcg: MetricDelta::new(before.ndcg, after.ndcg),
+            mrr: MetricDelta::new(before.mrr, after.mrr),
+            recall: MetricDelta::new(before.recall, after.recall),
+            changed_queries,
+        });
+    }
+    diff
+}
+
+/// Attach a hash-embedder semantic context built from every message in the
+/// database, so semantic and hybrid modes can be evaluated without a model.
+pub fn attach_hash_semantic_context(client: &SearchClient, db_path: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("opening {}", db_path.display()))?;
+    let embedder = HashEmbedder::default_dimension();
+    let maps = SemanticFilterMaps::from_connection(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT m.id, m.content, m.created_at, m.role, c.agent_id, c.workspace_id, c.source_id
+         FROM messages m JOIN conversations c ON m.conversation_id = c.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<i64>>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, i64>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+            row.get::<_, Option<String>>(6)?,
+        ))
+    })?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let (id, content, created_at, role, agent_id, workspace_id, source_id) = row?;
+        let canonical = canonicalize_for_embedding(&content);
+        if canonical.trim().is_empty() {
+            continue;
+        }
+        let source_id = source_id.unwrap_or_else(|| LOCAL_SOURCE_ID.to_string());
+        entries.push(VectorEntry {
+            message_id: u64::try_from(id)?,
+            created_at_ms: created_at.unwrap_or(0),
+            agent_id: u32::try_from(agent_id)?,
+            workspace_id: u32::try_from(workspace_id.unwrap_or(0))?,
+            source_id: maps.source_id(&source_id),
+            role: role_code_from_str(&role).unwrap_or(0),
+            chunk_idx: 0,
+            content_hash: content_hash(&canonical),
+            vector: embedder
+                .embed(&canonical)
+                .map_err(|e| anyhow!("hash embedding failed: {e}"))?,
+        });
+    }
+
+    let index = VectorIndex::build(
+        embedder.id(),
+        "eval",
+        embedder.dimension(),
+        Quantization::F32,
+        entries,
+    )?;
+    client.set_semantic_context(Arc::new(embedder), index, maps, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::MatchType;
+
+    fn hit(path: &str, line: usize) -> SearchHit {
+        SearchHit {
+            title: path.to_string(),
+            snippet: String::new(),
+            content: String::new(),
+            score: 1.0,
+            source_path: path.to_string(),
+            agent: "codex".to_string(),
+            workspace: String::new(),
+            workspace_original: None,
+            created_at: None,
+            line_number: Some(line),
+            match_type: MatchType::Exact,
+            source_id: "local".to_string(),
+            origin_kind: "local".to_string(),
+            origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
+        }
+    }
+
+    fn relevant(path: &str, line: Option<usize>, grade: u32) -> Relevant {
+        Relevant {
+            source_path: path.to_string(),
+            line,
+            grade,
+        }
+    }
+
+    #[test]
+    fn judge_matches_path_suffix_and_credits_once() {
+        let hits = vec![
+            hit("/tmp/x/.codex/sessions/a.jsonl", 1),
+            hit("/tmp/x/.codex/sessions/a.jsonl", 2),
+            hit("/tmp/x/.codex/sessions/b.jsonl", 4),
+            hit("/tmp/x/.codex/sessions/xb.jsonl", 4),
+        ];
+        let judged = vec![
+            relevant("sessions/a.jsonl", None, 2),
+            relevant("b.jsonl", Some(4), 1),
+        ];
+        // Second hit from a.jsonl earns nothing; "xb.jsonl" is not a path suffix of "b.jsonl"
+        assert_eq!(judge(&hits, &judged), vec![2, 0, 1, 0]);
+    }
+
+    #[test]
+    fn metrics_match_hand_computed_values() {
+        let judged = vec![relevant("a", None, 1), relevant("b", None, 1)];
+        let gains = vec![0, 1, 0, 1];
+
+        assert!((reciprocal_rank(&gains) - 0.5).abs() < 1e-12);
+        assert!((recall_at_k(&gains, &judged, 2) - 0.5).abs() < 1e-12);
+        assert!((recall_at_k(&gains, &judged, 4) - 1.0).abs() < 1e-12);
+
+        let ideal = 1.0 + 1.0 / 3f64.log2();
+        let actual = 1.0 / 3f64.log2() + 1.0 / 5f64.log2();
+        assert!((ndcg_at_k(&gains, &judged, 4) - actual / ideal).abs() < 1e-12);
+        assert_eq!(ndcg_at_k(&[1, 1], &judged, 2), 1.0);
+        assert_eq!(ndcg_at_k(&[0, 0], &judged, 2), 0.0);
+        assert_eq!(reciprocal_rank(&[0, 0]), 0.0);
+    }
+
+    fn report(run: &str, ndcg: f64, per_query: &[(&str, f64)]) -> EvalReport {
+        EvalReport {
+            k: 10,
+            queries: per_query.len(),
+            runs: vec![RunReport {
+                run: run.to_string(),
+                mode: "lexical".to_string(),
+                ranking: "balanced".to_string(),
+                ndcg,
+                mrr: 1.0,
+                recall: 1.0,
+                per_query: per_query
+                    .iter()
+                    .map(|(q, n)| QueryScore {
+                        query: q.to_string(),
+                        ndcg: *n,
+                        reciprocal_rank: 1.0,
+                        recall: 1.0,
+                    })
+                    .collect(),
+            }],
+            skipped: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn diff_reports_flags_drops_and_missing_runs() {
+        let baseline = report("lexical/balanced", 0.9, &[("auth", 1.0), ("retry", 0.8)]);
+        let current = report("lexical/balanced", 0.7, &[("auth", 0.6), ("retry", 0.8)]);
+
+        let diff = diff_reports(&baseline, &current);
+        assert_eq!(diff.runs.len(), 1);
+        assert!((diff.runs[0].ndcg.delta + 0.2).abs() < 1e-12);
+        assert_eq!(diff.runs[0].changed_queries.len(), 1);
+        assert_eq!(diff.runs[0].changed_queries[0].query, "auth");
+        assert!((diff.worst_drop() - 0.2).abs() < 1e-12);
+
+        let renamed = report("hybrid/balanced", 0.9, &[("auth", 1.0)]);
+        let diff = diff_reports(&baseline, &renamed);
+        assert!(diff.runs.is_empty());
+        assert_eq!(diff.missing_runs, vec!["lexical/balanced".to_string()]);
+        assert_eq!(diff.worst_drop(), 0.0);
+    }
+}
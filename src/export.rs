@@ -370,6 +370,9 @@ mod tests {
             source_id: "local".to_string(),
             origin_kind: "local".to_string(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         }
     }
 
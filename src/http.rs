@@ -0,0 +1,248 @@
+//! Minimal HTTP/1.1 plumbing shared by `cass pages serve` and
+//! `cass serve-ingest`.
+//!
+//! Both servers answer one request per connection with `Connection: close`.
+//! The request head is read under a byte budget and a wall-clock deadline,
+//! before any authentication: a client can neither grow a line without end
+//! nor hold a connection open by dripping bytes.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// Largest request head accepted
+pub const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// Time a client has to send the whole request head
+pub const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed request line and its headers
+#[derive(Debug, Clone)]
+pub struct RequestHead {
+    pub method: String,
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    /// Value of the first header called `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Read the request head from `reader` within [`MAX_REQUEST_HEAD`] bytes
+/// and `timeout`
+///
+/// The inner result is the status to answer with when the head is
+/// unusable: too large, too slow or malformed. The stream's read timeout
+/// is left at the time remaining; callers reading a body set their own.
+pub fn read_request_head(
+    reader: &mut BufReader<&TcpStream>,
+    timeout: Duration,
+) -> io::Result<Result<RequestHead, &'static str>> {
+    let deadline = Instant::now() + timeout;
+    let mut budget = MAX_REQUEST_HEAD;
+    let mut lines = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        match read_line(reader, &mut line, &mut budget, deadline)? {
+            LineRead::Line => {}
+            LineRead::TooLarge => return Ok(Err("431 Request Header Fields Too Large")),
+            LineRead::TimedOut => return Ok(Err("408 Request Timeout")),
+        }
+        if line.is_empty() || line == b"\r\n" || line == b"\n" {
+            break;
+        }
+        lines.push(String::from_utf8_lossy(&line).trim_end().to_string());
+    }
+
+    let mut lines = lines.into_iter();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(Err("400 Bad Request"));
+    };
+    let headers = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    Ok(Ok(RequestHead {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers,
+    }))
+}
+
+enum LineRead {
+    /// A full line, or what was left before the client closed
+    Line,
+    TooLarge,
+    TimedOut,
+}
+
+/// Read up to and including the next `\n`, spending `budget` and never
+/// waiting past `deadline`
+///
+/// Bytes are taken from the buffer as they arrive, so a line that never
+/// ends stops at the budget instead of filling memory.
+fn read_line(
+    reader: &mut BufReader<&TcpStream>,
+    line: &mut Vec<u8>,
+    budget: &mut usize,
+    deadline: Instant,
+) -> io::Result<LineRead> {
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(LineRead::TimedOut);
+        }
+        reader.get_ref().set_read_timeout(Some(remaining))?;
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                return Ok(LineRead::TimedOut);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok(LineRead::Line);
+        }
+        let (take, done) = match available.iter().position(|b| *b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (available.len(), false),
+        };
+        if take > *budget {
+            return Ok(LineRead::TooLarge);
+        }
+        line.extend_from_slice(&available[..take]);
+        reader.consume(take);
+        *budget -= take;
+        if done {
+            return Ok(LineRead::Line);
+        }
+    }
+}
+
+/// Write a complete response and close out the exchange
+///
+/// `head_only` leaves the body out (for `HEAD`) while keeping its length.
+pub fn write_response(
+    mut stream: &TcpStream,
+    status: &str,
+    content_type: &str,
+    extra_headers: &[(&str, &str)],
+    body: &[u8],
+    head_only: bool,
+) -> io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in extra_headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes())?;
+    if !head_only {
+        stream.write_all(body)?;
+    }
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Feed `send` to a fresh connection and parse the head on the server side
+    fn parse(
+        timeout: Duration,
+        send: impl FnOnce(TcpStream) + Send + 'static,
+    ) -> Result<RequestHead, &'static str> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let sender = std::thread::spawn(move || send(client));
+        let (stream, _) = listener.accept().unwrap();
+        let result = read_request_head(&mut BufReader::new(&stream), timeout).unwrap();
+        drop(stream);
+        let _ = sender.join();
+        result
+    }
+
+    #[test]
+    fn parses_request_line_and_headers() {
+        let head = parse(HEAD_TIMEOUT, |mut client| {
+            client
+                .write_all(b"PUT /v1/files/a?offset=3 HTTP/1.1\r\nContent-Length: 5\r\nX-A:  b \r\n\r\nbody")
+                .unwrap();
+        })
+        .unwrap();
+        assert_eq!(head.method, "PUT");
+        assert_eq!(head.target, "/v1/files/a?offset=3");
+        assert_eq!(head.header("content-length"), Some("5"));
+        assert_eq!(head.header("x-a"), Some("b"));
+        assert_eq!(head.header("missing"), None);
+    }
+
+    #[test]
+    fn endless_lines_stop_at_the_budget() {
+        let status = parse(HEAD_TIMEOUT, |mut client| {
+            let chunk = vec![b'a'; 64 * 1024];
+            // The server hangs up once the budget is spent
+            for _ in 0..64 {
+                if client.write_all(&chunk).is_err() {
+                    break;
+                }
+            }
+        })
+        .unwrap_err();
+        assert_eq!(status, "431 Request Header Fields Too Large");
+
+        let status = parse(HEAD_TIMEOUT, |mut client| {
+            let header = format!("X-Pad: {}\r\n", "a".repeat(1024));
+            let mut request = b"GET / HTTP/1.1\r\n".to_vec();
+            for _ in 0..32 {
+                request.extend_from_slice(header.as_bytes());
+            }
+            let _ = client.write_all(&request);
+        })
+        .unwrap_err();
+        assert_eq!(status, "431 Request Header Fields Too Large");
+    }
+
+    #[test]
+    fn slow_clients_run_out_of_time() {
+        let start = Instant::now();
+        let status = parse(Duration::from_millis(300), |mut client| {
+            // Each byte arrives well within any per-read timeout
+            for byte in b"GET / HTTP/1.1\r\nHost: x\r\n\r\n" {
+                if client.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        })
+        .unwrap_err();
+        assert_eq!(status, "408 Request Timeout");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn empty_requests_are_malformed() {
+        let status = parse(HEAD_TIMEOUT, drop).unwrap_err();
+        assert_eq!(status, "400 Bad Request");
+    }
+}
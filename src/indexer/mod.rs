@@ -18,8 +18,9 @@ use crate::connectors::{
 };
 use crate::search::tantivy::{TantivyIndex, index_dir};
 use crate::sources::config::{Platform, SourcesConfig};
-use crate::sources::provenance::{Origin, Source};
-use crate::sources::sync::path_to_safe_dirname;
+use crate::sources::filter::{FileFilter, SyncFilter};
+use crate::sources::provenance::{Origin, Source, SourceKind};
+use crate::sources::sync::{SyncEngine, path_to_safe_dirname};
 use crate::storage::sqlite::SqliteStorage;
 
 #[derive(Debug, Clone)]
@@ -188,7 +189,7 @@ pub fn run_index(
                 let ctx = crate::connectors::ScanContext::local_default(data_dir.clone(), since_ts);
                 match conn.scan(&ctx) {
                     Ok(mut local_convs) => {
-                        let local_origin = Origin::local();
+                        let local_origin = local_origin();
                         for conv in &mut local_convs {
                             inject_provenance(conv, &local_origin);
                         }
@@ -211,6 +212,7 @@ pub fn run_index(
                     );
                     match conn.scan(&ctx) {
                         Ok(mut remote_convs) => {
+                            remote_convs.retain(|conv| passes_root_filter(conv, root));
                             for conv in &mut remote_convs {
                                 inject_provenance(conv, &root.origin);
                                 apply_workspace_rewrite(conv, &root.workspace_rewrites);
@@ -527,6 +529,7 @@ fn reset_storage(storage: &mut SqliteStorage) -> Result<()> {
          DELETE FROM fts_messages;
          DELETE FROM snippets;
          DELETE FROM messages;
+         DELETE FROM conversation_sources;
          DELETE FROM conversations;
          DELETE FROM agents;
          DELETE FROM workspaces;
@@ -584,7 +587,7 @@ fn reindex_paths(
         let mut convs = conn.scan(&ctx)?;
 
         // Inject local provenance into all conversations (P2.2)
-        let local_origin = Origin::local();
+        let local_origin = local_origin();
         for conv in &mut convs {
             inject_provenance(conv, &local_origin);
         }
@@ -713,7 +716,7 @@ fn sync_sources_config_to_db(storage: &SqliteStorage) {
         }
     };
 
-    for source in config.remote_sources() {
+    for source in config.mirrored_sources() {
         let platform = source.platform.map(|p| match p {
             Platform::Macos => "macos".to_string(),
             Platform::Linux => "linux".to_string(),
@@ -722,19 +725,23 @@ fn sync_sources_config_to_db(storage: &SqliteStorage) {
 
         let config_json = serde_json::json!({
             "paths": source.paths.clone(),
+            "url": source.url.clone(),
+            "branch": source.branch.clone(),
             "path_mappings": source.path_mappings.clone(),
             "sync_schedule": source.sync_schedule,
+            "filter": source.filter,
         });
 
         let record = Source {
             id: source.name.clone(),
             kind: source.source_type,
-            host_label: source.host.clone(),
+            host_label: source.host.clone().or_else(|| source.url.clone()),
             machine_id: None,
             platform,
             config_json: Some(config_json),
             created_at: None,
             updated_at: None,
+            owner: source.effective_owner(),
         };
 
         if let Err(e) = storage.upsert_source(&record) {
@@ -765,37 +772,29 @@ pub fn build_scan_roots(storage: &SqliteStorage, data_dir: &Path) -> Vec<ScanRoo
     if dotenvy::var("CASS_IGNORE_SOURCES_CONFIG").is_err()
         && let Ok(config) = SourcesConfig::load()
     {
-        let remotes: Vec<_> = config.remote_sources().collect();
+        let remotes: Vec<_> = config.mirrored_sources().collect();
         if !remotes.is_empty() {
+            let engine = SyncEngine::new(data_dir);
             for source in remotes {
                 let origin = Origin {
                     source_id: source.name.clone(),
                     kind: source.source_type,
-                    host: source.host.clone(),
+                    host: source.host.clone().or_else(|| source.url.clone()),
+                    owner: source.effective_owner(),
                 };
                 let platform = source.platform;
                 let workspace_rewrites = source.path_mappings.clone();
+                // Validated when the config was loaded
+                let filter = FileFilter::new(&source.filter).unwrap_or_default();
 
-                for path in &source.paths {
-                    let expanded_path = if path.starts_with("~/") {
-                        path.to_string()
-                    } else if path.starts_with('~') {
-                        path.replacen('~', "~/", 1)
-                    } else {
-                        path.to_string()
-                    };
-                    let safe_name = path_to_safe_dirname(&expanded_path);
-                    let mirror_path = data_dir
-                        .join("remotes")
-                        .join(&source.name)
-                        .join("mirror")
-                        .join(&safe_name);
+                for mirror_path in engine.scan_dirs(source) {
                     if !mirror_path.exists() {
                         continue;
                     }
 
                     let mut scan_root = ScanRoot::remote(mirror_path, origin.clone(), platform);
                     scan_root.workspace_rewrites = workspace_rewrites.clone();
+                    scan_root.filter = filter.clone();
                     roots.push(scan_root);
                 }
             }
@@ -849,11 +848,21 @@ pub fn build_scan_roots(storage: &SqliteStorage, data_dir: &Path) -> Vec<ScanRoo
                 })
                 .unwrap_or_default();
 
-            if let Some(paths) = source
+            let filter = source
                 .config_json
                 .as_ref()
-                .and_then(|cfg| cfg.get("paths"))
-                .and_then(|arr| arr.as_array())
+                .and_then(|cfg| cfg.get("filter"))
+                .and_then(|f| serde_json::from_value::<SyncFilter>(f.clone()).ok())
+                .and_then(|f| FileFilter::new(&f).ok())
+                .unwrap_or_default();
+
+            // Git and HTTP sources keep their own layout under the mirror
+            if !matches!(source.kind, SourceKind::Git | SourceKind::Http)
+                && let Some(paths) = source
+                    .config_json
+                    .as_ref()
+                    .and_then(|cfg| cfg.get("paths"))
+                    .and_then(|arr| arr.as_array())
             {
                 for path_val in paths {
                     let Some(path) = path_val.as_str() else {
@@ -880,9 +889,11 @@ pub fn build_scan_roots(storage: &SqliteStorage, data_dir: &Path) -> Vec<ScanRoo
                         source_id: source.id.clone(),
                         kind: source.kind,
                         host: source.host_label.clone(),
+                        owner: source.owner.clone(),
                     };
                     let mut scan_root = ScanRoot::remote(mirror_path, origin, platform);
                     scan_root.workspace_rewrites = workspace_rewrites.clone();
+                    scan_root.filter = filter.clone();
                     roots.push(scan_root);
                 }
                 continue;
@@ -896,9 +907,11 @@ pub fn build_scan_roots(storage: &SqliteStorage, data_dir: &Path) -> Vec<ScanRoo
                     source_id: source.id.clone(),
                     kind: source.kind,
                     host: source.host_label.clone(),
+                    owner: source.owner.clone(),
                 };
                 let mut scan_root = ScanRoot::remote(mirror_path, origin, platform);
                 scan_root.workspace_rewrites = workspace_rewrites;
+                scan_root.filter = filter;
 
                 roots.push(scan_root);
             }
@@ -908,6 +921,50 @@ pub fn build_scan_roots(storage: &SqliteStorage, data_dir: &Path) -> Vec<ScanRoo
     roots
 }
 
+/// Whether a conversation scanned from a mirror passes its source's filter.
+///
+/// The age limit is checked against the conversation's last activity, and
+/// the file's mtime when it has no timestamps.
+fn passes_root_filter(conv: &NormalizedConversation, root: &ScanRoot) -> bool {
+    let filter = &root.filter;
+    if filter.is_empty() {
+        return true;
+    }
+    if !filter.allows_agent(&conv.agent_slug) {
+        return false;
+    }
+    let rel = conv
+        .source_path
+        .strip_prefix(&root.path)
+        .unwrap_or(&conv.source_path);
+    if !filter.allows_path(rel) {
+        return false;
+    }
+    let meta = std::fs::metadata(&conv.source_path).ok();
+    if let Some(meta) = &meta
+        && !filter.allows_size(meta.len())
+    {
+        return false;
+    }
+    let modified = conv
+        .ended_at
+        .or(conv.started_at)
+        .map(|ms| std::time::UNIX_EPOCH + Duration::from_millis(ms.max(0) as u64))
+        .or_else(|| meta.and_then(|m| m.modified().ok()));
+    filter.allows_modified(modified, std::time::SystemTime::now())
+}
+
+/// Provenance of sessions found on this machine, owned by the local user
+/// (the config's `owner`, or the login name).
+fn local_origin() -> Origin {
+    let config = if dotenvy::var("CASS_IGNORE_SOURCES_CONFIG").is_ok() {
+        SourcesConfig::default()
+    } else {
+        SourcesConfig::load().unwrap_or_default()
+    };
+    Origin::local().with_owner(config.local_owner())
+}
+
 /// Inject provenance metadata into a conversation from a scan root's origin.
 ///
 /// This adds the `cass.origin` field to the conversation's metadata JSON
@@ -931,7 +988,8 @@ fn inject_provenance(conv: &mut NormalizedConversation, origin: &Origin) {
                 serde_json::json!({
                     "source_id": origin.source_id,
                     "kind": origin.kind.as_str(),
-                    "host": origin.host
+                    "host": origin.host,
+                    "owner": origin.owner
                 }),
             );
         }
@@ -1019,27 +1077,23 @@ pub mod persist {
     use crate::search::tantivy::TantivyIndex;
     use crate::storage::sqlite::{InsertOutcome, SqliteStorage};
 
-    /// Extract provenance (source_id, origin_host) from conversation metadata.
+    /// Extract provenance (source_id, origin_host, owner) from conversation metadata.
     ///
-    /// Looks for `metadata.cass.origin` object with source_id and host fields.
-    /// Returns ("local", None) if no provenance is found.
-    fn extract_provenance(metadata: &serde_json::Value) -> (String, Option<String>) {
-        let source_id = metadata
-            .get("cass")
-            .and_then(|c| c.get("origin"))
-            .and_then(|o| o.get("source_id"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("local")
-            .to_string();
-
-        let origin_host = metadata
-            .get("cass")
-            .and_then(|c| c.get("origin"))
-            .and_then(|o| o.get("host"))
-            .and_then(|v| v.as_str())
-            .map(String::from);
+    /// Looks for `metadata.cass.origin` object with source_id, host and owner fields.
+    /// Returns ("local", None, None) if no provenance is found.
+    fn extract_provenance(
+        metadata: &serde_json::Value,
+    ) -> (String, Option<String>, Option<String>) {
+        let origin = metadata.get("cass").and_then(|c| c.get("origin"));
+        let field = |name: &str| {
+            origin
+                .and_then(|o| o.get(name))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
 
-        (source_id, origin_host)
+        let source_id = field("source_id").unwrap_or_else(|| "local".to_string());
+        (source_id, field("host"), field("owner"))
     }
 
     /// Convert a NormalizedConversation to the internal Conversation type for SQLite storage.
@@ -1047,7 +1101,7 @@ pub mod persist {
     /// Extracts provenance from `metadata.cass.origin` if present, otherwise defaults to local.
     pub fn map_to_internal(conv: &NormalizedConversation) -> Conversation {
         // Extract provenance from metadata (P2.2)
-        let (source_id, origin_host) = extract_provenance(&conv.metadata);
+        let (source_id, origin_host, owner) = extract_provenance(&conv.metadata);
 
         Conversation {
             id: None,
@@ -1087,6 +1141,7 @@ pub mod persist {
                 .collect(),
             source_id,
             origin_host,
+            owner,
         }
     }
 
@@ -1114,8 +1169,7 @@ pub mod persist {
         let internal_conv = map_to_internal(conv);
 
         let InsertOutcome {
-            conversation_id: _,
-            inserted_indices,
+            inserted_indices, ..
         } = storage.insert_conversation_tree(agent_id, workspace_id, &internal_conv)?;
 
         // Only add newly inserted messages to the Tantivy index (incremental)
@@ -1175,7 +1229,10 @@ pub mod persist {
 
         // Add newly inserted messages to Tantivy index
         for (conv, outcome) in convs.iter().zip(outcomes.iter()) {
-            if force_tantivy_reindex {
+            if force_tantivy_reindex && outcome.linked {
+                // Stored (and indexed) under the source it was first seen in
+                continue;
+            } else if force_tantivy_reindex {
                 // Rebuild path: the Tantivy index is known-empty, so index all messages.
                 t_index.add_messages(conv, &conv.messages)?;
             } else if !outcome.inserted_indices.is_empty() {
@@ -1319,6 +1376,7 @@ mod tests {
                         .collect(),
                     source_id: "local".to_string(),
                     origin_host: None,
+                    owner: None,
                 },
             )
             .unwrap();
@@ -1643,11 +1701,70 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
         assert_eq!(origin_obj.get("kind").unwrap().as_str(), Some("local"));
     }
 
+    #[test]
+    fn root_filter_drops_unwanted_remote_conversations() {
+        let root = |filter: SyncFilter| {
+            let mut root = ScanRoot::remote(
+                PathBuf::from("/mirror/projects"),
+                Origin::remote("laptop"),
+                None,
+            );
+            root.filter = FileFilter::new(&filter).unwrap();
+            root
+        };
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let conv = |agent: &str, path: &str, ended_at: i64| NormalizedConversation {
+            agent_slug: agent.into(),
+            source_path: PathBuf::from(path),
+            ended_at: Some(ended_at),
+            ..norm_conv(Some("c"), vec![])
+        };
+
+        let by_agent = root(SyncFilter {
+            agents: vec!["codex".into()],
+            ..Default::default()
+        });
+        assert!(passes_root_filter(
+            &conv("codex", "/mirror/projects/a.jsonl", now_ms),
+            &by_agent
+        ));
+        assert!(!passes_root_filter(
+            &conv("claude_code", "/mirror/projects/a.jsonl", now_ms),
+            &by_agent
+        ));
+
+        let by_path = root(SyncFilter {
+            exclude: vec!["debug".into()],
+            max_age_days: Some(7),
+            ..Default::default()
+        });
+        assert!(passes_root_filter(
+            &conv("codex", "/mirror/projects/a/b.jsonl", now_ms),
+            &by_path
+        ));
+        assert!(!passes_root_filter(
+            &conv("codex", "/mirror/projects/debug/b.jsonl", now_ms),
+            &by_path
+        ));
+        let old = now_ms - 8 * 24 * 3600 * 1000;
+        assert!(!passes_root_filter(
+            &conv("codex", "/mirror/projects/a/b.jsonl", old),
+            &by_path
+        ));
+
+        // Without rules everything passes
+        assert!(passes_root_filter(
+            &conv("x", "/elsewhere/b.log", old),
+            &root(SyncFilter::default())
+        ));
+    }
+
     #[test]
     fn inject_provenance_handles_remote_origin() {
         let mut conv = norm_conv(Some("test"), vec![norm_msg(0, 100)]);
 
-        let origin = Origin::remote_with_host("laptop", "user@laptop.local");
+        let origin =
+            Origin::remote_with_host("laptop", "user@laptop.local").with_owner(Some("bob".into()));
         inject_provenance(&mut conv, &origin);
 
         let cass = conv.metadata.get("cass").expect("cass field should exist");
@@ -1661,6 +1778,7 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
             origin_obj.get("host").unwrap().as_str(),
             Some("user@laptop.local")
         );
+        assert_eq!(origin_obj.get("owner").unwrap().as_str(), Some("bob"));
     }
 
     #[test]
@@ -1678,6 +1796,7 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
         });
         assert_eq!(conv.source_id, "local");
         assert!(conv.origin_host.is_none());
+        assert!(conv.owner.is_none());
     }
 
     #[test]
@@ -1687,7 +1806,8 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
                 "origin": {
                     "source_id": "laptop",
                     "kind": "ssh",
-                    "host": "user@laptop.local"
+                    "host": "user@laptop.local",
+                    "owner": "bob"
                 }
             }
         });
@@ -1704,6 +1824,7 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
         });
         assert_eq!(conv.source_id, "laptop");
         assert_eq!(conv.origin_host, Some("user@laptop.local".to_string()));
+        assert_eq!(conv.owner, Some("bob".to_string()));
     }
 
     #[test]
@@ -1745,6 +1866,7 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
                 host_label: Some("user@laptop.local".to_string()),
                 machine_id: None,
                 platform: Some("linux".to_string()),
+                owner: None,
                 config_json: None,
                 created_at: None,
                 updated_at: None,
@@ -1791,6 +1913,7 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
                 host_label: Some("user@host".to_string()),
                 machine_id: None,
                 platform: None,
+                owner: None,
                 config_json: None,
                 created_at: None,
                 updated_at: None,
@@ -1,7 +1,9 @@
 pub mod bookmarks;
 pub mod connectors;
 pub mod encryption;
+pub mod eval;
 pub mod export;
+pub mod http;
 pub mod indexer;
 pub mod model;
 pub mod pages;
@@ -238,9 +240,32 @@ pub enum Commands {
         /// Enables chained searches: `cass search "query1" --robot-format sessions | cass search "query2" --sessions-from -`
         #[arg(long)]
         sessions_from: Option<String>,
+        /// Filter to sessions owned by this user (can be specified multiple times)
+        #[arg(long)]
+        owner: Vec<String>,
+        /// Include every owner's sessions even when `hide_other_owners` is set
+        #[arg(long, conflicts_with = "owner")]
+        all_owners: bool,
         /// Search mode: lexical (default), semantic, or hybrid
         #[arg(long, value_enum)]
         mode: Option<crate::search::query::SearchMode>,
+        /// Hybrid fusion strategy: rrf (default), weighted (normalized scores), or auto
+        /// (weight chosen from the query type). Implies --mode hybrid when --mode is omitted.
+        #[arg(long, value_enum)]
+        fusion: Option<crate::search::query::FusionStrategy>,
+        /// Semantic share of the hybrid score, 0.0 (lexical only) to 1.0 (semantic only).
+        /// Overrides the `auto` heuristic. Default: 0.5
+        #[arg(long)]
+        semantic_weight: Option<f32>,
+        /// RRF smoothing constant for rrf/auto fusion (default: 60)
+        #[arg(long)]
+        rrf_k: Option<f32>,
+        /// Also search remote sources live: run `cass search` on each SSH source and merge the hits
+        #[arg(long)]
+        federated: bool,
+        /// Per-host deadline in seconds for --federated; slower hosts contribute partial results
+        #[arg(long, default_value_t = crate::sources::federated::DEFAULT_FEDERATED_TIMEOUT_SECS)]
+        federated_timeout: u64,
     },
     /// Show statistics about indexed data
     Stats {
@@ -379,6 +404,129 @@ pub enum Commands {
         #[arg(long, default_value_t = 5)]
         limit: usize,
     },
+    /// Find messages semantically similar to an existing session or message ("more like this")
+    Similar {
+        /// Path to the source session file (`source_path` from search results)
+        source_path: PathBuf,
+        /// Use the message at this line number as the anchor (default: whole session)
+        #[arg(long, short = 'n')]
+        line: Option<usize>,
+        /// Filter by agent slug (can be specified multiple times)
+        #[arg(long)]
+        agent: Vec<String>,
+        /// Filter by workspace path (can be specified multiple times)
+        #[arg(long)]
+        workspace: Vec<String>,
+        /// Max results
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Offset for pagination (start at Nth result)
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Filter to last N days
+        #[arg(long)]
+        days: Option<u32>,
+        /// Filter to today only
+        #[arg(long)]
+        today: bool,
+        /// Filter to yesterday only
+        #[arg(long)]
+        yesterday: bool,
+        /// Filter to last 7 days
+        #[arg(long)]
+        week: bool,
+        /// Filter to entries since ISO date (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)
+        #[arg(long)]
+        since: Option<String>,
+        /// Filter to entries until ISO date
+        #[arg(long)]
+        until: Option<String>,
+        /// Filter by source: 'local', 'remote', 'all', or a specific source hostname
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Cluster sessions into topics using their embeddings ("what have we been working on")
+    Topics {
+        /// Number of topics (default: chosen from the number of sessions)
+        #[arg(long)]
+        k: Option<usize>,
+        /// Label terms per topic
+        #[arg(long, default_value_t = 5)]
+        terms: usize,
+        /// Member sessions listed per topic (0 = all)
+        #[arg(long, default_value_t = 10)]
+        members: usize,
+        /// Filter by agent slug (can be specified multiple times)
+        #[arg(long)]
+        agent: Vec<String>,
+        /// Filter by workspace path (can be specified multiple times)
+        #[arg(long)]
+        workspace: Vec<String>,
+        /// Only sessions started in the last N days
+        #[arg(long)]
+        days: Option<u32>,
+        /// Only sessions started since ISO date (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only sessions started until ISO date
+        #[arg(long)]
+        until: Option<String>,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Score search quality against relevance judgments (nDCG@k, MRR, recall@k)
+    Eval {
+        /// Judgments JSON: {"k": 10, "queries": [{"query": "...", "relevant": [{"source_path": "...", "line": 3}]}]}
+        #[arg(required_unless_present = "from_report")]
+        judgments: Option<PathBuf>,
+        /// Cutoff for nDCG@k and recall@k (overrides the judgments file)
+        #[arg(long)]
+        k: Option<usize>,
+        /// Search modes to evaluate (repeatable; default: all three)
+        #[arg(long, value_enum)]
+        mode: Vec<crate::search::query::SearchMode>,
+        /// TUI ranking modes to evaluate: recent, balanced, relevance, quality, newest, oldest
+        /// (repeatable; default: all)
+        #[arg(long)]
+        ranking: Vec<String>,
+        /// Hybrid fusion strategy used for hybrid runs
+        #[arg(long, value_enum)]
+        fusion: Option<crate::search::query::FusionStrategy>,
+        /// Semantic share of the hybrid score for hybrid runs (0.0-1.0)
+        #[arg(long)]
+        semantic_weight: Option<f32>,
+        /// Embedder for semantic/hybrid runs: model (installed vector index) or hash (no model needed)
+        #[arg(long, value_enum, default_value_t = crate::eval::EvalEmbedder::Model)]
+        embedder: crate::eval::EvalEmbedder,
+        /// Previous report to diff against
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Diff this saved report against --baseline instead of running the judgments
+        #[arg(long, requires = "baseline")]
+        from_report: Option<PathBuf>,
+        /// Write the report JSON to this path
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Fail (exit 1) if any metric of any run drops by more than this versus --baseline
+        #[arg(long, requires = "baseline")]
+        max_regression: Option<f64>,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
     /// Export a conversation to markdown or other formats
     Export {
         /// Path to session file
@@ -436,6 +584,9 @@ pub enum Commands {
     },
     /// Export encrypted searchable archive for GitHub Pages (P4.1)
     Pages {
+        #[command(subcommand)]
+        command: Option<PagesCommand>,
+
         /// Export only (skip wizard and encryption) to specified directory
         #[arg(long)]
         export_only: Option<PathBuf>,
@@ -460,6 +611,28 @@ pub enum Commands {
         #[arg(long, value_enum, default_value_t = crate::pages::export::PathMode::Relative)]
         path_mode: crate::pages::export::PathMode,
 
+        /// Curation file (JSON) listing conversations and messages to leave out
+        #[arg(long)]
+        curation: Option<PathBuf>,
+
+        /// Regex redaction rules (TOML) applied to message content, snippets, titles,
+        /// workspaces, source paths and conversation metadata
+        #[arg(long)]
+        redact_rules: Option<PathBuf>,
+
+        /// Publish without encryption: the viewer loads a plaintext database, no password.
+        /// With --export-only, the directory receives the ready-to-serve bundle
+        #[arg(long)]
+        no_encrypt: bool,
+
+        /// Pre-render one HTML page per conversation plus a search index (implies --no-encrypt)
+        #[arg(long)]
+        static_html: bool,
+
+        /// Include quantized message vectors for semantic and hybrid search in the viewer
+        #[arg(long, conflicts_with = "static_html")]
+        vectors: bool,
+
         /// Dry run (don't write files)
         #[arg(long)]
         dry_run: bool,
@@ -467,6 +640,58 @@ pub enum Commands {
     /// Manage remote sources (P5.x)
     #[command(subcommand)]
     Sources(SourcesCommand),
+    /// Sync remote sources on their schedules and reindex in the background
+    Daemon {
+        /// Run a single pass over due sources and exit
+        #[arg(long)]
+        once: bool,
+        /// Write a systemd user unit that runs the daemon, then exit
+        #[arg(long, conflicts_with = "once")]
+        install_systemd: bool,
+        /// Override data dir (mirrors, sync status, index)
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output one JSON object per pass
+        #[arg(long)]
+        json: bool,
+    },
+    /// Accept sessions uploaded by `cass push` from push sources
+    ServeIngest {
+        /// Address to listen on. The server speaks plain HTTP: keep it on loopback and
+        /// expose it through a TLS reverse proxy rather than binding a public address
+        #[arg(long, default_value = crate::sources::ingest::DEFAULT_INGEST_ADDR)]
+        listen: String,
+        /// Create the push source NAME (or rotate its token), print the token and exit
+        #[arg(long, value_name = "NAME")]
+        new_token: Option<String>,
+        /// Override data dir (mirrors, index)
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Upload new local sessions to a `cass serve-ingest` server
+    Push {
+        /// Server URL (e.g. http://central.example:8731)
+        #[arg(long)]
+        to: String,
+        /// Upload token of the push source (from `cass serve-ingest --new-token`)
+        #[arg(long, env = "CASS_PUSH_TOKEN", hide_env_values = true)]
+        token: String,
+        /// Session directory or file to push (repeatable; default: this platform's agent paths)
+        #[arg(long = "path", value_name = "PATH")]
+        paths: Vec<String>,
+        /// Upload NormalizedConversation JSONL from this file ('-' for stdin) instead of session files
+        #[arg(long, conflicts_with = "paths")]
+        jsonl: Option<PathBuf>,
+        /// Override data dir (where push state is kept)
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Manage semantic search models
     #[command(subcommand)]
     Models(ModelsCommand),
@@ -512,11 +737,15 @@ pub enum SourcesCommand {
         #[arg(long, short = 'y')]
         yes: bool,
     },
-    /// Diagnose source connectivity and configuration issues
+    /// Grade source health (connectivity, auth, versions, mirrors, mappings) with optional repairs
     Doctor {
         /// Check only specific source (defaults to all)
         #[arg(long, short)]
         source: Option<String>,
+        /// Apply safe repairs: re-probe hosts, upgrade outdated remote cass,
+        /// delete stale mirror directories
+        #[arg(long)]
+        fix: bool,
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -535,7 +764,10 @@ pub enum SourcesCommand {
         /// Dry run - show what would be synced without actually syncing
         #[arg(long)]
         dry_run: bool,
-        /// Output as JSON
+        /// Sources to sync at once (default: sync_parallelism in sources.toml, or 4)
+        #[arg(long, short = 'j', value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+        parallel: Option<u16>,
+        /// Output as JSON (per-source progress goes to stderr as JSON lines)
         #[arg(long)]
         json: bool,
     },
@@ -641,20 +873,168 @@ pub enum SourcesCommand {
     },
 }
 
+/// Subcommands of `cass pages`
+#[derive(Subcommand, Debug, Clone)]
+pub enum PagesCommand {
+    /// Manage key slots of an exported archive
+    #[command(subcommand)]
+    Keys(PagesKeysCommand),
+    /// Preview a bundle over local HTTP with cross-origin isolation headers
+    Serve {
+        /// Bundle directory (containing site/) or site directory
+        bundle_dir: PathBuf,
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port to listen on (0 picks a free port)
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Do not open the browser
+        #[arg(long)]
+        no_open: bool,
+        /// Rebuild the bundle whenever this encrypted export directory changes
+        #[arg(long, value_name = "EXPORT_DIR")]
+        rebuild_from: Option<PathBuf>,
+    },
+    /// Import a published archive (URL or bundle directory) as a searchable source
+    ///
+    /// The password is read from CASS_PAGES_PASSWORD or prompted for on a terminal.
+    Import {
+        /// Archive URL, bundle directory (containing site/) or site directory
+        archive: String,
+        /// Source id for the imported conversations (defaults to one derived from the location)
+        #[arg(long)]
+        name: Option<String>,
+        /// Unlock with the recovery secret in this file instead of a password
+        #[arg(long)]
+        recovery_file: Option<PathBuf>,
+        /// Unlock with the age identity (X25519 secret key) in this file
+        #[arg(long, conflicts_with = "recovery_file")]
+        identity_file: Option<PathBuf>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Key slot management for exported archives.
+///
+/// Passwords are read from CASS_PAGES_PASSWORD (unlock) and
+/// CASS_PAGES_NEW_PASSWORD (new slot), or prompted for on a terminal.
+#[derive(Subcommand, Debug, Clone)]
+pub enum PagesKeysCommand {
+    /// List key slots
+    List {
+        /// Bundle directory (containing site/) or site directory
+        bundle_dir: PathBuf,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Add a password slot (or a recovery/recipient slot)
+    Add {
+        /// Bundle directory (containing site/) or site directory
+        bundle_dir: PathBuf,
+        /// Add a freshly generated recovery secret instead of a password
+        #[arg(long)]
+        recovery: bool,
+        /// Wrap the key to an age X25519 public key (age1...) instead of a password
+        #[arg(long, value_name = "PUBKEY", conflicts_with = "recovery")]
+        recipient: Option<String>,
+        /// Unlock with the recovery secret in this file instead of a password
+        #[arg(long)]
+        recovery_file: Option<PathBuf>,
+        /// Unlock with the age identity (X25519 secret key) in this file
+        #[arg(long, conflicts_with = "recovery_file")]
+        identity_file: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove a key slot
+    Revoke {
+        /// Bundle directory (containing site/) or site directory
+        bundle_dir: PathBuf,
+        /// Slot id (see `cass pages keys list`)
+        slot: u8,
+        /// Unlock with the recovery secret in this file instead of a password
+        #[arg(long)]
+        recovery_file: Option<PathBuf>,
+        /// Unlock with the age identity (X25519 secret key) in this file
+        #[arg(long, conflicts_with = "recovery_file")]
+        identity_file: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Re-encrypt the archive under a new key, dropping password and recovery slots
+    Rotate {
+        /// Bundle directory (containing site/) or site directory
+        bundle_dir: PathBuf,
+        /// Set a new password instead of keeping the one used to unlock
+        #[arg(long)]
+        new_password: bool,
+        /// Also generate a new recovery secret
+        #[arg(long)]
+        recovery: bool,
+        /// Unlock with the recovery secret in this file instead of a password
+        #[arg(long)]
+        recovery_file: Option<PathBuf>,
+        /// Unlock with the age identity (X25519 secret key) in this file
+        #[arg(long, conflicts_with = "recovery_file")]
+        identity_file: Option<PathBuf>,
+        /// Drop password and recovery slots that cannot be carried over without asking
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 /// Subcommands for managing semantic search models
 #[derive(Subcommand, Debug, Clone)]
 pub enum ModelsCommand {
     /// Show model installation status
     Status {
+        /// Model to inspect (default: the active model)
+        #[arg(long)]
+        model: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// List available models (built-in and from manifest files)
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Select the model used for semantic search
+    Use {
+        /// Model id (see `cass models list`)
+        model: String,
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
     },
     /// Download and install the semantic search model
     Install {
-        /// Model to install (default: all-minilm-l6-v2)
-        #[arg(long, default_value = "all-minilm-l6-v2")]
-        model: String,
+        /// Model to install (default: the active model)
+        #[arg(long)]
+        model: Option<String>,
         /// Custom mirror URL for downloading
         #[arg(long)]
         mirror: Option<String>,
@@ -670,6 +1050,9 @@ pub enum ModelsCommand {
     },
     /// Verify model integrity (SHA256 checksums)
     Verify {
+        /// Model to verify (default: the active model)
+        #[arg(long)]
+        model: Option<String>,
         /// Attempt to repair corrupted files
         #[arg(long)]
         repair: bool,
@@ -682,9 +1065,9 @@ pub enum ModelsCommand {
     },
     /// Remove model files to free disk space
     Remove {
-        /// Model to remove (default: all-minilm-l6-v2)
-        #[arg(long, default_value = "all-minilm-l6-v2")]
-        model: String,
+        /// Model to remove (default: the active model)
+        #[arg(long)]
+        model: Option<String>,
         /// Skip confirmation prompt
         #[arg(long, short = 'y')]
         yes: bool,
@@ -694,6 +1077,9 @@ pub enum ModelsCommand {
     },
     /// Check for model updates
     CheckUpdate {
+        /// Model to check (default: the active model)
+        #[arg(long)]
+        model: Option<String>,
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -745,6 +1131,26 @@ pub enum MappingsAction {
         #[arg(long)]
         agent: Option<String>,
     },
+    /// Suggest mappings by matching the source's workspaces to local ones
+    ///
+    /// Workspaces are paired by git remote URL, then by directory name.
+    /// Accepted mappings are saved and applied to already indexed sessions.
+    Suggest {
+        /// Source name
+        source: String,
+        /// Accept every suggestion without prompting
+        #[arg(long)]
+        apply: bool,
+        /// Ignore suggestions below this confidence (0-1)
+        #[arg(long, default_value_t = 0.5)]
+        min_confidence: f64,
+        /// Don't read git remotes over SSH; match remote workspaces by name only
+        #[arg(long)]
+        offline: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
@@ -1886,6 +2292,9 @@ async fn execute_cli(
         | Commands::Stats { .. }
         | Commands::Diag { .. }
         | Commands::Status { .. }
+        | Commands::Similar { .. }
+        | Commands::Topics { .. }
+        | Commands::Eval { .. }
         | Commands::View { .. } => {
             tracing_subscriber::fmt()
                 .with_env_filter(filter)
@@ -1949,7 +2358,14 @@ async fn execute_cli(
                     highlight,
                     source,
                     sessions_from,
+                    owner,
+                    all_owners,
                     mode,
+                    fusion,
+                    semantic_weight,
+                    rrf_k,
+                    federated,
+                    federated_timeout,
                 } => {
                     run_cli_search(
                         &query,
@@ -1986,7 +2402,14 @@ async fn execute_cli(
                         highlight,
                         source,
                         sessions_from,
+                        owner,
+                        all_owners,
                         mode,
+                        fusion,
+                        semantic_weight,
+                        rrf_k,
+                        federated,
+                        federated_timeout,
                     )?;
                 }
                 Commands::Stats {
@@ -2026,44 +2449,106 @@ async fn execute_cli(
                 } => {
                     run_view(&path, line, context, json || robot_mode)?;
                 }
-                Commands::Pages {
-                    export_only,
-                    agents,
-                    workspaces,
+                Commands::Similar {
+                    source_path,
+                    line,
+                    agent,
+                    workspace,
+                    limit,
+                    offset,
+                    json,
+                    data_dir,
+                    days,
+                    today,
+                    yesterday,
+                    week,
                     since,
                     until,
-                    path_mode,
-                    dry_run,
+                    source,
                 } => {
-                    if let Some(output_path) = export_only {
-                        crate::pages::export::run_pages_export(
-                            cli.db.clone(),
-                            output_path.clone(),
-                            agents.clone(),
-                            workspaces.clone(),
-                            since.clone(),
-                            until.clone(),
-                            path_mode,
-                            dry_run,
-                        )
-                        .map_err(|e| CliError {
-                            code: 9,
-                            kind: "pages",
-                            message: format!("Export failed: {e}"),
-                            hint: None,
-                            retryable: false,
-                        })?;
-                    } else {
-                        crate::pages::wizard::PagesWizard::new()
-                            .run()
-                            .map_err(|e| CliError {
-                                code: 9,
-                                kind: "pages",
-                                message: format!("Wizard failed: {e}"),
-                                hint: None,
-                                retryable: false,
-                            })?;
-                    }
+                    run_similar(
+                        &source_path,
+                        line,
+                        &agent,
+                        &workspace,
+                        limit,
+                        offset,
+                        json || robot_mode,
+                        &data_dir,
+                        cli.db.clone(),
+                        TimeFilter::new(
+                            days,
+                            today,
+                            yesterday,
+                            week,
+                            since.as_deref(),
+                            until.as_deref(),
+                        ),
+                        source,
+                    )?;
+                }
+                Commands::Topics {
+                    k,
+                    terms,
+                    members,
+                    agent,
+                    workspace,
+                    days,
+                    since,
+                    until,
+                    json,
+                    data_dir,
+                } => {
+                    run_topics(
+                        k,
+                        terms,
+                        members,
+                        &agent,
+                        &workspace,
+                        TimeFilter::new(
+                            days,
+                            false,
+                            false,
+                            false,
+                            since.as_deref(),
+                            until.as_deref(),
+                        ),
+                        json || robot_mode,
+                        &data_dir,
+                        cli.db.clone(),
+                    )?;
+                }
+                Commands::Eval {
+                    judgments,
+                    k,
+                    mode,
+                    ranking,
+                    fusion,
+                    semantic_weight,
+                    embedder,
+                    baseline,
+                    from_report,
+                    save,
+                    max_regression,
+                    json,
+                    data_dir,
+                } => {
+                    run_eval(
+                        judgments.as_deref(),
+                        k,
+                        &mode,
+                        &ranking,
+                        fusion,
+                        semantic_weight,
+                        embedder,
+                        baseline.as_deref(),
+                        from_report.as_deref(),
+                        save.as_deref(),
+                        max_regression,
+                        json || robot_mode,
+                        &data_dir,
+                        cli.db.clone(),
+                    )?;
                 }
                 _ => {}
             }
@@ -2174,9 +2659,133 @@ async fn execute_cli(
                 Commands::Sources(subcmd) => {
                     run_sources_command(subcmd)?;
                 }
+                Commands::Daemon {
+                    once,
+                    install_systemd,
+                    data_dir,
+                    json,
+                } => {
+                    run_daemon(once, install_systemd, data_dir, json)?;
+                }
+                Commands::ServeIngest {
+                    listen,
+                    new_token,
+                    data_dir,
+                    json,
+                } => {
+                    run_serve_ingest(&listen, new_token.as_deref(), data_dir, json)?;
+                }
+                Commands::Push {
+                    to,
+                    token,
+                    paths,
+                    jsonl,
+                    data_dir,
+                    json,
+                } => {
+                    run_push(&to, &token, paths, jsonl.as_deref(), data_dir, json)?;
+                }
                 Commands::Models(subcmd) => {
                     run_models_command(subcmd)?;
                 }
+                Commands::Pages {
+                    command: Some(PagesCommand::Keys(keys)),
+                    ..
+                } => {
+                    run_pages_keys(keys)?;
+                }
+                Commands::Pages {
+                    command:
+                        Some(PagesCommand::Serve {
+                            bundle_dir,
+                            host,
+                            port,
+                            no_open,
+                            rebuild_from,
+                        }),
+                    ..
+                } => {
+                    run_pages_serve(&bundle_dir, &host, port, no_open, rebuild_from.as_deref())?;
+                }
+                Commands::Pages {
+                    command:
+                        Some(PagesCommand::Import {
+                            archive,
+                            name,
+                            recovery_file,
+                            identity_file,
+                            data_dir,
+                            json,
+                        }),
+                    ..
+                } => {
+                    run_pages_import(
+                        &archive,
+                        name,
+                        recovery_file.as_deref(),
+                        identity_file.as_deref(),
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
+                Commands::Pages {
+                    command: None,
+                    export_only,
+                    agents,
+                    workspaces,
+                    since,
+                    until,
+                    path_mode,
+                    curation,
+                    redact_rules,
+                    no_encrypt,
+                    static_html,
+                    vectors,
+                    dry_run,
+                } => {
+                    use crate::pages::bundle::PlaintextMode;
+                    let plaintext = if static_html {
+                        Some(PlaintextMode::StaticHtml)
+                    } else {
+                        no_encrypt.then_some(PlaintextMode::Viewer)
+                    };
+                    if let Some(output_path) = export_only {
+                        crate::pages::export::run_pages_export(
+                            cli.db.clone(),
+                            output_path.clone(),
+                            agents.clone(),
+                            workspaces.clone(),
+                            since.clone(),
+                            until.clone(),
+                            path_mode,
+                            curation.clone(),
+                            redact_rules.clone(),
+                            plaintext,
+                            vectors,
+                            dry_run,
+                        )
+                        .map_err(|e| CliError {
+                            code: 9,
+                            kind: "pages",
+                            message: format!("Export failed: {e}"),
+                            hint: None,
+                            retryable: false,
+                        })?;
+                    } else {
+                        crate::pages::wizard::PagesWizard::new()
+                            .plaintext(plaintext)
+                            .vectors(vectors)
+                            .run()
+                            .map_err(|e| CliError {
+                                code: 9,
+                                kind: "pages",
+                                message: format!("Wizard failed: {e}"),
+                                hint: None,
+                                retryable: false,
+                            })?;
+                    }
+                }
                 _ => {}
             }
         }
@@ -2334,10 +2943,16 @@ fn describe_command(cli: &Cli) -> String {
         Some(Commands::Health { .. }) => "health".to_string(),
         Some(Commands::Doctor { .. }) => "doctor".to_string(),
         Some(Commands::Context { .. }) => "context".to_string(),
+        Some(Commands::Similar { .. }) => "similar".to_string(),
+        Some(Commands::Topics { .. }) => "topics".to_string(),
+        Some(Commands::Eval { .. }) => "eval".to_string(),
         Some(Commands::Export { .. }) => "export".to_string(),
         Some(Commands::Expand { .. }) => "expand".to_string(),
         Some(Commands::Timeline { .. }) => "timeline".to_string(),
         Some(Commands::Sources(..)) => "sources".to_string(),
+        Some(Commands::Daemon { .. }) => "daemon".to_string(),
+        Some(Commands::ServeIngest { .. }) => "serve-ingest".to_string(),
+        Some(Commands::Push { .. }) => "push".to_string(),
         Some(Commands::Models(..)) => "models".to_string(),
         Some(Commands::Pages { .. }) => "pages".to_string(),
         None => "(default)".to_string(),
@@ -2366,6 +2981,12 @@ fn is_robot_mode(command: &Commands) -> bool {
         Commands::Capabilities { json, .. } => *json,
         Commands::Introspect { json, .. } => *json,
         Commands::Context { json, .. } => *json,
+        Commands::Similar { json, .. } => *json,
+        Commands::Topics { json, .. } => *json,
+        Commands::Eval { json, .. } => *json,
+        Commands::Daemon { json, .. } => *json,
+        Commands::ServeIngest { json, .. } => *json,
+        Commands::Push { json, .. } => *json,
         _ => false,
     }
 }
@@ -2622,8 +3243,8 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "    --offset N        Pagination offset (default: 0)".to_string(),
             "    --json | --robot  JSON output for automation".to_string(),
             "    --fields F1,F2    Select specific fields in hits (reduces token usage)".to_string(),
-            "                      Presets: minimal (path,line,agent), summary (+title,score), provenance (source_id,origin_kind,origin_host)".to_string(),
-            "                      Fields: score,agent,workspace,source_path,snippet,content,title,created_at,line_number,match_type,source_id,origin_kind,origin_host".to_string(),
+            "                      Presets: minimal (path,line,agent), summary (+title,score), provenance (source_id,origin_kind,origin_host,seen_in)".to_string(),
+            "                      Fields: score,agent,workspace,source_path,snippet,content,title,created_at,line_number,match_type,source_id,origin_kind,origin_host,seen_in".to_string(),
             "    --max-content-length N  Truncate content/snippet/title to N chars (UTF-8 safe, adds '...')".to_string(),
             "                            Adds *_truncated: true indicator for each truncated field".to_string(),
             "    --today           Filter to today only".to_string(),
@@ -3091,9 +3712,18 @@ fn run_cli_search(
     highlight: bool,
     source: Option<String>,
     sessions_from: Option<String>,
+    owners: Vec<String>,
+    all_owners: bool,
     mode: Option<crate::search::query::SearchMode>,
+    fusion: Option<crate::search::query::FusionStrategy>,
+    semantic_weight: Option<f32>,
+    rrf_k: Option<f32>,
+    federated: bool,
+    federated_timeout: u64,
 ) -> CliResult<()> {
-    use crate::search::query::{QueryExplanation, SearchClient, SearchFilters, SearchMode};
+    use crate::search::query::{
+        FusionConfig, QueryExplanation, SearchClient, SearchFilters, SearchMode,
+    };
     use crate::search::tantivy::index_dir;
     use crate::sources::provenance::SourceFilter;
     use std::collections::HashSet;
@@ -3101,6 +3731,46 @@ fn run_cli_search(
     // Start timing for robot_meta elapsed_ms
     let start_time = Instant::now();
 
+    // Validate hybrid fusion tuning before touching the index
+    let fusion_requested = fusion.is_some() || semantic_weight.is_some() || rrf_k.is_some();
+    if fusion_requested && matches!(mode, Some(SearchMode::Lexical | SearchMode::Semantic)) {
+        return Err(CliError {
+            code: 2,
+            kind: "usage",
+            message: "--fusion, --semantic-weight, and --rrf-k only apply to hybrid search"
+                .to_string(),
+            hint: Some("Use --mode hybrid or drop the fusion flags".to_string()),
+            retryable: false,
+        });
+    }
+    if let Some(weight) = semantic_weight
+        && !(0.0..=1.0).contains(&weight)
+    {
+        return Err(CliError {
+            code: 2,
+            kind: "usage",
+            message: format!("--semantic-weight must be between 0.0 and 1.0 (got {weight})"),
+            hint: None,
+            retryable: false,
+        });
+    }
+    if let Some(k) = rrf_k
+        && !(k.is_finite() && k > 0.0)
+    {
+        return Err(CliError {
+            code: 2,
+            kind: "usage",
+            message: format!("--rrf-k must be a positive number (got {k})"),
+            hint: None,
+            retryable: false,
+        });
+    }
+    let fusion_config = FusionConfig {
+        strategy: fusion.unwrap_or_default(),
+        rrf_k: rrf_k.unwrap_or(FusionConfig::default().rrf_k),
+        semantic_weight,
+    };
+
     let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
     let index_path = index_dir(&data_dir).map_err(|e| CliError {
         code: 9,
@@ -3157,6 +3827,17 @@ fn run_cli_search(
         filters.session_paths = session_paths;
     }
 
+    // Owner filter: explicit --owner, else the config's default (hide_other_owners)
+    if !owners.is_empty() {
+        filters.owners = HashSet::from_iter(owners);
+    } else if !all_owners
+        && let Some(owner) = crate::sources::config::SourcesConfig::load()
+            .ok()
+            .and_then(|config| config.default_owner())
+    {
+        filters.owners.insert(owner);
+    }
+
     // Apply cursor overrides (base64-encoded JSON { "offset": usize, "limit": usize })
     let mut limit_val = *limit;
     let mut offset_val = *offset;
@@ -3239,6 +3920,9 @@ fn run_cli_search(
     // Fetch up to 1000 for aggregation starting at offset 0, then apply offset/limit
     let (search_limit, search_offset) = if has_aggregation {
         (1000.max(limit_val + offset_val), 0)
+    } else if federated {
+        // Remote hits are merged in before paging
+        (limit_val + offset_val, 0)
     } else {
         (limit_val, offset_val)
     };
@@ -3260,10 +3944,35 @@ fn run_cli_search(
         });
     }
 
-    // Determine effective search mode (default to Lexical)
-    let effective_mode = mode.unwrap_or(SearchMode::Lexical);
+    // Determine effective search mode (default to Lexical; fusion flags imply Hybrid)
+    let effective_mode = mode.unwrap_or(if fusion_requested {
+        SearchMode::Hybrid
+    } else {
+        SearchMode::Lexical
+    });
+    let resolved_fusion =
+        (effective_mode == SearchMode::Hybrid).then(|| fusion_config.resolve(query));
+
+    // Remote hosts search while the local index does
+    let federated_search = if federated {
+        Some(start_federated_search(
+            crate::sources::federated::RemoteQuery {
+                query: query.to_string(),
+                agents: agents.to_vec(),
+                workspaces: workspaces.to_vec(),
+                limit: search_limit,
+                since: filters.created_from,
+                until: filters.created_to,
+                mode,
+            },
+            &filters.source_filter,
+            Duration::from_secs(federated_timeout),
+        )?)
+    } else {
+        None
+    };
 
-    let result = match effective_mode {
+    let mut result = match effective_mode {
         SearchMode::Lexical => client
             .search_with_fallback(query, filters.clone(), search_limit, search_offset, sparse_threshold)
             .map_err(|e| CliError {
@@ -3307,7 +4016,15 @@ fn run_cli_search(
             }
         }
         SearchMode::Hybrid => client
-            .search_hybrid(query, query, filters.clone(), search_limit, search_offset, sparse_threshold)
+            .search_hybrid(
+                query,
+                query,
+                filters.clone(),
+                search_limit,
+                search_offset,
+                sparse_threshold,
+                &fusion_config,
+            )
             .map_err(|e| {
                 let err_str = e.to_string();
                 if err_str.contains("unavailable") || err_str.contains("no embedder") {
@@ -3333,6 +4050,31 @@ fn run_cli_search(
             })?,
     };
 
+    let federated_hosts = if let Some(handle) = federated_search {
+        let (mut remote_hits, hosts) = handle
+            .join()
+            .map_err(|_| CliError::unknown("federated search panicked"))?;
+        if !filters.owners.is_empty() {
+            for host_hits in &mut remote_hits {
+                host_hits.retain(|h| h.owner.as_ref().is_some_and(|o| filters.owners.contains(o)));
+            }
+        }
+        let merged =
+            crate::sources::federated::merge_hits(std::mem::take(&mut result.hits), remote_hits);
+        result.hits = if has_aggregation {
+            merged
+        } else {
+            merged
+                .into_iter()
+                .skip(offset_val)
+                .take(limit_val)
+                .collect()
+        };
+        Some(hosts)
+    } else {
+        None
+    };
+
     // Check if search exceeded timeout - return partial results with timeout indicator
     let timed_out = timeout_duration.is_some_and(|t| start_time.elapsed() > t);
 
@@ -3464,6 +4206,22 @@ fn run_cli_search(
         meta
     });
 
+    if effective_robot.is_none()
+        && let Some(hosts) = &federated_hosts
+    {
+        for host in hosts.iter().filter(|h| h.is_partial()) {
+            let reason = if host.timed_out {
+                format!("timed out after {federated_timeout}s")
+            } else {
+                host.error.clone().unwrap_or_default()
+            };
+            eprintln!(
+                "Warning: remote source '{}' returned partial results ({reason})",
+                host.source_id
+            );
+        }
+    }
+
     if let Some(format) = effective_robot {
         // Robot output mode (JSON)
         output_robot_results(
@@ -3489,6 +4247,8 @@ fn run_cli_search(
             timed_out,
             timeout_ms,
             effective_mode,
+            resolved_fusion.as_ref(),
+            federated_hosts.as_deref(),
         )?;
     } else if display_result.hits.is_empty() {
         eprintln!("No results found.");
@@ -3518,6 +4278,40 @@ fn run_cli_search(
     Ok(())
 }
 
+/// Start `cass search --federated` on the SSH sources selected by `source_filter`.
+fn start_federated_search(
+    query: crate::sources::federated::RemoteQuery,
+    source_filter: &crate::sources::provenance::SourceFilter,
+    timeout: Duration,
+) -> CliResult<std::thread::JoinHandle<crate::sources::federated::FederatedResults>> {
+    use crate::sources::config::SourcesConfig;
+    use crate::sources::federated::FederatedSearch;
+    use crate::sources::provenance::SourceFilter;
+
+    let config = SourcesConfig::load().map_err(|e| CliError {
+        code: 9,
+        kind: "config",
+        message: format!("Failed to load sources config: {e}"),
+        hint: Some("Run 'cass sources add' to configure a source".into()),
+        retryable: false,
+    })?;
+    let sources: Vec<_> = config
+        .remote_sources()
+        .filter(|source| source.is_ssh() && source.host.is_some())
+        .filter(|source| match source_filter {
+            SourceFilter::All | SourceFilter::Remote => true,
+            SourceFilter::Local => false,
+            SourceFilter::SourceId(id) => source.name == *id,
+        })
+        .cloned()
+        .collect();
+
+    Ok(std::thread::spawn(move || {
+        let sources: Vec<_> = sources.iter().collect();
+        FederatedSearch::new(timeout).search(&sources, &query)
+    }))
+}
+
 /// Output search results in human-readable display format
 fn output_display_results(
     hits: &[crate::search::query::SearchHit],
@@ -3571,6 +4365,9 @@ fn output_display_results(
                 println!("## {}. {} (score: {:.2})\n", i + 1, hit.agent, hit.score);
                 println!("- **Workspace**: `{}`", hit.workspace);
                 println!("- **Path**: `{}`", hit.source_path);
+                if !hit.seen_in.is_empty() {
+                    println!("- **Seen in**: {}", hit.seen_in.join(", "));
+                }
                 if let Some(ts) = hit.created_at {
                     let dt = chrono::DateTime::from_timestamp_millis(ts).map_or_else(
                         || "unknown".to_string(),
@@ -3615,6 +4412,8 @@ fn expand_field_presets(fields: &Option<Vec<String>>) -> Option<Vec<String>> {
                     "source_id".to_string(),
                     "origin_kind".to_string(),
                     "origin_host".to_string(),
+                    "owner".to_string(),
+                    "seen_in".to_string(),
                 ],
                 "*" | "all" => vec![], // Empty means include all - handled specially
                 other => vec![other.to_string()],
@@ -3650,6 +4449,10 @@ fn filter_hit_fields(
                 "source_id",
                 "origin_kind",
                 "origin_host",
+                "owner",
+                "seen_in",
+                // Hybrid ranking details
+                "fusion",
             ];
 
             for field in field_list {
@@ -3772,6 +4575,8 @@ fn output_robot_results(
     timed_out: bool,
     timeout_ms: Option<u64>,
     search_mode: crate::search::query::SearchMode,
+    fusion: Option<&crate::search::query::ResolvedFusion>,
+    federated_hosts: Option<&[crate::sources::federated::HostOutcome]>,
 ) -> CliResult<()> {
     if matches!(format, RobotFormat::Sessions) {
         // Output unique session paths only, one per line.
@@ -3810,6 +4615,14 @@ fn output_robot_results(
         Some(serde_json::to_value(aggregations).unwrap_or_default())
     };
 
+    // Per-host outcomes of a federated search
+    let federated_json = federated_hosts.map(|hosts| {
+        serde_json::json!({
+            "hosts": hosts,
+            "partial_results": hosts.iter().any(|h| h.is_partial()),
+        })
+    });
+
     match format {
         RobotFormat::Json => {
             let mut payload = serde_json::json!({
@@ -3840,6 +4653,11 @@ fn output_robot_results(
                 map.insert("aggregations".to_string(), agg.clone());
             }
 
+            // Add federated host outcomes if present
+            if let (Some(fed), serde_json::Value::Object(map)) = (&federated_json, &mut payload) {
+                map.insert("federated".to_string(), fed.clone());
+            }
+
             // Add query explanation if requested
             if let (Some(exp), serde_json::Value::Object(map)) = (explanation, &mut payload) {
                 map.insert(
@@ -3875,6 +4693,14 @@ fn output_robot_results(
                 {
                     m.insert("index_freshness".to_string(), freshness);
                 }
+                if let Some(fusion) = fusion
+                    && let serde_json::Value::Object(ref mut m) = meta
+                {
+                    m.insert(
+                        "fusion".to_string(),
+                        serde_json::to_value(fusion).unwrap_or_default(),
+                    );
+                }
                 // Add timeout info to _meta if timeout was configured
                 if let Some(timeout) = timeout_ms
                     && let serde_json::Value::Object(ref mut m) = meta
@@ -3923,6 +4749,7 @@ fn output_robot_results(
                 || agg_json.is_some()
                 || !result.suggestions.is_empty()
                 || explanation.is_some()
+                || federated_json.is_some()
             {
                 let mut meta = serde_json::json!({
                     "_meta": {
@@ -3958,6 +4785,14 @@ fn output_robot_results(
                 {
                     m.insert("index_freshness".to_string(), freshness);
                 }
+                if let Some(fusion) = fusion
+                    && let Some(m) = meta.get_mut("_meta").and_then(|v| v.as_object_mut())
+                {
+                    m.insert(
+                        "fusion".to_string(),
+                        serde_json::to_value(fusion).unwrap_or_default(),
+                    );
+                }
                 // Add suggestions to meta line
                 if !result.suggestions.is_empty()
                     && let serde_json::Value::Object(ref mut map) = meta
@@ -3971,6 +4806,10 @@ fn output_robot_results(
                 if let (Some(agg), serde_json::Value::Object(map)) = (&agg_json, &mut meta) {
                     map.insert("aggregations".to_string(), agg.clone());
                 }
+                // Add federated host outcomes to meta line
+                if let (Some(fed), serde_json::Value::Object(map)) = (&federated_json, &mut meta) {
+                    map.insert("federated".to_string(), fed.clone());
+                }
                 // Add explanation to meta line
                 if let (Some(exp), serde_json::Value::Object(map)) = (explanation, &mut meta) {
                     map.insert(
@@ -4046,6 +4885,11 @@ fn output_robot_results(
                 map.insert("aggregations".to_string(), agg.clone());
             }
 
+            // Add federated host outcomes if present
+            if let (Some(fed), serde_json::Value::Object(map)) = (&federated_json, &mut payload) {
+                map.insert("federated".to_string(), fed.clone());
+            }
+
             // Add query explanation if requested
             if let (Some(exp), serde_json::Value::Object(map)) = (explanation, &mut payload) {
                 map.insert(
@@ -4075,6 +4919,14 @@ fn output_robot_results(
                 {
                     m.insert("index_freshness".to_string(), freshness);
                 }
+                if let Some(fusion) = fusion
+                    && let serde_json::Value::Object(ref mut m) = meta
+                {
+                    m.insert(
+                        "fusion".to_string(),
+                        serde_json::to_value(fusion).unwrap_or_default(),
+                    );
+                }
                 // Add timeout info to _meta if timeout was configured
                 if let Some(timeout) = timeout_ms
                     && let serde_json::Value::Object(ref mut m) = meta
@@ -4885,6 +5737,7 @@ fn ensure_cass_origin(
     source_id: &str,
     kind: crate::sources::provenance::SourceKind,
     host: Option<&str>,
+    owner: Option<&str>,
 ) {
     if !metadata.is_object() {
         *metadata = serde_json::json!({});
@@ -4916,6 +5769,13 @@ fn ensure_cass_origin(
                 .entry("host".to_string())
                 .or_insert_with(|| serde_json::Value::String(host.to_string()));
         }
+        // The stored column wins: it follows owner changes made after ingest
+        if let Some(owner) = owner {
+            origin_obj.insert(
+                "owner".to_string(),
+                serde_json::Value::String(owner.to_string()),
+            );
+        }
     }
 }
 
@@ -5013,7 +5873,13 @@ fn rebuild_tantivy_from_db(
                 });
 
             let host = conv.origin_host.as_deref().or(host_label.as_deref());
-            ensure_cass_origin(&mut metadata, &conv.source_id, kind, host);
+            ensure_cass_origin(
+                &mut metadata,
+                &conv.source_id,
+                kind,
+                host,
+                conv.owner.as_deref(),
+            );
 
             let normalized_messages: Vec<NormalizedMessage> = messages
                 .into_iter()
@@ -6066,6 +6932,9 @@ fn run_context(
         .collect()
     };
 
+    // Find related sessions: semantically similar (only when semantic search is set up)
+    let similar = similar_sessions(&data_dir, &db_path, &path_str, limit);
+
     if json {
         let format_ts = |ts: Option<i64>| -> Option<String> {
             ts.and_then(|t| chrono::DateTime::from_timestamp_millis(t).map(|d| d.to_rfc3339()))
@@ -6103,11 +6972,20 @@ fn run_context(
                         "started_at": format_ts(*ts),
                     })
                 }).collect::<Vec<_>>(),
+                "similar": similar.iter().map(|(p, t, a, score)| {
+                    serde_json::json!({
+                        "path": p,
+                        "title": t,
+                        "agent": a,
+                        "score": score,
+                    })
+                }).collect::<Vec<_>>(),
             },
             "counts": {
                 "same_workspace": same_workspace.len(),
                 "same_day": same_day.len(),
                 "same_agent": same_agent.len(),
+                "similar": similar.len(),
             }
         });
         println!(
@@ -6186,7 +7064,25 @@ fn run_context(
             println!();
         }
 
-        if same_workspace.is_empty() && same_day.is_empty() && same_agent.is_empty() {
+        if !similar.is_empty() {
+            println!("{} ({}):", "Similar".bold().cyan(), similar.len());
+            for (path, title_str, agent, score) in &similar {
+                println!(
+                    "  • {} [{}] {}",
+                    title_str.as_str().yellow(),
+                    agent.as_str().green(),
+                    format!("{score:.2}").dimmed()
+                );
+                println!("    {}", path.as_str().dimmed());
+            }
+            println!();
+        }
+
+        if same_workspace.is_empty()
+            && same_day.is_empty()
+            && same_agent.is_empty()
+            && similar.is_empty()
+        {
             println!("{}", "No related sessions found.".dimmed());
         }
     }
@@ -6194,42 +7090,593 @@ fn run_context(
     Ok(())
 }
 
-/// Capabilities response for agent introspection.
-/// Provides static information about CLI features, versions, and limits.
-#[derive(Debug, Clone, Serialize)]
-pub struct CapabilitiesResponse {
-    /// Semantic version of the crate
-    pub crate_version: String,
-    /// API contract version (bumped on breaking changes)
-    pub api_version: u32,
-    /// Human-readable contract identifier
-    pub contract_version: String,
-    /// List of supported feature flags
-    pub features: Vec<String>,
-    /// List of supported agent connectors
-    pub connectors: Vec<String>,
-    /// System limits
-    pub limits: CapabilitiesLimits,
-}
+/// Sessions whose messages are semantically closest to `path`, best first.
+/// Empty when the semantic model or vector index is not available.
+fn similar_sessions(
+    data_dir: &Path,
+    db_path: &Path,
+    path: &str,
+    limit: usize,
+) -> Vec<(String, String, String, f32)> {
+    use crate::search::query::{SearchClient, SearchFilters, SimilarAnchor};
 
-#[derive(Debug, Clone, Serialize)]
-pub struct CapabilitiesLimits {
-    /// Maximum --limit value
-    pub max_limit: usize,
-    /// Maximum --max-content-length value (0 = unlimited)
-    pub max_content_length: usize,
-    /// Maximum fields in --fields selection
-    pub max_fields: usize,
-    /// Maximum aggregation bucket count per field
-    pub max_agg_buckets: usize,
+    let Ok(index_path) = crate::search::tantivy::index_dir(data_dir) else {
+        return Vec::new();
+    };
+    let Ok(Some(client)) = SearchClient::open(&index_path, Some(db_path)) else {
+        return Vec::new();
+    };
+    if attach_semantic_context(&client, data_dir, db_path).is_err() {
+        return Vec::new();
+    }
+    let anchor = SimilarAnchor {
+        source_path: path.to_string(),
+        line_number: None,
+    };
+    // Hits are per message; over-fetch so several sessions survive collapsing.
+    let hits = client
+        .search_similar(&anchor, SearchFilters::default(), limit * 8, 0)
+        .unwrap_or_default();
+
+    let mut seen = std::collections::HashSet::new();
+    hits.into_iter()
+        .filter(|hit| seen.insert(hit.source_path.clone()))
+        .take(limit)
+        .map(|hit| (hit.source_path, hit.title, hit.agent, hit.score))
+        .collect()
 }
 
-// ============================================================================
-// Introspect command schema structures
-// ============================================================================
+/// Load the embedder and vector index into a CLI search client.
+/// Returns a human-readable reason when semantic search is unavailable.
+fn attach_semantic_context(
+    client: &crate::search::query::SearchClient,
+    data_dir: &Path,
+    db_path: &Path,
+) -> Result<(), String> {
+    let setup = crate::search::model_manager::load_semantic_context(data_dir, db_path);
+    let Some(context) = setup.context else {
+        return Err(setup.availability.summary());
+    };
+    client
+        .set_semantic_context(
+            context.embedder,
+            context.index,
+            context.filter_maps,
+            context.roles,
+        )
+        .map_err(|e| format!("failed to load semantic context: {e}"))
+}
 
-/// Full API introspection response
-#[derive(Debug, Clone, Serialize)]
+/// Find messages similar to an existing session (or a single message in it).
+#[allow(clippy::too_many_arguments)]
+fn run_similar(
+    source_path: &Path,
+    line: Option<usize>,
+    agents: &[String],
+    workspaces: &[String],
+    limit: usize,
+    offset: usize,
+    json: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    time_filter: TimeFilter,
+    source: Option<String>,
+) -> CliResult<()> {
+    use crate::search::query::{SearchClient, SearchFilters, SimilarAnchor};
+    use crate::search::tantivy::index_dir;
+    use crate::sources::provenance::SourceFilter;
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let index_path = index_dir(&data_dir).map_err(|e| CliError {
+        code: 9,
+        kind: "path",
+        message: format!("failed to open index dir: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+
+    let client = SearchClient::open(&index_path, Some(&db_path))
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "open-index",
+            message: format!("failed to open index: {e}"),
+            hint: Some("try cass index --full".to_string()),
+            retryable: true,
+        })?
+        .ok_or_else(|| CliError {
+            code: 3,
+            kind: "missing-index",
+            message: format!(
+                "Index not found at {}. Run 'cass index --full' first.",
+                index_path.display()
+            ),
+            hint: None,
+            retryable: true,
+        })?;
+
+    attach_semantic_context(&client, &data_dir, &db_path).map_err(|reason| CliError {
+        code: 15,
+        kind: "semantic-unavailable",
+        message: format!("Similar search requires semantic search: {reason}"),
+        hint: Some(
+            "Run 'cass models install' and press Alt+S in 'cass tui' to build the vector index"
+                .to_string(),
+        ),
+        retryable: true,
+    })?;
+
+    let filters = SearchFilters {
+        agents: agents.iter().cloned().collect(),
+        workspaces: workspaces.iter().cloned().collect(),
+        created_from: time_filter.since,
+        created_to: time_filter.until,
+        source_filter: source
+            .as_deref()
+            .map(SourceFilter::parse)
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let anchor = SimilarAnchor {
+        source_path: source_path.to_string_lossy().to_string(),
+        line_number: line,
+    };
+    let hits = client
+        .search_similar(&anchor, filters, limit, offset)
+        .map_err(|e| {
+            let msg = e.to_string();
+            if msg.contains("no indexed session") || msg.contains("no message at line") {
+                CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: msg,
+                    hint: Some(
+                        "Use 'cass search' to find sessions, then pass source_path (and line_number) from results."
+                            .to_string(),
+                    ),
+                    retryable: false,
+                }
+            } else {
+                CliError {
+                    code: 9,
+                    kind: "similar",
+                    message: format!("similar search failed: {msg}"),
+                    hint: None,
+                    retryable: true,
+                }
+            }
+        })?;
+
+    if json {
+        let payload = serde_json::json!({
+            "anchor": anchor,
+            "limit": limit,
+            "offset": offset,
+            "count": hits.len(),
+            "hits": hits,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else if hits.is_empty() {
+        eprintln!("No similar messages found.");
+    } else {
+        for hit in &hits {
+            println!("----------------------------------------------------------------");
+            println!(
+                "Score: {:.2} | Agent: {} | WS: {}",
+                hit.score, hit.agent, hit.workspace
+            );
+            match hit.line_number {
+                Some(line) => println!("Path: {}:{line}", hit.source_path),
+                None => println!("Path: {}", hit.source_path),
+            }
+            println!("Snippet: {}", hit.snippet.replace('\n', " "));
+        }
+        println!("----------------------------------------------------------------");
+    }
+
+    Ok(())
+}
+
+/// Cluster indexed sessions into topics and print them as JSON or a table.
+#[allow(clippy::too_many_arguments)]
+fn run_topics(
+    k: Option<usize>,
+    terms: usize,
+    members: usize,
+    agents: &[String],
+    workspaces: &[String],
+    time_filter: TimeFilter,
+    json: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<()> {
+    use crate::search::topics::{TopicOptions, build_topics};
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    if !db_path.exists() {
+        return Err(CliError {
+            code: 3,
+            kind: "missing_index",
+            message: "Database not found".to_string(),
+            hint: Some("Run 'cass index --full' to create the database.".to_string()),
+            retryable: true,
+        });
+    }
+    if k == Some(0) {
+        return Err(CliError {
+            code: 2,
+            kind: "usage",
+            message: "--k must be at least 1".to_string(),
+            hint: None,
+            retryable: false,
+        });
+    }
+
+    let options = TopicOptions {
+        k,
+        label_terms: terms,
+        agents: agents.iter().cloned().collect(),
+        workspaces: workspaces.iter().cloned().collect(),
+        created_from: time_filter.since,
+        created_to: time_filter.until,
+    };
+    let mut report = build_topics(&data_dir, &db_path, &options).map_err(|e| {
+        let msg = e.to_string();
+        if msg.contains("vector index not found") {
+            CliError {
+                code: 15,
+                kind: "semantic-unavailable",
+                message: format!("Topic clustering requires the semantic vector index: {msg}"),
+                hint: Some(
+                    "Run 'cass models install' and press Alt+S in 'cass tui' to build the vector index"
+                        .to_string(),
+                ),
+                retryable: true,
+            }
+        } else {
+            CliError {
+                code: 9,
+                kind: "topics",
+                message: format!("topic clustering failed: {msg}"),
+                hint: None,
+                retryable: false,
+            }
+        }
+    })?;
+    if members > 0 {
+        for topic in &mut report.topics {
+            topic.members.truncate(members);
+        }
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    use colored::Colorize;
+    if report.topics.is_empty() {
+        println!("{}", "No embedded sessions to cluster.".dimmed());
+        return Ok(());
+    }
+    println!(
+        "{} ({} sessions, {} topics)",
+        "Topics".bold().cyan(),
+        report.sessions,
+        report.k
+    );
+    println!("{:<4} {:<6} LABEL", "#", "SIZE");
+    println!("{}", "-".repeat(80));
+    for topic in &report.topics {
+        println!(
+            "{:<4} {:<6} {}",
+            topic.id,
+            topic.size,
+            topic.label.as_str().bold().yellow()
+        );
+        for member in &topic.members {
+            println!(
+                "       • {} [{}] {}",
+                truncate_end(&member.title, 50),
+                member.agent.as_str().green(),
+                member.source_path.as_str().dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_eval(
+    judgments_path: Option<&Path>,
+    k: Option<usize>,
+    modes: &[crate::search::query::SearchMode],
+    rankings: &[String],
+    fusion: Option<crate::search::query::FusionStrategy>,
+    semantic_weight: Option<f32>,
+    embedder: crate::eval::EvalEmbedder,
+    baseline_path: Option<&Path>,
+    from_report: Option<&Path>,
+    save: Option<&Path>,
+    max_regression: Option<f64>,
+    json: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<()> {
+    use crate::eval::{
+        EvalEmbedder, EvalOptions, EvalReport, Judgments, ReportDiff, attach_hash_semantic_context,
+        diff_reports,
+    };
+    use crate::search::query::{FusionConfig, SearchClient};
+    use crate::search::ranking::RankingMode;
+    use crate::search::tantivy::index_dir;
+
+    let usage = |message: String| CliError {
+        code: 2,
+        kind: "usage",
+        message,
+        hint: None,
+        retryable: false,
+    };
+    let load_error = |e: anyhow::Error| CliError {
+        code: 2,
+        kind: "eval-input",
+        message: format!("{e:#}"),
+        hint: None,
+        retryable: false,
+    };
+    if k == Some(0) {
+        return Err(usage("--k must be at least 1".to_string()));
+    }
+    if let Some(weight) = semantic_weight
+        && !(0.0..=1.0).contains(&weight)
+    {
+        return Err(usage(format!(
+            "--semantic-weight must be between 0.0 and 1.0 (got {weight})"
+        )));
+    }
+    let rankings = rankings
+        .iter()
+        .map(|name| {
+            RankingMode::parse(name).ok_or_else(|| {
+                usage(format!(
+                    "unknown ranking mode '{name}' (expected one of: {})",
+                    RankingMode::ALL.map(RankingMode::as_str).join(", ")
+                ))
+            })
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+    let baseline = baseline_path
+        .map(EvalReport::load)
+        .transpose()
+        .map_err(load_error)?;
+
+    let report = if let Some(path) = from_report {
+        EvalReport::load(path).map_err(load_error)?
+    } else {
+        let judgments_path =
+            judgments_path.ok_or_else(|| usage("a judgments file is required".to_string()))?;
+        let judgments = Judgments::load(judgments_path).map_err(load_error)?;
+
+        let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+        let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+        let index_path = index_dir(&data_dir).map_err(|e| CliError {
+            code: 9,
+            kind: "path",
+            message: format!("failed to open index dir: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+        let client = SearchClient::open(&index_path, Some(&db_path))
+            .map_err(|e| CliError {
+                code: 9,
+                kind: "open-index",
+                message: format!("failed to open index: {e}"),
+                hint: Some("try cass index --full".to_string()),
+                retryable: true,
+            })?
+            .ok_or_else(|| CliError {
+                code: 3,
+                kind: "missing-index",
+                message: format!(
+                    "Index not found at {}. Run 'cass index --full' first.",
+                    index_path.display()
+                ),
+                hint: None,
+                retryable: true,
+            })?;
+
+        let mut options = EvalOptions {
+            k,
+            fusion: FusionConfig {
+                strategy: fusion.unwrap_or_default(),
+                semantic_weight,
+                ..FusionConfig::default()
+            },
+            ..EvalOptions::default()
+        };
+        if !modes.is_empty() {
+            options.modes = modes.to_vec();
+        }
+        if !rankings.is_empty() {
+            options.rankings = rankings;
+        }
+        let semantic = if options
+            .modes
+            .iter()
+            .all(|m| *m == crate::search::query::SearchMode::Lexical)
+        {
+            Ok(())
+        } else {
+            match embedder {
+                EvalEmbedder::Model => attach_semantic_context(&client, &data_dir, &db_path),
+                EvalEmbedder::Hash => {
+                    attach_hash_semantic_context(&client, &db_path).map_err(|e| format!("{e:#}"))
+                }
+            }
+        };
+        crate::eval::run_eval(&client, &judgments, &options, semantic).map_err(|e| CliError {
+            code: 9,
+            kind: "eval",
+            message: format!("evaluation failed: {e:#}"),
+            hint: None,
+            retryable: true,
+        })?
+    };
+
+    if let Some(path) = save {
+        let encoded = serde_json::to_string_pretty(&report).unwrap_or_default();
+        std::fs::write(path, encoded + "\n").map_err(|e| CliError {
+            code: 9,
+            kind: "io",
+            message: format!("failed to write {}: {e}", path.display()),
+            hint: None,
+            retryable: false,
+        })?;
+    }
+    let diff: Option<ReportDiff> = baseline.as_ref().map(|b| diff_reports(b, &report));
+
+    if json {
+        let mut payload = serde_json::to_value(&report).unwrap_or_default();
+        if let (Some(diff), serde_json::Value::Object(map)) = (&diff, &mut payload) {
+            map.insert(
+                "diff".to_string(),
+                serde_json::to_value(diff).unwrap_or_default(),
+            );
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else {
+        use colored::Colorize;
+        let k = report.k;
+        println!(
+            "{} ({} queries, k={k})",
+            "Search quality".bold().cyan(),
+            report.queries
+        );
+        println!(
+            "{:<22} {:>9} {:>9} {:>10}",
+            "RUN",
+            format!("nDCG@{k}"),
+            "MRR",
+            format!("Recall@{k}")
+        );
+        println!("{}", "-".repeat(54));
+        for run in &report.runs {
+            println!(
+                "{:<22} {:>9.4} {:>9.4} {:>10.4}",
+                run.run, run.ndcg, run.mrr, run.recall
+            );
+        }
+        for skipped in &report.skipped {
+            println!(
+                "{}",
+                format!("skipped {}: {}", skipped.mode, skipped.reason).dimmed()
+            );
+        }
+        if let Some(diff) = &diff {
+            let signed = |d: f64| {
+                let text = format!("{d:+.4}");
+                if d < -1e-9 {
+                    text.red().to_string()
+                } else if d > 1e-9 {
+                    text.green().to_string()
+                } else {
+                    text.dimmed().to_string()
+                }
+            };
+            println!();
+            println!("{}", "Change vs baseline".bold().cyan());
+            for run in &diff.runs {
+                println!(
+                    "{:<22} {:>9} {:>9} {:>10}",
+                    run.run,
+                    signed(run.ndcg.delta),
+                    signed(run.mrr.delta),
+                    signed(run.recall.delta)
+                );
+                for query in run.changed_queries.iter().filter(|q| q.ndcg.delta < 0.0) {
+                    println!(
+                        "    {} {:.4} -> {:.4}  {}",
+                        "↓".red(),
+                        query.ndcg.baseline,
+                        query.ndcg.current,
+                        query.query
+                    );
+                }
+            }
+            for run in &diff.missing_runs {
+                println!(
+                    "{}",
+                    format!("missing run {run} (in baseline only)").dimmed()
+                );
+            }
+        }
+    }
+
+    if let (Some(limit), Some(diff)) = (max_regression, &diff) {
+        let worst = diff.worst_drop();
+        if worst > limit {
+            return Err(CliError {
+                code: 1,
+                kind: "eval-regression",
+                message: format!(
+                    "search quality regressed by {worst:.4} (allowed {limit:.4}) versus baseline"
+                ),
+                hint: Some("Compare per-query changes in the diff output".to_string()),
+                retryable: false,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Capabilities response for agent introspection.
+/// Provides static information about CLI features, versions, and limits.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilitiesResponse {
+    /// Semantic version of the crate
+    pub crate_version: String,
+    /// API contract version (bumped on breaking changes)
+    pub api_version: u32,
+    /// Human-readable contract identifier
+    pub contract_version: String,
+    /// List of supported feature flags
+    pub features: Vec<String>,
+    /// List of supported agent connectors
+    pub connectors: Vec<String>,
+    /// System limits
+    pub limits: CapabilitiesLimits,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilitiesLimits {
+    /// Maximum --limit value
+    pub max_limit: usize,
+    /// Maximum --max-content-length value (0 = unlimited)
+    pub max_content_length: usize,
+    /// Maximum fields in --fields selection
+    pub max_fields: usize,
+    /// Maximum aggregation bucket count per field
+    pub max_agg_buckets: usize,
+}
+
+// ============================================================================
+// Introspect command schema structures
+// ============================================================================
+
+/// Full API introspection response
+#[derive(Debug, Clone, Serialize)]
 pub struct IntrospectResponse {
     /// API version (matches capabilities)
     pub api_version: u32,
@@ -6427,6 +7874,10 @@ fn run_capabilities(json: bool) -> CliResult<()> {
             "expand_command".to_string(),
             "timeline_command".to_string(),
             "highlight_matches".to_string(),
+            "similar_command".to_string(),
+            "topics_command".to_string(),
+            "hybrid_fusion".to_string(),
+            "eval_command".to_string(),
         ],
         connectors: vec![
             "codex".to_string(),
@@ -6781,7 +8232,10 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
                             "match_type": { "type": ["string", "null"] },
                             "source_id": { "type": "string", "description": "Source identifier (e.g., 'local', 'work-laptop')" },
                             "origin_kind": { "type": "string", "description": "Origin kind ('local' or 'ssh')" },
-                            "origin_host": { "type": ["string", "null"], "description": "Host label for remote sources" }
+                            "origin_host": { "type": ["string", "null"], "description": "Host label for remote sources" },
+                            "owner": { "type": ["string", "null"], "description": "User the session belongs to (shared indexes)" },
+                            "seen_in": { "type": "array", "items": { "type": "string" }, "description": "Every source the session was seen in, when it arrived from more than one" },
+                            "fusion": { "type": ["object", "null"], "description": "Hybrid mode only: lexical_rank/semantic_rank (1-based) and lexical_score/semantic_score" }
                         }
                     }
                 },
@@ -9096,17 +10550,25 @@ fn run_sources_command(cmd: SourcesCommand) -> CliResult<()> {
         SourcesCommand::Remove { name, purge, yes } => {
             run_sources_remove(&name, purge, yes)?;
         }
-        SourcesCommand::Doctor { source, json } => {
-            run_sources_doctor(source.as_deref(), json)?;
+        SourcesCommand::Doctor { source, fix, json } => {
+            run_sources_doctor(source.as_deref(), fix, json)?;
         }
         SourcesCommand::Sync {
             source,
             no_index,
             verbose,
             dry_run,
+            parallel,
             json,
         } => {
-            run_sources_sync(source, no_index, verbose, dry_run, json)?;
+            run_sources_sync(
+                source,
+                no_index,
+                verbose,
+                dry_run,
+                parallel.map(usize::from),
+                json,
+            )?;
         }
         SourcesCommand::Mappings(action) => {
             run_mappings_command(action)?;
@@ -9174,6 +10636,8 @@ fn run_sources_list(verbose: bool, json: bool) -> CliResult<()> {
                     "name": s.name,
                     "type": s.source_type.as_str(),
                     "host": s.host,
+                    "url": s.url,
+                    "branch": s.branch,
                     "paths": s.paths,
                     "sync_schedule": s.sync_schedule.to_string(),
                     "platform": s.platform.map(|p| p.to_string()),
@@ -9212,6 +10676,12 @@ fn run_sources_list(verbose: bool, json: bool) -> CliResult<()> {
                 if let Some(ref host) = source.host {
                     println!("  Host: {host}");
                 }
+                if let Some(ref url) = source.url {
+                    println!("  URL: {url}");
+                }
+                if let Some(ref branch) = source.branch {
+                    println!("  Branch: {branch}");
+                }
                 println!("  Schedule: {}", source.sync_schedule);
                 if let Some(platform) = source.platform {
                     println!("  Platform: {platform}");
@@ -9244,7 +10714,11 @@ fn run_sources_list(verbose: bool, json: bool) -> CliResult<()> {
             println!("  {:15} {:8} {:30} {:>5}", "NAME", "TYPE", "HOST", "PATHS");
             println!("  {}", "-".repeat(62));
             for source in &config.sources {
-                let host = source.host.as_deref().unwrap_or("-");
+                let host = source
+                    .host
+                    .as_deref()
+                    .or(source.url.as_deref())
+                    .unwrap_or("-");
                 let host_truncated = if host.len() > 30 {
                     format!("{}...", &host[..27])
                 } else {
@@ -9563,28 +11037,13 @@ fn run_sources_remove(name: &str, purge: bool, skip_confirm: bool) -> CliResult<
     Ok(())
 }
 
-/// Diagnostic check result for sources doctor command (P5.6)
-#[derive(serde::Serialize)]
-struct DiagnosticCheck {
-    name: String,
-    status: String, // "pass", "warn", "fail"
-    message: String,
-    remediation: Option<String>,
-}
-
-/// Aggregated diagnostics for a single source (P5.6)
-#[derive(serde::Serialize)]
-struct SourceDiagnostics {
-    source_id: String,
-    checks: Vec<DiagnosticCheck>,
-    passed: usize,
-    warnings: usize,
-    failed: usize,
-}
-
 /// Diagnose source connectivity and configuration issues (P5.6)
-fn run_sources_doctor(source_filter: Option<&str>, json_output: bool) -> CliResult<()> {
+///
+/// Grades each source with `sources::doctor`, applies safe repairs with
+/// `--fix`, and saves the results for the TUI sources panel.
+fn run_sources_doctor(source_filter: Option<&str>, fix: bool, json_output: bool) -> CliResult<()> {
     use crate::sources::config::SourcesConfig;
+    use crate::sources::doctor::{CheckStatus, Doctor, Grade, HealthReport};
     use colored::Colorize;
 
     let config = SourcesConfig::load().map_err(|e| CliError {
@@ -9628,42 +11087,25 @@ fn run_sources_doctor(source_filter: Option<&str>, json_output: bool) -> CliResu
         });
     }
 
+    let data_dir = default_data_dir();
+    let doctor = Doctor::new(&data_dir);
     let mut all_diagnostics = Vec::new();
-
     for source in sources_to_check {
-        let mut checks = Vec::new();
-
-        // Check 1: SSH connectivity
-        let host = source.host.as_deref().unwrap_or("unknown");
-        let ssh_check = check_ssh_connectivity(host);
-        checks.push(ssh_check);
-
-        // Check 2: rsync availability on remote
-        let rsync_check = check_rsync_available(host);
-        checks.push(rsync_check);
-
-        // Check 3: Remote paths exist
-        for path in &source.paths {
-            let path_check = check_remote_path(host, path);
-            checks.push(path_check);
-        }
-
-        // Check 4: Local storage writable
-        let storage_check = check_local_storage(&source.name);
-        checks.push(storage_check);
-
-        // Compute summary
-        let passed = checks.iter().filter(|c| c.status == "pass").count();
-        let warnings = checks.iter().filter(|c| c.status == "warn").count();
-        let failed = checks.iter().filter(|c| c.status == "fail").count();
-
-        all_diagnostics.push(SourceDiagnostics {
-            source_id: source.name.clone(),
-            checks,
-            passed,
-            warnings,
-            failed,
-        });
+        let mut diagnostics = doctor.diagnose(source);
+        if fix {
+            doctor.repair(source, &mut diagnostics);
+        }
+        all_diagnostics.push(diagnostics);
+    }
+
+    // Keep the latest results for the TUI sources panel
+    let mut report = HealthReport::load(&data_dir).unwrap_or_default();
+    report.retain_sources(config.sources.iter().map(|s| s.name.as_str()));
+    for diagnostics in &all_diagnostics {
+        report.record(diagnostics);
+    }
+    if let Err(e) = report.save(&data_dir) {
+        tracing::warn!("Failed to save source health: {}", e);
     }
 
     // Output results
@@ -9679,17 +11121,10 @@ fn run_sources_doctor(source_filter: Option<&str>, json_output: bool) -> CliResu
             println!();
 
             for check in &diag.checks {
-                let icon = match check.status.as_str() {
-                    "pass" => "✓".green(),
-                    "warn" => "⚠".yellow(),
-                    "fail" => "✗".red(),
-                    _ => "?".normal(),
-                };
-                let name_styled = match check.status.as_str() {
-                    "pass" => check.name.green(),
-                    "warn" => check.name.yellow(),
-                    "fail" => check.name.red(),
-                    _ => check.name.normal(),
+                let (icon, name_styled) = match check.status {
+                    CheckStatus::Pass => ("✓".green(), check.name.green()),
+                    CheckStatus::Warn => ("⚠".yellow(), check.name.yellow()),
+                    CheckStatus::Fail => ("✗".red(), check.name.red()),
                 };
                 println!("  {} {}", icon, name_styled);
                 println!("    {}", check.message.dimmed());
@@ -9698,227 +11133,57 @@ fn run_sources_doctor(source_filter: Option<&str>, json_output: bool) -> CliResu
                 }
             }
 
-            println!();
-            println!(
-                "Summary: {} passed, {} warnings, {} failed",
-                diag.passed.to_string().green(),
-                diag.warnings.to_string().yellow(),
-                diag.failed.to_string().red()
-            );
-        }
-    }
-
-    // Set exit code based on results
-    let total_failed: usize = all_diagnostics.iter().map(|d| d.failed).sum();
-    if total_failed > 0 {
-        std::process::exit(1);
-    }
-
-    Ok(())
-}
-
-/// Check SSH connectivity to a host
-fn check_ssh_connectivity(host: &str) -> DiagnosticCheck {
-    let output = std::process::Command::new("ssh")
-        .args([
-            "-o",
-            "ConnectTimeout=5",
-            "-o",
-            "BatchMode=yes",
-            "-o",
-            "StrictHostKeyChecking=accept-new",
-            "--",
-            host,
-            "true",
-        ])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => DiagnosticCheck {
-            name: "SSH Connectivity".into(),
-            status: "pass".into(),
-            message: format!("Connected to {} successfully", host),
-            remediation: None,
-        },
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            let remediation = if stderr.contains("Permission denied") {
-                Some("Ensure SSH key is added to remote authorized_keys".into())
-            } else if stderr.contains("Connection refused") {
-                Some("Verify SSH server is running on remote host".into())
-            } else if stderr.contains("Could not resolve") {
-                Some("Check hostname is correct and DNS resolves".into())
-            } else {
-                Some("Check SSH configuration and network connectivity".into())
-            };
-            DiagnosticCheck {
-                name: "SSH Connectivity".into(),
-                status: "fail".into(),
-                message: stderr.trim().to_string(),
-                remediation,
-            }
-        }
-        Err(e) => DiagnosticCheck {
-            name: "SSH Connectivity".into(),
-            status: "fail".into(),
-            message: format!("Failed to run ssh: {}", e),
-            remediation: Some("Ensure SSH client is installed and in PATH".into()),
-        },
-    }
-}
-
-/// Check rsync availability on remote
-fn check_rsync_available(host: &str) -> DiagnosticCheck {
-    let output = std::process::Command::new("ssh")
-        .args([
-            "-o",
-            "ConnectTimeout=5",
-            "-o",
-            "BatchMode=yes",
-            "--",
-            host,
-            "rsync",
-            "--version",
-        ])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            let version = stdout
-                .lines()
-                .next()
-                .unwrap_or("version unknown")
-                .to_string();
-            DiagnosticCheck {
-                name: "rsync Available".into(),
-                status: "pass".into(),
-                message: version,
-                remediation: None,
-            }
-        }
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            DiagnosticCheck {
-                name: "rsync Available".into(),
-                status: "fail".into(),
-                message: format!("rsync not found: {}", stderr.trim()),
-                remediation: Some("Install rsync on the remote host".into()),
-            }
-        }
-        Err(e) => DiagnosticCheck {
-            name: "rsync Available".into(),
-            status: "warn".into(),
-            message: format!("Could not check rsync: {}", e),
-            remediation: Some("SSH connectivity may have failed".into()),
-        },
-    }
-}
-
-fn sh_quote(value: &str) -> String {
-    if value.is_empty() {
-        "''".to_string()
-    } else {
-        format!("'{}'", value.replace('\'', "'\"'\"'"))
-    }
-}
-
-/// Check if a remote path exists
-fn check_remote_path(host: &str, path: &str) -> DiagnosticCheck {
-    let quoted = sh_quote(path);
-    let cmd = format!("test -d {quoted} && ls -1 {quoted} | wc -l");
-    let output = std::process::Command::new("ssh")
-        .args([
-            "-o",
-            "ConnectTimeout=5",
-            "-o",
-            "BatchMode=yes",
-            "--",
-            host,
-            "sh",
-            "-c",
-            &cmd,
-        ])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            let count = String::from_utf8_lossy(&out.stdout)
-                .trim()
-                .parse::<usize>()
-                .unwrap_or(0);
-            DiagnosticCheck {
-                name: format!("Remote Path: {}", path),
-                status: if count > 0 { "pass" } else { "warn" }.into(),
-                message: if count > 0 {
-                    format!("Path exists, {} items found", count)
-                } else {
-                    "Path exists but is empty".into()
-                },
-                remediation: if count == 0 {
-                    Some("No agent sessions on this machine yet".into())
-                } else {
-                    None
-                },
+            if !diag.repairs.is_empty() {
+                println!();
+                println!("  {}", "Repairs:".bold());
+                for outcome in &diag.repairs {
+                    let icon = if outcome.success {
+                        "✓".green()
+                    } else {
+                        "✗".red()
+                    };
+                    println!(
+                        "  {} {}: {}",
+                        icon,
+                        outcome.repair.describe(),
+                        outcome.message
+                    );
+                }
             }
-        }
-        Ok(_) => DiagnosticCheck {
-            name: format!("Remote Path: {}", path),
-            status: "fail".into(),
-            message: "Path does not exist".into(),
-            remediation: Some("Remove this path or create it on the remote".into()),
-        },
-        Err(e) => DiagnosticCheck {
-            name: format!("Remote Path: {}", path),
-            status: "warn".into(),
-            message: format!("Could not check path: {}", e),
-            remediation: Some("SSH connectivity may have failed".into()),
-        },
-    }
-}
 
-/// Check if local storage directory is writable
-fn check_local_storage(source_name: &str) -> DiagnosticCheck {
-    let data_dir = default_data_dir();
-    let source_dir = data_dir.join("remotes").join(source_name);
-
-    // Try to create the directory if it doesn't exist
-    if !source_dir.exists() {
-        if std::fs::create_dir_all(&source_dir).is_ok() {
-            return DiagnosticCheck {
-                name: "Local Storage".into(),
-                status: "pass".into(),
-                message: format!("{} is writable", source_dir.display()),
-                remediation: None,
-            };
-        } else {
-            return DiagnosticCheck {
-                name: "Local Storage".into(),
-                status: "fail".into(),
-                message: format!("Cannot create {}", source_dir.display()),
-                remediation: Some("Check file permissions on data directory".into()),
+            let grade = match diag.grade {
+                Grade::Healthy => diag.grade.as_str().green(),
+                Grade::Degraded => diag.grade.as_str().yellow(),
+                Grade::Failing => diag.grade.as_str().red(),
             };
+            println!();
+            println!(
+                "Summary: {} passed, {} warnings, {} failed ({})",
+                diag.passed.to_string().green(),
+                diag.warnings.to_string().yellow(),
+                diag.failed.to_string().red(),
+                grade
+            );
+            if !fix {
+                let repairs: Vec<String> = diag.pending_repairs().map(|r| r.describe()).collect();
+                if !repairs.is_empty() {
+                    println!(
+                        "{}: run with --fix to {}",
+                        "Fixable".cyan(),
+                        repairs.join(", ")
+                    );
+                }
+            }
         }
     }
 
-    // Directory exists, check if writable
-    let test_file = source_dir.join(".doctor_test");
-    if std::fs::write(&test_file, b"test").is_ok() {
-        let _ = std::fs::remove_file(&test_file);
-        DiagnosticCheck {
-            name: "Local Storage".into(),
-            status: "pass".into(),
-            message: format!("{} is writable", source_dir.display()),
-            remediation: None,
-        }
-    } else {
-        DiagnosticCheck {
-            name: "Local Storage".into(),
-            status: "fail".into(),
-            message: format!("{} is not writable", source_dir.display()),
-            remediation: Some("Check file permissions on data directory".into()),
-        }
+    // Set exit code based on results
+    let total_failed: usize = all_diagnostics.iter().map(|d| d.failed).sum();
+    if total_failed > 0 {
+        std::process::exit(1);
     }
+
+    Ok(())
 }
 
 /// Sync sessions from remote sources (P5.5)
@@ -9927,10 +11192,12 @@ fn run_sources_sync(
     no_index: bool,
     verbose: bool,
     dry_run: bool,
+    parallel: Option<usize>,
     json_output: bool,
 ) -> CliResult<()> {
     use crate::sources::config::SourcesConfig;
-    use crate::sources::sync::{SyncEngine, SyncStatus};
+    use crate::sources::filter::FileFilter;
+    use crate::sources::sync::{DEFAULT_SYNC_PARALLELISM, SyncEngine, SyncProgress, SyncStatus};
     use colored::Colorize;
 
     let config = SourcesConfig::load().map_err(|e| CliError {
@@ -9992,7 +11259,11 @@ fn run_sources_sync(
     let data_dir = default_data_dir();
 
     // Create sync engine
-    let engine = SyncEngine::new(&data_dir);
+    let parallelism = parallel
+        .or(config.sync_parallelism)
+        .unwrap_or(DEFAULT_SYNC_PARALLELISM)
+        .max(1);
+    let engine = SyncEngine::new(&data_dir).with_parallelism(parallelism);
 
     // Load existing sync status
     let mut status = SyncStatus::load(&data_dir).unwrap_or_default();
@@ -10006,155 +11277,548 @@ fn run_sources_sync(
     let mut total_files = 0u64;
     let mut total_bytes = 0u64;
 
-    for source in &sources_to_sync {
-        if !json_output {
-            println!(
-                "{} {}...",
-                "Syncing".cyan().bold(),
-                source.name.white().bold()
-            );
-        }
+    if dry_run {
+        for source in &sources_to_sync {
+            // Validated when the config was loaded
+            let filter = FileFilter::new(&source.filter).unwrap_or_default();
+            let (paths, skipped): (Vec<&String>, Vec<&String>) = source
+                .paths
+                .iter()
+                .partition(|p| filter.allows_source_path(p));
+            if json_output {
+                all_reports.push(serde_json::json!({
+                    "source": source.name,
+                    "status": "dry_run",
+                    "url": source.url,
+                    "paths": paths,
+                    "skipped_paths": skipped,
+                    "bwlimit": source.bwlimit,
+                    "filter": source.filter,
+                    "rsync_args": filter.rsync_args(),
+                }));
+                continue;
+            }
+            println!(
+                "{} {}...",
+                "Syncing".cyan().bold(),
+                source.name.white().bold()
+            );
+            // In dry run, just show what would be synced
+            if let Some(url) = &source.url {
+                println!("  {} {}", "Would fetch:".dimmed(), url);
+            }
+            for path in paths {
+                println!("  {} {}", "Would sync:".dimmed(), path);
+            }
+            for path in skipped {
+                println!("  {} {} (agent not selected)", "Would skip:".dimmed(), path);
+            }
+            if let Some(limit) = source.bwlimit {
+                println!("  {} {} KiB/s", "Bandwidth limit:".dimmed(), limit);
+            }
+            let rules = &source.filter;
+            if !rules.agents.is_empty() {
+                println!("  {} {}", "Agents:".dimmed(), rules.agents.join(", "));
+            }
+            if !rules.include.is_empty() {
+                println!("  {} {}", "Include:".dimmed(), rules.include.join(", "));
+            }
+            if !rules.exclude.is_empty() {
+                println!("  {} {}", "Exclude:".dimmed(), rules.exclude.join(", "));
+            }
+            if let Some(max) = filter.max_size() {
+                println!("  {} {}", "Max file size:".dimmed(), format_bytes(max));
+            }
+            if let Some(days) = rules.max_age_days {
+                println!("  {} {} days", "Max age:".dimmed(), days);
+            }
+            let rsync_args = filter.rsync_args();
+            if !rsync_args.is_empty() {
+                println!("  {} {}", "rsync filter:".dimmed(), rsync_args.join(" "));
+            }
+            println!();
+        }
+    } else {
+        if !json_output && sources_to_sync.len() > 1 {
+            println!(
+                "{} {} sources, {} at a time",
+                "Syncing".cyan().bold(),
+                sources_to_sync.len(),
+                parallelism.min(sources_to_sync.len())
+            );
+            println!();
+        }
+
+        // Sources run concurrently, so progress is printed as it arrives
+        let results = engine.sync_sources(&sources_to_sync, |event| {
+            if json_output {
+                eprintln!("{}", serde_json::to_string(&event).unwrap_or_default());
+                return;
+            }
+            match event {
+                SyncProgress::SourceStarted { source } => {
+                    println!("{} {}...", "Syncing".cyan().bold(), source.white().bold());
+                }
+                SyncProgress::PathFinished {
+                    source,
+                    path,
+                    success,
+                    files,
+                    bytes,
+                    error,
+                } => {
+                    let outcome = if !success {
+                        error.as_deref().unwrap_or("failed").red().to_string()
+                    } else if verbose || files > 0 {
+                        format!(
+                            "{} files ({})",
+                            files.to_string().green(),
+                            format_bytes(bytes)
+                        )
+                    } else {
+                        "up to date".green().to_string()
+                    };
+                    println!("  {} {}: {}", source.white(), path.dimmed(), outcome);
+                }
+                SyncProgress::SourceFinished {
+                    source,
+                    success,
+                    files,
+                    bytes,
+                    duration_ms,
+                    error,
+                } => match error {
+                    Some(e) => println!("{} {}: {}", "Error".red().bold(), source, e.red()),
+                    None => println!(
+                        "{} {}: {} files, {} in {:.1}s",
+                        if success {
+                            "Synced".green().bold()
+                        } else {
+                            "Partial".yellow().bold()
+                        },
+                        source,
+                        files,
+                        format_bytes(bytes),
+                        duration_ms as f64 / 1000.0
+                    ),
+                },
+            }
+        });
+        if !json_output {
+            println!();
+        }
+
+        for (source, result) in sources_to_sync.iter().zip(results) {
+            let report = match result {
+                Ok(r) => r,
+                Err(e) => {
+                    if json_output {
+                        all_reports.push(serde_json::json!({
+                            "source": source.name,
+                            "status": "error",
+                            "error": e.to_string()
+                        }));
+                    }
+                    status.record_error(&source.name, &e.to_string());
+                    continue;
+                }
+            };
+
+            // Update status
+            status.update(&source.name, &report);
+
+            if json_output {
+                all_reports.push(serde_json::json!({
+                    "source": source.name,
+                    "status": if report.all_succeeded { "success" } else { "partial" },
+                    "method": report.method.to_string(),
+                    "paths": report.path_results.iter().map(|r| serde_json::json!({
+                        "path": r.remote_path,
+                        "success": r.success,
+                        "files": r.files_transferred,
+                        "bytes": r.bytes_transferred,
+                        "error": r.error,
+                    })).collect::<Vec<_>>(),
+                    "total_files": report.total_files(),
+                    "total_bytes": report.total_bytes(),
+                    "duration_ms": report.total_duration_ms,
+                }));
+            }
+
+            total_files += report.total_files();
+            total_bytes += report.total_bytes();
+        }
+    }
+
+    // Save sync status
+    if !dry_run && let Err(e) = status.save(&data_dir) {
+        tracing::warn!("Failed to save sync status: {}", e);
+    }
+
+    // Output summary
+    if json_output {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "status": "complete",
+                "dry_run": dry_run,
+                "parallelism": parallelism,
+                "sources": all_reports,
+                "total_files": total_files,
+                "total_bytes": total_bytes,
+                "will_reindex": !no_index && !dry_run,
+            }))
+            .unwrap_or_default()
+        );
+    }
+
+    // Trigger re-index if requested
+    if !no_index && !dry_run && total_files > 0 {
+        if !json_output {
+            println!(
+                "{} {} new files...",
+                "Re-indexing".cyan().bold(),
+                total_files
+            );
+        }
+
+        // Call indexer to include synced sessions
+        let progress = if json_output {
+            ProgressResolved::None
+        } else if std::io::stdout().is_terminal() {
+            ProgressResolved::Bars
+        } else {
+            ProgressResolved::Plain
+        };
+
+        run_index_with_data(
+            None,           // db_override (uses data_dir default)
+            false,          // full
+            false,          // force_rebuild
+            false,          // watch
+            None,           // watch_once
+            Some(data_dir), // data_dir
+            progress,
+            json_output,
+            None, // idempotency_key
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Run the background sync daemon, or install its systemd unit
+fn run_daemon(
+    once: bool,
+    install_systemd: bool,
+    data_dir_override: Option<PathBuf>,
+    json_output: bool,
+) -> CliResult<()> {
+    use crate::sources::daemon::{self, SyncDaemon};
+    use colored::Colorize;
+
+    if install_systemd {
+        let exe = std::env::current_exe()
+            .map_err(|e| CliError::unknown(format!("Cannot locate cass executable: {e}")))?;
+        let path = daemon::install_systemd_unit(&exe, data_dir_override.as_deref())
+            .map_err(|e| CliError::unknown(format!("Failed to write systemd unit: {e:#}")))?;
+        let enable = format!(
+            "systemctl --user enable --now {}",
+            daemon::SYSTEMD_UNIT_NAME
+        );
+        if json_output {
+            println!(
+                "{}",
+                serde_json::json!({ "unit_path": path, "enable_command": enable })
+            );
+        } else {
+            println!("{} {}", "Wrote".green().bold(), path.display());
+            println!("Enable it with: {}", enable.cyan());
+        }
+        return Ok(());
+    }
+
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let daemon = SyncDaemon::new(&data_dir);
+    if !json_output && !once {
+        eprintln!(
+            "{} sources on their sync_schedule (Ctrl+C to stop)",
+            "Watching".cyan().bold()
+        );
+    }
+
+    let result = daemon.run(once, |report| {
+        if json_output {
+            println!("{}", serde_json::to_string(report).unwrap_or_default());
+        } else {
+            if report.scheduled == 0 {
+                println!(
+                    "{}",
+                    "No remote sources have a sync_schedule; set one in sources.toml.".yellow()
+                );
+            }
+            for pass in &report.synced {
+                match &pass.error {
+                    Some(e) => println!("{} {}: {}", "Sync".red().bold(), pass.source, e.red()),
+                    None => println!(
+                        "{} {}: {} files, {}",
+                        "Synced".green().bold(),
+                        pass.source,
+                        pass.files,
+                        format_bytes(pass.bytes)
+                    ),
+                }
+            }
+        }
+        if report.needs_index() {
+            let progress = if json_output {
+                ProgressResolved::None
+            } else {
+                ProgressResolved::Plain
+            };
+            run_index_with_data(
+                None,
+                false,
+                false,
+                false,
+                None,
+                Some(data_dir.clone()),
+                progress,
+                false,
+                None,
+            )
+            .map_err(|e| anyhow::anyhow!(e.message))?;
+        }
+        Ok(())
+    });
+
+    result.map_err(|e| CliError {
+        code: 9,
+        kind: "daemon",
+        message: format!("{e:#}"),
+        hint: Some("Run 'cass sources list' to check the sources config".into()),
+        retryable: true,
+    })
+}
+
+/// Serve the ingest endpoint for push sources, or issue a push token
+fn run_serve_ingest(
+    listen: &str,
+    new_token: Option<&str>,
+    data_dir_override: Option<PathBuf>,
+    json_output: bool,
+) -> CliResult<()> {
+    use crate::sources::config::SourcesConfig;
+    use crate::sources::ingest::{IngestServer, issue_token};
+    use colored::Colorize;
+
+    let ingest_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "ingest",
+        message: format!("{e:#}"),
+        hint: None,
+        retryable: false,
+    };
+
+    if let Some(name) = new_token {
+        let mut config = SourcesConfig::load().map_err(|e| ingest_err(e.into()))?;
+        let token = issue_token(&mut config, name).map_err(ingest_err)?;
+        config.save().map_err(|e| ingest_err(e.into()))?;
+        if json_output {
+            println!(
+                "{}",
+                serde_json::json!({ "source_id": name, "token": token })
+            );
+        } else {
+            println!("{} push source {}", "Saved".green().bold(), name.cyan());
+            println!("Token (shown once): {}", token.bold());
+            println!("Push with: CASS_PUSH_TOKEN=<token> cass push --to http://<this host>:<port>");
+        }
+        return Ok(());
+    }
+
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let db_path = data_dir.join("agent_search.db");
+    let server = IngestServer::bind(&data_dir, &db_path, listen).map_err(|e| CliError {
+        code: 9,
+        kind: "ingest",
+        message: format!("{e:#}"),
+        hint: Some("Pick another address with --listen".into()),
+        retryable: true,
+    })?;
+    let url = server.url().map_err(ingest_err)?;
+    let loopback = server.local_addr().map_err(ingest_err)?.ip().is_loopback();
+    if !loopback {
+        eprintln!(
+            "{} {} is not a loopback address and upload tokens travel unencrypted; \
+             put a TLS reverse proxy in front of serve-ingest",
+            "Warning:".yellow().bold(),
+            listen
+        );
+    }
+    if json_output {
+        println!(
+            "{}",
+            serde_json::json!({ "event": "listening", "url": url })
+        );
+    } else {
+        eprintln!(
+            "{} push uploads on {} (Ctrl+C to stop)",
+            "Accepting".cyan().bold(),
+            url
+        );
+    }
+
+    server
+        .run(move |sources| {
+            if json_output {
+                println!(
+                    "{}",
+                    serde_json::json!({ "event": "files", "sources": sources })
+                );
+            } else {
+                println!(
+                    "{} files from {}",
+                    "Indexing".green().bold(),
+                    sources.join(", ")
+                );
+            }
+            let progress = if json_output {
+                ProgressResolved::None
+            } else {
+                ProgressResolved::Plain
+            };
+            if let Err(e) = run_index_with_data(
+                None,
+                false,
+                false,
+                false,
+                None,
+                Some(data_dir.clone()),
+                progress,
+                false,
+                None,
+            ) {
+                tracing::warn!("serve-ingest: indexing failed: {}", e.message);
+            }
+        })
+        .map_err(ingest_err)
+}
+
+/// Upload new session files (or conversation JSONL) to a serve-ingest server
+fn run_push(
+    to: &str,
+    token: &str,
+    paths: Vec<String>,
+    jsonl: Option<&Path>,
+    data_dir_override: Option<PathBuf>,
+    json_output: bool,
+) -> CliResult<()> {
+    use crate::sources::ingest::{PushClient, PushState, default_push_roots, push_roots};
+    use colored::Colorize;
+    use std::io::Read;
+
+    let push_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "push",
+        message: format!("{e:#}"),
+        hint: Some("Check --to and the token from 'cass serve-ingest --new-token'".into()),
+        retryable: true,
+    };
 
-        if dry_run {
-            // In dry run, just show what would be synced
-            if !json_output {
-                for path in &source.paths {
-                    println!("  {} {}", "Would sync:".dimmed(), path);
-                }
-                println!();
-            }
-            continue;
-        }
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
 
-        // Perform actual sync
-        let report = match engine.sync_source(source) {
-            Ok(r) => r,
-            Err(e) => {
-                if json_output {
-                    all_reports.push(serde_json::json!({
-                        "source": source.name,
-                        "status": "error",
-                        "error": e.to_string()
-                    }));
-                } else {
-                    println!("  {} {}", "Error:".red().bold(), e.to_string().red());
-                }
-                continue;
-            }
-        };
+    let body = match jsonl {
+        Some(path) if path == Path::new("-") => {
+            let mut buf = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut buf)
+                .map_err(|e| push_err(e.into()))?;
+            Some(buf)
+        }
+        Some(path) => Some(
+            std::fs::read(path)
+                .map_err(|e| push_err(anyhow::anyhow!("Cannot read {}: {e}", path.display())))?,
+        ),
+        None => None,
+    };
 
-        // Update status
-        status.update(&source.name, &report);
+    // The blocking HTTP client cannot be used on the async runtime's threads
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| -> CliResult<()> {
+                let client = PushClient::new(to, token).map_err(push_err)?;
+                let source_id = client.status().map_err(push_err)?;
 
-        // Print results
-        if json_output {
-            all_reports.push(serde_json::json!({
-                "source": source.name,
-                "status": if report.all_succeeded { "success" } else { "partial" },
-                "method": report.method.to_string(),
-                "paths": report.path_results.iter().map(|r| serde_json::json!({
-                    "path": r.remote_path,
-                    "success": r.success,
-                    "files": r.files_transferred,
-                    "bytes": r.bytes_transferred,
-                    "error": r.error,
-                })).collect::<Vec<_>>(),
-                "total_files": report.total_files(),
-                "total_bytes": report.total_bytes(),
-                "duration_ms": report.total_duration_ms,
-            }));
-        } else {
-            for result in &report.path_results {
-                if result.success {
-                    if verbose || result.files_transferred > 0 {
+                if let Some(body) = body {
+                    let receipt = client.push_conversations(body).map_err(push_err)?;
+                    if json_output {
                         println!(
-                            "  {}: {} files ({} bytes)",
-                            result.remote_path.dimmed(),
-                            result.files_transferred.to_string().green(),
-                            format_bytes(result.bytes_transferred)
+                            "{}",
+                            serde_json::json!({
+                                "source_id": source_id,
+                                "conversations": receipt.conversations,
+                                "new_messages": receipt.new_messages,
+                            })
                         );
                     } else {
                         println!(
-                            "  {}: {}",
-                            result.remote_path.dimmed(),
-                            "up to date".green()
+                            "{} {} conversations to {} ({} new messages)",
+                            "Pushed".green().bold(),
+                            receipt.conversations,
+                            source_id.cyan(),
+                            receipt.new_messages
                         );
                     }
+                    return Ok(());
+                }
+
+                let roots = if paths.is_empty() {
+                    default_push_roots()
+                } else {
+                    paths.clone()
+                };
+                let mut state = PushState::load(&data_dir).map_err(push_err)?;
+                let report = push_roots(&client, &roots, &mut state);
+                state.save(&data_dir).map_err(push_err)?;
+
+                if json_output {
+                    let mut value = serde_json::to_value(&report).unwrap_or_default();
+                    value["source_id"] = serde_json::json!(source_id);
+                    println!("{value}");
                 } else {
+                    if report.roots.is_empty() {
+                        println!("{}", "No session directories found to push.".yellow());
+                    }
                     println!(
-                        "  {}: {}",
-                        result.remote_path.dimmed(),
-                        result.error.as_deref().unwrap_or("failed").red()
+                        "{} {} to {}: {} uploaded, {} appended, {} unchanged",
+                        "Pushed".green().bold(),
+                        format_bytes(report.bytes),
+                        source_id.cyan(),
+                        report.uploaded,
+                        report.appended,
+                        report.unchanged
                     );
+                    for error in &report.errors {
+                        eprintln!("  {} {}", "Failed".red(), error);
+                    }
                 }
-            }
-            println!(
-                "  {} {} files, {}",
-                "Total:".dimmed(),
-                report.total_files(),
-                format_bytes(report.total_bytes())
-            );
-            println!();
-        }
-
-        total_files += report.total_files();
-        total_bytes += report.total_bytes();
-    }
-
-    // Save sync status
-    if !dry_run && let Err(e) = status.save(&data_dir) {
-        tracing::warn!("Failed to save sync status: {}", e);
-    }
-
-    // Output summary
-    if json_output {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "status": "complete",
-                "dry_run": dry_run,
-                "sources": all_reports,
-                "total_files": total_files,
-                "total_bytes": total_bytes,
-                "will_reindex": !no_index && !dry_run,
-            }))
-            .unwrap_or_default()
-        );
-    }
-
-    // Trigger re-index if requested
-    if !no_index && !dry_run && total_files > 0 {
-        if !json_output {
-            println!(
-                "{} {} new files...",
-                "Re-indexing".cyan().bold(),
-                total_files
-            );
-        }
-
-        // Call indexer to include synced sessions
-        let progress = if json_output {
-            ProgressResolved::None
-        } else if std::io::stdout().is_terminal() {
-            ProgressResolved::Bars
-        } else {
-            ProgressResolved::Plain
-        };
-
-        run_index_with_data(
-            None,           // db_override (uses data_dir default)
-            false,          // full
-            false,          // force_rebuild
-            false,          // watch
-            None,           // watch_once
-            Some(data_dir), // data_dir
-            progress,
-            json_output,
-            None, // idempotency_key
-        )?;
-    }
 
-    Ok(())
+                if report.errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(CliError {
+                        code: 9,
+                        kind: "push",
+                        message: format!("{} files failed to upload", report.errors.len()),
+                        hint: Some("Run 'cass push' again; uploaded files are not resent".into()),
+                        retryable: true,
+                    })
+                }
+            })
+            .join()
+            .unwrap_or_else(|_| Err(CliError::unknown("push thread panicked")))
+    })
 }
 
 /// Auto-discover SSH hosts from ~/.ssh/config (P5.6)
@@ -10357,10 +12021,480 @@ fn run_sources_setup(opts: sources::setup::SetupOptions) -> CliResult<()> {
     }
 }
 
+/// Environment variable holding the password that unlocks an archive
+const PAGES_PASSWORD_ENV: &str = "CASS_PAGES_PASSWORD";
+
+/// Environment variable holding the password for a new key slot
+const PAGES_NEW_PASSWORD_ENV: &str = "CASS_PAGES_NEW_PASSWORD";
+
+/// Read a pages password from the environment, or prompt on a terminal
+fn pages_password(env_var: &str, prompt: &str, confirm: bool) -> CliResult<String> {
+    use std::io::IsTerminal;
+
+    if let Ok(password) = dotenvy::var(env_var)
+        && !password.is_empty()
+    {
+        return Ok(password);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(CliError {
+            code: 2,
+            kind: "usage",
+            message: format!("{prompt}: no password given"),
+            hint: Some(format!("Set {env_var} or run in a terminal")),
+            retryable: false,
+        });
+    }
+
+    let mut input = dialoguer::Password::new().with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation("Confirm password", "Passwords don't match");
+    }
+    input.interact().map_err(|e| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("Failed to read password: {e}"),
+        hint: None,
+        retryable: false,
+    })
+}
+
+/// Credential for unlocking an archive: a recovery file, an identity file or a password
+fn pages_unlock(
+    recovery_file: Option<&Path>,
+    identity_file: Option<&Path>,
+) -> CliResult<crate::pages::keys::Unlock> {
+    use crate::pages::keys::{Unlock, parse_recovery_secret};
+    use crate::pages::recipient::Identity;
+
+    if let Some(path) = identity_file {
+        let identity = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Identity::parse(&text))
+            .map_err(|e| CliError {
+                code: 9,
+                kind: "pages",
+                message: format!("Failed to read identity {}: {e}", path.display()),
+                hint: None,
+                retryable: false,
+            })?;
+        return Ok(Unlock::Identity(identity));
+    }
+    match recovery_file {
+        Some(path) => {
+            let secret = std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| parse_recovery_secret(&text))
+                .map_err(|e| CliError {
+                    code: 9,
+                    kind: "pages",
+                    message: format!("Failed to read recovery secret {}: {e}", path.display()),
+                    hint: None,
+                    retryable: false,
+                })?;
+            Ok(Unlock::Recovery(secret))
+        }
+        None => Ok(Unlock::Password(pages_password(
+            PAGES_PASSWORD_ENV,
+            "Archive password",
+            false,
+        )?)),
+    }
+}
+
+/// Handle `cass pages keys` subcommands
+fn run_pages_keys(cmd: PagesKeysCommand) -> CliResult<()> {
+    use crate::pages::keys::{
+        NewSlot, add_slot, generate_recovery_secret, list_slots, resolve_site_dir, revoke_slot,
+        rotate,
+    };
+    use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+    use colored::Colorize;
+
+    let pages_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("{e:#}"),
+        hint: None,
+        retryable: false,
+    };
+    let print_json = |value: serde_json::Value| {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        );
+    };
+    let print_recovery = |secret: &[u8]| {
+        println!(
+            "  {} Recovery secret (save this securely, it is not stored anywhere):",
+            "⚠".yellow().bold()
+        );
+        println!("    {}", BASE64.encode(secret).cyan());
+    };
+
+    match cmd {
+        PagesKeysCommand::List { bundle_dir, json } => {
+            let site_dir = resolve_site_dir(&bundle_dir).map_err(pages_err)?;
+            let slots = list_slots(&site_dir).map_err(pages_err)?;
+            if json {
+                print_json(serde_json::json!({
+                    "site_dir": site_dir.display().to_string(),
+                    "slots": slots,
+                }));
+                return Ok(());
+            }
+            println!("Key slots in {}:", site_dir.display());
+            for slot in slots {
+                let kind = serde_json::to_value(slot.slot_type).unwrap_or_default();
+                let kdf = serde_json::to_value(slot.kdf).unwrap_or_default();
+                println!(
+                    "  {:>3}  {:<10} {:<18} {}",
+                    slot.id,
+                    kind.as_str().unwrap_or_default(),
+                    kdf.as_str().unwrap_or_default(),
+                    slot.recipient.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        PagesKeysCommand::Add {
+            bundle_dir,
+            recovery,
+            recipient,
+            recovery_file,
+            identity_file,
+            json,
+        } => {
+            let site_dir = resolve_site_dir(&bundle_dir).map_err(pages_err)?;
+            let unlock = pages_unlock(recovery_file.as_deref(), identity_file.as_deref())?;
+            let (slot_id, secret) = if recovery {
+                let secret = generate_recovery_secret();
+                let id =
+                    add_slot(&site_dir, &unlock, NewSlot::Recovery(&secret)).map_err(pages_err)?;
+                (id, Some(secret))
+            } else if let Some(recipient) = &recipient {
+                let id = add_slot(&site_dir, &unlock, NewSlot::Recipient(recipient))
+                    .map_err(pages_err)?;
+                (id, None)
+            } else {
+                let password = pages_password(PAGES_NEW_PASSWORD_ENV, "New password", true)?;
+                let id = add_slot(&site_dir, &unlock, NewSlot::Password(&password))
+                    .map_err(pages_err)?;
+                (id, None)
+            };
+            if json {
+                print_json(serde_json::json!({
+                    "slot": slot_id,
+                    "slot_type": if recovery {
+                        "recovery"
+                    } else if recipient.is_some() {
+                        "recipient"
+                    } else {
+                        "password"
+                    },
+                    "recovery_secret": secret.as_ref().map(|s| BASE64.encode(s)),
+                }));
+                return Ok(());
+            }
+            println!("{} Added key slot {}", "✓".green(), slot_id);
+            if let Some(secret) = secret {
+                print_recovery(&secret);
+            }
+            println!("  Redeploy the site for the change to take effect.");
+        }
+        PagesKeysCommand::Revoke {
+            bundle_dir,
+            slot,
+            recovery_file,
+            identity_file,
+            json,
+        } => {
+            let site_dir = resolve_site_dir(&bundle_dir).map_err(pages_err)?;
+            let unlock = pages_unlock(recovery_file.as_deref(), identity_file.as_deref())?;
+            revoke_slot(&site_dir, &unlock, slot).map_err(pages_err)?;
+            if json {
+                print_json(serde_json::json!({ "revoked": slot }));
+                return Ok(());
+            }
+            println!("{} Revoked key slot {}", "✓".green(), slot);
+            println!(
+                "  Anyone who already unlocked the archive still has its key; run `cass pages keys rotate` to re-key it."
+            );
+        }
+        PagesKeysCommand::Rotate {
+            bundle_dir,
+            new_password,
+            recovery,
+            recovery_file,
+            identity_file,
+            yes,
+            json,
+        } => {
+            use std::io::IsTerminal;
+
+            let site_dir = resolve_site_dir(&bundle_dir).map_err(pages_err)?;
+            let unlock = pages_unlock(recovery_file.as_deref(), identity_file.as_deref())?;
+            let password = if new_password || recovery_file.is_some() || identity_file.is_some() {
+                Some(pages_password(
+                    PAGES_NEW_PASSWORD_ENV,
+                    "New password",
+                    true,
+                )?)
+            } else {
+                None
+            };
+            let secret = recovery.then(generate_recovery_secret);
+            let interactive = !json && std::io::stdin().is_terminal();
+            let mut declined = false;
+            let mut unconfirmed = false;
+            let confirm_drop = |dropped: &[crate::pages::keys::SlotSummary]| {
+                if yes {
+                    return true;
+                }
+                if !interactive {
+                    unconfirmed = true;
+                    return false;
+                }
+                println!(
+                    "Rotation cannot carry over these key slots (their secrets are unknown):"
+                );
+                for slot in dropped {
+                    let kind = serde_json::to_value(slot.slot_type).unwrap_or_default();
+                    println!("  {:>3}  {}", slot.id, kind.as_str().unwrap_or_default());
+                }
+                print!("Remove them and rotate? [y/N] ");
+                std::io::Write::flush(&mut std::io::stdout()).ok();
+                let mut input = String::new();
+                std::io::stdin().read_line(&mut input).ok();
+                let input = input.trim().to_lowercase();
+                declined = input != "y" && input != "yes";
+                !declined
+            };
+            let outcome = match rotate(
+                &site_dir,
+                &unlock,
+                password.as_deref(),
+                secret.as_deref(),
+                confirm_drop,
+            ) {
+                Ok(outcome) => outcome,
+                Err(_) if declined => {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+                Err(e) => {
+                    return Err(CliError {
+                        hint: unconfirmed.then(|| {
+                            "Pass --yes to drop them, or re-add them after rotating with `cass pages keys add`".to_string()
+                        }),
+                        ..pages_err(e)
+                    });
+                }
+            };
+            if json {
+                print_json(serde_json::json!({
+                    "slots": outcome.slots,
+                    "dropped": outcome.dropped,
+                    "chunks": outcome.chunks,
+                    "fingerprint": outcome.fingerprint,
+                    "recovery_secret": secret.as_ref().map(|s| BASE64.encode(s)),
+                }));
+                return Ok(());
+            }
+            println!(
+                "{} Re-encrypted {} chunks under a new key",
+                "✓".green(),
+                outcome.chunks
+            );
+            println!("  New integrity fingerprint: {}", outcome.fingerprint);
+            if !outcome.dropped.is_empty() {
+                let ids: Vec<String> = outcome.dropped.iter().map(|s| s.id.to_string()).collect();
+                println!(
+                    "  Removed key slot(s) {}; re-add them with `cass pages keys add`.",
+                    ids.join(", ")
+                );
+            }
+            if let Some(secret) = secret {
+                print_recovery(&secret);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handle `cass pages serve`
+fn run_pages_serve(
+    bundle_dir: &Path,
+    host: &str,
+    port: u16,
+    no_open: bool,
+    rebuild_from: Option<&Path>,
+) -> CliResult<()> {
+    use crate::pages::keys::resolve_site_dir;
+    use crate::pages::serve::{PreviewServer, watch_and_rebuild};
+    use crate::pages::size::BundleVerifier;
+    use colored::Colorize;
+
+    let pages_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("{e:#}"),
+        hint: None,
+        retryable: false,
+    };
+    let verify = |site_dir: &Path| -> anyhow::Result<()> {
+        for warning in BundleVerifier::verify(site_dir)? {
+            eprintln!("  {} {}", "⚠".yellow(), warning);
+        }
+        Ok(())
+    };
+
+    let site_dir = resolve_site_dir(bundle_dir).map_err(pages_err)?;
+    verify(&site_dir).map_err(pages_err)?;
+
+    if let Some(export_dir) = rebuild_from {
+        let bundle_root = site_dir.parent().unwrap_or(&site_dir).to_path_buf();
+        let watched_site = site_dir.clone();
+        watch_and_rebuild(export_dir, &bundle_root, move |result| {
+            match result.and_then(|built| verify(&watched_site).map(|_| built)) {
+                Ok(built) => println!(
+                    "{} Rebuilt bundle ({} files, fingerprint {})",
+                    "✓".green(),
+                    built.total_files,
+                    built.fingerprint
+                ),
+                Err(e) => eprintln!("{} Rebuild failed: {e:#}", "✗".red()),
+            }
+        })
+        .map_err(pages_err)?;
+    }
+
+    let server = PreviewServer::bind(&site_dir, &format!("{host}:{port}")).map_err(pages_err)?;
+    let url = server.url().map_err(pages_err)?;
+    println!("Serving {} at {}", site_dir.display(), url.cyan());
+    if let Some(export_dir) = rebuild_from {
+        println!("  Rebuilding on changes to {}", export_dir.display());
+    }
+    println!("  Press Ctrl+C to stop.");
+    if !no_open && let Err(e) = crate::update_check::open_in_browser(&url) {
+        eprintln!("  {} Could not open a browser: {e}", "⚠".yellow());
+    }
+
+    server.run().map_err(pages_err)
+}
+
+/// Handle `cass pages import`
+fn run_pages_import(
+    archive: &str,
+    name: Option<String>,
+    recovery_file: Option<&Path>,
+    identity_file: Option<&Path>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use crate::pages::import::{
+        ArchiveLocation, FetchedArchive, import_archive, validate_source_id,
+    };
+    use colored::Colorize;
+
+    let pages_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("{e:#}"),
+        hint: None,
+        retryable: false,
+    };
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+
+    let location = ArchiveLocation::parse(archive).map_err(pages_err)?;
+    let source_id = name.unwrap_or_else(|| location.default_source_id());
+    validate_source_id(&source_id).map_err(|e| {
+        CliError::usage(
+            format!("{e:#}"),
+            Some("Pass --name with a plain name such as 'teammate'".to_string()),
+        )
+    })?;
+    // Downloads and the decrypted database live in a fresh directory that
+    // is removed once the import is done, whatever the outcome
+    let archives = data_dir.join("archives");
+    std::fs::create_dir_all(&archives).map_err(|e| pages_err(e.into()))?;
+    let staging_dir = tempfile::Builder::new()
+        .prefix(".import-")
+        .tempdir_in(&archives)
+        .map_err(|e| pages_err(e.into()))?;
+    let staging = staging_dir.path();
+
+    if !json {
+        println!("Fetching {}...", location.label().cyan());
+    }
+    // The blocking HTTP client cannot be used on the async runtime's threads
+    let fetched = std::thread::scope(|scope| {
+        scope
+            .spawn(|| FetchedArchive::fetch(location, staging))
+            .join()
+    })
+    .map_err(|_| CliError::unknown("archive fetch panicked"))?
+    .map_err(pages_err)?;
+    if !json {
+        println!(
+            "  {} {} payload file(s) match integrity.json",
+            "✓".green(),
+            fetched.file_count()
+        );
+    }
+
+    let unlock = if fetched.is_encrypted() {
+        Some(pages_unlock(recovery_file, identity_file)?)
+    } else {
+        None
+    };
+    let summary = import_archive(
+        &fetched,
+        unlock.as_ref(),
+        &source_id,
+        &db_path,
+        &data_dir,
+        staging,
+    )
+    .map_err(pages_err)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summary).unwrap_or_default()
+        );
+    } else {
+        println!(
+            "{} Imported {} conversation(s), {} new message(s) as source '{}'",
+            "✓".green(),
+            summary.conversations,
+            summary.new_messages,
+            summary.source_id.cyan()
+        );
+        println!(
+            "  Search it with: cass search <query> --source {}",
+            summary.source_id
+        );
+    }
+    Ok(())
+}
+
 /// Handle models subcommands
 fn run_models_command(cmd: ModelsCommand) -> CliResult<()> {
     match cmd {
-        ModelsCommand::Status { json } => run_models_status(json),
+        ModelsCommand::Status {
+            model,
+            json,
+            data_dir,
+        } => run_models_status(model.as_deref(), json, data_dir),
+        ModelsCommand::List { json, data_dir } => run_models_list(json, data_dir),
+        ModelsCommand::Use {
+            model,
+            json,
+            data_dir,
+        } => run_models_use(&model, json, data_dir),
         ModelsCommand::Install {
             model,
             mirror,
@@ -10368,36 +12502,76 @@ fn run_models_command(cmd: ModelsCommand) -> CliResult<()> {
             yes,
             data_dir,
         } => run_models_install(
-            &model,
+            model.as_deref(),
             mirror.as_deref(),
             from_file.as_deref(),
             yes,
             data_dir,
         ),
         ModelsCommand::Verify {
+            model,
             repair,
             data_dir,
             json,
-        } => run_models_verify(repair, data_dir, json),
+        } => run_models_verify(model.as_deref(), repair, data_dir, json),
         ModelsCommand::Remove {
             model,
             yes,
             data_dir,
-        } => run_models_remove(&model, yes, data_dir),
-        ModelsCommand::CheckUpdate { json, data_dir } => run_models_check_update(json, data_dir),
+        } => run_models_remove(model.as_deref(), yes, data_dir),
+        ModelsCommand::CheckUpdate {
+            model,
+            json,
+            data_dir,
+        } => run_models_check_update(model.as_deref(), json, data_dir),
     }
 }
 
+/// Look up `model` in the registry, defaulting to the active model.
+fn resolve_model_manifest(
+    data_dir: &Path,
+    model: Option<&str>,
+) -> CliResult<crate::search::model_download::ModelManifest> {
+    use crate::search::model_registry::{ModelRegistry, manifests_dir};
+
+    let registry = ModelRegistry::load(data_dir);
+    let Some(id) = model else {
+        return Ok(registry.active(data_dir).clone());
+    };
+    registry.get(id).cloned().ok_or_else(|| CliError {
+        code: 20,
+        kind: "model",
+        message: format!(
+            "Unknown model '{}'. Available: {}",
+            id,
+            registry.ids().join(", ")
+        ),
+        hint: Some(format!(
+            "Use 'cass models list' to see available models, or add a pinned manifest to {}",
+            manifests_dir(data_dir).display()
+        )),
+        retryable: false,
+    })
+}
+
 /// Show semantic model installation status
-fn run_models_status(json_output: bool) -> CliResult<()> {
-    use crate::search::fastembed_embedder::FastEmbedder;
-    use crate::search::model_download::{ModelManifest, ModelState, check_model_installed};
+fn run_models_status(
+    model: Option<&str>,
+    json_output: bool,
+    data_dir_override: Option<PathBuf>,
+) -> CliResult<()> {
+    use crate::search::model_download::{ModelState, check_manifest_installed};
+    use crate::search::model_manager::needs_index_rebuild_for;
+    use crate::search::model_registry::active_model_id;
+    use crate::search::vector_index::vector_index_path;
 
-    let data_dir = default_data_dir();
-    let model_dir = FastEmbedder::default_model_dir(&data_dir);
-    let manifest = ModelManifest::minilm_v2();
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let manifest = resolve_model_manifest(&data_dir, model)?;
+    let model_dir = manifest.model_dir(&data_dir);
+    let is_active = active_model_id(&data_dir) == manifest.id;
+    let index_path = vector_index_path(&data_dir, &manifest.embedder_id);
 
-    let state = check_model_installed(&model_dir);
+    let state = check_manifest_installed(&model_dir, &manifest);
     let total_size = manifest.total_size();
     let total_size_mb = total_size as f64 / 1_048_576.0;
 
@@ -10420,7 +12594,7 @@ fn run_models_status(json_output: bool) -> CliResult<()> {
             "expected_size": mfile.size,
             "actual_size": size,
             "exists": exists,
-            "size_match": exists && size == mfile.size,
+            "size_match": exists && (mfile.size == 0 || size == mfile.size),
         }));
     }
 
@@ -10440,8 +12614,14 @@ fn run_models_status(json_output: bool) -> CliResult<()> {
                 ModelState::Cancelled => "cancelled",
             },
             "state_detail": state.summary(),
+            "active": is_active,
+            "embedder_id": manifest.embedder_id,
+            "dimension": manifest.dimension,
             "revision": manifest.revision,
             "license": manifest.license,
+            "index_path": index_path.display().to_string(),
+            "index_present": index_path.is_file(),
+            "index_needs_rebuild": needs_index_rebuild_for(&data_dir, &manifest),
             "total_size_bytes": total_size,
             "installed_size_bytes": installed_size,
             "files": file_info,
@@ -10456,13 +12636,29 @@ fn run_models_status(json_output: bool) -> CliResult<()> {
         println!("Semantic Search Model Status");
         println!("============================");
         println!();
-        println!("Model:    {} ({})", manifest.id, manifest.license);
+        println!(
+            "Model:    {} ({}){}",
+            manifest.id,
+            manifest.license,
+            if is_active { " [active]" } else { "" }
+        );
         println!(
             "Revision: {}",
             manifest.revision.get(..12).unwrap_or(&manifest.revision)
         );
         println!("Location: {}", model_dir.display());
-        println!("Size:     {:.1} MB", total_size_mb);
+        if total_size > 0 {
+            println!("Size:     {:.1} MB", total_size_mb);
+        }
+        println!(
+            "Index:    {} ({})",
+            index_path.display(),
+            if index_path.is_file() {
+                "present"
+            } else {
+                "not built"
+            }
+        );
         println!();
 
         let status_str = match &state {
@@ -10504,9 +12700,11 @@ fn run_models_status(json_output: bool) -> CliResult<()> {
             } else {
                 0
             };
-            let size_mb = mfile.size as f64 / 1_048_576.0;
+            // Manifests that leave the size out report the size on disk
+            let expected_size = if mfile.size > 0 { mfile.size } else { size };
+            let size_mb = expected_size as f64 / 1_048_576.0;
 
-            let status = if exists && size == mfile.size {
+            let status = if exists && size == expected_size {
                 "✓".green().to_string()
             } else if exists {
                 "⚠".yellow().to_string()
@@ -10518,18 +12716,27 @@ fn run_models_status(json_output: bool) -> CliResult<()> {
         println!();
 
         // Suggestions based on state
+        let model_arg = if is_active {
+            String::new()
+        } else {
+            format!(" --model {}", manifest.id)
+        };
         match state {
             ModelState::NotInstalled | ModelState::NeedsConsent => {
                 println!("To install the model, run:");
-                println!("  cass models install");
+                println!("  cass models install{model_arg}");
             }
             ModelState::VerificationFailed { .. } => {
                 println!("To repair the model, run:");
-                println!("  cass models verify --repair");
+                println!("  cass models verify --repair{model_arg}");
             }
             ModelState::UpdateAvailable { .. } => {
                 println!("To update the model, run:");
-                println!("  cass models install");
+                println!("  cass models install{model_arg}");
+            }
+            ModelState::Ready if !is_active => {
+                println!("Model is installed. To use it for semantic search, run:");
+                println!("  cass models use {}", manifest.id);
             }
             ModelState::Ready => {
                 println!("Model is ready for semantic search.");
@@ -10541,36 +12748,194 @@ fn run_models_status(json_output: bool) -> CliResult<()> {
     Ok(())
 }
 
+/// List registered models with their install and index state
+fn run_models_list(json_output: bool, data_dir_override: Option<PathBuf>) -> CliResult<()> {
+    use crate::search::model_download::check_manifest_installed;
+    use crate::search::model_registry::{ManifestSource, ModelRegistry, manifests_dir};
+    use crate::search::vector_index::vector_index_path;
+    use colored::Colorize;
+
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let registry = ModelRegistry::load(&data_dir);
+    let active_id = registry.active(&data_dir).id.clone();
+
+    let models: Vec<serde_json::Value> = registry
+        .entries()
+        .iter()
+        .map(|entry| {
+            let manifest = &entry.manifest;
+            let installed =
+                check_manifest_installed(&manifest.model_dir(&data_dir), manifest).is_ready();
+            let index_present = vector_index_path(&data_dir, &manifest.embedder_id).is_file();
+            serde_json::json!({
+                "id": manifest.id,
+                "repo": manifest.repo,
+                "revision": manifest.revision,
+                "license": manifest.license,
+                "embedder_id": manifest.embedder_id,
+                "dimension": manifest.dimension,
+                "pooling": manifest.pooling,
+                "source": match &entry.source {
+                    ManifestSource::Builtin => "builtin".to_string(),
+                    ManifestSource::File(path) => path.display().to_string(),
+                },
+                    "installed": installed,
+                "index_present": index_present,
+                "active": manifest.id == active_id,
+            })
+        })
+        .collect();
+    let errors: Vec<serde_json::Value> = registry
+        .errors()
+        .iter()
+        .map(|(path, err)| {
+            serde_json::json!({
+                "path": path.display().to_string(),
+                "error": err.to_string(),
+            })
+        })
+        .collect();
+
+    if json_output {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "active": active_id,
+                "manifests_dir": manifests_dir(&data_dir).display().to_string(),
+                "models": models,
+                "manifest_errors": errors,
+            }))
+            .unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    println!("Semantic Search Models");
+    println!("======================");
+    println!();
+    for model in &models {
+        let marker = if model["active"] == true {
+            "*".green().to_string()
+        } else {
+            " ".to_string()
+        };
+        let mut flags = Vec::new();
+        if model["installed"] == true {
+            flags.push("installed".green().to_string());
+        }
+        if model["index_present"] == true {
+            flags.push("indexed".cyan().to_string());
+        }
+        if model["source"] != "builtin" {
+            flags.push("manifest".dimmed().to_string());
+        }
+        println!(
+            "{} {:<30} {:>4}d  {}",
+            marker,
+            model["id"].as_str().unwrap_or_default(),
+            model["dimension"],
+            flags.join(" ")
+        );
+    }
+    for (path, err) in registry.errors() {
+        println!();
+        println!("{} {}: {}", "⚠".yellow(), path.display(), err);
+    }
+    println!();
+    println!(
+        "Add models with manifest files in {}",
+        manifests_dir(&data_dir).display()
+    );
+    println!("Switch models with: cass models use <id>");
+
+    Ok(())
+}
+
+/// Persist the active model selection
+fn run_models_use(
+    model: &str,
+    json_output: bool,
+    data_dir_override: Option<PathBuf>,
+) -> CliResult<()> {
+    use crate::search::model_download::check_manifest_installed;
+    use crate::search::model_manager::needs_index_rebuild_for;
+    use crate::search::model_registry::{ACTIVE_MODEL_ENV, set_active_model};
+    use crate::search::vector_index::vector_index_path;
+    use colored::Colorize;
+
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let manifest = resolve_model_manifest(&data_dir, Some(model))?;
+    set_active_model(&data_dir, &manifest.id).map_err(|e| CliError {
+        code: 22,
+        kind: "io",
+        message: format!("Failed to save model selection: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+
+    let installed = check_manifest_installed(&manifest.model_dir(&data_dir), &manifest).is_ready();
+    let index_path = vector_index_path(&data_dir, &manifest.embedder_id);
+    let index_present = index_path.is_file();
+    let needs_rebuild = needs_index_rebuild_for(&data_dir, &manifest);
+    let env_override = dotenvy::var(ACTIVE_MODEL_ENV)
+        .ok()
+        .filter(|id| !id.trim().is_empty() && id.trim() != manifest.id);
+
+    if json_output {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "active": manifest.id,
+                "embedder_id": manifest.embedder_id,
+                "installed": installed,
+                "index_path": index_path.display().to_string(),
+                "index_present": index_present,
+                "index_needs_rebuild": needs_rebuild,
+                "env_override": env_override,
+            }))
+            .unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    println!("{} Active model: {}", "✓".green(), manifest.id);
+    if !installed {
+        println!("  Not installed yet. Run: cass models install");
+    }
+    if !index_present || needs_rebuild {
+        println!(
+            "  Vector index {} needs to be built for this model ({})",
+            manifest.embedder_id,
+            index_path.display()
+        );
+        println!("  Indexes of other models are kept and reused when you switch back.");
+    }
+    if let Some(other) = env_override {
+        println!(
+            "  {} {ACTIVE_MODEL_ENV}={other} overrides this selection",
+            "⚠".yellow()
+        );
+    }
+    Ok(())
+}
+
 /// Download and install the semantic search model
 fn run_models_install(
-    model_name: &str,
+    model: Option<&str>,
     mirror: Option<&str>,
     from_file: Option<&Path>,
     skip_confirm: bool,
     data_dir_override: Option<PathBuf>,
 ) -> CliResult<()> {
-    use crate::search::fastembed_embedder::FastEmbedder;
-    use crate::search::model_download::{ModelDownloader, ModelManifest, check_model_installed};
+    use crate::search::model_download::{ModelDownloader, check_manifest_installed};
+    use crate::search::model_registry::active_model_id;
     use colored::Colorize;
     use indicatif::{ProgressBar, ProgressStyle};
 
-    // Only support the default model for now
-    if model_name != "all-minilm-l6-v2" {
-        return Err(CliError {
-            code: 20,
-            kind: "model",
-            message: format!(
-                "Unknown model '{}'. Only 'all-minilm-l6-v2' is supported.",
-                model_name
-            ),
-            hint: Some("Use 'cass models status' to see available models".into()),
-            retryable: false,
-        });
-    }
-
     let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
-    let model_dir = FastEmbedder::default_model_dir(&data_dir);
-    let manifest = ModelManifest::minilm_v2();
+    let manifest = resolve_model_manifest(&data_dir, model)?;
+    let model_dir = manifest.model_dir(&data_dir);
+    let is_active = active_model_id(&data_dir) == manifest.id;
 
     // Check if from_file is specified
     if let Some(file_path) = from_file {
@@ -10595,7 +12960,7 @@ fn run_models_install(
     }
 
     // Check current state
-    let state = check_model_installed(&model_dir);
+    let state = check_manifest_installed(&model_dir, &manifest);
     if state.is_ready() {
         println!("{} Model is already installed and verified.", "✓".green());
         println!("  Location: {}", model_dir.display());
@@ -10611,7 +12976,9 @@ fn run_models_install(
         println!("===================================");
         println!();
         println!("Model:   {} ({})", manifest.id, manifest.license);
-        println!("Size:    {:.1} MB", total_size_mb);
+        if total_size > 0 {
+            println!("Size:    {:.1} MB", total_size_mb);
+        }
         println!("Source:  HuggingFace ({})", manifest.repo);
         println!();
         println!("This will download the model from HuggingFace.");
@@ -10666,7 +13033,12 @@ fn run_models_install(
             println!("{} Model installed successfully!", "✓".green());
             println!("  Location: {}", model_dir.display());
             println!();
-            println!("Semantic search is now available. Run 'cass search' to try it out.");
+            if is_active {
+                println!("Semantic search is now available. Run 'cass search' to try it out.");
+            } else {
+                println!("To use it for semantic search, run:");
+                println!("  cass models use {}", manifest.id);
+            }
             Ok(())
         }
         Err(e) => {
@@ -10684,17 +13056,17 @@ fn run_models_install(
 
 /// Verify model file integrity
 fn run_models_verify(
+    model: Option<&str>,
     repair: bool,
     data_dir_override: Option<PathBuf>,
     json_output: bool,
 ) -> CliResult<()> {
-    use crate::search::fastembed_embedder::FastEmbedder;
-    use crate::search::model_download::{ModelManifest, compute_sha256};
+    use crate::search::model_download::compute_sha256;
     use colored::Colorize;
 
     let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
-    let model_dir = FastEmbedder::default_model_dir(&data_dir);
-    let manifest = ModelManifest::minilm_v2();
+    let manifest = resolve_model_manifest(&data_dir, model)?;
+    let model_dir = manifest.model_dir(&data_dir);
 
     if !model_dir.is_dir() {
         if json_output {
@@ -10702,6 +13074,7 @@ fn run_models_verify(
                 "{}",
                 serde_json::to_string_pretty(&serde_json::json!({
                     "status": "not_installed",
+                    "model_id": manifest.id,
                     "model_dir": model_dir.display().to_string(),
                     "error": "Model directory does not exist",
                 }))
@@ -10729,11 +13102,12 @@ fn run_models_verify(
     for mfile in &manifest.files {
         let file_path = model_dir.join(&mfile.name);
         let exists = file_path.is_file();
+        let expected = mfile.sha256.as_str();
 
         let (valid, actual_hash, error) = if exists {
             match compute_sha256(&file_path) {
                 Ok(hash) => {
-                    let matches = hash == mfile.sha256;
+                    let matches = hash == expected;
                     (matches, Some(hash), None)
                 }
                 Err(e) => (false, None, Some(e.to_string())),
@@ -10751,7 +13125,7 @@ fn run_models_verify(
             "file": mfile.name,
             "exists": exists,
             "valid": valid,
-            "expected_sha256": mfile.sha256,
+            "expected_sha256": expected,
             "actual_sha256": actual_hash,
             "error": error,
         }));
@@ -10766,10 +13140,7 @@ fn run_models_verify(
             if let Some(ref err) = error {
                 println!("      Error: {}", err);
             } else if !valid {
-                println!(
-                    "      Expected: {}",
-                    mfile.sha256.get(..16).unwrap_or(&mfile.sha256)
-                );
+                println!("      Expected: {}", expected.get(..16).unwrap_or(expected));
                 if let Some(ref actual) = actual_hash {
                     println!("      Got:      {}", actual.get(..16).unwrap_or(actual));
                 }
@@ -10781,6 +13152,7 @@ fn run_models_verify(
         println!(
             "{}",
             serde_json::to_string_pretty(&serde_json::json!({
+                "model_id": manifest.id,
                 "model_dir": model_dir.display().to_string(),
                 "all_valid": all_valid,
                 "files": results,
@@ -10802,7 +13174,7 @@ fn run_models_verify(
                 println!("Repairing by re-downloading model files...");
                 println!();
                 // Actually perform the repair by re-running install
-                return run_models_install("all-minilm-l6-v2", None, None, true, data_dir_override);
+                return run_models_install(Some(&manifest.id), None, None, true, data_dir_override);
             } else {
                 println!();
                 println!("To repair corrupted files, run:");
@@ -10818,29 +13190,16 @@ fn run_models_verify(
 
 /// Remove model files
 fn run_models_remove(
-    model_name: &str,
+    model: Option<&str>,
     skip_confirm: bool,
     data_dir_override: Option<PathBuf>,
 ) -> CliResult<()> {
-    use crate::search::fastembed_embedder::FastEmbedder;
     use colored::Colorize;
 
-    // Only support the default model for now
-    if model_name != "all-minilm-l6-v2" {
-        return Err(CliError {
-            code: 20,
-            kind: "model",
-            message: format!(
-                "Unknown model '{}'. Only 'all-minilm-l6-v2' is supported.",
-                model_name
-            ),
-            hint: Some("Use 'cass models status' to see available models".into()),
-            retryable: false,
-        });
-    }
-
     let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
-    let model_dir = FastEmbedder::default_model_dir(&data_dir);
+    let manifest = resolve_model_manifest(&data_dir, model)?;
+    let model_name = manifest.id.as_str();
+    let model_dir = manifest.model_dir(&data_dir);
 
     if !model_dir.is_dir() {
         println!("{} Model is not installed.", "✗".yellow());
@@ -10900,24 +13259,28 @@ fn run_models_remove(
 }
 
 /// Check for model updates
-fn run_models_check_update(json_output: bool, data_dir_override: Option<PathBuf>) -> CliResult<()> {
-    use crate::search::fastembed_embedder::FastEmbedder;
+fn run_models_check_update(
+    model: Option<&str>,
+    json_output: bool,
+    data_dir_override: Option<PathBuf>,
+) -> CliResult<()> {
     use crate::search::model_download::{
-        ModelManifest, ModelState, check_model_installed, check_version_mismatch,
+        ModelState, check_manifest_installed, check_version_mismatch,
     };
     use colored::Colorize;
 
     let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
-    let model_dir = FastEmbedder::default_model_dir(&data_dir);
-    let manifest = ModelManifest::minilm_v2();
+    let manifest = resolve_model_manifest(&data_dir, model)?;
+    let model_dir = manifest.model_dir(&data_dir);
 
-    let state = check_model_installed(&model_dir);
+    let state = check_manifest_installed(&model_dir, &manifest);
 
     if !state.is_ready() {
         if json_output {
             println!(
                 "{}",
                 serde_json::to_string_pretty(&serde_json::json!({
+                    "model_id": manifest.id,
                     "update_available": false,
                     "reason": "model_not_installed",
                     "current_revision": null,
@@ -10950,6 +13313,7 @@ fn run_models_check_update(json_output: bool, data_dir_override: Option<PathBuf>
             println!(
                 "{}",
                 serde_json::to_string_pretty(&serde_json::json!({
+                    "model_id": manifest.id,
                     "update_available": true,
                     "current_revision": current_revision,
                     "latest_revision": latest_revision,
@@ -10974,7 +13338,8 @@ fn run_models_check_update(json_output: bool, data_dir_override: Option<PathBuf>
         println!(
             "{}",
             serde_json::to_string_pretty(&serde_json::json!({
-                "update_available": false,
+                "model_id": manifest.id,
+                    "update_available": false,
                 "current_revision": manifest.revision,
                 "latest_revision": manifest.revision,
             }))
@@ -11015,6 +13380,15 @@ fn run_mappings_command(action: MappingsAction) -> CliResult<()> {
         } => {
             run_mappings_test(&source, &path, agent.as_deref())?;
         }
+        MappingsAction::Suggest {
+            source,
+            apply,
+            min_confidence,
+            offline,
+            json,
+        } => {
+            run_mappings_suggest(&source, apply, min_confidence, offline, json)?;
+        }
     }
     Ok(())
 }
@@ -11275,6 +13649,205 @@ fn run_mappings_test(source_name: &str, path: &str, agent: Option<&str>) -> CliR
     Ok(())
 }
 
+/// Suggest path mappings from workspace overlap and apply the accepted ones
+fn run_mappings_suggest(
+    source_name: &str,
+    apply: bool,
+    min_confidence: f64,
+    offline: bool,
+    json_output: bool,
+) -> CliResult<()> {
+    use crate::sources::config::SourcesConfig;
+    use crate::sources::mappings::{self, WorkspaceInfo};
+    use crate::storage::sqlite::SqliteStorage;
+    use std::io::{IsTerminal, Write};
+
+    let mut config = SourcesConfig::load().map_err(|e| CliError {
+        code: 9,
+        kind: "config",
+        message: format!("Failed to load sources config: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+
+    let source = config
+        .find_source(source_name)
+        .cloned()
+        .ok_or_else(|| CliError {
+            code: 12,
+            kind: "source",
+            message: format!("Source '{}' not found", source_name),
+            hint: Some("Use 'cass sources list' to see available sources".into()),
+            retryable: false,
+        })?;
+
+    let data_dir = default_data_dir();
+    let db_path = data_dir.join("agent_search.db");
+    let db_error = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "db",
+        message: format!("Failed to read indexed workspaces: {e}"),
+        hint: Some("Run 'cass index' first".into()),
+        retryable: false,
+    };
+    if !db_path.exists() {
+        return Err(db_error(anyhow::anyhow!(
+            "no database at {}",
+            db_path.display()
+        )));
+    }
+    let mut storage = SqliteStorage::open(&db_path).map_err(db_error)?;
+
+    let remote_paths = mappings::unmapped_remote_workspaces(&storage, &source).map_err(db_error)?;
+    let local = mappings::local_workspaces(&storage).map_err(db_error)?;
+
+    let mut warning = None;
+    let mut remote_urls = std::collections::HashMap::new();
+    if !offline
+        && source.is_ssh()
+        && let Some(host) = source.host.as_deref()
+    {
+        match mappings::remote_git_remotes(host, &remote_paths, 10) {
+            Ok(urls) => remote_urls = urls,
+            Err(e) => {
+                warning = Some(format!(
+                    "Could not read git remotes on {host}: {e}; matching by name only"
+                ))
+            }
+        }
+    }
+    let remote: Vec<WorkspaceInfo> = remote_paths
+        .iter()
+        .map(|path| WorkspaceInfo::new(path.as_str(), remote_urls.get(path).map(String::as_str)))
+        .collect();
+
+    let suggestions: Vec<_> = mappings::suggest_mappings(&remote, &local)
+        .into_iter()
+        .filter(|s| s.confidence >= min_confidence)
+        .collect();
+
+    if let Some(warning) = &warning
+        && !json_output
+    {
+        eprintln!("Warning: {warning}");
+    }
+
+    // --apply accepts everything; otherwise ask when someone can answer
+    let interactive = !apply && !json_output && std::io::stdin().is_terminal();
+    if !json_output {
+        println!(
+            "Matched {} unmapped workspace(s) of '{}' against {} local workspace(s).",
+            remote.len(),
+            source_name,
+            local.len()
+        );
+        if suggestions.is_empty() {
+            println!("No mapping suggestions.");
+        }
+    }
+
+    let mut accepted = Vec::new();
+    for suggestion in &suggestions {
+        if !json_output {
+            println!();
+            println!(
+                "  {} → {}  (confidence {:.2})",
+                suggestion.from, suggestion.to, suggestion.confidence
+            );
+            for m in &suggestion.matches {
+                let reason = match m.reason {
+                    mappings::MatchReason::GitRemote => "same git remote",
+                    mappings::MatchReason::Basename => "same name",
+                };
+                println!("      {} ↔ {} ({reason})", m.remote, m.local);
+            }
+        }
+        let accept = if apply {
+            true
+        } else if interactive {
+            print!("  Add this mapping? [y/N] ");
+            std::io::stdout().flush().ok();
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer).ok();
+            matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        } else {
+            false
+        };
+        if accept {
+            accepted.push(suggestion.to_mapping());
+        }
+    }
+
+    let mut applied = Vec::new();
+    let mut rewritten = 0;
+    if !accepted.is_empty() {
+        let source = config
+            .find_source_mut(source_name)
+            .expect("source was found above");
+        for mapping in accepted {
+            let exists = source
+                .path_mappings
+                .iter()
+                .any(|m| m.from == mapping.from && m.to == mapping.to && m.agents.is_none());
+            if !exists {
+                source.path_mappings.push(mapping.clone());
+                applied.push(mapping);
+            }
+        }
+        let source = source.clone();
+        config.save().map_err(|e| CliError {
+            code: 11,
+            kind: "config",
+            message: format!("Failed to save config: {e}"),
+            hint: Some("Check file permissions on config directory".into()),
+            retryable: false,
+        })?;
+
+        rewritten =
+            mappings::rewrite_indexed_workspaces(&mut storage, &source).map_err(|e| CliError {
+                code: 9,
+                kind: "db",
+                message: format!("Failed to rewrite indexed workspaces: {e}"),
+                hint: None,
+                retryable: false,
+            })?;
+        drop(storage);
+        if rewritten > 0 {
+            rebuild_tantivy_from_db(&db_path, &data_dir, 0, None)?;
+        }
+    }
+
+    if json_output {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "source": source_name,
+                "suggestions": suggestions,
+                "applied": applied,
+                "rewritten": rewritten,
+                "warning": warning,
+            }))
+            .unwrap_or_default()
+        );
+    } else if !applied.is_empty() {
+        println!();
+        println!(
+            "Added {} mapping(s) to '{}'; rewrote {} indexed session(s).",
+            applied.len(),
+            source_name,
+            rewritten
+        );
+    } else if !suggestions.is_empty() && !apply && !interactive {
+        println!();
+        println!(
+            "Run 'cass sources mappings suggest {} --apply' to add these mappings.",
+            source_name
+        );
+    }
+
+    Ok(())
+}
+
 fn parse_datetime_flexible(s: &str) -> Option<i64> {
     use chrono::{Local, NaiveDate, TimeZone};
 
@@ -68,6 +68,9 @@ pub struct Conversation {
     /// Origin host label for remote sources.
     #[serde(default)]
     pub origin_host: Option<String>,
+    /// User the session belongs to, when known (shared indexes).
+    #[serde(default)]
+    pub owner: Option<String>,
 }
 
 fn default_source_id() -> String {
@@ -2,6 +2,8 @@
 //!
 //! Creates the deployable static site bundle (site/) and private offline artifacts (private/)
 //! from an encrypted export. Output is safe for public hosting (GitHub Pages / Cloudflare Pages).
+//! Public archives can skip encryption and ship the plaintext database or pre-rendered HTML
+//! instead (see [`PlaintextMode`]).
 
 use anyhow::{Context, Result, bail};
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
@@ -13,7 +15,8 @@ use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read};
 use std::path::{Path, PathBuf};
 
-use super::encrypt::EncryptionConfig;
+use super::encrypt::{EncryptionConfig, load_config};
+use super::static_site::{CONVERSATIONS_DIR, render_static_site};
 
 /// Files embedded from pages_assets at compile time
 const PAGES_ASSETS: &[(&str, &[u8])] = &[
@@ -22,6 +25,7 @@ const PAGES_ASSETS: &[(&str, &[u8])] = &[
     ("auth.js", include_bytes!("../pages_assets/auth.js")),
     ("viewer.js", include_bytes!("../pages_assets/viewer.js")),
     ("search.js", include_bytes!("../pages_assets/search.js")),
+    ("semantic.js", include_bytes!("../pages_assets/semantic.js")),
     (
         "conversation.js",
         include_bytes!("../pages_assets/conversation.js"),
@@ -56,6 +60,19 @@ pub struct IntegrityEntry {
     pub size: u64,
 }
 
+/// Integrity entry for a content-addressed payload chunk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkIntegrity {
+    /// Content id of the chunk (from config.json)
+    pub id: String,
+    /// Relative path of the chunk file
+    pub path: String,
+    /// SHA256 hash of the encrypted chunk as hex string
+    pub sha256: String,
+    /// Encrypted chunk size in bytes
+    pub size: u64,
+}
+
 /// Full integrity manifest
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntegrityManifest {
@@ -65,6 +82,80 @@ pub struct IntegrityManifest {
     pub generated_at: String,
     /// Map of relative path -> integrity entry
     pub files: BTreeMap<String, IntegrityEntry>,
+    /// Payload chunks in decryption order (version 2+)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<ChunkIntegrity>,
+}
+
+impl IntegrityManifest {
+    /// Load integrity.json from a deployed or built site directory
+    pub fn load<P: AsRef<Path>>(site_dir: P) -> Result<Self> {
+        let path = site_dir.as_ref().join("integrity.json");
+        let file = File::open(&path).context("Failed to open integrity.json")?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    /// Compare against the manifest of a previous deployment
+    ///
+    /// Files are matched by path and hash; since chunk files are named by
+    /// content, an unchanged chunk is always `unchanged` here.
+    pub fn diff(&self, previous: Option<&IntegrityManifest>) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+
+        for (path, entry) in &self.files {
+            match previous.and_then(|p| p.files.get(path)) {
+                Some(old) if old.sha256 == entry.sha256 && old.size == entry.size => {
+                    diff.unchanged += 1;
+                }
+                Some(_) => {
+                    diff.upload_bytes += entry.size;
+                    diff.changed.push(path.clone());
+                }
+                None => {
+                    diff.upload_bytes += entry.size;
+                    diff.added.push(path.clone());
+                }
+            }
+        }
+
+        if let Some(previous) = previous {
+            diff.removed = previous
+                .files
+                .keys()
+                .filter(|path| !self.files.contains_key(*path))
+                .cloned()
+                .collect();
+        }
+
+        diff
+    }
+}
+
+/// Files to push when updating a deployment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDiff {
+    /// Paths new in this bundle
+    pub added: Vec<String>,
+    /// Paths whose content changed
+    pub changed: Vec<String>,
+    /// Paths to delete from the deployment
+    pub removed: Vec<String>,
+    /// Number of files already deployed as-is
+    pub unchanged: usize,
+    /// Bytes of added and changed files
+    pub upload_bytes: u64,
+}
+
+impl ManifestDiff {
+    /// Whether the deployment is already up to date
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    /// Paths that need uploading (added and changed)
+    pub fn uploads(&self) -> impl Iterator<Item = &String> {
+        self.added.iter().chain(self.changed.iter())
+    }
 }
 
 /// Site metadata for public config
@@ -77,6 +168,37 @@ pub struct SiteMetadata {
     pub generator_version: String,
 }
 
+/// How an unencrypted bundle presents the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlaintextMode {
+    /// The regular viewer, loading a plaintext database without an auth screen
+    Viewer,
+    /// Pre-rendered HTML pages and a prebuilt search index, no SQLite in the browser
+    StaticHtml,
+}
+
+/// config.json of an unencrypted bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaintextConfig {
+    /// Always false; lets the viewer skip the auth screen
+    pub encrypted: bool,
+    pub mode: PlaintextMode,
+    /// Database the viewer loads (viewer mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<PlaintextDatabase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaintextDatabase {
+    pub file: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Site-relative path of the database in a plaintext viewer bundle
+const PLAINTEXT_DATABASE: &str = "payload/archive.db";
+
 /// Bundle configuration
 #[derive(Debug, Clone)]
 pub struct BundleConfig {
@@ -214,7 +336,7 @@ impl BundleBuilder {
         progress("payload", "Copying encrypted payload...");
 
         // Copy payload chunks to site/payload/
-        let chunk_count = copy_payload_chunks(&payload_dir, &site_payload_dir)?;
+        let chunk_count = copy_payload_chunks(encrypted_dir, &site_dir, &enc_config)?;
 
         progress("config", "Writing configuration files...");
 
@@ -223,35 +345,15 @@ impl BundleBuilder {
         let config_file = File::create(&site_config_path)?;
         serde_json::to_writer_pretty(BufWriter::new(config_file), &enc_config)?;
 
-        // Write site metadata
-        let site_metadata = SiteMetadata {
-            title: self.config.title.clone(),
-            description: self.config.description.clone(),
-            generated_at: Utc::now().to_rfc3339(),
-            generator: "cass".to_string(),
-            generator_version: env!("CARGO_PKG_VERSION").to_string(),
-        };
-        let site_json_path = site_dir.join("site.json");
-        let site_json_file = File::create(&site_json_path)?;
-        serde_json::to_writer_pretty(BufWriter::new(site_json_file), &site_metadata)?;
-
-        progress("static", "Writing static files...");
-
-        // Write robots.txt
-        let robots_content = "User-agent: *\nDisallow: /\n";
-        fs::write(site_dir.join("robots.txt"), robots_content)?;
-
-        // Write .nojekyll (empty file to disable Jekyll processing)
-        fs::write(site_dir.join(".nojekyll"), "")?;
-
-        // Write public README.md
+        // Write site metadata and static files
         let public_readme = generate_public_readme(&self.config.title, &self.config.description);
-        fs::write(site_dir.join("README.md"), public_readme)?;
+        self.write_site_files(&site_dir, &public_readme, &progress)?;
 
         progress("integrity", "Generating integrity manifest...");
 
         // Generate integrity.json for all files in site/
-        let integrity_manifest = generate_integrity_manifest(&site_dir)?;
+        let mut integrity_manifest = generate_integrity_manifest(&site_dir)?;
+        integrity_manifest.chunks = chunk_integrity(&integrity_manifest.files, &enc_config);
         let integrity_path = site_dir.join("integrity.json");
         let integrity_file = File::create(&integrity_path)?;
         serde_json::to_writer_pretty(BufWriter::new(integrity_file), &integrity_manifest)?;
@@ -274,6 +376,132 @@ impl BundleBuilder {
             total_files: integrity_manifest.files.len(),
         })
     }
+
+    /// Build an unencrypted bundle from an exported database
+    ///
+    /// Anyone who can reach the site can read the archive; meant for public
+    /// demos. private/ only receives the integrity fingerprint.
+    pub fn build_unencrypted<P: AsRef<Path>>(
+        &self,
+        database: P,
+        output_dir: P,
+        mode: PlaintextMode,
+        progress: impl Fn(&str, &str),
+    ) -> Result<BundleResult> {
+        let database = database.as_ref();
+        let output_dir = output_dir.as_ref();
+        if !database.is_file() {
+            bail!("Missing export database {}", database.display());
+        }
+
+        progress("setup", "Creating directory structure...");
+
+        let site_dir = output_dir.join("site");
+        let private_dir = output_dir.join("private");
+        fs::create_dir_all(&site_dir).context("Failed to create site/ directory")?;
+        fs::create_dir_all(&private_dir).context("Failed to create private/ directory")?;
+
+        // Payload and pages from an earlier build in another mode are stale
+        for stale in ["payload", CONVERSATIONS_DIR] {
+            let dir = site_dir.join(stale);
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+        }
+
+        let database_entry = match mode {
+            PlaintextMode::Viewer => {
+                progress("assets", "Copying web assets...");
+                for (name, content) in PAGES_ASSETS {
+                    fs::write(site_dir.join(name), content)
+                        .with_context(|| format!("Failed to write {}", name))?;
+                }
+
+                progress("payload", "Copying database...");
+                let dest = site_dir.join(PLAINTEXT_DATABASE);
+                fs::create_dir_all(site_dir.join("payload"))?;
+                fs::copy(database, &dest)?;
+                Some(PlaintextDatabase {
+                    file: PLAINTEXT_DATABASE.to_string(),
+                    size: fs::metadata(&dest)?.len(),
+                    sha256: hash_file(&dest)?,
+                })
+            }
+            PlaintextMode::StaticHtml => {
+                progress("render", "Rendering conversation pages...");
+                render_static_site(
+                    database,
+                    &site_dir,
+                    &self.config.title,
+                    &self.config.description,
+                )?;
+                None
+            }
+        };
+
+        progress("config", "Writing configuration files...");
+
+        let plain_config = PlaintextConfig {
+            encrypted: false,
+            mode,
+            database: database_entry,
+        };
+        let config_file = File::create(site_dir.join("config.json"))?;
+        serde_json::to_writer_pretty(BufWriter::new(config_file), &plain_config)?;
+
+        let readme = generate_plaintext_readme(&self.config.title, &self.config.description, mode);
+        self.write_site_files(&site_dir, &readme, &progress)?;
+
+        progress("integrity", "Generating integrity manifest...");
+
+        let integrity_manifest = generate_integrity_manifest(&site_dir)?;
+        let integrity_file = File::create(site_dir.join("integrity.json"))?;
+        serde_json::to_writer_pretty(BufWriter::new(integrity_file), &integrity_manifest)?;
+        let fingerprint = compute_fingerprint(&integrity_manifest);
+
+        progress("private", "Writing private artifacts...");
+        write_fingerprint_file(&private_dir, &fingerprint)?;
+
+        progress("complete", "Bundle complete!");
+
+        Ok(BundleResult {
+            site_dir,
+            private_dir,
+            chunk_count: 0,
+            fingerprint,
+            total_files: integrity_manifest.files.len(),
+        })
+    }
+
+    /// Write site.json, robots.txt, .nojekyll and README.md into site/
+    fn write_site_files(
+        &self,
+        site_dir: &Path,
+        readme: &str,
+        progress: &impl Fn(&str, &str),
+    ) -> Result<()> {
+        let site_metadata = SiteMetadata {
+            title: self.config.title.clone(),
+            description: self.config.description.clone(),
+            generated_at: Utc::now().to_rfc3339(),
+            generator: "cass".to_string(),
+            generator_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        let site_json_file = File::create(site_dir.join("site.json"))?;
+        serde_json::to_writer_pretty(BufWriter::new(site_json_file), &site_metadata)?;
+
+        progress("static", "Writing static files...");
+
+        // Write robots.txt
+        let robots_content = "User-agent: *\nDisallow: /\n";
+        fs::write(site_dir.join("robots.txt"), robots_content)?;
+
+        // Write .nojekyll (empty file to disable Jekyll processing)
+        fs::write(site_dir.join(".nojekyll"), "")?;
+
+        fs::write(site_dir.join("README.md"), readme)?;
+        Ok(())
+    }
 }
 
 /// Result from bundle building
@@ -291,23 +519,42 @@ pub struct BundleResult {
     pub total_files: usize,
 }
 
-/// Copy payload chunks from source to destination
-fn copy_payload_chunks(src_dir: &Path, dest_dir: &Path) -> Result<usize> {
-    let mut count = 0;
+/// Copy payload chunks listed in config.json into site/
+///
+/// Chunks already present with the same content are left alone and chunk
+/// files from an earlier build that are no longer referenced are removed, so
+/// rebuilding into an existing site/ only touches what changed.
+fn copy_payload_chunks(
+    encrypted_dir: &Path,
+    site_dir: &Path,
+    enc_config: &EncryptionConfig,
+) -> Result<usize> {
+    let mut live = std::collections::HashSet::new();
 
-    for entry in fs::read_dir(src_dir)? {
-        let entry = entry?;
-        let path = entry.path();
+    for rel in &enc_config.payload.files {
+        if !live.insert(rel.as_str()) {
+            continue; // identical chunks share one file
+        }
+        let src_path = encrypted_dir.join(rel);
+        let dest_path = site_dir.join(rel);
+        if !src_path.is_file() {
+            bail!("Missing payload chunk {}", rel);
+        }
+        if dest_path.is_file() && hash_file(&dest_path)? == hash_file(&src_path)? {
+            continue;
+        }
+        fs::copy(&src_path, &dest_path)?;
+    }
 
-        if path.extension().map(|e| e == "bin").unwrap_or(false) {
-            let filename = path.file_name().unwrap();
-            let dest_path = dest_dir.join(filename);
-            fs::copy(&path, &dest_path)?;
-            count += 1;
+    for entry in fs::read_dir(site_dir.join("payload"))? {
+        let entry = entry?;
+        let rel = format!("payload/{}", entry.file_name().to_string_lossy());
+        if entry.path().extension().is_some_and(|e| e == "bin") && !live.contains(rel.as_str()) {
+            fs::remove_file(entry.path())?;
         }
     }
 
-    Ok(count)
+    Ok(live.len())
 }
 
 /// Generate integrity manifest for all files in a directory
@@ -317,12 +564,50 @@ fn generate_integrity_manifest(dir: &Path) -> Result<IntegrityManifest> {
     collect_file_hashes(dir, dir, &mut files)?;
 
     Ok(IntegrityManifest {
-        version: 1,
+        version: 2,
         generated_at: Utc::now().to_rfc3339(),
         files,
+        chunks: Vec::new(),
     })
 }
 
+/// Ordered chunk hashes for a content-addressed payload
+fn chunk_integrity(
+    files: &BTreeMap<String, IntegrityEntry>,
+    enc_config: &EncryptionConfig,
+) -> Vec<ChunkIntegrity> {
+    enc_config
+        .payload
+        .chunks
+        .iter()
+        .filter_map(|chunk| {
+            files.get(&chunk.file).map(|entry| ChunkIntegrity {
+                id: chunk.id.clone(),
+                path: chunk.file.clone(),
+                sha256: entry.sha256.clone(),
+                size: entry.size,
+            })
+        })
+        .collect()
+}
+
+/// SHA256 of a file as hex string
+pub(crate) fn hash_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 /// Recursively collect SHA256 hashes of all files
 fn collect_file_hashes(
     base_dir: &Path,
@@ -346,23 +631,8 @@ fn collect_file_hashes(
             }
 
             // Compute hash and size
-            let file = File::open(&path)?;
-            let metadata = file.metadata()?;
-            let size = metadata.len();
-
-            let mut hasher = Sha256::new();
-            let mut reader = BufReader::new(file);
-            let mut buffer = [0u8; 8192];
-
-            loop {
-                let bytes_read = reader.read(&mut buffer)?;
-                if bytes_read == 0 {
-                    break;
-                }
-                hasher.update(&buffer[..bytes_read]);
-            }
-
-            let hash = format!("{:x}", hasher.finalize());
+            let size = fs::metadata(&path)?.len();
+            let hash = hash_file(&path)?;
 
             files.insert(rel_str, IntegrityEntry { sha256: hash, size });
         }
@@ -395,19 +665,7 @@ fn write_private_artifacts(
     enc_config: &EncryptionConfig,
 ) -> Result<()> {
     // Write integrity fingerprint
-    let fingerprint_content = format!(
-        "Integrity Fingerprint: {}\n\n\
-        Generated: {}\n\n\
-        Verify this fingerprint matches the one displayed in the web viewer\n\
-        before entering your password. If it doesn't match, the archive may\n\
-        have been tampered with.\n",
-        fingerprint,
-        Utc::now().to_rfc3339()
-    );
-    fs::write(
-        private_dir.join("integrity-fingerprint.txt"),
-        fingerprint_content,
-    )?;
+    write_fingerprint_file(private_dir, fingerprint)?;
 
     // Write recovery secret if provided
     if let Some(secret) = &config.recovery_secret {
@@ -436,6 +694,31 @@ fn write_private_artifacts(
     }
 
     // Write master key backup (encrypted DEK wrapped with KEK)
+    write_master_key_backup(private_dir, enc_config)?;
+
+    Ok(())
+}
+
+/// Write the integrity fingerprint for visual verification
+fn write_fingerprint_file(private_dir: &Path, fingerprint: &str) -> Result<()> {
+    let fingerprint_content = format!(
+        "Integrity Fingerprint: {}\n\n\
+        Generated: {}\n\n\
+        Verify this fingerprint matches the one displayed in the web viewer\n\
+        before entering your password. If it doesn't match, the archive may\n\
+        have been tampered with.\n",
+        fingerprint,
+        Utc::now().to_rfc3339()
+    );
+    fs::write(
+        private_dir.join("integrity-fingerprint.txt"),
+        fingerprint_content,
+    )?;
+    Ok(())
+}
+
+/// Write the backup of the wrapped key slots
+fn write_master_key_backup(private_dir: &Path, enc_config: &EncryptionConfig) -> Result<()> {
     let master_key_backup = serde_json::json!({
         "export_id": enc_config.export_id,
         "key_slots": enc_config.key_slots,
@@ -445,10 +728,35 @@ fn write_private_artifacts(
     let master_key_path = private_dir.join("master-key.json");
     let master_key_file = File::create(&master_key_path)?;
     serde_json::to_writer_pretty(BufWriter::new(master_key_file), &master_key_backup)?;
-
     Ok(())
 }
 
+/// Regenerate integrity.json after config.json or the payload changed in place
+///
+/// When the bundle's private/ directory sits next to `site_dir`, its
+/// fingerprint and key slot backup are refreshed too. Returns the new
+/// fingerprint.
+pub fn refresh_integrity(site_dir: &Path) -> Result<String> {
+    let enc_config = load_config(site_dir)?;
+
+    let mut manifest = generate_integrity_manifest(site_dir)?;
+    manifest.chunks = chunk_integrity(&manifest.files, &enc_config);
+    let integrity_file = File::create(site_dir.join("integrity.json"))?;
+    serde_json::to_writer_pretty(BufWriter::new(integrity_file), &manifest)?;
+
+    let fingerprint = compute_fingerprint(&manifest);
+    if let Some(private_dir) = site_dir
+        .parent()
+        .map(|bundle| bundle.join("private"))
+        .filter(|dir| dir.is_dir())
+    {
+        write_fingerprint_file(&private_dir, &fingerprint)?;
+        write_master_key_backup(&private_dir, &enc_config)?;
+    }
+
+    Ok(fingerprint)
+}
+
 /// Generate QR code images for recovery secret
 fn generate_qr_codes(private_dir: &Path, recovery_b64: &str) -> Result<()> {
     // Use the qr module from pages if available
@@ -527,6 +835,43 @@ Generated by cass v{}
     )
 }
 
+/// Generate the README for an unencrypted site directory
+fn generate_plaintext_readme(title: &str, description: &str, mode: PlaintextMode) -> String {
+    let how = match mode {
+        PlaintextMode::Viewer => {
+            "The archive opens directly in the cass web viewer, which loads the\n\
+             plaintext database (`payload/archive.db`) with SQLite in the browser."
+        }
+        PlaintextMode::StaticHtml => {
+            "Every conversation is a pre-rendered page under `c/`, listed on\n\
+             `index.html`. Search uses the prebuilt `search-index.json`; the\n\
+             pages themselves work without JavaScript."
+        }
+    };
+    format!(
+        r#"# {}
+
+{}
+
+## About This Archive
+
+This is an **unencrypted**, publicly readable archive of AI coding agent
+conversations generated by [cass](https://github.com/Dicklesworthstone/coding_agent_session_search).
+Anyone who can reach this site can read its contents.
+
+{}
+
+---
+
+Generated by cass v{}
+"#,
+        title,
+        description,
+        how,
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -569,6 +914,7 @@ mod tests {
             version: 1,
             generated_at: "2024-01-01T00:00:00Z".to_string(),
             files,
+            chunks: Vec::new(),
         };
 
         let fingerprint = compute_fingerprint(&manifest);
@@ -626,4 +972,55 @@ mod tests {
             assert_eq!(entry.sha256.len(), 64);
         }
     }
+
+    fn manifest_of(files: &[(&str, &str)]) -> IntegrityManifest {
+        IntegrityManifest {
+            version: 2,
+            generated_at: "2024-01-01T00:00:00Z".to_string(),
+            files: files
+                .iter()
+                .map(|(path, hash)| {
+                    (
+                        path.to_string(),
+                        IntegrityEntry {
+                            sha256: hash.to_string(),
+                            size: 10,
+                        },
+                    )
+                })
+                .collect(),
+            chunks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_manifest_diff() {
+        let previous = manifest_of(&[
+            ("index.html", "aa"),
+            ("config.json", "bb"),
+            ("payload/chunk-1.bin", "c1"),
+            ("payload/chunk-2.bin", "c2"),
+        ]);
+        let current = manifest_of(&[
+            ("index.html", "aa"),
+            ("config.json", "bb2"),
+            ("payload/chunk-1.bin", "c1"),
+            ("payload/chunk-3.bin", "c3"),
+        ]);
+
+        let diff = current.diff(Some(&previous));
+        assert_eq!(diff.added, vec!["payload/chunk-3.bin"]);
+        assert_eq!(diff.changed, vec!["config.json"]);
+        assert_eq!(diff.removed, vec!["payload/chunk-2.bin"]);
+        assert_eq!(diff.unchanged, 2);
+        assert_eq!(diff.upload_bytes, 20);
+        assert!(!diff.is_empty());
+
+        // First deployment uploads everything
+        let first = current.diff(None);
+        assert_eq!(first.added.len(), 4);
+        assert!(first.removed.is_empty());
+
+        assert!(current.diff(Some(&current)).is_empty());
+    }
 }
@@ -0,0 +1,347 @@
+//! Curation of a pages export before publishing.
+//!
+//! A [`Curation`] records which conversations and messages to leave out and
+//! whether to drop tool output. It is keyed by the conversation's original
+//! source path (and message index), which survives re-indexing, so the same
+//! curation file can be reused for every re-export of an archive. The wizard
+//! keeps one curation per output directory (see [`curation_path`]).
+//!
+//! [`RedactRules`] are regex replacements applied to everything an export
+//! carries as text: message content, snippets, conversation titles,
+//! workspaces, source paths and the string values of conversation metadata.
+//! They are loaded from a TOML file:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "openai-key"
+//! pattern = "sk-[A-Za-z0-9]{20,}"
+//! replacement = "[REDACTED:openai-key]"
+//! ```
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Directory under the data directory holding the wizard's curations
+pub const CURATION_DIR: &str = "pages-curation";
+
+/// Replacement used when a redaction rule does not set one
+const DEFAULT_REPLACEMENT: &str = "[REDACTED]";
+
+/// Conversations and messages excluded from an export
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Curation {
+    /// Source paths of conversations left out entirely
+    #[serde(default)]
+    pub excluded_conversations: BTreeSet<String>,
+    /// Message indexes left out, per conversation source path
+    #[serde(default)]
+    pub excluded_messages: BTreeMap<String, BTreeSet<i64>>,
+    /// Drop messages with the `tool` role
+    #[serde(default)]
+    pub drop_tool_output: bool,
+    /// Redaction rules file chosen for this export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redact_rules: Option<PathBuf>,
+}
+
+/// Curation file the wizard uses for exports written to `output_dir`
+///
+/// Each output directory gets its own file so curating one archive does not
+/// change what another one publishes.
+pub fn curation_path(data_dir: &Path, output_dir: &Path) -> PathBuf {
+    let output_dir = std::path::absolute(output_dir).unwrap_or_else(|_| output_dir.to_path_buf());
+    let digest = Sha256::digest(output_dir.to_string_lossy().as_bytes());
+    data_dir
+        .join(CURATION_DIR)
+        .join(format!("{}.json", hex::encode(&digest[..8])))
+}
+
+impl Curation {
+    /// Load a curation file that must exist
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read curation file {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid curation file {}", path.display()))
+    }
+
+    /// Load the wizard's curation file; a missing file is an empty curation
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(path)
+    }
+
+    /// Write the curation file, creating parent directories
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write curation file {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.excluded_conversations.is_empty()
+            && self.excluded_messages.is_empty()
+            && !self.drop_tool_output
+            && self.redact_rules.is_none()
+    }
+
+    pub fn excludes_conversation(&self, source_path: &str) -> bool {
+        self.excluded_conversations.contains(source_path)
+    }
+
+    pub fn excludes_message(&self, source_path: &str, idx: i64, role: &str) -> bool {
+        (self.drop_tool_output && role.eq_ignore_ascii_case("tool"))
+            || self
+                .excluded_messages
+                .get(source_path)
+                .is_some_and(|excluded| excluded.contains(&idx))
+    }
+
+    /// Include or exclude a whole conversation
+    pub fn set_conversation_included(&mut self, source_path: &str, included: bool) {
+        if included {
+            self.excluded_conversations.remove(source_path);
+        } else {
+            self.excluded_conversations.insert(source_path.to_string());
+        }
+    }
+
+    /// Exclude a single message of a conversation
+    pub fn exclude_message(&mut self, source_path: &str, idx: i64) {
+        self.excluded_messages
+            .entry(source_path.to_string())
+            .or_default()
+            .insert(idx);
+    }
+
+    /// Include or exclude a single message of a conversation
+    pub fn set_message_included(&mut self, source_path: &str, idx: i64, included: bool) {
+        if !included {
+            self.exclude_message(source_path, idx);
+            return;
+        }
+        if let Some(excluded) = self.excluded_messages.get_mut(source_path) {
+            excluded.remove(&idx);
+            if excluded.is_empty() {
+                self.excluded_messages.remove(source_path);
+            }
+        }
+    }
+}
+
+/// A single regex redaction rule as written in the rules file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactRule {
+    /// Label for reports
+    #[serde(default)]
+    pub name: Option<String>,
+    pub pattern: String,
+    /// Replacement text; may use `$1`-style capture references
+    #[serde(default)]
+    pub replacement: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RedactRulesFile {
+    #[serde(default)]
+    rules: Vec<RedactRule>,
+}
+
+/// Compiled redaction rules, applied in file order
+#[derive(Debug, Clone, Default)]
+pub struct RedactRules {
+    rules: Vec<(Regex, String)>,
+}
+
+impl RedactRules {
+    /// Load and compile rules from a TOML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read redact rules {}", path.display()))?;
+        let file: RedactRulesFile = toml::from_str(&content)
+            .with_context(|| format!("Invalid redact rules file {}", path.display()))?;
+        Self::new(file.rules)
+    }
+
+    /// Compile rules, failing on the first invalid pattern
+    pub fn new(rules: Vec<RedactRule>) -> Result<Self> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern).with_context(|| {
+                    format!(
+                        "Invalid redact pattern {}",
+                        rule.name.as_deref().unwrap_or(&rule.pattern)
+                    )
+                })?;
+                let replacement = rule
+                    .replacement
+                    .unwrap_or_else(|| DEFAULT_REPLACEMENT.to_string());
+                Ok((regex, replacement))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Apply all rules; borrowed when nothing matched
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        for (regex, replacement) in &self.rules {
+            if let Cow::Owned(replaced) = regex.replace_all(&out, replacement.as_str()) {
+                out = Cow::Owned(replaced);
+            }
+        }
+        out
+    }
+
+    /// Apply all rules to the string values of a JSON document
+    ///
+    /// Keys and structure are kept so the result stays valid JSON; text that
+    /// does not parse is redacted as plain text.
+    pub fn apply_json<'a>(&self, json: &'a str) -> Cow<'a, str> {
+        if self.is_empty() {
+            return Cow::Borrowed(json);
+        }
+        let Ok(mut value) = serde_json::from_str::<serde_json::Value>(json) else {
+            return self.apply(json);
+        };
+        if !self.redact_value(&mut value) {
+            return Cow::Borrowed(json);
+        }
+        Cow::Owned(value.to_string())
+    }
+
+    fn redact_value(&self, value: &mut serde_json::Value) -> bool {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => match self.apply(text) {
+                Cow::Owned(redacted) => {
+                    *text = redacted;
+                    true
+                }
+                Cow::Borrowed(_) => false,
+            },
+            Value::Array(items) => items
+                .iter_mut()
+                .fold(false, |changed, item| self.redact_value(item) | changed),
+            Value::Object(fields) => fields
+                .values_mut()
+                .fold(false, |changed, item| self.redact_value(item) | changed),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn curation_round_trips_and_matches() {
+        let temp = TempDir::new().unwrap();
+        let path = curation_path(temp.path(), Path::new("cass-export"));
+        assert!(Curation::load(&path).is_err());
+        assert!(Curation::load_or_default(&path).unwrap().is_empty());
+
+        let mut curation = Curation::default();
+        curation.set_conversation_included("/a.jsonl", false);
+        curation.exclude_message("/b.jsonl", 3);
+        curation.drop_tool_output = true;
+        curation.save(&path).unwrap();
+
+        let loaded = Curation::load(&path).unwrap();
+        assert_eq!(loaded, curation);
+        assert!(loaded.excludes_conversation("/a.jsonl"));
+        assert!(loaded.excludes_message("/b.jsonl", 3, "user"));
+        assert!(!loaded.excludes_message("/b.jsonl", 4, "user"));
+        assert!(loaded.excludes_message("/c.jsonl", 0, "tool"));
+
+        curation.set_conversation_included("/a.jsonl", true);
+        assert!(!curation.excludes_conversation("/a.jsonl"));
+        curation.set_message_included("/b.jsonl", 3, true);
+        assert!(curation.excluded_messages.is_empty());
+    }
+
+    #[test]
+    fn curation_is_kept_per_output_dir() {
+        let temp = TempDir::new().unwrap();
+        let first = curation_path(temp.path(), &temp.path().join("site-a"));
+        let second = curation_path(temp.path(), &temp.path().join("site-b"));
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            curation_path(temp.path(), &temp.path().join("site-a"))
+        );
+        assert!(first.starts_with(temp.path().join(CURATION_DIR)));
+
+        let curation = Curation {
+            redact_rules: Some(PathBuf::from("/rules/redact.toml")),
+            ..Curation::default()
+        };
+        curation.save(&first).unwrap();
+        assert_eq!(
+            Curation::load(&first).unwrap().redact_rules.as_deref(),
+            Some(Path::new("/rules/redact.toml"))
+        );
+        assert!(Curation::load_or_default(&second).unwrap().is_empty());
+    }
+
+    #[test]
+    fn redact_rules_apply_in_order() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("redact.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[rules]]
+name = "key"
+pattern = "sk-[A-Za-z0-9]{8,}"
+replacement = "[KEY]"
+
+[[rules]]
+pattern = "(?i)password=\\S+"
+"#,
+        )
+        .unwrap();
+        let rules = RedactRules::load(&path).unwrap();
+
+        assert_eq!(
+            rules.apply("use sk-abcdef123456 with PASSWORD=hunter2 ok"),
+            "use [KEY] with [REDACTED] ok"
+        );
+        assert!(matches!(rules.apply("clean"), Cow::Borrowed(_)));
+
+        let metadata = r#"{"cwd":"/x","env":["PASSWORD=hunter2"],"token":"sk-abcdef123456"}"#;
+        let redacted: serde_json::Value =
+            serde_json::from_str(&rules.apply_json(metadata)).unwrap();
+        assert_eq!(redacted["env"][0], "[REDACTED]");
+        assert_eq!(redacted["token"], "[KEY]");
+        assert_eq!(redacted["cwd"], "/x");
+        assert!(matches!(rules.apply_json(r#"{"a":1}"#), Cow::Borrowed(_)));
+        assert_eq!(
+            rules.apply_json("not json sk-abcdef123456"),
+            "not json [KEY]"
+        );
+
+        let bad = vec![RedactRule {
+            name: Some("broken".into()),
+            pattern: "(".into(),
+            replacement: None,
+        }];
+        assert!(RedactRules::new(bad).is_err());
+    }
+}
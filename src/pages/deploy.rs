@@ -0,0 +1,247 @@
+//! Deployment targets for pages bundles.
+//!
+//! Every target implements [`Deployer`]: publish a built site/ directory and
+//! report what changed. Targets that store plain files (SFTP, S3) share
+//! [`deploy_to_store`], which compares the bundle's integrity.json with the
+//! one already deployed and only transfers the difference.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+use super::bundle::IntegrityManifest;
+use super::deploy_github::GitHubDeployer;
+
+/// Manifest file that identifies a deployed cass site
+const INTEGRITY_FILE: &str = "integrity.json";
+
+/// Outcome of a deployment
+#[derive(Debug, Clone, Serialize)]
+pub struct DeploySummary {
+    /// Where the site was published (repository, host path, bucket URL)
+    pub destination: String,
+    /// Public URL of the site, when the target knows it
+    pub url: Option<String>,
+    /// Whether this updated an existing deployment in place
+    pub incremental: bool,
+    /// Files added or changed by this deployment
+    pub uploaded_files: usize,
+    /// Files deleted by this deployment
+    pub removed_files: usize,
+    /// Files already deployed and left untouched
+    pub unchanged_files: usize,
+}
+
+/// A place a built site/ directory can be published to
+pub trait Deployer {
+    /// Human-readable destination for messages
+    fn destination(&self) -> String;
+
+    /// Publish `site_dir`, updating an earlier deployment in place when possible
+    ///
+    /// `progress` receives (phase, message) pairs.
+    fn deploy(&self, site_dir: &Path, progress: &dyn Fn(&str, &str)) -> Result<DeploySummary>;
+}
+
+impl Deployer for GitHubDeployer {
+    fn destination(&self) -> String {
+        format!("GitHub repository {}", self.repo_name())
+    }
+
+    fn deploy(&self, site_dir: &Path, progress: &dyn Fn(&str, &str)) -> Result<DeploySummary> {
+        let result = GitHubDeployer::deploy(self, site_dir, progress)?;
+        Ok(DeploySummary {
+            destination: result.repo_url,
+            url: Some(result.pages_url),
+            incremental: result.incremental,
+            uploaded_files: result.uploaded_files,
+            removed_files: result.removed_files,
+            unchanged_files: result.unchanged_files,
+        })
+    }
+}
+
+/// File storage holding a deployed site, addressed by site-relative path
+pub(crate) trait SiteStore {
+    /// Read a file; `None` if it does not exist
+    fn read(&self, rel: &str) -> Result<Option<Vec<u8>>>;
+    /// Create or replace a file, creating parent directories as needed
+    fn write(&self, rel: &str, data: &[u8]) -> Result<()>;
+    /// Delete a file; deleting a missing file is not an error
+    fn remove(&self, rel: &str) -> Result<()>;
+}
+
+/// Publish `site_dir` into `store`, transferring only what changed
+///
+/// Payload chunks go up first and integrity.json last, so a reader never
+/// sees a config.json referencing chunks that are not uploaded yet; files
+/// dropped from the bundle are deleted at the end.
+pub(crate) fn deploy_to_store(
+    store: &dyn SiteStore,
+    site_dir: &Path,
+    destination: String,
+    progress: &dyn Fn(&str, &str),
+) -> Result<DeploySummary> {
+    let manifest = IntegrityManifest::load(site_dir)
+        .context("Bundle has no integrity.json; build it with the bundle builder first")?;
+
+    progress("compare", "Reading deployed manifest...");
+    let previous = store
+        .read(INTEGRITY_FILE)?
+        .and_then(|bytes| serde_json::from_slice::<IntegrityManifest>(&bytes).ok());
+    let diff = manifest.diff(previous.as_ref());
+
+    let mut uploads: Vec<&String> = diff.uploads().collect();
+    uploads.sort_by_key(|rel| !rel.starts_with("payload/"));
+
+    progress(
+        "upload",
+        &format!(
+            "Uploading {} files ({:.1} MB), removing {}, keeping {}...",
+            uploads.len(),
+            diff.upload_bytes as f64 / (1024.0 * 1024.0),
+            diff.removed.len(),
+            diff.unchanged
+        ),
+    );
+    for rel in &uploads {
+        let data =
+            std::fs::read(site_dir.join(rel)).with_context(|| format!("Failed to read {}", rel))?;
+        store
+            .write(rel, &data)
+            .with_context(|| format!("Failed to upload {}", rel))?;
+    }
+    store.write(
+        INTEGRITY_FILE,
+        &std::fs::read(site_dir.join(INTEGRITY_FILE))?,
+    )?;
+
+    for rel in &diff.removed {
+        store
+            .remove(rel)
+            .with_context(|| format!("Failed to delete {}", rel))?;
+    }
+
+    progress("complete", "Deployment complete!");
+
+    Ok(DeploySummary {
+        destination,
+        url: None,
+        incremental: previous.is_some(),
+        uploaded_files: uploads.len(),
+        removed_files: diff.removed.len(),
+        unchanged_files: diff.unchanged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pages::bundle::IntegrityEntry;
+    use sha2::{Digest, Sha256};
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        files: RefCell<BTreeMap<String, Vec<u8>>>,
+        writes: RefCell<Vec<String>>,
+    }
+
+    impl SiteStore for MemoryStore {
+        fn read(&self, rel: &str) -> Result<Option<Vec<u8>>> {
+            Ok(self.files.borrow().get(rel).cloned())
+        }
+        fn write(&self, rel: &str, data: &[u8]) -> Result<()> {
+            self.writes.borrow_mut().push(rel.to_string());
+            self.files
+                .borrow_mut()
+                .insert(rel.to_string(), data.to_vec());
+            Ok(())
+        }
+        fn remove(&self, rel: &str) -> Result<()> {
+            self.files.borrow_mut().remove(rel);
+            Ok(())
+        }
+    }
+
+    fn write_site(dir: &Path, files: &[(&str, &str)]) {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+        for (rel, content) in files {
+            let path = dir.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let manifest = IntegrityManifest {
+            version: 2,
+            generated_at: String::new(),
+            files: files
+                .iter()
+                .map(|(rel, content)| {
+                    let entry = IntegrityEntry {
+                        sha256: hex::encode(Sha256::digest(content.as_bytes())),
+                        size: content.len() as u64,
+                    };
+                    (rel.to_string(), entry)
+                })
+                .collect(),
+            chunks: Vec::new(),
+        };
+        std::fs::write(
+            dir.join(INTEGRITY_FILE),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn store_deploys_only_the_difference() {
+        let temp = TempDir::new().unwrap();
+        let site = temp.path().join("site");
+        let store = MemoryStore::default();
+
+        write_site(
+            &site,
+            &[
+                ("index.html", "v1"),
+                ("config.json", "{}"),
+                ("payload/a.bin", "a"),
+            ],
+        );
+        let first = deploy_to_store(&store, &site, "mem".into(), &|_, _| {}).unwrap();
+        assert!(!first.incremental);
+        assert_eq!(first.uploaded_files, 3);
+        // Chunks before everything else, manifest last
+        let writes = store.writes.borrow().clone();
+        assert_eq!(writes.first().unwrap(), "payload/a.bin");
+        assert_eq!(writes.last().unwrap(), INTEGRITY_FILE);
+
+        write_site(
+            &site,
+            &[
+                ("index.html", "v1"),
+                ("config.json", "{\"v\":2}"),
+                ("payload/b.bin", "b"),
+            ],
+        );
+        store.writes.borrow_mut().clear();
+        let second = deploy_to_store(&store, &site, "mem".into(), &|_, _| {}).unwrap();
+        assert!(second.incremental);
+        assert_eq!(
+            (
+                second.uploaded_files,
+                second.removed_files,
+                second.unchanged_files
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            *store.writes.borrow(),
+            ["payload/b.bin", "config.json", INTEGRITY_FILE]
+        );
+        assert!(!store.files.borrow().contains_key("payload/a.bin"));
+    }
+}
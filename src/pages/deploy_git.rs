@@ -0,0 +1,101 @@
+//! Deployment to an arbitrary git remote and branch.
+//!
+//! For self-hosted forges or any static host that builds from a branch.
+//! Works like the GitHub Pages deployer without the gh CLI: the branch is
+//! updated in place when it already holds a cass archive, and replaced with
+//! an orphan commit otherwise (which needs `force` if the branch has other
+//! content).
+
+use anyhow::{Result, bail};
+use std::path::Path;
+
+use super::bundle::IntegrityManifest;
+use super::deploy::{DeploySummary, Deployer};
+use super::deploy_github::{
+    checkout_branch, clone_repo, copy_bundle_to_repo, create_temp_dir, push_branch_update,
+    push_orphan_branch, sync_bundle_to_repo,
+};
+
+/// Deployer pushing the site to `branch` of any git remote
+#[derive(Debug, Clone)]
+pub struct GitRemoteDeployer {
+    /// Remote URL or path, as accepted by `git clone`
+    remote: String,
+    /// Branch holding the site
+    branch: String,
+    /// Replace a branch that does not hold a cass archive
+    force: bool,
+}
+
+impl GitRemoteDeployer {
+    pub fn new(remote: impl Into<String>, branch: impl Into<String>) -> Self {
+        Self {
+            remote: remote.into(),
+            branch: branch.into(),
+            force: false,
+        }
+    }
+
+    /// Set whether to overwrite a branch with unrelated content
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
+impl Deployer for GitRemoteDeployer {
+    fn destination(&self) -> String {
+        format!("{} ({})", self.remote, self.branch)
+    }
+
+    fn deploy(&self, site_dir: &Path, progress: &dyn Fn(&str, &str)) -> Result<DeploySummary> {
+        let manifest = IntegrityManifest::load(site_dir)?;
+
+        progress("clone", "Cloning remote...");
+        let temp_dir = create_temp_dir()?;
+        let work_dir = temp_dir.join("site");
+        let result = (|| {
+            clone_repo(&self.remote, &work_dir)?;
+
+            let branch_exists = checkout_branch(&work_dir, &self.branch);
+            let previous = if branch_exists && !self.force {
+                IntegrityManifest::load(&work_dir).ok()
+            } else {
+                None
+            };
+            if branch_exists && !self.force && previous.is_none() {
+                bail!(
+                    "Branch {} of {} already exists and does not hold a cass archive. Use force to overwrite.",
+                    self.branch,
+                    self.remote
+                );
+            }
+
+            let diff = manifest.diff(previous.as_ref());
+            if previous.is_some() {
+                progress("copy", "Updating changed files...");
+                sync_bundle_to_repo(site_dir, &work_dir, &diff)?;
+                progress("push", &format!("Pushing to {}...", self.branch));
+                push_branch_update(&work_dir, &self.branch)?;
+            } else {
+                progress("copy", "Copying bundle files...");
+                copy_bundle_to_repo(site_dir, &work_dir)?;
+                progress("push", &format!("Pushing to {}...", self.branch));
+                push_orphan_branch(&work_dir, &self.branch)?;
+            }
+
+            progress("complete", "Deployment complete!");
+            Ok(DeploySummary {
+                destination: self.destination(),
+                url: None,
+                incremental: previous.is_some(),
+                uploaded_files: diff.added.len() + diff.changed.len(),
+                removed_files: diff.removed.len(),
+                unchanged_files: diff.unchanged,
+            })
+        })();
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+        result
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Deploys encrypted archives to GitHub Pages using the gh CLI.
 //! Creates a repository, pushes to gh-pages branch, and enables Pages.
+//! Redeploying to a repository that already hosts a cass archive only
+//! commits the files whose hashes differ from its integrity.json.
 
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
@@ -10,6 +12,11 @@ use std::process::Command;
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use super::bundle::{IntegrityManifest, ManifestDiff};
+
+/// Branch GitHub Pages serves from
+const PAGES_BRANCH: &str = "gh-pages";
+
 /// Maximum number of retry attempts for network operations
 const MAX_RETRIES: u32 = 3;
 
@@ -90,6 +97,14 @@ pub struct DeployResult {
     pub pages_enabled: bool,
     /// Deployment commit SHA
     pub commit_sha: String,
+    /// Whether this updated an existing deployment in place
+    pub incremental: bool,
+    /// Files added or changed by this deployment
+    pub uploaded_files: usize,
+    /// Files deleted by this deployment
+    pub removed_files: usize,
+    /// Files already deployed and left untouched
+    pub unchanged_files: usize,
 }
 
 /// GitHub Pages deployer
@@ -139,6 +154,11 @@ impl GitHubDeployer {
         self
     }
 
+    /// Repository name
+    pub fn repo_name(&self) -> &str {
+        &self.repo_name
+    }
+
     /// Check deployment prerequisites
     pub fn check_prerequisites(&self) -> Result<Prerequisites> {
         // Check gh CLI
@@ -271,27 +291,66 @@ impl GitHubDeployer {
                 .collect();
             progress(
                 "warning",
-                &format!("Large files detected (may slow deployment): {}", warnings.join(", ")),
+                &format!(
+                    "Large files detected (may slow deployment): {}",
+                    warnings.join(", ")
+                ),
             );
         }
 
+        let manifest = IntegrityManifest::load(bundle_dir)
+            .context("Bundle has no integrity.json; build it with the bundle builder first")?;
+
         // Step 3: Create or verify repository
         progress("repo", "Creating repository...");
-        let repo_url = self.ensure_repository(username)?;
+        let (repo_url, existed) = self.ensure_repository(username)?;
 
         // Step 4: Clone to temp directory
         progress("clone", "Cloning repository...");
         let temp_dir = create_temp_dir()?;
-        clone_repo(&repo_url, &temp_dir)?;
-
-        // Step 5: Copy bundle contents
-        progress("copy", "Copying bundle files...");
         let work_dir = temp_dir.join(&self.repo_name);
-        copy_bundle_to_repo(bundle_dir, &work_dir)?;
+        clone_repo(&repo_url, &work_dir)?;
+
+        // An existing cass deployment is updated in place; anything else needs --force
+        let previous = if existed && !self.force && checkout_branch(&work_dir, PAGES_BRANCH) {
+            IntegrityManifest::load(&work_dir).ok()
+        } else {
+            None
+        };
+        if existed && !self.force && previous.is_none() {
+            bail!(
+                "Repository {}/{} already exists and does not host a cass archive. Use --force to overwrite.",
+                username,
+                self.repo_name
+            );
+        }
 
-        // Step 6: Create orphan branch and push
-        progress("push", "Pushing to gh-pages branch...");
-        let commit_sha = push_gh_pages(&work_dir)?;
+        let diff = manifest.diff(previous.as_ref());
+        let incremental = previous.is_some();
+
+        // Step 5/6: Copy bundle contents and push
+        let commit_sha = if incremental {
+            progress(
+                "copy",
+                &format!(
+                    "Updating {} changed files ({:.1} MB), removing {}, keeping {}...",
+                    diff.added.len() + diff.changed.len(),
+                    diff.upload_bytes as f64 / (1024.0 * 1024.0),
+                    diff.removed.len(),
+                    diff.unchanged
+                ),
+            );
+            sync_bundle_to_repo(bundle_dir, &work_dir, &diff)?;
+
+            progress("push", "Pushing changes to gh-pages branch...");
+            push_branch_update(&work_dir, PAGES_BRANCH)?
+        } else {
+            progress("copy", "Copying bundle files...");
+            copy_bundle_to_repo(bundle_dir, &work_dir)?;
+
+            progress("push", "Pushing to gh-pages branch...");
+            push_orphan_branch(&work_dir, PAGES_BRANCH)?
+        };
 
         // Step 7: Enable GitHub Pages
         progress("pages", "Enabling GitHub Pages...");
@@ -307,23 +366,22 @@ impl GitHubDeployer {
             pages_url,
             pages_enabled,
             commit_sha,
+            incremental,
+            uploaded_files: diff.added.len() + diff.changed.len(),
+            removed_files: diff.removed.len(),
+            unchanged_files: diff.unchanged,
         })
     }
 
     /// Ensure repository exists, create if needed
-    fn ensure_repository(&self, username: &str) -> Result<String> {
+    ///
+    /// Returns the repository URL and whether it already existed.
+    fn ensure_repository(&self, username: &str) -> Result<(String, bool)> {
         let repo_full_name = format!("{}/{}", username, self.repo_name);
 
         // Check if repo exists
         let exists = check_repo_exists(&repo_full_name);
 
-        if exists && !self.force {
-            bail!(
-                "Repository {} already exists. Use --force to overwrite.",
-                repo_full_name
-            );
-        }
-
         if !exists {
             // Create repository
             let visibility = if self.public { "--public" } else { "--private" };
@@ -345,14 +403,14 @@ impl GitHubDeployer {
             }
         }
 
-        Ok(format!("https://github.com/{}", repo_full_name))
+        Ok((format!("https://github.com/{}", repo_full_name), exists))
     }
 }
 
 // Helper functions
 
 /// Create a temporary directory
-fn create_temp_dir() -> Result<PathBuf> {
+pub(super) fn create_temp_dir() -> Result<PathBuf> {
     let temp_base = std::env::temp_dir();
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -493,12 +551,13 @@ where
     }))
 }
 
-/// Clone repository to directory with retry logic
-fn clone_repo(repo_url: &str, dest: &Path) -> Result<()> {
+/// Clone repository into `dest` with retry logic
+pub(super) fn clone_repo(repo_url: &str, dest: &Path) -> Result<()> {
     retry_with_backoff("git clone", || {
         let output = Command::new("git")
-            .args(["clone", repo_url])
-            .current_dir(dest)
+            .arg("clone")
+            .arg(repo_url)
+            .arg(dest)
             .output()
             .context("Failed to run git clone")?;
 
@@ -515,7 +574,7 @@ fn clone_repo(repo_url: &str, dest: &Path) -> Result<()> {
 }
 
 /// Copy bundle contents to repository directory
-fn copy_bundle_to_repo(bundle_dir: &Path, repo_dir: &Path) -> Result<()> {
+pub(super) fn copy_bundle_to_repo(bundle_dir: &Path, repo_dir: &Path) -> Result<()> {
     // Clear existing files (except .git)
     if repo_dir.exists() {
         for entry in std::fs::read_dir(repo_dir)? {
@@ -545,6 +604,45 @@ fn copy_bundle_to_repo(bundle_dir: &Path, repo_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Check out `branch` from origin if the repository has it
+pub(super) fn checkout_branch(repo_dir: &Path, branch: &str) -> bool {
+    Command::new("git")
+        .args(["checkout", "-B", branch, &format!("origin/{branch}")])
+        .current_dir(repo_dir)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Apply a manifest diff to a checked-out deployment
+///
+/// Copies only added and changed files, deletes removed ones, and refreshes
+/// integrity.json (which the manifest does not list).
+pub(super) fn sync_bundle_to_repo(
+    bundle_dir: &Path,
+    repo_dir: &Path,
+    diff: &ManifestDiff,
+) -> Result<()> {
+    let integrity = "integrity.json".to_string();
+    for rel in diff.uploads().chain(std::iter::once(&integrity)) {
+        let dest = repo_dir.join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(bundle_dir.join(rel), &dest)
+            .with_context(|| format!("Failed to copy {}", rel))?;
+    }
+
+    for rel in &diff.removed {
+        let path = repo_dir.join(rel);
+        if path.is_file() {
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Copy directory recursively
 fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     if !dst.exists() {
@@ -566,18 +664,47 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Push to gh-pages branch as orphan
-fn push_gh_pages(repo_dir: &Path) -> Result<String> {
+/// Run `git commit`, with a fallback identity on machines without one (CI)
+fn git_commit(repo_dir: &Path, message: &str) -> Result<std::process::Output> {
+    let has_identity = Command::new("git")
+        .args(["var", "GIT_COMMITTER_IDENT"])
+        .current_dir(repo_dir)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    let mut cmd = Command::new("git");
+    if !has_identity {
+        cmd.args(["-c", "user.name=cass", "-c", "user.email=cass@localhost"]);
+    }
+    cmd.args(["commit", "-m", message])
+        .current_dir(repo_dir)
+        .output()
+        .context("Failed to git commit")
+}
+
+/// Push to `branch` as an orphan, replacing its history
+pub(super) fn push_orphan_branch(repo_dir: &Path, branch: &str) -> Result<String> {
+    // A forced deploy replaces a checked-out branch; drop the local copy first
+    let _ = Command::new("git")
+        .args(["checkout", "-q", "--detach"])
+        .current_dir(repo_dir)
+        .output();
+    let _ = Command::new("git")
+        .args(["branch", "-q", "-D", branch])
+        .current_dir(repo_dir)
+        .output();
+
     // Create orphan branch
     let output = Command::new("git")
-        .args(["checkout", "--orphan", "gh-pages"])
+        .args(["checkout", "--orphan", branch])
         .current_dir(repo_dir)
         .output()
         .context("Failed to create orphan branch")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to create gh-pages branch: {}", stderr);
+        bail!("Failed to create {} branch: {}", branch, stderr);
     }
 
     // Add all files
@@ -593,11 +720,7 @@ fn push_gh_pages(repo_dir: &Path) -> Result<String> {
     }
 
     // Commit
-    let output = Command::new("git")
-        .args(["commit", "-m", "Deploy cass archive"])
-        .current_dir(repo_dir)
-        .output()
-        .context("Failed to git commit")?;
+    let output = git_commit(repo_dir, "Deploy cass archive")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -619,7 +742,7 @@ fn push_gh_pages(repo_dir: &Path) -> Result<String> {
     let repo_dir_owned = repo_dir.to_owned();
     retry_with_backoff("git push", move || {
         let output = Command::new("git")
-            .args(["push", "-f", "origin", "gh-pages"])
+            .args(["push", "-f", "origin", branch])
             .current_dir(&repo_dir_owned)
             .output()
             .context("Failed to git push")?;
@@ -635,6 +758,65 @@ fn push_gh_pages(repo_dir: &Path) -> Result<String> {
     Ok(commit_sha)
 }
 
+/// Commit changes on top of the existing `branch` and push them
+///
+/// Unlike [`push_orphan_branch`] this keeps history, so git only transfers
+/// the objects for files that changed.
+pub(super) fn push_branch_update(repo_dir: &Path, branch: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["add", "-A"])
+        .current_dir(repo_dir)
+        .output()
+        .context("Failed to git add")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to add files: {}", stderr);
+    }
+
+    // Nothing staged means the deployment is already current
+    let unchanged = Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(repo_dir)
+        .status()
+        .context("Failed to run git diff")?
+        .success();
+
+    if !unchanged {
+        let output = git_commit(repo_dir, "Update cass archive")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to commit: {}", stderr);
+        }
+
+        let repo_dir_owned = repo_dir.to_owned();
+        retry_with_backoff("git push", move || {
+            let output = Command::new("git")
+                .args(["push", "origin", branch])
+                .current_dir(&repo_dir_owned)
+                .output()
+                .context("Failed to git push")?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("Failed to push: {}", stderr);
+            }
+
+            Ok(())
+        })?;
+    }
+
+    let sha_output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_dir)
+        .output()
+        .context("Failed to get commit SHA")?;
+
+    let commit_sha = String::from_utf8_lossy(&sha_output.stdout);
+    Ok(commit_sha.trim().to_string())
+}
+
 /// Enable GitHub Pages via API with retry logic
 fn enable_github_pages(username: &str, repo_name: &str) -> bool {
     let api_path = format!("repos/{}/{}/pages", username, repo_name);
@@ -772,4 +954,38 @@ mod tests {
         assert!(dst.path().join("root.txt").exists());
         assert!(dst.path().join("subdir/nested.txt").exists());
     }
+
+    #[test]
+    fn test_sync_bundle_to_repo_applies_diff() {
+        use tempfile::TempDir;
+
+        let bundle = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+
+        std::fs::create_dir_all(bundle.path().join("payload")).unwrap();
+        std::fs::write(bundle.path().join("config.json"), "new config").unwrap();
+        std::fs::write(bundle.path().join("payload/chunk-b.bin"), "b").unwrap();
+        std::fs::write(bundle.path().join("integrity.json"), "{}").unwrap();
+
+        std::fs::create_dir_all(repo.path().join("payload")).unwrap();
+        std::fs::write(repo.path().join("config.json"), "old config").unwrap();
+        std::fs::write(repo.path().join("index.html"), "kept").unwrap();
+        std::fs::write(repo.path().join("payload/chunk-a.bin"), "a").unwrap();
+
+        let diff = ManifestDiff {
+            added: vec!["payload/chunk-b.bin".to_string()],
+            changed: vec!["config.json".to_string()],
+            removed: vec!["payload/chunk-a.bin".to_string()],
+            unchanged: 1,
+            upload_bytes: 11,
+        };
+        sync_bundle_to_repo(bundle.path(), repo.path(), &diff).unwrap();
+
+        let read = |rel: &str| std::fs::read_to_string(repo.path().join(rel)).unwrap();
+        assert_eq!(read("config.json"), "new config");
+        assert_eq!(read("payload/chunk-b.bin"), "b");
+        assert_eq!(read("index.html"), "kept");
+        assert!(repo.path().join("integrity.json").exists());
+        assert!(!repo.path().join("payload/chunk-a.bin").exists());
+    }
 }
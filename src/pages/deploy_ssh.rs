@@ -0,0 +1,294 @@
+//! Deployment to a web server over SSH.
+//!
+//! Publishes site/ into a directory on a host (e.g. the docroot of an nginx
+//! box). Uses rsync when it is installed and falls back to SFTP through the
+//! same ssh2 connection code the sources sync uses. Either way the
+//! deployed integrity.json decides what changed: a first deployment never
+//! deletes anything on the server, an update removes files dropped from the
+//! bundle.
+
+use anyhow::{Context, Result, bail};
+use ssh2::{ErrorCode, Sftp};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::bundle::IntegrityManifest;
+use super::deploy::{DeploySummary, Deployer, SiteStore, deploy_to_store};
+use crate::sources::sync::{SyncEngine, SyncMethod, connect_ssh, parse_rsync_stats};
+
+/// SFTP status code for a missing file
+const SFTP_NO_SUCH_FILE: i32 = 2;
+
+/// Default SSH connection timeout (seconds)
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Deployer copying the site to `host:remote_path`
+#[derive(Debug, Clone)]
+pub struct SshDeployer {
+    /// `[user@]host`, resolved through ~/.ssh/config
+    host: String,
+    /// Directory on the host that receives the site
+    remote_path: String,
+    /// Transfer method; detected when `None`
+    method: Option<SyncMethod>,
+    /// SSH command for rsync and remote commands (port, key, options)
+    ssh_command: Option<String>,
+    /// Connection timeout in seconds
+    timeout: u64,
+}
+
+impl SshDeployer {
+    pub fn new(host: impl Into<String>, remote_path: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            remote_path: remote_path.into(),
+            method: None,
+            ssh_command: None,
+            timeout: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+
+    /// Parse an scp-style destination, `[user@]host:/path`
+    pub fn parse(destination: &str) -> Result<Self> {
+        match destination.split_once(':') {
+            Some((host, path)) if !host.is_empty() && !path.is_empty() => Ok(Self::new(host, path)),
+            _ => bail!("SSH destination must look like [user@]host:/path, got {destination}"),
+        }
+    }
+
+    /// Force rsync or SFTP instead of detecting rsync
+    pub fn method(mut self, method: SyncMethod) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// SSH command used by rsync, e.g. `ssh -p 2222 -i ~/.ssh/deploy`
+    ///
+    /// Only applies to rsync; SFTP reads port and key from ~/.ssh/config.
+    pub fn ssh_command(mut self, command: impl Into<String>) -> Self {
+        self.ssh_command = Some(command.into());
+        self
+    }
+
+    /// Set the connection timeout in seconds
+    pub fn timeout(mut self, seconds: u64) -> Self {
+        self.timeout = seconds;
+        self
+    }
+
+    fn ssh_argv(&self) -> Vec<String> {
+        match &self.ssh_command {
+            Some(command) => shell_words::split(command).unwrap_or_else(|_| vec![command.clone()]),
+            None => vec![
+                "ssh".to_string(),
+                "-o".to_string(),
+                "BatchMode=yes".to_string(),
+                "-o".to_string(),
+                format!("ConnectTimeout={}", self.timeout),
+            ],
+        }
+    }
+
+    /// Read the deployed integrity.json with `cat` over SSH
+    fn remote_manifest(&self) -> Result<Option<IntegrityManifest>> {
+        let argv = self.ssh_argv();
+        let remote = format!(
+            "cat -- {} 2>/dev/null || true",
+            shell_words::quote(&format!(
+                "{}/integrity.json",
+                self.remote_path.trim_end_matches('/')
+            ))
+        );
+        let output = Command::new(&argv[0])
+            .args(&argv[1..])
+            .arg(&self.host)
+            .arg(remote)
+            .output()
+            .context("Failed to run ssh")?;
+        if !output.status.success() {
+            bail!(
+                "SSH to {} failed: {}",
+                self.host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(serde_json::from_slice(&output.stdout).ok())
+    }
+
+    fn deploy_rsync(
+        &self,
+        site_dir: &Path,
+        progress: &dyn Fn(&str, &str),
+    ) -> Result<DeploySummary> {
+        let manifest = IntegrityManifest::load(site_dir)
+            .context("Bundle has no integrity.json; build it with the bundle builder first")?;
+
+        progress("compare", "Reading deployed manifest...");
+        let previous = self.remote_manifest()?;
+        let diff = manifest.diff(previous.as_ref());
+
+        progress("upload", "Running rsync...");
+        let mut cmd = Command::new("rsync");
+        cmd.args([
+            "-rtz",
+            "--checksum",
+            "--stats",
+            "--protect-args",
+            // Swap updated files into place together at the end
+            "--delay-updates",
+        ]);
+        // Only an existing cass deployment is pruned; never delete on first deploy
+        if previous.is_some() {
+            cmd.arg("--delete-after");
+        }
+        let source = format!("{}/", site_dir.display());
+        let dest = format!("{}:{}/", self.host, self.remote_path.trim_end_matches('/'));
+        let output = cmd
+            .arg("-e")
+            .arg(shell_words::join(self.ssh_argv()))
+            .arg("--")
+            .arg(&source)
+            .arg(&dest)
+            .output()
+            .context("Failed to execute rsync")?;
+        if !output.status.success() {
+            bail!(
+                "rsync failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let stats = parse_rsync_stats(&String::from_utf8_lossy(&output.stdout));
+        tracing::info!(
+            files = stats.files_transferred,
+            bytes = stats.bytes_transferred,
+            "pages rsync deploy completed"
+        );
+
+        progress("complete", "Deployment complete!");
+        Ok(DeploySummary {
+            destination: self.destination(),
+            url: None,
+            incremental: previous.is_some(),
+            uploaded_files: diff.added.len() + diff.changed.len(),
+            removed_files: if previous.is_some() {
+                diff.removed.len()
+            } else {
+                0
+            },
+            unchanged_files: diff.unchanged,
+        })
+    }
+
+    fn deploy_sftp(&self, site_dir: &Path, progress: &dyn Fn(&str, &str)) -> Result<DeploySummary> {
+        progress("connect", &format!("Connecting to {}...", self.host));
+        let session = connect_ssh(&self.host, self.timeout).map_err(anyhow::Error::msg)?;
+        let sftp = session.sftp().context("Failed to open SFTP session")?;
+        // SFTP paths are relative to the login directory
+        let root = self
+            .remote_path
+            .strip_prefix("~/")
+            .unwrap_or(&self.remote_path);
+        let store = SftpStore {
+            sftp,
+            root: PathBuf::from(root),
+        };
+        deploy_to_store(&store, site_dir, self.destination(), progress)
+    }
+}
+
+impl Deployer for SshDeployer {
+    fn destination(&self) -> String {
+        format!("{}:{}", self.host, self.remote_path)
+    }
+
+    fn deploy(&self, site_dir: &Path, progress: &dyn Fn(&str, &str)) -> Result<DeploySummary> {
+        match self.method.unwrap_or_else(SyncEngine::detect_sync_method) {
+            SyncMethod::Rsync => self.deploy_rsync(site_dir, progress),
+            SyncMethod::Sftp => self.deploy_sftp(site_dir, progress),
+            method => bail!("{} cannot deploy over SSH", method),
+        }
+    }
+}
+
+/// A site directory on an SFTP server
+struct SftpStore {
+    sftp: Sftp,
+    root: PathBuf,
+}
+
+impl SftpStore {
+    /// Create `dir` and its missing parents
+    fn mkdir_all(&self, dir: &Path) -> Result<()> {
+        let mut current = PathBuf::new();
+        for component in dir.components() {
+            current.push(component);
+            if self.sftp.stat(&current).is_err() {
+                self.sftp
+                    .mkdir(&current, 0o755)
+                    .with_context(|| format!("Failed to create {}", current.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_missing(e: &ssh2::Error) -> bool {
+    e.code() == ErrorCode::SFTP(SFTP_NO_SUCH_FILE)
+}
+
+impl SiteStore for SftpStore {
+    fn read(&self, rel: &str) -> Result<Option<Vec<u8>>> {
+        match self.sftp.open(self.root.join(rel)) {
+            Ok(mut file) => {
+                let mut data = Vec::new();
+                file.read_to_end(&mut data)?;
+                Ok(Some(data))
+            }
+            Err(e) if is_missing(&e) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, rel: &str, data: &[u8]) -> Result<()> {
+        let path = self.root.join(rel);
+        if let Some(parent) = path.parent() {
+            self.mkdir_all(parent)?;
+        }
+        let mut file = self.sftp.create(&path)?;
+        file.write_all(data)?;
+        Ok(())
+    }
+
+    fn remove(&self, rel: &str) -> Result<()> {
+        match self.sftp.unlink(&self.root.join(rel)) {
+            Err(e) if !is_missing(&e) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scp_style_destinations() {
+        let deployer = SshDeployer::parse("deploy@web1:/srv/www/archive").unwrap();
+        assert_eq!(deployer.host, "deploy@web1");
+        assert_eq!(deployer.remote_path, "/srv/www/archive");
+        assert_eq!(deployer.destination(), "deploy@web1:/srv/www/archive");
+
+        assert!(SshDeployer::parse("web1").is_err());
+        assert!(SshDeployer::parse(":/srv").is_err());
+        assert!(SshDeployer::parse("web1:").is_err());
+
+        let custom = SshDeployer::parse("web1:www")
+            .unwrap()
+            .ssh_command("ssh -p 2222 -i '/keys/deploy key'");
+        assert_eq!(
+            custom.ssh_argv(),
+            ["ssh", "-p", "2222", "-i", "/keys/deploy key"]
+        );
+    }
+}
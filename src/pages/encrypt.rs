@@ -6,6 +6,8 @@
 //! - AES-256-GCM authenticated encryption
 //! - Streaming encryption for large files
 //! - Multiple key slots (like LUKS)
+//! - Content-addressed chunks, so a re-export under the same DEK reuses the
+//!   encrypted chunks whose plaintext did not change
 
 use aes_gcm::{
     Aes256Gcm, Nonce,
@@ -18,13 +20,20 @@ use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
 use hkdf::Hkdf;
 use rand::{RngCore, rngs::OsRng};
 use serde::{Deserialize, Serialize};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use super::recipient::{Identity, encode_recipient, parse_recipient};
+
 /// Default chunk size for streaming encryption (8 MiB)
+///
+/// Chunks are cut at fixed offsets, so keeping this a multiple of the export
+/// database page size means a page that did not change always lands in the
+/// same chunk on re-export.
 pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024 * 1024;
 
 /// Maximum chunk size (32 MiB)
@@ -36,7 +45,10 @@ const ARGON2_ITERATIONS: u32 = 3;
 const ARGON2_PARALLELISM: u32 = 4;
 
 /// Encryption schema version
-const SCHEMA_VERSION: u8 = 2;
+const SCHEMA_VERSION: u8 = 3;
+
+/// Last schema version using counter nonces and index-named chunks
+const COUNTER_NONCE_VERSION: u8 = 2;
 
 /// Secret key material that zeros on drop
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
@@ -64,6 +76,7 @@ impl SecretKey {
 pub enum SlotType {
     Password,
     Recovery,
+    Recipient,
 }
 
 /// KDF algorithm identifier
@@ -72,6 +85,7 @@ pub enum SlotType {
 pub enum KdfAlgorithm {
     Argon2id,
     HkdfSha256,
+    X25519HkdfSha256,
 }
 
 /// Key slot in config.json
@@ -85,6 +99,9 @@ pub struct KeySlot {
     pub nonce: String,       // base64-encoded (for DEK wrapping)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub argon2_params: Option<Argon2Params>,
+    /// age1… public key the slot is wrapped to (recipient slots only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
 }
 
 /// Argon2 parameters for config.json
@@ -113,6 +130,33 @@ pub struct PayloadMeta {
     pub total_compressed_size: u64,
     pub total_plaintext_size: u64,
     pub files: Vec<String>,
+    /// Content-addressed chunk list in payload order (schema v3+)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<ChunkMeta>,
+}
+
+/// A content-addressed payload chunk
+///
+/// The id is keyed by the DEK, so equal plaintext under the same DEK always
+/// produces the same id, nonce and ciphertext, while nothing about the
+/// plaintext is learnable without the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkMeta {
+    pub id: String,    // hex-encoded 32 bytes
+    pub file: String,  // path relative to the export root
+    pub nonce: String, // base64-encoded 12 bytes
+    pub plaintext_size: u64,
+}
+
+/// How many chunks an encryption run wrote versus reused
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkReuse {
+    /// Chunks encrypted and written in this run
+    pub written: usize,
+    /// Chunks already present from a previous run under the same DEK
+    pub reused: usize,
+    /// Stale chunk files removed from the payload directory
+    pub removed: usize,
 }
 
 /// Full config.json structure
@@ -160,9 +204,60 @@ impl EncryptionEngine {
         }
     }
 
+    /// Continue a previous export, keeping its DEK, export id, chunk size and
+    /// key slots so unchanged chunks can be reused as-is.
+    pub fn from_existing(config: &EncryptionConfig, password: &str) -> Result<Self> {
+        let unlocked = DecryptionEngine::unlock_with_password(config.clone(), password)?;
+        Self::from_unlocked(&unlocked)
+    }
+
+    /// Build an engine around the DEK of an unlocked archive
+    pub fn from_unlocked(unlocked: &DecryptionEngine) -> Result<Self> {
+        let config = &unlocked.config;
+        let export_id: [u8; 16] = BASE64
+            .decode(&config.export_id)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid export_id length"))?;
+        let base_nonce: [u8; 12] = BASE64
+            .decode(&config.base_nonce)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid base_nonce length"))?;
+
+        Ok(Self {
+            dek: unlocked.dek.clone(),
+            export_id,
+            base_nonce,
+            chunk_size: config.payload.chunk_size.min(MAX_CHUNK_SIZE),
+            key_slots: config.key_slots.clone(),
+        })
+    }
+
+    /// Current key slots
+    pub fn key_slots(&self) -> &[KeySlot] {
+        &self.key_slots
+    }
+
+    /// Remove a key slot by id, returning whether it existed
+    pub fn remove_slot(&mut self, slot_id: u8) -> bool {
+        let before = self.key_slots.len();
+        self.key_slots.retain(|slot| slot.id != slot_id);
+        self.key_slots.len() != before
+    }
+
+    /// Slot ids are bound into the wrapping AAD and must stay unique; after a
+    /// revoke the slot count no longer matches the highest id
+    fn next_slot_id(&self) -> Result<u8> {
+        match self.key_slots.iter().map(|slot| slot.id).max() {
+            None => Ok(0),
+            Some(max) => max
+                .checked_add(1)
+                .context("No free key slot ids left (255 slots used)"),
+        }
+    }
+
     /// Add a password-based key slot using Argon2id
     pub fn add_password_slot(&mut self, password: &str) -> Result<u8> {
-        let slot_id = self.key_slots.len() as u8;
+        let slot_id = self.next_slot_id()?;
 
         // Generate salt
         let salt = SaltString::generate(&mut OsRng);
@@ -182,6 +277,7 @@ impl EncryptionEngine {
             wrapped_dek: BASE64.encode(&wrapped_dek),
             nonce: BASE64.encode(nonce),
             argon2_params: Some(Argon2Params::default()),
+            recipient: None,
         });
 
         Ok(slot_id)
@@ -189,7 +285,7 @@ impl EncryptionEngine {
 
     /// Add a recovery secret slot using HKDF-SHA256
     pub fn add_recovery_slot(&mut self, secret: &[u8]) -> Result<u8> {
-        let slot_id = self.key_slots.len() as u8;
+        let slot_id = self.next_slot_id()?;
 
         // Generate salt
         let mut salt = [0u8; 16];
@@ -209,6 +305,39 @@ impl EncryptionEngine {
             wrapped_dek: BASE64.encode(&wrapped_dek),
             nonce: BASE64.encode(nonce),
             argon2_params: None,
+            recipient: None,
+        });
+
+        Ok(slot_id)
+    }
+
+    /// Add a slot wrapped to an X25519 public key (`age1…`)
+    ///
+    /// A fresh ephemeral key is agreed with the recipient; its public half is
+    /// stored as the slot salt so the holder of the secret key can redo the
+    /// agreement.
+    pub fn add_recipient_slot(&mut self, recipient: &str) -> Result<u8> {
+        let recipient_public = parse_recipient(recipient)?;
+        let slot_id = self.next_slot_id()?;
+
+        let ephemeral = Identity::generate();
+        let ephemeral_public = ephemeral.public_bytes();
+        let shared = ephemeral
+            .shared_secret(&recipient_public)
+            .context("Recipient key is a low-order point")?;
+        let kek = derive_kek_x25519(&shared, &ephemeral_public, &recipient_public)?;
+
+        let (wrapped_dek, nonce) = wrap_key(&kek, self.dek.as_bytes(), &self.export_id, slot_id)?;
+
+        self.key_slots.push(KeySlot {
+            id: slot_id,
+            slot_type: SlotType::Recipient,
+            kdf: KdfAlgorithm::X25519HkdfSha256,
+            salt: BASE64.encode(ephemeral_public),
+            wrapped_dek: BASE64.encode(&wrapped_dek),
+            nonce: BASE64.encode(nonce),
+            argon2_params: None,
+            recipient: Some(encode_recipient(&recipient_public)),
         });
 
         Ok(slot_id)
@@ -221,6 +350,25 @@ impl EncryptionEngine {
         output_dir: P,
         progress: impl Fn(u64, u64),
     ) -> Result<EncryptionConfig> {
+        self.encrypt_file_incremental(input, output_dir, progress)
+            .map(|(config, _)| config)
+    }
+
+    /// Encrypt a file, reusing chunk files already present in `output_dir`
+    ///
+    /// Chunk files are named by their content id, so a chunk left behind by a
+    /// previous run under the same DEK at the same position is byte-identical
+    /// to what this run would write; such a file is kept without compressing
+    /// or encrypting the chunk again. Chunks are written through a temporary
+    /// file, so an existing chunk file is always complete. Chunk files no
+    /// longer referenced are removed only after the new config.json is in
+    /// place, so an interrupted run leaves the previous export readable.
+    pub fn encrypt_file_incremental<P: AsRef<Path>>(
+        &self,
+        input: P,
+        output_dir: P,
+        progress: impl Fn(u64, u64),
+    ) -> Result<(EncryptionConfig, ChunkReuse)> {
         let input_path = input.as_ref();
         let output_dir = output_dir.as_ref();
 
@@ -237,13 +385,14 @@ impl EncryptionEngine {
 
         // Compress and encrypt in chunks
         let mut chunk_files = Vec::new();
-        let mut chunk_index = 0u32;
+        let mut chunks = Vec::new();
+        let mut reuse = ChunkReuse::default();
         let mut total_compressed = 0u64;
         let mut bytes_read = 0u64;
 
         let cipher = Aes256Gcm::new_from_slice(self.dek.as_bytes()).expect("Invalid key length");
 
-        loop {
+        for chunk_index in 0u32.. {
             // Read up to chunk_size bytes
             let mut plaintext = vec![0u8; self.chunk_size];
             let mut total_read = 0;
@@ -266,40 +415,58 @@ impl EncryptionEngine {
 
             plaintext.truncate(total_read);
 
-            // Compress the chunk
-            let mut compressed = Vec::new();
-            {
-                let mut encoder = DeflateEncoder::new(&mut compressed, Compression::default());
-                encoder.write_all(&plaintext)?;
-                encoder.finish()?;
-            }
-
-            // Derive nonce for this chunk (counter-based)
-            let nonce = derive_chunk_nonce(&self.base_nonce, chunk_index);
-
-            // Build AAD: export_id || chunk_index || schema_version
-            let aad = build_chunk_aad(&self.export_id, chunk_index);
-
-            // Encrypt with AEAD
-            let ciphertext = cipher
-                .encrypt(
-                    Nonce::from_slice(&nonce),
-                    Payload {
-                        msg: &compressed,
-                        aad: &aad,
-                    },
-                )
-                .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
-
-            // Write chunk file
-            let chunk_filename = format!("chunk-{:05}.bin", chunk_index);
+            // Content id and nonce are both derived from the plaintext and its
+            // position under the DEK
+            let (chunk_id, nonce) = derive_chunk_address(&self.dek, chunk_index, &plaintext)?;
+            let chunk_id_hex = hex::encode(chunk_id);
+            let chunk_filename = format!("chunk-{}.bin", chunk_id_hex);
             let chunk_path = payload_dir.join(&chunk_filename);
-            let mut chunk_file = File::create(&chunk_path)?;
-            chunk_file.write_all(&ciphertext)?;
 
-            chunk_files.push(format!("payload/{}", chunk_filename));
-            total_compressed += ciphertext.len() as u64;
-            chunk_index += 1;
+            let ciphertext_len = match std::fs::metadata(&chunk_path) {
+                Ok(meta) if meta.is_file() => {
+                    reuse.reused += 1;
+                    meta.len()
+                }
+                _ => {
+                    // Compress the chunk
+                    let mut compressed = Vec::new();
+                    {
+                        let mut encoder =
+                            DeflateEncoder::new(&mut compressed, Compression::default());
+                        encoder.write_all(&plaintext)?;
+                        encoder.finish()?;
+                    }
+
+                    // Build AAD: export_id || chunk_index || chunk_id || schema_version
+                    let aad = build_content_chunk_aad(&self.export_id, chunk_index, &chunk_id);
+
+                    let ciphertext = cipher
+                        .encrypt(
+                            Nonce::from_slice(&nonce),
+                            Payload {
+                                msg: &compressed,
+                                aad: &aad,
+                            },
+                        )
+                        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+                    let tmp_path = payload_dir.join(format!("{chunk_filename}.tmp"));
+                    std::fs::write(&tmp_path, &ciphertext)?;
+                    std::fs::rename(&tmp_path, &chunk_path)?;
+                    reuse.written += 1;
+                    ciphertext.len() as u64
+                }
+            };
+
+            let file = format!("payload/{}", chunk_filename);
+            chunk_files.push(file.clone());
+            chunks.push(ChunkMeta {
+                id: chunk_id_hex,
+                file,
+                nonce: BASE64.encode(nonce),
+                plaintext_size: total_read as u64,
+            });
+            total_compressed += ciphertext_len;
         }
 
         // Build config
@@ -311,20 +478,46 @@ impl EncryptionEngine {
             kdf_defaults: Argon2Params::default(),
             payload: PayloadMeta {
                 chunk_size: self.chunk_size,
-                chunk_count: chunk_index as usize,
+                chunk_count: chunks.len(),
                 total_compressed_size: total_compressed,
                 total_plaintext_size: input_size,
                 files: chunk_files,
+                chunks,
             },
             key_slots: self.key_slots.clone(),
         };
 
-        // Write config.json
+        // Write config.json, replacing the previous one in a single step
         let config_path = output_dir.join("config.json");
-        let config_file = File::create(&config_path)?;
-        serde_json::to_writer_pretty(BufWriter::new(config_file), &config)?;
+        let config_tmp = output_dir.join("config.json.tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&config_tmp)?);
+            serde_json::to_writer_pretty(&mut writer, &config)?;
+            writer.flush()?;
+        }
+        std::fs::rename(&config_tmp, &config_path)?;
+
+        // Drop chunks from earlier runs that are no longer part of the payload
+        let live: HashSet<&str> = config
+            .payload
+            .files
+            .iter()
+            .filter_map(|f| f.strip_prefix("payload/"))
+            .collect();
+        for entry in std::fs::read_dir(&payload_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // Temporary files are what an interrupted run left behind
+            let stale =
+                name.ends_with(".bin.tmp") || (name.ends_with(".bin") && !live.contains(&*name));
+            if name.starts_with("chunk-") && stale {
+                std::fs::remove_file(entry.path())?;
+                reuse.removed += 1;
+            }
+        }
 
-        Ok(config)
+        Ok((config, reuse))
     }
 }
 
@@ -332,6 +525,7 @@ impl EncryptionEngine {
 pub struct DecryptionEngine {
     dek: SecretKey,
     config: EncryptionConfig,
+    slot_id: u8,
 }
 
 impl DecryptionEngine {
@@ -352,6 +546,7 @@ impl DecryptionEngine {
             if let Ok(dek) = unwrap_key(&kek, &wrapped_dek, &nonce, &export_id, slot.id) {
                 return Ok(Self {
                     dek: SecretKey::from_bytes(dek),
+                    slot_id: slot.id,
                     config,
                 });
             }
@@ -377,6 +572,7 @@ impl DecryptionEngine {
             if let Ok(dek) = unwrap_key(&kek, &wrapped_dek, &nonce, &export_id, slot.id) {
                 return Ok(Self {
                     dek: SecretKey::from_bytes(dek),
+                    slot_id: slot.id,
                     config,
                 });
             }
@@ -385,6 +581,52 @@ impl DecryptionEngine {
         bail!("Invalid recovery secret or no matching key slot")
     }
 
+    /// Unlock with an X25519 identity holding a recipient slot's secret key
+    pub fn unlock_with_identity(config: EncryptionConfig, identity: &Identity) -> Result<Self> {
+        let recipient = identity.recipient();
+        let recipient_public = identity.public_bytes();
+        for slot in &config.key_slots {
+            if slot.slot_type != SlotType::Recipient
+                || slot.recipient.as_deref().is_some_and(|r| r != recipient)
+            {
+                continue;
+            }
+
+            let ephemeral_public: [u8; 32] = match BASE64.decode(&slot.salt)?.try_into() {
+                Ok(bytes) => bytes,
+                Err(_) => continue,
+            };
+            let Some(shared) = identity.shared_secret(&ephemeral_public) else {
+                continue;
+            };
+            let wrapped_dek = BASE64.decode(&slot.wrapped_dek)?;
+            let nonce = BASE64.decode(&slot.nonce)?;
+
+            let kek = derive_kek_x25519(&shared, &ephemeral_public, &recipient_public)?;
+
+            let export_id = BASE64.decode(&config.export_id)?;
+            if let Ok(dek) = unwrap_key(&kek, &wrapped_dek, &nonce, &export_id, slot.id) {
+                return Ok(Self {
+                    dek: SecretKey::from_bytes(dek),
+                    slot_id: slot.id,
+                    config,
+                });
+            }
+        }
+
+        bail!("No key slot is wrapped to this identity")
+    }
+
+    /// Config of the unlocked archive
+    pub fn config(&self) -> &EncryptionConfig {
+        &self.config
+    }
+
+    /// Id of the key slot that unlocked the archive
+    pub fn slot_id(&self) -> u8 {
+        self.slot_id
+    }
+
     /// Decrypt all chunks to output file
     pub fn decrypt_to_file<P: AsRef<Path>>(
         &self,
@@ -403,17 +645,43 @@ impl DecryptionEngine {
         let mut output_file = File::create(output_path)?;
         let mut writer = BufWriter::new(&mut output_file);
 
+        let content_addressed = self.config.version > COUNTER_NONCE_VERSION;
+        if content_addressed && self.config.payload.chunks.len() != self.config.payload.files.len()
+        {
+            bail!("config.json chunk list does not match payload files");
+        }
+
         for (chunk_index, chunk_file) in self.config.payload.files.iter().enumerate() {
             progress(chunk_index, self.config.payload.chunk_count);
 
             let chunk_path = encrypted_dir.join(chunk_file);
             let ciphertext = std::fs::read(&chunk_path)?;
 
-            // Derive nonce
-            let nonce = derive_chunk_nonce(base_nonce.as_slice().try_into()?, chunk_index as u32);
-
-            // Build AAD
-            let aad = build_chunk_aad(export_id.as_slice().try_into()?, chunk_index as u32);
+            // Content-addressed chunks carry their own nonce; legacy chunks use
+            // the counter nonce and index-bound AAD
+            let (nonce, aad) = if content_addressed {
+                let meta = &self.config.payload.chunks[chunk_index];
+                let chunk_id: [u8; 32] = hex::decode(&meta.id)?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid chunk id for chunk {}", chunk_index))?;
+                let nonce: [u8; 12] = BASE64
+                    .decode(&meta.nonce)?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid nonce for chunk {}", chunk_index))?;
+                (
+                    nonce,
+                    build_content_chunk_aad(
+                        export_id.as_slice().try_into()?,
+                        chunk_index as u32,
+                        &chunk_id,
+                    ),
+                )
+            } else {
+                (
+                    derive_chunk_nonce(base_nonce.as_slice().try_into()?, chunk_index as u32),
+                    build_chunk_aad(export_id.as_slice().try_into()?, chunk_index as u32),
+                )
+            };
 
             // Decrypt
             let compressed = cipher
@@ -431,6 +699,16 @@ impl DecryptionEngine {
             let mut plaintext = Vec::new();
             decoder.read_to_end(&mut plaintext)?;
 
+            // A content-addressed chunk must still hash to its id; the AAD and
+            // the id both bind the chunk's index, so reordering fails too
+            if content_addressed {
+                let (chunk_id, _) =
+                    derive_chunk_address(&self.dek, chunk_index as u32, &plaintext)?;
+                if hex::encode(chunk_id) != self.config.payload.chunks[chunk_index].id {
+                    bail!("Chunk {} does not match its content id", chunk_index);
+                }
+            }
+
             writer.write_all(&plaintext)?;
         }
 
@@ -473,6 +751,25 @@ fn derive_kek_hkdf(secret: &[u8], salt: &[u8]) -> Result<SecretKey> {
     Ok(SecretKey::from_bytes(kek))
 }
 
+/// Derive a recipient slot KEK from an X25519 shared secret
+///
+/// Both public keys go into the salt so the KEK is bound to this exact
+/// ephemeral/recipient pair.
+pub fn derive_kek_x25519(
+    shared: &[u8; 32],
+    ephemeral_public: &[u8; 32],
+    recipient_public: &[u8; 32],
+) -> Result<SecretKey> {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient_public);
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), shared);
+    let mut kek = [0u8; 32];
+    hkdf.expand(b"cass-pages-x25519-v1", &mut kek)
+        .map_err(|_| anyhow::anyhow!("HKDF expansion failed"))?;
+    Ok(SecretKey::from_bytes(kek))
+}
+
 /// Wrap DEK with KEK using AES-256-GCM
 fn wrap_key(
     kek: &SecretKey,
@@ -546,15 +843,60 @@ fn derive_chunk_nonce(base_nonce: &[u8; 12], chunk_index: u32) -> [u8; 12] {
     nonce
 }
 
-/// Build AAD for chunk encryption
+/// Build AAD for legacy (schema v2) chunk encryption
 fn build_chunk_aad(export_id: &[u8; 16], chunk_index: u32) -> Vec<u8> {
     let mut aad = Vec::with_capacity(21);
     aad.extend_from_slice(export_id);
     aad.extend_from_slice(&chunk_index.to_be_bytes());
+    aad.push(COUNTER_NONCE_VERSION);
+    aad
+}
+
+/// Derive the content id and nonce of a chunk from its plaintext and index
+///
+/// HKDF-SHA256 keyed by the DEK with the plaintext digest as salt and the
+/// chunk index in the info. Identical plaintext at the same index maps to
+/// the same nonce (and so the same ciphertext), anything else to independent
+/// nonces, so nonces never repeat across messages.
+fn derive_chunk_address(
+    dek: &SecretKey,
+    chunk_index: u32,
+    plaintext: &[u8],
+) -> Result<([u8; 32], [u8; 12])> {
+    let digest = Sha256::digest(plaintext);
+    let hkdf = Hkdf::<Sha256>::new(Some(&digest), dek.as_bytes());
+    let mut info = b"cass-pages-chunk-v3".to_vec();
+    info.extend_from_slice(&chunk_index.to_be_bytes());
+    let mut okm = [0u8; 44];
+    hkdf.expand(&info, &mut okm)
+        .map_err(|_| anyhow::anyhow!("HKDF expansion failed"))?;
+
+    let mut chunk_id = [0u8; 32];
+    let mut nonce = [0u8; 12];
+    chunk_id.copy_from_slice(&okm[..32]);
+    nonce.copy_from_slice(&okm[32..]);
+    okm.zeroize();
+    Ok((chunk_id, nonce))
+}
+
+/// Build AAD for content-addressed chunk encryption
+fn build_content_chunk_aad(export_id: &[u8; 16], chunk_index: u32, chunk_id: &[u8; 32]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(53);
+    aad.extend_from_slice(export_id);
+    aad.extend_from_slice(&chunk_index.to_be_bytes());
+    aad.extend_from_slice(chunk_id);
     aad.push(SCHEMA_VERSION);
     aad
 }
 
+/// Write encryption config to directory
+pub fn write_config<P: AsRef<Path>>(dir: P, config: &EncryptionConfig) -> Result<()> {
+    let config_path = dir.as_ref().join("config.json");
+    let file = File::create(&config_path).context("Failed to create config.json")?;
+    serde_json::to_writer_pretty(BufWriter::new(file), config)?;
+    Ok(())
+}
+
 /// Load encryption config from directory
 pub fn load_config<P: AsRef<Path>>(dir: P) -> Result<EncryptionConfig> {
     let config_path = dir.as_ref().join("config.json");
@@ -717,6 +1059,43 @@ mod tests {
         assert!(DecryptionEngine::unlock_with_password(config, "wrong").is_err());
     }
 
+    #[test]
+    fn test_recipient_slot_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_path = temp_dir.path().join("input.txt");
+        let output_dir = temp_dir.path().join("encrypted");
+        let decrypted_path = temp_dir.path().join("decrypted.txt");
+        std::fs::write(&input_path, b"Shared with a teammate").unwrap();
+
+        let teammate = Identity::generate();
+        let outsider = Identity::generate();
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_recovery_slot(b"owner-secret").unwrap();
+        let slot_id = engine.add_recipient_slot(&teammate.recipient()).unwrap();
+        let config = engine
+            .encrypt_file(&input_path, &output_dir, |_, _| {})
+            .unwrap();
+
+        let slot = &config.key_slots[slot_id as usize];
+        assert_eq!(slot.slot_type, SlotType::Recipient);
+        assert_eq!(
+            slot.recipient.as_deref(),
+            Some(teammate.recipient().as_str())
+        );
+
+        let unlocked = DecryptionEngine::unlock_with_identity(config.clone(), &teammate).unwrap();
+        unlocked
+            .decrypt_to_file(&output_dir, &decrypted_path, |_, _| {})
+            .unwrap();
+        assert_eq!(
+            std::fs::read(&decrypted_path).unwrap(),
+            b"Shared with a teammate"
+        );
+
+        assert!(DecryptionEngine::unlock_with_identity(config, &outsider).is_err());
+        assert!(engine.add_recipient_slot("age1bogus").is_err());
+    }
+
     #[test]
     fn test_tampered_chunk_fails() {
         let temp_dir = TempDir::new().unwrap();
@@ -734,7 +1113,7 @@ mod tests {
             .unwrap();
 
         // Tamper with first chunk
-        let chunk_path = output_dir.join("payload/chunk-00000.bin");
+        let chunk_path = output_dir.join(&config.payload.files[0]);
         let mut chunk_data = std::fs::read(&chunk_path).unwrap();
         chunk_data[0] ^= 0xFF; // Flip some bits
         std::fs::write(&chunk_path, &chunk_data).unwrap();
@@ -747,4 +1126,160 @@ mod tests {
                 .is_err()
         );
     }
+
+    #[test]
+    fn test_reexport_reuses_unchanged_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_path = temp_dir.path().join("input.bin");
+        let output_dir = temp_dir.path().join("encrypted");
+        let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+        let mut data: Vec<u8> = (0..4096u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&input_path, &data).unwrap();
+
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_password_slot("password").unwrap();
+        let (first, reuse) = engine
+            .encrypt_file_incremental(&input_path, &output_dir, |_, _| {})
+            .unwrap();
+        assert_eq!(first.payload.chunk_count, 4);
+        assert_eq!(reuse.written, 4);
+
+        // Change only the third chunk and re-export under the recovered DEK
+        data[2048] ^= 0xFF;
+        std::fs::write(&input_path, &data).unwrap();
+        let resumed = EncryptionEngine::from_existing(&first, "password").unwrap();
+        let (second, reuse) = resumed
+            .encrypt_file_incremental(&input_path, &output_dir, |_, _| {})
+            .unwrap();
+
+        assert_eq!(
+            reuse,
+            ChunkReuse {
+                written: 1,
+                reused: 3,
+                removed: 1
+            }
+        );
+        assert_eq!(second.export_id, first.export_id);
+        assert_eq!(second.payload.files[0], first.payload.files[0]);
+        assert_ne!(second.payload.files[2], first.payload.files[2]);
+        assert!(!output_dir.join(&first.payload.files[2]).exists());
+
+        // Existing chunks are reused without re-encrypting; a temporary chunk
+        // left by an interrupted run is cleaned up after config.json is written
+        let leftover = output_dir.join("payload/chunk-00.bin.tmp");
+        std::fs::write(&leftover, b"partial").unwrap();
+        let (third, reuse) = resumed
+            .encrypt_file_incremental(&input_path, &output_dir, |_, _| {})
+            .unwrap();
+        assert_eq!(
+            reuse,
+            ChunkReuse {
+                written: 0,
+                reused: 4,
+                removed: 1
+            }
+        );
+        assert!(!leftover.exists());
+        assert!(!output_dir.join("config.json.tmp").exists());
+        assert_eq!(
+            third.payload.total_compressed_size,
+            second.payload.total_compressed_size
+        );
+
+        let decryptor = DecryptionEngine::unlock_with_password(second, "password").unwrap();
+        decryptor
+            .decrypt_to_file(&output_dir, &decrypted_path, |_, _| {})
+            .unwrap();
+        assert_eq!(std::fs::read(&decrypted_path).unwrap(), data);
+
+        // A wrong password cannot continue the export
+        assert!(EncryptionEngine::from_existing(&first, "wrong").is_err());
+    }
+
+    #[test]
+    fn test_swapped_chunks_fail() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_path = temp_dir.path().join("input.bin");
+        let output_dir = temp_dir.path().join("encrypted");
+        let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+        let data: Vec<u8> = (0..2048u32).map(|i| (i / 7) as u8).collect();
+        std::fs::write(&input_path, &data).unwrap();
+
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_password_slot("password").unwrap();
+        let mut config = engine
+            .encrypt_file(&input_path, &output_dir, |_, _| {})
+            .unwrap();
+
+        // Reordering the chunk list must not yield a different plaintext,
+        // whether only the files or the files and their metadata are swapped
+        let mut files_only = config.clone();
+        files_only.payload.files.swap(0, 1);
+        config.payload.files.swap(0, 1);
+        config.payload.chunks.swap(0, 1);
+        for config in [files_only, config] {
+            let decryptor = DecryptionEngine::unlock_with_password(config, "password").unwrap();
+            assert!(
+                decryptor
+                    .decrypt_to_file(&output_dir, &decrypted_path, |_, _| {})
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_legacy_counter_nonce_payload_decrypts() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("encrypted");
+        let decrypted_path = temp_dir.path().join("decrypted.txt");
+        std::fs::create_dir_all(output_dir.join("payload")).unwrap();
+
+        // Write a single v2 chunk by hand
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_password_slot("password").unwrap();
+        let plaintext = b"schema v2 payload";
+        let mut compressed = Vec::new();
+        {
+            let mut encoder = DeflateEncoder::new(&mut compressed, Compression::default());
+            encoder.write_all(plaintext).unwrap();
+            encoder.finish().unwrap();
+        }
+        let cipher = Aes256Gcm::new_from_slice(engine.dek.as_bytes()).unwrap();
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&derive_chunk_nonce(&engine.base_nonce, 0)),
+                Payload {
+                    msg: &compressed,
+                    aad: &build_chunk_aad(&engine.export_id, 0),
+                },
+            )
+            .unwrap();
+        std::fs::write(output_dir.join("payload/chunk-00000.bin"), &ciphertext).unwrap();
+
+        let config = EncryptionConfig {
+            version: COUNTER_NONCE_VERSION,
+            export_id: BASE64.encode(engine.export_id),
+            base_nonce: BASE64.encode(engine.base_nonce),
+            compression: "deflate".to_string(),
+            kdf_defaults: Argon2Params::default(),
+            payload: PayloadMeta {
+                chunk_size: 1024,
+                chunk_count: 1,
+                total_compressed_size: ciphertext.len() as u64,
+                total_plaintext_size: plaintext.len() as u64,
+                files: vec!["payload/chunk-00000.bin".to_string()],
+                chunks: Vec::new(),
+            },
+            key_slots: engine.key_slots.clone(),
+        };
+
+        let decryptor = DecryptionEngine::unlock_with_password(config, "password").unwrap();
+        decryptor
+            .decrypt_to_file(&output_dir, &decrypted_path, |_, _| {})
+            .unwrap();
+        assert_eq!(std::fs::read(&decrypted_path).unwrap(), plaintext);
+    }
 }
@@ -1,3 +1,6 @@
+use crate::pages::bundle::{BundleBuilder, PlaintextMode};
+use crate::pages::curation::{Curation, RedactRules};
+use crate::pages::vectors::{VECTOR_QUANTIZATION, VectorSource, quantize_i8};
 use crate::ui::time_parser::parse_time_input;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -9,6 +12,13 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+/// Page size of the exported database.
+///
+/// Payload chunks are cut at fixed offsets that are a multiple of this, so
+/// with a deterministic row order an unchanged page re-encrypts to the same
+/// chunk content on the next export.
+pub const EXPORT_PAGE_SIZE: u32 = 4096;
+
 #[derive(Debug, Clone)]
 pub struct ExportFilter {
     pub agents: Option<Vec<String>>,
@@ -30,11 +40,46 @@ pub struct ExportEngine {
     source_db_path: PathBuf,
     output_path: PathBuf,
     filter: ExportFilter,
+    curation: Curation,
+    redactions: RedactRules,
+    vectors: Option<VectorSource>,
 }
 
 pub struct ExportStats {
     pub conversations_processed: usize,
     pub messages_processed: usize,
+    /// Conversations matching the filter but excluded by the curation
+    pub conversations_excluded: usize,
+    /// Messages excluded by the curation (including dropped tool output)
+    pub messages_excluded: usize,
+    /// Messages whose content was changed by a redaction rule
+    pub messages_redacted: usize,
+    /// Code snippets exported with the kept messages
+    pub snippets_processed: usize,
+    /// Messages exported with a vector for semantic search
+    pub vectors_exported: usize,
+}
+
+/// A conversation matching the export filter, for curation screens
+#[derive(Debug, Clone)]
+pub struct ConversationSummary {
+    /// Row id in the source database
+    pub id: i64,
+    /// Original (untransformed) source path, the curation key
+    pub source_path: String,
+    pub agent: String,
+    pub title: Option<String>,
+    pub started_at: Option<i64>,
+    pub message_count: i64,
+}
+
+/// A message of a listed conversation, for curation screens
+#[derive(Debug, Clone)]
+pub struct MessageSummary {
+    /// Position in the conversation, the curation key
+    pub idx: i64,
+    pub role: String,
+    pub content: String,
 }
 
 impl ExportEngine {
@@ -43,20 +88,82 @@ impl ExportEngine {
             source_db_path: source_db_path.to_path_buf(),
             output_path: output_path.to_path_buf(),
             filter,
+            curation: Curation::default(),
+            redactions: RedactRules::default(),
+            vectors: None,
         }
     }
 
-    pub fn execute<F>(&self, progress: F, running: Option<Arc<AtomicBool>>) -> Result<ExportStats>
-    where
-        F: Fn(usize, usize),
-    {
-        // 1. Open source DB
+    /// Leave out the conversations and messages excluded by `curation`
+    pub fn with_curation(mut self, curation: Curation) -> Self {
+        self.curation = curation;
+        self
+    }
+
+    /// Apply redaction rules to every text field of the export
+    pub fn with_redactions(mut self, redactions: RedactRules) -> Self {
+        self.redactions = redactions;
+        self
+    }
+
+    /// Export a quantized vector per message for semantic search in the viewer
+    pub fn with_vectors(mut self, vectors: VectorSource) -> Self {
+        self.vectors = Some(vectors);
+        self
+    }
+
+    /// List conversations matching the filter (ignoring the curation)
+    pub fn list_conversations(&self) -> Result<Vec<ConversationSummary>> {
+        let src = self.open_source()?;
+        let (mut query, params) = self.filter_query();
+        query.push_str(" ORDER BY started_at DESC, id DESC");
+
+        let mut stmt = src.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            Ok(ConversationSummary {
+                id: row.get(0)?,
+                agent: row.get(1)?,
+                title: row.get(3)?,
+                source_path: row.get(4)?,
+                started_at: row.get(5)?,
+                message_count: row.get::<_, Option<i64>>(7)?.unwrap_or(0),
+            })
+        })?;
+        rows.collect::<rusqlite::Result<_>>().map_err(Into::into)
+    }
+
+    /// List the messages of a conversation returned by [`Self::list_conversations`]
+    pub fn list_messages(&self, conversation_id: i64) -> Result<Vec<MessageSummary>> {
+        let src = self.open_source()?;
+        let mut stmt = src.prepare(
+            "SELECT idx, role, content FROM messages WHERE conversation_id = ? ORDER BY idx ASC",
+        )?;
+        let rows = stmt.query_map(params![conversation_id], |row| {
+            Ok(MessageSummary {
+                idx: row.get(0)?,
+                role: row.get(1)?,
+                content: row.get(2)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<_>>().map_err(Into::into)
+    }
+
+    fn open_source(&self) -> Result<Connection> {
         let src = Connection::open_with_flags(
             &self.source_db_path,
             rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
         )
         .context("Failed to open source database")?;
         src.busy_timeout(Duration::from_secs(5))?;
+        Ok(src)
+    }
+
+    pub fn execute<F>(&self, progress: F, running: Option<Arc<AtomicBool>>) -> Result<ExportStats>
+    where
+        F: Fn(usize, usize),
+    {
+        // 1. Open source DB
+        let src = self.open_source()?;
 
         // 2. Prepare output DB
         if self.output_path.exists() {
@@ -65,6 +172,7 @@ impl ExportEngine {
         }
         let mut dest =
             Connection::open(&self.output_path).context("Failed to create output database")?;
+        dest.pragma_update(None, "page_size", EXPORT_PAGE_SIZE)?;
 
         // Enable FTS5
         // Note: rusqlite bundled feature should handle this, but we need to ensure extension loading if not.
@@ -104,6 +212,45 @@ impl ExportEngine {
         )
         .context("Failed to create messages table")?;
 
+        tx.execute(
+            "CREATE TABLE snippets (
+                id INTEGER PRIMARY KEY,
+                message_id INTEGER NOT NULL,
+                file_path TEXT,
+                language TEXT,
+                start_line INTEGER,
+                end_line INTEGER,
+                snippet_text TEXT,
+                FOREIGN KEY (message_id) REFERENCES messages(id)
+            )",
+            [],
+        )
+        .context("Failed to create snippets table")?;
+
+        tx.execute(
+            "CREATE INDEX idx_snippets_message ON snippets(message_id)",
+            [],
+        )?;
+
+        // Code search covers a message together with its snippets (path and text)
+        tx.execute(
+            "CREATE VIEW message_code_text AS
+             SELECT m.id AS id,
+                    m.content || COALESCE((
+                        SELECT group_concat(part, '')
+                        FROM (
+                            SELECT char(10) || COALESCE(s.file_path, '') || char(10)
+                                   || COALESCE(s.snippet_text, '') AS part
+                            FROM snippets s
+                            WHERE s.message_id = m.id
+                            ORDER BY s.id
+                        )
+                    ), '') AS content
+             FROM messages m",
+            [],
+        )
+        .context("Failed to create message_code_text view")?;
+
         tx.execute(
             "CREATE TABLE export_meta (
                 key TEXT PRIMARY KEY,
@@ -127,7 +274,7 @@ impl ExportEngine {
         tx.execute(
             r#"CREATE VIRTUAL TABLE messages_code_fts USING fts5(
                 content,
-                content='messages',
+                content='message_code_text',
                 content_rowid='id',
                 tokenize="unicode61 tokenchars '_./'"
             )"#,
@@ -135,56 +282,21 @@ impl ExportEngine {
         )
         .context("Failed to create messages_code_fts table")?;
 
-        // 4. Query Source
-        let mut query = String::from(
-            "SELECT id, agent, workspace, title, source_path, started_at, ended_at, message_count, metadata_json 
-             FROM conversations WHERE 1=1"
-        );
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        if let Some(agents) = &self.filter.agents {
-            if agents.is_empty() {
-                query.push_str(" AND 1=0");
-            } else {
-                query.push_str(" AND agent IN (");
-                for (i, agent) in agents.iter().enumerate() {
-                    if i > 0 {
-                        query.push_str(", ");
-                    }
-                    query.push('?');
-                    params.push(Box::new(agent.clone()));
-                }
-                query.push(')');
-            }
+        if self.vectors.is_some() {
+            tx.execute(
+                "CREATE TABLE message_vectors (
+                    message_id INTEGER PRIMARY KEY,
+                    scale REAL NOT NULL,
+                    vector BLOB NOT NULL,
+                    FOREIGN KEY (message_id) REFERENCES messages(id)
+                )",
+                [],
+            )
+            .context("Failed to create message_vectors table")?;
         }
 
-        // Note: Workspace filtering in source DB might be string matching if paths aren't normalized consistently.
-        // Assuming strict matching for now.
-        if let Some(workspaces) = &self.filter.workspaces {
-            if workspaces.is_empty() {
-                query.push_str(" AND 1=0");
-            } else {
-                query.push_str(" AND workspace IN (");
-                for (i, ws) in workspaces.iter().enumerate() {
-                    if i > 0 {
-                        query.push_str(", ");
-                    }
-                    query.push('?');
-                    params.push(Box::new(ws.to_string_lossy().to_string()));
-                }
-                query.push(')');
-            }
-        }
-
-        if let Some(since) = self.filter.since {
-            query.push_str(" AND started_at >= ?");
-            params.push(Box::new(since.timestamp_millis()));
-        }
-
-        if let Some(until) = self.filter.until {
-            query.push_str(" AND started_at <= ?");
-            params.push(Box::new(until.timestamp_millis()));
-        }
+        // 4. Query Source
+        let (mut query, params) = self.filter_query();
 
         // Count total for progress
         let count_query = format!("SELECT COUNT(*) FROM ({})", query);
@@ -194,20 +306,42 @@ impl ExportEngine {
             |row| row.get(0),
         )?;
 
+        // Stable row order keeps the output byte-identical across runs over the
+        // same data, which is what lets re-exports reuse encrypted chunks
+        query.push_str(" ORDER BY id ASC");
+
         // Execute Main Query
         let mut stmt = src.prepare(&query)?;
         let mut rows = stmt.query(rusqlite::params_from_iter(params.iter()))?;
 
         let mut processed = 0;
+        let mut seen = 0;
         let mut msg_processed = 0;
+        let mut convs_excluded = 0;
+        let mut msgs_excluded = 0;
+        let mut msgs_redacted = 0;
+        let mut snippets_processed = 0;
+        let mut vectors_exported = 0;
 
         let mut msg_stmt = src.prepare(
-            "SELECT role, content, created_at, idx 
+            "SELECT role, content, created_at, idx, id 
              FROM messages 
              WHERE conversation_id = ? 
              ORDER BY idx ASC",
         )?;
 
+        // Databases from before snippets were stored have no table for them
+        let mut snippet_stmt = if table_exists(&src, "snippets")? {
+            Some(src.prepare(
+                "SELECT file_path, language, start_line, end_line, snippet_text
+                 FROM snippets
+                 WHERE message_id = ?
+                 ORDER BY id ASC",
+            )?)
+        } else {
+            None
+        };
+
         let mut insert_conv = tx.prepare(
             "INSERT INTO conversations (id, agent, workspace, title, source_path, started_at, ended_at, message_count, metadata_json)
              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
@@ -221,8 +355,17 @@ impl ExportEngine {
         let mut insert_fts =
             tx.prepare("INSERT INTO messages_fts (rowid, content) VALUES (?, ?)")?;
 
-        let mut insert_code_fts =
-            tx.prepare("INSERT INTO messages_code_fts (rowid, content) VALUES (?, ?)")?;
+        let mut insert_vector = match self.vectors {
+            Some(_) => Some(tx.prepare(
+                "INSERT INTO message_vectors (message_id, scale, vector) VALUES (?, ?, ?)",
+            )?),
+            None => None,
+        };
+
+        let mut insert_snippet = tx.prepare(
+            "INSERT INTO snippets (message_id, file_path, language, start_line, end_line, snippet_text)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )?;
 
         while let Some(row) = rows.next()? {
             if let Some(r) = &running
@@ -241,13 +384,25 @@ impl ExportEngine {
             let message_count: i64 = row.get(7)?;
             let metadata_json: Option<String> = row.get(8)?;
 
+            seen += 1;
+            if self.curation.excludes_conversation(&source_path) {
+                convs_excluded += 1;
+                progress(seen, total_convs);
+                continue;
+            }
+
             // Transform Path
-            let transformed_path = self.transform_path(&source_path, &workspace);
+            let transformed_path = self.export_path(&source_path, &workspace);
+            let title = title.map(|t| self.redactions.apply(&t).into_owned());
+            let exported_workspace = workspace
+                .as_deref()
+                .map(|w| self.redactions.apply(w).into_owned());
+            let metadata_json = metadata_json.map(|m| self.redactions.apply_json(&m).into_owned());
 
             insert_conv.execute(params![
                 id,
                 agent,
-                workspace,
+                exported_workspace,
                 title,
                 transformed_path,
                 started_at,
@@ -257,26 +412,79 @@ impl ExportEngine {
             ])?;
 
             // Fetch messages
+            let mut dropped = 0;
+            let mut kept = 0;
             let mut msg_rows = msg_stmt.query(params![id])?;
             while let Some(msg_row) = msg_rows.next()? {
                 let role: String = msg_row.get(0)?;
                 let content: String = msg_row.get(1)?;
                 let created_at: Option<i64> = msg_row.get(2)?;
                 let idx: i64 = msg_row.get(3)?;
+                let source_msg_id: i64 = msg_row.get(4)?;
+
+                if self.curation.excludes_message(&source_path, idx, &role) {
+                    dropped += 1;
+                    continue;
+                }
+                let content = self.redactions.apply(&content);
+                let redacted = matches!(content, std::borrow::Cow::Owned(_));
+                if redacted {
+                    msgs_redacted += 1;
+                }
 
                 let msg_id = insert_msg.insert(params![id, idx, role, content, created_at])?;
 
-                // Populate FTS
+                // Populate FTS (the code index is rebuilt once snippets are in)
                 insert_fts.execute(params![msg_id, content])?;
-                insert_code_fts.execute(params![msg_id, content])?;
+
+                if let (Some(vectors), Some(insert_vector)) = (&self.vectors, &mut insert_vector)
+                    && let Some(vector) = vectors.vector_for(source_msg_id, &content, redacted)?
+                    && let Some((scale, bytes)) = quantize_i8(&vector)
+                {
+                    insert_vector.execute(params![msg_id, scale, bytes])?;
+                    vectors_exported += 1;
+                }
+
+                if let Some(snippet_stmt) = snippet_stmt.as_mut() {
+                    let mut snippet_rows = snippet_stmt.query(params![source_msg_id])?;
+                    while let Some(snippet) = snippet_rows.next()? {
+                        let file_path: Option<String> = snippet.get(0)?;
+                        let snippet_text: Option<String> = snippet.get(4)?;
+                        insert_snippet.execute(params![
+                            msg_id,
+                            file_path.map(|p| self.export_path(&p, &workspace)),
+                            snippet.get::<_, Option<String>>(1)?,
+                            snippet.get::<_, Option<i64>>(2)?,
+                            snippet.get::<_, Option<i64>>(3)?,
+                            snippet_text.map(|t| self.redactions.apply(&t).into_owned()),
+                        ])?;
+                        snippets_processed += 1;
+                    }
+                }
 
                 msg_processed += 1;
+                kept += 1;
+            }
+
+            // Curated conversations report what is actually in the archive
+            if dropped > 0 {
+                msgs_excluded += dropped;
+                tx.execute(
+                    "UPDATE conversations SET message_count = ? WHERE id = ?",
+                    params![kept, id],
+                )?;
             }
 
             processed += 1;
-            progress(processed, total_convs);
+            progress(seen, total_convs);
         }
 
+        tx.execute(
+            "INSERT INTO messages_code_fts (messages_code_fts) VALUES ('rebuild')",
+            [],
+        )
+        .context("Failed to build messages_code_fts")?;
+
         // Metadata
         tx.execute(
             "INSERT INTO export_meta (key, value) VALUES ('schema_version', '1')",
@@ -286,11 +494,19 @@ impl ExportEngine {
             "INSERT INTO export_meta (key, value) VALUES ('exported_at', ?)",
             params![Utc::now().to_rfc3339()],
         )?;
+        if let Some(vectors) = &self.vectors {
+            let mut insert_meta =
+                tx.prepare("INSERT INTO export_meta (key, value) VALUES (?, ?)")?;
+            insert_meta.execute(params!["vector_embedder", vectors.embedder_id()])?;
+            insert_meta.execute(params!["vector_dimension", vectors.dimension().to_string()])?;
+            insert_meta.execute(params!["vector_quantization", VECTOR_QUANTIZATION])?;
+        }
 
         drop(insert_conv);
         drop(insert_msg);
         drop(insert_fts);
-        drop(insert_code_fts);
+        drop(insert_snippet);
+        drop(insert_vector);
         // drop(msg_stmt); // Removed: Let Rust handle drop order
         // drop(stmt);     // Removed: Let Rust handle drop order
 
@@ -299,9 +515,76 @@ impl ExportEngine {
         Ok(ExportStats {
             conversations_processed: processed,
             messages_processed: msg_processed,
+            conversations_excluded: convs_excluded,
+            messages_excluded: msgs_excluded,
+            messages_redacted: msgs_redacted,
+            snippets_processed,
+            vectors_exported,
         })
     }
 
+    /// Conversation query and parameters for the export filter
+    fn filter_query(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut query = String::from(
+            "SELECT id, agent, workspace, title, source_path, started_at, ended_at, message_count, metadata_json 
+             FROM conversations WHERE 1=1"
+        );
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(agents) = &self.filter.agents {
+            if agents.is_empty() {
+                query.push_str(" AND 1=0");
+            } else {
+                query.push_str(" AND agent IN (");
+                for (i, agent) in agents.iter().enumerate() {
+                    if i > 0 {
+                        query.push_str(", ");
+                    }
+                    query.push('?');
+                    params.push(Box::new(agent.clone()));
+                }
+                query.push(')');
+            }
+        }
+
+        // Note: Workspace filtering in source DB might be string matching if paths aren't normalized consistently.
+        // Assuming strict matching for now.
+        if let Some(workspaces) = &self.filter.workspaces {
+            if workspaces.is_empty() {
+                query.push_str(" AND 1=0");
+            } else {
+                query.push_str(" AND workspace IN (");
+                for (i, ws) in workspaces.iter().enumerate() {
+                    if i > 0 {
+                        query.push_str(", ");
+                    }
+                    query.push('?');
+                    params.push(Box::new(ws.to_string_lossy().to_string()));
+                }
+                query.push(')');
+            }
+        }
+
+        if let Some(since) = self.filter.since {
+            query.push_str(" AND started_at >= ?");
+            params.push(Box::new(since.timestamp_millis()));
+        }
+
+        if let Some(until) = self.filter.until {
+            query.push_str(" AND started_at <= ?");
+            params.push(Box::new(until.timestamp_millis()));
+        }
+
+        (query, params)
+    }
+
+    /// A path as written to the export: transformed, then redacted
+    fn export_path(&self, path: &str, workspace: &Option<String>) -> String {
+        self.redactions
+            .apply(&self.transform_path(path, workspace))
+            .into_owned()
+    }
+
     fn transform_path(&self, path: &str, workspace: &Option<String>) -> String {
         match self.filter.path_mode {
             PathMode::Relative => {
@@ -326,6 +609,15 @@ impl ExportEngine {
     }
 }
 
+pub(crate) fn table_exists(conn: &Connection, name: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn run_pages_export(
     db_path: Option<PathBuf>,
@@ -335,6 +627,10 @@ pub fn run_pages_export(
     since: Option<String>,
     until: Option<String>,
     path_mode: PathMode,
+    curation: Option<PathBuf>,
+    redact_rules: Option<PathBuf>,
+    plaintext: Option<PlaintextMode>,
+    vectors: bool,
     dry_run: bool,
 ) -> Result<()> {
     if dry_run {
@@ -342,6 +638,18 @@ pub fn run_pages_export(
         return Ok(());
     }
 
+    // An unencrypted export writes a whole bundle; the database is an input to it
+    let (bundle_dir, output_path) = match plaintext {
+        Some(_) => {
+            std::fs::create_dir_all(&output_path)?;
+            (
+                Some(output_path.clone()),
+                output_path.join(".cass-export.db"),
+            )
+        }
+        None => (None, output_path),
+    };
+
     let db_path = db_path.unwrap_or_else(|| {
         directories::ProjectDirs::from("com", "dicklesworthstone", "coding-agent-search")
             .map(|dirs| dirs.data_dir().join("agent_search.db"))
@@ -365,7 +673,25 @@ pub fn run_pages_export(
         path_mode,
     };
 
-    let engine = ExportEngine::new(&db_path, &output_path, filter);
+    let mut engine = ExportEngine::new(&db_path, &output_path, filter);
+    let curation = curation.as_deref().map(Curation::load).transpose()?;
+    // An explicit rules file wins over the one saved with the curation
+    let redact_rules = redact_rules.or_else(|| curation.as_ref()?.redact_rules.clone());
+    if let Some(curation) = curation {
+        engine = engine.with_curation(curation);
+    }
+    if let Some(path) = &redact_rules {
+        engine = engine.with_redactions(RedactRules::load(path)?);
+    }
+    if vectors {
+        let data_dir = db_path.parent().unwrap_or(Path::new("."));
+        let source = VectorSource::for_viewer(data_dir)?;
+        println!(
+            "Including {} message vectors for semantic search.",
+            source.embedder_id()
+        );
+        engine = engine.with_vectors(source);
+    }
 
     println!("Exporting to {:?}...", output_path);
     let stats = engine.execute(
@@ -382,6 +708,34 @@ pub fn run_pages_export(
         "\rExport complete! Processed {} conversations, {} messages.",
         stats.conversations_processed, stats.messages_processed
     );
+    if stats.conversations_excluded > 0 || stats.messages_excluded > 0 {
+        println!(
+            "Curation excluded {} conversations, {} messages.",
+            stats.conversations_excluded, stats.messages_excluded
+        );
+    }
+    if stats.vectors_exported > 0 {
+        println!("Exported {} message vectors.", stats.vectors_exported);
+    }
+    if stats.snippets_processed > 0 {
+        println!("Included {} code snippets.", stats.snippets_processed);
+    }
+    if stats.messages_redacted > 0 {
+        println!("Redacted content in {} messages.", stats.messages_redacted);
+    }
+
+    if let (Some(bundle_dir), Some(mode)) = (bundle_dir, plaintext) {
+        let built = BundleBuilder::new()
+            .description("Archive of AI coding agent conversations")
+            .build_unencrypted(&output_path, &bundle_dir, mode, |_, _| {});
+        std::fs::remove_file(&output_path).ok();
+        let result = built?;
+        println!(
+            "Unencrypted bundle written to {} ({} files). Anyone who can reach it can read it.",
+            result.site_dir.display(),
+            result.total_files
+        );
+    }
 
     Ok(())
 }
@@ -0,0 +1,520 @@
+//! Import of published archives into the local index.
+//!
+//! `cass pages import` turns a bundle (a deployed URL or a local directory)
+//! back into searchable conversations: it fetches the files the database is
+//! made of, checks each against integrity.json, unlocks and decrypts the
+//! payload, and ingests the exported conversations under their own source id.
+//! Conversations carry the archive as their origin, so `--source <id>` (or
+//! `--source remote`) filters work on them like on an SSH source.
+//!
+//! Re-importing the same archive only adds messages that are new since the
+//! last import.
+
+use anyhow::{Context, Result, bail};
+use reqwest::Url;
+use reqwest::blocking::Client;
+use rusqlite::{Connection, OpenFlags, params};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use super::bundle::{IntegrityManifest, PlaintextConfig, hash_file};
+use super::encrypt::EncryptionConfig;
+use super::export::table_exists;
+use super::keys::{Unlock, resolve_site_dir};
+use crate::connectors::{NormalizedConversation, NormalizedMessage, NormalizedSnippet};
+use crate::indexer::persist::persist_conversations_batched;
+use crate::search::tantivy::{TantivyIndex, index_dir};
+use crate::sources::provenance::{LOCAL_SOURCE_ID, Source, SourceKind};
+use crate::storage::sqlite::SqliteStorage;
+
+/// Request timeout for a single bundle file
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Where a published archive lives
+#[derive(Debug, Clone)]
+pub enum ArchiveLocation {
+    /// Deployed site (the URL the viewer is served from)
+    Url(Url),
+    /// Bundle directory (containing site/) or site directory
+    Dir(PathBuf),
+}
+
+impl ArchiveLocation {
+    /// Parse an http(s) URL or a local path
+    pub fn parse(input: &str) -> Result<Self> {
+        if input.starts_with("http://") || input.starts_with("https://") {
+            let mut url = Url::parse(input).with_context(|| format!("Invalid URL {input}"))?;
+            // The viewer URL may point at index.html; files sit next to it
+            if url.path().ends_with("/index.html") {
+                let path = url.path().trim_end_matches("index.html").to_string();
+                url.set_path(&path);
+            }
+            if !url.path().ends_with('/') {
+                let path = format!("{}/", url.path());
+                url.set_path(&path);
+            }
+            url.set_query(None);
+            url.set_fragment(None);
+            return Ok(Self::Url(url));
+        }
+        Ok(Self::Dir(resolve_site_dir(Path::new(input))?))
+    }
+
+    /// Human-readable location, recorded as the source's host label
+    pub fn label(&self) -> String {
+        match self {
+            Self::Url(url) => url.to_string(),
+            Self::Dir(dir) => dir.display().to_string(),
+        }
+    }
+
+    /// Source id derived from the location: the URL's host and path, or
+    /// the bundle directory's name
+    pub fn default_source_id(&self) -> String {
+        let raw = match self {
+            Self::Url(url) => {
+                let mut parts = vec![url.host_str().unwrap_or("archive").to_string()];
+                parts.extend(
+                    url.path_segments()
+                        .into_iter()
+                        .flatten()
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string),
+                );
+                parts.join("-")
+            }
+            Self::Dir(site_dir) => {
+                let bundle = match site_dir.file_name() {
+                    Some(name) if name == "site" => site_dir.parent().unwrap_or(site_dir),
+                    _ => site_dir,
+                };
+                bundle
+                    .canonicalize()
+                    .unwrap_or_else(|_| bundle.to_path_buf())
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "archive".to_string())
+            }
+        };
+        let id: String = raw
+            .to_lowercase()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let id = id.trim_matches('-');
+        if id.is_empty() {
+            "archive".to_string()
+        } else {
+            id.to_string()
+        }
+    }
+}
+
+/// config.json of either kind of bundle
+#[derive(Debug, Clone)]
+enum ArchiveConfig {
+    Encrypted(EncryptionConfig),
+    Plaintext(PlaintextConfig),
+}
+
+/// A bundle whose database files are on disk and match integrity.json
+#[derive(Debug)]
+pub struct FetchedArchive {
+    pub location: ArchiveLocation,
+    site_dir: PathBuf,
+    config: ArchiveConfig,
+    files: Vec<String>,
+}
+
+impl FetchedArchive {
+    /// Fetch integrity.json, config.json and the database files
+    ///
+    /// A URL is downloaded into `staging`, which must be an empty directory
+    /// owned by the caller; a directory is read in place.
+    /// Only the files the database is made of are checked against the
+    /// manifest, the viewer assets are not needed for an import.
+    pub fn fetch(location: ArchiveLocation, staging: &Path) -> Result<Self> {
+        let site_dir = match &location {
+            ArchiveLocation::Dir(dir) => dir.clone(),
+            ArchiveLocation::Url(url) => {
+                let client = Client::builder()
+                    .timeout(REQUEST_TIMEOUT)
+                    .user_agent(concat!("cass/", env!("CARGO_PKG_VERSION")))
+                    .build()?;
+                download(&client, url, "integrity.json", staging)?;
+                download(&client, url, "config.json", staging)?;
+                for rel in database_files(&load_archive_config(staging)?)? {
+                    download(&client, url, &rel, staging)?;
+                }
+                staging.to_path_buf()
+            }
+        };
+
+        let manifest = IntegrityManifest::load(&site_dir)
+            .context("The archive has no readable integrity.json")?;
+        let config = load_archive_config(&site_dir)?;
+        let files = database_files(&config)?;
+        for rel in std::iter::once("config.json").chain(files.iter().map(String::as_str)) {
+            let Some(entry) = manifest.files.get(rel) else {
+                bail!("{rel} is not covered by integrity.json");
+            };
+            let path = site_dir.join(rel);
+            let size = fs::metadata(&path)
+                .with_context(|| format!("Missing archive file {rel}"))?
+                .len();
+            if size != entry.size || hash_file(&path)? != entry.sha256 {
+                bail!("{rel} does not match integrity.json");
+            }
+        }
+
+        Ok(Self {
+            location,
+            site_dir,
+            config,
+            files,
+        })
+    }
+
+    /// Whether unlocking needs a password, recovery secret or identity
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self.config, ArchiveConfig::Encrypted(_))
+    }
+
+    /// Number of verified payload files
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Write the archive database to `dest`
+    pub fn extract_database(&self, unlock: Option<&Unlock>, dest: &Path) -> Result<()> {
+        match &self.config {
+            ArchiveConfig::Encrypted(config) => {
+                let Some(unlock) = unlock else {
+                    bail!(
+                        "The archive is encrypted; a password, recovery secret or identity is needed"
+                    );
+                };
+                unlock.open(config.clone())?.decrypt_to_file(
+                    self.site_dir.as_path(),
+                    dest,
+                    |_, _| {},
+                )
+            }
+            ArchiveConfig::Plaintext(_) => {
+                fs::copy(self.site_dir.join(&self.files[0]), dest)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Check a source id by the rules sources.toml applies to source names
+pub fn validate_source_id(id: &str) -> Result<()> {
+    if id.is_empty() {
+        bail!("Source name cannot be empty");
+    }
+    if id.contains('/') || id.contains('\\') {
+        bail!("Source name cannot contain path separators");
+    }
+    let mut components = Path::new(id).components();
+    if !matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) {
+        bail!("Invalid source name '{id}'");
+    }
+    Ok(())
+}
+
+fn load_archive_config(site_dir: &Path) -> Result<ArchiveConfig> {
+    let text =
+        fs::read_to_string(site_dir.join("config.json")).context("Failed to read config.json")?;
+    let value: serde_json::Value = serde_json::from_str(&text)?;
+    if value.get("encrypted") == Some(&serde_json::Value::Bool(false)) {
+        Ok(ArchiveConfig::Plaintext(serde_json::from_value(value)?))
+    } else {
+        Ok(ArchiveConfig::Encrypted(serde_json::from_value(value)?))
+    }
+}
+
+/// Site-relative files the archive database is made of
+fn database_files(config: &ArchiveConfig) -> Result<Vec<String>> {
+    let files = match config {
+        ArchiveConfig::Encrypted(config) => config.payload.files.clone(),
+        ArchiveConfig::Plaintext(config) => match &config.database {
+            Some(database) => vec![database.file.clone()],
+            None => bail!("Static HTML archives have no database to import"),
+        },
+    };
+    // config.json is untrusted input; keep every path inside the site
+    for rel in &files {
+        if !Path::new(rel)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            bail!("Invalid payload path {rel} in config.json");
+        }
+    }
+    Ok(files)
+}
+
+fn download(client: &Client, base: &Url, rel: &str, staging: &Path) -> Result<()> {
+    let url = base.join(rel)?;
+    let response = client
+        .get(url.clone())
+        .send()
+        .with_context(|| format!("Failed to fetch {url}"))?;
+    if !response.status().is_success() {
+        bail!("Failed to fetch {url}: HTTP {}", response.status());
+    }
+    let bytes = response.bytes()?;
+    let dest = staging.join(rel);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&dest, &bytes).with_context(|| format!("Failed to write {}", dest.display()))?;
+    Ok(())
+}
+
+/// Outcome of an archive import
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub source_id: String,
+    pub location: String,
+    pub encrypted: bool,
+    /// Conversations in the archive
+    pub conversations: usize,
+    /// Messages added to the local index by this import
+    pub new_messages: usize,
+}
+
+/// Ingest a fetched archive into the database and search index
+///
+/// The decrypted database only exists under `staging` while it is read.
+pub fn import_archive(
+    archive: &FetchedArchive,
+    unlock: Option<&Unlock>,
+    source_id: &str,
+    db_path: &Path,
+    data_dir: &Path,
+    staging: &Path,
+) -> Result<ImportSummary> {
+    validate_source_id(source_id)?;
+    if source_id == LOCAL_SOURCE_ID {
+        bail!("'{LOCAL_SOURCE_ID}' is reserved for this machine's sessions");
+    }
+
+    fs::create_dir_all(staging)?;
+    let database = staging.join("archive.db");
+    archive.extract_database(unlock, &database)?;
+    let read = read_conversations(&database, source_id, &archive.location.label());
+    let _ = fs::remove_file(&database);
+    let conversations = read?;
+
+    let mut storage = SqliteStorage::open(db_path)?;
+    let existing = storage.get_source(source_id)?;
+    if existing
+        .as_ref()
+        .is_some_and(|s| s.kind != SourceKind::Archive)
+    {
+        bail!("Source '{source_id}' already exists and is not an imported archive");
+    }
+    let mut source =
+        existing.unwrap_or_else(|| Source::remote(source_id, archive.location.label()));
+    source.kind = SourceKind::Archive;
+    source.host_label = Some(archive.location.label());
+    source.config_json = Some(serde_json::json!({
+        "location": archive.location.label(),
+        "encrypted": archive.is_encrypted(),
+    }));
+    storage.upsert_source(&source)?;
+
+    let before = count_source_messages(&storage, source_id)?;
+    let mut t_index = TantivyIndex::open_or_create(&index_dir(data_dir)?)?;
+    persist_conversations_batched(&mut storage, &mut t_index, &conversations, false)?;
+    t_index.commit()?;
+    let after = count_source_messages(&storage, source_id)?;
+
+    Ok(ImportSummary {
+        source_id: source_id.to_string(),
+        location: archive.location.label(),
+        encrypted: archive.is_encrypted(),
+        conversations: conversations.len(),
+        new_messages: (after - before) as usize,
+    })
+}
+
+fn count_source_messages(storage: &SqliteStorage, source_id: &str) -> Result<i64> {
+    Ok(storage.raw().query_row(
+        "SELECT COUNT(*) FROM messages m
+         JOIN conversations c ON m.conversation_id = c.id
+         WHERE c.source_id = ?",
+        params![source_id],
+        |row| row.get(0),
+    )?)
+}
+
+/// Read an exported database as normalized conversations from `source_id`
+fn read_conversations(
+    database: &Path,
+    source_id: &str,
+    host: &str,
+) -> Result<Vec<NormalizedConversation>> {
+    let conn = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .context("Failed to open the archive database")?;
+
+    let mut snippets: HashMap<i64, Vec<NormalizedSnippet>> = HashMap::new();
+    if table_exists(&conn, "snippets")? {
+        let mut stmt = conn.prepare(
+            "SELECT message_id, file_path, start_line, end_line, language, snippet_text
+             FROM snippets ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                NormalizedSnippet {
+                    file_path: row.get::<_, Option<String>>(1)?.map(PathBuf::from),
+                    start_line: row.get(2)?,
+                    end_line: row.get(3)?,
+                    language: row.get(4)?,
+                    snippet_text: row.get(5)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (message_id, snippet) = row?;
+            snippets.entry(message_id).or_default().push(snippet);
+        }
+    }
+
+    let mut messages: HashMap<i64, Vec<NormalizedMessage>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT id, conversation_id, idx, role, content, created_at
+         FROM messages ORDER BY conversation_id, idx",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, conversation_id, idx, role, content, created_at) = row?;
+        messages
+            .entry(conversation_id)
+            .or_default()
+            .push(NormalizedMessage {
+                idx,
+                role,
+                author: None,
+                created_at,
+                content,
+                extra: serde_json::json!({}),
+                snippets: snippets.remove(&id).unwrap_or_default(),
+            });
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, agent, workspace, title, source_path, started_at, ended_at, metadata_json
+         FROM conversations ORDER BY id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+            row.get::<_, Option<i64>>(6)?,
+            row.get::<_, Option<String>>(7)?,
+        ))
+    })?;
+
+    let mut conversations = Vec::new();
+    for row in rows {
+        let (id, agent, workspace, title, source_path, started_at, ended_at, metadata_json) = row?;
+        let mut metadata = metadata_json
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .filter(serde_json::Value::is_object)
+            .unwrap_or_else(|| serde_json::json!({}));
+        // The archive is the origin here, whatever the exporting machine recorded
+        metadata["cass"] = serde_json::json!({
+            "origin": {
+                "source_id": source_id,
+                "kind": SourceKind::Archive.as_str(),
+                "host": host,
+            }
+        });
+        conversations.push(NormalizedConversation {
+            agent_slug: agent,
+            // Export ids change between exports; path and start time do not
+            external_id: Some(format!("{source_path}@{}", started_at.unwrap_or_default())),
+            title,
+            workspace: workspace.map(PathBuf::from),
+            source_path: PathBuf::from(source_path),
+            started_at,
+            ended_at,
+            metadata,
+            messages: messages.remove(&id).unwrap_or_default(),
+        });
+    }
+    Ok(conversations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_locations_point_at_the_site_root() {
+        let location =
+            ArchiveLocation::parse("https://alice.github.io/team/index.html?x#y").unwrap();
+        assert_eq!(location.label(), "https://alice.github.io/team/");
+        assert_eq!(location.default_source_id(), "alice.github.io-team");
+
+        let location = ArchiveLocation::parse("http://127.0.0.1:8080").unwrap();
+        assert_eq!(location.label(), "http://127.0.0.1:8080/");
+        assert_eq!(location.default_source_id(), "127.0.0.1");
+    }
+
+    #[test]
+    fn payload_paths_must_stay_inside_the_site() {
+        let config = |file: &str| {
+            ArchiveConfig::Plaintext(PlaintextConfig {
+                encrypted: false,
+                mode: super::super::bundle::PlaintextMode::Viewer,
+                database: Some(super::super::bundle::PlaintextDatabase {
+                    file: file.to_string(),
+                    size: 0,
+                    sha256: String::new(),
+                }),
+            })
+        };
+        assert!(database_files(&config("payload/archive.db")).is_ok());
+        assert!(database_files(&config("../secrets.db")).is_err());
+        assert!(database_files(&config("/etc/passwd")).is_err());
+    }
+
+    #[test]
+    fn source_ids_are_single_path_components() {
+        assert!(validate_source_id("teammate").is_ok());
+        assert!(validate_source_id("alice.github.io-team").is_ok());
+        for bad in ["", ".", "..", "a/b", "a\\b", "/etc"] {
+            assert!(validate_source_id(bad).is_err(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Key slot management for published archives.
+//!
+//! Edits the key slots in a bundle's config.json after deployment: list them,
+//! add a password, recovery or recipient slot, revoke one, or rotate the DEK. Adding and
+//! revoking only rewrite config.json (every slot wraps the same DEK), so a
+//! revoked password that was already used to unlock the archive still knows
+//! the key; rotation re-encrypts the payload under a fresh DEK for that case.
+//! Rotation keeps the slot used to unlock and every recipient slot; other
+//! password and recovery slots are only dropped once the caller confirms.
+
+use anyhow::{Context, Result, bail};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use rand::{RngCore, rngs::OsRng};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::bundle::refresh_integrity;
+use super::encrypt::{
+    DecryptionEngine, EncryptionConfig, EncryptionEngine, KdfAlgorithm, SlotType, load_config,
+    write_config,
+};
+use super::recipient::Identity;
+
+/// Credential used to unlock an archive before editing it
+pub enum Unlock {
+    Password(String),
+    Recovery(Vec<u8>),
+    Identity(Identity),
+}
+
+impl Unlock {
+    pub(crate) fn open(&self, config: EncryptionConfig) -> Result<DecryptionEngine> {
+        match self {
+            Unlock::Password(password) => DecryptionEngine::unlock_with_password(config, password),
+            Unlock::Recovery(secret) => DecryptionEngine::unlock_with_recovery(config, secret),
+            Unlock::Identity(identity) => DecryptionEngine::unlock_with_identity(config, identity),
+        }
+    }
+}
+
+/// A slot to add to an archive
+pub enum NewSlot<'a> {
+    Password(&'a str),
+    Recovery(&'a [u8]),
+    /// age1… X25519 public key
+    Recipient(&'a str),
+}
+
+/// Public description of a key slot
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotSummary {
+    pub id: u8,
+    pub slot_type: SlotType,
+    pub kdf: KdfAlgorithm,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+}
+
+/// Result of a DEK rotation
+#[derive(Debug, Clone, Serialize)]
+pub struct RotateOutcome {
+    /// Slots of the re-encrypted archive
+    pub slots: Vec<SlotSummary>,
+    /// Password and recovery slots of the old archive that no longer exist
+    pub dropped: Vec<SlotSummary>,
+    /// Number of payload chunks written
+    pub chunks: usize,
+    /// New integrity fingerprint
+    pub fingerprint: String,
+}
+
+/// Find the directory holding config.json: the bundle's site/ or the dir itself
+pub fn resolve_site_dir(dir: &Path) -> Result<PathBuf> {
+    let site_dir = dir.join("site");
+    if site_dir.join("config.json").is_file() {
+        return Ok(site_dir);
+    }
+    if dir.join("config.json").is_file() {
+        return Ok(dir.to_path_buf());
+    }
+    bail!(
+        "No config.json in {} or {}",
+        dir.display(),
+        site_dir.display()
+    )
+}
+
+/// List key slots of an archive (no unlock needed, slots are public)
+pub fn list_slots(site_dir: &Path) -> Result<Vec<SlotSummary>> {
+    Ok(summarize(&load_config(site_dir)?))
+}
+
+/// Add a key slot wrapping the archive's existing DEK
+pub fn add_slot(site_dir: &Path, unlock: &Unlock, slot: NewSlot<'_>) -> Result<u8> {
+    let mut config = load_config(site_dir)?;
+    let unlocked = unlock.open(config.clone())?;
+    let mut engine = EncryptionEngine::from_unlocked(&unlocked)?;
+
+    let slot_id = match slot {
+        NewSlot::Password(password) => engine.add_password_slot(password)?,
+        NewSlot::Recovery(secret) => engine.add_recovery_slot(secret)?,
+        NewSlot::Recipient(recipient) => engine.add_recipient_slot(recipient)?,
+    };
+
+    config.key_slots = engine.key_slots().to_vec();
+    write_config(site_dir, &config)?;
+    refresh_integrity(site_dir)?;
+    Ok(slot_id)
+}
+
+/// Remove a key slot; the last remaining slot cannot be revoked
+pub fn revoke_slot(site_dir: &Path, unlock: &Unlock, slot_id: u8) -> Result<()> {
+    let mut config = load_config(site_dir)?;
+    let unlocked = unlock.open(config.clone())?;
+    let mut engine = EncryptionEngine::from_unlocked(&unlocked)?;
+
+    if !engine.remove_slot(slot_id) {
+        bail!("No key slot with id {}", slot_id);
+    }
+    if engine.key_slots().is_empty() {
+        bail!("Refusing to revoke the last key slot; the archive would become unreadable");
+    }
+
+    config.key_slots = engine.key_slots().to_vec();
+    write_config(site_dir, &config)?;
+    refresh_integrity(site_dir)?;
+    Ok(())
+}
+
+/// Re-encrypt the payload under a new DEK
+///
+/// Recipient slots only need the public key and are re-wrapped to the same
+/// recipients. The slot used to unlock is carried over too: its password (or
+/// the new password) and its recovery secret are known here. Other password
+/// and recovery slots cannot be re-wrapped without their secrets; they are
+/// passed to `confirm_drop` first, and nothing changes unless it agrees. An
+/// optional new recovery slot is added on top.
+pub fn rotate(
+    site_dir: &Path,
+    unlock: &Unlock,
+    new_password: Option<&str>,
+    recovery_secret: Option<&[u8]>,
+    confirm_drop: impl FnOnce(&[SlotSummary]) -> bool,
+) -> Result<RotateOutcome> {
+    let config = load_config(site_dir)?;
+    let password = match (new_password, unlock) {
+        (Some(password), _) => password,
+        (None, Unlock::Password(password)) => password.as_str(),
+        (None, Unlock::Recovery(_) | Unlock::Identity(_)) => {
+            bail!("Rotating without a password needs a new password for the new key")
+        }
+    };
+    let unlocked = unlock.open(config.clone())?;
+
+    let dropped: Vec<SlotSummary> = summarize(&config)
+        .into_iter()
+        .filter(|slot| slot.slot_type != SlotType::Recipient && slot.id != unlocked.slot_id())
+        .collect();
+    if !dropped.is_empty() && !confirm_drop(&dropped) {
+        let ids: Vec<String> = dropped.iter().map(|slot| slot.id.to_string()).collect();
+        bail!(
+            "Rotation would remove key slot(s) {}; nothing was changed",
+            ids.join(", ")
+        );
+    }
+
+    // The decrypted database only lives in a private temporary directory
+    // (owner-only permissions) and is deleted on drop, whatever the outcome
+    let scratch = tempfile::Builder::new().prefix("cass-rotate-").tempdir()?;
+    let plaintext = tempfile::Builder::new()
+        .prefix("export-")
+        .suffix(".db")
+        .tempfile_in(scratch.path())?;
+    let plaintext_path = plaintext.path().to_path_buf();
+    unlocked.decrypt_to_file(site_dir, &plaintext_path, |_, _| {})?;
+
+    let mut engine = EncryptionEngine::new(config.payload.chunk_size);
+    engine.add_password_slot(password)?;
+    if let Unlock::Recovery(secret) = unlock {
+        engine.add_recovery_slot(secret)?;
+    }
+    if let Some(secret) = recovery_secret {
+        engine.add_recovery_slot(secret)?;
+    }
+    for recipient in config
+        .key_slots
+        .iter()
+        .filter_map(|s| s.recipient.as_deref())
+    {
+        engine.add_recipient_slot(recipient)?;
+    }
+    let site = site_dir.to_path_buf();
+    let (new_config, _) = engine.encrypt_file_incremental(&plaintext_path, &site, |_, _| {})?;
+    let fingerprint = refresh_integrity(site_dir)?;
+
+    Ok(RotateOutcome {
+        slots: summarize(&new_config),
+        dropped,
+        chunks: new_config.payload.chunk_count,
+        fingerprint,
+    })
+}
+
+/// Generate a fresh 32-byte recovery secret
+pub fn generate_recovery_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+/// Parse a recovery secret from a bare base64 string or a recovery-secret.txt
+pub fn parse_recovery_secret(text: &str) -> Result<Vec<u8>> {
+    let encoded = match text.split_once("Secret (base64):") {
+        Some((_, rest)) => rest.lines().map(str::trim).find(|l| !l.is_empty()),
+        None => Some(text.trim()),
+    }
+    .filter(|s| !s.is_empty())
+    .context("Recovery secret is empty")?;
+
+    BASE64
+        .decode(encoded)
+        .context("Recovery secret is not valid base64")
+}
+
+fn summarize(config: &EncryptionConfig) -> Vec<SlotSummary> {
+    config
+        .key_slots
+        .iter()
+        .map(|slot| SlotSummary {
+            id: slot.id,
+            slot_type: slot.slot_type,
+            kdf: slot.kdf,
+            recipient: slot.recipient.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn encrypted_site(dir: &Path, data: &[u8]) -> PathBuf {
+        let input = dir.join("export.db");
+        std::fs::write(&input, data).unwrap();
+        let site = dir.join("site");
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_password_slot("owner").unwrap();
+        engine.encrypt_file(&input, &site, |_, _| {}).unwrap();
+        refresh_integrity(&site).unwrap();
+        site
+    }
+
+    fn decrypt(site: &Path, unlock: &Unlock) -> Result<Vec<u8>> {
+        let out = site.parent().unwrap().join("out.db");
+        unlock
+            .open(load_config(site)?)?
+            .decrypt_to_file(site, &out, |_, _| {})?;
+        Ok(std::fs::read(out)?)
+    }
+
+    #[test]
+    fn add_and_revoke_slots() {
+        let temp = TempDir::new().unwrap();
+        let site = encrypted_site(temp.path(), b"archive contents");
+        let owner = Unlock::Password("owner".into());
+
+        let id = add_slot(&site, &owner, NewSlot::Password("colleague")).unwrap();
+        assert_eq!(id, 1);
+        let colleague = Unlock::Password("colleague".into());
+        assert_eq!(decrypt(&site, &colleague).unwrap(), b"archive contents");
+
+        revoke_slot(&site, &owner, 1).unwrap();
+        assert!(decrypt(&site, &colleague).is_err());
+
+        // A gap left by a revoke never makes two slots share an id
+        add_slot(&site, &owner, NewSlot::Password("x")).unwrap();
+        add_slot(&site, &owner, NewSlot::Password("y")).unwrap();
+        revoke_slot(&site, &owner, 1).unwrap();
+        assert_eq!(add_slot(&site, &owner, NewSlot::Password("z")).unwrap(), 3);
+
+        assert!(revoke_slot(&site, &owner, 7).is_err());
+        assert!(add_slot(&site, &colleague, NewSlot::Password("w")).is_err());
+    }
+
+    #[test]
+    fn last_slot_cannot_be_revoked() {
+        let temp = TempDir::new().unwrap();
+        let site = encrypted_site(temp.path(), b"data");
+        let owner = Unlock::Password("owner".into());
+        assert!(revoke_slot(&site, &owner, 0).is_err());
+        assert_eq!(list_slots(&site).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rotate_replaces_dek_and_payload() {
+        let temp = TempDir::new().unwrap();
+        let data: Vec<u8> = (0..3000u32).map(|i| (i % 13) as u8).collect();
+        let site = encrypted_site(temp.path(), &data);
+        let owner = Unlock::Password("owner".into());
+        add_slot(&site, &owner, NewSlot::Password("leaked")).unwrap();
+        let before = load_config(&site).unwrap();
+        let listing = |dir: &Path| {
+            let mut names: Vec<_> = std::fs::read_dir(dir)
+                .unwrap()
+                .map(|e| e.unwrap().file_name())
+                .collect();
+            names.sort();
+            names
+        };
+        let beside_bundle = listing(temp.path());
+
+        // Nothing changes unless dropping the other password is confirmed
+        let secret = generate_recovery_secret();
+        let err = rotate(&site, &owner, None, Some(&secret), |_| false).unwrap_err();
+        assert!(err.to_string().contains("slot(s) 1"));
+        assert_eq!(load_config(&site).unwrap().export_id, before.export_id);
+
+        let outcome = rotate(&site, &owner, None, Some(&secret), |dropped| {
+            dropped.iter().map(|slot| slot.id).eq([1])
+        })
+        .unwrap();
+        // The decrypted database is never written next to the bundle
+        assert_eq!(listing(temp.path()), beside_bundle);
+        assert_eq!(outcome.slots.len(), 2);
+        assert_eq!(outcome.dropped.len(), 1);
+        assert_eq!(outcome.chunks, 3);
+
+        let after = load_config(&site).unwrap();
+        assert_ne!(after.export_id, before.export_id);
+        assert!(before.payload.files.iter().all(|f| !site.join(f).exists()));
+        assert!(decrypt(&site, &Unlock::Password("leaked".into())).is_err());
+        assert_eq!(decrypt(&site, &owner).unwrap(), data);
+        assert_eq!(decrypt(&site, &Unlock::Recovery(secret)).unwrap(), data);
+    }
+
+    #[test]
+    fn recipient_slots_unlock_and_survive_rotation() {
+        let temp = TempDir::new().unwrap();
+        let site = encrypted_site(temp.path(), b"team archive");
+        let owner = Unlock::Password("owner".into());
+        let teammate = Identity::generate();
+        let recipient = teammate.recipient();
+
+        let id = add_slot(&site, &owner, NewSlot::Recipient(&recipient)).unwrap();
+        let slots = list_slots(&site).unwrap();
+        assert_eq!(slots[id as usize].slot_type, SlotType::Recipient);
+        assert_eq!(slots[id as usize].kdf, KdfAlgorithm::X25519HkdfSha256);
+
+        let teammate = Unlock::Identity(teammate);
+        assert_eq!(decrypt(&site, &teammate).unwrap(), b"team archive");
+
+        let outcome = rotate(&site, &owner, None, None, |_| false).unwrap();
+        assert!(outcome.dropped.is_empty());
+        assert_eq!(list_slots(&site).unwrap().len(), 2);
+        assert_eq!(decrypt(&site, &teammate).unwrap(), b"team archive");
+    }
+
+    #[test]
+    fn rotating_with_recovery_secret_keeps_it() {
+        let temp = TempDir::new().unwrap();
+        let site = encrypted_site(temp.path(), b"data");
+        let owner = Unlock::Password("owner".into());
+        let secret = generate_recovery_secret();
+        add_slot(&site, &owner, NewSlot::Recovery(&secret)).unwrap();
+
+        let recovery = Unlock::Recovery(secret);
+        let outcome = rotate(&site, &recovery, Some("fresh"), None, |dropped| {
+            dropped.iter().map(|slot| slot.id).eq([0])
+        })
+        .unwrap();
+        assert_eq!(outcome.dropped[0].slot_type, SlotType::Password);
+        assert!(decrypt(&site, &owner).is_err());
+        assert_eq!(decrypt(&site, &recovery).unwrap(), b"data");
+        assert_eq!(
+            decrypt(&site, &Unlock::Password("fresh".into())).unwrap(),
+            b"data"
+        );
+    }
+
+    #[test]
+    fn parses_recovery_secret_file_and_bare_value() {
+        let secret = vec![7u8; 32];
+        let encoded = BASE64.encode(&secret);
+        assert_eq!(parse_recovery_secret(&encoded).unwrap(), secret);
+
+        let file =
+            format!("Recovery Secret\n\nSecret (base64):\n{encoded}\n\nArchive Export ID: x\n");
+        assert_eq!(parse_recovery_secret(&file).unwrap(), secret);
+        assert!(parse_recovery_secret("  ").is_err());
+    }
+}
@@ -1,9 +1,20 @@
 pub mod bundle;
+pub mod curation;
+pub mod deploy;
 pub mod deploy_cloudflare;
+pub mod deploy_git;
 pub mod deploy_github;
+pub mod deploy_s3;
+pub mod deploy_ssh;
 pub mod encrypt;
 pub mod export;
 pub mod fts;
+pub mod import;
+pub mod keys;
 pub mod qr;
+pub mod recipient;
+pub mod serve;
 pub mod size;
+pub mod static_site;
+pub mod vectors;
 pub mod wizard;
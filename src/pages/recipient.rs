@@ -0,0 +1,135 @@
+//! age-compatible X25519 keys for recipient slots.
+//!
+//! Recipients are written as `age1…` public keys and identities as
+//! `AGE-SECRET-KEY-1…` secret keys (bech32, as produced by `age-keygen`), so
+//! teammates can reuse keys they already have. Only the key encoding follows
+//! age; the DEK wrapping itself is cass's own (see `encrypt::derive_kek_x25519`).
+
+use anyhow::{Context, Result, bail};
+use bech32::{Bech32, Hrp};
+use rand::rngs::OsRng;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Human-readable part of an age public key
+const RECIPIENT_HRP: &str = "age";
+
+/// Human-readable part of an age secret key (uppercased when encoded)
+const IDENTITY_HRP: &str = "age-secret-key-";
+
+/// Parse an `age1…` public key into raw X25519 bytes
+pub fn parse_recipient(encoded: &str) -> Result<[u8; 32]> {
+    let (hrp, data) = bech32::decode(encoded.trim())
+        .with_context(|| format!("Invalid recipient key: {}", encoded.trim()))?;
+    if hrp.to_lowercase() != RECIPIENT_HRP {
+        bail!("Recipient key must start with age1");
+    }
+    data.try_into()
+        .map_err(|_| anyhow::anyhow!("Recipient key must encode 32 bytes"))
+}
+
+/// Encode raw X25519 public key bytes as an `age1…` string
+pub fn encode_recipient(public: &[u8; 32]) -> String {
+    let hrp = Hrp::parse_unchecked(RECIPIENT_HRP);
+    bech32::encode::<Bech32>(hrp, public).expect("32-byte key fits in bech32")
+}
+
+/// An X25519 secret key able to open recipient slots
+pub struct Identity {
+    secret: StaticSecret,
+}
+
+impl Identity {
+    /// Generate a new random identity
+    pub fn generate() -> Self {
+        Self {
+            secret: StaticSecret::random_from_rng(OsRng),
+        }
+    }
+
+    /// Build an identity from raw secret key bytes
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self {
+            secret: StaticSecret::from(bytes),
+        }
+    }
+
+    /// Parse an `AGE-SECRET-KEY-1…` key or an age identity file
+    ///
+    /// Identity files may hold comments (`# public key: …`) and blank lines;
+    /// the first secret key line is used.
+    pub fn parse(text: &str) -> Result<Self> {
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .context("Identity file holds no secret key")?;
+        let (hrp, data) = bech32::decode(line).context("Invalid identity secret key")?;
+        if hrp.to_lowercase() != IDENTITY_HRP {
+            bail!("Identity must be an AGE-SECRET-KEY-1 secret key");
+        }
+        let bytes: [u8; 32] = data
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Identity secret key must encode 32 bytes"))?;
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Encode as an `AGE-SECRET-KEY-1…` string
+    pub fn to_secret_string(&self) -> String {
+        let hrp = Hrp::parse_unchecked(IDENTITY_HRP);
+        bech32::encode::<Bech32>(hrp, self.secret.as_bytes())
+            .expect("32-byte key fits in bech32")
+            .to_uppercase()
+    }
+
+    /// Raw X25519 public key
+    pub fn public_bytes(&self) -> [u8; 32] {
+        PublicKey::from(&self.secret).to_bytes()
+    }
+
+    /// The matching `age1…` recipient
+    pub fn recipient(&self) -> String {
+        encode_recipient(&self.public_bytes())
+    }
+
+    /// X25519 with a peer public key; `None` if the result is all zeros
+    /// (a low-order peer point contributes nothing to the secret)
+    pub fn shared_secret(&self, peer_public: &[u8; 32]) -> Option<[u8; 32]> {
+        let shared = self.secret.diffie_hellman(&PublicKey::from(*peer_public));
+        shared.was_contributory().then(|| shared.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_round_trips_through_encoding() {
+        let identity = Identity::generate();
+        let secret = identity.to_secret_string();
+        assert!(secret.starts_with("AGE-SECRET-KEY-1"));
+
+        let file = format!(
+            "# created: 2026-01-01\n# public key: {}\n{}\n",
+            identity.recipient(),
+            secret
+        );
+        let parsed = Identity::parse(&file).unwrap();
+        assert_eq!(parsed.public_bytes(), identity.public_bytes());
+        assert_eq!(
+            parse_recipient(&identity.recipient()).unwrap(),
+            identity.public_bytes()
+        );
+    }
+
+    #[test]
+    fn rejects_swapped_or_malformed_keys() {
+        let identity = Identity::generate();
+        assert!(parse_recipient(&identity.to_secret_string()).is_err());
+        assert!(Identity::parse(&identity.recipient()).is_err());
+        assert!(parse_recipient("age1notakey").is_err());
+        assert!(Identity::parse("# only a comment\n").is_err());
+        // Low-order points yield no shared secret
+        assert!(identity.shared_secret(&[0u8; 32]).is_none());
+    }
+}
@@ -0,0 +1,287 @@
+//! Local preview server for pages bundles.
+//!
+//! The viewer needs to be served over HTTP: `file://` has no service worker,
+//! and without the COOP/COEP headers the page is not cross-origin isolated,
+//! which `coi-detector.js` reports as a degraded environment. This server
+//! sends those headers on every response so a bundle behaves locally the
+//! way it does once deployed.
+
+use anyhow::{Context, Result, bail};
+use notify::{RecursiveMode, Watcher, recommended_watcher};
+use std::io::BufReader;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use crate::http;
+use crate::pages::bundle::{BundleBuilder, BundleResult, SiteMetadata};
+
+/// Quiet period after the last change before rebuilding
+const REBUILD_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Headers sent with every response
+const ISOLATION_HEADERS: &[(&str, &str)] = &[
+    ("Cross-Origin-Opener-Policy", "same-origin"),
+    ("Cross-Origin-Embedder-Policy", "require-corp"),
+    ("Cross-Origin-Resource-Policy", "same-origin"),
+    ("X-Content-Type-Options", "nosniff"),
+    // Rebuilds replace files in place; always revalidate
+    ("Cache-Control", "no-cache"),
+];
+
+/// A blocking HTTP server for one site/ directory
+pub struct PreviewServer {
+    listener: TcpListener,
+    site_dir: PathBuf,
+}
+
+impl PreviewServer {
+    /// Bind to `addr` (e.g. `127.0.0.1:8080`; port 0 picks a free port)
+    pub fn bind(site_dir: &Path, addr: &str) -> Result<Self> {
+        if !site_dir.join("index.html").is_file() {
+            bail!("No index.html in {}", site_dir.display());
+        }
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to listen on {addr}"))?;
+        Ok(Self {
+            listener,
+            site_dir: site_dir.to_path_buf(),
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// URL of the site root
+    pub fn url(&self) -> Result<String> {
+        Ok(format!("http://{}/", self.local_addr()?))
+    }
+
+    /// Serve requests until the process exits, one thread per connection
+    pub fn run(self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let site_dir = self.site_dir.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &site_dir) {
+                    tracing::debug!("pages serve: {e:#}");
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn handle_connection(stream: TcpStream, site_dir: &Path) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let head = match http::read_request_head(&mut reader, http::HEAD_TIMEOUT)? {
+        Ok(head) => head,
+        Err(status) => return respond_status(&stream, status, false),
+    };
+    let head_only = match head.method.as_str() {
+        "GET" => false,
+        "HEAD" => true,
+        _ => return respond_status(&stream, "405 Method Not Allowed", false),
+    };
+
+    match resolve_request_path(site_dir, &head.target) {
+        Some(path) => {
+            let body = std::fs::read(&path)?;
+            respond(&stream, "200 OK", content_type(&path), &body, head_only)
+        }
+        None => respond_status(&stream, "404 Not Found", head_only),
+    }
+}
+
+/// Write a response with the isolation headers
+fn respond(
+    stream: &TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+    head_only: bool,
+) -> Result<()> {
+    let mut headers = Vec::with_capacity(ISOLATION_HEADERS.len() + 1);
+    if status.starts_with("405") {
+        headers.push(("Allow", "GET, HEAD"));
+    }
+    headers.extend_from_slice(ISOLATION_HEADERS);
+    http::write_response(stream, status, content_type, &headers, body, head_only)?;
+    Ok(())
+}
+
+/// Respond with a bare status line as the body
+fn respond_status(stream: &TcpStream, status: &str, head_only: bool) -> Result<()> {
+    respond(
+        stream,
+        status,
+        "text/plain; charset=utf-8",
+        status.as_bytes(),
+        head_only,
+    )
+}
+
+/// Map a request target onto a file under `site_dir`
+///
+/// Returns `None` for anything outside the site (`..`, absolute or drive
+/// paths, symlinks pointing elsewhere) and for missing files. Directories
+/// resolve to their index.html.
+pub fn resolve_request_path(site_dir: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let decoded = urlencoding::decode(path).ok()?;
+
+    let mut resolved = site_dir.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if resolved.is_dir() {
+        resolved.push("index.html");
+    }
+    let resolved = resolved.canonicalize().ok()?;
+    let site_dir = site_dir.canonicalize().ok()?;
+    (resolved.starts_with(&site_dir) && resolved.is_file()).then_some(resolved)
+}
+
+/// Content type by file extension
+pub fn content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+    {
+        "html" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" => "application/json",
+        "wasm" => "application/wasm",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "ico" => "image/x-icon",
+        "txt" | "md" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Rebuild `bundle_dir` from the encrypted export in `export_dir` whenever
+/// the export changes
+///
+/// Runs on a background thread for as long as the process lives; `on_rebuild`
+/// gets the outcome of every rebuild. The title and description of the
+/// current site are kept.
+pub fn watch_and_rebuild<F>(export_dir: &Path, bundle_dir: &Path, on_rebuild: F) -> Result<()>
+where
+    F: Fn(Result<BundleResult>) + Send + 'static,
+{
+    if !export_dir.join("config.json").is_file() {
+        bail!(
+            "No encrypted export (config.json) in {}",
+            export_dir.display()
+        );
+    }
+    let export_dir = export_dir.to_path_buf();
+    let bundle_dir = bundle_dir.to_path_buf();
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let mut watcher = recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if res.is_ok() {
+            let _ = tx.send(());
+        }
+    })?;
+    watcher.watch(&export_dir, RecursiveMode::Recursive)?;
+
+    std::thread::spawn(move || {
+        // The watcher stops when dropped; keep it alive with the thread
+        let _watcher = watcher;
+        while rx.recv().is_ok() {
+            // Wait for the export to settle before rebuilding
+            while rx.recv_timeout(REBUILD_DEBOUNCE).is_ok() {}
+            on_rebuild(rebuild(&export_dir, &bundle_dir));
+        }
+    });
+    Ok(())
+}
+
+fn rebuild(export_dir: &Path, bundle_dir: &Path) -> Result<BundleResult> {
+    let mut builder = BundleBuilder::new();
+    let site_json = bundle_dir.join("site").join("site.json");
+    if let Some(metadata) = std::fs::read_to_string(site_json)
+        .ok()
+        .and_then(|s| serde_json::from_str::<SiteMetadata>(&s).ok())
+    {
+        builder = builder
+            .title(metadata.title)
+            .description(metadata.description);
+    }
+    builder.build(export_dir, bundle_dir, |_, _| {})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use tempfile::TempDir;
+
+    fn get(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_site_with_isolation_headers() {
+        let temp = TempDir::new().unwrap();
+        let site = temp.path().join("site");
+        std::fs::create_dir_all(site.join("payload")).unwrap();
+        std::fs::write(site.join("index.html"), "<h1>archive</h1>").unwrap();
+        std::fs::write(site.join("viewer.js"), "export {}").unwrap();
+        std::fs::write(temp.path().join("secret.txt"), "private").unwrap();
+
+        let server = PreviewServer::bind(&site, "127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || server.run());
+
+        let index = get(addr, "GET /?v=1 HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert!(index.starts_with("HTTP/1.1 200 OK"));
+        assert!(index.contains("Cross-Origin-Opener-Policy: same-origin"));
+        assert!(index.contains("Cross-Origin-Embedder-Policy: require-corp"));
+        assert!(index.contains("Content-Type: text/html"));
+        assert!(index.ends_with("<h1>archive</h1>"));
+
+        let script = get(addr, "HEAD /viewer.js HTTP/1.1\r\n\r\n");
+        assert!(script.contains("Content-Type: text/javascript"));
+        assert!(script.ends_with("\r\n\r\n"));
+
+        let escape = get(addr, "GET /%2e%2e/secret.txt HTTP/1.1\r\n\r\n");
+        assert!(escape.starts_with("HTTP/1.1 404"));
+        let post = get(addr, "POST / HTTP/1.1\r\n\r\n");
+        assert!(post.starts_with("HTTP/1.1 405"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_cannot_escape_the_site() {
+        let temp = TempDir::new().unwrap();
+        let site = temp.path().join("site");
+        std::fs::create_dir_all(site.join("assets")).unwrap();
+        std::fs::write(site.join("assets/app.js"), "export {}").unwrap();
+        std::fs::write(temp.path().join("secret.txt"), "private").unwrap();
+        std::os::unix::fs::symlink(temp.path().join("secret.txt"), site.join("leak.txt")).unwrap();
+        std::os::unix::fs::symlink(temp.path(), site.join("parent")).unwrap();
+        std::os::unix::fs::symlink(site.join("assets"), site.join("static")).unwrap();
+
+        assert_eq!(resolve_request_path(&site, "/leak.txt"), None);
+        assert_eq!(resolve_request_path(&site, "/parent/secret.txt"), None);
+        // Links that stay inside the site still work
+        assert_eq!(
+            resolve_request_path(&site, "/static/app.js"),
+            Some(site.join("assets/app.js").canonicalize().unwrap())
+        );
+    }
+}
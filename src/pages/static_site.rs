@@ -0,0 +1,297 @@
+//! Pre-rendered static HTML for unencrypted pages exports.
+//!
+//! Renders an exported database into one HTML page per conversation, an index
+//! page listing them, and `search-index.json`, a prebuilt inverted index that
+//! `static-search.js` queries in the browser. Nothing needs SQLite or WASM at
+//! view time, and every page is readable with JavaScript disabled.
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use rusqlite::{Connection, OpenFlags, params};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::html_escape;
+
+/// Assets used by the static pages
+const STATIC_ASSETS: &[(&str, &[u8])] = &[
+    ("static.css", include_bytes!("../pages_assets/static.css")),
+    (
+        "static-search.js",
+        include_bytes!("../pages_assets/static-search.js"),
+    ),
+];
+
+/// Directory (under site/) holding the conversation pages
+pub const CONVERSATIONS_DIR: &str = "c";
+
+/// Index file name
+pub const SEARCH_INDEX_FILE: &str = "search-index.json";
+
+/// Tokens outside this length range (in chars) are not indexed
+const MIN_TOKEN_CHARS: usize = 2;
+const MAX_TOKEN_CHARS: usize = 40;
+
+const CSP: &str = "default-src 'self'; script-src 'self'; style-src 'self'; \
+                   connect-src 'self'; object-src 'none'; base-uri 'none'; form-action 'none'";
+
+/// Counts from rendering a static site
+#[derive(Debug, Clone, Default)]
+pub struct StaticSiteStats {
+    pub conversations: usize,
+    pub messages: usize,
+    /// Distinct terms in the search index
+    pub terms: usize,
+}
+
+/// Prebuilt search index shipped as search-index.json
+#[derive(Debug, Default, Serialize)]
+pub struct SearchIndex {
+    pub version: u32,
+    pub docs: Vec<SearchDoc>,
+    /// Term -> ascending indexes into `docs`
+    pub terms: BTreeMap<String, Vec<u32>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchDoc {
+    pub url: String,
+    pub title: String,
+    pub agent: String,
+    pub date: String,
+}
+
+struct Conversation {
+    id: i64,
+    agent: String,
+    workspace: Option<String>,
+    title: Option<String>,
+    source_path: String,
+    started_at: Option<i64>,
+}
+
+/// Render the exported database at `database` into `site_dir`
+pub fn render_static_site(
+    database: &Path,
+    site_dir: &Path,
+    title: &str,
+    description: &str,
+) -> Result<StaticSiteStats> {
+    let conn = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .context("Failed to open export database")?;
+
+    let conversations_dir = site_dir.join(CONVERSATIONS_DIR);
+    if conversations_dir.exists() {
+        // Pages of conversations dropped since the last render must go
+        fs::remove_dir_all(&conversations_dir)?;
+    }
+    fs::create_dir_all(&conversations_dir)?;
+
+    for (name, content) in STATIC_ASSETS {
+        fs::write(site_dir.join(name), content)
+            .with_context(|| format!("Failed to write {}", name))?;
+    }
+
+    let mut conv_stmt = conn.prepare(
+        "SELECT id, agent, workspace, title, source_path, started_at
+         FROM conversations
+         ORDER BY started_at DESC, id DESC",
+    )?;
+    let conversations = conv_stmt
+        .query_map([], |row| {
+            Ok(Conversation {
+                id: row.get(0)?,
+                agent: row.get(1)?,
+                workspace: row.get(2)?,
+                title: row.get(3)?,
+                source_path: row.get(4)?,
+                started_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut msg_stmt = conn.prepare(
+        "SELECT role, content, created_at FROM messages
+         WHERE conversation_id = ? ORDER BY idx ASC",
+    )?;
+
+    let mut stats = StaticSiteStats::default();
+    let mut index = SearchIndex {
+        version: 1,
+        ..Default::default()
+    };
+    let mut postings: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+
+    for (doc, conv) in conversations.iter().enumerate() {
+        let doc = doc as u32;
+        let display_title = conv
+            .title
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| conv.source_path.clone());
+        let url = format!("{}/{}.html", CONVERSATIONS_DIR, conv.id);
+
+        let messages = msg_stmt
+            .query_map(params![conv.id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for token in tokenize(&display_title).chain(messages.iter().flat_map(|m| tokenize(&m.1))) {
+            postings.entry(token).or_default().insert(doc);
+        }
+
+        let page = render_conversation(conv, &display_title, &messages, title);
+        fs::write(site_dir.join(&url), page)?;
+
+        stats.conversations += 1;
+        stats.messages += messages.len();
+        index.docs.push(SearchDoc {
+            url,
+            title: display_title,
+            agent: conv.agent.clone(),
+            date: format_date(conv.started_at),
+        });
+    }
+
+    index.terms = postings
+        .into_iter()
+        .map(|(term, docs)| (term, docs.into_iter().collect()))
+        .collect();
+    stats.terms = index.terms.len();
+
+    fs::write(
+        site_dir.join("index.html"),
+        render_index(&index, title, description),
+    )?;
+    fs::write(
+        site_dir.join(SEARCH_INDEX_FILE),
+        serde_json::to_string(&index)?,
+    )?;
+
+    Ok(stats)
+}
+
+/// Lowercase word tokens, as `static-search.js` splits queries
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|t| (MIN_TOKEN_CHARS..=MAX_TOKEN_CHARS).contains(&t.chars().count()))
+        .map(str::to_lowercase)
+}
+
+fn format_date(millis: Option<i64>) -> String {
+    millis
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn page_head(page_title: &str, css_href: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n\
+         <meta charset=\"UTF-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n\
+         <meta http-equiv=\"Content-Security-Policy\" content=\"{CSP}\">\n\
+         <meta name=\"referrer\" content=\"no-referrer\">\n\
+         <meta name=\"robots\" content=\"noindex,nofollow\">\n\
+         <title>{}</title>\n\
+         <link rel=\"stylesheet\" href=\"{css_href}\">\n\
+         </head>\n",
+        html_escape(page_title)
+    )
+}
+
+fn render_index(index: &SearchIndex, title: &str, description: &str) -> String {
+    let mut html = page_head(title, "static.css");
+    html.push_str("<body>\n<main>\n<header>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", html_escape(title)));
+    html.push_str(&format!(
+        "<p class=\"subtitle\">{} · {} conversations</p>\n",
+        html_escape(description),
+        index.docs.len()
+    ));
+    html.push_str("</header>\n");
+    html.push_str(
+        "<input id=\"search-input\" type=\"search\" placeholder=\"Search conversations…\" \
+         autocomplete=\"off\" disabled>\n\
+         <div id=\"search-results\" class=\"hidden\"></div>\n",
+    );
+    html.push_str("<ul id=\"conversations\" class=\"conversation-list\">\n");
+    for doc in &index.docs {
+        html.push_str(&format!(
+            "<li><a href=\"{}\">{}</a><span class=\"meta\">{} · {}</span></li>\n",
+            html_escape(&doc.url),
+            html_escape(&doc.title),
+            html_escape(&doc.date),
+            html_escape(&doc.agent)
+        ));
+    }
+    html.push_str("</ul>\n</main>\n<script src=\"static-search.js\"></script>\n</body>\n</html>\n");
+    html
+}
+
+fn render_conversation(
+    conv: &Conversation,
+    display_title: &str,
+    messages: &[(String, String, Option<i64>)],
+    site_title: &str,
+) -> String {
+    let mut html = page_head(&format!("{display_title} · {site_title}"), "../static.css");
+    html.push_str("<body>\n<main>\n<header>\n");
+    html.push_str(&format!(
+        "<p><a href=\"../index.html\">← {}</a></p>\n",
+        html_escape(site_title)
+    ));
+    html.push_str(&format!("<h1>{}</h1>\n", html_escape(display_title)));
+    let mut meta = vec![conv.agent.clone(), format_date(conv.started_at)];
+    if let Some(workspace) = &conv.workspace {
+        meta.push(workspace.clone());
+    }
+    meta.retain(|m| !m.is_empty());
+    html.push_str(&format!(
+        "<p class=\"subtitle\">{}</p>\n</header>\n",
+        html_escape(&meta.join(" · "))
+    ));
+
+    for (role, content, created_at) in messages {
+        let role_class: String = role
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        let time = created_at
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|d| format!(" · {}", d.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        html.push_str(&format!(
+            "<article class=\"message role-{role_class}\">\n\
+             <div class=\"message-header\">{}{}</div>\n\
+             <pre>{}</pre>\n</article>\n",
+            html_escape(role),
+            time,
+            html_escape(content)
+        ));
+    }
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_matches_search_script_rules() {
+        let tokens: Vec<String> = tokenize("Fix the parse_args() bug in Main.rs, x").collect();
+        assert_eq!(
+            tokens,
+            ["fix", "the", "parse_args", "bug", "in", "main", "rs"]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Message vectors for semantic search in the web viewer.
+//!
+//! An export can carry one vector per message in the `message_vectors` table
+//! of the archive database, so they are encrypted with the rest of the
+//! payload. Vectors are quantized to int8 with a per-vector scale (a quarter
+//! of the CVVI f32 size). The viewer embeds queries with a port of the hash
+//! embedder in `semantic.js`, so only hash-embedder vectors are searchable
+//! there unless the page registers an in-browser model for the embedder.
+
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::search::canonicalize::canonicalize_for_embedding;
+use crate::search::embedder::Embedder;
+use crate::search::hash_embedder::HashEmbedder;
+use crate::search::vector_index::{VectorIndex, vector_index_path};
+
+/// Quantization recorded in export_meta (`vector_quantization`)
+pub const VECTOR_QUANTIZATION: &str = "int8";
+
+/// Embedder id prefix of the hash embedder (`fnv1a-<dimension>`)
+const HASH_EMBEDDER_PREFIX: &str = "fnv1a-";
+
+/// Where exported message vectors come from
+pub enum VectorSource {
+    /// Vectors already in a CVVI index, keyed by source message id
+    Index {
+        index: VectorIndex,
+        rows_by_message: HashMap<u64, Vec<usize>>,
+    },
+    /// Embed each exported message with the hash embedder
+    Hash(HashEmbedder),
+}
+
+impl VectorSource {
+    pub fn from_index(index: VectorIndex) -> Self {
+        let mut rows_by_message: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, row) in index.rows().iter().enumerate() {
+            rows_by_message.entry(row.message_id).or_default().push(i);
+        }
+        Self::Index {
+            index,
+            rows_by_message,
+        }
+    }
+
+    /// The hash-embedder CVVI index in `data_dir` if one was built, else
+    /// embedding at export time with the same embedder
+    pub fn for_viewer(data_dir: &Path) -> Result<Self> {
+        let embedder = HashEmbedder::default_dimension();
+        let path = vector_index_path(data_dir, embedder.id());
+        if path.is_file() {
+            Ok(Self::from_index(VectorIndex::load(&path)?))
+        } else {
+            Ok(Self::Hash(embedder))
+        }
+    }
+
+    pub fn embedder_id(&self) -> &str {
+        match self {
+            Self::Index { index, .. } => &index.header().embedder_id,
+            Self::Hash(embedder) => embedder.id(),
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        match self {
+            Self::Index { index, .. } => index.header().dimension as usize,
+            Self::Hash(embedder) => embedder.dimension(),
+        }
+    }
+
+    /// Vector for one exported message, `None` when there is nothing to ship
+    ///
+    /// A redacted message never gets its indexed vector, which was computed
+    /// from the original text; it is re-embedded when the index uses the hash
+    /// embedder and left out otherwise.
+    pub fn vector_for(
+        &self,
+        source_message_id: i64,
+        content: &str,
+        redacted: bool,
+    ) -> Result<Option<Vec<f32>>> {
+        match self {
+            Self::Index {
+                index,
+                rows_by_message,
+            } => {
+                if redacted {
+                    let id = &index.header().embedder_id;
+                    return match id.strip_prefix(HASH_EMBEDDER_PREFIX) {
+                        Some(dim) => embed_hash(&HashEmbedder::new(dim.parse()?), content),
+                        None => Ok(None),
+                    };
+                }
+                let Some(rows) = u64::try_from(source_message_id)
+                    .ok()
+                    .and_then(|id| rows_by_message.get(&id))
+                else {
+                    return Ok(None);
+                };
+                // Messages split into chunks get the normalized mean
+                let mut sum = vec![0.0f32; self.dimension()];
+                for &i in rows {
+                    let vector = index.vector_at_f32(&index.rows()[i])?;
+                    for (acc, value) in sum.iter_mut().zip(vector) {
+                        *acc += value;
+                    }
+                }
+                l2_normalize(&mut sum);
+                Ok(Some(sum))
+            }
+            Self::Hash(embedder) => embed_hash(embedder, content),
+        }
+    }
+}
+
+fn embed_hash(embedder: &HashEmbedder, content: &str) -> Result<Option<Vec<f32>>> {
+    let canonical = canonicalize_for_embedding(content);
+    if canonical.trim().is_empty() {
+        return Ok(None);
+    }
+    embedder
+        .embed(&canonical)
+        .map(Some)
+        .map_err(|e| anyhow!("hash embedding failed: {e}"))
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+/// Quantize to int8 with a symmetric per-vector scale
+///
+/// Returns the scale and the components as two's-complement bytes;
+/// component `i` is approximately `scale * (bytes[i] as i8)`. `None` for an
+/// all-zero vector.
+pub fn quantize_i8(vector: &[f32]) -> Option<(f32, Vec<u8>)> {
+    let max = vector.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    if max <= f32::EPSILON {
+        return None;
+    }
+    let scale = max / 127.0;
+    let bytes = vector
+        .iter()
+        .map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8 as u8)
+        .collect();
+    Some((scale, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::vector_index::{Quantization, VectorEntry};
+
+    fn entry(message_id: u64, chunk_idx: u8, vector: Vec<f32>) -> VectorEntry {
+        VectorEntry {
+            message_id,
+            created_at_ms: 0,
+            agent_id: 0,
+            workspace_id: 0,
+            source_id: 0,
+            role: 0,
+            chunk_idx,
+            content_hash: [0; 32],
+            vector,
+        }
+    }
+
+    #[test]
+    fn quantize_round_trips_within_one_step() {
+        let vector = [0.5, -0.25, 0.0, 0.125];
+        let (scale, bytes) = quantize_i8(&vector).unwrap();
+        assert_eq!(bytes[0] as i8, 127);
+        assert_eq!(bytes[1] as i8, -64);
+        for (v, b) in vector.iter().zip(&bytes) {
+            assert!((scale * f32::from(*b as i8) - v).abs() <= scale);
+        }
+        assert!(quantize_i8(&[0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn index_vectors_average_chunks_and_skip_redacted() {
+        let index = VectorIndex::build(
+            "minilm-384",
+            "test",
+            2,
+            Quantization::F32,
+            vec![
+                entry(7, 0, vec![1.0, 0.0]),
+                entry(7, 1, vec![0.0, 1.0]),
+                entry(8, 0, vec![0.0, 1.0]),
+            ],
+        )
+        .unwrap();
+        let source = VectorSource::from_index(index);
+        assert_eq!(source.embedder_id(), "minilm-384");
+
+        let mean = source.vector_for(7, "text", false).unwrap().unwrap();
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((mean[0] - half).abs() < 1e-6 && (mean[1] - half).abs() < 1e-6);
+        assert!(source.vector_for(9, "text", false).unwrap().is_none());
+        // Model vectors of redacted text cannot be recomputed; drop them
+        assert!(source.vector_for(8, "[REDACTED]", true).unwrap().is_none());
+    }
+
+    #[test]
+    fn hash_source_embeds_canonical_text() {
+        let source = VectorSource::Hash(HashEmbedder::new(16));
+        let vector = source.vector_for(1, "**Fix** the parser", false).unwrap();
+        let expected = HashEmbedder::new(16).embed("Fix the parser").unwrap();
+        assert_eq!(vector.unwrap(), expected);
+        assert!(source.vector_for(2, "   ", false).unwrap().is_none());
+    }
+}
@@ -3,21 +3,36 @@ use console::{style, Term};
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Password, Select};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::pages::encrypt::EncryptionEngine;
-use crate::pages::export::{ExportEngine, ExportFilter, PathMode};
+use crate::pages::bundle::{BundleBuilder, PlaintextMode};
+use crate::pages::curation::{curation_path, Curation, RedactRules};
+use crate::pages::deploy::Deployer;
+use crate::pages::deploy_git::GitRemoteDeployer;
+use crate::pages::deploy_github::GitHubDeployer;
+use crate::pages::deploy_s3::{S3Credentials, S3Deployer, DEFAULT_REGION};
+use crate::pages::deploy_ssh::SshDeployer;
+use crate::pages::encrypt::{load_config, EncryptionEngine, SlotType};
+use crate::pages::export::{ConversationSummary, ExportEngine, ExportFilter, PathMode};
+use crate::pages::keys::generate_recovery_secret;
+use crate::pages::vectors::VectorSource;
 use crate::storage::sqlite::SqliteStorage;
 
+/// Characters of a message shown in the message picker
+const MESSAGE_PREVIEW_CHARS: usize = 80;
+
 /// Deployment target for the export
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeployTarget {
     Local,
     GitHubPages,
     CloudflarePages,
+    SshServer,
+    S3Compatible,
+    GitRemote,
 }
 
 impl std::fmt::Display for DeployTarget {
@@ -26,6 +41,9 @@ impl std::fmt::Display for DeployTarget {
             DeployTarget::Local => write!(f, "Local export only"),
             DeployTarget::GitHubPages => write!(f, "GitHub Pages"),
             DeployTarget::CloudflarePages => write!(f, "Cloudflare Pages"),
+            DeployTarget::SshServer => write!(f, "Web server (rsync/SSH)"),
+            DeployTarget::S3Compatible => write!(f, "S3-compatible storage"),
+            DeployTarget::GitRemote => write!(f, "Git remote"),
         }
     }
 }
@@ -38,7 +56,14 @@ pub struct WizardState {
     pub time_range: Option<String>,
     pub workspaces: Option<Vec<PathBuf>>,
 
+    // Curation (persisted per output directory across re-exports)
+    pub curation: Curation,
+    /// Export message vectors for semantic search in the viewer
+    pub include_vectors: bool,
+
     // Security configuration
+    /// Publish unencrypted (no password); `None` for an encrypted archive
+    pub plaintext: Option<PlaintextMode>,
     pub password: Option<String>,
     pub recovery_secret: Option<Vec<u8>>,
     pub generate_recovery: bool,
@@ -53,6 +78,10 @@ pub struct WizardState {
     pub target: DeployTarget,
     pub output_dir: PathBuf,
     pub repo_name: Option<String>,
+    /// SSH destination, S3 URL or git remote, depending on the target
+    pub destination: Option<String>,
+    pub git_branch: String,
+    pub s3_region: String,
 
     // Database path
     pub db_path: PathBuf,
@@ -68,6 +97,9 @@ impl Default for WizardState {
             agents: Vec::new(),
             time_range: None,
             workspaces: None,
+            curation: Curation::default(),
+            plaintext: None,
+            include_vectors: false,
             password: None,
             recovery_secret: None,
             generate_recovery: true,
@@ -78,6 +110,9 @@ impl Default for WizardState {
             target: DeployTarget::Local,
             output_dir: PathBuf::from("cass-export"),
             repo_name: None,
+            destination: None,
+            git_branch: "pages".to_string(),
+            s3_region: DEFAULT_REGION.to_string(),
             db_path,
         }
     }
@@ -100,6 +135,21 @@ impl PagesWizard {
         }
     }
 
+    /// Publish without encryption in the given mode
+    pub fn plaintext(mut self, mode: Option<PlaintextMode>) -> Self {
+        self.state.plaintext = mode;
+        if mode.is_some() {
+            self.state.description = "Archive of AI coding agent conversations".to_string();
+        }
+        self
+    }
+
+    /// Include message vectors for semantic and hybrid search in the viewer
+    pub fn vectors(mut self, include: bool) -> Self {
+        self.state.include_vectors = include;
+        self
+    }
+
     pub fn run(&mut self) -> Result<()> {
         let mut term = Term::stdout();
         let theme = ColorfulTheme::default();
@@ -268,9 +318,241 @@ impl PagesWizard {
             time_options[time_selection]
         )?;
 
+        // The curation is kept per output directory, so ask for it first
+        self.state.output_dir = PathBuf::from(
+            Input::<String>::with_theme(theme)
+                .with_prompt("Output directory")
+                .default(self.state.output_dir.to_string_lossy().into_owned())
+                .interact_text()?,
+        );
+
+        writeln!(
+            term,
+            "  {} Output: {}",
+            style("✓").green(),
+            self.state.output_dir.display()
+        )?;
+
+        self.step_curation(term, theme)?;
+
+        Ok(())
+    }
+
+    /// Curation file for the chosen output directory
+    fn curation_path(&self) -> PathBuf {
+        let data_dir = self.state.db_path.parent().unwrap_or(Path::new("."));
+        curation_path(data_dir, &self.state.output_dir)
+    }
+
+    /// Per-conversation and per-message selection, tool output and redaction rules
+    ///
+    /// Choices are saved to the output directory's curation file so the next
+    /// re-export to it starts from the same selection.
+    fn step_curation(&mut self, term: &mut Term, theme: &ColorfulTheme) -> Result<()> {
+        let curation_path = self.curation_path();
+        let mut curation = Curation::load_or_default(&curation_path)?;
+        if !curation.is_empty() {
+            writeln!(
+                term,
+                "  {} Loaded curation from {} ({} conversations excluded)",
+                style("✓").green(),
+                curation_path.display(),
+                curation.excluded_conversations.len()
+            )?;
+        }
+
+        let review = Confirm::with_theme(theme)
+            .with_prompt("Review individual conversations before publishing?")
+            .default(false)
+            .interact()?;
+
+        if review {
+            let engine = ExportEngine::new(
+                &self.state.db_path,
+                &self.state.output_dir,
+                self.export_filter(),
+            );
+            let conversations = engine.list_conversations()?;
+            let items: Vec<String> = conversations
+                .iter()
+                .map(|c| {
+                    let date = c
+                        .started_at
+                        .and_then(chrono::DateTime::from_timestamp_millis)
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .unwrap_or_else(|| "----------".to_string());
+                    format!(
+                        "{} {:<12} {} ({} msgs)",
+                        date,
+                        c.agent,
+                        c.title.as_deref().unwrap_or(&c.source_path),
+                        c.message_count
+                    )
+                })
+                .collect();
+            let defaults: Vec<bool> = conversations
+                .iter()
+                .map(|c| !curation.excludes_conversation(&c.source_path))
+                .collect();
+
+            let selected = MultiSelect::with_theme(theme)
+                .with_prompt("Conversations to publish (space to toggle)")
+                .items(&items)
+                .defaults(&defaults)
+                .interact()?;
+
+            for (i, c) in conversations.iter().enumerate() {
+                curation.set_conversation_included(&c.source_path, selected.contains(&i));
+            }
+            writeln!(
+                term,
+                "  {} {} of {} conversations selected",
+                style("✓").green(),
+                selected.len(),
+                conversations.len()
+            )?;
+
+            let selected: Vec<_> = selected.into_iter().map(|i| &conversations[i]).collect();
+            self.pick_messages(term, theme, &engine, &selected, &mut curation)?;
+        }
+
+        curation.drop_tool_output = Confirm::with_theme(theme)
+            .with_prompt("Drop tool outputs (command results, file dumps)?")
+            .default(curation.drop_tool_output)
+            .interact()?;
+
+        let saved_rules = curation
+            .redact_rules
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let rules: String = Input::with_theme(theme)
+            .with_prompt("Redaction rules file (TOML, empty for none)")
+            .with_initial_text(saved_rules)
+            .allow_empty(true)
+            .interact_text()?;
+        curation.redact_rules = None;
+        if !rules.trim().is_empty() {
+            let path = PathBuf::from(rules.trim());
+            // Fail now rather than after the export has started
+            RedactRules::load(&path)?;
+            writeln!(
+                term,
+                "  {} Redaction rules: {}",
+                style("✓").green(),
+                path.display()
+            )?;
+            curation.redact_rules = Some(path);
+        }
+
+        curation.save(&curation_path)?;
+        self.state.curation = curation;
         Ok(())
     }
 
+    /// Let the user leave out single messages of the selected conversations
+    fn pick_messages(
+        &self,
+        term: &mut Term,
+        theme: &ColorfulTheme,
+        engine: &ExportEngine,
+        conversations: &[&ConversationSummary],
+        curation: &mut Curation,
+    ) -> Result<()> {
+        if conversations.is_empty()
+            || !Confirm::with_theme(theme)
+                .with_prompt("Exclude individual messages?")
+                .default(false)
+                .interact()?
+        {
+            return Ok(());
+        }
+
+        let mut items: Vec<String> = conversations
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} ({} msgs)",
+                    c.title.as_deref().unwrap_or(&c.source_path),
+                    c.message_count
+                )
+            })
+            .collect();
+        items.push("Done".to_string());
+
+        loop {
+            let choice = Select::with_theme(theme)
+                .with_prompt("Conversation to edit")
+                .default(items.len() - 1)
+                .items(&items)
+                .interact()?;
+            let Some(conversation) = conversations.get(choice) else {
+                return Ok(());
+            };
+
+            let messages = engine.list_messages(conversation.id)?;
+            let source_path = &conversation.source_path;
+            let labels: Vec<String> = messages
+                .iter()
+                .map(|m| {
+                    let preview: String = m
+                        .content
+                        .lines()
+                        .find(|line| !line.trim().is_empty())
+                        .unwrap_or_default()
+                        .chars()
+                        .take(MESSAGE_PREVIEW_CHARS)
+                        .collect();
+                    format!("{:>4} {:<9} {}", m.idx, m.role, preview.trim())
+                })
+                .collect();
+            let defaults: Vec<bool> = messages
+                .iter()
+                .map(|m| {
+                    !curation
+                        .excluded_messages
+                        .get(source_path)
+                        .is_some_and(|excluded| excluded.contains(&m.idx))
+                })
+                .collect();
+
+            let kept = MultiSelect::with_theme(theme)
+                .with_prompt("Messages to publish (space to toggle)")
+                .items(&labels)
+                .defaults(&defaults)
+                .interact()?;
+            for (i, m) in messages.iter().enumerate() {
+                curation.set_message_included(source_path, m.idx, kept.contains(&i));
+            }
+            writeln!(
+                term,
+                "  {} {} of {} messages kept",
+                style("✓").green(),
+                kept.len(),
+                messages.len()
+            )?;
+        }
+    }
+
+    fn export_filter(&self) -> ExportFilter {
+        let since_dt = self.state.time_range.as_deref().and_then(|s| {
+            crate::ui::time_parser::parse_time_input(s)
+                .and_then(chrono::DateTime::from_timestamp_millis)
+        });
+
+        ExportFilter {
+            agents: Some(self.state.agents.clone()),
+            workspaces: self.state.workspaces.clone(),
+            since: since_dt,
+            until: None,
+            path_mode: if self.state.hide_metadata {
+                PathMode::Hash
+            } else {
+                PathMode::Relative
+            },
+        }
+    }
+
     fn step_security_config(&mut self, term: &mut Term, theme: &ColorfulTheme) -> Result<()> {
         writeln!(
             term,
@@ -279,6 +561,16 @@ impl PagesWizard {
         )?;
         writeln!(term, "{}", style("─".repeat(40)).dim())?;
 
+        if self.state.plaintext.is_some() {
+            self.state.generate_recovery = false;
+            writeln!(
+                term,
+                "  {} Encryption disabled: anyone who can reach the site can read the archive",
+                style("⚠").yellow().bold()
+            )?;
+            return Ok(());
+        }
+
         // Password
         let password = Password::with_theme(theme)
             .with_prompt("Archive password (min 8 characters)")
@@ -398,6 +690,9 @@ impl PagesWizard {
             "Local export only (generate files)",
             "GitHub Pages (requires gh CLI)",
             "Cloudflare Pages (requires wrangler CLI)",
+            "Web server over SSH (rsync, or SFTP)",
+            "S3-compatible storage (AWS S3, MinIO)",
+            "Git remote and branch",
         ];
 
         let target_selection = Select::with_theme(theme)
@@ -409,6 +704,9 @@ impl PagesWizard {
         self.state.target = match target_selection {
             1 => DeployTarget::GitHubPages,
             2 => DeployTarget::CloudflarePages,
+            3 => DeployTarget::SshServer,
+            4 => DeployTarget::S3Compatible,
+            5 => DeployTarget::GitRemote,
             _ => DeployTarget::Local,
         };
 
@@ -419,23 +717,11 @@ impl PagesWizard {
             self.state.target
         )?;
 
-        // Output directory
-        self.state.output_dir = PathBuf::from(
-            Input::<String>::with_theme(theme)
-                .with_prompt("Output directory")
-                .default("cass-export".to_string())
-                .interact_text()?,
-        );
-
-        writeln!(
-            term,
-            "  {} Output: {}",
-            style("✓").green(),
-            self.state.output_dir.display()
-        )?;
-
         // Repository name for remote deployment
-        if self.state.target != DeployTarget::Local {
+        if matches!(
+            self.state.target,
+            DeployTarget::GitHubPages | DeployTarget::CloudflarePages
+        ) {
             let default_repo = format!(
                 "cass-archive-{}",
                 chrono::Utc::now().format("%Y%m%d")
@@ -455,9 +741,89 @@ impl PagesWizard {
             )?;
         }
 
+        self.prompt_destination(theme)?;
+        if let Some(destination) = &self.state.destination {
+            writeln!(term, "  {} Destination: {}", style("✓").green(), destination)?;
+        }
+
         Ok(())
     }
 
+    /// Ask where the SSH, S3 and git targets publish to
+    fn prompt_destination(&mut self, theme: &ColorfulTheme) -> Result<()> {
+        let destination = match self.state.target {
+            DeployTarget::SshServer => Input::<String>::with_theme(theme)
+                .with_prompt("SSH destination ([user@]host:/path)")
+                .validate_with(|input: &String| -> Result<(), String> {
+                    SshDeployer::parse(input).map(|_| ()).map_err(|e| e.to_string())
+                })
+                .interact_text()?,
+            DeployTarget::S3Compatible => {
+                let url = Input::<String>::with_theme(theme)
+                    .with_prompt("Bucket URL (https://host[:port]/bucket[/prefix])")
+                    .interact_text()?;
+                self.state.s3_region = Input::<String>::with_theme(theme)
+                    .with_prompt("Region")
+                    .default(self.state.s3_region.clone())
+                    .interact_text()?;
+                url
+            }
+            DeployTarget::GitRemote => {
+                let remote = Input::<String>::with_theme(theme)
+                    .with_prompt("Git remote URL")
+                    .interact_text()?;
+                self.state.git_branch = Input::<String>::with_theme(theme)
+                    .with_prompt("Branch")
+                    .default(self.state.git_branch.clone())
+                    .interact_text()?;
+                remote
+            }
+            _ => return Ok(()),
+        };
+        self.state.destination = Some(destination);
+        Ok(())
+    }
+
+    /// Deployer for the selected target (`None` for local and Cloudflare)
+    fn deployer(&self) -> Result<Option<Box<dyn Deployer>>> {
+        let destination = self.state.destination.as_deref().unwrap_or_default();
+        Ok(Some(match self.state.target {
+            DeployTarget::GitHubPages => Box::new(
+                GitHubDeployer::new(self.state.repo_name.clone().unwrap_or_default())
+                    .description(&self.state.description),
+            ),
+            DeployTarget::SshServer => Box::new(SshDeployer::parse(destination)?),
+            DeployTarget::S3Compatible => Box::new(
+                S3Deployer::from_url(destination, S3Credentials::from_env()?)?
+                    .region(&self.state.s3_region),
+            ),
+            DeployTarget::GitRemote => Box::new(GitRemoteDeployer::new(
+                destination,
+                &self.state.git_branch,
+            )),
+            DeployTarget::Local | DeployTarget::CloudflarePages => return Ok(None),
+        }))
+    }
+
+    /// The site/ directory to publish, bundling an encrypted export first
+    fn deployable_site(&self) -> Result<PathBuf> {
+        if self.state.plaintext.is_some() {
+            return Ok(self.state.output_dir.join("site"));
+        }
+        let result = BundleBuilder::new()
+            .title(&self.state.title)
+            .description(&self.state.description)
+            .hide_metadata(self.state.hide_metadata)
+            .recovery_secret(self.state.recovery_secret.clone())
+            .generate_qr(self.state.generate_qr)
+            .build(
+                &self.state.output_dir,
+                &self.state.output_dir.join("bundle"),
+                |_, _| {},
+            )?;
+        Ok(result.site_dir)
+    }
+
     fn step_summary(&self, term: &mut Term, theme: &ColorfulTheme) -> Result<bool> {
         writeln!(
             term,
@@ -475,7 +841,22 @@ impl PagesWizard {
         )?;
         writeln!(term, "  Title: {}", self.state.title)?;
         writeln!(term, "  Target: {}", self.state.target)?;
+        if let Some(destination) = &self.state.destination {
+            writeln!(term, "  Destination: {}", destination)?;
+        }
         writeln!(term, "  Output: {}", self.state.output_dir.display())?;
+        writeln!(
+            term,
+            "  Encryption: {}",
+            match self.state.plaintext {
+                None => "Yes",
+                Some(PlaintextMode::Viewer) => "No (public viewer)",
+                Some(PlaintextMode::StaticHtml) => "No (static HTML)",
+            }
+        )?;
+        if self.state.include_vectors {
+            writeln!(term, "  Semantic search: Yes (message vectors included)")?;
+        }
         writeln!(
             term,
             "  Recovery secret: {}",
@@ -499,6 +880,23 @@ impl PagesWizard {
                 "No"
             }
         )?;
+        writeln!(
+            term,
+            "  Excluded conversations: {}",
+            self.state.curation.excluded_conversations.len()
+        )?;
+        writeln!(
+            term,
+            "  Drop tool output: {}",
+            if self.state.curation.drop_tool_output {
+                "Yes"
+            } else {
+                "No"
+            }
+        )?;
+        if let Some(rules) = &self.state.curation.redact_rules {
+            writeln!(term, "  Redaction rules: {}", rules.display())?;
+        }
 
         writeln!(term)?;
 
@@ -529,26 +927,18 @@ impl PagesWizard {
         pb.enable_steady_tick(Duration::from_millis(100));
         pb.set_message("Filtering and exporting conversations...");
 
-        // Build export filter with workspaces
-        let workspaces = self.state.workspaces.clone();
-        let since_dt = self.state.time_range.as_deref().and_then(|s| {
-            crate::ui::time_parser::parse_time_input(s)
-                .and_then(chrono::DateTime::from_timestamp_millis)
-        });
-
-        let filter = ExportFilter {
-            agents: Some(self.state.agents.clone()),
-            workspaces,
-            since: since_dt,
-            until: None,
-            path_mode: if self.state.hide_metadata {
-                PathMode::Hash
-            } else {
-                PathMode::Relative
-            },
+        let redactions = match &self.state.curation.redact_rules {
+            Some(path) => RedactRules::load(path)?,
+            None => RedactRules::default(),
         };
-
-        let engine = ExportEngine::new(&self.state.db_path, &export_db_path, filter);
+        let mut engine =
+            ExportEngine::new(&self.state.db_path, &export_db_path, self.export_filter())
+                .with_curation(self.state.curation.clone())
+                .with_redactions(redactions);
+        if self.state.include_vectors {
+            let data_dir = self.state.db_path.parent().unwrap_or(Path::new("."));
+            engine = engine.with_vectors(VectorSource::for_viewer(data_dir)?);
+        }
         let running = Arc::new(AtomicBool::new(true));
 
         let stats = engine.execute(
@@ -567,6 +957,17 @@ impl PagesWizard {
             "✓ Exported {} conversations, {} messages",
             stats.conversations_processed, stats.messages_processed
         ));
+        if stats.conversations_excluded + stats.messages_excluded + stats.messages_redacted > 0 {
+            writeln!(
+                term,
+                "  Curation excluded {} conversations, {} messages; redacted {} messages",
+                stats.conversations_excluded, stats.messages_excluded, stats.messages_redacted
+            )?;
+        }
+
+        if let Some(mode) = self.state.plaintext {
+            return self.bundle_unencrypted(term, &export_db_path, mode);
+        }
 
         // Phase 2: Encryption
         let pb2 = ProgressBar::new_spinner();
@@ -578,31 +979,49 @@ impl PagesWizard {
         pb2.enable_steady_tick(Duration::from_millis(100));
         pb2.set_message("Encrypting archive...");
 
-        // Initialize encryption engine
-        let mut enc_engine = EncryptionEngine::default();
-
-        // Add password slot
-        if let Some(password) = &self.state.password {
-            enc_engine.add_password_slot(password)?;
-        }
+        // Re-exporting into a previous output keeps its key (and key slots) when
+        // the password still unlocks it, so unchanged chunks are reused as-is
+        let previous = self
+            .state
+            .password
+            .as_deref()
+            .and_then(|password| {
+                load_config(&self.state.output_dir)
+                    .ok()
+                    .map(|config| EncryptionEngine::from_existing(&config, password))
+            })
+            .and_then(Result::ok);
+        let resumed = previous.is_some();
 
-        // Generate and add recovery secret if requested
-        if self.state.generate_recovery {
-            let mut recovery_bytes = [0u8; 32];
-            use rand::RngCore;
-            rand::rngs::OsRng.fill_bytes(&mut recovery_bytes);
-            enc_engine.add_recovery_slot(&recovery_bytes)?;
-            self.state.recovery_secret = Some(recovery_bytes.to_vec());
-        }
+        let mut enc_engine = match previous {
+            Some(engine) => engine,
+            None => {
+                let mut enc_engine = EncryptionEngine::default();
 
-        // Encrypt the database
-        let config = enc_engine.encrypt_file(&export_db_path, &self.state.output_dir, |_, _| {})?;
+                // Add password slot
+                if let Some(password) = &self.state.password {
+                    enc_engine.add_password_slot(password)?;
+                }
+                enc_engine
+            }
+        };
+        let revoked_recovery = self.sync_recovery_slot(&mut enc_engine)?;
 
-        // Write config.json
-        let config_path = self.state.output_dir.join("config.json");
-        std::fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
+        // Encrypt the database (writes config.json)
+        let (_, reuse) = enc_engine.encrypt_file_incremental(
+            &export_db_path,
+            &self.state.output_dir,
+            |_, _| {},
+        )?;
 
-        pb2.finish_with_message("✓ Encryption complete");
+        if resumed {
+            pb2.finish_with_message(format!(
+                "✓ Encryption complete ({} chunks updated, {} unchanged, {} removed)",
+                reuse.written, reuse.reused, reuse.removed
+            ));
+        } else {
+            pb2.finish_with_message("✓ Encryption complete");
+        }
 
         // Phase 3: Bundling (placeholder for now)
         let pb3 = ProgressBar::new_spinner();
@@ -670,6 +1089,22 @@ Then open http://localhost:3000 in a modern browser.
             self.state.output_dir.join("payload").display()
         )?;
 
+        if resumed && self.state.generate_recovery && self.state.recovery_secret.is_none() {
+            writeln!(
+                term,
+                "  {} Updated the existing archive; its recovery secret is unchanged.",
+                style("ℹ").blue()
+            )?;
+        }
+        if revoked_recovery > 0 {
+            writeln!(
+                term,
+                "  {} Removed the archive's recovery slot. Anyone who already unlocked the \
+                 archive still has its key; run `cass pages keys rotate` to re-key it.",
+                style("ℹ").blue()
+            )?;
+        }
+
         // Display recovery secret if generated
         if let Some(recovery) = &self.state.recovery_secret {
             use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
@@ -700,6 +1135,73 @@ Then open http://localhost:3000 in a modern browser.
         Ok(())
     }
 
+    /// Give the archive a recovery slot exactly when one was asked for
+    ///
+    /// A new archive, or a resumed one without a recovery slot, gets a fresh
+    /// secret; a resumed archive keeps its slot (and secret), or loses it
+    /// when recovery was declined. Returns the number of slots removed.
+    fn sync_recovery_slot(&mut self, engine: &mut EncryptionEngine) -> Result<usize> {
+        let recovery_slots: Vec<u8> = engine
+            .key_slots()
+            .iter()
+            .filter(|slot| slot.slot_type == SlotType::Recovery)
+            .map(|slot| slot.id)
+            .collect();
+        if !self.state.generate_recovery {
+            for slot_id in &recovery_slots {
+                engine.remove_slot(*slot_id);
+            }
+            return Ok(recovery_slots.len());
+        }
+        if recovery_slots.is_empty() {
+            let secret = generate_recovery_secret();
+            engine.add_recovery_slot(&secret)?;
+            self.state.recovery_secret = Some(secret);
+        }
+        Ok(0)
+    }
+
+    /// Bundle the export without encryption, in place of phases 2 and 3
+    fn bundle_unencrypted(
+        &self,
+        term: &mut Term,
+        export_db_path: &std::path::Path,
+        mode: PlaintextMode,
+    ) -> Result<()> {
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg}")
+                .unwrap(),
+        );
+        pb.enable_steady_tick(Duration::from_millis(100));
+
+        let built = BundleBuilder::new()
+            .title(&self.state.title)
+            .description(&self.state.description)
+            .hide_metadata(self.state.hide_metadata)
+            .build_unencrypted(export_db_path, &self.state.output_dir, mode, |_, msg| {
+                pb.set_message(msg.to_string())
+            });
+        std::fs::remove_file(export_db_path).ok();
+        let result = built?;
+        pb.finish_with_message(format!("✓ Bundle complete ({} files)", result.total_files));
+
+        writeln!(term)?;
+        writeln!(
+            term,
+            "  {} Unencrypted site: {}",
+            style("✓").green(),
+            result.site_dir.display()
+        )?;
+        writeln!(
+            term,
+            "  {} Anyone who can reach this site can read every conversation in it.",
+            style("⚠").yellow().bold()
+        )?;
+        Ok(())
+    }
+
     fn step_deploy(&self, term: &mut Term) -> Result<()> {
         writeln!(term, "\n{}", style("Step 7 of 7: Deployment").bold())?;
         writeln!(term, "{}", style("─".repeat(40)).dim())?;
@@ -736,26 +1238,6 @@ Then open http://localhost:3000 in a modern browser.
                     style("http://localhost:8080").cyan()
                 )?;
             }
-            DeployTarget::GitHubPages => {
-                writeln!(
-                    term,
-                    "  {} GitHub Pages deployment...",
-                    style("→").cyan()
-                )?;
-
-                // TODO: Actually deploy using pages::deploy_github
-                writeln!(
-                    term,
-                    "  {} GitHub Pages deployment not yet implemented",
-                    style("⚠").yellow()
-                )?;
-                writeln!(term)?;
-                writeln!(
-                    term,
-                    "To deploy manually, push the {} directory to a gh-pages branch.",
-                    self.state.output_dir.display()
-                )?;
-            }
             DeployTarget::CloudflarePages => {
                 writeln!(
                     term,
@@ -776,12 +1258,68 @@ Then open http://localhost:3000 in a modern browser.
                     self.state.output_dir.display()
                 )?;
             }
+            DeployTarget::GitHubPages
+            | DeployTarget::SshServer
+            | DeployTarget::S3Compatible
+            | DeployTarget::GitRemote => {
+                // Deployers may use a blocking HTTP client, which cannot run
+                // on the async runtime's threads
+                std::thread::scope(|scope| scope.spawn(|| self.deploy_site(term)).join())
+                    .map_err(|_| anyhow::anyhow!("Deployment panicked"))??;
+            }
         }
 
         writeln!(term)?;
         Ok(())
     }
 
+    /// Publish the site with the deployer of the selected target
+    fn deploy_site(&self, term: &mut Term) -> Result<()> {
+        let deployer = self
+            .deployer()?
+            .context("No deployer for the selected target")?;
+        writeln!(
+            term,
+            "  {} Deploying to {}...",
+            style("→").cyan(),
+            deployer.destination()
+        )?;
+
+        let site_dir = self.deployable_site()?;
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg}")
+                .unwrap(),
+        );
+        pb.enable_steady_tick(Duration::from_millis(100));
+        let summary = deployer.deploy(&site_dir, &|_, msg| pb.set_message(msg.to_string()));
+        pb.finish_and_clear();
+        let summary = summary?;
+
+        writeln!(
+            term,
+            "{}",
+            style("✓ Deployment complete!").green().bold()
+        )?;
+        writeln!(
+            term,
+            "  {} uploaded, {} removed, {} unchanged{}",
+            summary.uploaded_files,
+            summary.removed_files,
+            summary.unchanged_files,
+            if summary.incremental {
+                " (updated in place)"
+            } else {
+                ""
+            }
+        )?;
+        if let Some(url) = &summary.url {
+            writeln!(term, "  Site: {}", style(url).cyan())?;
+        }
+        Ok(())
+    }
+
     /// Estimate password strength (simple heuristic)
     fn estimate_password_strength(&self, password: &str) -> u8 {
         let mut score = 0u8;
@@ -815,3 +1353,95 @@ Then open http://localhost:3000 in a modern browser.
         score.min(5)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    #[test]
+    fn resumed_archive_recovery_slot_follows_the_choice() -> Result<()> {
+        let recovery_slots = |engine: &EncryptionEngine| {
+            engine
+                .key_slots()
+                .iter()
+                .filter(|slot| slot.slot_type == SlotType::Recovery)
+                .count()
+        };
+
+        // Asking for recovery on an archive that has none adds a slot
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_password_slot("password")?;
+        let mut wizard = PagesWizard::new();
+        assert_eq!(wizard.sync_recovery_slot(&mut engine)?, 0);
+        assert_eq!(recovery_slots(&engine), 1);
+        assert!(wizard.state.recovery_secret.is_some());
+
+        // An existing slot is kept, and no new secret is shown
+        let mut wizard = PagesWizard::new();
+        assert_eq!(wizard.sync_recovery_slot(&mut engine)?, 0);
+        assert_eq!(recovery_slots(&engine), 1);
+        assert!(wizard.state.recovery_secret.is_none());
+        assert_eq!(engine.key_slots().len(), 2);
+
+        // Declining recovery removes it
+        wizard.state.generate_recovery = false;
+        assert_eq!(wizard.sync_recovery_slot(&mut engine)?, 1);
+        assert_eq!(recovery_slots(&engine), 0);
+        assert_eq!(engine.key_slots().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn step_deploy_publishes_from_inside_async_runtime() -> Result<()> {
+        let temp = TempDir::new()?;
+        let remote = temp.path().join("remote.git");
+        std::fs::create_dir_all(&remote)?;
+        git(&remote, &["init", "--bare", "-q"]);
+
+        let database = temp.path().join("export.db");
+        let conn = rusqlite::Connection::open(&database)?;
+        conn.execute_batch(
+            "CREATE TABLE conversations (id INTEGER PRIMARY KEY, agent TEXT NOT NULL,
+                 workspace TEXT, title TEXT, source_path TEXT NOT NULL, started_at INTEGER);
+             CREATE TABLE messages (id INTEGER PRIMARY KEY, conversation_id INTEGER NOT NULL,
+                 idx INTEGER NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL, created_at INTEGER);
+             INSERT INTO conversations VALUES (1, 'claude', 'proj', 'Demo', 'proj/1.jsonl', 0);
+             INSERT INTO messages (conversation_id, idx, role, content) VALUES (1, 0, 'user', 'hello');",
+        )?;
+        drop(conn);
+
+        let output_dir = temp.path().join("out");
+        BundleBuilder::new().build_unencrypted(
+            &database,
+            &output_dir,
+            PlaintextMode::Viewer,
+            |_, _| {},
+        )?;
+
+        let mut wizard = PagesWizard::new();
+        wizard.state.plaintext = Some(PlaintextMode::Viewer);
+        wizard.state.target = DeployTarget::GitRemote;
+        wizard.state.destination = Some(remote.to_string_lossy().into_owned());
+        wizard.state.output_dir = output_dir;
+
+        // `cass pages` runs the wizard on a runtime thread
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async { wizard.step_deploy(&mut Term::buffered_stdout()) })?;
+
+        let files = git(&remote, &["ls-tree", "-r", "--name-only", "pages"]);
+        assert!(files.lines().any(|f| f == "index.html"));
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
  * cass Archive Authentication Module
  *
  * Handles password and QR code authentication for encrypted archives.
+ * Unencrypted archives (config.encrypted === false) skip straight to the viewer.
  * CSP-safe: No inline event handlers, no eval.
  */
 
@@ -51,6 +52,11 @@ async function init() {
         return;
     }
 
+    if (config.encrypted === false) {
+        await openPlaintextArchive();
+        return;
+    }
+
     // Initialize crypto worker
     // Note: Using classic worker (not module) because crypto_worker.js uses importScripts()
     try {
@@ -83,6 +89,8 @@ function cacheElements() {
     elements.qrScanner = document.getElementById('qr-scanner');
     elements.qrReader = document.getElementById('qr-reader');
     elements.qrCancelBtn = document.getElementById('qr-cancel-btn');
+    elements.identityBtn = document.getElementById('identity-btn');
+    elements.identityFile = document.getElementById('identity-file');
     elements.fingerprintValue = document.getElementById('fingerprint-value');
     elements.fingerprintHelp = document.getElementById('fingerprint-help');
     elements.fingerprintTooltip = document.getElementById('fingerprint-tooltip');
@@ -114,6 +122,10 @@ function setupEventListeners() {
     elements.qrBtn?.addEventListener('click', openQrScanner);
     elements.qrCancelBtn?.addEventListener('click', closeQrScanner);
 
+    // Private key (age identity) file
+    elements.identityBtn?.addEventListener('click', () => elements.identityFile?.click());
+    elements.identityFile?.addEventListener('change', handleIdentityFile);
+
     // Fingerprint help tooltip
     elements.fingerprintHelp?.addEventListener('click', toggleFingerprintTooltip);
 
@@ -324,6 +336,34 @@ function handleQrSuccess(decodedText) {
     });
 }
 
+/**
+ * Handle a selected private key file (AGE-SECRET-KEY-1...)
+ */
+async function handleIdentityFile() {
+    const file = elements.identityFile.files?.[0];
+    // Reset so picking the same file again fires another change event
+    elements.identityFile.value = '';
+    if (!file) {
+        return;
+    }
+
+    if (!worker) {
+        showError('Decryption worker not initialized');
+        return;
+    }
+
+    hideError();
+    showProgress('Reading key...');
+    disableForm();
+
+    // The key text only goes to the worker, it is never stored
+    worker.postMessage({
+        type: 'UNLOCK_IDENTITY',
+        identity: await file.text(),
+        config: config,
+    });
+}
+
 /**
  * Handle QR code scan error (called continuously during scanning)
  */
@@ -462,6 +502,35 @@ function transitionToApp() {
     loadViewerModule();
 }
 
+/**
+ * Open an unencrypted archive: no auth screen, no crypto worker
+ */
+async function openPlaintextArchive() {
+    elements.authScreen.classList.add('hidden');
+    elements.appScreen.classList.remove('hidden');
+    // Nothing to lock
+    elements.lockBtn?.classList.add('hidden');
+
+    try {
+        const response = await fetch(`./${config.database.file}`);
+        if (!response.ok) {
+            throw new Error(`HTTP ${response.status}`);
+        }
+        const dbBytes = new Uint8Array(await response.arrayBuffer());
+
+        const { initDatabase } = await import('./database.js');
+        await initDatabase(dbBytes);
+    } catch (error) {
+        console.error('Failed to load archive database:', error);
+        elements.appScreen.classList.add('hidden');
+        elements.authScreen.classList.remove('hidden');
+        showError('Failed to load the archive database.');
+        return;
+    }
+
+    loadViewerModule();
+}
+
 /**
  * Lock the archive (return to auth screen)
  */
@@ -564,6 +633,7 @@ function disableForm() {
     elements.passwordInput.disabled = true;
     elements.unlockBtn.disabled = true;
     elements.qrBtn.disabled = true;
+    elements.identityBtn.disabled = true;
 }
 
 /**
@@ -573,6 +643,7 @@ function enableForm() {
     elements.passwordInput.disabled = false;
     elements.unlockBtn.disabled = false;
     elements.qrBtn.disabled = false;
+    elements.identityBtn.disabled = false;
 }
 
 /**
@@ -6,7 +6,7 @@
  * Uses virtual scrolling for long conversations with 50+ messages.
  */
 
-import { getConversation, getConversationMessages } from './database.js';
+import { getConversation, getConversationMessages, getConversationSnippets } from './database.js';
 import { VariableHeightVirtualList } from './virtual-list.js';
 
 // Virtual scrolling configuration
@@ -16,6 +16,25 @@ const VIRTUAL_CONFIG = {
     OVERSCAN: 3, // Extra items to render above/below viewport
 };
 
+// Snippet language names that differ from their Prism grammar id
+const LANGUAGE_ALIASES = {
+    rs: 'rust',
+    py: 'python',
+    js: 'javascript',
+    ts: 'typescript',
+    sh: 'bash',
+    shell: 'bash',
+    zsh: 'bash',
+    yml: 'yaml',
+    md: 'markdown',
+    'c++': 'cpp',
+    'c#': 'csharp',
+    cs: 'csharp',
+    golang: 'go',
+    rb: 'ruby',
+    kt: 'kotlin',
+};
+
 // DOMPurify configuration for XSS prevention
 const SANITIZE_CONFIG = {
     ALLOWED_TAGS: [
@@ -66,8 +85,18 @@ export async function loadConversation(conversationId, highlightMessageId = null
         return;
     }
 
-    // Load messages
+    // Load messages and attach their code snippets
     currentMessages = getConversationMessages(conversationId);
+    const snippetsByMessage = new Map();
+    for (const snippet of getConversationSnippets(conversationId)) {
+        if (!snippetsByMessage.has(snippet.message_id)) {
+            snippetsByMessage.set(snippet.message_id, []);
+        }
+        snippetsByMessage.get(snippet.message_id).push(snippet);
+    }
+    for (const message of currentMessages) {
+        message.snippets = snippetsByMessage.get(message.id) || [];
+    }
 
     // Render the view
     render(currentConversation, currentMessages, highlightMessageId);
@@ -212,6 +241,7 @@ function createMessageElement(message, index, isHighlighted = false) {
         <div class="message-content">
             ${renderedContent}
         </div>
+        ${renderSnippets(message.snippets)}
     `;
 
     // Apply syntax highlighting after element is created
@@ -281,10 +311,60 @@ function renderMessage(message, index, isHighlighted = false) {
             <div class="message-content">
                 ${renderedContent}
             </div>
+            ${renderSnippets(message.snippets)}
         </article>
     `;
 }
 
+/**
+ * Render the code snippets attached to a message
+ * Each snippet shows its file and line range above the highlighted code.
+ */
+function renderSnippets(snippets) {
+    if (!snippets || snippets.length === 0) return '';
+
+    const blocks = snippets.map(snippet => {
+        const lang = normalizeLanguage(snippet.language);
+        const langAttr = lang ? ` data-language="${lang}"` : '';
+        const caption = [
+            snippet.file_path ? `<code class="snippet-path">${escapeHtml(snippet.file_path)}</code>` : '',
+            formatLineRange(snippet.start_line, snippet.end_line),
+            lang ? `<span class="snippet-language">${escapeHtml(lang)}</span>` : '',
+        ].filter(Boolean).join(' ');
+
+        return `
+            <figure class="code-snippet">
+                ${caption ? `<figcaption>${caption}</figcaption>` : ''}
+                <pre><code${langAttr}>${escapeHtml(snippet.snippet_text || '')}</code></pre>
+            </figure>
+        `;
+    });
+
+    return `<div class="message-snippets">${blocks.join('')}</div>`;
+}
+
+/**
+ * Map a snippet language name onto a Prism grammar id
+ * Returns '' for missing names and anything that is not a plain identifier.
+ */
+function normalizeLanguage(language) {
+    if (!language) return '';
+    const lower = String(language).trim().toLowerCase();
+    const lang = LANGUAGE_ALIASES[lower] || lower;
+    return /^[a-z0-9_+#-]+$/.test(lang) ? lang : '';
+}
+
+/**
+ * Format a snippet line range ("line 4", "lines 4–9")
+ */
+function formatLineRange(start, end) {
+    if (start == null) return '';
+    if (end == null || end === start) {
+        return `<span class="snippet-lines">line ${start}</span>`;
+    }
+    return `<span class="snippet-lines">lines ${start}–${end}</span>`;
+}
+
 /**
  * Set up event listeners
  */
@@ -485,6 +565,16 @@ function formatConversationAsText(conv, messages) {
         lines.push('');
         lines.push(msg.content);
         lines.push('');
+        (msg.snippets || []).forEach(snippet => {
+            const location = snippet.start_line != null
+                ? `${snippet.file_path || ''}:${snippet.start_line}`
+                : (snippet.file_path || '');
+            if (location) lines.push(location);
+            lines.push('```' + normalizeLanguage(snippet.language));
+            lines.push(snippet.snippet_text || '');
+            lines.push('```');
+            lines.push('');
+        });
         lines.push('---');
         lines.push('');
     });
@@ -25,6 +25,10 @@ self.onmessage = async (event) => {
                 await handleUnlockRecovery(data.recoverySecret, data.config);
                 break;
 
+            case 'UNLOCK_IDENTITY':
+                await handleUnlockIdentity(data.identity, data.config);
+                break;
+
             case 'DECRYPT_DATABASE':
                 await handleDecryptDatabase(data.dek, data.config);
                 break;
@@ -39,7 +43,7 @@ self.onmessage = async (event) => {
     } catch (error) {
         console.error('Worker error:', error);
         self.postMessage({
-            type: type.replace('UNLOCK', 'UNLOCK_FAILED').replace('DECRYPT', 'DECRYPT_FAILED'),
+            type: type.startsWith('UNLOCK') ? 'UNLOCK_FAILED' : 'DECRYPT_FAILED',
             error: error.message,
         });
     }
@@ -132,6 +136,190 @@ async function handleUnlockRecovery(recoverySecret, cfg) {
     throw new Error('Invalid recovery code');
 }
 
+/**
+ * Handle X25519 identity (age secret key) unlock
+ */
+async function handleUnlockIdentity(identityText, cfg) {
+    config = cfg;
+
+    const recipientSlots = config.key_slots.filter(s => s.slot_type === 'recipient');
+    if (recipientSlots.length === 0) {
+        throw new Error('No recipient slot found in archive');
+    }
+
+    self.postMessage({ type: 'PROGRESS', phase: 'Reading key...', percent: 10 });
+
+    const identity = await importIdentity(identityText);
+
+    for (const slot of recipientSlots) {
+        if (slot.recipient && !bytesEqual(bech32Decode(slot.recipient, 'age'), identity.publicKey)) {
+            continue;
+        }
+        try {
+            const kek = await deriveKekFromIdentity(identity, slot);
+            self.postMessage({ type: 'PROGRESS', phase: 'Unwrapping key...', percent: 80 });
+
+            const unwrappedDek = await unwrapDek(kek, slot, config.export_id);
+            dek = unwrappedDek;
+
+            self.postMessage({
+                type: 'UNLOCK_SUCCESS',
+                dek: arrayToBase64(dek),
+            });
+            return;
+        } catch (error) {
+            // Try next slot
+            console.debug('Recipient slot unlock failed:', error);
+        }
+    }
+
+    throw new Error('This key cannot unlock the archive');
+}
+
+/**
+ * Import an AGE-SECRET-KEY-1... secret key (or identity file) as an X25519 key
+ */
+async function importIdentity(identityText) {
+    const line = identityText
+        .split(/\r?\n/)
+        .map(l => l.trim())
+        .find(l => l && !l.startsWith('#'));
+    if (!line) {
+        throw new Error('Key file holds no secret key');
+    }
+    const secret = bech32Decode(line, 'age-secret-key-');
+
+    // PKCS#8 wrapper for a raw X25519 private key (RFC 8410)
+    const pkcs8 = new Uint8Array(X25519_PKCS8_PREFIX.length + secret.length);
+    pkcs8.set(X25519_PKCS8_PREFIX);
+    pkcs8.set(secret, X25519_PKCS8_PREFIX.length);
+
+    const privateKey = await crypto.subtle.importKey(
+        'pkcs8',
+        pkcs8,
+        { name: 'X25519' },
+        true,
+        ['deriveBits']
+    );
+    const jwk = await crypto.subtle.exportKey('jwk', privateKey);
+    const publicKey = base64ToArray(jwk.x.replace(/-/g, '+').replace(/_/g, '/') + '=');
+
+    return { privateKey, publicKey };
+}
+
+const X25519_PKCS8_PREFIX = new Uint8Array([
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06,
+    0x03, 0x2b, 0x65, 0x6e, 0x04, 0x22, 0x04, 0x20,
+]);
+
+/**
+ * Derive KEK for a recipient slot: X25519 with the slot's ephemeral key, then HKDF-SHA256
+ */
+async function deriveKekFromIdentity(identity, slot) {
+    const ephemeralPublic = base64ToArray(slot.salt);
+    const ephemeralKey = await crypto.subtle.importKey(
+        'raw',
+        ephemeralPublic,
+        { name: 'X25519' },
+        false,
+        []
+    );
+    const shared = new Uint8Array(await crypto.subtle.deriveBits(
+        { name: 'X25519', public: ephemeralKey },
+        identity.privateKey,
+        256
+    ));
+    if (shared.every(b => b === 0)) {
+        throw new Error('Invalid ephemeral key');
+    }
+
+    // Salt: ephemeral_public || recipient_public
+    const salt = new Uint8Array(64);
+    salt.set(ephemeralPublic);
+    salt.set(identity.publicKey, 32);
+
+    const baseKey = await crypto.subtle.importKey('raw', shared, 'HKDF', false, ['deriveBits']);
+    const kekBits = await crypto.subtle.deriveBits(
+        {
+            name: 'HKDF',
+            hash: 'SHA-256',
+            salt: salt,
+            info: new TextEncoder().encode('cass-pages-x25519-v1'),
+        },
+        baseKey,
+        256
+    );
+
+    return new Uint8Array(kekBits);
+}
+
+const BECH32_CHARSET = 'qpzry9x8gf2tvdw0s3jn54khce6mua7l';
+
+/**
+ * Decode a bech32 string with the expected human-readable part into bytes
+ */
+function bech32Decode(text, expectedHrp) {
+    const lower = text.toLowerCase();
+    if (lower !== text && text.toUpperCase() !== text) {
+        throw new Error('Invalid key: mixed case');
+    }
+    const sep = lower.lastIndexOf('1');
+    const hrp = lower.slice(0, sep);
+    if (sep < 1 || hrp !== expectedHrp) {
+        throw new Error(`Invalid key: expected ${expectedHrp.toUpperCase()}1...`);
+    }
+    const values = [];
+    for (const ch of lower.slice(sep + 1)) {
+        const v = BECH32_CHARSET.indexOf(ch);
+        if (v === -1) {
+            throw new Error('Invalid key: bad character');
+        }
+        values.push(v);
+    }
+
+    const expanded = [];
+    for (const ch of hrp) expanded.push(ch.charCodeAt(0) >> 5);
+    expanded.push(0);
+    for (const ch of hrp) expanded.push(ch.charCodeAt(0) & 31);
+    if (values.length < 6 || bech32Polymod(expanded.concat(values)) !== 1) {
+        throw new Error('Invalid key: checksum mismatch');
+    }
+
+    // Regroup 5-bit values into bytes
+    const bytes = [];
+    let acc = 0;
+    let bits = 0;
+    for (const v of values.slice(0, -6)) {
+        acc = ((acc << 5) | v) & 0xfff;
+        bits += 5;
+        if (bits >= 8) {
+            bits -= 8;
+            bytes.push((acc >> bits) & 0xff);
+        }
+    }
+    if (bytes.length !== 32) {
+        throw new Error('Invalid key: expected 32 bytes');
+    }
+    return new Uint8Array(bytes);
+}
+
+function bech32Polymod(values) {
+    const gen = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let chk = 1;
+    for (const v of values) {
+        const top = chk >>> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ v;
+        for (let i = 0; i < 5; i++) {
+            if ((top >>> i) & 1) chk ^= gen[i];
+        }
+    }
+    return chk >>> 0;
+}
+
+function bytesEqual(a, b) {
+    return a.length === b.length && a.every((v, i) => v === b[i]);
+}
+
 /**
  * Derive KEK from password using Argon2id
  */
@@ -235,6 +423,8 @@ async function handleDecryptDatabase(dekBase64, cfg) {
     const totalChunks = payload.chunk_count;
     const baseNonce = base64ToArray(config.base_nonce);
     const exportId = base64ToArray(config.export_id);
+    // Schema v3 chunks are content-addressed and carry their own nonce
+    const contentAddressed = config.version >= 3;
 
     self.postMessage({ type: 'PROGRESS', phase: 'Decrypting...', percent: 0 });
 
@@ -247,13 +437,14 @@ async function handleDecryptDatabase(dekBase64, cfg) {
         ['decrypt']
     );
 
-    // Decrypt each chunk
+    // Decrypt and decompress each chunk (every chunk is its own deflate stream)
     const decryptedChunks = [];
     let totalDecrypted = 0;
 
     for (let i = 0; i < totalChunks; i++) {
-        const chunkName = `chunk-${String(i).padStart(5, '0')}.bin`;
-        const chunkUrl = `./payload/${chunkName}`;
+        const chunkUrl = payload.files && payload.files[i]
+            ? `./${payload.files[i]}`
+            : `./payload/chunk-${String(i).padStart(5, '0')}.bin`;
 
         try {
             const response = await fetch(chunkUrl);
@@ -262,11 +453,19 @@ async function handleDecryptDatabase(dekBase64, cfg) {
             }
             const encryptedChunk = await response.arrayBuffer();
 
-            // Derive chunk nonce: first 8 bytes from base_nonce, last 4 bytes are counter
-            const chunkNonce = deriveChunkNonce(baseNonce, i);
-
-            // Build chunk AAD: export_id || chunk_index (big-endian u32)
-            const aad = buildChunkAad(exportId, i);
+            let chunkNonce;
+            let aad;
+            if (contentAddressed) {
+                const chunk = payload.chunks[i];
+                chunkNonce = base64ToArray(chunk.nonce);
+                // Build chunk AAD: export_id || chunk_index || chunk_id || schema_version
+                aad = buildContentChunkAad(exportId, i, hexToArray(chunk.id));
+            } else {
+                // Derive chunk nonce: first 8 bytes from base_nonce, last 4 bytes are counter
+                chunkNonce = deriveChunkNonce(baseNonce, i);
+                // Build chunk AAD: export_id || chunk_index (big-endian u32)
+                aad = buildChunkAad(exportId, i);
+            }
 
             // Decrypt chunk
             const decrypted = await crypto.subtle.decrypt(
@@ -279,8 +478,11 @@ async function handleDecryptDatabase(dekBase64, cfg) {
                 encryptedChunk
             );
 
-            decryptedChunks.push(new Uint8Array(decrypted));
-            totalDecrypted += decrypted.byteLength;
+            const plain = config.compression === 'deflate'
+                ? await decompressDeflate(new Uint8Array(decrypted))
+                : new Uint8Array(decrypted);
+            decryptedChunks.push(plain);
+            totalDecrypted += plain.byteLength;
 
             // Report progress
             const percent = Math.round(((i + 1) / totalChunks) * 90);
@@ -294,19 +496,8 @@ async function handleDecryptDatabase(dekBase64, cfg) {
         }
     }
 
-    self.postMessage({ type: 'PROGRESS', phase: 'Decompressing...', percent: 92 });
-
     // Concatenate chunks
-    const compressed = concatenateChunks(decryptedChunks);
-
-    // Decompress
-    let decompressed;
-    if (config.compression === 'deflate') {
-        decompressed = await decompressDeflate(compressed);
-    } else {
-        // No compression
-        decompressed = compressed;
-    }
+    const decompressed = concatenateChunks(decryptedChunks);
 
     self.postMessage({ type: 'PROGRESS', phase: 'Loading database...', percent: 95 });
 
@@ -342,7 +533,7 @@ function deriveChunkNonce(baseNonce, counter) {
 }
 
 /**
- * Build chunk AAD: export_id || chunk_index || schema_version
+ * Build legacy (schema v2) chunk AAD: export_id || chunk_index || schema_version
  * Must match Rust's build_chunk_aad for interoperability
  */
 function buildChunkAad(exportId, chunkIndex) {
@@ -360,6 +551,35 @@ function buildChunkAad(exportId, chunkIndex) {
     return aad;
 }
 
+/**
+ * Build content-addressed chunk AAD: export_id || chunk_index || chunk_id || schema_version
+ * Must match Rust's build_content_chunk_aad for interoperability
+ */
+function buildContentChunkAad(exportId, chunkIndex, chunkId) {
+    const SCHEMA_VERSION = 3;
+    const aad = new Uint8Array(exportId.length + 4 + chunkId.length + 1); // 16 + 4 + 32 + 1 = 53 bytes
+    aad.set(exportId);
+
+    // Big-endian u32 chunk index
+    const view = new DataView(aad.buffer, exportId.length, 4);
+    view.setUint32(0, chunkIndex, false);
+
+    aad.set(chunkId, exportId.length + 4);
+    aad[exportId.length + 4 + chunkId.length] = SCHEMA_VERSION;
+    return aad;
+}
+
+/**
+ * Convert hex string to Uint8Array
+ */
+function hexToArray(hex) {
+    const bytes = new Uint8Array(hex.length / 2);
+    for (let i = 0; i < bytes.length; i++) {
+        bytes[i] = parseInt(hex.substr(i * 2, 2), 16);
+    }
+    return bytes;
+}
+
 /**
  * Concatenate array of Uint8Arrays
  */
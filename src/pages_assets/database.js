@@ -230,6 +230,77 @@ export function getConversationMessages(convId) {
     `, [convId]);
 }
 
+/**
+ * Get code snippets attached to a conversation's messages
+ * Archives exported before snippets were included have no snippets table.
+ * @param {number} convId - Conversation ID
+ * @returns {Array<Object>} Snippet objects, in message order
+ */
+export function getConversationSnippets(convId) {
+    const hasTable = queryValue(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'snippets'"
+    );
+    if (!hasTable) {
+        return [];
+    }
+    return queryAll(`
+        SELECT s.id, s.message_id, s.file_path, s.language, s.start_line, s.end_line, s.snippet_text
+        FROM snippets s
+        JOIN messages m ON s.message_id = m.id
+        WHERE m.conversation_id = ?
+        ORDER BY m.idx ASC, s.id ASC
+    `, [convId]);
+}
+
+/**
+ * Get the exported message vectors with each message's agent
+ * Only archives exported with --vectors have the message_vectors table.
+ * @returns {Array<Object>} Rows of message_id, scale, vector (Uint8Array), agent
+ */
+export function getMessageVectors() {
+    const hasTable = queryValue(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'message_vectors'"
+    );
+    if (!hasTable) {
+        return [];
+    }
+    return queryAll(`
+        SELECT v.message_id, v.scale, v.vector, c.agent
+        FROM message_vectors v
+        JOIN messages m ON v.message_id = m.id
+        JOIN conversations c ON m.conversation_id = c.id
+        ORDER BY v.message_id
+    `);
+}
+
+/**
+ * Get search-result rows for messages found without FTS5
+ * Rows have the same fields as searchConversations() results, with the
+ * start of the message as an unhighlighted preview instead of a snippet.
+ * @param {Array<number>} messageIds - Message IDs
+ * @returns {Array<Object>} Result rows (in no particular order)
+ */
+export function getMessageResults(messageIds) {
+    if (messageIds.length === 0) {
+        return [];
+    }
+    const placeholders = messageIds.map(() => '?').join(', ');
+    return queryAll(`
+        SELECT
+            m.conversation_id,
+            m.id as message_id,
+            m.role,
+            substr(m.content, 1, 200) as preview,
+            c.agent,
+            c.workspace,
+            c.title,
+            c.started_at
+        FROM messages m
+        JOIN conversations c ON m.conversation_id = c.id
+        WHERE m.id IN (${placeholders})
+    `, messageIds);
+}
+
 /**
  * Detect if query looks like code (for FTS table routing)
  * @param {string} query - Search query
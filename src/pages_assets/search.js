@@ -2,7 +2,8 @@
  * cass Archive Search UI Component
  *
  * Provides search interface with query input, filters, and result rendering.
- * Uses FTS5 for full-text search with intelligent query routing.
+ * Uses FTS5 for full-text search with intelligent query routing, fused with
+ * vector search when the archive was exported with message vectors.
  */
 
 import {
@@ -11,7 +12,14 @@ import {
     getRecentConversations,
     getConversationsByAgent,
     getConversationsByTimeRange,
+    getMessageResults,
 } from './database.js';
+import {
+    initSemanticSearch,
+    semanticSearch,
+    hybridCandidateCount,
+    rrfFuse,
+} from './semantic.js';
 import { VirtualList } from './virtual-list.js';
 
 // Search configuration
@@ -28,6 +36,7 @@ const SEARCH_CONFIG = {
 
 // Module state
 let currentQuery = '';
+let currentMode = 'lexical';
 let currentFilters = {
     agent: null,
     since: null,
@@ -45,6 +54,8 @@ let elements = {
     searchInput: null,
     agentFilter: null,
     timeFilter: null,
+    modeGroup: null,
+    modeFilter: null,
     resultsContainer: null,
     resultsList: null,
     loadingIndicator: null,
@@ -114,6 +125,15 @@ function renderSearchUI() {
                         <option value="year">Past year</option>
                     </select>
                 </div>
+
+                <div id="mode-filter-group" class="filter-group hidden">
+                    <label for="mode-filter">Mode</label>
+                    <select id="mode-filter" class="filter-select">
+                        <option value="hybrid">Hybrid</option>
+                        <option value="semantic">Semantic</option>
+                        <option value="lexical">Keyword</option>
+                    </select>
+                </div>
             </div>
 
             <div class="search-results">
@@ -140,6 +160,8 @@ function cacheElements() {
     elements.searchInput = document.getElementById('search-input');
     elements.agentFilter = document.getElementById('agent-filter');
     elements.timeFilter = document.getElementById('time-filter');
+    elements.modeGroup = document.getElementById('mode-filter-group');
+    elements.modeFilter = document.getElementById('mode-filter');
     elements.resultsContainer = elements.container.querySelector('.search-results');
     elements.resultsList = document.getElementById('results-list');
     elements.loadingIndicator = document.getElementById('loading-indicator');
@@ -185,6 +207,12 @@ function setupEventListeners() {
         handleSearch(currentQuery);
     });
 
+    // Search mode (only shown for archives with message vectors)
+    elements.modeFilter.addEventListener('change', (e) => {
+        currentMode = e.target.value;
+        handleSearch(currentQuery);
+    });
+
     // Result click delegation
     elements.resultsList.addEventListener('click', (e) => {
         const resultCard = e.target.closest('.result-card');
@@ -227,6 +255,16 @@ async function populateFilters() {
     } catch (error) {
         console.error('[Search] Failed to populate filters:', error);
     }
+
+    try {
+        if (initSemanticSearch()) {
+            currentMode = 'hybrid';
+            elements.modeFilter.value = currentMode;
+            elements.modeGroup.classList.remove('hidden');
+        }
+    } catch (error) {
+        console.error('[Search] Failed to load message vectors:', error);
+    }
 }
 
 /**
@@ -285,7 +323,7 @@ async function handleSearch(query) {
 }
 
 /**
- * Perform FTS5 search
+ * Perform FTS5, vector or hybrid search depending on the mode
  */
 async function performSearch() {
     const options = {
@@ -294,8 +332,28 @@ async function performSearch() {
         agent: currentFilters.agent,
     };
 
-    // Pass raw query - searchConversations handles escaping and FTS table routing
-    currentResults = searchConversations(currentQuery, options);
+    if (currentMode === 'lexical') {
+        // Pass raw query - searchConversations handles escaping and FTS table routing
+        currentResults = searchConversations(currentQuery, options);
+    } else if (currentMode === 'semantic') {
+        const hits = await semanticSearch(currentQuery, {
+            limit: options.limit + options.offset,
+            agent: options.agent,
+        });
+        currentResults = attachMessageDetails(hits.slice(options.offset));
+    } else {
+        const candidates = hybridCandidateCount(options.limit, options.offset);
+        const lexical = searchConversations(currentQuery, {
+            limit: candidates,
+            offset: 0,
+            agent: options.agent,
+        });
+        const semantic = await semanticSearch(currentQuery, {
+            limit: candidates,
+            agent: options.agent,
+        });
+        currentResults = attachMessageDetails(rrfFuse(lexical, semantic, options));
+    }
 
     // Apply time filter post-query if needed
     if (currentFilters.since || currentFilters.until) {
@@ -310,6 +368,24 @@ async function performSearch() {
     renderResults();
 }
 
+/**
+ * Fill in conversation details for hits that only have a message id
+ * Vector-only hits get the escaped start of the message as their snippet.
+ */
+function attachMessageDetails(hits) {
+    const missing = hits.filter(hit => hit.conversation_id === undefined);
+    const rows = new Map(
+        getMessageResults(missing.map(hit => hit.message_id)).map(row => [row.message_id, row])
+    );
+    return hits
+        .map(hit => {
+            if (hit.conversation_id !== undefined) return hit;
+            const row = rows.get(hit.message_id);
+            return row && { ...row, ...hit, snippet: escapeHtml(row.preview) };
+        })
+        .filter(Boolean);
+}
+
 /**
  * Load recent conversations (no search query)
  */
@@ -644,6 +720,10 @@ export function clearSearch() {
     if (elements.timeFilter) {
         elements.timeFilter.value = '';
     }
+    if (elements.modeFilter && !elements.modeGroup.classList.contains('hidden')) {
+        currentMode = 'hybrid';
+        elements.modeFilter.value = currentMode;
+    }
 
     loadRecentConversations();
 }
@@ -654,6 +734,7 @@ export function clearSearch() {
 export function getSearchState() {
     return {
         query: currentQuery,
+        mode: currentMode,
         filters: { ...currentFilters },
         resultCount: currentResults.length,
     };
@@ -0,0 +1,243 @@
+/**
+ * cass Archive Semantic Search
+ *
+ * Vector search over the message vectors exported with --vectors, and
+ * Reciprocal Rank Fusion with the FTS5 results for hybrid mode.
+ *
+ * Queries are embedded in the browser. The FNV-1a hash embedder used by cass
+ * is ported here; archives exported with another embedder need a matching
+ * in-browser model registered through registerQueryEmbedder().
+ */
+
+import { getExportMeta, getMessageVectors } from './database.js';
+
+// Fusion configuration (matches cass hybrid search defaults)
+const FUSION_CONFIG = {
+    RRF_K: 60,
+    SEMANTIC_WEIGHT: 0.5,
+    CANDIDATE_MULTIPLIER: 3,
+};
+
+// FNV-1a (64-bit) constants of the hash embedder
+const FNV_OFFSET_BASIS = 0xcbf29ce484222325n;
+const FNV_PRIME = 0x100000001b3n;
+const U64_MASK = (1n << 64n) - 1n;
+const MIN_TOKEN_BYTES = 2;
+const MAX_QUERY_CHARS = 2000;
+
+const utf8 = new TextEncoder();
+
+// Query embedders by embedder id prefix; the hash embedder is built in
+const queryEmbedders = [
+    {
+        prefix: 'fnv1a-',
+        embed: (text, dimension) => hashEmbed(text, dimension),
+    },
+];
+
+// Module state
+let vectorIndex = null;
+
+/**
+ * Register a query embedder for archives exported with another embedder
+ * @param {string} prefix - Embedder id prefix it handles (e.g. 'minilm-')
+ * @param {Function} embed - (text, dimension) => Float32Array or a Promise of one
+ */
+export function registerQueryEmbedder(prefix, embed) {
+    queryEmbedders.unshift({ prefix, embed });
+    vectorIndex = null;
+}
+
+/**
+ * Embed text with the cass hash embedder
+ * Lowercase, split on non-alphanumerics, drop tokens under 2 bytes, add
+ * ±1 per token at FNV-1a(token) mod dimension, then L2-normalize.
+ * @param {string} text - Text to embed
+ * @param {number} dimension - Vector dimension
+ * @returns {Float32Array} Unit-length vector
+ */
+export function hashEmbed(text, dimension) {
+    const vector = new Float32Array(dimension);
+    const tokens = text
+        .toLowerCase()
+        .split(/[^\p{Alphabetic}\p{N}]/u)
+        .filter(token => utf8.encode(token).length >= MIN_TOKEN_BYTES);
+
+    if (tokens.length === 0) {
+        vector.fill(1 / Math.sqrt(dimension));
+        return normalize(vector);
+    }
+
+    const dim = BigInt(dimension);
+    for (const token of tokens) {
+        let hash = FNV_OFFSET_BASIS;
+        for (const byte of utf8.encode(token)) {
+            hash ^= BigInt(byte);
+            hash = (hash * FNV_PRIME) & U64_MASK;
+        }
+        const index = Number(hash % dim);
+        vector[index] += (hash >> 63n) === 0n ? 1 : -1;
+    }
+    return normalize(vector);
+}
+
+/**
+ * Normalize a query the way cass canonicalizes text before embedding
+ * (plain-text subset: NFC, collapsed whitespace, length cap)
+ */
+function canonicalizeQuery(query) {
+    return query.normalize('NFC').replace(/\s+/g, ' ').trim().slice(0, MAX_QUERY_CHARS);
+}
+
+function normalize(vector) {
+    let norm = 0;
+    for (const value of vector) norm += value * value;
+    norm = Math.sqrt(norm);
+    if (norm > Number.EPSILON) {
+        for (let i = 0; i < vector.length; i++) vector[i] /= norm;
+    }
+    return vector;
+}
+
+/**
+ * Load the exported vectors, if the archive has them and their embedder
+ * can run in the browser
+ * @returns {boolean} True when semantic search is available
+ */
+export function initSemanticSearch() {
+    const meta = getExportMeta();
+    const embedderId = meta.vector_embedder;
+    const dimension = parseInt(meta.vector_dimension, 10);
+    if (!embedderId || !dimension || meta.vector_quantization !== 'int8') {
+        return false;
+    }
+    const embedder = queryEmbedders.find(e => embedderId.startsWith(e.prefix));
+    if (!embedder) {
+        console.warn(`[Semantic] No query embedder for ${embedderId}`);
+        return false;
+    }
+
+    const rows = getMessageVectors();
+    const vectors = new Int8Array(rows.length * dimension);
+    rows.forEach((row, i) => {
+        const bytes = row.vector;
+        vectors.set(new Int8Array(bytes.buffer, bytes.byteOffset, dimension), i * dimension);
+    });
+
+    vectorIndex = {
+        dimension,
+        embed: embedder.embed,
+        messageIds: rows.map(row => row.message_id),
+        agents: rows.map(row => row.agent),
+        scales: Float32Array.from(rows, row => row.scale),
+        vectors,
+    };
+    console.log(`[Semantic] Loaded ${rows.length} vectors (${embedderId})`);
+    return rows.length > 0;
+}
+
+/**
+ * Whether semantic and hybrid modes are available
+ */
+export function isSemanticAvailable() {
+    return vectorIndex !== null && vectorIndex.messageIds.length > 0;
+}
+
+/**
+ * Rank messages by cosine similarity to the query
+ * @param {string} query - Search query
+ * @param {Object} options - { limit, agent }
+ * @returns {Promise<Array<Object>>} { message_id, score }, best first
+ */
+export async function semanticSearch(query, options = {}) {
+    const { limit = 50, agent = null } = options;
+    if (!vectorIndex) return [];
+
+    const canonical = canonicalizeQuery(query);
+    if (!canonical) return [];
+
+    const { dimension, messageIds, agents, scales, vectors } = vectorIndex;
+    const queryVector = await vectorIndex.embed(canonical, dimension);
+
+    const scored = [];
+    for (let row = 0; row < messageIds.length; row++) {
+        if (agent && agents[row] !== agent) continue;
+        let dot = 0;
+        const base = row * dimension;
+        for (let i = 0; i < dimension; i++) {
+            dot += queryVector[i] * vectors[base + i];
+        }
+        scored.push({ message_id: messageIds[row], score: dot * scales[row] });
+    }
+
+    scored.sort((a, b) => b.score - a.score || a.message_id - b.message_id);
+    return scored.slice(0, limit);
+}
+
+/**
+ * Number of candidates to fetch from each list for one page of hybrid results
+ */
+export function hybridCandidateCount(limit, offset) {
+    return (limit + offset) * FUSION_CONFIG.CANDIDATE_MULTIPLIER;
+}
+
+/**
+ * Fuse lexical and semantic hits with Reciprocal Rank Fusion
+ * Mirrors cass rrf_fuse_hits: each list contributes 2w/(k + rank), hits in
+ * both lists win ties, then lower message id; lexical hit details are kept.
+ * @param {Array<Object>} lexical - Lexical hits (with message_id), best first
+ * @param {Array<Object>} semantic - Semantic hits (with message_id), best first
+ * @param {Object} options - { limit, offset, k, semanticWeight }
+ * @returns {Array<Object>} Fused hits with score and fusion ranks
+ */
+export function rrfFuse(lexical, semantic, options = {}) {
+    const {
+        limit = 50,
+        offset = 0,
+        k = FUSION_CONFIG.RRF_K,
+        semanticWeight = FUSION_CONFIG.SEMANTIC_WEIGHT,
+    } = options;
+    if (limit === 0) return [];
+
+    const weight = Math.min(Math.max(semanticWeight, 0), 1);
+    const fused = new Map();
+    const entry = (hit) => {
+        if (!fused.has(hit.message_id)) {
+            fused.set(hit.message_id, { hit, score: 0, lexicalRank: null, semanticRank: null });
+        }
+        return fused.get(hit.message_id);
+    };
+
+    lexical.forEach((hit, rank) => {
+        const e = entry(hit);
+        e.hit = hit;
+        e.score += (2 * (1 - weight)) / (k + rank + 1);
+        e.lexicalRank = rank + 1;
+    });
+    semantic.forEach((hit, rank) => {
+        const e = entry(hit);
+        e.score += (2 * weight) / (k + rank + 1);
+        e.semanticRank = rank + 1;
+    });
+
+    const both = e => e.lexicalRank !== null && e.semanticRank !== null;
+    return [...fused.values()]
+        .sort((a, b) => b.score - a.score
+            || Number(both(b)) - Number(both(a))
+            || a.hit.message_id - b.hit.message_id)
+        .slice(offset, offset + limit)
+        .map(e => ({
+            ...e.hit,
+            score: e.score,
+            fusion: { lexical_rank: e.lexicalRank, semantic_rank: e.semanticRank },
+        }));
+}
+
+export default {
+    initSemanticSearch,
+    isSemanticAvailable,
+    semanticSearch,
+    rrfFuse,
+    hashEmbed,
+    registerQueryEmbedder,
+};
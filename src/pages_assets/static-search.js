@@ -0,0 +1,126 @@
+/**
+ * cass Static Archive Search
+ *
+ * Client-side search over the prebuilt search-index.json of a pre-rendered
+ * (unencrypted) archive. The pages work without this script; it only adds
+ * the search box on the index page.
+ * CSP-safe: No inline event handlers, no eval.
+ */
+
+(function () {
+    'use strict';
+
+    const MIN_TOKEN = 2;
+    const MAX_TOKEN = 40;
+    const MAX_RESULTS = 200;
+
+    let index = null;
+    let termKeys = null;
+
+    /**
+     * Split text into lowercase index tokens (mirrors the Rust indexer)
+     */
+    function tokenize(text) {
+        return text
+            .toLowerCase()
+            .split(/[^\p{L}\p{N}_]+/u)
+            .filter(t => t.length >= MIN_TOKEN && t.length <= MAX_TOKEN);
+    }
+
+    /**
+     * Documents containing a term starting with `prefix`
+     */
+    function lookup(prefix) {
+        const docs = new Set();
+        for (const term of termKeys) {
+            if (term.startsWith(prefix)) {
+                for (const doc of index.terms[term]) {
+                    docs.add(doc);
+                }
+            }
+        }
+        return docs;
+    }
+
+    /**
+     * Documents matching every query token (last token as a prefix)
+     */
+    function search(query) {
+        const tokens = tokenize(query);
+        if (tokens.length === 0) {
+            return null;
+        }
+        let result = null;
+        tokens.forEach((token, i) => {
+            const docs = i === tokens.length - 1
+                ? lookup(token)
+                : new Set(index.terms[token] || []);
+            result = result === null
+                ? docs
+                : new Set([...result].filter(d => docs.has(d)));
+        });
+        return [...result].sort((a, b) => a - b).slice(0, MAX_RESULTS);
+    }
+
+    function renderResults(container, docs) {
+        container.replaceChildren();
+        if (docs.length === 0) {
+            const empty = document.createElement('p');
+            empty.className = 'empty';
+            empty.textContent = 'No matching conversations';
+            container.appendChild(empty);
+            return;
+        }
+        const list = document.createElement('ul');
+        list.className = 'conversation-list';
+        for (const i of docs) {
+            const doc = index.docs[i];
+            const item = document.createElement('li');
+            const link = document.createElement('a');
+            link.href = doc.url;
+            link.textContent = doc.title;
+            const meta = document.createElement('span');
+            meta.className = 'meta';
+            meta.textContent = `${doc.date} · ${doc.agent}`;
+            item.append(link, meta);
+            list.appendChild(item);
+        }
+        container.appendChild(list);
+    }
+
+    async function init() {
+        const input = document.getElementById('search-input');
+        const results = document.getElementById('search-results');
+        const all = document.getElementById('conversations');
+        if (!input || !results || !all) {
+            return;
+        }
+
+        try {
+            const response = await fetch('./search-index.json');
+            if (!response.ok) {
+                throw new Error(`HTTP ${response.status}`);
+            }
+            index = await response.json();
+            termKeys = Object.keys(index.terms);
+        } catch (error) {
+            console.error('[Search] Failed to load index:', error);
+            return;
+        }
+
+        input.disabled = false;
+        input.addEventListener('input', () => {
+            const docs = search(input.value);
+            if (docs === null) {
+                results.classList.add('hidden');
+                all.classList.remove('hidden');
+                return;
+            }
+            renderResults(results, docs);
+            all.classList.add('hidden');
+            results.classList.remove('hidden');
+        });
+    }
+
+    document.addEventListener('DOMContentLoaded', init);
+})();
@@ -15,6 +15,7 @@ const STATIC_ASSETS = [
     './styles.css',
     './viewer.js',
     './search.js',
+    './semantic.js',
     './database.js',
     './vendor/sqlite3.js',
     './vendor/sqlite3.wasm',
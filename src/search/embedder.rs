@@ -19,7 +19,7 @@
 //! use crate::search::embedder::{Embedder, EmbedderError};
 //!
 //! fn search_with_embedder(embedder: &dyn Embedder, query: &str) -> Result<(), EmbedderError> {
-//!     let embedding = embedder.embed(query)?;
+//!     let embedding = embedder.embed_query(query)?;
 //!     println!("Embedding dimension: {}", embedding.len());
 //!     println!("Embedder: {} (semantic: {})", embedder.id(), embedder.is_semantic());
 //!     Ok(())
@@ -91,6 +91,16 @@ pub trait Embedder: Send + Sync {
     /// - [`EmbedderError::EmbeddingFailed`] if embedding fails for any other reason.
     fn embed(&self, text: &str) -> EmbedderResult<Vec<f32>>;
 
+    /// Embed a search query.
+    ///
+    /// [`embed()`](Self::embed) and [`embed_batch()`](Self::embed_batch) embed the
+    /// documents that get indexed. Models trained with distinct query and document
+    /// inputs (such as Nomic's `search_query: ` / `search_document: ` prefixes)
+    /// override this; the default embeds queries like documents.
+    fn embed_query(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+        self.embed(text)
+    }
+
     /// Embed multiple texts in a batch.
     ///
     /// Batch embedding can be significantly faster than calling [`embed()`](Self::embed)
@@ -244,6 +254,18 @@ mod tests {
         assert!(embedder.is_semantic());
     }
 
+    #[test]
+    fn test_embedder_query_defaults_to_document_embedding() {
+        let embedder = MockEmbedder {
+            dimension: 8,
+            is_semantic: true,
+        };
+        assert_eq!(
+            embedder.embed_query("find the bug").unwrap(),
+            embedder.embed("find the bug").unwrap()
+        );
+    }
+
     #[test]
     fn test_embedder_batch() {
         let embedder = MockEmbedder {
@@ -1,6 +1,7 @@
-//! FastEmbed-based ML embedder (MiniLM).
+//! FastEmbed-based ML embedder.
 //!
 //! Loads a local ONNX model + tokenizer bundle and produces semantic embeddings.
+//! MiniLM is the default; any model in the registry can be loaded from its manifest.
 //! This implementation never downloads model assets; it expects the model files
 //! to be present on disk and returns a clear error when they are missing.
 
@@ -13,11 +14,11 @@ use fastembed::{
 };
 
 use super::embedder::{Embedder, EmbedderError, EmbedderResult};
+use super::model_download::{ModelManifest, ModelPooling};
 
 const MODEL_ID: &str = "all-minilm-l6-v2";
 const MODEL_DIR_NAME: &str = "all-MiniLM-L6-v2";
 const EMBEDDER_ID: &str = "minilm-384";
-const EMBEDDING_DIMENSION: usize = 384;
 
 const MODEL_FILE: &str = "model.onnx";
 const TOKENIZER_JSON: &str = "tokenizer.json";
@@ -25,12 +26,14 @@ const CONFIG_JSON: &str = "config.json";
 const SPECIAL_TOKENS_JSON: &str = "special_tokens_map.json";
 const TOKENIZER_CONFIG_JSON: &str = "tokenizer_config.json";
 
-/// FastEmbed-backed semantic embedder using MiniLM.
+/// FastEmbed-backed semantic embedder.
 pub struct FastEmbedder {
     model: Mutex<TextEmbedding>,
     id: String,
     model_id: String,
     dimension: usize,
+    query_prefix: String,
+    document_prefix: String,
 }
 
 impl FastEmbedder {
@@ -60,11 +63,37 @@ impl FastEmbedder {
         data_dir.join("models").join(MODEL_DIR_NAME)
     }
 
+    /// Files required to load the model described by `manifest`, relative to its
+    /// model directory.
+    pub fn required_files_for(manifest: &ModelManifest) -> Vec<String> {
+        let model_file = manifest
+            .onnx_file()
+            .map(str::to_string)
+            .unwrap_or_else(|| MODEL_FILE.to_string());
+        std::iter::once(model_file)
+            .chain(
+                [
+                    TOKENIZER_JSON,
+                    CONFIG_JSON,
+                    SPECIAL_TOKENS_JSON,
+                    TOKENIZER_CONFIG_JSON,
+                ]
+                .iter()
+                .map(|name| manifest.local_file(name)),
+            )
+            .collect()
+    }
+
     /// Load the MiniLM model + tokenizer from a local directory.
     ///
     /// This never downloads; it returns `EmbedderError::Unavailable` if any
     /// required file is missing.
     pub fn load_from_dir(model_dir: &Path) -> EmbedderResult<Self> {
+        Self::load_from_manifest(model_dir, &ModelManifest::minilm_v2())
+    }
+
+    /// Load the model described by `manifest` from a local directory.
+    pub fn load_from_manifest(model_dir: &Path, manifest: &ModelManifest) -> EmbedderResult<Self> {
         if !model_dir.is_dir() {
             return Err(EmbedderError::Unavailable(format!(
                 "model directory not found: {}",
@@ -72,14 +101,12 @@ impl FastEmbedder {
             )));
         }
 
-        let required = Self::required_model_files();
-        let mut missing = Vec::new();
-        for name in required {
-            let path = model_dir.join(name);
-            if !path.is_file() {
-                missing.push(*name);
-            }
-        }
+        let required = Self::required_files_for(manifest);
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|name| !model_dir.join(name).is_file())
+            .map(String::as_str)
+            .collect();
         if !missing.is_empty() {
             return Err(EmbedderError::Unavailable(format!(
                 "model files missing in {}: {}",
@@ -88,13 +115,14 @@ impl FastEmbedder {
             )));
         }
 
-        let model_file = Self::read_required(model_dir.join(MODEL_FILE), MODEL_FILE)?;
-        let tokenizer_file = Self::read_required(model_dir.join(TOKENIZER_JSON), TOKENIZER_JSON)?;
-        let config_file = Self::read_required(model_dir.join(CONFIG_JSON), CONFIG_JSON)?;
+        // Same order as `required_files_for`.
+        let model_file = Self::read_required(model_dir.join(&required[0]), &required[0])?;
+        let tokenizer_file = Self::read_required(model_dir.join(&required[1]), TOKENIZER_JSON)?;
+        let config_file = Self::read_required(model_dir.join(&required[2]), CONFIG_JSON)?;
         let special_tokens_map_file =
-            Self::read_required(model_dir.join(SPECIAL_TOKENS_JSON), SPECIAL_TOKENS_JSON)?;
+            Self::read_required(model_dir.join(&required[3]), SPECIAL_TOKENS_JSON)?;
         let tokenizer_config_file =
-            Self::read_required(model_dir.join(TOKENIZER_CONFIG_JSON), TOKENIZER_CONFIG_JSON)?;
+            Self::read_required(model_dir.join(&required[4]), TOKENIZER_CONFIG_JSON)?;
 
         let tokenizer_files = TokenizerFiles {
             tokenizer_file,
@@ -104,7 +132,10 @@ impl FastEmbedder {
         };
 
         let mut model = UserDefinedEmbeddingModel::new(model_file, tokenizer_files);
-        model.pooling = Some(Pooling::Mean);
+        model.pooling = Some(match manifest.pooling {
+            ModelPooling::Mean => Pooling::Mean,
+            ModelPooling::Cls => Pooling::Cls,
+        });
 
         let init_options = InitOptionsUserDefined::new();
 
@@ -113,9 +144,11 @@ impl FastEmbedder {
 
         Ok(Self {
             model: Mutex::new(model),
-            id: EMBEDDER_ID.to_string(),
-            model_id: MODEL_ID.to_string(),
-            dimension: EMBEDDING_DIMENSION,
+            id: manifest.embedder_id.clone(),
+            model_id: manifest.id.clone(),
+            dimension: manifest.dimension,
+            query_prefix: manifest.query_prefix.clone().unwrap_or_default(),
+            document_prefix: manifest.document_prefix.clone().unwrap_or_default(),
         })
     }
 
@@ -138,13 +171,13 @@ impl FastEmbedder {
             }
         }
     }
-}
 
-impl Embedder for FastEmbedder {
-    fn embed(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+    /// Embed `text` with the model's query or document prefix in front.
+    fn embed_prefixed(&self, prefix: &str, text: &str) -> EmbedderResult<Vec<f32>> {
         if text.is_empty() {
             return Err(EmbedderError::InvalidInput("empty text".to_string()));
         }
+        let text = format!("{prefix}{text}");
 
         let mut model = self
             .model
@@ -170,6 +203,16 @@ impl Embedder for FastEmbedder {
         Self::normalize_in_place(&mut embedding);
         Ok(embedding)
     }
+}
+
+impl Embedder for FastEmbedder {
+    fn embed(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+        self.embed_prefixed(&self.document_prefix, text)
+    }
+
+    fn embed_query(&self, text: &str) -> EmbedderResult<Vec<f32>> {
+        self.embed_prefixed(&self.query_prefix, text)
+    }
 
     fn embed_batch(&self, texts: &[&str]) -> EmbedderResult<Vec<Vec<f32>>> {
         for text in texts {
@@ -189,7 +232,10 @@ impl Embedder for FastEmbedder {
             .lock()
             .map_err(|_| EmbedderError::Internal("fastembed lock poisoned".to_string()))?;
 
-        let inputs = texts.to_vec();
+        let inputs: Vec<String> = texts
+            .iter()
+            .map(|text| format!("{}{text}", self.document_prefix))
+            .collect();
         let mut embeddings = model
             .embed(inputs, None)
             .map_err(|e| EmbedderError::EmbeddingFailed(format!("fastembed embed failed: {e}")))?;
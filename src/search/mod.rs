@@ -6,10 +6,13 @@
 //! - **[`tantivy`]**: Tantivy index creation, schema management, and document indexing.
 //! - **[`embedder`]**: Embedder trait for semantic search (hash and ML implementations).
 //! - **[`hash_embedder`]**: FNV-1a feature hashing embedder (deterministic fallback).
-//! - **[`fastembed_embedder`]**: FastEmbed-backed ML embedder (MiniLM).
+//! - **[`fastembed_embedder`]**: FastEmbed-backed ML embedder (any registry model).
 //! - **[`model_manager`]**: Semantic model detection + context wiring (no downloads).
 //! - **[`model_download`]**: Model download system with consent, verification, and atomic install.
+//! - **[`model_registry`]**: Built-in and user-provided model manifests; active model selection.
+//! - **[`ranking`]**: Ranking modes that re-order hits by relevance and recency.
 //! - **[`canonicalize`]**: Text preprocessing for consistent embedding input.
+//! - **[`topics`]**: Topic clustering of sessions over conversation-level embeddings.
 
 pub mod canonicalize;
 pub mod embedder;
@@ -17,6 +20,9 @@ pub mod fastembed_embedder;
 pub mod hash_embedder;
 pub mod model_download;
 pub mod model_manager;
+pub mod model_registry;
 pub mod query;
+pub mod ranking;
 pub mod tantivy;
+pub mod topics;
 pub mod vector_index;
@@ -18,6 +18,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 /// Model state machine for download lifecycle.
@@ -106,18 +107,36 @@ impl ModelState {
 }
 
 /// A file in the model manifest.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ModelFile {
-    /// File name (e.g., "model.onnx").
+    /// Path of the file inside the repository (e.g., "model.onnx" or "onnx/model.onnx").
+    /// The file is installed at the same relative path under the model directory.
     pub name: String,
     /// Expected SHA256 hash (hex string).
     pub sha256: String,
-    /// Expected file size in bytes.
+    /// Expected file size in bytes (0 when unknown).
+    #[serde(default)]
     pub size: u64,
 }
 
+impl ModelFile {
+    /// Whether the manifest pins this file's checksum.
+    pub fn is_pinned(&self) -> bool {
+        !self.sha256.is_empty()
+    }
+}
+
+/// Pooling applied to token embeddings to produce a sentence embedding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelPooling {
+    #[default]
+    Mean,
+    Cls,
+}
+
 /// Model manifest describing a downloadable model.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ModelManifest {
     /// Model identifier (e.g., "all-minilm-l6-v2").
     pub id: String,
@@ -129,6 +148,23 @@ pub struct ModelManifest {
     pub files: Vec<ModelFile>,
     /// License identifier.
     pub license: String,
+    /// Embedder identifier; names the vector index built with this model.
+    #[serde(default)]
+    pub embedder_id: String,
+    /// Output embedding dimension.
+    pub dimension: usize,
+    /// Pooling strategy for sentence embeddings.
+    #[serde(default)]
+    pub pooling: ModelPooling,
+    /// Directory name under `<data_dir>/models` (defaults to the model id).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir_name: Option<String>,
+    /// Text prepended to search queries before embedding (e.g. "search_query: ").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_prefix: Option<String>,
+    /// Text prepended to indexed documents before embedding (e.g. "search_document: ").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_prefix: Option<String>,
 }
 
 impl ModelManifest {
@@ -139,6 +175,12 @@ impl ModelManifest {
         Self {
             id: "all-minilm-l6-v2".into(),
             repo: "sentence-transformers/all-MiniLM-L6-v2".into(),
+            embedder_id: "minilm-384".into(),
+            dimension: 384,
+            pooling: ModelPooling::Mean,
+            dir_name: Some("all-MiniLM-L6-v2".into()),
+            query_prefix: None,
+            document_prefix: None,
             // Pinned revision for reproducibility
             revision: "e4ce9877abf3edfe10b0d82785e83bdcb973e22e".into(),
             files: vec![
@@ -173,6 +215,37 @@ impl ModelManifest {
         self.files.iter().map(|f| f.size).sum()
     }
 
+    /// Whether every file has a pinned checksum.
+    pub fn is_pinned(&self) -> bool {
+        self.files.iter().all(ModelFile::is_pinned)
+    }
+
+    /// Install directory for this model under the cass data dir.
+    pub fn model_dir(&self, data_dir: &Path) -> PathBuf {
+        data_dir
+            .join("models")
+            .join(self.dir_name.as_deref().unwrap_or(&self.id))
+    }
+
+    /// Relative path of the manifest file whose file name is `basename`.
+    ///
+    /// Files not listed in the manifest are expected at the model dir root.
+    pub fn local_file(&self, basename: &str) -> String {
+        self.files
+            .iter()
+            .find(|f| Path::new(&f.name).file_name().and_then(|n| n.to_str()) == Some(basename))
+            .map(|f| f.name.clone())
+            .unwrap_or_else(|| basename.to_string())
+    }
+
+    /// Relative path of the ONNX model file.
+    pub fn onnx_file(&self) -> Option<&str> {
+        self.files
+            .iter()
+            .map(|f| f.name.as_str())
+            .find(|name| name.ends_with(".onnx"))
+    }
+
     /// HuggingFace download URL for a file.
     pub fn download_url(&self, file: &ModelFile) -> String {
         format!(
@@ -225,6 +298,8 @@ pub enum DownloadError {
     Timeout,
     /// HTTP error response.
     HttpError { status: u16, message: String },
+    /// The manifest does not pin every file's checksum.
+    Unpinned { model: String },
 }
 
 impl std::fmt::Display for DownloadError {
@@ -247,6 +322,12 @@ impl std::fmt::Display for DownloadError {
             DownloadError::HttpError { status, message } => {
                 write!(f, "HTTP error {status}: {message}")
             }
+            DownloadError::Unpinned { model } => {
+                write!(
+                    f,
+                    "{model} does not pin its checksums; refusing to install it"
+                )
+            }
         }
     }
 }
@@ -337,12 +418,19 @@ impl ModelDownloader {
     ///
     /// # Errors
     ///
-    /// Returns `DownloadError` if download fails.
+    /// Returns `DownloadError` if download fails, or `DownloadError::Unpinned`
+    /// when the manifest does not pin every file's checksum.
     pub fn download(
         &self,
         manifest: &ModelManifest,
         on_progress: Option<ProgressCallback>,
     ) -> Result<(), DownloadError> {
+        if !manifest.is_pinned() {
+            return Err(DownloadError::Unpinned {
+                model: manifest.id.clone(),
+            });
+        }
+
         // Reset cancellation flag
         self.cancelled.store(false, Ordering::SeqCst);
 
@@ -360,6 +448,9 @@ impl ModelDownloader {
             }
 
             let file_path = self.temp_dir.join(&file.name);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
             let url = manifest.download_url(file);
 
             // Track bytes_downloaded at start of this file to reset on retry
@@ -448,15 +539,15 @@ impl ModelDownloader {
         grand_total: u64,
         on_progress: Option<&ProgressCallback>,
     ) -> Result<(), DownloadError> {
-        // Check for existing partial download
-        let existing_size = if path.exists() {
+        // Check for existing partial download (only resumable when the size is known)
+        let existing_size = if expected_size > 0 && path.exists() {
             fs::metadata(path).map(|m| m.len()).unwrap_or(0)
         } else {
             0
         };
 
         // If already complete, skip download
-        if existing_size == expected_size {
+        if expected_size > 0 && existing_size == expected_size {
             bytes_downloaded.fetch_add(expected_size, Ordering::SeqCst);
             return Ok(());
         }
@@ -647,8 +738,13 @@ pub fn compute_sha256(path: &Path) -> Result<String, DownloadError> {
     Ok(hex::encode(hash))
 }
 
-/// Check if a model is installed and verified.
+/// Check if the default MiniLM model is installed and verified.
 pub fn check_model_installed(model_dir: &Path) -> ModelState {
+    check_manifest_installed(model_dir, &ModelManifest::minilm_v2())
+}
+
+/// Check if the model described by `manifest` is installed and verified.
+pub fn check_manifest_installed(model_dir: &Path, manifest: &ModelManifest) -> ModelState {
     if !model_dir.is_dir() {
         return ModelState::NotInstalled;
     }
@@ -658,10 +754,9 @@ pub fn check_model_installed(model_dir: &Path) -> ModelState {
         return ModelState::NotInstalled;
     }
 
-    // Check if all required files exist
-    let required = ["model.onnx", "tokenizer.json", "config.json"];
-    for file in required {
-        if !model_dir.join(file).is_file() {
+    // Check if all manifest files exist
+    for file in &manifest.files {
+        if !model_dir.join(&file.name).is_file() {
             return ModelState::NotInstalled;
         }
     }
@@ -826,6 +921,22 @@ mod tests {
         assert!(err.to_string().contains("test.onnx"));
     }
 
+    #[test]
+    fn test_downloader_refuses_unpinned_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut manifest = ModelManifest::minilm_v2();
+        manifest.files[1].sha256.clear();
+        assert!(!manifest.is_pinned());
+
+        let downloader = ModelDownloader::new(tmp.path().join("model"));
+        assert!(matches!(
+            downloader.download(&manifest, None),
+            Err(DownloadError::Unpinned { .. })
+        ));
+        // Refused before any network or disk access
+        assert!(!tmp.path().join("model.downloading").exists());
+    }
+
     #[test]
     fn test_downloader_cancellation() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1,6 +1,7 @@
 //! Semantic model management (local-only detection).
 //!
-//! This module wires the FastEmbed MiniLM embedder into semantic search by:
+//! This module wires the active FastEmbed model (see [`model_registry`]) into
+//! semantic search by:
 //! - validating the local model files
 //! - loading the vector index
 //! - building filter maps from the SQLite database
@@ -8,6 +9,8 @@
 //!
 //! It does **not** download models. Missing files are surfaced as availability
 //! states so the UI can guide the user. Downloads are handled by [`model_download`].
+//!
+//! [`model_registry`]: crate::search::model_registry
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -16,6 +19,7 @@ use std::sync::Arc;
 use crate::search::embedder::Embedder;
 use crate::search::fastembed_embedder::FastEmbedder;
 use crate::search::model_download::{ModelManifest, ModelState, check_version_mismatch};
+use crate::search::model_registry::active_model_manifest;
 use crate::search::vector_index::{
     ROLE_ASSISTANT, ROLE_USER, SemanticFilterMaps, VectorIndex, vector_index_path,
 };
@@ -274,12 +278,12 @@ pub struct SemanticSetup {
     pub context: Option<SemanticContext>,
 }
 
-/// Load semantic context with optional version mismatch checking.
+/// Load semantic context for the active model, checking for version mismatches.
 ///
-/// If `check_for_updates` is true, this function will check if the installed
-/// model version matches the manifest and return `UpdateAvailable` if they differ.
+/// Returns `UpdateAvailable` if the installed model revision differs from the
+/// manifest.
 pub fn load_semantic_context(data_dir: &Path, db_path: &Path) -> SemanticSetup {
-    load_semantic_context_inner(data_dir, db_path, true)
+    load_semantic_context_for(data_dir, db_path, &active_model_manifest(data_dir), true)
 }
 
 /// Load semantic context without version checking.
@@ -287,19 +291,23 @@ pub fn load_semantic_context(data_dir: &Path, db_path: &Path) -> SemanticSetup {
 /// Use this when you've already acknowledged an update and want to load
 /// the model anyway.
 pub fn load_semantic_context_no_version_check(data_dir: &Path, db_path: &Path) -> SemanticSetup {
-    load_semantic_context_inner(data_dir, db_path, false)
+    load_semantic_context_for(data_dir, db_path, &active_model_manifest(data_dir), false)
 }
 
-fn load_semantic_context_inner(
+/// Load semantic context for a specific model.
+///
+/// If `check_for_updates` is true, this function will check if the installed
+/// model version matches the manifest and return `UpdateAvailable` if they differ.
+pub fn load_semantic_context_for(
     data_dir: &Path,
     db_path: &Path,
+    manifest: &ModelManifest,
     check_for_updates: bool,
 ) -> SemanticSetup {
-    let model_dir = FastEmbedder::default_model_dir(data_dir);
-    let missing_files = FastEmbedder::required_model_files()
-        .iter()
-        .filter(|name| !model_dir.join(*name).is_file())
-        .map(|name| (*name).to_string())
+    let model_dir = manifest.model_dir(data_dir);
+    let missing_files = FastEmbedder::required_files_for(manifest)
+        .into_iter()
+        .filter(|name| !model_dir.join(name).is_file())
         .collect::<Vec<_>>();
 
     if !missing_files.is_empty() {
@@ -313,25 +321,23 @@ fn load_semantic_context_inner(
     }
 
     // Check for model version mismatch
-    if check_for_updates {
-        let manifest = ModelManifest::minilm_v2();
-        if let Some(ModelState::UpdateAvailable {
+    if check_for_updates
+        && let Some(ModelState::UpdateAvailable {
             current_revision,
             latest_revision,
-        }) = check_version_mismatch(&model_dir, &manifest)
-        {
-            return SemanticSetup {
-                availability: SemanticAvailability::UpdateAvailable {
-                    embedder_id: FastEmbedder::embedder_id_static().to_string(),
-                    current_revision,
-                    latest_revision,
-                },
-                context: None,
-            };
-        }
+        }) = check_version_mismatch(&model_dir, manifest)
+    {
+        return SemanticSetup {
+            availability: SemanticAvailability::UpdateAvailable {
+                embedder_id: manifest.embedder_id.clone(),
+                current_revision,
+                latest_revision,
+            },
+            context: None,
+        };
     }
 
-    let index_path = vector_index_path(data_dir, FastEmbedder::embedder_id_static());
+    let index_path = vector_index_path(data_dir, &manifest.embedder_id);
     if !index_path.is_file() {
         return SemanticSetup {
             availability: SemanticAvailability::IndexMissing { index_path },
@@ -376,7 +382,7 @@ fn load_semantic_context_inner(
         }
     };
 
-    let embedder = match FastEmbedder::load_from_dir(&model_dir) {
+    let embedder = match FastEmbedder::load_from_manifest(&model_dir, manifest) {
         Ok(embedder) => Arc::new(embedder) as Arc<dyn Embedder>,
         Err(err) => {
             return SemanticSetup {
@@ -403,15 +409,22 @@ fn load_semantic_context_inner(
     }
 }
 
-/// Check if the vector index needs rebuilding after a model upgrade.
+/// Check if the active model's vector index needs rebuilding.
 ///
-/// This compares the embedder ID in the vector index header with the expected
-/// embedder ID. If they differ, the index was built with a different model
-/// and needs to be rebuilt.
+/// See [`needs_index_rebuild_for`].
+pub fn needs_index_rebuild(data_dir: &Path) -> bool {
+    needs_index_rebuild_for(data_dir, &active_model_manifest(data_dir))
+}
+
+/// Check if a model's vector index needs rebuilding after a model upgrade.
+///
+/// This compares the embedder ID and dimension in the vector index header with
+/// the manifest. If they differ, the index was built with a different model
+/// and needs to be rebuilt. Indexes of other models are never considered.
 ///
 /// Returns `true` if rebuild is needed, `false` otherwise.
-pub fn needs_index_rebuild(data_dir: &Path) -> bool {
-    let index_path = vector_index_path(data_dir, FastEmbedder::embedder_id_static());
+pub fn needs_index_rebuild_for(data_dir: &Path, manifest: &ModelManifest) -> bool {
+    let index_path = vector_index_path(data_dir, &manifest.embedder_id);
 
     if !index_path.is_file() {
         // Index doesn't exist, so it needs to be built (not rebuilt)
@@ -423,8 +436,9 @@ pub fn needs_index_rebuild(data_dir: &Path) -> bool {
         Ok(index) => {
             // Check if the index was built with a different embedder
             // The vector index stores the embedder ID in its header
-            let expected_id = FastEmbedder::embedder_id_static();
-            index.header().embedder_id != expected_id
+            let header = index.header();
+            header.embedder_id != manifest.embedder_id
+                || header.dimension as usize != manifest.dimension
         }
         Err(_) => {
             // Index is corrupted or unreadable, needs rebuild
@@ -433,7 +447,14 @@ pub fn needs_index_rebuild(data_dir: &Path) -> bool {
     }
 }
 
-/// Delete the vector index to force a rebuild.
+/// Delete the active model's vector index to force a rebuild.
+///
+/// See [`delete_vector_index_for_rebuild_for`].
+pub fn delete_vector_index_for_rebuild(data_dir: &Path) -> std::io::Result<bool> {
+    delete_vector_index_for_rebuild_for(data_dir, &active_model_manifest(data_dir))
+}
+
+/// Delete a model's vector index to force a rebuild.
 ///
 /// Call this after a model upgrade when the user has consented to rebuilding
 /// the semantic index. The next index run will rebuild from scratch.
@@ -443,8 +464,11 @@ pub fn needs_index_rebuild(data_dir: &Path) -> bool {
 /// `Ok(true)` if the index was deleted.
 /// `Ok(false)` if the index didn't exist.
 /// `Err(_)` if deletion failed.
-pub fn delete_vector_index_for_rebuild(data_dir: &Path) -> std::io::Result<bool> {
-    let index_path = vector_index_path(data_dir, FastEmbedder::embedder_id_static());
+pub fn delete_vector_index_for_rebuild_for(
+    data_dir: &Path,
+    manifest: &ModelManifest,
+) -> std::io::Result<bool> {
+    let index_path = vector_index_path(data_dir, &manifest.embedder_id);
 
     if index_path.is_file() {
         std::fs::remove_file(&index_path)?;
@@ -580,6 +604,40 @@ mod tests {
         assert!(!needs_index_rebuild(tmp.path()));
     }
 
+    #[test]
+    fn test_needs_index_rebuild_is_per_model() {
+        use crate::search::vector_index::Quantization;
+
+        let tmp = tempdir().unwrap();
+        let minilm = ModelManifest::minilm_v2();
+        let mut bge = ModelManifest::minilm_v2();
+        bge.id = "bge-small-en-v1.5".into();
+        bge.embedder_id = "bge-small-384".into();
+        let path = vector_index_path(tmp.path(), &minilm.embedder_id);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        VectorIndex::build(
+            &minilm.embedder_id,
+            "test",
+            384,
+            Quantization::F32,
+            Vec::new(),
+        )
+        .unwrap()
+        .save(&path)
+        .unwrap();
+
+        assert!(!needs_index_rebuild_for(tmp.path(), &minilm));
+        // Switching to another model never touches the MiniLM index.
+        assert!(!needs_index_rebuild_for(tmp.path(), &bge));
+        assert!(!delete_vector_index_for_rebuild_for(tmp.path(), &bge).unwrap());
+        assert!(path.is_file());
+
+        // Same embedder id but a different dimension is stale.
+        let mut resized = minilm.clone();
+        resized.dimension = 768;
+        assert!(needs_index_rebuild_for(tmp.path(), &resized));
+    }
+
     #[test]
     fn test_delete_vector_index_no_file() {
         let tmp = tempdir().unwrap();
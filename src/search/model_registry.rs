@@ -0,0 +1,425 @@
+//! Embedding model registry.
+//!
+//! The registry combines the built-in manifests with user-provided TOML manifests
+//! from `<data_dir>/models/manifests/*.toml`. A user manifest with the same id as a
+//! built-in replaces it. Every manifest pins a revision and a SHA256 per file, so
+//! other models (BGE, Nomic, Jina code, ...) are added as user manifests.
+//!
+//! One model is *active* at a time: the one `cass models use` selected (stored in
+//! `<data_dir>/models/active`), overridden by `CASS_SEMANTIC_MODEL`. Every model
+//! writes its own vector index (`index-<embedder_id>.cvvi`), so switching models
+//! only requires building the index for the newly active one.
+//!
+//! # Manifest format
+//!
+//! ```toml
+//! id = "e5-small-v2"
+//! repo = "intfloat/e5-small-v2"
+//! revision = "<commit sha>"
+//! license = "MIT"
+//! dimension = 384
+//! pooling = "mean"              # or "cls"
+//! embedder_id = "e5-small-384"  # optional, defaults to "<id>-<dimension>"
+//! query_prefix = "query: "       # optional, prepended to search queries
+//! document_prefix = "passage: "  # optional, prepended to indexed text
+//!
+//! [[files]]
+//! name = "onnx/model.onnx"
+//! sha256 = "<64 hex chars>"
+//! size = 133093490
+//!
+//! [[files]]
+//! name = "tokenizer.json"
+//! sha256 = "<64 hex chars>"
+//! ```
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::search::model_download::ModelManifest;
+
+/// Environment variable that overrides the active model.
+pub const ACTIVE_MODEL_ENV: &str = "CASS_SEMANTIC_MODEL";
+
+const MANIFESTS_DIR: &str = "manifests";
+const ACTIVE_MODEL_FILE: &str = "active";
+
+/// Errors from loading or validating a model manifest.
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("failed to read manifest: {0}")]
+    Read(#[from] std::io::Error),
+
+    #[error("failed to parse manifest: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("invalid manifest: {0}")]
+    Validation(String),
+}
+
+/// Where a registry entry came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestSource {
+    Builtin,
+    File(PathBuf),
+}
+
+/// A manifest in the registry.
+#[derive(Debug, Clone)]
+pub struct RegistryEntry {
+    pub manifest: ModelManifest,
+    pub source: ManifestSource,
+}
+
+/// All known embedding models.
+#[derive(Debug)]
+pub struct ModelRegistry {
+    entries: Vec<RegistryEntry>,
+    errors: Vec<(PathBuf, ManifestError)>,
+}
+
+/// Built-in manifests; the first entry is the default model.
+pub fn builtin_manifests() -> Vec<ModelManifest> {
+    vec![ModelManifest::minilm_v2()]
+}
+
+/// Directory holding user-provided manifest files.
+pub fn manifests_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("models").join(MANIFESTS_DIR)
+}
+
+impl ModelRegistry {
+    /// Registry with only the built-in manifests.
+    pub fn builtin() -> Self {
+        Self {
+            entries: builtin_manifests()
+                .into_iter()
+                .map(|manifest| RegistryEntry {
+                    manifest,
+                    source: ManifestSource::Builtin,
+                })
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Built-in manifests plus every `*.toml` manifest under [`manifests_dir`].
+    ///
+    /// Invalid files are skipped and reported through [`ModelRegistry::errors`].
+    pub fn load(data_dir: &Path) -> Self {
+        let mut registry = Self::builtin();
+        let Ok(dir) = fs::read_dir(manifests_dir(data_dir)) else {
+            return registry;
+        };
+        let mut paths: Vec<PathBuf> = dir
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match load_manifest(&path)
+                .and_then(|manifest| registry.check_conflicts(&manifest).map(|()| manifest))
+            {
+                Ok(manifest) => registry.insert(RegistryEntry {
+                    manifest,
+                    source: ManifestSource::File(path),
+                }),
+                Err(err) => registry.errors.push((path, err)),
+            }
+        }
+        registry
+    }
+
+    /// All entries, built-ins first.
+    pub fn entries(&self) -> &[RegistryEntry] {
+        &self.entries
+    }
+
+    /// Manifest files that failed to load.
+    pub fn errors(&self) -> &[(PathBuf, ManifestError)] {
+        &self.errors
+    }
+
+    /// Look up a model by id.
+    pub fn get(&self, id: &str) -> Option<&ModelManifest> {
+        self.entries
+            .iter()
+            .find(|entry| entry.manifest.id == id)
+            .map(|entry| &entry.manifest)
+    }
+
+    /// Ids of all registered models.
+    pub fn ids(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .map(|entry| entry.manifest.id.as_str())
+            .collect()
+    }
+
+    /// Manifest of the active model, falling back to the default when the
+    /// selected id is not registered.
+    pub fn active(&self, data_dir: &Path) -> &ModelManifest {
+        let id = active_model_id(data_dir);
+        self.get(&id).unwrap_or(&self.entries[0].manifest)
+    }
+
+    fn insert(&mut self, entry: RegistryEntry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|existing| existing.manifest.id == entry.manifest.id)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Two models must never share a vector index or a model directory.
+    fn check_conflicts(&self, manifest: &ModelManifest) -> Result<(), ManifestError> {
+        let dir = manifest.model_dir(Path::new(""));
+        for other in self.entries.iter().map(|entry| &entry.manifest) {
+            if other.id == manifest.id {
+                continue;
+            }
+            if other.embedder_id == manifest.embedder_id {
+                return Err(ManifestError::Validation(format!(
+                    "embedder_id '{}' is already used by model '{}'",
+                    manifest.embedder_id, other.id
+                )));
+            }
+            if other.model_dir(Path::new("")) == dir {
+                return Err(ManifestError::Validation(format!(
+                    "model directory '{}' is already used by model '{}'",
+                    dir.display(),
+                    other.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load and validate a manifest file.
+pub fn load_manifest(path: &Path) -> Result<ModelManifest, ManifestError> {
+    let content = fs::read_to_string(path)?;
+    parse_manifest(&content)
+}
+
+/// Parse and validate a manifest.
+///
+/// Manifests must pin a SHA256 for every file.
+pub fn parse_manifest(content: &str) -> Result<ModelManifest, ManifestError> {
+    let mut manifest: ModelManifest = toml::from_str(content)?;
+    if manifest.embedder_id.is_empty() {
+        manifest.embedder_id = format!("{}-{}", manifest.id, manifest.dimension);
+    }
+
+    let invalid = |msg: String| Err(ManifestError::Validation(msg));
+    if !is_safe_name(&manifest.id) {
+        return invalid(format!("id '{}' must be [A-Za-z0-9._-]", manifest.id));
+    }
+    if !is_safe_name(&manifest.embedder_id) {
+        return invalid(format!(
+            "embedder_id '{}' must be [A-Za-z0-9._-]",
+            manifest.embedder_id
+        ));
+    }
+    if let Some(dir_name) = &manifest.dir_name
+        && !is_safe_name(dir_name)
+    {
+        return invalid(format!("dir_name '{dir_name}' must be [A-Za-z0-9._-]"));
+    }
+    if manifest.repo.trim().is_empty() || manifest.revision.trim().is_empty() {
+        return invalid("repo and revision are required".into());
+    }
+    if manifest.dimension == 0 {
+        return invalid("dimension must be greater than 0".into());
+    }
+    if manifest.files.is_empty() {
+        return invalid("at least one file is required".into());
+    }
+    for file in &manifest.files {
+        let relative = Path::new(&file.name);
+        if file.name.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return invalid(format!("file name '{}' must be a relative path", file.name));
+        }
+        if file.sha256.len() != 64 || !file.sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return invalid(format!("file '{}' needs a 64-char hex sha256", file.name));
+        }
+    }
+    if manifest.onnx_file().is_none() {
+        return invalid("no .onnx model file listed".into());
+    }
+    for file in &mut manifest.files {
+        file.sha256.make_ascii_lowercase();
+    }
+    Ok(manifest)
+}
+
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Id of the active model: `CASS_SEMANTIC_MODEL`, then the persisted selection,
+/// then the default model.
+pub fn active_model_id(data_dir: &Path) -> String {
+    if let Ok(id) = dotenvy::var(ACTIVE_MODEL_ENV)
+        && !id.trim().is_empty()
+    {
+        return id.trim().to_string();
+    }
+    fs::read_to_string(data_dir.join("models").join(ACTIVE_MODEL_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| ModelManifest::minilm_v2().id)
+}
+
+/// Persist the active model selection.
+pub fn set_active_model(data_dir: &Path, id: &str) -> std::io::Result<()> {
+    let models_dir = data_dir.join("models");
+    fs::create_dir_all(&models_dir)?;
+    fs::write(models_dir.join(ACTIVE_MODEL_FILE), format!("{id}\n"))
+}
+
+/// Manifest of the active model under `data_dir`.
+pub fn active_model_manifest(data_dir: &Path) -> ModelManifest {
+    ModelRegistry::load(data_dir).active(data_dir).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA: &str = "af9eceaf5d8a75d882c9cb8ba36c693a36bd41cf57ffe0adac38daa59bdf4bca";
+
+    fn manifest_toml(id: &str, embedder_id: &str) -> String {
+        format!(
+            r#"
+id = "{id}"
+repo = "example/{id}"
+revision = "abc123"
+license = "MIT"
+dimension = 384
+pooling = "cls"
+embedder_id = "{embedder_id}"
+
+[[files]]
+name = "onnx/model.onnx"
+sha256 = "{SHA}"
+size = 10
+
+[[files]]
+name = "tokenizer.json"
+sha256 = "{SHA}"
+"#
+        )
+    }
+
+    #[test]
+    fn builtin_registry_has_distinct_indexes() {
+        let registry = ModelRegistry::builtin();
+        assert_eq!(registry.entries()[0].manifest.id, "all-minilm-l6-v2");
+        let mut embedder_ids: Vec<_> = registry
+            .entries()
+            .iter()
+            .map(|e| e.manifest.embedder_id.clone())
+            .collect();
+        embedder_ids.sort();
+        embedder_ids.dedup();
+        assert_eq!(embedder_ids.len(), registry.entries().len());
+        assert!(registry.entries().iter().all(|e| e.manifest.is_pinned()));
+    }
+
+    #[test]
+    fn parse_manifest_defaults_and_validation() {
+        let manifest = parse_manifest(&manifest_toml("e5-small", "")).unwrap();
+        assert_eq!(manifest.embedder_id, "e5-small-384");
+        assert_eq!(manifest.local_file("model.onnx"), "onnx/model.onnx");
+        assert_eq!(manifest.local_file("config.json"), "config.json");
+        assert!(manifest.is_pinned());
+
+        let unpinned = manifest_toml("e5-small", "e5").replace(SHA, "");
+        assert!(matches!(
+            parse_manifest(&unpinned),
+            Err(ManifestError::Validation(_))
+        ));
+        let escaping = manifest_toml("e5-small", "e5").replace("onnx/model.onnx", "../model.onnx");
+        assert!(parse_manifest(&escaping).is_err());
+        let bad_id = manifest_toml("../e5", "e5");
+        assert!(parse_manifest(&bad_id).is_err());
+    }
+
+    #[test]
+    fn parse_manifest_reads_query_and_document_prefixes() {
+        let plain = parse_manifest(&manifest_toml("e5-small", "")).unwrap();
+        assert_eq!(plain.query_prefix, None);
+        assert_eq!(plain.document_prefix, None);
+
+        let prefixed = manifest_toml("nomic-embed-text-v1.5", "nomic-embed-768").replacen(
+            "license",
+            "query_prefix = \"search_query: \"\ndocument_prefix = \"search_document: \"\nlicense",
+            1,
+        );
+        let manifest = parse_manifest(&prefixed).unwrap();
+        assert_eq!(manifest.query_prefix.as_deref(), Some("search_query: "));
+        assert_eq!(
+            manifest.document_prefix.as_deref(),
+            Some("search_document: ")
+        );
+    }
+
+    #[test]
+    fn user_manifests_override_and_conflicts_are_reported() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = manifests_dir(tmp.path());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.toml"), manifest_toml("e5-small", "e5-384")).unwrap();
+        fs::write(
+            dir.join("b.toml"),
+            manifest_toml("all-minilm-l6-v2", "minilm-384"),
+        )
+        .unwrap();
+        fs::write(dir.join("c.toml"), manifest_toml("clash", "minilm-384")).unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let registry = ModelRegistry::load(tmp.path());
+        assert!(registry.get("e5-small").is_some());
+        // Overrides the built-in in place
+        assert_eq!(
+            registry.get("all-minilm-l6-v2").unwrap().repo,
+            "example/all-minilm-l6-v2"
+        );
+        assert!(registry.get("clash").is_none());
+        assert_eq!(registry.errors().len(), 1);
+        assert_eq!(registry.entries().len(), 2);
+    }
+
+    #[test]
+    fn active_model_falls_back_to_default() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = manifests_dir(tmp.path());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("e5.toml"), manifest_toml("e5-small", "e5-384")).unwrap();
+        let registry = ModelRegistry::load(tmp.path());
+        assert_eq!(registry.active(tmp.path()).id, "all-minilm-l6-v2");
+
+        set_active_model(tmp.path(), "e5-small").unwrap();
+        assert_eq!(registry.active(tmp.path()).id, "e5-small");
+
+        set_active_model(tmp.path(), "no-such-model").unwrap();
+        assert_eq!(registry.active(tmp.path()).id, "all-minilm-l6-v2");
+    }
+}
@@ -3,7 +3,7 @@ use lru::LruCache;
 use once_cell::sync::Lazy;
 use std::cell::RefCell;
 use std::cmp::Ordering as CmpOrdering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -43,6 +43,17 @@ pub struct SearchFilters {
     /// Filter to specific session source paths (for chained searches)
     #[serde(skip_serializing_if = "HashSet::is_empty")]
     pub session_paths: HashSet<String>,
+    /// Filter to sessions owned by these users (shared indexes)
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub owners: HashSet<String>,
+}
+
+/// Anchor for a "more like this" search: a whole session, or one message within it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SimilarAnchor {
+    pub source_path: String,
+    /// 1-based message line number (matches `SearchHit::line_number`); `None` = whole session
+    pub line_number: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, clap::ValueEnum)]
@@ -53,7 +64,7 @@ pub enum SearchMode {
     Lexical,
     /// Semantic search - embedding similarity
     Semantic,
-    /// Hybrid search - RRF fusion of lexical and semantic
+    /// Hybrid search - fusion of lexical and semantic (see `FusionStrategy`)
     Hybrid,
 }
 
@@ -67,8 +78,156 @@ impl SearchMode {
     }
 }
 
-const RRF_K: f32 = 60.0;
+/// Reciprocal Rank Fusion smoothing constant
+pub const RRF_K: f32 = 60.0;
 const HYBRID_CANDIDATE_MULTIPLIER: usize = 3;
+const DEFAULT_SEMANTIC_WEIGHT: f32 = 0.5;
+/// Semantic weight `auto` fusion uses for identifiers, phrases, wildcards, and operators.
+const AUTO_LEXICAL_SEMANTIC_WEIGHT: f32 = 0.2;
+/// Semantic weight `auto` fusion uses for natural-language questions.
+const AUTO_NATURAL_SEMANTIC_WEIGHT: f32 = 0.7;
+/// Term count at which `auto` fusion treats an operator-free query as natural language.
+const AUTO_NATURAL_MIN_TERMS: usize = 4;
+const QUESTION_WORDS: &[&str] = &[
+    "how", "why", "what", "when", "where", "which", "who", "can", "does", "should", "is",
+];
+
+/// How hybrid search combines the lexical and semantic rankings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum FusionStrategy {
+    /// Reciprocal Rank Fusion: combine 1/(k + rank) from each list
+    #[default]
+    Rrf,
+    /// Min-max normalize each list's scores, then take a weighted sum
+    Weighted,
+    /// Pick the semantic weight from the query shape, then fuse with RRF
+    Auto,
+}
+
+/// Caller-supplied hybrid fusion settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FusionConfig {
+    pub strategy: FusionStrategy,
+    /// RRF smoothing constant; larger values flatten differences between ranks
+    pub rrf_k: f32,
+    /// Semantic share in [0, 1]; `None` uses 0.5 (or the `auto` heuristic)
+    pub semantic_weight: Option<f32>,
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self {
+            strategy: FusionStrategy::Rrf,
+            rrf_k: RRF_K,
+            semantic_weight: None,
+        }
+    }
+}
+
+impl FusionConfig {
+    /// Resolve `auto` and default weights against a concrete query.
+    pub fn resolve(&self, query: &str) -> ResolvedFusion {
+        let (strategy, auto_weight, query_type) = match self.strategy {
+            FusionStrategy::Auto => {
+                let (weight, query_type) = auto_semantic_weight(query);
+                (FusionStrategy::Rrf, weight, Some(query_type))
+            }
+            other => (other, DEFAULT_SEMANTIC_WEIGHT, None),
+        };
+        ResolvedFusion {
+            requested: self.strategy,
+            strategy,
+            rrf_k: if self.rrf_k > 0.0 { self.rrf_k } else { RRF_K },
+            semantic_weight: self.semantic_weight.unwrap_or(auto_weight).clamp(0.0, 1.0),
+            query_type,
+        }
+    }
+}
+
+/// Fusion parameters actually applied to a query (reported in robot `_meta`).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct ResolvedFusion {
+    /// Strategy the caller asked for (may be `auto`)
+    pub requested: FusionStrategy,
+    /// Strategy used to combine the lists (`rrf` or `weighted`)
+    pub strategy: FusionStrategy,
+    pub rrf_k: f32,
+    pub semantic_weight: f32,
+    /// Query classification that drove `auto` weighting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_type: Option<QueryType>,
+}
+
+impl Default for ResolvedFusion {
+    fn default() -> Self {
+        FusionConfig::default().resolve("")
+    }
+}
+
+/// Per-hit ranking details from hybrid fusion, for debugging result order.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HybridRanks {
+    /// 1-based position in the lexical candidate list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lexical_rank: Option<usize>,
+    /// 1-based position in the semantic candidate list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_rank: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lexical_score: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_score: Option<f32>,
+}
+
+/// Semantic weight for `auto` fusion: lexical dominates for identifiers and
+/// operator queries, semantic for natural-language questions.
+fn auto_semantic_weight(query: &str) -> (f32, QueryType) {
+    let explanation = QueryExplanation::analyze(query, &SearchFilters::default());
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let is_question = query.trim_end().ends_with('?')
+        || words
+            .first()
+            .is_some_and(|w| QUESTION_WORDS.contains(&w.to_lowercase().as_str()));
+    let has_identifier = words.iter().any(|w| looks_like_identifier(w));
+    let natural = is_question || explanation.parsed.terms.len() >= AUTO_NATURAL_MIN_TERMS;
+
+    let weight = match explanation.query_type {
+        QueryType::Empty | QueryType::Filtered => DEFAULT_SEMANTIC_WEIGHT,
+        QueryType::Phrase | QueryType::Wildcard => AUTO_LEXICAL_SEMANTIC_WEIGHT,
+        QueryType::Boolean if !is_question => AUTO_LEXICAL_SEMANTIC_WEIGHT,
+        _ => match (has_identifier, natural) {
+            (true, false) => AUTO_LEXICAL_SEMANTIC_WEIGHT,
+            (false, true) => AUTO_NATURAL_SEMANTIC_WEIGHT,
+            _ => DEFAULT_SEMANTIC_WEIGHT,
+        },
+    };
+    (weight, explanation.query_type)
+}
+
+/// Heuristic for code-like tokens: snake_case, paths, `::`, calls, camelCase, or
+/// mixed letters and digits (error codes, versions).
+fn looks_like_identifier(word: &str) -> bool {
+    let word = word.trim_matches(|c: char| matches!(c, '?' | '!' | ',' | ';' | '"' | '\''));
+    let word = word.trim_end_matches('.');
+    if word.len() < 2 {
+        return false;
+    }
+    if word.contains(['_', '/', '\\', '(', '<', '=', '#', '$', '@'])
+        || word.contains("::")
+        || word.contains("->")
+        || (word.contains('.') && !word.starts_with('.'))
+    {
+        return true;
+    }
+    let has_alpha = word.chars().any(char::is_alphabetic);
+    let has_digit = word.chars().any(|c| c.is_ascii_digit());
+    let camel = word
+        .chars()
+        .zip(word.chars().skip(1))
+        .any(|(a, b)| a.is_lowercase() && b.is_uppercase());
+    camel || (has_alpha && has_digit)
+}
 
 // ============================================================================
 // Query Explanation types (--explain flag support)
@@ -280,7 +439,8 @@ impl QueryExplanation {
             || !filters.workspaces.is_empty()
             || filters.created_from.is_some()
             || filters.created_to.is_some()
-            || !filters.source_filter.is_all();
+            || !filters.source_filter.is_all()
+            || !filters.owners.is_empty();
 
         if has_filters {
             return QueryType::Filtered;
@@ -474,7 +634,7 @@ impl QueryExplanation {
 
 /// Indicates how a search result matched the query.
 /// Used for ranking: exact matches rank higher than wildcard matches.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MatchType {
     /// No wildcards - matched via exact term or edge n-gram prefix
@@ -588,7 +748,7 @@ impl QuerySuggestion {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SearchHit {
     pub title: String,
     pub snippet: String,
@@ -616,6 +776,16 @@ pub struct SearchHit {
     /// Origin host label for remote sources
     #[serde(skip_serializing_if = "Option::is_none")]
     pub origin_host: Option<String>,
+    /// User the session belongs to, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Lexical/semantic ranks behind a hybrid result (hybrid mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fusion: Option<HybridRanks>,
+    /// Every source the session was seen in, when it arrived from more than
+    /// one (duplicate copies are stored once, under the first source)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seen_in: Vec<String>,
 }
 
 fn default_source_id() -> String {
@@ -679,7 +849,7 @@ impl PartialOrd for SearchHitKey {
 
 #[derive(Debug, Default, Clone)]
 struct HybridScore {
-    rrf: f32,
+    fused: f32,
     lexical_rank: Option<usize>,
     semantic_rank: Option<usize>,
     lexical_score: Option<f32>,
@@ -704,35 +874,83 @@ fn stable_content_hash(content: &str) -> u64 {
     hash
 }
 
-/// Fuse lexical + semantic hits using Reciprocal Rank Fusion (RRF).
-/// Applies deterministic tie-breaking and returns the requested page slice.
+/// Fuse lexical + semantic hits using Reciprocal Rank Fusion (RRF) with the
+/// default `k` and equal weights.
 pub fn rrf_fuse_hits(
     lexical: &[SearchHit],
     semantic: &[SearchHit],
     limit: usize,
     offset: usize,
+) -> Vec<SearchHit> {
+    fuse_hits(lexical, semantic, limit, offset, &ResolvedFusion::default())
+}
+
+/// Min-max normalize scores to [0, 1]; a list with a single distinct score maps to 1.
+fn normalized_scores(hits: &[SearchHit]) -> Vec<f32> {
+    let (min, max) = hits
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), h| {
+            (lo.min(h.score), hi.max(h.score))
+        });
+    let range = max - min;
+    hits.iter()
+        .map(|h| {
+            if range > f32::EPSILON {
+                (h.score - min) / range
+            } else {
+                1.0
+            }
+        })
+        .collect()
+}
+
+/// Fuse lexical + semantic hits with the given fusion parameters.
+///
+/// RRF weights each list's `1/(k + rank)` by `2(1-w)` and `2w` (so `w = 0.5` is
+/// plain RRF); weighted fusion sums `(1-w)` and `w` times min-max normalized
+/// scores. Applies deterministic tie-breaking, records per-hit ranks in
+/// `SearchHit::fusion`, and returns the requested page slice.
+pub fn fuse_hits(
+    lexical: &[SearchHit],
+    semantic: &[SearchHit],
+    limit: usize,
+    offset: usize,
+    fusion: &ResolvedFusion,
 ) -> Vec<SearchHit> {
     if limit == 0 {
         return Vec::new();
     }
 
+    let semantic_weight = fusion.semantic_weight.clamp(0.0, 1.0);
+    let lexical_weight = 1.0 - semantic_weight;
+    let weighted = fusion.strategy == FusionStrategy::Weighted;
+    let contribution = |rank: usize, normalized: f32, weight: f32| {
+        if weighted {
+            weight * normalized
+        } else {
+            2.0 * weight / (fusion.rrf_k + rank as f32 + 1.0)
+        }
+    };
+
     let mut scores: HashMap<SearchHitKey, HybridScore> = HashMap::new();
     let mut hits: HashMap<SearchHitKey, SearchHit> = HashMap::new();
 
+    let lexical_norm = normalized_scores(lexical);
     for (rank, hit) in lexical.iter().enumerate() {
         let key = SearchHitKey::from_hit(hit);
         let entry = scores.entry(key.clone()).or_default();
-        entry.rrf += 1.0 / (RRF_K + rank as f32 + 1.0);
+        entry.fused += contribution(rank, lexical_norm[rank], lexical_weight);
         entry.lexical_rank = Some(rank);
         entry.lexical_score = Some(hit.score);
         // Prefer lexical hit details (snippets highlight query terms).
         hits.insert(key, hit.clone());
     }
 
+    let semantic_norm = normalized_scores(semantic);
     for (rank, hit) in semantic.iter().enumerate() {
         let key = SearchHitKey::from_hit(hit);
         let entry = scores.entry(key.clone()).or_default();
-        entry.rrf += 1.0 / (RRF_K + rank as f32 + 1.0);
+        entry.fused += contribution(rank, semantic_norm[rank], semantic_weight);
         entry.semantic_rank = Some(rank);
         entry.semantic_score = Some(hit.score);
         hits.entry(key).or_insert_with(|| hit.clone());
@@ -747,8 +965,8 @@ pub fn rrf_fuse_hits(
 
     fused.sort_by(|a, b| {
         b.score
-            .rrf
-            .total_cmp(&a.score.rrf)
+            .fused
+            .total_cmp(&a.score.fused)
             .then_with(|| {
                 let a_both = a.score.lexical_rank.is_some() && a.score.semantic_rank.is_some();
                 let b_both = b.score.lexical_rank.is_some() && b.score.semantic_rank.is_some();
@@ -765,7 +983,13 @@ pub fn rrf_fuse_hits(
     let end = start.saturating_add(limit).min(fused.len());
     let mut results = Vec::with_capacity(end.saturating_sub(start));
     for mut entry in fused.into_iter().skip(start).take(limit) {
-        entry.hit.score = entry.score.rrf;
+        entry.hit.score = entry.score.fused;
+        entry.hit.fusion = Some(HybridRanks {
+            lexical_rank: entry.score.lexical_rank.map(|r| r + 1),
+            semantic_rank: entry.score.semantic_rank.map(|r| r + 1),
+            lexical_score: entry.score.lexical_score,
+            semantic_score: entry.score.semantic_score,
+        });
         results.push(entry.hit);
     }
     results
@@ -795,7 +1019,7 @@ impl QueryCache {
         }
 
         let embedding = embedder
-            .embed(canonical)
+            .embed_query(canonical)
             .map_err(|e| anyhow!("embedding failed: {e}"))?;
         self.embeddings
             .put(canonical.to_string(), embedding.clone());
@@ -1689,13 +1913,23 @@ impl SearchClient {
                 offset = offset,
                 "search_start"
             );
+            // Sessions linked to the filtered source are matched through the
+            // source they are stored under and narrowed down after the search,
+            // so that case pages from the first hit
+            let linked = self.linked_sessions(&filters.source_filter);
+            let (fetch_limit, fetch_offset) = if linked.is_empty() {
+                (limit * 3, offset)
+            } else {
+                (offset.saturating_add(limit) * 3, 0)
+            };
             let hits = self.search_tantivy(
                 reader,
                 fields,
                 &sanitized,
                 filters.clone(),
-                limit * 3,
-                offset,
+                fetch_limit,
+                fetch_offset,
+                &linked,
             )?;
             if !hits.is_empty() {
                 let mut deduped = deduplicate_hits(hits);
@@ -1703,7 +1937,12 @@ impl SearchClient {
                 if !filters.session_paths.is_empty() {
                     deduped.retain(|h| filters.session_paths.contains(&h.source_path));
                 }
+                if !linked.is_empty() {
+                    retain_source_or_linked(&mut deduped, &filters.source_filter, &linked);
+                    deduped.drain(..offset.min(deduped.len()));
+                }
                 deduped.truncate(limit);
+                self.attach_seen_in(&mut deduped);
                 self.put_cache(&sanitized, &filters, &deduped);
                 return Ok(deduped);
             }
@@ -1739,6 +1978,7 @@ impl SearchClient {
                 deduped.retain(|h| filters.session_paths.contains(&h.source_path));
             }
             deduped.truncate(limit);
+            self.attach_seen_in(&mut deduped);
             self.put_cache(&sanitized, &filters, &deduped);
             return Ok(deduped);
         }
@@ -1838,13 +2078,118 @@ impl SearchClient {
         }
 
         let mut hits = self.hydrate_semantic_hits(&results)?;
-        // Apply session_paths filter (not supported at SemanticFilter level)
+        // Apply session_paths and owner filters (not supported at SemanticFilter level)
         if !filters.session_paths.is_empty() {
             hits.retain(|h| filters.session_paths.contains(&h.source_path));
         }
+        retain_owners(&mut hits, &filters);
         Ok(hits)
     }
 
+    /// "More like this": find messages semantically close to an existing message or session.
+    ///
+    /// The anchor vector is read straight from the vector index (no re-embedding). With a
+    /// line number it is that message's vector; otherwise the mean of every message in the
+    /// session. Hits from the anchor session itself are excluded.
+    pub fn search_similar(
+        &self,
+        anchor: &SimilarAnchor,
+        filters: SearchFilters,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let conn = self
+            .sqlite
+            .as_ref()
+            .ok_or_else(|| anyhow!("similar search requires database connection"))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT m.id, m.idx FROM messages m
+             JOIN conversations c ON m.conversation_id = c.id
+             WHERE c.source_path = ?1",
+        )?;
+        let rows = stmt.query_map([anchor.source_path.as_str()], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        let mut session_ids: HashSet<u64> = HashSet::new();
+        let mut anchor_ids: HashSet<u64> = HashSet::new();
+        for row in rows {
+            let (id, idx) = row?;
+            let id = id as u64;
+            session_ids.insert(id);
+            match anchor.line_number {
+                Some(line) if idx + 1 == line as i64 => {
+                    anchor_ids.insert(id);
+                }
+                Some(_) => {}
+                None => {
+                    anchor_ids.insert(id);
+                }
+            }
+        }
+        if session_ids.is_empty() {
+            bail!("no indexed session found at {}", anchor.source_path);
+        }
+        if anchor_ids.is_empty() {
+            bail!(
+                "no message at line {} in {}",
+                anchor.line_number.unwrap_or_default(),
+                anchor.source_path
+            );
+        }
+
+        let guard = self
+            .semantic
+            .lock()
+            .map_err(|_| anyhow!("semantic lock poisoned"))?;
+        let state = guard
+            .as_ref()
+            .ok_or_else(|| anyhow!("semantic search unavailable (no embedder or vector index)"))?;
+
+        let Some(anchor_vec) = state.index.mean_vector_for_messages(&anchor_ids)? else {
+            bail!("anchor has no embedding in the vector index");
+        };
+        let mut semantic_filter =
+            SemanticFilter::from_search_filters(&filters, &state.filter_maps)?;
+        if let Some(roles) = state.roles.clone() {
+            semantic_filter = semantic_filter.with_roles(Some(roles));
+        }
+
+        let fetch = limit.saturating_add(offset);
+        if fetch == 0 {
+            return Ok(Vec::new());
+        }
+        // Over-fetch so excluding the anchor session does not starve the page.
+        // Session-path and owner filters only apply after hydration, so widen
+        // the candidates until the filtered hits fill the page or run out.
+        let mut k = fetch.saturating_add(session_ids.len());
+        let hits = loop {
+            let results =
+                state
+                    .index
+                    .search_top_k_collapsed(&anchor_vec, k, Some(&semantic_filter))?;
+            let exhausted = results.len() < k;
+            let results: Vec<VectorSearchResult> = results
+                .into_iter()
+                .filter(|r| !session_ids.contains(&r.message_id))
+                .collect();
+
+            let mut hits = self.hydrate_semantic_hits(&results)?;
+            hits.retain(|h| h.source_path != anchor.source_path);
+            if !filters.session_paths.is_empty() {
+                hits.retain(|h| filters.session_paths.contains(&h.source_path));
+            }
+            retain_owners(&mut hits, &filters);
+            if hits.len() >= fetch || exhausted {
+                break hits;
+            }
+            k = k.saturating_mul(2);
+        };
+        drop(guard);
+
+        Ok(hits.into_iter().skip(offset).take(limit).collect())
+    }
+
     fn hydrate_semantic_hits(&self, results: &[VectorSearchResult]) -> Result<Vec<SearchHit>> {
         if results.is_empty() {
             return Ok(Vec::new());
@@ -1865,7 +2210,7 @@ impl SearchClient {
         }
 
         let sql = format!(
-            "SELECT m.id, m.content, m.created_at, m.idx, m.role, c.title, c.source_path, c.source_id, c.origin_host, a.slug, w.path, COALESCE(s.kind, 'local')
+            "SELECT m.id, m.content, m.created_at, m.idx, m.role, c.title, c.source_path, c.source_id, c.origin_host, a.slug, w.path, COALESCE(s.kind, 'local'), c.owner
              FROM messages m
              JOIN conversations c ON m.conversation_id = c.id
              JOIN agents a ON c.agent_id = a.id
@@ -1889,6 +2234,7 @@ impl SearchClient {
                 let agent: String = row.get(9)?;
                 let workspace: Option<String> = row.get(10)?;
                 let origin_kind: String = row.get(11)?;
+                let owner: Option<String> = row.get(12)?;
 
                 let line_number = idx.map(|i| (i + 1) as usize);
                 let snippet = snippet_from_content(&content);
@@ -1908,6 +2254,9 @@ impl SearchClient {
                     source_id: source_id.unwrap_or_else(default_source_id),
                     origin_kind,
                     origin_host,
+                    owner,
+                    fusion: None,
+                    seen_in: Vec::new(),
                 };
 
                 Ok((message_id as u64, hit))
@@ -1927,10 +2276,74 @@ impl SearchClient {
                 ordered.push(hit);
             }
         }
+        self.attach_seen_in(&mut ordered);
 
         Ok(ordered)
     }
 
+    /// Sessions stored under another source that the filtered source also has,
+    /// as `(source_id, source_path)` of the stored copy.
+    ///
+    /// Deduplication keeps one copy of a session, under the source it was
+    /// first seen in; the link table records the others. Best effort, like
+    /// `attach_seen_in`.
+    fn linked_sessions(&self, filter: &SourceFilter) -> HashSet<(String, String)> {
+        let (SourceFilter::SourceId(source_id), Some(conn)) = (filter, &self.sqlite) else {
+            return HashSet::new();
+        };
+        let Ok(mut stmt) = conn.prepare(
+            "SELECT c.source_id, c.source_path FROM conversation_sources cs
+             JOIN conversations c ON c.id = cs.conversation_id
+             WHERE cs.source_id = ? AND c.source_id != cs.source_id",
+        ) else {
+            return HashSet::new();
+        };
+        stmt.query_map([source_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map(|rows| rows.filter_map(std::result::Result::ok).collect())
+        .unwrap_or_default()
+    }
+
+    /// Fill `seen_in` for hits whose session was also seen in other sources.
+    ///
+    /// Best effort: databases from before deduplication have no link table.
+    fn attach_seen_in(&self, hits: &mut [SearchHit]) {
+        let Some(conn) = &self.sqlite else {
+            return;
+        };
+        if hits.is_empty() {
+            return;
+        }
+        let Ok(mut stmt) = conn.prepare(
+            "SELECT cs.source_id FROM conversation_sources cs
+             JOIN conversations c ON c.id = cs.conversation_id
+             WHERE c.source_path = ? AND c.source_id = ?
+             ORDER BY cs.seen_at, cs.source_id",
+        ) else {
+            return;
+        };
+
+        let mut linked_by_session: HashMap<(String, String), Vec<String>> = HashMap::new();
+        for hit in hits.iter_mut() {
+            let key = (hit.source_path.clone(), hit.source_id.clone());
+            let linked = linked_by_session.entry(key).or_insert_with(|| {
+                stmt.query_map([&hit.source_path, &hit.source_id], |row| {
+                    row.get::<_, String>(0)
+                })
+                .map(|rows| rows.filter_map(std::result::Result::ok).collect())
+                .unwrap_or_default()
+            });
+            hit.seen_in = if linked.is_empty() {
+                Vec::new()
+            } else {
+                std::iter::once(hit.source_id.clone())
+                    .chain(linked.iter().cloned())
+                    .collect()
+            };
+        }
+    }
+
     /// Search with automatic wildcard fallback for sparse results.
     /// If the initial search returns fewer than `sparse_threshold` results and the query
     /// doesn't already contain wildcards, automatically retry with substring wildcards (*term*).
@@ -2020,7 +2433,8 @@ impl SearchClient {
         }
     }
 
-    /// Hybrid search that fuses lexical + semantic results with RRF.
+    /// Hybrid search that fuses lexical + semantic results per `fusion`.
+    #[allow(clippy::too_many_arguments)]
     pub fn search_hybrid(
         &self,
         lexical_query: &str,
@@ -2029,6 +2443,7 @@ impl SearchClient {
         limit: usize,
         offset: usize,
         sparse_threshold: usize,
+        fusion: &FusionConfig,
     ) -> Result<SearchResult> {
         let fetch = limit.saturating_add(offset);
         if fetch == 0 {
@@ -2059,7 +2474,13 @@ impl SearchClient {
             sparse_threshold,
         )?;
         let semantic = self.search_semantic(semantic_query, filters, candidate, 0)?;
-        let fused = rrf_fuse_hits(&lexical.hits, &semantic, limit, offset);
+        let fused = fuse_hits(
+            &lexical.hits,
+            &semantic,
+            limit,
+            offset,
+            &fusion.resolve(semantic_query),
+        );
         let suggestions = if fused.is_empty() {
             lexical.suggestions.clone()
         } else {
@@ -2173,6 +2594,7 @@ impl SearchClient {
         *guard = Some(generation);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn search_tantivy(
         &self,
         reader: &IndexReader,
@@ -2181,6 +2603,7 @@ impl SearchClient {
         filters: SearchFilters,
         limit: usize,
         offset: usize,
+        linked: &HashSet<(String, String)>,
     ) -> Result<Vec<SearchHit>> {
         self.maybe_reload_reader(reader)?;
         let searcher = self.searcher_for_thread(reader);
@@ -2244,6 +2667,23 @@ impl SearchClient {
             clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
         }
 
+        if !filters.owners.is_empty() {
+            let terms = filters
+                .owners
+                .into_iter()
+                .map(|owner| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(fields.owner, &owner),
+                            IndexRecordOption::Basic,
+                        )) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+        }
+
         if filters.created_from.is_some() || filters.created_to.is_some() {
             use std::ops::Bound::{Included, Unbounded};
             let lower = filters.created_from.map_or(Unbounded, |v| {
@@ -2279,12 +2719,24 @@ impl SearchClient {
                 ));
             }
             SourceFilter::SourceId(source_id) => {
-                // Filter to specific source by ID
-                let term = Term::from_field_text(fields.source_id, source_id);
-                clauses.push((
-                    Occur::Must,
-                    Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
-                ));
+                // Filter to specific source by ID, or to the sources storing
+                // sessions linked to it (the caller drops their other sessions)
+                let ids: BTreeSet<&str> = std::iter::once(source_id.as_str())
+                    .chain(linked.iter().map(|(id, _)| id.as_str()))
+                    .collect();
+                let terms = ids
+                    .into_iter()
+                    .map(|id| {
+                        (
+                            Occur::Should,
+                            Box::new(TermQuery::new(
+                                Term::from_field_text(fields.source_id, id),
+                                IndexRecordOption::Basic,
+                            )) as Box<dyn Query>,
+                        )
+                    })
+                    .collect();
+                clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
             }
         }
 
@@ -2384,6 +2836,10 @@ impl SearchClient {
                 .and_then(|v| v.as_str())
                 .filter(|s| !s.is_empty())
                 .map(String::from);
+            let owner = doc
+                .get_first(fields.owner)
+                .and_then(|v| v.as_str())
+                .map(String::from);
             hits.push(SearchHit {
                 title,
                 snippet,
@@ -2399,6 +2855,9 @@ impl SearchClient {
                 source_id,
                 origin_kind,
                 origin_host,
+                owner,
+                fusion: None,
+                seen_in: Vec::new(),
             });
         }
         Ok(hits)
@@ -2456,6 +2915,20 @@ impl SearchClient {
             }
         }
 
+        // The FTS table has no owner column; go through the message's conversation
+        if !filters.owners.is_empty() {
+            let placeholders = (0..filters.owners.len())
+                .map(|_| "?".to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            sql.push_str(&format!(
+                " AND m.conversation_id IN (SELECT id FROM conversations WHERE owner IN ({placeholders}))"
+            ));
+            for o in filters.owners {
+                params.push(Box::new(o));
+            }
+        }
+
         if let Some(created_from) = filters.created_from {
             sql.push_str(" AND f.created_at >= ?");
             params.push(Box::new(created_from));
@@ -2500,6 +2973,9 @@ impl SearchClient {
                     source_id: default_source_id(),
                     origin_kind: default_origin_kind(),
                     origin_host: None,
+                    owner: None,
+                    fusion: None,
+                    seen_in: Vec::new(),
                 })
             },
         )?;
@@ -2775,6 +3251,32 @@ fn cached_prefix_snippet(content: &str, query: &str, max_chars: usize) -> Option
     })
 }
 
+/// Keep hits owned by one of `filters.owners` (no-op when none are set).
+fn retain_owners(hits: &mut Vec<SearchHit>, filters: &SearchFilters) {
+    if !filters.owners.is_empty() {
+        hits.retain(|h| {
+            h.owner
+                .as_ref()
+                .is_some_and(|owner| filters.owners.contains(owner))
+        });
+    }
+}
+
+/// Keep hits from the filtered source itself or from one of its `linked`
+/// sessions (see `SearchClient::linked_sessions`).
+fn retain_source_or_linked(
+    hits: &mut Vec<SearchHit>,
+    filter: &SourceFilter,
+    linked: &HashSet<(String, String)>,
+) {
+    if let SourceFilter::SourceId(source_id) = filter {
+        hits.retain(|h| {
+            &h.source_id == source_id
+                || linked.contains(&(h.source_id.clone(), h.source_path.clone()))
+        });
+    }
+}
+
 fn filters_fingerprint(filters: &SearchFilters) -> String {
     let mut parts = Vec::new();
     if !filters.agents.is_empty() {
@@ -2806,6 +3308,11 @@ fn filters_fingerprint(filters: &SearchFilters) -> String {
         v.sort();
         parts.push(format!("sp:{v:?}"));
     }
+    if !filters.owners.is_empty() {
+        let mut v: Vec<_> = filters.owners.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("o:{v:?}"));
+    }
     parts.join("|")
 }
 
@@ -2964,6 +3471,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
 
         let cached = CachedHit {
@@ -3048,6 +3558,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         }];
 
         client.put_cache("こん", &SearchFilters::default(), &hits);
@@ -3076,6 +3589,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
         let cached = cached_hit_from(&hit);
         assert!(hit_matches_query_cached(&cached, "hello"));
@@ -3512,6 +4028,136 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn search_similar_uses_stored_vectors_and_excludes_anchor_session() -> Result<()> {
+        use crate::search::hash_embedder::HashEmbedder;
+        use crate::search::vector_index::{Quantization, VectorEntry};
+
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE agents (id INTEGER PRIMARY KEY, slug TEXT NOT NULL);
+            CREATE TABLE workspaces (id INTEGER PRIMARY KEY, path TEXT NOT NULL);
+            CREATE TABLE sources (id TEXT PRIMARY KEY, kind TEXT NOT NULL);
+            CREATE TABLE conversations (
+                id INTEGER PRIMARY KEY, agent_id INTEGER, workspace_id INTEGER,
+                source_id TEXT, origin_host TEXT, title TEXT, source_path TEXT NOT NULL,
+                owner TEXT
+            );
+            CREATE TABLE messages (
+                id INTEGER PRIMARY KEY, conversation_id INTEGER, idx INTEGER,
+                role TEXT, created_at INTEGER, content TEXT NOT NULL
+            );
+            INSERT INTO agents VALUES (1, 'codex');
+            INSERT INTO sources VALUES ('local', 'local');
+            INSERT INTO conversations VALUES (1, 1, NULL, 'local', NULL, 'anchor', '/a.jsonl', NULL);
+            INSERT INTO conversations VALUES (2, 1, NULL, 'local', NULL, 'other', '/b.jsonl', NULL);
+            INSERT INTO messages VALUES (1, 1, 0, 'user', 1, 'anchor one');
+            INSERT INTO messages VALUES (2, 1, 1, 'assistant', 2, 'anchor two');
+            INSERT INTO messages VALUES (3, 2, 0, 'user', 3, 'close to one');
+            INSERT INTO messages VALUES (4, 2, 1, 'assistant', 4, 'close to two');
+            INSERT INTO conversations VALUES (3, 1, NULL, 'local', NULL, 'far', '/c.jsonl', 'bob');
+            INSERT INTO messages VALUES (5, 3, 0, 'user', 5, 'far one');
+            INSERT INTO messages VALUES (6, 3, 1, 'assistant', 6, 'far two');
+            "#,
+        )?;
+        let filter_maps = SemanticFilterMaps::from_connection(&conn)?;
+
+        let entry = |message_id: u64, vector: Vec<f32>| VectorEntry {
+            message_id,
+            created_at_ms: message_id as i64,
+            agent_id: 1,
+            workspace_id: 0,
+            source_id: 0,
+            role: 0,
+            chunk_idx: 0,
+            content_hash: [message_id as u8; 32],
+            vector,
+        };
+        let index = VectorIndex::build(
+            "fnv1a-3",
+            "rev",
+            3,
+            Quantization::F32,
+            vec![
+                entry(1, vec![1.0, 0.0, 0.0]),
+                entry(2, vec![0.0, 1.0, 0.0]),
+                entry(3, vec![0.9, 0.1, 0.0]),
+                entry(4, vec![0.0, 0.9, 0.1]),
+                entry(5, vec![0.1, 0.0, 0.9]),
+                entry(6, vec![0.0, 0.0, 1.0]),
+            ],
+        )?;
+
+        let client = SearchClient {
+            reader: None,
+            sqlite: Some(conn),
+            prefix_cache: Mutex::new(CacheShards::new(*CACHE_TOTAL_CAP, *CACHE_BYTE_CAP)),
+            last_reload: Mutex::new(None),
+            last_generation: Mutex::new(None),
+            reload_epoch: Arc::new(AtomicU64::new(0)),
+            warm_tx: None,
+            _warm_handle: None,
+            _shared_filters: Arc::new(Mutex::new(())),
+            metrics: Metrics::default(),
+            cache_namespace: format!("v{CACHE_KEY_VERSION}|schema:test"),
+            semantic: Mutex::new(None),
+        };
+        client.set_semantic_context(Arc::new(HashEmbedder::new(3)), index, filter_maps, None)?;
+
+        // Single-message anchor: nearest neighbour of message 1 outside its own session.
+        let anchor = SimilarAnchor {
+            source_path: "/a.jsonl".into(),
+            line_number: Some(1),
+        };
+        let hits = client.search_similar(&anchor, SearchFilters::default(), 1, 0)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].content, "close to one");
+
+        // Whole-session anchor: never returns messages from the anchor session.
+        let anchor = SimilarAnchor {
+            source_path: "/a.jsonl".into(),
+            line_number: None,
+        };
+        let hits = client.search_similar(&anchor, SearchFilters::default(), 10, 0)?;
+        assert_eq!(hits.len(), 4);
+        assert!(hits.iter().all(|h| h.source_path != "/a.jsonl"));
+
+        // Owner and session-path filters apply before the page is cut, even
+        // when the matching hits rank below unfiltered ones.
+        let anchor = SimilarAnchor {
+            source_path: "/a.jsonl".into(),
+            line_number: Some(1),
+        };
+        let by_owner = SearchFilters {
+            owners: HashSet::from(["bob".to_string()]),
+            ..SearchFilters::default()
+        };
+        let hits = client.search_similar(&anchor, by_owner, 1, 1)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].content, "far two");
+        let by_path = SearchFilters {
+            session_paths: HashSet::from(["/c.jsonl".to_string()]),
+            ..SearchFilters::default()
+        };
+        let hits = client.search_similar(&anchor, by_path, 10, 0)?;
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.source_path == "/c.jsonl"));
+
+        // Unknown session is an error rather than an empty result.
+        let missing = SimilarAnchor {
+            source_path: "/missing.jsonl".into(),
+            line_number: None,
+        };
+        assert!(
+            client
+                .search_similar(&missing, SearchFilters::default(), 10, 0)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn cache_invalidates_on_new_data() -> Result<()> {
         let dir = TempDir::new()?;
@@ -3633,6 +4279,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
         let hits = vec![hit];
 
@@ -3687,6 +4336,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
         let hits = vec![hit.clone()];
 
@@ -3769,6 +4421,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
 
         // Put 3 entries - should trigger 1 eviction (cap is 2)
@@ -3831,6 +4486,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
 
         // Put 3 large entries - should trigger byte-based evictions
@@ -4126,6 +4784,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -4142,6 +4803,9 @@ mod tests {
                 source_id: "local".into(), // same source_id = will dedupe
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
         ];
 
@@ -4169,6 +4833,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -4185,6 +4852,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
         ];
 
@@ -4212,6 +4882,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -4228,6 +4901,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
         ];
 
@@ -4253,6 +4929,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -4269,6 +4948,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
         ];
 
@@ -4295,6 +4977,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -4311,6 +4996,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "title3".into(),
@@ -4327,6 +5015,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
         ];
 
@@ -4354,6 +5045,9 @@ mod tests {
                 source_id: "local".into(),
                 origin_kind: "local".into(),
                 origin_host: None,
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
             SearchHit {
                 title: "remote title".into(),
@@ -4370,6 +5064,9 @@ mod tests {
                 source_id: "work-laptop".into(), // different source = no dedupe
                 origin_kind: "ssh".into(),
                 origin_host: Some("work-laptop.local".into()),
+                owner: None,
+                fusion: None,
+                seen_in: Vec::new(),
             },
         ];
 
@@ -6451,6 +7148,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn search_owner_filter() -> Result<()> {
+        // Shared index: each session carries its owner from cass.origin
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+
+        for (i, owner) in [Some("alice"), Some("bob"), None].iter().enumerate() {
+            let conv = NormalizedConversation {
+                agent_slug: "claude".into(),
+                external_id: None,
+                title: Some(format!("session-{i}")),
+                workspace: Some(std::path::PathBuf::from("/ws")),
+                source_path: dir.path().join(format!("session-{i}.jsonl")),
+                started_at: Some(100 + i as i64),
+                ended_at: None,
+                metadata: serde_json::json!({
+                    "cass": { "origin": { "source_id": "local", "kind": "local", "owner": owner } }
+                }),
+                messages: vec![NormalizedMessage {
+                    idx: 0,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(100 + i as i64),
+                    content: format!("needle content for session {i}"),
+                    extra: serde_json::json!({}),
+                    snippets: vec![],
+                }],
+            };
+            index.add_conversation(&conv)?;
+        }
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let hits_all = client.search("needle", SearchFilters::default(), 10, 0)?;
+        assert_eq!(hits_all.len(), 3);
+
+        let mut filters = SearchFilters::default();
+        filters.owners.insert("bob".into());
+        let hits = client.search("needle", filters, 10, 0)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].owner.as_deref(), Some("bob"));
+        assert_eq!(hits[0].title, "session-1");
+
+        Ok(())
+    }
+
     #[test]
     fn search_session_paths_empty_filter_returns_all() -> Result<()> {
         // Empty session_paths filter should not restrict results
@@ -6511,6 +7254,9 @@ mod tests {
             source_id: "local".to_string(),
             origin_kind: "local".to_string(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         }
     }
 
@@ -6666,4 +7412,156 @@ mod tests {
             assert!(seen.insert(&hit.title), "Duplicate hit: {}", hit.title);
         }
     }
+
+    fn resolved(strategy: FusionStrategy, semantic_weight: f32) -> ResolvedFusion {
+        FusionConfig {
+            strategy,
+            semantic_weight: Some(semantic_weight),
+            ..FusionConfig::default()
+        }
+        .resolve("")
+    }
+
+    #[test]
+    fn test_fuse_hits_records_one_based_ranks() {
+        let lexical = vec![make_test_hit("A", 10.0), make_test_hit("B", 8.0)];
+        let semantic = vec![make_test_hit("B", 0.9), make_test_hit("C", 0.7)];
+
+        let fused = rrf_fuse_hits(&lexical, &semantic, 10, 0);
+        let b = fused.iter().find(|h| h.title == "B").unwrap();
+        let ranks = b.fusion.as_ref().expect("fused hits carry ranks");
+        assert_eq!(ranks.lexical_rank, Some(2));
+        assert_eq!(ranks.semantic_rank, Some(1));
+        assert_eq!(ranks.lexical_score, Some(8.0));
+        assert_eq!(ranks.semantic_score, Some(0.9));
+
+        let c = fused.iter().find(|h| h.title == "C").unwrap();
+        let ranks = c.fusion.as_ref().unwrap();
+        assert_eq!(ranks.lexical_rank, None);
+        assert_eq!(ranks.semantic_rank, Some(2));
+
+        // Default fusion is plain RRF: 1/(k + rank)
+        assert!((fused[0].score - (1.0 / 62.0 + 1.0 / 61.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fuse_hits_semantic_weight_shifts_rrf_order() {
+        let lexical = vec![make_test_hit("A", 10.0)];
+        let semantic = vec![make_test_hit("C", 0.9)];
+
+        let lexical_heavy = fuse_hits(
+            &lexical,
+            &semantic,
+            10,
+            0,
+            &resolved(FusionStrategy::Rrf, 0.2),
+        );
+        assert_eq!(lexical_heavy[0].title, "A");
+
+        let semantic_heavy = fuse_hits(
+            &lexical,
+            &semantic,
+            10,
+            0,
+            &resolved(FusionStrategy::Rrf, 0.8),
+        );
+        assert_eq!(semantic_heavy[0].title, "C");
+
+        let lexical_only = fuse_hits(
+            &lexical,
+            &semantic,
+            10,
+            0,
+            &resolved(FusionStrategy::Rrf, 0.0),
+        );
+        assert_eq!(lexical_only[1].title, "C");
+        assert_eq!(lexical_only[1].score, 0.0);
+    }
+
+    #[test]
+    fn test_fuse_hits_weighted_uses_normalized_scores() {
+        let lexical = vec![
+            make_test_hit("A", 10.0),
+            make_test_hit("B", 5.0),
+            make_test_hit("C", 0.0),
+        ];
+        let semantic = vec![
+            make_test_hit("C", 0.9),
+            make_test_hit("B", 0.8),
+            make_test_hit("A", 0.1),
+        ];
+
+        let fused = fuse_hits(
+            &lexical,
+            &semantic,
+            10,
+            0,
+            &resolved(FusionStrategy::Weighted, 0.5),
+        );
+        // B: 0.5 * 0.5 + 0.5 * 0.875 beats A and C (0.5 each)
+        assert_eq!(fused[0].title, "B");
+        assert!((fused[0].score - 0.6875).abs() < 1e-6);
+        assert!((fused[1].score - 0.5).abs() < 1e-6);
+
+        // Single-element lists normalize to 1.0 rather than dividing by zero
+        let fused = fuse_hits(
+            &lexical[..1],
+            &[],
+            10,
+            0,
+            &resolved(FusionStrategy::Weighted, 0.25),
+        );
+        assert!((fused[0].score - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_auto_fusion_weights_by_query_type() {
+        let auto = FusionConfig {
+            strategy: FusionStrategy::Auto,
+            ..FusionConfig::default()
+        };
+
+        for query in [
+            "parse_boolean_query",
+            "SearchClient::open",
+            "E0502",
+            "vectorIndex",
+            "\"connection refused\"",
+            "auth*",
+            "tokio AND spawn",
+        ] {
+            let plan = auto.resolve(query);
+            assert_eq!(plan.strategy, FusionStrategy::Rrf, "{query}");
+            assert_eq!(plan.requested, FusionStrategy::Auto);
+            assert_eq!(
+                plan.semantic_weight, AUTO_LEXICAL_SEMANTIC_WEIGHT,
+                "{query} should lean lexical"
+            );
+        }
+
+        for query in [
+            "how do I retry failed uploads",
+            "why is the build slow?",
+            "flaky tests after upgrading the database driver",
+        ] {
+            assert_eq!(
+                auto.resolve(query).semantic_weight,
+                AUTO_NATURAL_SEMANTIC_WEIGHT,
+                "{query} should lean semantic"
+            );
+        }
+
+        assert_eq!(
+            auto.resolve("deadlock").semantic_weight,
+            DEFAULT_SEMANTIC_WEIGHT
+        );
+        assert_eq!(auto.resolve("deadlock").query_type, Some(QueryType::Simple));
+
+        // An explicit weight overrides the heuristic
+        let pinned = FusionConfig {
+            semantic_weight: Some(0.9),
+            ..auto
+        };
+        assert_eq!(pinned.resolve("parse_boolean_query").semantic_weight, 0.9);
+    }
 }
@@ -0,0 +1,151 @@
+//! Result ordering for the TUI's ranking modes (F12).
+//!
+//! Hits come back from [`query`](super::query) in score order; a
+//! [`RankingMode`] re-sorts them to trade relevance against recency. `cass
+//! eval` applies the same ordering so offline metrics match what the TUI shows.
+
+use crate::search::query::{SearchHit, SearchMode};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RankingMode {
+    RecentHeavy,
+    Balanced,
+    RelevanceHeavy,
+    MatchQualityHeavy, // Prioritizes exact matches over wildcard/fuzzy
+    DateNewest,        // Pure newest-first (ignores relevance score)
+    DateOldest,        // Pure oldest-first (ignores relevance score)
+}
+
+impl RankingMode {
+    pub const ALL: [RankingMode; 6] = [
+        RankingMode::RecentHeavy,
+        RankingMode::Balanced,
+        RankingMode::RelevanceHeavy,
+        RankingMode::MatchQualityHeavy,
+        RankingMode::DateNewest,
+        RankingMode::DateOldest,
+    ];
+
+    /// Stable name, as persisted in `tui_state.json` and accepted by `cass eval --ranking`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RankingMode::RecentHeavy => "recent",
+            RankingMode::Balanced => "balanced",
+            RankingMode::RelevanceHeavy => "relevance",
+            RankingMode::MatchQualityHeavy => "quality",
+            RankingMode::DateNewest => "newest",
+            RankingMode::DateOldest => "oldest",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_str() == s)
+    }
+}
+
+/// Re-order fetched results for a `RankingMode` (F12). Shared with `cass eval`
+/// so offline metrics see the same order as the TUI.
+pub fn apply_ranking_mode(
+    results: &mut [SearchHit],
+    search_mode: SearchMode,
+    ranking_mode: RankingMode,
+) {
+    let max_created = results
+        .iter()
+        .filter_map(|h| h.created_at)
+        .max()
+        .unwrap_or(0) as f32;
+    // Handle pure date sorting modes separately
+    if matches!(
+        ranking_mode,
+        RankingMode::DateNewest | RankingMode::DateOldest
+    ) {
+        results.sort_by(|a, b| {
+            let ts_a = a.created_at.unwrap_or(0);
+            let ts_b = b.created_at.unwrap_or(0);
+            if matches!(ranking_mode, RankingMode::DateNewest) {
+                ts_b.cmp(&ts_a) // Descending (newest first)
+            } else {
+                ts_a.cmp(&ts_b) // Ascending (oldest first)
+            }
+        });
+        return;
+    }
+
+    // RankingMode support for all search modes (bead vq8v)
+    // Recency helper (shared across all modes)
+    let recency = |h: &SearchHit| -> f32 {
+        if max_created <= 0.0 {
+            return 0.0;
+        }
+        h.created_at.map_or(0.0, |v| v as f32 / max_created)
+    };
+
+    match search_mode {
+        SearchMode::Lexical => {
+            // Lexical: BM25 score * quality_factor + alpha * recency
+            let alpha = match ranking_mode {
+                RankingMode::RecentHeavy => 1.0,
+                RankingMode::Balanced => 0.4,
+                RankingMode::RelevanceHeavy => 0.1,
+                RankingMode::MatchQualityHeavy => 0.2,
+                RankingMode::DateNewest | RankingMode::DateOldest => unreachable!(),
+            };
+            // Per-hit quality factor based on match_type
+            //   Exact: 1.0, Prefix: 0.9, Suffix: 0.8,
+            //   Substring: 0.7, ImplicitWildcard: 0.6
+            let quality_factor = |h: &SearchHit| -> f32 { h.match_type.quality_factor() };
+            results.sort_by(|a, b| {
+                let score_a = (a.score * quality_factor(a)) + alpha * recency(a);
+                let score_b = (b.score * quality_factor(b)) + alpha * recency(b);
+                score_b
+                    .partial_cmp(&score_a)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+        SearchMode::Semantic => {
+            // Semantic: normalize similarity [-1,1] -> [0,1]
+            // Then apply weighted blend (per bead vq8v spec)
+            let (score_weight, recency_weight) = match ranking_mode {
+                RankingMode::RecentHeavy => (0.3, 0.7),
+                RankingMode::Balanced => (0.5, 0.5),
+                RankingMode::RelevanceHeavy => (0.8, 0.2),
+                RankingMode::MatchQualityHeavy => (0.85, 0.15),
+                RankingMode::DateNewest | RankingMode::DateOldest => unreachable!(),
+            };
+            let norm_score = |h: &SearchHit| (h.score + 1.0) / 2.0;
+            results.sort_by(|a, b| {
+                let score_a = score_weight * norm_score(a) + recency_weight * recency(a);
+                let score_b = score_weight * norm_score(b) + recency_weight * recency(b);
+                score_b
+                    .partial_cmp(&score_a)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+        SearchMode::Hybrid => {
+            // Hybrid: RRF scores normalized to [0,1], then weighted blend
+            let max_rrf = results.iter().map(|h| h.score).fold(0.0f32, f32::max);
+            let (score_weight, recency_weight) = match ranking_mode {
+                RankingMode::RecentHeavy => (0.3, 0.7),
+                RankingMode::Balanced => (0.5, 0.5),
+                RankingMode::RelevanceHeavy => (0.8, 0.2),
+                RankingMode::MatchQualityHeavy => (0.85, 0.15),
+                RankingMode::DateNewest | RankingMode::DateOldest => unreachable!(),
+            };
+            let norm_score = |h: &SearchHit| {
+                if max_rrf > 0.0 {
+                    h.score / max_rrf
+                } else {
+                    0.0
+                }
+            };
+            results.sort_by(|a, b| {
+                let score_a = score_weight * norm_score(a) + recency_weight * recency(a);
+                let score_b = score_weight * norm_score(b) + recency_weight * recency(b);
+                score_b
+                    .partial_cmp(&score_a)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+    }
+}
@@ -49,7 +49,7 @@ impl MergeStatus {
 }
 
 // Bump this when schema/tokenizer changes. Used to trigger rebuilds.
-pub const SCHEMA_HASH: &str = "tantivy-schema-v6-provenance-indexed";
+pub const SCHEMA_HASH: &str = "tantivy-schema-v7-owner";
 
 #[derive(Clone, Copy)]
 pub struct Fields {
@@ -68,6 +68,7 @@ pub struct Fields {
     pub source_id: Field,
     pub origin_kind: Field,
     pub origin_host: Field,
+    pub owner: Field,
 }
 
 pub struct TantivyIndex {
@@ -277,6 +278,9 @@ impl TantivyIndex {
         let origin_host = cass_origin
             .and_then(|o| o.get("host"))
             .and_then(|v| v.as_str());
+        let owner = cass_origin
+            .and_then(|o| o.get("owner"))
+            .and_then(|v| v.as_str());
 
         // Precompute per-conversation fields once (indexing hot path).
         let source_path = conv.source_path.to_string_lossy();
@@ -304,6 +308,11 @@ impl TantivyIndex {
             {
                 d.add_text(self.fields.origin_host, host);
             }
+            if let Some(owner) = owner
+                && !owner.is_empty()
+            {
+                d.add_text(self.fields.owner, owner);
+            }
             if let Some(ws) = &workspace {
                 d.add_text(self.fields.workspace, ws.as_ref());
             }
@@ -413,6 +422,8 @@ pub fn build_schema() -> Schema {
     schema_builder.add_text_field("source_id", STRING | STORED);
     schema_builder.add_text_field("origin_kind", STRING | STORED);
     schema_builder.add_text_field("origin_host", STRING | STORED);
+    // User the session belongs to (shared indexes)
+    schema_builder.add_text_field("owner", STRING | STORED);
     schema_builder.build()
 }
 
@@ -437,6 +448,7 @@ pub fn fields_from_schema(schema: &Schema) -> Result<Fields> {
         source_id: get("source_id")?,
         origin_kind: get("origin_kind")?,
         origin_host: get("origin_host")?,
+        owner: get("owner")?,
     })
 }
 
@@ -736,6 +748,7 @@ mod tests {
         assert!(schema.get_field("source_id").is_ok());
         assert!(schema.get_field("origin_kind").is_ok());
         assert!(schema.get_field("origin_host").is_ok());
+        assert!(schema.get_field("owner").is_ok());
     }
 
     #[test]
@@ -759,6 +772,7 @@ mod tests {
         let _ = fields.source_id;
         let _ = fields.origin_kind;
         let _ = fields.origin_host;
+        let _ = fields.owner;
     }
 
     #[test]
@@ -800,7 +814,7 @@ mod tests {
     #[test]
     fn title_prefix_ngrams_are_reused_for_each_message_doc() {
         use crate::connectors::{NormalizedConversation, NormalizedMessage};
-        use crate::search::query::{FieldMask, SearchClient, SearchFilters};
+        use crate::search::query::{SearchClient, SearchFilters};
 
         let dir = TempDir::new().unwrap();
         let index_path = dir.path();
@@ -846,7 +860,7 @@ mod tests {
 
         let client = SearchClient::open(index_path, None).unwrap().unwrap();
         let hits = client
-            .search("un", SearchFilters::default(), 10, 0, FieldMask::FULL)
+            .search("un", SearchFilters::default(), 10, 0)
             .unwrap();
 
         assert_eq!(
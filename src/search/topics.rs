@@ -0,0 +1,584 @@
+//! Topic clustering over conversation-level embeddings.
+//!
+//! Each conversation is represented by the L2-normalized mean of its message vectors from
+//! the CVVI index. Conversations are grouped with spherical k-means (cosine similarity,
+//! deterministic farthest-point seeding) and each cluster is labelled with the terms that
+//! are frequent inside it but rare across the Tantivy index (TF-IDF).
+//!
+//! Everything runs locally against existing indexes; nothing is re-embedded.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use rusqlite::Connection;
+use serde::Serialize;
+use tantivy::{Index, Term};
+
+use crate::search::model_registry::active_model_manifest;
+use crate::search::query::{MatchType, SearchHit};
+use crate::search::tantivy::{ensure_tokenizer, fields_from_schema, index_dir};
+use crate::search::vector_index::{VectorIndex, vector_index_path};
+
+/// Number of label terms per topic when not specified.
+pub const DEFAULT_LABEL_TERMS: usize = 5;
+/// Upper bound for the automatically chosen cluster count.
+const MAX_AUTO_K: usize = 20;
+const MAX_ITERATIONS: usize = 50;
+/// Label terms shorter than this are ignored (mostly noise like "a", "is", "fn").
+const MIN_TERM_LEN: usize = 3;
+
+/// Options for [`build_topics`].
+#[derive(Debug, Clone, Default)]
+pub struct TopicOptions {
+    /// Number of clusters; `None` picks one from the number of sessions.
+    pub k: Option<usize>,
+    /// Label terms per topic (0 = [`DEFAULT_LABEL_TERMS`]).
+    pub label_terms: usize,
+    pub agents: HashSet<String>,
+    pub workspaces: HashSet<String>,
+    pub created_from: Option<i64>,
+    pub created_to: Option<i64>,
+}
+
+/// A session belonging to a topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicMember {
+    pub source_path: String,
+    pub title: String,
+    pub agent: String,
+    pub workspace: Option<String>,
+    pub started_at: Option<i64>,
+    pub source_id: String,
+    pub origin_kind: String,
+    pub origin_host: Option<String>,
+    /// Cosine similarity to the topic centroid.
+    pub similarity: f32,
+}
+
+impl TopicMember {
+    /// Session-level hit (no line number) so members can be shown in result lists.
+    pub fn to_search_hit(&self) -> SearchHit {
+        SearchHit {
+            title: self.title.clone(),
+            snippet: self.title.clone(),
+            content: self.title.clone(),
+            score: self.similarity,
+            source_path: self.source_path.clone(),
+            agent: self.agent.clone(),
+            workspace: self.workspace.clone().unwrap_or_default(),
+            workspace_original: None,
+            created_at: self.started_at,
+            line_number: None,
+            match_type: MatchType::default(),
+            source_id: self.source_id.clone(),
+            origin_kind: self.origin_kind.clone(),
+            origin_host: self.origin_host.clone(),
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
+        }
+    }
+}
+
+/// A cluster of related sessions, members ordered by similarity to the centroid.
+#[derive(Debug, Clone, Serialize)]
+pub struct Topic {
+    pub id: usize,
+    pub label: String,
+    pub terms: Vec<String>,
+    pub size: usize,
+    pub members: Vec<TopicMember>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicsReport {
+    /// Sessions that had at least one embedded message.
+    pub sessions: usize,
+    pub k: usize,
+    /// Topics ordered by size (largest first).
+    pub topics: Vec<Topic>,
+}
+
+/// Pick a cluster count for `n` sessions: √(n/2), clamped to `2..=MAX_AUTO_K`.
+pub fn default_k(n: usize) -> usize {
+    if n < 2 {
+        return n;
+    }
+    let k = ((n as f64) / 2.0).sqrt().round() as usize;
+    k.clamp(2, MAX_AUTO_K).min(n)
+}
+
+/// Average the vectors of every message in each session and L2-normalize.
+///
+/// `message_sessions` maps message id to conversation id; rows for other messages are
+/// skipped. Output is sorted by conversation id so clustering is deterministic.
+pub fn session_vectors(
+    index: &VectorIndex,
+    message_sessions: &HashMap<u64, i64>,
+) -> Result<Vec<(i64, Vec<f32>)>> {
+    let dimension = index.header().dimension as usize;
+    let mut sums: HashMap<i64, Vec<f32>> = HashMap::new();
+    for row in index.rows() {
+        let Some(conversation_id) = message_sessions.get(&row.message_id) else {
+            continue;
+        };
+        let vector = index.vector_at_f32(row)?;
+        let sum = sums
+            .entry(*conversation_id)
+            .or_insert_with(|| vec![0.0; dimension]);
+        for (acc, value) in sum.iter_mut().zip(vector.iter()) {
+            *acc += *value;
+        }
+    }
+
+    let mut out: Vec<(i64, Vec<f32>)> = sums
+        .into_iter()
+        .map(|(id, mut vector)| {
+            normalize(&mut vector);
+            (id, vector)
+        })
+        .collect();
+    out.sort_by_key(|(id, _)| *id);
+    Ok(out)
+}
+
+/// Spherical k-means over unit vectors.
+///
+/// Seeds with the first vector, then repeatedly the vector least similar to any chosen
+/// centroid, so results are reproducible. Returns per-vector assignments and centroids.
+pub fn spherical_kmeans(
+    vectors: &[Vec<f32>],
+    k: usize,
+    max_iterations: usize,
+) -> (Vec<usize>, Vec<Vec<f32>>) {
+    let k = k.min(vectors.len());
+    if k == 0 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut centroids: Vec<Vec<f32>> = vec![vectors[0].clone()];
+    let mut best_sim: Vec<f32> = vectors.iter().map(|v| dot(v, &vectors[0])).collect();
+    while centroids.len() < k {
+        let next = best_sim
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(idx, _)| idx);
+        centroids.push(vectors[next].clone());
+        for (sim, vector) in best_sim.iter_mut().zip(vectors) {
+            *sim = sim.max(dot(vector, &vectors[next]));
+        }
+    }
+
+    let mut assignments = vec![usize::MAX; vectors.len()];
+    for _ in 0..max_iterations {
+        let mut changed = false;
+        for (assignment, vector) in assignments.iter_mut().zip(vectors) {
+            let nearest = nearest_centroid(vector, &centroids);
+            if *assignment != nearest {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let dimension = vectors[0].len();
+        let mut sums = vec![vec![0.0f32; dimension]; k];
+        for (assignment, vector) in assignments.iter().zip(vectors) {
+            for (acc, value) in sums[*assignment].iter_mut().zip(vector) {
+                *acc += *value;
+            }
+        }
+        for (centroid, mut sum) in centroids.iter_mut().zip(sums) {
+            // Empty clusters keep their previous centroid.
+            if sum.iter().any(|v| *v != 0.0) {
+                normalize(&mut sum);
+                *centroid = sum;
+            }
+        }
+    }
+
+    (assignments, centroids)
+}
+
+/// Rank terms by `tf * ln(total_docs / (1 + df))` and return the top `n`.
+///
+/// Ties are broken alphabetically so labels are stable between runs.
+pub fn distinctive_terms(
+    term_counts: &HashMap<String, u64>,
+    total_docs: u64,
+    doc_freq: impl Fn(&str) -> u64,
+    n: usize,
+) -> Vec<String> {
+    let total = total_docs.max(1) as f64;
+    let mut scored: Vec<(f64, &String)> = term_counts
+        .iter()
+        .filter(|(term, _)| is_label_candidate(term))
+        .map(|(term, tf)| {
+            let idf = (total / (1.0 + doc_freq(term) as f64)).ln().max(0.0);
+            (*tf as f64 * idf, term)
+        })
+        .filter(|(score, _)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(n)
+        .map(|(_, term)| term.clone())
+        .collect()
+}
+
+struct SessionInfo {
+    source_path: String,
+    title: String,
+    agent: String,
+    workspace: Option<String>,
+    started_at: Option<i64>,
+    source_id: String,
+    origin_kind: String,
+    origin_host: Option<String>,
+}
+
+/// Cluster indexed sessions into topics using the active model's vector index under `data_dir`.
+pub fn build_topics(
+    data_dir: &Path,
+    db_path: &Path,
+    options: &TopicOptions,
+) -> Result<TopicsReport> {
+    let vector_path = vector_index_path(data_dir, &active_model_manifest(data_dir).embedder_id);
+    if !vector_path.is_file() {
+        bail!("vector index not found at {}", vector_path.display());
+    }
+    let vector_index = VectorIndex::load(&vector_path)?;
+
+    let conn = Connection::open_with_flags(db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("opening sqlite db readonly at {}", db_path.display()))?;
+
+    let sessions = load_sessions(&conn, options)?;
+    let mut message_sessions: HashMap<u64, i64> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT id, conversation_id FROM messages")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (message_id, conversation_id) = row?;
+            if sessions.contains_key(&conversation_id) {
+                message_sessions.insert(message_id as u64, conversation_id);
+            }
+        }
+    }
+
+    let vectors = session_vectors(&vector_index, &message_sessions)?;
+    let k = options
+        .k
+        .unwrap_or_else(|| default_k(vectors.len()))
+        .min(vectors.len());
+    let only_vectors: Vec<Vec<f32>> = vectors.iter().map(|(_, v)| v.clone()).collect();
+    let (assignments, centroids) = spherical_kmeans(&only_vectors, k, MAX_ITERATIONS);
+
+    let mut members_by_cluster: Vec<Vec<(i64, f32)>> = vec![Vec::new(); k];
+    for ((conversation_id, vector), cluster) in vectors.iter().zip(&assignments) {
+        let similarity = dot(vector, &centroids[*cluster]);
+        members_by_cluster[*cluster].push((*conversation_id, similarity));
+    }
+
+    let labeler = TermLabeler::open(data_dir)?;
+    let label_terms = if options.label_terms == 0 {
+        DEFAULT_LABEL_TERMS
+    } else {
+        options.label_terms
+    };
+
+    let mut topics = Vec::new();
+    for mut members in members_by_cluster {
+        if members.is_empty() {
+            continue;
+        }
+        members.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let ids: Vec<i64> = members.iter().map(|(id, _)| *id).collect();
+        let terms = labeler.label(&conn, &ids, label_terms)?;
+        let members = members
+            .into_iter()
+            .filter_map(|(id, similarity)| {
+                sessions.get(&id).map(|info| TopicMember {
+                    source_path: info.source_path.clone(),
+                    title: info.title.clone(),
+                    agent: info.agent.clone(),
+                    workspace: info.workspace.clone(),
+                    started_at: info.started_at,
+                    source_id: info.source_id.clone(),
+                    origin_kind: info.origin_kind.clone(),
+                    origin_host: info.origin_host.clone(),
+                    similarity,
+                })
+            })
+            .collect::<Vec<_>>();
+        topics.push(Topic {
+            id: 0,
+            label: if terms.is_empty() {
+                "(unlabelled)".to_string()
+            } else {
+                terms.join(", ")
+            },
+            terms,
+            size: members.len(),
+            members,
+        });
+    }
+    topics.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.label.cmp(&b.label)));
+    for (id, topic) in topics.iter_mut().enumerate() {
+        topic.id = id + 1;
+    }
+
+    Ok(TopicsReport {
+        sessions: vectors.len(),
+        k: topics.len(),
+        topics,
+    })
+}
+
+fn load_sessions(conn: &Connection, options: &TopicOptions) -> Result<HashMap<i64, SessionInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.source_path, c.title, a.slug, w.path, c.started_at,
+                COALESCE(c.source_id, 'local'), COALESCE(s.kind, 'local'), c.origin_host
+         FROM conversations c
+         JOIN agents a ON c.agent_id = a.id
+         LEFT JOIN workspaces w ON c.workspace_id = w.id
+         LEFT JOIN sources s ON c.source_id = s.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            SessionInfo {
+                source_path: row.get(1)?,
+                title: row
+                    .get::<_, Option<String>>(2)?
+                    .unwrap_or_else(|| "Untitled".to_string()),
+                agent: row.get(3)?,
+                workspace: row.get(4)?,
+                started_at: row.get(5)?,
+                source_id: row.get(6)?,
+                origin_kind: row.get(7)?,
+                origin_host: row.get(8)?,
+            },
+        ))
+    })?;
+
+    let mut sessions = HashMap::new();
+    for row in rows {
+        let (id, info) = row?;
+        if !options.agents.is_empty() && !options.agents.contains(&info.agent) {
+            continue;
+        }
+        if !options.workspaces.is_empty()
+            && !info
+                .workspace
+                .as_ref()
+                .is_some_and(|ws| options.workspaces.contains(ws))
+        {
+            continue;
+        }
+        if let Some(from) = options.created_from
+            && info.started_at.is_none_or(|ts| ts < from)
+        {
+            continue;
+        }
+        if let Some(to) = options.created_to
+            && info.started_at.is_none_or(|ts| ts > to)
+        {
+            continue;
+        }
+        sessions.insert(id, info);
+    }
+    Ok(sessions)
+}
+
+/// Tokenizes with the content field's analyzer and looks up document frequencies in Tantivy.
+struct TermLabeler {
+    index: Index,
+    searcher: tantivy::Searcher,
+    content: tantivy::schema::Field,
+}
+
+impl TermLabeler {
+    fn open(data_dir: &Path) -> Result<Self> {
+        let path = index_dir(data_dir)?;
+        let mut index = Index::open_in_dir(&path)
+            .with_context(|| format!("opening tantivy index at {}", path.display()))?;
+        ensure_tokenizer(&mut index);
+        let fields = fields_from_schema(&index.schema())?;
+        let searcher = index.reader()?.searcher();
+        Ok(Self {
+            index,
+            searcher,
+            content: fields.content,
+        })
+    }
+
+    fn label(&self, conn: &Connection, conversation_ids: &[i64], n: usize) -> Result<Vec<String>> {
+        let mut analyzer = self
+            .index
+            .tokenizer_for_field(self.content)
+            .map_err(|e| anyhow!("content tokenizer: {e}"))?;
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        let mut stmt = conn.prepare("SELECT content FROM messages WHERE conversation_id = ?1")?;
+        for id in conversation_ids {
+            let rows = stmt.query_map([id], |row| row.get::<_, String>(0))?;
+            for content in rows {
+                let content = content?;
+                let mut stream = analyzer.token_stream(&content);
+                while stream.advance() {
+                    *counts.entry(stream.token().text.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        let total_docs = self.searcher.num_docs();
+        Ok(distinctive_terms(
+            &counts,
+            total_docs,
+            |term| {
+                self.searcher
+                    .doc_freq(&Term::from_field_text(self.content, term))
+                    .unwrap_or(0)
+            },
+            n,
+        ))
+    }
+}
+
+fn is_label_candidate(term: &str) -> bool {
+    term.chars().count() >= MIN_TERM_LEN && !term.chars().all(|c| c.is_ascii_digit())
+}
+
+fn nearest_centroid(vector: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .max_by(|a, b| {
+            dot(vector, a.1)
+                .total_cmp(&dot(vector, b.1))
+                .then(b.0.cmp(&a.0))
+        })
+        .map_or(0, |(idx, _)| idx)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::vector_index::{Quantization, VectorEntry};
+
+    fn entry(message_id: u64, vector: Vec<f32>) -> VectorEntry {
+        VectorEntry {
+            message_id,
+            created_at_ms: 0,
+            agent_id: 0,
+            workspace_id: 0,
+            source_id: 0,
+            role: 0,
+            chunk_idx: 0,
+            content_hash: [message_id as u8; 32],
+            vector,
+        }
+    }
+
+    #[test]
+    fn default_k_scales_with_sessions() {
+        assert_eq!(default_k(0), 0);
+        assert_eq!(default_k(1), 1);
+        assert_eq!(default_k(2), 2);
+        assert_eq!(default_k(200), 10);
+        assert_eq!(default_k(100_000), MAX_AUTO_K);
+    }
+
+    #[test]
+    fn session_vectors_average_messages_per_conversation() -> Result<()> {
+        let index = VectorIndex::build(
+            "test-2",
+            "rev",
+            2,
+            Quantization::F32,
+            vec![
+                entry(1, vec![1.0, 0.0]),
+                entry(2, vec![0.0, 1.0]),
+                entry(3, vec![0.0, 1.0]),
+                entry(4, vec![1.0, 0.0]),
+            ],
+        )?;
+        // Message 4 belongs to a filtered-out conversation.
+        let map = HashMap::from([(1, 10), (2, 10), (3, 20)]);
+        let vectors = session_vectors(&index, &map)?;
+
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0].0, 10);
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((vectors[0].1[0] - half).abs() < 1e-6);
+        assert!((vectors[0].1[1] - half).abs() < 1e-6);
+        assert_eq!(vectors[1], (20, vec![0.0, 1.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn kmeans_separates_obvious_groups() {
+        let mut vectors = Vec::new();
+        for i in 0..5 {
+            let mut a = vec![1.0, 0.05 * i as f32, 0.0];
+            let mut b = vec![0.0, 0.05 * i as f32, 1.0];
+            normalize(&mut a);
+            normalize(&mut b);
+            vectors.push(a);
+            vectors.push(b);
+        }
+
+        let (assignments, centroids) = spherical_kmeans(&vectors, 2, MAX_ITERATIONS);
+        assert_eq!(centroids.len(), 2);
+        for pair in assignments.chunks(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+        assert!(assignments.iter().step_by(2).all(|c| *c == assignments[0]));
+    }
+
+    #[test]
+    fn kmeans_handles_k_larger_than_input() {
+        let vectors = vec![vec![1.0, 0.0]];
+        let (assignments, centroids) = spherical_kmeans(&vectors, 5, MAX_ITERATIONS);
+        assert_eq!(assignments, vec![0]);
+        assert_eq!(centroids.len(), 1);
+        assert!(spherical_kmeans(&[], 3, MAX_ITERATIONS).0.is_empty());
+    }
+
+    #[test]
+    fn distinctive_terms_prefer_rare_frequent_terms() {
+        let counts = HashMap::from([
+            ("the".to_string(), 50),
+            ("tokio".to_string(), 10),
+            ("runtime".to_string(), 10),
+            ("12345".to_string(), 40),
+            ("io".to_string(), 30),
+        ]);
+        let df = |term: &str| match term {
+            "the" => 1000,
+            "tokio" => 5,
+            "runtime" => 50,
+            _ => 1,
+        };
+
+        let terms = distinctive_terms(&counts, 1000, df, 3);
+        assert_eq!(terms, vec!["tokio".to_string(), "runtime".to_string()]);
+    }
+}
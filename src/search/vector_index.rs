@@ -515,7 +515,8 @@ impl SemanticFilterMaps {
         Ok(result)
     }
 
-    fn source_id(&self, source_id: &str) -> u32 {
+    /// Numeric source code stored in vector rows for `source_id`.
+    pub fn source_id(&self, source_id: &str) -> u32 {
         self.source_id_to_id
             .get(source_id)
             .copied()
@@ -1050,6 +1051,36 @@ impl VectorIndex {
         }
     }
 
+    /// Average every chunk vector belonging to `message_ids` and L2-normalize the result.
+    ///
+    /// Returns `None` when none of the messages have been embedded.
+    pub fn mean_vector_for_messages(&self, message_ids: &HashSet<u64>) -> Result<Option<Vec<f32>>> {
+        let dimension = self.header.dimension as usize;
+        let mut sum = vec![0.0f32; dimension];
+        let mut count = 0usize;
+        for row in &self.rows {
+            if !message_ids.contains(&row.message_id) {
+                continue;
+            }
+            let vector = self.vector_at_f32(row)?;
+            for (acc, value) in sum.iter_mut().zip(vector.iter()) {
+                *acc += *value;
+            }
+            count += 1;
+        }
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let norm = sum.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut sum {
+                *value /= norm;
+            }
+        }
+        Ok(Some(sum))
+    }
+
     pub fn header(&self) -> &CvviHeader {
         &self.header
     }
@@ -1756,6 +1787,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mean_vector_for_messages_normalizes() -> Result<()> {
+        let entries = sample_entries();
+        let index = VectorIndex::build("hash-3", "rev", 3, Quantization::F32, entries)?;
+
+        let mean = index
+            .mean_vector_for_messages(&HashSet::from([1, 2]))?
+            .expect("mean vector");
+        let expected = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((mean[0] - expected).abs() < 1e-6);
+        assert!((mean[1] - expected).abs() < 1e-6);
+        assert!(mean[2].abs() < 1e-6);
+
+        assert!(
+            index
+                .mean_vector_for_messages(&HashSet::from([99]))?
+                .is_none()
+        );
+        Ok(())
+    }
+
     #[test]
     fn f16_and_f32_rankings_match() -> Result<()> {
         let entries = sample_entries();
@@ -7,6 +7,9 @@
 //! # Example Configuration
 //!
 //! ```toml
+//! # How many sources `cass sources sync` and the daemon sync at once
+//! sync_parallelism = 4
+//!
 //! [[sources]]
 //! name = "laptop"
 //! type = "ssh"
@@ -14,12 +17,20 @@
 //! paths = ["~/.claude/projects", "~/.cursor"]
 //! sync_schedule = "manual"
 //!
+//! # Cap the transfer rate of a slow link at 512 KiB/s
 //! [[sources]]
 //! name = "workstation"
 //! type = "ssh"
 //! host = "user@work.example.com"
 //! paths = ["~/.claude/projects"]
 //! sync_schedule = "daily"
+//! bwlimit = 512
+//! # Only Codex sessions from the last month, without huge or debug logs
+//! agents = ["codex"]
+//! include = ["*.jsonl"]
+//! exclude = ["debug"]
+//! max_file_size = "50M"
+//! max_age_days = 30
 //!
 //! # Path mappings rewrite remote paths to local equivalents
 //! [[sources.path_mappings]]
@@ -31,12 +42,34 @@
 //! from = "/opt/work"
 //! to = "/Volumes/Work"
 //! agents = ["claude-code"]
+//!
+//! # CI agents pushing session logs to a git repo (paths are inside the repo)
+//! [[sources]]
+//! name = "ci-logs"
+//! type = "git"
+//! url = "git@github.com:acme/agent-logs.git"
+//! branch = "main"
+//! paths = ["claude"]
+//!
+//! # An HTTP file server; the index lists file paths relative to it
+//! [[sources]]
+//! name = "ci-http"
+//! type = "http"
+//! url = "https://files.example.com/agent-logs/index.txt"
+//!
+//! # A machine uploading with `cass push` to `cass serve-ingest`; only the
+//! # hash of its token is kept (created by `cass serve-ingest --new-token`)
+//! [[sources]]
+//! name = "ci-runner"
+//! type = "push"
+//! token_sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
 //! ```
 
 use serde::{Deserialize, Serialize};
 use std::path::{Component, Path, PathBuf};
 use thiserror::Error;
 
+use super::filter::SyncFilter;
 use super::provenance::SourceKind;
 
 /// Errors that can occur when loading or saving source configuration.
@@ -61,6 +94,19 @@ pub enum ConfigError {
 /// Root configuration containing all source definitions.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SourcesConfig {
+    /// How many sources to sync concurrently (default: 4).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_parallelism: Option<usize>,
+
+    /// Who this machine's local sessions belong to (default: the login name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Only show sessions owned by `owner` unless a search asks for others
+    /// (`--owner`, `--all-owners`). Useful for an index shared by a team.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_other_owners: bool,
+
     /// List of configured sources.
     #[serde(default)]
     pub sources: Vec<SourceDefinition>,
@@ -153,9 +199,22 @@ pub struct SourceDefinition {
     #[serde(default)]
     pub host: Option<String>,
 
+    /// Repository URL for git sources, index URL for HTTP sources.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Branch to track for git sources (default: the remote's HEAD).
+    #[serde(default)]
+    pub branch: Option<String>,
+
+    /// SHA-256 (hex) of the upload token of a push source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_sha256: Option<String>,
+
     /// Paths to sync from this source.
-    /// For SSH sources, these are remote paths.
-    /// Supports ~ expansion.
+    /// For SSH sources, these are remote paths and support ~ expansion.
+    /// For git sources, these are directories inside the repository
+    /// (default: the whole repository).
     #[serde(default)]
     pub paths: Vec<String>,
 
@@ -163,6 +222,15 @@ pub struct SourceDefinition {
     #[serde(default)]
     pub sync_schedule: SyncSchedule,
 
+    /// Bandwidth cap for syncing an SSH source, in KiB/s (rsync `--bwlimit`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bwlimit: Option<u32>,
+
+    /// Which files to sync and index: `include`/`exclude` globs, `agents`,
+    /// `max_file_size` and `max_age_days`.
+    #[serde(flatten)]
+    pub filter: SyncFilter,
+
     /// Path mappings for workspace rewriting.
     /// Maps remote paths to local equivalents.
     /// Example: "/home/user/projects" -> "/Users/me/projects"
@@ -172,6 +240,11 @@ pub struct SourceDefinition {
     /// Platform hint for default paths (macos, linux).
     #[serde(default)]
     pub platform: Option<Platform>,
+
+    /// Who the source's sessions belong to. SSH sources default to the
+    /// user in `host`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
 }
 
 impl SourceDefinition {
@@ -194,11 +267,71 @@ impl SourceDefinition {
         }
     }
 
-    /// Check if this source requires SSH connectivity.
+    /// Create a new git source definition.
+    pub fn git(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source_type: SourceKind::Git,
+            url: Some(url.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a new HTTP source definition.
+    pub fn http(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source_type: SourceKind::Http,
+            url: Some(url.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a new push source accepting uploads authenticated by a token
+    /// with the given SHA-256 (hex).
+    pub fn push(name: impl Into<String>, token_sha256: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source_type: SourceKind::Push,
+            token_sha256: Some(token_sha256.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Check if this source is synced into a local mirror (SSH, git or HTTP).
     pub fn is_remote(&self) -> bool {
+        matches!(
+            self.source_type,
+            SourceKind::Ssh | SourceKind::Git | SourceKind::Http
+        )
+    }
+
+    /// Check if this source uploads its sessions with `cass push`.
+    pub fn is_push(&self) -> bool {
+        matches!(self.source_type, SourceKind::Push)
+    }
+
+    /// Check if this source requires SSH connectivity.
+    pub fn is_ssh(&self) -> bool {
         matches!(self.source_type, SourceKind::Ssh)
     }
 
+    /// Who the source's sessions belong to: `owner`, else the user of an
+    /// SSH `user@host`.
+    pub fn effective_owner(&self) -> Option<String> {
+        if let Some(owner) = &self.owner {
+            return Some(owner.clone());
+        }
+        if !self.is_ssh() {
+            return None;
+        }
+        self.host
+            .as_deref()
+            .and_then(|h| h.split_once('@'))
+            .map(|(user, _)| user.to_string())
+            .filter(|user| !user.is_empty())
+    }
+
     /// Validate the source definition.
     pub fn validate(&self) -> Result<(), ConfigError> {
         if self.name.is_empty() {
@@ -219,14 +352,87 @@ impl SourceDefinition {
             ));
         }
 
-        if self.is_remote() && self.host.is_none() {
-            return Err(ConfigError::Validation("SSH sources require a host".into()));
+        if self.bwlimit == Some(0) {
+            return Err(ConfigError::Validation(
+                "bwlimit must be at least 1 KiB/s; remove it for no limit".into(),
+            ));
         }
 
-        if self.is_remote()
-            && let Some(host) = self.host.as_deref()
-        {
-            validate_ssh_host(host)?;
+        self.filter.validate()?;
+        if let Some(owner) = &self.owner {
+            validate_owner(owner)?;
+        }
+        if !self.filter.is_empty() && self.is_push() {
+            return Err(ConfigError::Validation(
+                "Push sources upload whole sessions; filter them on the pushing machine".into(),
+            ));
+        }
+
+        match self.source_type {
+            SourceKind::Local => {}
+            SourceKind::Ssh => {
+                let host = self
+                    .host
+                    .as_deref()
+                    .ok_or_else(|| ConfigError::Validation("SSH sources require a host".into()))?;
+                validate_ssh_host(host)?;
+            }
+            SourceKind::Git => {
+                let url = self
+                    .url
+                    .as_deref()
+                    .ok_or_else(|| ConfigError::Validation("Git sources require a url".into()))?;
+                validate_git_ref("Git url", url)?;
+                if let Some(branch) = self.branch.as_deref() {
+                    validate_git_ref("Git branch", branch)?;
+                }
+                for path in &self.paths {
+                    validate_relative_path(path)?;
+                }
+            }
+            SourceKind::Http => {
+                let url = self
+                    .url
+                    .as_deref()
+                    .ok_or_else(|| ConfigError::Validation("HTTP sources require a url".into()))?;
+                match reqwest::Url::parse(url) {
+                    Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                    _ => {
+                        return Err(ConfigError::Validation(format!(
+                            "HTTP source url must be an http(s) URL: {url}"
+                        )));
+                    }
+                }
+                if !self.paths.is_empty() {
+                    return Err(ConfigError::Validation(
+                        "HTTP sources take their files from the index at `url`; remove `paths`"
+                            .into(),
+                    ));
+                }
+            }
+            SourceKind::Push => {
+                let hash = self.token_sha256.as_deref().ok_or_else(|| {
+                    ConfigError::Validation(
+                        "Push sources require a token_sha256 (see 'cass serve-ingest --new-token')"
+                            .into(),
+                    )
+                })?;
+                if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(ConfigError::Validation(
+                        "token_sha256 must be 64 hex characters".into(),
+                    ));
+                }
+                if self.host.is_some() || self.url.is_some() || !self.paths.is_empty() {
+                    return Err(ConfigError::Validation(
+                        "Push sources upload their sessions; remove host, url and paths".into(),
+                    ));
+                }
+            }
+            SourceKind::Archive => {
+                return Err(ConfigError::Validation(
+                    "Archive sources are managed by 'cass pages import', not sources.toml".into(),
+                ));
+            }
         }
 
         Ok(())
@@ -262,6 +468,25 @@ impl SourceDefinition {
     }
 }
 
+/// Owners are matched exactly, so they are single words.
+fn validate_owner(owner: &str) -> Result<(), ConfigError> {
+    if owner.is_empty() || owner.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(ConfigError::Validation(format!(
+            "Invalid owner '{owner}': use a user name without spaces"
+        )));
+    }
+    Ok(())
+}
+
+/// Login name of the current user.
+fn login_name() -> Option<String> {
+    ["USER", "USERNAME"]
+        .iter()
+        .filter_map(|var| dotenvy::var(var).ok())
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty() && validate_owner(name).is_ok())
+}
+
 fn has_dot_components(path: &Path) -> bool {
     path.components()
         .any(|c| matches!(c, Component::CurDir | Component::ParentDir))
@@ -289,6 +514,43 @@ fn validate_ssh_host(host: &str) -> Result<(), ConfigError> {
     Ok(())
 }
 
+/// Reject values git would parse as options, and whitespace/control characters.
+fn validate_git_ref(what: &str, value: &str) -> Result<(), ConfigError> {
+    if value.trim().is_empty() {
+        return Err(ConfigError::Validation(format!("{what} cannot be empty")));
+    }
+
+    if value.starts_with('-') {
+        return Err(ConfigError::Validation(format!(
+            "{what} cannot start with '-' (would be parsed as a git option)"
+        )));
+    }
+
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(ConfigError::Validation(format!(
+            "{what} cannot contain whitespace or control characters"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Paths inside a git repository must stay inside it.
+fn validate_relative_path(path: &str) -> Result<(), ConfigError> {
+    let p = Path::new(path);
+    if path.is_empty()
+        || p.is_absolute()
+        || p.components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(ConfigError::Validation(format!(
+            "Git source paths must be relative paths inside the repository: {path}"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Sync schedule for remote sources.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -302,6 +564,17 @@ pub enum SyncSchedule {
     Daily,
 }
 
+impl SyncSchedule {
+    /// Time between scheduled syncs, `None` for manual sources.
+    pub fn interval(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Manual => None,
+            Self::Hourly => Some(std::time::Duration::from_secs(60 * 60)),
+            Self::Daily => Some(std::time::Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
 impl std::fmt::Display for SyncSchedule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -409,6 +682,15 @@ impl SourcesConfig {
 
     /// Validate all sources in the configuration.
     pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(owner) = &self.owner {
+            validate_owner(owner)?;
+        }
+        if self.hide_other_owners && self.local_owner().is_none() {
+            return Err(ConfigError::Validation(
+                "hide_other_owners needs an owner; set `owner` in sources.toml".into(),
+            ));
+        }
+
         // Check for duplicate names
         let mut seen_names = std::collections::HashSet::new();
         for source in &self.sources {
@@ -425,6 +707,21 @@ impl SourcesConfig {
         Ok(())
     }
 
+    /// Who local sessions belong to: `owner`, else the login name.
+    pub fn local_owner(&self) -> Option<String> {
+        self.owner.clone().or_else(login_name)
+    }
+
+    /// Owner a search is limited to when it names none: the local owner
+    /// with `hide_other_owners`, nobody (show everyone) otherwise.
+    pub fn default_owner(&self) -> Option<String> {
+        if self.hide_other_owners {
+            self.local_owner()
+        } else {
+            None
+        }
+    }
+
     /// Find a source by name.
     pub fn find_source(&self, name: &str) -> Option<&SourceDefinition> {
         self.sources.iter().find(|s| s.name == name)
@@ -457,10 +754,26 @@ impl SourcesConfig {
         self.sources.len() < initial_len
     }
 
-    /// Get all remote sources (SSH type).
+    /// Get all remote sources (SSH, git and HTTP).
     pub fn remote_sources(&self) -> impl Iterator<Item = &SourceDefinition> {
         self.sources.iter().filter(|s| s.is_remote())
     }
+
+    /// Get all sources whose sessions live in the mirror directory: the
+    /// remote sources plus push sources.
+    pub fn mirrored_sources(&self) -> impl Iterator<Item = &SourceDefinition> {
+        self.sources.iter().filter(|s| s.is_remote() || s.is_push())
+    }
+
+    /// Find the push source whose token has the given SHA-256 (hex).
+    pub fn find_push_source(&self, token_sha256: &str) -> Option<&SourceDefinition> {
+        self.sources.iter().find(|s| {
+            s.is_push()
+                && s.token_sha256
+                    .as_deref()
+                    .is_some_and(|h| h.eq_ignore_ascii_case(token_sha256))
+        })
+    }
 }
 
 /// Get preset paths for a given platform.
@@ -650,7 +963,7 @@ use super::probe::HostProbeResult;
 #[derive(Debug, Clone)]
 pub enum MergeResult {
     /// Source was added successfully.
-    Added(SourceDefinition),
+    Added(Box<SourceDefinition>),
     /// Source already exists with this name.
     AlreadyExists(String),
 }
@@ -780,10 +1093,16 @@ impl SourceConfigGenerator {
             name: host_name.to_string(),
             source_type: SourceKind::Ssh,
             host: Some(host_name.to_string()), // Use SSH alias
+            url: None,
+            branch: None,
+            token_sha256: None,
             paths,
             sync_schedule: SyncSchedule::Manual,
+            bwlimit: None,
+            filter: SyncFilter::default(),
             path_mappings,
             platform,
+            owner: None,
         }
     }
 
@@ -969,7 +1288,7 @@ impl SourcesConfig {
 
         let added = source.clone();
         self.sources.push(source);
-        Ok(MergeResult::Added(added))
+        Ok(MergeResult::Added(Box::new(added)))
     }
 
     /// Merge multiple sources from a preview.
@@ -1056,6 +1375,167 @@ mod tests {
         assert!(source.validate().is_err());
     }
 
+    #[test]
+    fn test_source_validation_git() {
+        let mut source = SourceDefinition::git("ci", "git@github.com:acme/logs.git");
+        assert!(source.is_remote());
+        assert!(!source.is_ssh());
+        source.paths = vec!["claude".into(), "codex/sessions".into()];
+        assert!(source.validate().is_ok());
+
+        source.paths = vec!["../outside".into()];
+        assert!(source.validate().is_err());
+
+        let mut source = SourceDefinition::git("ci", "--upload-pack=evil");
+        assert!(source.validate().is_err());
+        source.url = None;
+        assert!(source.validate().is_err());
+
+        let mut source = SourceDefinition::git("ci", "https://example.com/logs.git");
+        source.branch = Some("-x".into());
+        assert!(source.validate().is_err());
+    }
+
+    #[test]
+    fn test_source_validation_http() {
+        let source = SourceDefinition::http("files", "https://files.example.com/logs/index.txt");
+        assert!(source.validate().is_ok());
+
+        let source = SourceDefinition::http("files", "file:///etc/passwd");
+        assert!(source.validate().is_err());
+
+        let mut source = SourceDefinition::http("files", "https://files.example.com/index.txt");
+        source.paths = vec!["~/.claude".into()];
+        assert!(source.validate().is_err());
+    }
+
+    #[test]
+    fn test_source_validation_push() {
+        let hash = "ab".repeat(32);
+        let mut config = SourcesConfig::default();
+        config
+            .add_source(SourceDefinition::push("ci", &hash))
+            .unwrap();
+        assert_eq!(config.remote_sources().count(), 0);
+        assert_eq!(config.mirrored_sources().count(), 1);
+        assert_eq!(
+            config.find_push_source(&hash.to_uppercase()).unwrap().name,
+            "ci"
+        );
+        assert!(config.find_push_source(&"cd".repeat(32)).is_none());
+
+        assert!(
+            SourceDefinition::push("ci", "not-a-hash")
+                .validate()
+                .is_err()
+        );
+        let mut source = SourceDefinition::push("ci", &hash);
+        source.paths = vec!["~/.codex".into()];
+        assert!(source.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_sync_parallelism_and_bwlimit() {
+        let config: SourcesConfig = toml::from_str(
+            r#"
+            sync_parallelism = 8
+
+            [[sources]]
+            name = "slow-link"
+            type = "ssh"
+            host = "user@slow"
+            paths = ["~/.codex"]
+            bwlimit = 256
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.sync_parallelism, Some(8));
+        assert_eq!(config.sources[0].bwlimit, Some(256));
+        assert!(config.validate().is_ok());
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let reparsed: SourcesConfig = toml::from_str(&serialized).unwrap();
+        assert_eq!(reparsed.sync_parallelism, Some(8));
+        assert_eq!(reparsed.sources[0].bwlimit, Some(256));
+
+        let mut source = SourceDefinition::ssh("slow-link", "user@slow");
+        source.bwlimit = Some(0);
+        assert!(source.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_owners() {
+        let config: SourcesConfig = toml::from_str(
+            r#"
+            owner = "alice"
+            hide_other_owners = true
+
+            [[sources]]
+            name = "bob-laptop"
+            type = "ssh"
+            host = "bob@bob-laptop"
+            paths = ["~/.codex"]
+
+            [[sources]]
+            name = "build-box"
+            type = "ssh"
+            host = "ci@build-box"
+            paths = ["~/.codex"]
+            owner = "carol"
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.local_owner().as_deref(), Some("alice"));
+        assert_eq!(config.default_owner().as_deref(), Some("alice"));
+        assert_eq!(config.sources[0].effective_owner().as_deref(), Some("bob"));
+        assert_eq!(
+            config.sources[1].effective_owner().as_deref(),
+            Some("carol")
+        );
+        assert_eq!(SourceDefinition::ssh("box", "box").effective_owner(), None);
+        assert_eq!(SourceDefinition::local("here").effective_owner(), None);
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let reparsed: SourcesConfig = toml::from_str(&serialized).unwrap();
+        assert!(reparsed.hide_other_owners);
+        assert_eq!(reparsed.sources[1].owner.as_deref(), Some("carol"));
+
+        let shown = SourcesConfig {
+            hide_other_owners: false,
+            ..config
+        };
+        assert_eq!(shown.default_owner(), None);
+
+        let mut source = SourceDefinition::ssh("box", "user@box");
+        source.owner = Some("two words".into());
+        assert!(source.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_git_and_http_sources() {
+        let config: SourcesConfig = toml::from_str(
+            r#"
+            [[sources]]
+            name = "ci-logs"
+            type = "git"
+            url = "https://example.com/logs.git"
+            branch = "main"
+
+            [[sources]]
+            name = "ci-http"
+            type = "http"
+            url = "https://files.example.com/index.txt"
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.remote_sources().count(), 2);
+        assert_eq!(config.sources[0].source_type, SourceKind::Git);
+        assert_eq!(config.sources[0].branch.as_deref(), Some("main"));
+        assert_eq!(config.sources[1].source_type, SourceKind::Http);
+    }
+
     #[test]
     fn test_path_mapping_new() {
         let mapping = PathMapping::new("/home/user", "/Users/me");
@@ -1211,10 +1691,20 @@ mod tests {
             name: "laptop".into(),
             source_type: SourceKind::Ssh,
             host: Some("user@laptop.local".into()),
+            url: None,
+            branch: None,
+            token_sha256: None,
             paths: vec!["~/.claude/projects".into()],
             sync_schedule: SyncSchedule::Daily,
+            bwlimit: None,
+            filter: SyncFilter {
+                exclude: vec!["debug".into()],
+                max_age_days: Some(30),
+                ..Default::default()
+            },
             path_mappings: vec![PathMapping::new("/home/user", "/Users/me")],
             platform: Some(Platform::Linux),
+            owner: None,
         });
 
         let serialized = toml::to_string_pretty(&config).unwrap();
@@ -1226,6 +1716,7 @@ mod tests {
         assert_eq!(deserialized.sources[0].path_mappings.len(), 1);
         assert_eq!(deserialized.sources[0].path_mappings[0].from, "/home/user");
         assert_eq!(deserialized.sources[0].path_mappings[0].to, "/Users/me");
+        assert_eq!(deserialized.sources[0].filter, config.sources[0].filter);
     }
 
     #[test]
@@ -1235,13 +1726,19 @@ mod tests {
             name: "remote".into(),
             source_type: SourceKind::Ssh,
             host: Some("user@server".into()),
+            url: None,
+            branch: None,
+            token_sha256: None,
             paths: vec![],
             sync_schedule: SyncSchedule::Manual,
+            bwlimit: None,
+            filter: SyncFilter::default(),
             path_mappings: vec![
                 PathMapping::new("/home/user", "/Users/me"),
                 PathMapping::with_agents("/opt/work", "/Volumes/Work", vec!["claude-code".into()]),
             ],
             platform: None,
+            owner: None,
         });
 
         let serialized = toml::to_string_pretty(&config).unwrap();
@@ -1276,6 +1773,19 @@ mod tests {
         assert_eq!(SyncSchedule::Daily.to_string(), "daily");
     }
 
+    #[test]
+    fn test_sync_schedule_interval() {
+        assert_eq!(SyncSchedule::Manual.interval(), None);
+        assert_eq!(
+            SyncSchedule::Hourly.interval(),
+            Some(std::time::Duration::from_secs(3600))
+        );
+        assert_eq!(
+            SyncSchedule::Daily.interval(),
+            Some(std::time::Duration::from_secs(86400))
+        );
+    }
+
     #[test]
     fn test_discover_ssh_hosts() {
         // Just test that the function doesn't panic
@@ -0,0 +1,302 @@
+//! Background sync daemon (`cass daemon`).
+//!
+//! Syncs each remote source on its `sync_schedule` and lets the caller
+//! reindex after a pass that pulled new files. Last-run times and failure
+//! counts live in [`SyncStatus`] (`sync_status.json`), so schedules survive
+//! restarts and a manual `cass sources sync` counts as a run. A source whose
+//! last sync failed is retried with exponential backoff, but never later than
+//! its regular interval. Sources due together are synced concurrently, up to
+//! the config's `sync_parallelism`.
+//!
+//! The daemon re-reads `sources.toml` before every pass, so added or
+//! rescheduled sources are picked up without a restart.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::config::{SourceDefinition, SourcesConfig};
+use super::sync::{DEFAULT_SYNC_PARALLELISM, SourceSyncInfo, SyncEngine, SyncResult, SyncStatus};
+
+/// Delay before the first retry of a failed sync; doubles per failure.
+pub const RETRY_BASE: Duration = Duration::from_secs(5 * 60);
+
+/// Longest sleep between passes, so config changes are noticed.
+const MAX_IDLE: Duration = Duration::from_secs(60);
+
+/// Name of the generated systemd user unit.
+pub const SYSTEMD_UNIT_NAME: &str = "cass-daemon.service";
+
+/// Delay before retrying after `failures` failed syncs in a row.
+pub fn retry_delay(failures: u32, interval: Duration) -> Duration {
+    let factor = 1u32 << failures.saturating_sub(1).min(16);
+    RETRY_BASE.saturating_mul(factor).min(interval)
+}
+
+/// When `source` is next due (ms since epoch), `None` if it is not scheduled.
+///
+/// A scheduled source that never synced is due immediately (`0`).
+pub fn next_due(source: &SourceDefinition, info: Option<&SourceSyncInfo>) -> Option<i64> {
+    let interval = source.sync_schedule.interval()?;
+    let Some((last_sync, info)) = info.and_then(|i| i.last_sync.map(|t| (t, i))) else {
+        return Some(0);
+    };
+    let wait = match info.last_result {
+        SyncResult::Failed(_) => retry_delay(info.consecutive_failures.max(1), interval),
+        _ => interval,
+    };
+    Some(last_sync.saturating_add(wait.as_millis() as i64))
+}
+
+/// Outcome of syncing one source in a pass.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SourcePass {
+    pub source: String,
+    pub files: u64,
+    pub bytes: u64,
+    /// Error of a failed (or partially failed) sync
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When the source is due again (ms since epoch)
+    pub next_due: Option<i64>,
+}
+
+/// Outcome of one daemon pass.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PassReport {
+    /// Sources that were due and synced
+    pub synced: Vec<SourcePass>,
+    /// Scheduled remote sources in the config
+    pub scheduled: usize,
+    /// Earliest time any scheduled source is due again (ms since epoch)
+    pub next_wake: Option<i64>,
+}
+
+impl PassReport {
+    /// Whether the pass pulled files that need indexing.
+    pub fn needs_index(&self) -> bool {
+        self.synced.iter().any(|s| s.files > 0)
+    }
+}
+
+/// Syncs due sources and tracks their status.
+pub struct SyncDaemon {
+    data_dir: PathBuf,
+}
+
+impl SyncDaemon {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+        }
+    }
+
+    /// Sync every remote source of `config` that is due at `now` (ms since epoch).
+    ///
+    /// Due sources are synced concurrently; status is saved as each result
+    /// is recorded.
+    pub fn run_pass(&self, config: &SourcesConfig, now: i64) -> PassReport {
+        let mut status = SyncStatus::load(&self.data_dir).unwrap_or_default();
+        let mut report = PassReport::default();
+
+        let mut due_sources = Vec::new();
+        for source in config.remote_sources() {
+            let Some(due) = next_due(source, status.get(&source.name)) else {
+                continue;
+            };
+            report.scheduled += 1;
+            if due > now {
+                report.next_wake = Some(report.next_wake.map_or(due, |w| w.min(due)));
+            } else {
+                due_sources.push(source);
+            }
+        }
+        if due_sources.is_empty() {
+            return report;
+        }
+
+        let engine = SyncEngine::new(&self.data_dir)
+            .with_parallelism(config.sync_parallelism.unwrap_or(DEFAULT_SYNC_PARALLELISM));
+        let results = engine.sync_sources(&due_sources, |_| {});
+        for (source, result) in due_sources.into_iter().zip(results) {
+            let (files, bytes, error) = match result {
+                Ok(sync) => {
+                    status.update(&source.name, &sync);
+                    let error = match status.get(&source.name).map(|i| &i.last_result) {
+                        Some(SyncResult::Failed(e) | SyncResult::PartialFailure(e)) => {
+                            Some(e.clone())
+                        }
+                        _ => None,
+                    };
+                    (sync.total_files(), sync.total_bytes(), error)
+                }
+                Err(e) => {
+                    status.record_error(&source.name, &e.to_string());
+                    (0, 0, Some(e.to_string()))
+                }
+            };
+            if let Err(e) = status.save(&self.data_dir) {
+                tracing::warn!("Failed to save sync status: {}", e);
+            }
+
+            let next = next_due(source, status.get(&source.name));
+            if let Some(due) = next {
+                report.next_wake = Some(report.next_wake.map_or(due, |w| w.min(due)));
+            }
+            report.synced.push(SourcePass {
+                source: source.name.clone(),
+                files,
+                bytes,
+                error,
+                next_due: next,
+            });
+        }
+
+        report
+    }
+
+    /// Run passes until `on_pass` fails, or once when `once` is set.
+    ///
+    /// `on_pass` receives each pass that synced anything; it is where the
+    /// caller reindexes and reports.
+    pub fn run<F>(&self, once: bool, mut on_pass: F) -> anyhow::Result<()>
+    where
+        F: FnMut(&PassReport) -> anyhow::Result<()>,
+    {
+        loop {
+            let config = SourcesConfig::load()?;
+            let report = self.run_pass(&config, now_millis());
+            if once || !report.synced.is_empty() {
+                on_pass(&report)?;
+            }
+            if once {
+                return Ok(());
+            }
+
+            let idle = report
+                .next_wake
+                .map(|wake| Duration::from_millis(wake.saturating_sub(now_millis()).max(0) as u64))
+                .unwrap_or(MAX_IDLE)
+                .clamp(Duration::from_secs(1), MAX_IDLE);
+            std::thread::sleep(idle);
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// systemd user unit running `exe daemon` (with `--data-dir` when given).
+pub fn systemd_unit(exe: &Path, data_dir: Option<&Path>) -> String {
+    let mut exec = format!("{} daemon", systemd_quote(&exe.to_string_lossy()));
+    if let Some(dir) = data_dir {
+        exec.push_str(&format!(
+            " --data-dir {}",
+            systemd_quote(&dir.to_string_lossy())
+        ));
+    }
+    format!(
+        "[Unit]
+Description=cass background sync and index daemon
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+ExecStart={exec}
+Restart=on-failure
+RestartSec=60
+
+[Install]
+WantedBy=default.target
+"
+    )
+}
+
+/// Write the unit to `~/.config/systemd/user/`, returning its path.
+pub fn install_systemd_unit(exe: &Path, data_dir: Option<&Path>) -> anyhow::Result<PathBuf> {
+    let unit_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine config directory"))?
+        .join("systemd")
+        .join("user");
+    std::fs::create_dir_all(&unit_dir)?;
+    let path = unit_dir.join(SYSTEMD_UNIT_NAME);
+    std::fs::write(&path, systemd_unit(exe, data_dir))?;
+    Ok(path)
+}
+
+/// Quote an ExecStart argument (specifiers use `%`, so it is doubled)
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::config::SyncSchedule;
+
+    fn hourly() -> SourceDefinition {
+        SourceDefinition {
+            sync_schedule: SyncSchedule::Hourly,
+            ..SourceDefinition::ssh("laptop", "user@laptop")
+        }
+    }
+
+    fn info(result: SyncResult, failures: u32) -> SourceSyncInfo {
+        SourceSyncInfo {
+            last_sync: Some(1_000_000),
+            last_result: result,
+            consecutive_failures: failures,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn manual_sources_are_never_due() {
+        let source = SourceDefinition::ssh("laptop", "user@laptop");
+        assert_eq!(next_due(&source, None), None);
+    }
+
+    #[test]
+    fn scheduled_sources_are_due_after_their_interval() {
+        assert_eq!(next_due(&hourly(), None), Some(0));
+        let ok = info(SyncResult::Success, 0);
+        assert_eq!(next_due(&hourly(), Some(&ok)), Some(1_000_000 + 3_600_000));
+        // A partial failure still transferred something; keep the schedule
+        let partial = info(SyncResult::PartialFailure("x".into()), 0);
+        assert_eq!(
+            next_due(&hourly(), Some(&partial)),
+            Some(1_000_000 + 3_600_000)
+        );
+    }
+
+    #[test]
+    fn failed_sources_back_off_up_to_the_interval() {
+        let failed = |n| next_due(&hourly(), Some(&info(SyncResult::Failed("x".into()), n)));
+        assert_eq!(failed(1), Some(1_000_000 + 5 * 60_000));
+        assert_eq!(failed(2), Some(1_000_000 + 10 * 60_000));
+        assert_eq!(failed(3), Some(1_000_000 + 20 * 60_000));
+        assert_eq!(failed(4), Some(1_000_000 + 40 * 60_000));
+        assert_eq!(failed(5), Some(1_000_000 + 60 * 60_000));
+        assert_eq!(failed(40), Some(1_000_000 + 60 * 60_000));
+    }
+
+    #[test]
+    fn systemd_unit_quotes_paths() {
+        let unit = systemd_unit(
+            Path::new("/opt/my tools/cass"),
+            Some(Path::new("/data/100%")),
+        );
+        assert!(
+            unit.contains("ExecStart=\"/opt/my tools/cass\" daemon --data-dir \"/data/100%%\"")
+        );
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+}
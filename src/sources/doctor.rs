@@ -0,0 +1,1111 @@
+//! Source health checks for `cass sources doctor`.
+//!
+//! Every configured source is graded on a set of [`DiagnosticCheck`]s:
+//! SSH connectivity and auth drift, remote tools and paths, remote cass
+//! version skew (from [`probe_host`]), path mappings, stale mirror
+//! entries and the last sync recorded in [`SyncStatus`]. A check that
+//! can be repaired without touching user data carries a [`Repair`], which
+//! [`Doctor::repair`] applies for `--fix`.
+//!
+//! The latest results are kept in [`HealthReport`] (`source_health.json`),
+//! so the TUI sources panel can show them without contacting any host.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use super::config::{DiscoveredHost, SourceDefinition};
+use super::install::{CASS_VERSION, RemoteInstaller};
+use super::probe::{CassStatus, HostProbeResult, probe_host};
+use super::provenance::SourceKind;
+use super::sync::{SourceSyncInfo, SyncEngine, SyncResult, SyncStatus};
+
+/// Timeout of the probe that reads the remote cass version.
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// Timeout of a repair re-probe, for hosts that are slow to answer.
+const REPROBE_TIMEOUT_SECS: u64 = 30;
+
+/// How long a manually synced source may go without a sync before it is flagged.
+const MANUAL_STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// A safe repair `cass sources doctor --fix` can apply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Repair {
+    /// Probe the host again with a longer timeout.
+    Reprobe,
+    /// Install this cass version on the host.
+    UpgradeCass { version: String },
+    /// Delete mirror entries that no configured path syncs into.
+    PruneMirror { paths: Vec<PathBuf> },
+}
+
+impl Repair {
+    /// Short description for human output.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Reprobe => "re-probe the host".to_string(),
+            Self::UpgradeCass { version } => format!("upgrade remote cass to {version}"),
+            Self::PruneMirror { paths } => format!("delete {} stale mirror entries", paths.len()),
+        }
+    }
+}
+
+/// Result of one diagnostic check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub remediation: Option<String>,
+    /// Repair `--fix` applies for this check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repair: Option<Repair>,
+}
+
+impl DiagnosticCheck {
+    pub fn pass(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            message: message.into(),
+            remediation: None,
+            repair: None,
+        }
+    }
+
+    pub fn warn(
+        name: impl Into<String>,
+        message: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            remediation: Some(remediation.into()),
+            ..Self::pass(name, message)
+        }
+    }
+
+    pub fn fail(
+        name: impl Into<String>,
+        message: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            remediation: Some(remediation.into()),
+            ..Self::pass(name, message)
+        }
+    }
+
+    pub fn with_repair(mut self, repair: Repair) -> Self {
+        self.repair = Some(repair);
+        self
+    }
+}
+
+/// Overall health of a source: its worst check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Grade {
+    /// Every check passed.
+    Healthy,
+    /// Some checks warned.
+    Degraded,
+    /// At least one check failed.
+    Failing,
+}
+
+impl Grade {
+    pub fn from_checks(checks: &[DiagnosticCheck]) -> Self {
+        if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+            Self::Failing
+        } else if checks.iter().any(|c| c.status == CheckStatus::Warn) {
+            Self::Degraded
+        } else {
+            Self::Healthy
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Failing => "failing",
+        }
+    }
+}
+
+/// A repair that `--fix` attempted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairOutcome {
+    pub repair: Repair,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Graded diagnostics for a single source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceDiagnostics {
+    pub source_id: String,
+    pub grade: Grade,
+    /// When the checks ran (ms since epoch)
+    pub checked_at: i64,
+    pub checks: Vec<DiagnosticCheck>,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    /// Repairs applied by `--fix`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repairs: Vec<RepairOutcome>,
+    /// Latest probe of an SSH host, needed to install cass on it
+    #[serde(skip)]
+    pub probe: Option<HostProbeResult>,
+}
+
+impl SourceDiagnostics {
+    pub fn new(source_id: impl Into<String>, checks: Vec<DiagnosticCheck>) -> Self {
+        let mut diagnostics = Self {
+            source_id: source_id.into(),
+            grade: Grade::Healthy,
+            checked_at: now_millis(),
+            checks,
+            passed: 0,
+            warnings: 0,
+            failed: 0,
+            repairs: Vec::new(),
+            probe: None,
+        };
+        diagnostics.regrade();
+        diagnostics
+    }
+
+    /// Recompute the counts and grade after checks changed.
+    fn regrade(&mut self) {
+        let count = |status| self.checks.iter().filter(|c| c.status == status).count();
+        self.passed = count(CheckStatus::Pass);
+        self.warnings = count(CheckStatus::Warn);
+        self.failed = count(CheckStatus::Fail);
+        self.grade = Grade::from_checks(&self.checks);
+    }
+
+    /// Repairs suggested by the checks.
+    pub fn pending_repairs(&self) -> impl Iterator<Item = &Repair> {
+        self.checks.iter().filter_map(|c| c.repair.as_ref())
+    }
+}
+
+/// Latest diagnostics per source, shown by the TUI sources panel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Diagnostics keyed by source name.
+    pub sources: HashMap<String, SourceDiagnostics>,
+}
+
+impl HealthReport {
+    /// Load the report from disk (empty if it was never written).
+    pub fn load(data_dir: &Path) -> Result<Self, std::io::Error> {
+        let path = Self::report_path(data_dir);
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Save the report to disk.
+    pub fn save(&self, data_dir: &Path) -> Result<(), std::io::Error> {
+        let path = Self::report_path(data_dir);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+    }
+
+    /// Record the diagnostics of one source, replacing earlier ones.
+    pub fn record(&mut self, diagnostics: &SourceDiagnostics) {
+        self.sources
+            .insert(diagnostics.source_id.clone(), diagnostics.clone());
+    }
+
+    /// Drop sources that are no longer configured.
+    pub fn retain_sources<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        let names: Vec<&str> = names.into_iter().collect();
+        self.sources
+            .retain(|name, _| names.contains(&name.as_str()));
+    }
+
+    pub fn get(&self, source_name: &str) -> Option<&SourceDiagnostics> {
+        self.sources.get(source_name)
+    }
+
+    fn report_path(data_dir: &Path) -> PathBuf {
+        data_dir.join("source_health.json")
+    }
+}
+
+/// Runs the checks and repairs for configured sources.
+pub struct Doctor {
+    data_dir: PathBuf,
+    engine: SyncEngine,
+    status: SyncStatus,
+}
+
+impl Doctor {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            engine: SyncEngine::new(data_dir),
+            status: SyncStatus::load(data_dir).unwrap_or_default(),
+        }
+    }
+
+    /// Run every check that applies to `source`.
+    ///
+    /// Host checks only run once SSH connectivity passed, so an unreachable
+    /// host fails a single check instead of all of them.
+    pub fn diagnose(&self, source: &SourceDefinition) -> SourceDiagnostics {
+        let info = self.status.get(&source.name);
+        let mut checks = Vec::new();
+        let mut probe = None;
+
+        if source.is_ssh() {
+            let host = source.host.as_deref().unwrap_or("unknown");
+            let connectivity = check_ssh_connectivity(host, info);
+            let connected = connectivity.status == CheckStatus::Pass;
+            checks.push(connectivity);
+            if connected {
+                checks.push(check_rsync_available(host));
+                for path in &source.paths {
+                    checks.push(check_remote_path(host, path));
+                }
+                let result = probe_host(&discovered_host(host), PROBE_TIMEOUT_SECS);
+                checks.push(check_cass_version(&result, CASS_VERSION));
+                probe = Some(result);
+            }
+        }
+        checks.extend(check_path_mappings(source));
+        if source.is_remote() || source.is_push() {
+            checks.push(check_mirror(&self.engine, source));
+        }
+        if source.is_remote() {
+            checks.push(check_last_sync(source, info, now_millis()));
+        }
+        checks.push(check_local_storage(&self.data_dir, &source.name));
+
+        let mut diagnostics = SourceDiagnostics::new(&source.name, checks);
+        diagnostics.probe = probe;
+        diagnostics
+    }
+
+    /// Apply the repairs suggested by `diagnostics`, updating the checks they fix.
+    ///
+    /// A repair can surface another one (a re-probe that finds an outdated
+    /// cass), which is applied too; each repair is attempted at most once.
+    pub fn repair(&self, source: &SourceDefinition, diagnostics: &mut SourceDiagnostics) {
+        let mut attempted: Vec<Repair> = Vec::new();
+        while let Some((index, repair)) =
+            diagnostics.checks.iter().enumerate().find_map(|(i, c)| {
+                c.repair
+                    .clone()
+                    .filter(|r| !attempted.contains(r))
+                    .map(|r| (i, r))
+            })
+        {
+            attempted.push(repair.clone());
+            let result = match &repair {
+                Repair::Reprobe => self.reprobe(source, diagnostics, index),
+                Repair::UpgradeCass { version } => {
+                    self.upgrade_cass(source, diagnostics, index, version)
+                }
+                Repair::PruneMirror { paths } => {
+                    self.prune_mirror(source, diagnostics, index, paths)
+                }
+            };
+            let (success, message) = match result {
+                Ok(message) => (true, message),
+                Err(message) => (false, message),
+            };
+            diagnostics.repairs.push(RepairOutcome {
+                repair,
+                success,
+                message,
+            });
+        }
+        diagnostics.checked_at = now_millis();
+        diagnostics.regrade();
+    }
+
+    fn reprobe(
+        &self,
+        source: &SourceDefinition,
+        diagnostics: &mut SourceDiagnostics,
+        index: usize,
+    ) -> Result<String, String> {
+        let host = source.host.as_deref().unwrap_or("unknown");
+        let result = probe_host(&discovered_host(host), REPROBE_TIMEOUT_SECS);
+        let check = check_cass_version(&result, CASS_VERSION);
+        let answered = check.repair != Some(Repair::Reprobe);
+        diagnostics.checks[index] = check;
+        diagnostics.probe = Some(result);
+        if answered {
+            Ok(format!("{host} answered the probe"))
+        } else {
+            Err(format!("{host} still did not answer the probe"))
+        }
+    }
+
+    fn upgrade_cass(
+        &self,
+        source: &SourceDefinition,
+        diagnostics: &mut SourceDiagnostics,
+        index: usize,
+        version: &str,
+    ) -> Result<String, String> {
+        let host = source.host.as_deref().unwrap_or("unknown");
+        let (system_info, resources) = diagnostics
+            .probe
+            .as_ref()
+            .and_then(|p| Some((p.system_info.clone()?, p.resources.clone()?)))
+            .ok_or_else(|| format!("the probe of {host} did not report its system"))?;
+        let installer = RemoteInstaller::with_version(host, system_info, resources, version);
+        let installed = installer.install(|_| {}).map_err(|e| e.to_string())?;
+
+        // Verify with a fresh probe, which also updates the version check
+        let result = probe_host(&discovered_host(host), REPROBE_TIMEOUT_SECS);
+        diagnostics.checks[index] = check_cass_version(&result, CASS_VERSION);
+        diagnostics.probe = Some(result);
+        Ok(format!(
+            "installed cass {} on {host} ({})",
+            installed.version, installed.method
+        ))
+    }
+
+    fn prune_mirror(
+        &self,
+        source: &SourceDefinition,
+        diagnostics: &mut SourceDiagnostics,
+        index: usize,
+        paths: &[PathBuf],
+    ) -> Result<String, String> {
+        let mirror = self.engine.mirror_dir(&source.name);
+        let mut errors = Vec::new();
+        for path in paths {
+            // Only ever delete inside this source's mirror
+            if !path.starts_with(&mirror) || path == &mirror {
+                errors.push(format!("{} is outside the mirror", path.display()));
+                continue;
+            }
+            let removed = if path.is_dir() {
+                std::fs::remove_dir_all(path)
+            } else {
+                std::fs::remove_file(path)
+            };
+            if let Err(e) = removed {
+                errors.push(format!("{}: {e}", path.display()));
+            }
+        }
+        diagnostics.checks[index] = check_mirror(&self.engine, source);
+        if errors.is_empty() {
+            Ok(format!(
+                "deleted {} stale mirror entries; run `cass index --full` to drop their sessions",
+                paths.len()
+            ))
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+/// Probe target for a source host (`user@host` or an SSH config alias).
+fn discovered_host(host: &str) -> DiscoveredHost {
+    DiscoveredHost {
+        name: host.to_string(),
+        hostname: None,
+        user: None,
+        port: None,
+        identity_file: None,
+    }
+}
+
+/// Check SSH connectivity to a host, telling auth drift apart from network trouble.
+///
+/// A host key change, or a key refused by a host this source synced from
+/// before, means the credentials drifted rather than the host being down.
+fn check_ssh_connectivity(host: &str, info: Option<&SourceSyncInfo>) -> DiagnosticCheck {
+    let output = Command::new("ssh")
+        .args([
+            "-o",
+            "ConnectTimeout=5",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "StrictHostKeyChecking=accept-new",
+            "--",
+            host,
+            "true",
+        ])
+        .output();
+
+    match output {
+        Ok(out) if out.status.success() => DiagnosticCheck::pass(
+            "SSH Connectivity",
+            format!("Connected to {} successfully", host),
+        ),
+        Ok(out) => ssh_failure_check(host, &String::from_utf8_lossy(&out.stderr), info),
+        Err(e) => DiagnosticCheck::fail(
+            "SSH Connectivity",
+            format!("Failed to run ssh: {}", e),
+            "Ensure SSH client is installed and in PATH",
+        ),
+    }
+}
+
+/// Classify a failed SSH connection from its stderr.
+fn ssh_failure_check(host: &str, stderr: &str, info: Option<&SourceSyncInfo>) -> DiagnosticCheck {
+    let hostname = host.rsplit('@').next().unwrap_or(host);
+    if stderr.contains("REMOTE HOST IDENTIFICATION HAS CHANGED")
+        || stderr.contains("Host key verification failed")
+    {
+        return DiagnosticCheck::fail(
+            "SSH Authentication",
+            format!("The host key of {host} changed since it was last trusted"),
+            format!(
+                "If the host was reinstalled, run `ssh-keygen -R {hostname}` and connect once to trust the new key"
+            ),
+        );
+    }
+    if stderr.contains("Permission denied") {
+        let message = match info.and_then(|i| i.last_sync) {
+            Some(last_sync) => format!(
+                "{host} refused our key, but this source synced {} ago: its credentials changed",
+                format_age(now_millis().saturating_sub(last_sync))
+            ),
+            None => format!("{host} refused our key"),
+        };
+        return DiagnosticCheck::fail(
+            "SSH Authentication",
+            message,
+            "Load the key into ssh-agent, or add it to the remote authorized_keys again",
+        );
+    }
+    let remediation = if stderr.contains("Connection refused") {
+        "Verify SSH server is running on remote host"
+    } else if stderr.contains("Could not resolve") {
+        "Check hostname is correct and DNS resolves"
+    } else {
+        "Check SSH configuration and network connectivity"
+    };
+    DiagnosticCheck::fail("SSH Connectivity", stderr.trim(), remediation)
+}
+
+/// Check rsync availability on remote
+fn check_rsync_available(host: &str) -> DiagnosticCheck {
+    let output = Command::new("ssh")
+        .args([
+            "-o",
+            "ConnectTimeout=5",
+            "-o",
+            "BatchMode=yes",
+            "--",
+            host,
+            "rsync",
+            "--version",
+        ])
+        .output();
+
+    match output {
+        Ok(out) if out.status.success() => {
+            let stdout = String::from_utf8_lossy(&out.stdout);
+            let version = stdout.lines().next().unwrap_or("version unknown");
+            DiagnosticCheck::pass("rsync Available", version)
+        }
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            DiagnosticCheck::fail(
+                "rsync Available",
+                format!("rsync not found: {}", stderr.trim()),
+                "Install rsync on the remote host",
+            )
+        }
+        Err(e) => DiagnosticCheck::warn(
+            "rsync Available",
+            format!("Could not check rsync: {}", e),
+            "SSH connectivity may have failed",
+        ),
+    }
+}
+
+fn sh_quote(value: &str) -> String {
+    if value.is_empty() {
+        "''".to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\"'\"'"))
+    }
+}
+
+/// Check if a remote path exists
+fn check_remote_path(host: &str, path: &str) -> DiagnosticCheck {
+    let name = format!("Remote Path: {}", path);
+    let quoted = sh_quote(path);
+    let cmd = format!("test -d {quoted} && ls -1 {quoted} | wc -l");
+    let output = Command::new("ssh")
+        .args([
+            "-o",
+            "ConnectTimeout=5",
+            "-o",
+            "BatchMode=yes",
+            "--",
+            host,
+            "sh",
+            "-c",
+            &cmd,
+        ])
+        .output();
+
+    match output {
+        Ok(out) if out.status.success() => {
+            let count = String::from_utf8_lossy(&out.stdout)
+                .trim()
+                .parse::<usize>()
+                .unwrap_or(0);
+            if count > 0 {
+                DiagnosticCheck::pass(name, format!("Path exists, {} items found", count))
+            } else {
+                DiagnosticCheck::warn(
+                    name,
+                    "Path exists but is empty",
+                    "No agent sessions on this machine yet",
+                )
+            }
+        }
+        Ok(_) => DiagnosticCheck::fail(
+            name,
+            "Path does not exist",
+            "Remove this path or create it on the remote",
+        ),
+        Err(e) => DiagnosticCheck::warn(
+            name,
+            format!("Could not check path: {}", e),
+            "SSH connectivity may have failed",
+        ),
+    }
+}
+
+/// Compare the remote cass install reported by a probe with the local version.
+///
+/// cass is only needed on the remote for federated search, so a missing
+/// install passes; an older one can be upgraded by `--fix`.
+pub fn check_cass_version(probe: &HostProbeResult, local_version: &str) -> DiagnosticCheck {
+    const NAME: &str = "Remote cass Version";
+    if !probe.reachable {
+        return DiagnosticCheck::warn(
+            NAME,
+            format!(
+                "Could not probe the host: {}",
+                probe.error.as_deref().unwrap_or("no answer")
+            ),
+            "Run `cass sources doctor --fix` to probe again with a longer timeout",
+        )
+        .with_repair(Repair::Reprobe);
+    }
+    let remote_version = match &probe.cass_status {
+        CassStatus::NotFound => {
+            return DiagnosticCheck::pass(
+                NAME,
+                "cass is not installed (only needed for `cass search --federated`)",
+            );
+        }
+        CassStatus::Unknown => {
+            return DiagnosticCheck::warn(
+                NAME,
+                "The probe did not report the cass install",
+                "Run `cass sources doctor --fix` to probe again",
+            )
+            .with_repair(Repair::Reprobe);
+        }
+        status => status.version().unwrap_or_default(),
+    };
+    let upgrade = Repair::UpgradeCass {
+        version: local_version.to_string(),
+    };
+    let parse = |v: &str| Version::parse(v.trim().trim_start_matches('v')).ok();
+    let (Some(remote), Some(local)) = (parse(remote_version), parse(local_version)) else {
+        return DiagnosticCheck::warn(
+            NAME,
+            format!("Unrecognised remote cass version '{remote_version}'"),
+            format!("Run `cass sources doctor --fix` to install cass {local_version}"),
+        )
+        .with_repair(upgrade);
+    };
+    match remote.cmp(&local) {
+        std::cmp::Ordering::Equal => {
+            DiagnosticCheck::pass(NAME, format!("cass {remote} (same as local)"))
+        }
+        std::cmp::Ordering::Less => DiagnosticCheck::warn(
+            NAME,
+            format!("Remote cass {remote} is older than local {local}"),
+            format!("Run `cass sources doctor --fix` to upgrade it to {local}"),
+        )
+        .with_repair(upgrade),
+        std::cmp::Ordering::Greater => DiagnosticCheck::warn(
+            NAME,
+            format!("Remote cass {remote} is newer than local {local}"),
+            "Update the local cass install",
+        ),
+    }
+}
+
+/// Check each path mapping of a source for prefixes that can never apply
+/// and local targets that do not exist.
+pub fn check_path_mappings(source: &SourceDefinition) -> Vec<DiagnosticCheck> {
+    let remove = |index: usize| {
+        format!(
+            "Run `cass sources mappings remove {} {index}` and add a corrected mapping",
+            source.name
+        )
+    };
+    source
+        .path_mappings
+        .iter()
+        .enumerate()
+        .map(|(index, mapping)| {
+            let name = format!("Path Mapping: {} -> {}", mapping.from, mapping.to);
+            let shadowed = source.path_mappings[..index]
+                .iter()
+                .any(|m| m.from == mapping.from && m.agents == mapping.agents);
+            if !is_absolute_prefix(&mapping.from) {
+                DiagnosticCheck::fail(
+                    name,
+                    "The remote prefix is not an absolute path, so it never matches a workspace",
+                    remove(index),
+                )
+            } else if shadowed {
+                DiagnosticCheck::warn(
+                    name,
+                    "An earlier mapping has the same prefix, so this one never applies",
+                    remove(index),
+                )
+            } else if mapping.from == mapping.to {
+                DiagnosticCheck::warn(name, "The mapping does not change any path", remove(index))
+            } else if !expand_home(&mapping.to).exists() {
+                DiagnosticCheck::warn(
+                    name,
+                    format!("The local path {} does not exist", mapping.to),
+                    format!(
+                        "Check out the project at {}, or update the mapping: {}",
+                        mapping.to,
+                        remove(index)
+                    ),
+                )
+            } else {
+                DiagnosticCheck::pass(name, "Maps to an existing local path")
+            }
+        })
+        .collect()
+}
+
+/// `/abs`, `~/home-relative` or `C:\windows` prefixes.
+fn is_absolute_prefix(prefix: &str) -> bool {
+    let bytes = prefix.as_bytes();
+    prefix.starts_with('/')
+        || prefix.starts_with('~')
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/'))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Entries of a source's mirror directory that no configured path syncs into,
+/// left behind by removed paths or a changed source type.
+pub fn stale_mirror_entries(engine: &SyncEngine, source: &SourceDefinition) -> Vec<PathBuf> {
+    let expected = match source.source_type {
+        SourceKind::Git => vec![engine.git_checkout_dir(&source.name)],
+        SourceKind::Http => vec![engine.http_mirror_dir(&source.name)],
+        SourceKind::Push => vec![
+            engine.push_files_dir(&source.name),
+            engine.push_conversations_dir(&source.name),
+        ],
+        _ => engine.scan_dirs(source),
+    };
+    let mut stale: Vec<PathBuf> = std::fs::read_dir(engine.mirror_dir(&source.name))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| !expected.contains(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    stale.sort();
+    stale
+}
+
+fn check_mirror(engine: &SyncEngine, source: &SourceDefinition) -> DiagnosticCheck {
+    const NAME: &str = "Mirror Directory";
+    let stale = stale_mirror_entries(engine, source);
+    if stale.is_empty() {
+        return DiagnosticCheck::pass(NAME, "Holds only configured paths");
+    }
+    let names: Vec<String> = stale
+        .iter()
+        .filter_map(|p| p.file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .collect();
+    DiagnosticCheck::warn(
+        NAME,
+        format!(
+            "{} stale entries no configured path syncs into: {}",
+            stale.len(),
+            names.join(", ")
+        ),
+        "Run `cass sources doctor --fix` to delete them",
+    )
+    .with_repair(Repair::PruneMirror { paths: stale })
+}
+
+/// Grade the last sync of a remote source at `now` (ms since epoch).
+///
+/// Scheduled sources are stale after two missed intervals, manual ones after
+/// a week.
+pub fn check_last_sync(
+    source: &SourceDefinition,
+    info: Option<&SourceSyncInfo>,
+    now: i64,
+) -> DiagnosticCheck {
+    const NAME: &str = "Last Sync";
+    let sync_now = format!("Run `cass sources sync --source {}`", source.name);
+    let Some((last_sync, info)) = info.and_then(|i| i.last_sync.map(|t| (t, i))) else {
+        return DiagnosticCheck::warn(NAME, "Never synced", sync_now);
+    };
+    let age_ms = now.saturating_sub(last_sync);
+    let age = format_age(age_ms);
+    let stale_after = source
+        .sync_schedule
+        .interval()
+        .map_or(MANUAL_STALE_AFTER, |interval| interval * 2);
+
+    match &info.last_result {
+        SyncResult::Failed(error) => DiagnosticCheck::fail(
+            NAME,
+            format!(
+                "Last sync {age} ago failed ({} in a row): {error}",
+                info.consecutive_failures.max(1)
+            ),
+            format!(
+                "Fix the failing checks, then {}",
+                lowercase_first(&sync_now)
+            ),
+        ),
+        _ if age_ms > stale_after.as_millis() as i64 => {
+            let remediation = if source.sync_schedule.interval().is_some() {
+                format!(
+                    "Make sure `cass daemon` is running, or {}",
+                    lowercase_first(&sync_now)
+                )
+            } else {
+                sync_now
+            };
+            DiagnosticCheck::warn(NAME, format!("Last synced {age} ago"), remediation)
+        }
+        SyncResult::PartialFailure(error) => DiagnosticCheck::warn(
+            NAME,
+            format!("Last sync {age} ago partly failed: {error}"),
+            sync_now,
+        ),
+        SyncResult::Success | SyncResult::Skipped => {
+            DiagnosticCheck::pass(NAME, format!("Synced {age} ago"))
+        }
+    }
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Check if local storage directory is writable
+fn check_local_storage(data_dir: &Path, source_name: &str) -> DiagnosticCheck {
+    let source_dir = data_dir.join("remotes").join(source_name);
+
+    // Try to create the directory if it doesn't exist
+    if !source_dir.exists() {
+        return if std::fs::create_dir_all(&source_dir).is_ok() {
+            DiagnosticCheck::pass(
+                "Local Storage",
+                format!("{} is writable", source_dir.display()),
+            )
+        } else {
+            DiagnosticCheck::fail(
+                "Local Storage",
+                format!("Cannot create {}", source_dir.display()),
+                "Check file permissions on data directory",
+            )
+        };
+    }
+
+    // Directory exists, check if writable
+    let test_file = source_dir.join(".doctor_test");
+    if std::fs::write(&test_file, b"test").is_ok() {
+        let _ = std::fs::remove_file(&test_file);
+        DiagnosticCheck::pass(
+            "Local Storage",
+            format!("{} is writable", source_dir.display()),
+        )
+    } else {
+        DiagnosticCheck::fail(
+            "Local Storage",
+            format!("{} is not writable", source_dir.display()),
+            "Check file permissions on data directory",
+        )
+    }
+}
+
+/// Compact age ("45s", "12m", "3h", "2d").
+fn format_age(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 60 * 60 => format!("{}m", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86_400),
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::config::{PathMapping, SyncSchedule};
+    use crate::sources::probe::{ResourceInfo, SystemInfo};
+
+    fn probe(status: CassStatus) -> HostProbeResult {
+        HostProbeResult {
+            cass_status: status,
+            reachable: true,
+            system_info: None::<SystemInfo>,
+            resources: None::<ResourceInfo>,
+            ..HostProbeResult::unreachable("laptop", "")
+        }
+    }
+
+    fn installed(version: &str) -> HostProbeResult {
+        probe(CassStatus::InstalledNotIndexed {
+            version: version.to_string(),
+        })
+    }
+
+    #[test]
+    fn grade_is_the_worst_check() {
+        let pass = DiagnosticCheck::pass("a", "ok");
+        let warn = DiagnosticCheck::warn("b", "meh", "fix it");
+        let fail = DiagnosticCheck::fail("c", "bad", "fix it");
+        assert_eq!(
+            Grade::from_checks(std::slice::from_ref(&pass)),
+            Grade::Healthy
+        );
+        assert_eq!(
+            Grade::from_checks(&[pass.clone(), warn.clone()]),
+            Grade::Degraded
+        );
+        let diagnostics = SourceDiagnostics::new("laptop", vec![pass, warn, fail]);
+        assert_eq!(diagnostics.grade, Grade::Failing);
+        assert_eq!(
+            (diagnostics.passed, diagnostics.warnings, diagnostics.failed),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn version_skew_offers_an_upgrade_only_for_older_installs() {
+        let older = check_cass_version(&installed("0.1.40"), "0.1.50");
+        assert_eq!(older.status, CheckStatus::Warn);
+        assert_eq!(
+            older.repair,
+            Some(Repair::UpgradeCass {
+                version: "0.1.50".into()
+            })
+        );
+
+        let same = check_cass_version(&installed("0.1.50"), "0.1.50");
+        assert_eq!(same.status, CheckStatus::Pass);
+
+        let newer = check_cass_version(&installed("0.2.0"), "0.1.50");
+        assert_eq!(newer.status, CheckStatus::Warn);
+        assert_eq!(newer.repair, None);
+
+        let missing = check_cass_version(&probe(CassStatus::NotFound), "0.1.50");
+        assert_eq!(missing.status, CheckStatus::Pass);
+
+        let unreachable = HostProbeResult::unreachable("laptop", "Connection timed out");
+        let check = check_cass_version(&unreachable, "0.1.50");
+        assert_eq!(check.repair, Some(Repair::Reprobe));
+        assert!(check.message.contains("timed out"));
+    }
+
+    #[test]
+    fn auth_failures_are_reported_as_drift() {
+        let synced = SourceSyncInfo {
+            last_sync: Some(now_millis() - 3 * 3_600_000),
+            ..Default::default()
+        };
+        let check = ssh_failure_check(
+            "dev@laptop",
+            "dev@laptop: Permission denied (publickey).",
+            Some(&synced),
+        );
+        assert_eq!(check.name, "SSH Authentication");
+        assert!(check.message.contains("synced 3h ago"), "{}", check.message);
+
+        let check = ssh_failure_check(
+            "dev@laptop",
+            "@@@ WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED! @@@",
+            None,
+        );
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.remediation.unwrap().contains("ssh-keygen -R laptop"));
+
+        let check = ssh_failure_check("dev@laptop", "Connection refused", None);
+        assert_eq!(check.name, "SSH Connectivity");
+    }
+
+    #[test]
+    fn path_mappings_flag_relative_shadowed_and_missing_targets() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let existing = tmp.path().to_string_lossy().to_string();
+        let mut source = SourceDefinition::ssh("laptop", "dev@laptop");
+        source.path_mappings = vec![
+            PathMapping::new("/home/dev/projects", &existing),
+            PathMapping::new("projects", &existing),
+            PathMapping::new("/home/dev/projects", "/elsewhere"),
+            PathMapping::new("/srv/app", "/nonexistent/cass/doctor/app"),
+        ];
+        let statuses: Vec<CheckStatus> = check_path_mappings(&source)
+            .iter()
+            .map(|c| c.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                CheckStatus::Pass,
+                CheckStatus::Fail,
+                CheckStatus::Warn,
+                CheckStatus::Warn
+            ]
+        );
+    }
+
+    #[test]
+    fn stale_mirror_entries_are_found_and_pruned() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let doctor = Doctor::new(tmp.path());
+        let mut source = SourceDefinition::ssh("laptop", "dev@laptop");
+        source.paths = vec!["~/.codex".into()];
+        let mirror = doctor.engine.mirror_dir("laptop");
+        std::fs::create_dir_all(mirror.join(".codex")).unwrap();
+        std::fs::create_dir_all(mirror.join(".claude_projects")).unwrap();
+
+        assert_eq!(
+            stale_mirror_entries(&doctor.engine, &source),
+            vec![mirror.join(".claude_projects")]
+        );
+
+        let mut diagnostics =
+            SourceDiagnostics::new("laptop", vec![check_mirror(&doctor.engine, &source)]);
+        assert_eq!(diagnostics.grade, Grade::Degraded);
+        doctor.repair(&source, &mut diagnostics);
+        assert_eq!(diagnostics.grade, Grade::Healthy);
+        assert!(diagnostics.repairs[0].success);
+        assert!(mirror.join(".codex").exists());
+        assert!(!mirror.join(".claude_projects").exists());
+    }
+
+    #[test]
+    fn last_sync_is_graded_by_result_and_age() {
+        let hour = 3_600_000;
+        let now = 100 * 24 * hour;
+        let hourly = SourceDefinition {
+            sync_schedule: SyncSchedule::Hourly,
+            ..SourceDefinition::ssh("laptop", "dev@laptop")
+        };
+        let synced = |hours_ago: i64, result: SyncResult| SourceSyncInfo {
+            last_sync: Some(now - hours_ago * hour),
+            last_result: result,
+            consecutive_failures: 2,
+            ..Default::default()
+        };
+
+        let never = check_last_sync(&hourly, None, now);
+        assert_eq!(never.status, CheckStatus::Warn);
+
+        let fresh = check_last_sync(&hourly, Some(&synced(1, SyncResult::Success)), now);
+        assert_eq!(fresh.status, CheckStatus::Pass);
+        assert_eq!(fresh.message, "Synced 1h ago");
+
+        let stale = check_last_sync(&hourly, Some(&synced(3, SyncResult::Success)), now);
+        assert_eq!(stale.status, CheckStatus::Warn);
+        assert!(stale.remediation.unwrap().contains("cass daemon"));
+
+        let failed = check_last_sync(
+            &hourly,
+            Some(&synced(1, SyncResult::Failed("boom".into()))),
+            now,
+        );
+        assert_eq!(failed.status, CheckStatus::Fail);
+        assert!(failed.message.contains("2 in a row"));
+
+        // Manual sources get a week
+        let manual = SourceDefinition::ssh("laptop", "dev@laptop");
+        let check = check_last_sync(&manual, Some(&synced(48, SyncResult::Success)), now);
+        assert_eq!(check.status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn health_report_keeps_only_configured_sources() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut report = HealthReport::default();
+        report.record(&SourceDiagnostics::new("laptop", vec![]));
+        report.record(&SourceDiagnostics::new("gone", vec![]));
+        report.retain_sources(["laptop"]);
+        report.save(tmp.path()).unwrap();
+
+        let loaded = HealthReport::load(tmp.path()).unwrap();
+        assert_eq!(loaded.sources.len(), 1);
+        assert_eq!(loaded.get("laptop").unwrap().grade, Grade::Healthy);
+    }
+}
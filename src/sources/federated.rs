@@ -0,0 +1,480 @@
+//! Federated search across remote cass installs.
+//!
+//! Instead of syncing session files, `cass search --federated` runs
+//! `cass search --robot-format jsonl` on every configured SSH source in
+//! parallel and merges the hits with the local ones. Remote hits are tagged
+//! with the source's provenance (`source_id`, `origin_kind`, `origin_host`)
+//! and get the source's path mappings applied to their workspace. Scores from
+//! different indexes are not comparable, so the per-host rankings are fused
+//! with Reciprocal Rank Fusion rather than sorted by raw score.
+//!
+//! Each host runs under its own deadline. A host that is unreachable or too
+//! slow only loses its own hits (plus whatever it had not streamed yet), and
+//! is reported in [`HostOutcome`] so callers can flag partial results.
+
+use std::io::{BufRead, BufReader, Write as IoWrite};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::search::query::{RRF_K, SearchHit, SearchMode};
+
+use super::config::SourceDefinition;
+
+/// Default per-host deadline for a federated query.
+pub const DEFAULT_FEDERATED_TIMEOUT_SECS: u64 = 10;
+
+/// Poll interval while waiting on remote searches.
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// The part of a search that is forwarded to remote hosts.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteQuery {
+    pub query: String,
+    pub agents: Vec<String>,
+    pub workspaces: Vec<String>,
+    /// Hits to request from each host (offset is applied after merging)
+    pub limit: usize,
+    /// Lower bound on `created_at` (ms since epoch)
+    pub since: Option<i64>,
+    /// Upper bound on `created_at` (ms since epoch)
+    pub until: Option<i64>,
+    pub mode: Option<SearchMode>,
+}
+
+impl RemoteQuery {
+    /// Shell script that runs the search on the remote.
+    ///
+    /// `--since`/`--until` are parsed in the remote's local time, so the
+    /// search runs with `TZ=UTC` and gets UTC timestamps.
+    pub fn script(&self) -> String {
+        let mut args = vec![
+            "search".to_string(),
+            shell_words::quote(&self.query).into_owned(),
+            "--limit".to_string(),
+            self.limit.to_string(),
+            "--robot-format".to_string(),
+            "jsonl".to_string(),
+        ];
+        for agent in &self.agents {
+            args.push(format!("--agent {}", shell_words::quote(agent)));
+        }
+        for workspace in &self.workspaces {
+            args.push(format!("--workspace {}", shell_words::quote(workspace)));
+        }
+        for (flag, ts) in [("--since", self.since), ("--until", self.until)] {
+            if let Some(dt) = ts.and_then(chrono::DateTime::from_timestamp_millis) {
+                args.push(format!("{flag} {}", dt.format("%Y-%m-%dT%H:%M:%S")));
+            }
+        }
+        if let Some(mode) = self.mode {
+            let mode = match mode {
+                SearchMode::Lexical => "lexical",
+                SearchMode::Semantic => "semantic",
+                SearchMode::Hybrid => "hybrid",
+            };
+            args.push(format!("--mode {mode}"));
+        }
+
+        format!(
+            r#"source ~/.cargo/env 2>/dev/null || true
+export PATH="$HOME/.local/bin:$HOME/.cargo/bin:$PATH"
+TZ=UTC exec cass {}
+"#,
+            args.join(" ")
+        )
+    }
+}
+
+/// Each host's tagged hits, in its own ranking order, and how each host answered.
+pub type FederatedResults = (Vec<Vec<SearchHit>>, Vec<HostOutcome>);
+
+/// How one host answered a federated query.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct HostOutcome {
+    pub source_id: String,
+    pub host: String,
+    pub hits: usize,
+    pub elapsed_ms: u64,
+    /// The host missed its deadline; `hits` is what it streamed before
+    pub timed_out: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HostOutcome {
+    /// Whether this host's hits may be incomplete.
+    pub fn is_partial(&self) -> bool {
+        self.timed_out || self.error.is_some()
+    }
+}
+
+/// Runs a [`RemoteQuery`] on SSH sources.
+pub struct FederatedSearch {
+    timeout: Duration,
+}
+
+impl FederatedSearch {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// Query every source in parallel.
+    pub fn search(&self, sources: &[&SourceDefinition], query: &RemoteQuery) -> FederatedResults {
+        let script = query.script();
+        let results: Vec<(Vec<SearchHit>, HostOutcome)> = std::thread::scope(|s| {
+            let handles: Vec<_> = sources
+                .iter()
+                .map(|source| s.spawn(|| self.search_host(source, &script)))
+                .collect();
+            handles
+                .into_iter()
+                .zip(sources)
+                .map(|(handle, source)| {
+                    handle.join().unwrap_or_else(|_| {
+                        (
+                            Vec::new(),
+                            HostOutcome {
+                                source_id: source.name.clone(),
+                                host: source.host.clone().unwrap_or_default(),
+                                error: Some("search thread panicked".into()),
+                                ..Default::default()
+                            },
+                        )
+                    })
+                })
+                .collect()
+        });
+
+        results.into_iter().unzip()
+    }
+
+    fn search_host(
+        &self,
+        source: &SourceDefinition,
+        script: &str,
+    ) -> (Vec<SearchHit>, HostOutcome) {
+        let start = Instant::now();
+        let host = source.host.clone().unwrap_or_default();
+        let mut outcome = HostOutcome {
+            source_id: source.name.clone(),
+            host: host.clone(),
+            ..Default::default()
+        };
+
+        let (lines, stderr) = match self.run_ssh(&host, script, &mut outcome) {
+            Ok(output) => output,
+            Err(e) => {
+                outcome.error = Some(e);
+                outcome.elapsed_ms = start.elapsed().as_millis() as u64;
+                return (Vec::new(), outcome);
+            }
+        };
+
+        let hits: Vec<SearchHit> = lines
+            .iter()
+            .filter_map(|line| parse_hit_line(line))
+            .map(|hit| tag_remote_hit(hit, source))
+            .collect();
+        if hits.is_empty() && outcome.error.is_none() && !stderr.trim().is_empty() {
+            outcome.error = Some(remote_error(&stderr));
+        }
+        outcome.hits = hits.len();
+        outcome.elapsed_ms = start.elapsed().as_millis() as u64;
+        (hits, outcome)
+    }
+
+    /// Run the script over SSH, returning stdout lines and stderr.
+    ///
+    /// On timeout the ssh process is killed and the lines streamed so far
+    /// are kept.
+    fn run_ssh(
+        &self,
+        host: &str,
+        script: &str,
+        outcome: &mut HostOutcome,
+    ) -> Result<(Vec<String>, String), String> {
+        let mut child = Command::new("ssh")
+            .arg("-o")
+            .arg("BatchMode=yes")
+            .arg("-o")
+            .arg(format!(
+                "ConnectTimeout={}",
+                self.timeout.as_secs().clamp(1, 30)
+            ))
+            .arg("-o")
+            .arg("StrictHostKeyChecking=accept-new")
+            .arg("-o")
+            .arg("LogLevel=ERROR")
+            .arg("--")
+            .arg(host)
+            .arg("bash")
+            .arg("-s")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("failed to run ssh: {e}"))?;
+
+        // A write error means ssh already exited; its status and stderr say why
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(script.as_bytes());
+        }
+
+        // Readers are detached: a killed ssh can leave a grandchild holding
+        // the pipes open, and the caller must not wait on it.
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        if let Some(stdout) = child.stdout.take() {
+            let lines = Arc::clone(&lines);
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    lines.lock().unwrap_or_else(|e| e.into_inner()).push(line);
+                }
+            });
+        }
+        let stderr = Arc::new(Mutex::new(String::new()));
+        if let Some(mut pipe) = child.stderr.take() {
+            let stderr = Arc::clone(&stderr);
+            std::thread::spawn(move || {
+                let mut buf = String::new();
+                let _ = std::io::Read::read_to_string(&mut pipe, &mut buf);
+                *stderr.lock().unwrap_or_else(|e| e.into_inner()) = buf;
+            });
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    outcome.timed_out = true;
+                    break None;
+                }
+                Ok(None) => std::thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(format!("failed to wait for ssh: {e}")),
+            }
+        };
+
+        // Let the readers drain what the process wrote before it exited
+        let grace = Duration::from_millis(200);
+        let drain_until = match status {
+            Some(_) => Instant::now().max(deadline) + grace,
+            None => Instant::now() + grace,
+        };
+        while (Arc::strong_count(&lines) > 1 || Arc::strong_count(&stderr) > 1)
+            && Instant::now() < drain_until
+        {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let lines = std::mem::take(&mut *lines.lock().unwrap_or_else(|e| e.into_inner()));
+        let stderr = std::mem::take(&mut *stderr.lock().unwrap_or_else(|e| e.into_inner()));
+        if let Some(status) = status
+            && !status.success()
+            && lines.is_empty()
+        {
+            return Err(remote_error(&stderr));
+        }
+        Ok((lines, stderr))
+    }
+}
+
+/// Parse one JSONL line of `cass search --robot-format jsonl` output.
+///
+/// Returns `None` for the optional `_meta` header and anything that is not a hit.
+pub fn parse_hit_line(line: &str) -> Option<SearchHit> {
+    let value: serde_json::Value = serde_json::from_str(line.trim()).ok()?;
+    if value.get("_meta").is_some() {
+        return None;
+    }
+    serde_json::from_value(value).ok()
+}
+
+/// Attribute a hit returned by a remote install to the source it came from.
+///
+/// Hits the remote indexed locally become hits of `source` (and its owner,
+/// when one is configured); hits the remote itself synced from elsewhere keep
+/// their source id.
+pub fn tag_remote_hit(mut hit: SearchHit, source: &SourceDefinition) -> SearchHit {
+    if hit.source_id == "local" {
+        hit.source_id = source.name.clone();
+        hit.origin_kind = "ssh".to_string();
+        hit.origin_host = source.host.clone();
+        if let Some(owner) = source.effective_owner() {
+            hit.owner = Some(owner);
+        }
+        let rewritten = source.rewrite_path_for_agent(&hit.workspace, Some(&hit.agent));
+        if rewritten != hit.workspace {
+            hit.workspace_original = Some(std::mem::replace(&mut hit.workspace, rewritten));
+        }
+    }
+    hit
+}
+
+/// Merge the local ranking and each host's ranking into one.
+///
+/// BM25 and similarity scores depend on each index's corpus, so hits are
+/// ordered by Reciprocal Rank Fusion over the per-host rankings instead, and
+/// `score` holds the fused value. A hit with the same source, path and line
+/// in several rankings (the remote was also synced into the local index) is
+/// kept once, with the contributions of every ranking it appears in.
+pub fn merge_hits(local: Vec<SearchHit>, remote: Vec<Vec<SearchHit>>) -> Vec<SearchHit> {
+    let mut merged: Vec<SearchHit> = Vec::new();
+    let mut positions: std::collections::HashMap<_, usize> = std::collections::HashMap::new();
+    for ranking in std::iter::once(local).chain(remote) {
+        let mut seen_here = std::collections::HashSet::new();
+        for (rank, mut hit) in ranking.into_iter().enumerate() {
+            let key = (
+                hit.source_id.clone(),
+                hit.source_path.clone(),
+                hit.line_number,
+                hit.created_at,
+            );
+            if !seen_here.insert(key.clone()) {
+                continue;
+            }
+            let contribution = 1.0 / (RRF_K + rank as f32 + 1.0);
+            match positions.get(&key) {
+                Some(&pos) => merged[pos].score += contribution,
+                None => {
+                    hit.score = contribution;
+                    positions.insert(key, merged.len());
+                    merged.push(hit);
+                }
+            }
+        }
+    }
+
+    merged.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.source_id.cmp(&b.source_id))
+            .then_with(|| a.source_path.cmp(&b.source_path))
+            .then_with(|| a.line_number.cmp(&b.line_number))
+    });
+    merged
+}
+
+fn remote_error(stderr: &str) -> String {
+    let message = stderr.trim();
+    if message.contains("command not found") {
+        "cass is not installed on the remote".to_string()
+    } else if message.is_empty() {
+        "remote search failed".to_string()
+    } else {
+        message.lines().last().unwrap_or(message).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::config::PathMapping;
+
+    fn remote_line(source_id: &str, score: f32) -> String {
+        serde_json::json!({
+            "title": "t",
+            "snippet": "s",
+            "content": "c",
+            "score": score,
+            "source_path": "/home/dev/.codex/sessions/a.jsonl",
+            "agent": "codex",
+            "workspace": "/home/dev/proj",
+            "created_at": 1_700_000_000_000i64,
+            "line_number": 3,
+            "match_type": "exact",
+            "source_id": source_id,
+            "origin_kind": "local",
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn script_quotes_query_and_filters() {
+        let query = RemoteQuery {
+            query: "it's broken".into(),
+            agents: vec!["codex".into()],
+            limit: 20,
+            since: Some(0),
+            mode: Some(SearchMode::Hybrid),
+            ..Default::default()
+        };
+        let script = query.script();
+        assert!(script.contains(
+            r#"TZ=UTC exec cass search 'it'\''s broken' --limit 20 --robot-format jsonl --agent codex --since 1970-01-01T00:00:00 --mode hybrid"#
+        ));
+    }
+
+    #[test]
+    fn remote_hits_are_tagged_with_the_source() {
+        let mut source = SourceDefinition::ssh("builder", "dev@builder");
+        source
+            .path_mappings
+            .push(PathMapping::new("/home/dev", "/Users/me"));
+
+        assert!(parse_hit_line(r#"{"_meta":{"count":1}}"#).is_none());
+        let hit = tag_remote_hit(parse_hit_line(&remote_line("local", 1.0)).unwrap(), &source);
+        assert_eq!(hit.source_id, "builder");
+        assert_eq!(hit.origin_kind, "ssh");
+        assert_eq!(hit.origin_host.as_deref(), Some("dev@builder"));
+        assert_eq!(hit.workspace, "/Users/me/proj");
+        assert_eq!(hit.workspace_original.as_deref(), Some("/home/dev/proj"));
+
+        // Hits the remote synced from a third machine keep their provenance
+        let hit = tag_remote_hit(
+            parse_hit_line(&remote_line("laptop", 1.0)).unwrap(),
+            &source,
+        );
+        assert_eq!(hit.source_id, "laptop");
+    }
+
+    #[test]
+    fn merge_fuses_host_rankings_and_drops_duplicates() {
+        let builder = SourceDefinition::ssh("builder", "dev@builder");
+        let other = SourceDefinition::ssh("other", "dev@other");
+        let hit = |source: &SourceDefinition, path: &str, score| {
+            let mut hit = tag_remote_hit(
+                parse_hit_line(&remote_line("local", score)).unwrap(),
+                source,
+            );
+            hit.source_path = path.into();
+            hit
+        };
+        let mut local = parse_hit_line(&remote_line("local", 2.0)).unwrap();
+        local.source_path = "/local/session.jsonl".into();
+
+        // The local index also holds builder's top hit; raw scores from the
+        // other host are on a larger scale and must not win on that alone.
+        let merged = merge_hits(
+            vec![hit(&builder, "/b/1.jsonl", 2.5), local],
+            vec![
+                vec![
+                    hit(&builder, "/b/1.jsonl", 1.5),
+                    hit(&builder, "/b/2.jsonl", 1.0),
+                ],
+                vec![
+                    hit(&other, "/o/1.jsonl", 40.0),
+                    hit(&other, "/o/2.jsonl", 30.0),
+                ],
+            ],
+        );
+        let order: Vec<_> = merged
+            .iter()
+            .map(|h| (h.source_id.as_str(), h.source_path.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("builder", "/b/1.jsonl"),
+                ("other", "/o/1.jsonl"),
+                ("builder", "/b/2.jsonl"),
+                ("local", "/local/session.jsonl"),
+                ("other", "/o/2.jsonl"),
+            ]
+        );
+        assert!((merged[0].score - 2.0 / (RRF_K + 1.0)).abs() < 1e-6);
+        assert!((merged[1].score - 1.0 / (RRF_K + 1.0)).abs() < 1e-6);
+    }
+}
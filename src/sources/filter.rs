@@ -0,0 +1,409 @@
+//! Per-source file selection: include/exclude globs, agents, size and age.
+//!
+//! The same rules apply at every stage so a file left out of a sync is
+//! not picked up later by a looser check:
+//!
+//! - rsync gets them as `--include`/`--exclude` filter rules and
+//!   `--max-size`; the age limit selects files with a remote `find`.
+//! - SFTP checks each remote entry before downloading it.
+//! - The indexer drops conversations from a mirror that fail them.
+//!
+//! Globs are matched against paths relative to the synced directory,
+//! starting with its name (`projects/abc/session.jsonl` for
+//! `~/.claude/projects`), using rsync's rules: a pattern without `/`
+//! matches a single name at any depth, a pattern with `/` matches the end
+//! of the path. `*` stays within a name and `**` spans directories.
+//! Excludes also prune directories; includes select files.
+
+use std::path::{Component, Path};
+use std::time::{Duration, SystemTime};
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use super::config::ConfigError;
+
+/// Filter settings of a source, as written in `sources.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncFilter {
+    /// Only sync files matching one of these globs (default: everything).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+
+    /// Never sync files or directories matching these globs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+
+    /// Only sync sessions of these agents (slugs such as `claude_code`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+
+    /// Skip files larger than this, e.g. `"50M"` (K, M and G are binary).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<String>,
+
+    /// Skip files not modified in this many days.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+}
+
+impl SyncFilter {
+    /// Whether no rule is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check the rules, returning the first problem.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        FileFilter::new(self).map(|_| ())
+    }
+}
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A glob and whether it is matched against a single name.
+#[derive(Debug, Clone)]
+struct Glob {
+    pattern: Pattern,
+    name_only: bool,
+}
+
+impl Glob {
+    fn new(raw: &str, field: &str) -> Result<Self, ConfigError> {
+        let trimmed = raw.trim_end_matches('/');
+        if trimmed.is_empty() {
+            return Err(ConfigError::Validation(format!(
+                "{field} pattern cannot be empty"
+            )));
+        }
+        if trimmed.starts_with('/') {
+            return Err(ConfigError::Validation(format!(
+                "{field} pattern '{raw}' must be relative to the synced path; drop the leading '/'"
+            )));
+        }
+        let pattern = Pattern::new(trimmed).map_err(|e| {
+            ConfigError::Validation(format!("Invalid {field} pattern '{raw}': {e}"))
+        })?;
+        Ok(Self {
+            pattern,
+            name_only: !trimmed.contains('/'),
+        })
+    }
+
+    /// Whether the pattern matches `components` (as a path ending there).
+    fn matches_end(&self, components: &[&str]) -> bool {
+        let Some(last) = components.last() else {
+            return false;
+        };
+        if self.name_only {
+            return self.pattern.matches_with(last, MATCH_OPTIONS);
+        }
+        (0..components.len()).any(|start| {
+            self.pattern
+                .matches_with(&components[start..].join("/"), MATCH_OPTIONS)
+        })
+    }
+}
+
+/// Compiled [`SyncFilter`]; the default allows everything.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+    agents: Vec<String>,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+}
+
+impl FileFilter {
+    pub fn new(filter: &SyncFilter) -> Result<Self, ConfigError> {
+        let include = filter
+            .include
+            .iter()
+            .map(|p| Glob::new(p, "include"))
+            .collect::<Result<_, _>>()?;
+        let exclude = filter
+            .exclude
+            .iter()
+            .map(|p| Glob::new(p, "exclude"))
+            .collect::<Result<_, _>>()?;
+        if let Some(agent) = filter.agents.iter().find(|a| a.trim().is_empty()) {
+            return Err(ConfigError::Validation(format!(
+                "Invalid agent '{agent}' in agents"
+            )));
+        }
+        let max_size = filter
+            .max_file_size
+            .as_deref()
+            .map(parse_size)
+            .transpose()
+            .map_err(ConfigError::Validation)?;
+        if filter.max_age_days == Some(0) {
+            return Err(ConfigError::Validation(
+                "max_age_days must be at least 1; remove it for no limit".into(),
+            ));
+        }
+        Ok(Self {
+            include,
+            exclude,
+            agents: filter.agents.clone(),
+            max_size,
+            max_age: filter
+                .max_age_days
+                .map(|days| Duration::from_secs(u64::from(days) * 24 * 60 * 60)),
+        })
+    }
+
+    /// Whether no rule is set.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.agents.is_empty()
+            && self.max_size.is_none()
+            && self.max_age.is_none()
+    }
+
+    /// Largest file to sync, in bytes.
+    pub fn max_size(&self) -> Option<u64> {
+        self.max_size
+    }
+
+    /// Oldest modification age of a file to sync.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Whether sessions of `agent` (a slug) are wanted.
+    pub fn allows_agent(&self, agent: &str) -> bool {
+        self.agents.is_empty() || self.agents.iter().any(|a| a == agent)
+    }
+
+    /// Whether a configured source path belongs to a wanted agent. Paths
+    /// that can't be attributed to an agent are kept.
+    pub fn allows_source_path(&self, path: &str) -> bool {
+        match super::probe::infer_agent_type(path).as_str() {
+            "unknown" => true,
+            agent => self.allows_agent(agent),
+        }
+    }
+
+    /// Whether a directory at `rel` should be descended into.
+    pub fn allows_dir(&self, rel: &Path) -> bool {
+        let components = components(rel);
+        !self.excluded(&components)
+    }
+
+    /// Whether a file at `rel` passes the include/exclude globs.
+    pub fn allows_path(&self, rel: &Path) -> bool {
+        let components = components(rel);
+        if self.excluded(&components) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|g| g.matches_end(&components))
+    }
+
+    /// Whether a file passes every rule.
+    pub fn allows_file(
+        &self,
+        rel: &Path,
+        size: u64,
+        modified: Option<SystemTime>,
+        now: SystemTime,
+    ) -> bool {
+        self.allows_size(size) && self.allows_modified(modified, now) && self.allows_path(rel)
+    }
+
+    pub fn allows_size(&self, size: u64) -> bool {
+        self.max_size.is_none_or(|max| size <= max)
+    }
+
+    /// Whether something last modified at `modified` is recent enough.
+    /// Unknown times pass.
+    pub fn allows_modified(&self, modified: Option<SystemTime>, now: SystemTime) -> bool {
+        match (self.max_age, modified) {
+            (Some(max_age), Some(modified)) => now
+                .duration_since(modified)
+                .map_or(true, |age| age <= max_age),
+            _ => true,
+        }
+    }
+
+    /// Any ancestor directory or the path itself matching an exclude.
+    fn excluded(&self, components: &[&str]) -> bool {
+        (1..=components.len()).any(|end| {
+            self.exclude
+                .iter()
+                .any(|g| g.matches_end(&components[..end]))
+        })
+    }
+
+    /// rsync arguments applying the globs and the size limit.
+    ///
+    /// Excludes come first so they win over includes; with includes, every
+    /// directory is traversed and anything not included is dropped.
+    pub fn rsync_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = self
+            .exclude
+            .iter()
+            .map(|g| format!("--exclude={}", g.pattern.as_str()))
+            .collect();
+        if !self.include.is_empty() {
+            args.push("--include=*/".into());
+            args.extend(
+                self.include
+                    .iter()
+                    .map(|g| format!("--include={}", g.pattern.as_str())),
+            );
+            args.push("--exclude=*".into());
+            args.push("--prune-empty-dirs".into());
+        }
+        if let Some(max) = self.max_size {
+            args.push(format!("--max-size={max}"));
+        }
+        args
+    }
+}
+
+fn components(rel: &Path) -> Vec<&str> {
+    rel.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parse a size such as `1048576`, `500K`, `50M` or `2G` into bytes.
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim();
+    let digits_end = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(digits_end);
+    let invalid = || format!("Invalid max_file_size '{value}': use bytes or a K, M or G suffix");
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+    if number == 0 {
+        return Err(format!("max_file_size must be greater than 0: '{value}'"));
+    }
+    number.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> FileFilter {
+        FileFilter::new(&SyncFilter {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn globs_follow_rsync_matching() {
+        let f = filter(&["*.jsonl"], &["debug", "projects/tmp-*/**"]);
+        assert!(f.allows_path(Path::new("projects/abc/session.jsonl")));
+        assert!(!f.allows_path(Path::new("projects/abc/session.log")));
+        // A name-only exclude prunes directories at any depth
+        assert!(!f.allows_path(Path::new("projects/abc/debug/session.jsonl")));
+        assert!(!f.allows_dir(Path::new("projects/debug")));
+        // A pattern with `/` matches the end of the path
+        assert!(!f.allows_path(Path::new("projects/tmp-1/a/b.jsonl")));
+        assert!(f.allows_path(Path::new("projects/keep/tmp-1/b.jsonl")));
+        // `*` stays within one name
+        let f = filter(&["projects/*.jsonl"], &[]);
+        assert!(f.allows_path(Path::new("projects/a.jsonl")));
+        assert!(!f.allows_path(Path::new("projects/abc/a.jsonl")));
+    }
+
+    #[test]
+    fn rsync_args_put_excludes_first() {
+        let f = FileFilter::new(&SyncFilter {
+            include: vec!["*.jsonl".into()],
+            exclude: vec!["debug/".into()],
+            max_file_size: Some("50M".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            f.rsync_args(),
+            [
+                "--exclude=debug",
+                "--include=*/",
+                "--include=*.jsonl",
+                "--exclude=*",
+                "--prune-empty-dirs",
+                "--max-size=52428800",
+            ]
+        );
+        assert!(FileFilter::default().rsync_args().is_empty());
+    }
+
+    #[test]
+    fn size_and_age_limits() {
+        let f = FileFilter::new(&SyncFilter {
+            max_file_size: Some("1K".into()),
+            max_age_days: Some(7),
+            ..Default::default()
+        })
+        .unwrap();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 3600);
+        let days_ago = |d: u64| Some(now - Duration::from_secs(d * 24 * 3600));
+        let rel = Path::new("a.jsonl");
+        assert!(f.allows_file(rel, 1024, days_ago(6), now));
+        assert!(!f.allows_file(rel, 1025, days_ago(6), now));
+        assert!(!f.allows_file(rel, 10, days_ago(8), now));
+        assert!(f.allows_file(rel, 10, None, now));
+    }
+
+    #[test]
+    fn agents_select_sessions_and_source_paths() {
+        let f = FileFilter::new(&SyncFilter {
+            agents: vec!["codex".into()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(f.allows_agent("codex"));
+        assert!(!f.allows_agent("claude_code"));
+        assert!(f.allows_source_path("~/.codex/sessions"));
+        assert!(!f.allows_source_path("~/.claude/projects"));
+        assert!(f.allows_source_path("~/logs"));
+        assert!(FileFilter::default().allows_agent("claude_code"));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let invalid = |filter: SyncFilter| filter.validate().is_err();
+        assert!(invalid(SyncFilter {
+            include: vec!["/abs/*.jsonl".into()],
+            ..Default::default()
+        }));
+        assert!(invalid(SyncFilter {
+            exclude: vec!["[".into()],
+            ..Default::default()
+        }));
+        assert!(invalid(SyncFilter {
+            max_file_size: Some("10 parsecs".into()),
+            ..Default::default()
+        }));
+        assert!(invalid(SyncFilter {
+            max_age_days: Some(0),
+            ..Default::default()
+        }));
+        assert_eq!(parse_size("2G"), Ok(2 << 30));
+        assert_eq!(parse_size("512"), Ok(512));
+    }
+}
@@ -0,0 +1,993 @@
+//! Push-mode sources: `cass serve-ingest` and `cass push`.
+//!
+//! Machines that cannot be reached for a pull sync (laptops behind NAT,
+//! short-lived CI runners) upload their sessions to a central cass instead.
+//! Each push source in `sources.toml` (`type = "push"`) holds the SHA-256 of
+//! a bearer token; the server maps the token of a request to its source and
+//! stores what it receives in that source's mirror directory:
+//!
+//! - `PUT /v1/files/<root>/<path>` stores a session file under
+//!   `mirror/files/<root>/<path>`. With `?offset=N` the body is appended to a
+//!   file that is exactly `N` bytes long, otherwise 409 reports the size the
+//!   server has. A stored file may not grow past [`MAX_FILE_BYTES`]. Pushed
+//!   roots are indexed like synced SSH mirrors.
+//! - `POST /v1/conversations` takes [`NormalizedConversation`] JSONL, keeps
+//!   it under `mirror/conversations/` and indexes it right away.
+//! - `GET /v1/status` names the source the token belongs to.
+//!
+//! The client remembers what it sent to each server ([`PushState`],
+//! `push_state.json`) and only uploads files that changed, sending just the
+//! new tail of a file that grew.
+//!
+//! The server speaks plain HTTP and tokens travel in the clear. It listens on
+//! loopback by default; to accept pushes from other machines, put a TLS
+//! reverse proxy in front of it rather than binding a public address.
+
+use anyhow::{Context, Result, bail};
+use rand::RngCore;
+use reqwest::Url;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::config::{SourceDefinition, SourcesConfig};
+use super::provenance::{LOCAL_SOURCE_ID, Source, SourceKind};
+use super::sync::{SyncEngine, expand_tilde_local, path_to_safe_dirname};
+use crate::connectors::NormalizedConversation;
+use crate::http::{self, RequestHead};
+use crate::indexer::persist::persist_conversations_batched;
+use crate::search::tantivy::{TantivyIndex, index_dir};
+use crate::storage::sqlite::SqliteStorage;
+
+/// Address `cass serve-ingest` listens on by default
+pub const DEFAULT_INGEST_ADDR: &str = "127.0.0.1:8731";
+
+/// Longest pause allowed while a request body streams in
+const BODY_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Largest session file a push source may build up, appends included
+pub const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Connections served at once; more are turned away with 503
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Quiet period after the last file upload before indexing
+const INDEX_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Client-side record of pushed files
+const STATE_FILE: &str = "push_state.json";
+
+/// A new random upload token (64 hex characters)
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// SHA-256 (hex) of a token, as stored in `sources.toml`
+pub fn token_sha256(token: &str) -> String {
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
+
+/// Create the push source `name`, or give it a new token, returning the token
+///
+/// Only the token's hash is stored; the token itself is shown once.
+pub fn issue_token(config: &mut SourcesConfig, name: &str) -> Result<String> {
+    if name == LOCAL_SOURCE_ID {
+        bail!("'{LOCAL_SOURCE_ID}' is reserved for this machine's sessions");
+    }
+    let token = new_token();
+    let hash = token_sha256(&token);
+    match config.find_source_mut(name) {
+        Some(source) if source.is_push() => source.token_sha256 = Some(hash),
+        Some(source) => bail!(
+            "Source '{name}' already exists as a {} source",
+            source.source_type
+        ),
+        None => config.add_source(SourceDefinition::push(name, hash))?,
+    }
+    Ok(token)
+}
+
+/// Resolve an upload path (`<root>/<path>`) under `base`
+///
+/// Returns `None` unless it names a file inside a root directory: no `..`,
+/// absolute or drive components, and at least two components.
+pub fn resolve_upload_path(base: &Path, rel: &str) -> Option<PathBuf> {
+    let mut resolved = base.to_path_buf();
+    let mut depth = 0;
+    for part in rel.split('/') {
+        let mut components = Path::new(part).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(name)), None) if !part.contains('\\') => {
+                resolved.push(name);
+                depth += 1;
+            }
+            _ => return None,
+        }
+    }
+    (depth >= 2).then_some(resolved)
+}
+
+/// A blocking HTTP server accepting uploads from push sources
+pub struct IngestServer {
+    listener: TcpListener,
+    data_dir: PathBuf,
+    db_path: PathBuf,
+    max_connections: usize,
+    max_file_bytes: u64,
+}
+
+/// State shared by the connection threads
+struct Ingest {
+    data_dir: PathBuf,
+    db_path: PathBuf,
+    engine: SyncEngine,
+    /// Held while writing to the database and index
+    index_lock: Arc<Mutex<()>>,
+    /// Sources that received files, for the indexing worker
+    uploads: Mutex<mpsc::Sender<String>>,
+    /// One lock per stored file, held from the size check to the last byte
+    file_locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+    max_file_bytes: u64,
+}
+
+/// A connection counted against the cap, released when its thread ends
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(active: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(active)))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Status and JSON body of a response
+type Reply = std::result::Result<(&'static str, serde_json::Value), (&'static str, String)>;
+
+impl IngestServer {
+    /// Bind to `addr` (e.g. `0.0.0.0:8731`; port 0 picks a free port)
+    pub fn bind(data_dir: &Path, db_path: &Path, addr: &str) -> Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to listen on {addr}"))?;
+        Ok(Self {
+            listener,
+            data_dir: data_dir.to_path_buf(),
+            db_path: db_path.to_path_buf(),
+            max_connections: MAX_CONNECTIONS,
+            max_file_bytes: MAX_FILE_BYTES,
+        })
+    }
+
+    /// Serve at most `max` connections at once (default [`MAX_CONNECTIONS`])
+    pub fn max_connections(mut self, max: usize) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Refuse uploads that would make a file larger than `max` bytes
+    /// (default [`MAX_FILE_BYTES`])
+    pub fn max_file_bytes(mut self, max: u64) -> Self {
+        self.max_file_bytes = max;
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Base URL clients push to
+    pub fn url(&self) -> Result<String> {
+        Ok(format!("http://{}/", self.local_addr()?))
+    }
+
+    /// Serve requests until the process exits, one thread per connection
+    ///
+    /// Connections beyond the cap get 503 right away. Each client has
+    /// [`http::HEAD_TIMEOUT`] to send its request head, so idle or dripping
+    /// connections give their slot back.
+    ///
+    /// `on_files` runs on a worker thread once uploads of session files
+    /// pause, with the sources that received them; it is where the caller
+    /// reindexes. It never overlaps with conversations being ingested.
+    pub fn run<F>(self, mut on_files: F) -> Result<()>
+    where
+        F: FnMut(&[String]) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel::<String>();
+        let index_lock = Arc::new(Mutex::new(()));
+        let worker_lock = Arc::clone(&index_lock);
+        std::thread::spawn(move || {
+            while let Ok(first) = rx.recv() {
+                let mut sources = BTreeSet::from([first]);
+                while let Ok(source) = rx.recv_timeout(INDEX_DEBOUNCE) {
+                    sources.insert(source);
+                }
+                let sources: Vec<String> = sources.into_iter().collect();
+                let _guard = worker_lock.lock().unwrap_or_else(|e| e.into_inner());
+                on_files(&sources);
+            }
+        });
+
+        let ingest = Arc::new(Ingest {
+            engine: SyncEngine::new(&self.data_dir),
+            data_dir: self.data_dir,
+            db_path: self.db_path,
+            index_lock,
+            uploads: Mutex::new(tx),
+            file_locks: Mutex::default(),
+            max_file_bytes: self.max_file_bytes,
+        });
+        let active = Arc::new(AtomicUsize::new(0));
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let Some(slot) = ConnectionSlot::acquire(&active, self.max_connections) else {
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(5)));
+                let _ = respond(
+                    &stream,
+                    "503 Service Unavailable",
+                    &serde_json::json!({ "error": "Too many connections" }),
+                );
+                continue;
+            };
+            let ingest = Arc::clone(&ingest);
+            std::thread::spawn(move || {
+                let _slot = slot;
+                if let Err(e) = handle_connection(stream, &ingest) {
+                    tracing::debug!("serve-ingest: {e:#}");
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn handle_connection(stream: TcpStream, ingest: &Ingest) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let reply = match http::read_request_head(&mut reader, http::HEAD_TIMEOUT)? {
+        Ok(head) => {
+            stream.set_read_timeout(Some(BODY_READ_TIMEOUT))?;
+            ingest.route(&head, &mut reader)
+        }
+        Err(status) => Err((status, status.to_string())),
+    };
+    let (status, body) = reply.unwrap_or_else(|(status, error)| {
+        if status.starts_with('5') {
+            tracing::warn!("serve-ingest: {error}");
+        }
+        (status, serde_json::json!({ "error": error }))
+    });
+    respond(&stream, status, &body)
+}
+
+fn respond(stream: &TcpStream, status: &str, body: &serde_json::Value) -> Result<()> {
+    let body = serde_json::to_vec(body)?;
+    http::write_response(stream, status, "application/json", &[], &body, false)?;
+    Ok(())
+}
+
+/// Map any error to a 500 reply
+fn internal(e: impl std::fmt::Display) -> (&'static str, String) {
+    ("500 Internal Server Error", e.to_string())
+}
+
+impl Ingest {
+    fn route(&self, head: &RequestHead, body: &mut impl Read) -> Reply {
+        let source = self.authenticate(head)?;
+        let (path, query) = head
+            .target
+            .split_once('?')
+            .unwrap_or((head.target.as_str(), ""));
+        match (head.method.as_str(), path) {
+            ("GET", "/v1/status") => Ok(("200 OK", serde_json::json!({ "source_id": source }))),
+            ("POST", "/v1/conversations") => self.ingest_conversations(&source, head, body),
+            ("PUT", _) if path.starts_with("/v1/files/") => {
+                self.store_file(&source, &path["/v1/files/".len()..], query, head, body)
+            }
+            (_, "/v1/status" | "/v1/conversations") => Err((
+                "405 Method Not Allowed",
+                format!("{} is not allowed on {path}", head.method),
+            )),
+            _ if path.starts_with("/v1/files/") => Err((
+                "405 Method Not Allowed",
+                format!("{} is not allowed on {path}", head.method),
+            )),
+            _ => Err(("404 Not Found", format!("No such endpoint: {path}"))),
+        }
+    }
+
+    /// The push source the request's token belongs to
+    ///
+    /// `sources.toml` is re-read per request, so new and rotated tokens
+    /// apply without a restart.
+    fn authenticate(
+        &self,
+        head: &RequestHead,
+    ) -> std::result::Result<String, (&'static str, String)> {
+        let token = head
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        let Some(token) = token.filter(|t| !t.is_empty()) else {
+            return Err(("401 Unauthorized", "Missing bearer token".into()));
+        };
+        let config = SourcesConfig::load().map_err(internal)?;
+        config
+            .find_push_source(&token_sha256(token))
+            .map(|source| source.name.clone())
+            .ok_or_else(|| ("401 Unauthorized", "Unknown token".into()))
+    }
+
+    fn content_length(head: &RequestHead) -> std::result::Result<u64, (&'static str, String)> {
+        let len = head
+            .header("content-length")
+            .ok_or_else(|| {
+                (
+                    "411 Length Required",
+                    "Content-Length is required".to_string(),
+                )
+            })?
+            .parse::<u64>()
+            .map_err(|_| ("400 Bad Request", "Invalid Content-Length".to_string()))?;
+        if len > MAX_BODY_BYTES {
+            return Err((
+                "413 Payload Too Large",
+                format!("Uploads are limited to {MAX_BODY_BYTES} bytes"),
+            ));
+        }
+        Ok(len)
+    }
+
+    fn store_file(
+        &self,
+        source: &str,
+        rel: &str,
+        query: &str,
+        head: &RequestHead,
+        body: &mut impl Read,
+    ) -> Reply {
+        let len = Self::content_length(head)?;
+        let rel = urlencoding::decode(rel)
+            .map_err(|_| ("400 Bad Request", "Path is not valid UTF-8".to_string()))?;
+        let dest = resolve_upload_path(&self.engine.push_files_dir(source), &rel)
+            .ok_or_else(|| ("400 Bad Request", format!("Invalid upload path: {rel}")))?;
+        let offset = match query.strip_prefix("offset=") {
+            Some(n) => Some(
+                n.parse::<u64>()
+                    .map_err(|_| ("400 Bad Request", format!("Invalid offset: {n}")))?,
+            ),
+            None => None,
+        };
+        if offset.unwrap_or(0).saturating_add(len) > self.max_file_bytes {
+            return Err((
+                "413 Payload Too Large",
+                format!("Files are limited to {} bytes", self.max_file_bytes),
+            ));
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(internal)?;
+        }
+
+        // Concurrent pushes of the same file must not both pass the size
+        // check and then interleave their bytes
+        let file_lock = self.file_lock(&dest);
+        let _guard = file_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let size = match offset {
+            Some(offset) => {
+                let current = fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+                if current != offset {
+                    return Err((
+                        "409 Conflict",
+                        format!("Server has {current} bytes of {rel}, not {offset}"),
+                    ));
+                }
+                let mut file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&dest)
+                    .map_err(internal)?;
+                if let Err(e) = copy_body(body, len, &mut file) {
+                    // Drop a partial tail so the next append sees the old size
+                    let _ = file.set_len(offset);
+                    return Err(e);
+                }
+                offset + len
+            }
+            None => {
+                let mut part_name = dest.file_name().unwrap_or_default().to_os_string();
+                part_name.push(".part");
+                let part = dest.with_file_name(part_name);
+                let mut file = fs::File::create(&part).map_err(internal)?;
+                if let Err(e) = copy_body(body, len, &mut file) {
+                    let _ = fs::remove_file(&part);
+                    return Err(e);
+                }
+                fs::rename(&part, &dest).map_err(internal)?;
+                len
+            }
+        };
+
+        if let Ok(uploads) = self.uploads.lock() {
+            let _ = uploads.send(source.to_string());
+        }
+        Ok(("200 OK", serde_json::json!({ "path": rel, "size": size })))
+    }
+
+    /// The lock serializing writes to `path`
+    fn file_lock(&self, path: &Path) -> Arc<Mutex<()>> {
+        let mut locks = self.file_locks.lock().unwrap_or_else(|e| e.into_inner());
+        // Forget locks nobody holds any more
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        Arc::clone(locks.entry(path.to_path_buf()).or_default())
+    }
+
+    fn ingest_conversations(
+        &self,
+        source: &str,
+        head: &RequestHead,
+        body: &mut impl Read,
+    ) -> Reply {
+        let len = Self::content_length(head)?;
+        // Grows with what actually arrives, not with the claimed length
+        let mut jsonl = Vec::new();
+        copy_body(body, len, &mut jsonl)?;
+
+        let mut conversations = Vec::new();
+        for (line_no, line) in jsonl.split(|b| *b == b'\n').enumerate() {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let mut conv: NormalizedConversation = serde_json::from_slice(line).map_err(|e| {
+                (
+                    "400 Bad Request",
+                    format!("Line {}: not a conversation: {e}", line_no + 1),
+                )
+            })?;
+            // The push source is the origin, whatever the client recorded
+            if !conv.metadata.is_object() {
+                conv.metadata = serde_json::json!({});
+            }
+            conv.metadata["cass"] = serde_json::json!({
+                "origin": {
+                    "source_id": source,
+                    "kind": SourceKind::Push.as_str(),
+                    "host": serde_json::Value::Null,
+                }
+            });
+            conversations.push(conv);
+        }
+
+        let dir = self.engine.push_conversations_dir(source);
+        fs::create_dir_all(&dir).map_err(internal)?;
+        let name = format!(
+            "{}-{}.jsonl",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            &hex::encode(Sha256::digest(&jsonl))[..12]
+        );
+        fs::write(dir.join(name), &jsonl).map_err(internal)?;
+
+        let _guard = self.index_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let new_messages = self
+            .persist(source, &conversations)
+            .map_err(|e| internal(format!("{e:#}")))?;
+        Ok((
+            "200 OK",
+            serde_json::json!({
+                "conversations": conversations.len(),
+                "new_messages": new_messages,
+            }),
+        ))
+    }
+
+    /// Write conversations to the database and index, returning how many
+    /// messages were new
+    fn persist(&self, source_id: &str, conversations: &[NormalizedConversation]) -> Result<usize> {
+        let mut storage = SqliteStorage::open(&self.db_path)?;
+        let mut source = storage
+            .get_source(source_id)?
+            .unwrap_or_else(|| Source::remote(source_id, source_id));
+        source.kind = SourceKind::Push;
+        source.host_label = None;
+        storage.upsert_source(&source)?;
+
+        let count = |storage: &SqliteStorage| -> Result<i64> {
+            Ok(storage.raw().query_row(
+                "SELECT COUNT(*) FROM messages m
+                 JOIN conversations c ON m.conversation_id = c.id
+                 WHERE c.source_id = ?",
+                [source_id],
+                |row| row.get(0),
+            )?)
+        };
+        let before = count(&storage)?;
+        let mut t_index = TantivyIndex::open_or_create(&index_dir(&self.data_dir)?)?;
+        persist_conversations_batched(&mut storage, &mut t_index, conversations, false)?;
+        t_index.commit()?;
+        Ok((count(&storage)? - before) as usize)
+    }
+}
+
+/// Copy exactly `len` bytes of request body into `out`
+fn copy_body(
+    body: &mut impl Read,
+    len: u64,
+    out: &mut impl Write,
+) -> std::result::Result<(), (&'static str, String)> {
+    let copied = std::io::copy(&mut body.take(len), out).map_err(internal)?;
+    if copied != len {
+        return Err((
+            "400 Bad Request",
+            format!("Body ended after {copied} of {len} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+/// Result of uploading one file
+enum PutOutcome {
+    Stored,
+    /// The server's copy is not the size the append expected
+    SizeMismatch,
+}
+
+/// Messages ingested from a JSONL upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationsReceipt {
+    pub conversations: usize,
+    pub new_messages: usize,
+}
+
+/// HTTP client for a `cass serve-ingest` server
+pub struct PushClient {
+    http: Client,
+    base: Url,
+    token: String,
+}
+
+impl PushClient {
+    pub fn new(url: &str, token: &str) -> Result<Self> {
+        let mut base = Url::parse(url).with_context(|| format!("Invalid server URL: {url}"))?;
+        if !matches!(base.scheme(), "http" | "https") {
+            bail!("Server URL must be http(s): {url}");
+        }
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        let http = Client::builder()
+            .user_agent(concat!("cass/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(300))
+            .build()?;
+        Ok(Self {
+            http,
+            base,
+            token: token.trim().to_string(),
+        })
+    }
+
+    /// Server URL, used to key [`PushState`]
+    pub fn base_url(&self) -> &str {
+        self.base.as_str()
+    }
+
+    /// Name of the push source the token belongs to
+    pub fn status(&self) -> Result<String> {
+        let response = self
+            .http
+            .get(self.base.join("v1/status")?)
+            .bearer_auth(&self.token)
+            .send()?;
+        let json = Self::json(response)?;
+        json["source_id"]
+            .as_str()
+            .map(str::to_string)
+            .context("Server did not name the push source")
+    }
+
+    /// Upload NormalizedConversation JSONL
+    pub fn push_conversations(&self, jsonl: Vec<u8>) -> Result<ConversationsReceipt> {
+        let response = self
+            .http
+            .post(self.base.join("v1/conversations")?)
+            .bearer_auth(&self.token)
+            .header("Content-Type", "application/x-ndjson")
+            .body(jsonl)
+            .send()?;
+        Ok(serde_json::from_value(Self::json(response)?)?)
+    }
+
+    fn put_file(
+        &self,
+        root: &str,
+        rel: &str,
+        offset: Option<u64>,
+        body: Vec<u8>,
+    ) -> Result<PutOutcome> {
+        let mut url = self.base.join("v1/files/")?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Server URL cannot take a path"))?
+            .pop_if_empty()
+            .push(root)
+            .extend(rel.split('/'));
+        if let Some(offset) = offset {
+            url.set_query(Some(&format!("offset={offset}")));
+        }
+        let response = self
+            .http
+            .put(url)
+            .bearer_auth(&self.token)
+            .body(body)
+            .send()?;
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Ok(PutOutcome::SizeMismatch);
+        }
+        Self::json(response)?;
+        Ok(PutOutcome::Stored)
+    }
+
+    /// The JSON body of a successful response, or the server's error
+    fn json(response: reqwest::blocking::Response) -> Result<serde_json::Value> {
+        let status = response.status();
+        let json: serde_json::Value = response.json().unwrap_or_default();
+        if !status.is_success() {
+            let error = json["error"].as_str().unwrap_or("no details");
+            bail!("Server returned {status}: {error}");
+        }
+        Ok(json)
+    }
+}
+
+/// What was last pushed of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PushedFile {
+    size: u64,
+    modified: i64,
+    sha256: String,
+}
+
+/// Files pushed to each server, keyed by server URL then `<root>/<path>`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PushState {
+    servers: BTreeMap<String, BTreeMap<String, PushedFile>>,
+}
+
+impl PushState {
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        match fs::read_to_string(data_dir.join(STATE_FILE)) {
+            Ok(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        fs::create_dir_all(data_dir)?;
+        let path = data_dir.join(STATE_FILE);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// Outcome of `cass push` for session files
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PushReport {
+    /// Roots that existed and were walked
+    pub roots: Vec<String>,
+    /// Files uploaded in full
+    pub uploaded: usize,
+    /// Files that only had new data appended
+    pub appended: usize,
+    /// Files the server already has
+    pub unchanged: usize,
+    /// Bytes sent
+    pub bytes: u64,
+    /// Files that failed, with their errors
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Session roots pushed when none are given: this platform's preset paths
+/// that exist
+pub fn default_push_roots() -> Vec<String> {
+    let preset = if cfg!(target_os = "macos") {
+        "macos-defaults"
+    } else {
+        "linux-defaults"
+    };
+    super::config::get_preset_paths(preset)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|root| Path::new(&expand_tilde_local(root)).exists())
+        .collect()
+}
+
+/// Push new and changed files under `roots` (files or directories)
+///
+/// Each root is uploaded as `<safe dirname>/<relative path>`, the layout SSH
+/// mirrors use, so connectors find pushed sessions the same way.
+pub fn push_roots(client: &PushClient, roots: &[String], state: &mut PushState) -> PushReport {
+    let pushed = state
+        .servers
+        .entry(client.base_url().to_string())
+        .or_default();
+    let mut report = PushReport::default();
+
+    for root in roots {
+        let local = PathBuf::from(expand_tilde_local(root));
+        if !local.exists() {
+            continue;
+        }
+        report.roots.push(root.clone());
+        let root_name = path_to_safe_dirname(root);
+        let base = if local.is_file() {
+            local.parent().map(Path::to_path_buf).unwrap_or_default()
+        } else {
+            local.clone()
+        };
+
+        for entry in walkdir::WalkDir::new(&local)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let Some(rel) = entry.path().strip_prefix(&base).ok().and_then(rel_to_slash) else {
+                continue;
+            };
+            let key = format!("{root_name}/{rel}");
+            match push_file(client, &root_name, &rel, entry.path(), pushed.get(&key)) {
+                Ok((outcome, sent, record)) => {
+                    match outcome {
+                        FileOutcome::Unchanged => report.unchanged += 1,
+                        FileOutcome::Uploaded => report.uploaded += 1,
+                        FileOutcome::Appended => report.appended += 1,
+                    }
+                    report.bytes += sent;
+                    pushed.insert(key, record);
+                }
+                Err(e) => report.errors.push(format!("{key}: {e:#}")),
+            }
+        }
+    }
+    report
+}
+
+enum FileOutcome {
+    Unchanged,
+    Uploaded,
+    Appended,
+}
+
+/// Upload one file if it changed since `previous`
+fn push_file(
+    client: &PushClient,
+    root: &str,
+    rel: &str,
+    path: &Path,
+    previous: Option<&PushedFile>,
+) -> Result<(FileOutcome, u64, PushedFile)> {
+    let meta = fs::metadata(path)?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default();
+    if let Some(prev) = previous
+        && prev.size == meta.len()
+        && prev.modified == modified
+    {
+        return Ok((FileOutcome::Unchanged, 0, prev.clone()));
+    }
+
+    let bytes = fs::read(path)?;
+    let record = PushedFile {
+        size: bytes.len() as u64,
+        modified,
+        sha256: hex::encode(Sha256::digest(&bytes)),
+    };
+    if let Some(prev) = previous {
+        if prev.sha256 == record.sha256 {
+            return Ok((FileOutcome::Unchanged, 0, record));
+        }
+        // Session logs mostly grow; send the tail when the rest is unchanged
+        let start = prev.size as usize;
+        if start > 0
+            && start < bytes.len()
+            && hex::encode(Sha256::digest(&bytes[..start])) == prev.sha256
+        {
+            let tail = bytes[start..].to_vec();
+            let sent = tail.len() as u64;
+            if let PutOutcome::Stored = client.put_file(root, rel, Some(prev.size), tail)? {
+                return Ok((FileOutcome::Appended, sent, record));
+            }
+        }
+    }
+
+    let sent = bytes.len() as u64;
+    client.put_file(root, rel, None, bytes)?;
+    Ok((FileOutcome::Uploaded, sent, record))
+}
+
+/// A relative path with `/` separators, `None` if it is not UTF-8
+fn rel_to_slash(rel: &Path) -> Option<String> {
+    let parts: Option<Vec<&str>> = rel.components().map(|c| c.as_os_str().to_str()).collect();
+    let parts = parts?;
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_random_and_stored_hashed() {
+        let token = new_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, new_token());
+        assert_eq!(token_sha256(&token), token_sha256(&format!("{token}\n")));
+        assert_eq!(
+            token_sha256("test"),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+    }
+
+    #[test]
+    fn issuing_a_token_creates_or_rotates_a_push_source() {
+        let mut config = SourcesConfig::default();
+        let first = issue_token(&mut config, "ci").unwrap();
+        assert_eq!(
+            config.find_push_source(&token_sha256(&first)).unwrap().name,
+            "ci"
+        );
+        let second = issue_token(&mut config, "ci").unwrap();
+        assert!(config.find_push_source(&token_sha256(&first)).is_none());
+        assert!(config.find_push_source(&token_sha256(&second)).is_some());
+        assert_eq!(config.sources.len(), 1);
+
+        config
+            .add_source(SourceDefinition::ssh("laptop", "user@laptop"))
+            .unwrap();
+        assert!(issue_token(&mut config, "laptop").is_err());
+    }
+
+    #[test]
+    fn upload_paths_must_stay_inside_a_root() {
+        let base = Path::new("/data/files");
+        assert_eq!(
+            resolve_upload_path(base, ".codex_sessions/2024/rollout.jsonl"),
+            Some(base.join(".codex_sessions/2024/rollout.jsonl"))
+        );
+        assert_eq!(resolve_upload_path(base, "rollout.jsonl"), None);
+        assert_eq!(resolve_upload_path(base, "root/../../etc/passwd"), None);
+        assert_eq!(resolve_upload_path(base, "/etc/passwd"), None);
+        assert_eq!(resolve_upload_path(base, "root//file"), None);
+        assert_eq!(resolve_upload_path(base, "root/./file"), None);
+        assert_eq!(resolve_upload_path(base, "root/a\\..\\b"), None);
+    }
+
+    #[test]
+    fn connections_beyond_the_cap_get_503() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = IngestServer::bind(temp.path(), &temp.path().join("db"), "127.0.0.1:0")
+            .unwrap()
+            .max_connections(1);
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || server.run(|_| {}));
+
+        // Holds the only slot: the server waits for its request head
+        let _idle = TcpStream::connect(addr).unwrap();
+        let mut second = TcpStream::connect(addr).unwrap();
+        second
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let mut status = String::new();
+        std::io::BufRead::read_line(&mut BufReader::new(&mut second), &mut status).unwrap();
+        assert_eq!(status.trim_end(), "HTTP/1.1 503 Service Unavailable");
+    }
+
+    /// An ingest over `dir` that stores files of up to `max_file_bytes`
+    fn test_ingest(dir: &Path, max_file_bytes: u64) -> Ingest {
+        let (tx, _rx) = mpsc::channel();
+        Ingest {
+            data_dir: dir.to_path_buf(),
+            db_path: dir.join("db"),
+            engine: SyncEngine::new(dir),
+            index_lock: Arc::default(),
+            uploads: Mutex::new(tx),
+            file_locks: Mutex::default(),
+            max_file_bytes,
+        }
+    }
+
+    /// PUT `body` (claiming `len` bytes) to `root/s.jsonl` of source `ci`
+    fn put(
+        ingest: &Ingest,
+        query: &str,
+        mut body: impl Read,
+        len: usize,
+    ) -> Result<(), &'static str> {
+        let head = RequestHead {
+            method: "PUT".into(),
+            target: String::new(),
+            headers: vec![("Content-Length".into(), len.to_string())],
+        };
+        ingest
+            .store_file("ci", "root/s.jsonl", query, &head, &mut body)
+            .map(|_| ())
+            .map_err(|(status, _)| status)
+    }
+
+    #[test]
+    fn appends_cannot_grow_a_file_past_the_cap() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let ingest = test_ingest(temp.path(), 8);
+
+        assert_eq!(put(&ingest, "", &b"123456"[..], 6), Ok(()));
+        assert_eq!(put(&ingest, "offset=6", &b"78"[..], 2), Ok(()));
+        assert_eq!(
+            put(&ingest, "offset=8", &b"9"[..], 1),
+            Err("413 Payload Too Large")
+        );
+        assert_eq!(
+            put(&ingest, "", &b"123456789"[..], 9),
+            Err("413 Payload Too Large")
+        );
+        let stored = ingest.engine.push_files_dir("ci").join("root/s.jsonl");
+        assert_eq!(fs::read(stored).unwrap(), b"12345678");
+    }
+
+    #[test]
+    fn concurrent_appends_at_one_offset_apply_once() {
+        /// A body that stalls before its bytes, so both requests overlap
+        struct Slow(&'static [u8]);
+        impl Read for Slow {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                std::thread::sleep(Duration::from_millis(100));
+                self.0.read(buf)
+            }
+        }
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let ingest = test_ingest(temp.path(), MAX_FILE_BYTES);
+        put(&ingest, "", &b"ab"[..], 2).unwrap();
+
+        let results: Vec<_> = std::thread::scope(|s| {
+            let first = s.spawn(|| put(&ingest, "offset=2", Slow(b"cd"), 2));
+            let second = s.spawn(|| put(&ingest, "offset=2", Slow(b"ef"), 2));
+            vec![first.join().unwrap(), second.join().unwrap()]
+        });
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results.contains(&Err("409 Conflict")));
+        let stored = ingest.engine.push_files_dir("ci").join("root/s.jsonl");
+        assert_eq!(fs::read(stored).unwrap().len(), 4);
+
+        // A body cut short leaves the file as it was
+        assert_eq!(
+            put(&ingest, "offset=4", &b"g"[..], 3),
+            Err("400 Bad Request")
+        );
+        assert_eq!(put(&ingest, "offset=4", &b"gh"[..], 2), Ok(()));
+    }
+}
@@ -0,0 +1,619 @@
+//! Path mapping suggestions for `cass sources mappings suggest`.
+//!
+//! Workspaces seen on a remote are paired with local workspaces that share
+//! a git remote (read from `.git/config`) or, failing that, a directory name.
+//! Each pair is reduced to the prefixes that differ, so
+//! `/home/alice/src/api` and `/Users/alice/code/api` propose
+//! `/home/alice/src` -> `/Users/alice/code`; pairs agreeing on the same
+//! prefixes back one suggestion and raise its confidence.
+//!
+//! Accepted mappings only apply to newly indexed sessions, so
+//! [`rewrite_indexed_workspaces`] re-applies a source's mappings to the
+//! conversations already stored.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+
+use crate::connectors::NormalizedConversation;
+use crate::indexer::apply_workspace_rewrite;
+use crate::storage::sqlite::{ConversationWorkspace, SqliteStorage};
+
+use super::config::{PathMapping, SourceDefinition};
+use super::provenance::LOCAL_SOURCE_ID;
+
+/// Confidence of a pair sharing a git remote.
+const GIT_REMOTE_CONFIDENCE: f64 = 0.95;
+
+/// Confidence of a pair only sharing a directory name.
+const BASENAME_CONFIDENCE: f64 = 0.6;
+
+/// Upper bound for a suggestion backed by several pairs.
+const MAX_CONFIDENCE: f64 = 0.99;
+
+/// Marker separating workspaces in the remote `.git/config` dump.
+const WORKSPACE_MARKER: &str = "===CASS_WORKSPACE ";
+
+/// A workspace path with the normalized URL of its git remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceInfo {
+    pub path: String,
+    pub git_remote: Option<String>,
+}
+
+impl WorkspaceInfo {
+    pub fn new(path: impl Into<String>, git_remote: Option<&str>) -> Self {
+        Self {
+            path: path.into(),
+            git_remote: git_remote.map(normalize_git_url),
+        }
+    }
+}
+
+/// Why a remote workspace was paired with a local one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchReason {
+    GitRemote,
+    Basename,
+}
+
+/// A remote workspace paired with a local one.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceMatch {
+    pub remote: String,
+    pub local: String,
+    pub reason: MatchReason,
+    pub confidence: f64,
+}
+
+/// A proposed path mapping and the workspace pairs backing it.
+#[derive(Debug, Clone, Serialize)]
+pub struct MappingSuggestion {
+    pub from: String,
+    pub to: String,
+    /// 0-1; pairs backing the same mapping combine as independent evidence
+    pub confidence: f64,
+    pub matches: Vec<WorkspaceMatch>,
+}
+
+impl MappingSuggestion {
+    pub fn to_mapping(&self) -> PathMapping {
+        PathMapping::new(&self.from, &self.to)
+    }
+}
+
+/// Suggest mappings from remote workspaces to local ones, best first.
+///
+/// Each `from` prefix is suggested once; pairs that disagree with the
+/// winning suggestion for their prefix get an exact workspace mapping.
+pub fn suggest_mappings(
+    remote: &[WorkspaceInfo],
+    local: &[WorkspaceInfo],
+) -> Vec<MappingSuggestion> {
+    let mut groups: BTreeMap<(String, String), Vec<WorkspaceMatch>> = BTreeMap::new();
+    for pair in match_workspaces(remote, local) {
+        let (from, to) = differing_prefixes(&pair.remote, &pair.local);
+        groups
+            .entry((from.to_string(), to.to_string()))
+            .or_default()
+            .push(pair);
+    }
+
+    let mut grouped: Vec<MappingSuggestion> = groups
+        .into_iter()
+        .map(|((from, to), matches)| MappingSuggestion {
+            confidence: combined_confidence(&matches),
+            from,
+            to,
+            matches,
+        })
+        .collect();
+    sort_suggestions(&mut grouped);
+
+    let mut taken = HashSet::new();
+    let mut suggestions = Vec::new();
+    let mut leftovers = Vec::new();
+    for suggestion in grouped {
+        if taken.insert(suggestion.from.clone()) {
+            suggestions.push(suggestion);
+        } else {
+            leftovers.extend(suggestion.matches);
+        }
+    }
+    for pair in leftovers {
+        if taken.insert(pair.remote.clone()) {
+            suggestions.push(MappingSuggestion {
+                from: pair.remote.clone(),
+                to: pair.local.clone(),
+                confidence: pair.confidence,
+                matches: vec![pair],
+            });
+        }
+    }
+
+    for suggestion in &mut suggestions {
+        suggestion.confidence = round2(suggestion.confidence);
+        for pair in &mut suggestion.matches {
+            pair.confidence = round2(pair.confidence);
+        }
+    }
+    sort_suggestions(&mut suggestions);
+    suggestions
+}
+
+/// Pair each remote workspace with the local workspaces it likely is.
+///
+/// A shared git remote wins over a shared directory name; several candidates
+/// split the confidence. Workspaces at the same path on both sides need no
+/// mapping and are skipped.
+pub fn match_workspaces(remote: &[WorkspaceInfo], local: &[WorkspaceInfo]) -> Vec<WorkspaceMatch> {
+    let mut matches = Vec::new();
+    for ws in remote {
+        let name = basename(&ws.path);
+        if name.is_empty() || local.iter().any(|l| l.path == ws.path) {
+            continue;
+        }
+
+        let same_remote: Vec<&WorkspaceInfo> = match &ws.git_remote {
+            Some(url) => local
+                .iter()
+                .filter(|l| l.git_remote.as_ref() == Some(url))
+                .collect(),
+            None => Vec::new(),
+        };
+        let (candidates, reason, confidence) = if same_remote.is_empty() {
+            let same_name: Vec<&WorkspaceInfo> = local
+                .iter()
+                .filter(|l| basename(&l.path) == name)
+                // Different repositories that happen to share a name
+                .filter(|l| match (&ws.git_remote, &l.git_remote) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => true,
+                })
+                .collect();
+            (same_name, MatchReason::Basename, BASENAME_CONFIDENCE)
+        } else {
+            // Several clones of the repository: prefer the one with the same name
+            let same_name: Vec<&WorkspaceInfo> = same_remote
+                .iter()
+                .copied()
+                .filter(|l| basename(&l.path) == name)
+                .collect();
+            let candidates = if same_name.is_empty() {
+                same_remote
+            } else {
+                same_name
+            };
+            (candidates, MatchReason::GitRemote, GIT_REMOTE_CONFIDENCE)
+        };
+
+        let share = confidence / candidates.len().max(1) as f64;
+        matches.extend(candidates.into_iter().map(|l| WorkspaceMatch {
+            remote: ws.path.clone(),
+            local: l.path.clone(),
+            reason,
+            confidence: share,
+        }));
+    }
+    matches
+}
+
+/// Strip the trailing components two paths share, keeping at least two
+/// components of the remote path so a mapping never covers a whole `/home`.
+pub fn differing_prefixes<'a>(remote: &'a str, local: &'a str) -> (&'a str, &'a str) {
+    let (mut remote, mut local) = (trim_separators(remote), trim_separators(local));
+    while let (Some((remote_parent, remote_name)), Some((local_parent, local_name))) =
+        (split_last(remote), split_last(local))
+    {
+        if remote_name != local_name
+            || component_count(remote_parent) < 2
+            || component_count(local_parent) < 1
+        {
+            break;
+        }
+        remote = remote_parent;
+        local = local_parent;
+    }
+    (remote, local)
+}
+
+fn trim_separators(path: &str) -> &str {
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() { path } else { trimmed }
+}
+
+fn split_last(path: &str) -> Option<(&str, &str)> {
+    let cut = path.rfind(['/', '\\'])?;
+    Some((&path[..cut], &path[cut + 1..]))
+}
+
+fn component_count(path: &str) -> usize {
+    path.split(['/', '\\']).filter(|c| !c.is_empty()).count()
+}
+
+fn basename(path: &str) -> &str {
+    let trimmed = trim_separators(path);
+    split_last(trimmed).map_or(trimmed, |(_, name)| name)
+}
+
+/// Independent evidence: 1 - Π(1 - c).
+fn combined_confidence(matches: &[WorkspaceMatch]) -> f64 {
+    let miss: f64 = matches.iter().map(|m| 1.0 - m.confidence).product();
+    (1.0 - miss).min(MAX_CONFIDENCE)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn sort_suggestions(suggestions: &mut [MappingSuggestion]) {
+    suggestions.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(b.matches.len().cmp(&a.matches.len()))
+            .then(a.from.cmp(&b.from))
+    });
+}
+
+/// Normalize a git remote URL so SSH and HTTPS clones compare equal:
+/// `git@github.com:Org/Repo.git` and `https://github.com/org/repo` both
+/// become `github.com/org/repo`.
+pub fn normalize_git_url(url: &str) -> String {
+    let url = url.trim();
+    let without_scheme = match url.split_once("://") {
+        Some((_, rest)) => {
+            // Drop credentials and port from the authority
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host = authority.rsplit('@').next().unwrap_or(authority);
+            let host = host.split(':').next().unwrap_or(host);
+            format!("{host}/{path}")
+        }
+        // scp-like `user@host:path`
+        None => match url.split_once(':') {
+            Some((authority, path)) if !authority.contains('/') => {
+                let host = authority.rsplit('@').next().unwrap_or(authority);
+                format!("{host}/{}", path.trim_start_matches('/'))
+            }
+            _ => url.to_string(),
+        },
+    };
+    without_scheme
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+/// URL of the `origin` remote in a `.git/config`, else of the first remote.
+pub fn git_remote_from_config(config: &str) -> Option<String> {
+    let mut remotes: Vec<(String, String)> = Vec::new();
+    let mut section: Option<String> = None;
+    for line in config.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = header
+                .strip_prefix("remote ")
+                .map(|name| name.trim().trim_matches('"').to_string());
+        } else if let Some(name) = &section
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "url"
+        {
+            remotes.push((name.clone(), value.trim().to_string()));
+        }
+    }
+    remotes
+        .iter()
+        .find(|(name, _)| name == "origin")
+        .or_else(|| remotes.first())
+        .map(|(_, url)| url.clone())
+}
+
+/// Git remote of a local workspace.
+pub fn local_git_remote(path: &Path) -> Option<String> {
+    let config = std::fs::read_to_string(path.join(".git").join("config")).ok()?;
+    git_remote_from_config(&config)
+}
+
+/// Git remotes of workspaces on an SSH host, read in one connection.
+pub fn remote_git_remotes(
+    host: &str,
+    paths: &[String],
+    timeout_secs: u64,
+) -> Result<HashMap<String, String>, String> {
+    if paths.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut script = String::new();
+    for path in paths {
+        let quoted = sh_quote(path);
+        script.push_str(&format!(
+            "printf '%s%s\\n' '{WORKSPACE_MARKER}' {quoted}\ncat {quoted}/.git/config 2>/dev/null\n"
+        ));
+    }
+
+    let mut child = Command::new("ssh")
+        .args([
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            &format!("ConnectTimeout={timeout_secs}"),
+            "-o",
+            "StrictHostKeyChecking=accept-new",
+            "--",
+            host,
+            "sh -s",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute ssh: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(script.as_bytes())
+            .map_err(|e| format!("Failed to send script: {e}"))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("SSH command failed: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("SSH failed: {}", stderr.trim()));
+    }
+    Ok(parse_remote_configs(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Split the remote dump into one `.git/config` per workspace.
+fn parse_remote_configs(output: &str) -> HashMap<String, String> {
+    let mut remotes = HashMap::new();
+    let mut current: Option<(&str, String)> = None;
+    let mut finish = |entry: Option<(&str, String)>| {
+        if let Some((path, config)) = entry
+            && let Some(url) = git_remote_from_config(&config)
+        {
+            remotes.insert(path.to_string(), url);
+        }
+    };
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix(WORKSPACE_MARKER) {
+            finish(current.take());
+            current = Some((path, String::new()));
+        } else if let Some((_, config)) = &mut current {
+            config.push_str(line);
+            config.push('\n');
+        }
+    }
+    finish(current);
+    remotes
+}
+
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\"'\"'"))
+}
+
+/// Original workspace of a conversation whose workspace was rewritten.
+fn original_workspace(metadata: &serde_json::Value) -> Option<&str> {
+    metadata
+        .get("cass")
+        .and_then(|c| c.get("workspace_original"))
+        .and_then(|w| w.as_str())
+}
+
+/// Workspaces of `source`'s indexed conversations that no mapping rewrites,
+/// as they were on the remote.
+pub fn unmapped_remote_workspaces(
+    storage: &SqliteStorage,
+    source: &SourceDefinition,
+) -> anyhow::Result<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for row in storage.list_conversation_workspaces(&source.name)? {
+        let Some(workspace) = &row.workspace else {
+            continue;
+        };
+        let original = original_workspace(&row.metadata)
+            .map(str::to_string)
+            .unwrap_or_else(|| workspace.to_string_lossy().into_owned());
+        let mapped = source.rewrite_path_for_agent(&original, Some(&row.agent_slug)) != original;
+        if !mapped && seen.insert(original.clone()) {
+            out.push(original);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// Workspaces of local conversations that still exist on disk, with their
+/// git remotes.
+pub fn local_workspaces(storage: &SqliteStorage) -> anyhow::Result<Vec<WorkspaceInfo>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for row in storage.list_conversation_workspaces(LOCAL_SOURCE_ID)? {
+        let Some(workspace) = row.workspace else {
+            continue;
+        };
+        if !workspace.is_dir() || !seen.insert(workspace.clone()) {
+            continue;
+        }
+        let remote = local_git_remote(&workspace);
+        out.push(WorkspaceInfo::new(
+            workspace.to_string_lossy(),
+            remote.as_deref(),
+        ));
+    }
+    Ok(out)
+}
+
+/// Re-apply `source`'s path mappings to its indexed conversations, starting
+/// from their original workspace. Returns how many conversations moved.
+pub fn rewrite_indexed_workspaces(
+    storage: &mut SqliteStorage,
+    source: &SourceDefinition,
+) -> anyhow::Result<usize> {
+    let mut updates = Vec::new();
+    for row in storage.list_conversation_workspaces(&source.name)? {
+        let Some(current) = row.workspace else {
+            continue;
+        };
+        let original = original_workspace(&row.metadata)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| current.clone());
+        let mut conv = NormalizedConversation {
+            agent_slug: row.agent_slug.clone(),
+            external_id: None,
+            title: None,
+            workspace: Some(original),
+            source_path: PathBuf::new(),
+            started_at: None,
+            ended_at: None,
+            metadata: row.metadata,
+            messages: Vec::new(),
+        };
+        apply_workspace_rewrite(&mut conv, &source.path_mappings);
+        if conv.workspace.as_ref() != Some(&current) {
+            updates.push(ConversationWorkspace {
+                conversation_id: row.conversation_id,
+                agent_slug: row.agent_slug,
+                workspace: conv.workspace,
+                metadata: conv.metadata,
+            });
+        }
+    }
+    storage.set_conversation_workspaces(&updates)?;
+    Ok(updates.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ws(path: &str, remote: Option<&str>) -> WorkspaceInfo {
+        WorkspaceInfo::new(path, remote)
+    }
+
+    #[test]
+    fn git_urls_normalize_across_protocols() {
+        let expected = "github.com/acme/api";
+        assert_eq!(normalize_git_url("git@github.com:Acme/api.git"), expected);
+        assert_eq!(normalize_git_url("https://github.com/acme/api"), expected);
+        assert_eq!(
+            normalize_git_url("ssh://git@github.com:22/acme/api.git/"),
+            expected
+        );
+        assert_eq!(
+            normalize_git_url("https://token@github.com/acme/api.git"),
+            expected
+        );
+    }
+
+    #[test]
+    fn git_config_prefers_origin() {
+        let config = r#"[core]
+	bare = false
+[remote "upstream"]
+	url = git@github.com:upstream/api.git
+[remote "origin"]
+	url = git@github.com:alice/api.git
+	fetch = +refs/heads/*:refs/remotes/origin/*
+"#;
+        assert_eq!(
+            git_remote_from_config(config).as_deref(),
+            Some("git@github.com:alice/api.git")
+        );
+        assert_eq!(git_remote_from_config("[core]\n\tbare = false\n"), None);
+    }
+
+    #[test]
+    fn remote_config_dump_is_split_per_workspace() {
+        let output = format!(
+            "{WORKSPACE_MARKER}/home/alice/src/api\n[remote \"origin\"]\n\turl = git@github.com:acme/api.git\n{WORKSPACE_MARKER}/home/alice/scratch\n"
+        );
+        let remotes = parse_remote_configs(&output);
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(
+            remotes["/home/alice/src/api"],
+            "git@github.com:acme/api.git"
+        );
+    }
+
+    #[test]
+    fn differing_prefixes_keep_a_meaningful_root() {
+        assert_eq!(
+            differing_prefixes("/home/alice/src/api", "/Users/alice/code/api"),
+            ("/home/alice/src", "/Users/alice/code")
+        );
+        // Never generalize to `/home` -> `/Users`
+        assert_eq!(
+            differing_prefixes("/home/alice/api", "/Users/alice/api"),
+            ("/home/alice", "/Users/alice")
+        );
+        assert_eq!(
+            differing_prefixes("/srv/api/", "C:\\work\\api"),
+            ("/srv/api", "C:\\work\\api")
+        );
+    }
+
+    #[test]
+    fn shared_prefixes_combine_into_one_confident_suggestion() {
+        let remote = [
+            ws("/home/alice/src/api", Some("git@github.com:acme/api.git")),
+            ws("/home/alice/src/web", None),
+            ws("/home/alice/src/unmatched", None),
+        ];
+        let local = [
+            ws("/Users/alice/code/api", Some("https://github.com/acme/api")),
+            ws("/Users/alice/code/web", None),
+        ];
+        let suggestions = suggest_mappings(&remote, &local);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].from, "/home/alice/src");
+        assert_eq!(suggestions[0].to, "/Users/alice/code");
+        assert_eq!(suggestions[0].matches.len(), 2);
+        assert_eq!(suggestions[0].confidence, 0.98);
+    }
+
+    #[test]
+    fn git_remotes_beat_names_and_conflicts_are_rejected() {
+        let remote = [ws("/home/bob/api", Some("git@github.com:acme/api.git"))];
+        let local = [
+            ws("/Users/bob/forks/api", Some("git@github.com:bob/api.git")),
+            ws(
+                "/Users/bob/work/service",
+                Some("git@github.com:acme/api.git"),
+            ),
+        ];
+        let matches = match_workspaces(&remote, &local);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].local, "/Users/bob/work/service");
+        assert_eq!(matches[0].reason, MatchReason::GitRemote);
+
+        // Same name, different repository: no match at all
+        let local = [ws("/Users/bob/api", Some("git@github.com:other/api.git"))];
+        assert!(match_workspaces(&remote, &local).is_empty());
+    }
+
+    #[test]
+    fn conflicting_prefixes_fall_back_to_exact_mappings() {
+        let remote = [
+            ws("/home/carol/src/api", None),
+            ws("/home/carol/src/web", None),
+            ws("/home/carol/src/cli", None),
+        ];
+        let local = [
+            ws("/Users/carol/code/api", None),
+            ws("/Users/carol/code/web", None),
+            ws("/Users/carol/old/cli", None),
+        ];
+        let suggestions = suggest_mappings(&remote, &local);
+        assert_eq!(suggestions[0].from, "/home/carol/src");
+        assert_eq!(suggestions[0].to, "/Users/carol/code");
+        assert_eq!(suggestions[1].from, "/home/carol/src/cli");
+        assert_eq!(suggestions[1].to, "/Users/carol/old/cli");
+        assert_eq!(suggestions[1].confidence, 0.6);
+    }
+}
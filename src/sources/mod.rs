@@ -9,6 +9,12 @@
 //! - **config**: Configuration types for defining remote sources
 //! - **provenance**: Types for tracking conversation origins
 //! - **sync**: Sync engine for pulling sessions from remotes via rsync/SSH
+//! - **filter**: Per-source include/exclude globs, agents and size/age limits
+//! - **daemon**: Background syncing of sources on their `sync_schedule`
+//! - **doctor**: Health checks, grades and safe repairs for `cass sources doctor`
+//! - **federated**: Live search on remote cass installs over SSH, without syncing
+//! - **ingest**: `cass serve-ingest` and `cass push` for machines that upload their sessions
+//! - **mappings**: Path mapping suggestions from remote and local workspace overlap
 //! - **status** (future): Sync status tracking
 //!
 //! # Configuration
@@ -69,9 +75,15 @@
 //! ```
 
 pub mod config;
+pub mod daemon;
+pub mod doctor;
+pub mod federated;
+pub mod filter;
 pub mod index;
+pub mod ingest;
 pub mod install;
 pub mod interactive;
+pub mod mappings;
 pub mod probe;
 pub mod provenance;
 pub mod setup;
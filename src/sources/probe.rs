@@ -507,7 +507,7 @@ fn parse_probe_output(host_name: &str, output: &str, connection_time_ms: u64) ->
 ///
 /// Note: More specific patterns must be checked first (e.g., `saoudrizwan.claude-dev`
 /// contains `claude` so Cline must be checked before Claude Code).
-fn infer_agent_type(path: &str) -> String {
+pub(crate) fn infer_agent_type(path: &str) -> String {
     // Check Cline first - it contains "claude-dev" which could match ".claude"
     if path.contains("saoudrizwan.claude-dev") || path.contains("rooveterinaryinc.roo-cline") {
         "cline".to_string()
@@ -39,10 +39,16 @@ pub enum SourceKind {
     Local,
     /// Remote machine via SSH.
     Ssh,
+    /// Published pages archive imported with `cass pages import`.
+    Archive,
+    /// Git repository cloned into the mirror directory.
+    Git,
+    /// HTTP file server listing its files in an index.
+    Http,
+    /// Machine uploading its sessions with `cass push`.
+    Push,
     // Future extensions:
     // S3,
-    // Git,
-    // Http,
 }
 
 impl SourceKind {
@@ -56,6 +62,10 @@ impl SourceKind {
         match self {
             Self::Local => "local",
             Self::Ssh => "ssh",
+            Self::Archive => "archive",
+            Self::Git => "git",
+            Self::Http => "http",
+            Self::Push => "push",
         }
     }
 
@@ -64,6 +74,10 @@ impl SourceKind {
         match s.to_lowercase().as_str() {
             "local" => Some(Self::Local),
             "ssh" => Some(Self::Ssh),
+            "archive" => Some(Self::Archive),
+            "git" => Some(Self::Git),
+            "http" => Some(Self::Http),
+            "push" => Some(Self::Push),
             _ => None,
         }
     }
@@ -106,6 +120,9 @@ pub struct Source {
     /// Platform hint (macos, linux, windows).
     pub platform: Option<String>,
 
+    /// Who the source's sessions belong to.
+    pub owner: Option<String>,
+
     /// Extra configuration as JSON (SSH params, path rewrites, etc.).
     pub config_json: Option<serde_json::Value>,
 
@@ -125,6 +142,7 @@ impl Source {
             host_label: None,
             machine_id: None,
             platform: None,
+            owner: None,
             config_json: None,
             created_at: None,
             updated_at: None,
@@ -139,6 +157,7 @@ impl Source {
             host_label: Some(host_label.into()),
             machine_id: None,
             platform: None,
+            owner: None,
             config_json: None,
             created_at: None,
             updated_at: None,
@@ -198,6 +217,10 @@ pub struct Origin {
     /// Display host label (may differ from source's host_label).
     /// Useful when the same source has multiple display representations.
     pub host: Option<String>,
+
+    /// Who the conversation belongs to (denormalized from the source).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
 }
 
 impl Origin {
@@ -207,6 +230,7 @@ impl Origin {
             source_id: LOCAL_SOURCE_ID.to_string(),
             kind: SourceKind::Local,
             host: None,
+            owner: None,
         }
     }
 
@@ -217,6 +241,7 @@ impl Origin {
             source_id: id.clone(),
             kind: SourceKind::Ssh,
             host: Some(id),
+            owner: None,
         }
     }
 
@@ -226,9 +251,16 @@ impl Origin {
             source_id: source_id.into(),
             kind: SourceKind::Ssh,
             host: Some(host.into()),
+            owner: None,
         }
     }
 
+    /// Attribute the conversation to `owner`.
+    pub fn with_owner(mut self, owner: Option<String>) -> Self {
+        self.owner = owner;
+        self
+    }
+
     /// Check if this origin is from a remote source.
     pub fn is_remote(&self) -> bool {
         self.kind.is_remote()
@@ -248,6 +280,11 @@ impl Origin {
             (Some(host), SourceKind::Local) => host.clone(),
             (None, SourceKind::Local) => "local".to_string(),
             (None, SourceKind::Ssh) => format!("{} (remote)", self.source_id),
+            // The host of an archive is its URL or directory; the id reads better
+            (_, SourceKind::Archive) => format!("{} (archive)", self.source_id),
+            (_, SourceKind::Git) => format!("{} (git)", self.source_id),
+            (_, SourceKind::Http) => format!("{} (http)", self.source_id),
+            (_, SourceKind::Push) => format!("{} (push)", self.source_id),
         }
     }
 
@@ -349,6 +386,10 @@ mod tests {
     fn test_source_kind_is_remote() {
         assert!(!SourceKind::Local.is_remote());
         assert!(SourceKind::Ssh.is_remote());
+        assert!(SourceKind::Archive.is_remote());
+        assert!(SourceKind::Git.is_remote());
+        assert!(SourceKind::Http.is_remote());
+        assert!(SourceKind::Push.is_remote());
     }
 
     #[test]
@@ -363,6 +404,10 @@ mod tests {
         assert_eq!(SourceKind::parse("LOCAL"), Some(SourceKind::Local));
         assert_eq!(SourceKind::parse("ssh"), Some(SourceKind::Ssh));
         assert_eq!(SourceKind::parse("SSH"), Some(SourceKind::Ssh));
+        assert_eq!(SourceKind::parse("archive"), Some(SourceKind::Archive));
+        assert_eq!(SourceKind::parse("git"), Some(SourceKind::Git));
+        assert_eq!(SourceKind::parse("HTTP"), Some(SourceKind::Http));
+        assert_eq!(SourceKind::parse("push"), Some(SourceKind::Push));
         assert_eq!(SourceKind::parse("unknown"), None);
     }
 
@@ -2,6 +2,17 @@
 //!
 //! This module provides the core sync functionality using rsync over SSH
 //! for efficient delta transfers, with progress reporting and error recovery.
+//! Git sources are cloned (then fetched) into the mirror directory, and HTTP
+//! sources download the files listed in an index, revalidating each one with
+//! its ETag / Last-Modified on later syncs.
+//!
+//! [`SyncEngine::sync_sources`] syncs several sources at once (see
+//! [`SyncEngine::with_parallelism`]) and reports per-source progress. A
+//! source's `bwlimit` is passed to rsync, and enforced by the SFTP fallback,
+//! which also resumes interrupted downloads from their partial file and skips
+//! files whose size and mtime already match. Both honour the source's
+//! [`filter`](super::filter) rules: paths of unwanted agents are skipped,
+//! rsync gets filter rules, and SFTP checks every file before downloading.
 //!
 //! # Safety
 //!
@@ -24,17 +35,32 @@
 //! }
 //! ```
 
-use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 
+use serde::Serialize;
 use thiserror::Error;
 
 use super::config::{SourceDefinition, discover_ssh_hosts};
+use super::filter::FileFilter;
+use super::provenance::SourceKind;
 use ssh2::{Session, Sftp};
-use std::io::{Read as IoRead, Write as IoWrite};
+use std::io::{Read as IoRead, Seek, SeekFrom, Write as IoWrite};
 use std::net::TcpStream;
 
+/// Number of sources synced at once unless configured otherwise.
+pub const DEFAULT_SYNC_PARALLELISM: usize = 4;
+
+/// SFTP connections tried per path when transfers keep making progress.
+const SFTP_ATTEMPTS: u32 = 3;
+
+/// Suffix of an SFTP download in progress, kept to resume from.
+const PARTIAL_SUFFIX: &str = ".cass-partial";
+
 /// Errors that can occur during sync operations.
 #[derive(Error, Debug)]
 pub enum SyncError {
@@ -44,6 +70,9 @@ pub enum SyncError {
     #[error("Source has no paths configured")]
     NoPaths,
 
+    #[error("Source has no url configured")]
+    NoUrl,
+
     #[error("rsync command failed: {0}")]
     RsyncFailed(String),
 
@@ -58,6 +87,9 @@ pub enum SyncError {
 
     #[error("Sync cancelled")]
     Cancelled,
+
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
 }
 
 /// Method used for syncing files from remote.
@@ -67,6 +99,10 @@ pub enum SyncMethod {
     Rsync,
     /// SFTP fallback when rsync is unavailable
     Sftp,
+    /// git clone/fetch of a repository
+    Git,
+    /// HTTP download of the files listed in an index
+    Http,
 }
 
 impl std::fmt::Display for SyncMethod {
@@ -74,6 +110,8 @@ impl std::fmt::Display for SyncMethod {
         match self {
             Self::Rsync => write!(f, "rsync"),
             Self::Sftp => write!(f, "sftp"),
+            Self::Git => write!(f, "git"),
+            Self::Http => write!(f, "http"),
         }
     }
 }
@@ -168,11 +206,76 @@ impl SyncReport {
     }
 }
 
+/// Progress of [`SyncEngine::sync_sources`], reported as each source runs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SyncProgress {
+    /// A source started syncing.
+    SourceStarted { source: String },
+    /// One path of a source finished.
+    PathFinished {
+        source: String,
+        path: String,
+        success: bool,
+        files: u64,
+        bytes: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A source finished, or failed before syncing any path.
+    SourceFinished {
+        source: String,
+        success: bool,
+        files: u64,
+        bytes: u64,
+        duration_ms: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// Paces transfers to a bandwidth limit.
+#[derive(Debug)]
+struct Throttle {
+    /// Bytes per second, `None` for no limit.
+    rate: Option<u64>,
+    start: Instant,
+    bytes: u64,
+}
+
+impl Throttle {
+    /// A throttle for a limit in KiB/s.
+    fn new(bwlimit_kib: Option<u32>) -> Self {
+        Self {
+            rate: bwlimit_kib.map(|kib| u64::from(kib.max(1)) * 1024),
+            start: Instant::now(),
+            bytes: 0,
+        }
+    }
+
+    /// Record `bytes` sent and sleep until the average rate is back under the limit.
+    fn consume(&mut self, bytes: u64) {
+        self.bytes += bytes;
+        let delay = self.delay(self.start.elapsed());
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+
+    /// How far ahead of the limit the transfer is after `elapsed`.
+    fn delay(&self, elapsed: Duration) -> Duration {
+        let Some(rate) = self.rate else {
+            return Duration::ZERO;
+        };
+        Duration::from_secs_f64(self.bytes as f64 / rate as f64).saturating_sub(elapsed)
+    }
+}
+
 /// Statistics parsed from rsync output.
 #[derive(Debug, Default)]
-struct RsyncStats {
-    files_transferred: u64,
-    bytes_transferred: u64,
+pub(crate) struct RsyncStats {
+    pub(crate) files_transferred: u64,
+    pub(crate) bytes_transferred: u64,
 }
 
 /// Sync engine for pulling sessions from remote sources.
@@ -184,6 +287,8 @@ pub struct SyncEngine {
     connection_timeout: u64,
     /// Transfer timeout in seconds (0 = no timeout).
     transfer_timeout: u64,
+    /// Sources synced at once by `sync_sources`.
+    parallelism: usize,
 }
 
 impl SyncEngine {
@@ -196,6 +301,7 @@ impl SyncEngine {
             local_store: data_dir.to_path_buf(),
             connection_timeout: 10,
             transfer_timeout: 300, // 5 minutes
+            parallelism: DEFAULT_SYNC_PARALLELISM,
         }
     }
 
@@ -211,6 +317,12 @@ impl SyncEngine {
         self
     }
 
+    /// Set how many sources `sync_sources` syncs at once (at least 1).
+    pub fn with_parallelism(mut self, sources: usize) -> Self {
+        self.parallelism = sources.max(1);
+        self
+    }
+
     /// Get the local mirror directory for a source.
     pub fn mirror_dir(&self, source_name: &str) -> PathBuf {
         self.local_store
@@ -219,6 +331,72 @@ impl SyncEngine {
             .join("mirror")
     }
 
+    /// Get the working checkout of a git source.
+    pub fn git_checkout_dir(&self, source_name: &str) -> PathBuf {
+        self.mirror_dir(source_name).join("git")
+    }
+
+    /// Get the download directory of an HTTP source.
+    pub fn http_mirror_dir(&self, source_name: &str) -> PathBuf {
+        self.mirror_dir(source_name).join("http")
+    }
+
+    /// Get the directory holding the session files uploaded to a push source,
+    /// one subdirectory per pushed root.
+    pub fn push_files_dir(&self, source_name: &str) -> PathBuf {
+        self.mirror_dir(source_name).join("files")
+    }
+
+    /// Get the directory keeping the conversation JSONL uploaded to a push source.
+    pub fn push_conversations_dir(&self, source_name: &str) -> PathBuf {
+        self.mirror_dir(source_name).join("conversations")
+    }
+
+    /// Get the local directories holding a source's synced sessions.
+    ///
+    /// These are the roots the indexer scans for the source.
+    pub fn scan_dirs(&self, source: &SourceDefinition) -> Vec<PathBuf> {
+        match source.source_type {
+            SourceKind::Git => {
+                let checkout = self.git_checkout_dir(&source.name);
+                if source.paths.is_empty() {
+                    vec![checkout]
+                } else {
+                    source.paths.iter().map(|p| checkout.join(p)).collect()
+                }
+            }
+            SourceKind::Http => vec![self.http_mirror_dir(&source.name)],
+            SourceKind::Push => {
+                let mut dirs: Vec<PathBuf> = std::fs::read_dir(self.push_files_dir(&source.name))
+                    .map(|entries| {
+                        entries
+                            .filter_map(|e| e.ok())
+                            .map(|e| e.path())
+                            .filter(|p| p.is_dir())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                dirs.sort();
+                dirs
+            }
+            _ => source
+                .paths
+                .iter()
+                .map(|path| {
+                    let expanded_path = if path.starts_with("~/") {
+                        path.to_string()
+                    } else if path.starts_with('~') {
+                        path.replacen('~', "~/", 1)
+                    } else {
+                        path.to_string()
+                    };
+                    self.mirror_dir(&source.name)
+                        .join(path_to_safe_dirname(&expanded_path))
+                })
+                .collect(),
+        }
+    }
+
     /// Get the remote home directory by SSH-ing to the host and running `echo $HOME`.
     ///
     /// This is called once per source sync to avoid repeated SSH calls for each path.
@@ -255,6 +433,45 @@ impl SyncEngine {
         Ok(remote_home)
     }
 
+    /// Files under `path` modified within `max_age`, NUL-separated and
+    /// relative to the parent of `path` (for rsync `--files-from`).
+    fn list_recent_files(
+        &self,
+        host: &str,
+        path: &str,
+        max_age: Duration,
+    ) -> Result<Vec<u8>, String> {
+        let minutes = max_age.as_secs().div_ceil(60);
+        let script = format!(
+            "cd {} && find {} -type f -mmin -{minutes} -print0",
+            sh_quote(remote_parent(path)),
+            sh_quote(remote_basename(path)),
+        );
+        let output = Command::new("ssh")
+            .args([
+                "-o",
+                "BatchMode=yes",
+                "-o",
+                &format!("ConnectTimeout={}", self.connection_timeout),
+                "-o",
+                "StrictHostKeyChecking=accept-new",
+                "--",
+                host,
+                &script,
+            ])
+            .output()
+            .map_err(|e| format!("Failed to execute ssh: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(if stderr.contains("No such file or directory") {
+                format!("Remote path not found: {}", path)
+            } else {
+                format!("Failed to list recent files: {}", stderr.trim())
+            });
+        }
+        Ok(output.stdout)
+    }
+
     /// Expand ~ in a remote path using the provided home directory.
     ///
     /// If `remote_home` is None, returns the path unchanged.
@@ -296,15 +513,47 @@ impl SyncEngine {
     /// Syncs all configured paths from the source to the local mirror directory.
     /// Individual path failures don't abort the entire sync.
     pub fn sync_source(&self, source: &SourceDefinition) -> Result<SyncReport, SyncError> {
+        self.sync_source_reporting(source, &|_| {})
+    }
+
+    /// Sync a single source, calling `on_path` as each path finishes.
+    fn sync_source_reporting(
+        &self,
+        source: &SourceDefinition,
+        on_path: &dyn Fn(&PathSyncResult),
+    ) -> Result<SyncReport, SyncError> {
         if !source.is_remote() {
             return Err(SyncError::NoHost);
         }
 
+        let report = match source.source_type {
+            SourceKind::Git => Some(self.sync_git(source)?),
+            SourceKind::Http => Some(self.sync_http(source)?),
+            _ => None,
+        };
+        if let Some(report) = report {
+            report.path_results.iter().for_each(on_path);
+            return Ok(report);
+        }
+
         let host = source.host.as_ref().ok_or(SyncError::NoHost)?;
 
         if source.paths.is_empty() {
             return Err(SyncError::NoPaths);
         }
+        let filter =
+            FileFilter::new(&source.filter).map_err(|e| SyncError::InvalidFilter(e.to_string()))?;
+        let paths: Vec<&String> = source
+            .paths
+            .iter()
+            .filter(|p| {
+                let wanted = filter.allows_source_path(p);
+                if !wanted {
+                    tracing::debug!(source = %source.name, path = %p, "skipping path of unselected agent");
+                }
+                wanted
+            })
+            .collect();
 
         let method = Self::detect_sync_method();
         let mut report = SyncReport::new(&source.name, method);
@@ -315,7 +564,7 @@ impl SyncEngine {
         std::fs::create_dir_all(&mirror_dir)?;
 
         // Pre-fetch remote home directory if any paths use tilde (avoids multiple SSH calls)
-        let remote_home = if source.paths.iter().any(|p| p.starts_with('~')) {
+        let remote_home = if paths.iter().any(|p| p.starts_with('~')) {
             match self.get_remote_home(host) {
                 Ok(home) => Some(home),
                 Err(e) => {
@@ -327,15 +576,27 @@ impl SyncEngine {
             None
         };
 
-        for remote_path in &source.paths {
+        for remote_path in paths {
             let result = match method {
-                SyncMethod::Rsync => {
-                    self.sync_path_rsync(host, remote_path, &mirror_dir, remote_home.as_deref())
-                }
-                SyncMethod::Sftp => {
-                    self.sync_path_sftp(host, remote_path, &mirror_dir, remote_home.as_deref())
-                }
+                SyncMethod::Rsync => self.sync_path_rsync(
+                    host,
+                    remote_path,
+                    &mirror_dir,
+                    remote_home.as_deref(),
+                    source.bwlimit,
+                    &filter,
+                ),
+                // detect_sync_method only picks rsync or SFTP
+                SyncMethod::Sftp | SyncMethod::Git | SyncMethod::Http => self.sync_path_sftp(
+                    host,
+                    remote_path,
+                    &mirror_dir,
+                    remote_home.as_deref(),
+                    source.bwlimit,
+                    &filter,
+                ),
             };
+            on_path(&result);
             report.add_path_result(result);
         }
 
@@ -350,27 +611,114 @@ impl SyncEngine {
         &self,
         sources: impl Iterator<Item = impl std::borrow::Borrow<SourceDefinition>>,
     ) -> Vec<SyncReport> {
-        sources
-            .map(|source| {
-                let source = source.borrow();
-                self.sync_source(source)
-                    .unwrap_or_else(|e| SyncReport::failed(&source.name, e))
-            })
+        let sources: Vec<_> = sources.collect();
+        let sources: Vec<&SourceDefinition> = sources.iter().map(|s| s.borrow()).collect();
+        self.sync_sources(&sources, |_| {})
+            .into_iter()
+            .zip(&sources)
+            .map(|(result, source)| result.unwrap_or_else(|e| SyncReport::failed(&source.name, e)))
+            .collect()
+    }
+
+    /// Sync `sources`, up to the engine's parallelism at once.
+    ///
+    /// `on_progress` is called from the worker threads as sources start and
+    /// finish. Results are returned in the order of `sources`.
+    pub fn sync_sources<F>(
+        &self,
+        sources: &[&SourceDefinition],
+        on_progress: F,
+    ) -> Vec<Result<SyncReport, SyncError>>
+    where
+        F: Fn(SyncProgress) + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<Result<SyncReport, SyncError>>>> =
+            Mutex::new(sources.iter().map(|_| None).collect());
+        let workers = self.parallelism.min(sources.len()).max(1);
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(source) = sources.get(index) else {
+                            break;
+                        };
+                        let result = self.sync_source_with_progress(source, &on_progress);
+                        if let Ok(mut results) = results.lock() {
+                            results[index] = Some(result);
+                        }
+                    }
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(SyncError::Cancelled)))
             .collect()
     }
 
+    /// Sync one source, reporting its progress.
+    fn sync_source_with_progress(
+        &self,
+        source: &SourceDefinition,
+        on_progress: &(dyn Fn(SyncProgress) + Sync),
+    ) -> Result<SyncReport, SyncError> {
+        on_progress(SyncProgress::SourceStarted {
+            source: source.name.clone(),
+        });
+        let start = Instant::now();
+        let result = self.sync_source_reporting(source, &|path| {
+            on_progress(SyncProgress::PathFinished {
+                source: source.name.clone(),
+                path: path.remote_path.clone(),
+                success: path.success,
+                files: path.files_transferred,
+                bytes: path.bytes_transferred,
+                error: path.error.clone(),
+            })
+        });
+        on_progress(match &result {
+            Ok(report) => SyncProgress::SourceFinished {
+                source: source.name.clone(),
+                success: report.all_succeeded,
+                files: report.total_files(),
+                bytes: report.total_bytes(),
+                duration_ms: report.total_duration_ms,
+                error: None,
+            },
+            Err(e) => SyncProgress::SourceFinished {
+                source: source.name.clone(),
+                success: false,
+                files: 0,
+                bytes: 0,
+                duration_ms: start.elapsed().as_millis() as u64,
+                error: Some(e.to_string()),
+            },
+        });
+        result
+    }
+
     /// Sync a single path using rsync.
     ///
     /// **IMPORTANT**: Uses rsync WITHOUT --delete for safe additive syncs.
     ///
     /// The `remote_home` parameter should be pre-fetched via `get_remote_home()` to avoid
-    /// repeated SSH calls for each path.
+    /// repeated SSH calls for each path. `bwlimit` (KiB/s) becomes `--bwlimit`.
+    /// `filter` becomes filter rules; with an age limit, only the files a
+    /// remote `find` lists as recent are transferred.
     fn sync_path_rsync(
         &self,
         host: &str,
         remote_path: &str,
         dest_dir: &Path,
         remote_home: Option<&str>,
+        bwlimit: Option<u32>,
+        filter: &FileFilter,
     ) -> PathSyncResult {
         let start = Instant::now();
 
@@ -401,9 +749,31 @@ impl SyncEngine {
             };
         }
 
+        // With an age limit, list the recent files up front; paths are
+        // relative to the parent so the mirror layout stays the same
+        let files_from = match filter.max_age() {
+            Some(max_age) => match self.list_recent_files(host, &expanded_path, max_age) {
+                Ok(list) => Some(list),
+                Err(e) => {
+                    return PathSyncResult {
+                        remote_path: remote_path.to_string(),
+                        local_path,
+                        success: false,
+                        error: Some(e),
+                        duration_ms: start.elapsed().as_millis() as u64,
+                        ..Default::default()
+                    };
+                }
+            },
+            None => None,
+        };
+
         // Build rsync command
         // NOTE: NO --delete flag! Safe additive sync only.
-        let remote_spec = format!("{}:{}", host, expanded_path);
+        let remote_spec = match &files_from {
+            Some(_) => format!("{}:{}/", host, remote_parent(&expanded_path)),
+            None => format!("{}:{}", host, expanded_path),
+        };
         let ssh_opts = format!(
             "ssh -o BatchMode=yes -o ConnectTimeout={} -o StrictHostKeyChecking=accept-new",
             self.connection_timeout
@@ -419,10 +789,15 @@ impl SyncEngine {
             &self.transfer_timeout.to_string(),
             "-e",
             &ssh_opts,
-            "--",
-            &remote_spec,
-            local_path.to_str().unwrap_or("."),
         ]);
+        if let Some(limit) = bwlimit {
+            cmd.arg(format!("--bwlimit={limit}"));
+        }
+        cmd.args(filter.rsync_args());
+        if files_from.is_some() {
+            cmd.args(["--files-from=-", "--from0"]);
+        }
+        cmd.args(["--", &remote_spec, local_path.to_str().unwrap_or(".")]);
 
         tracing::debug!(
             host = %host,
@@ -431,7 +806,7 @@ impl SyncEngine {
             "starting rsync"
         );
 
-        let output = match cmd.output() {
+        let output = match run_with_input(cmd, files_from.as_deref()) {
             Ok(o) => o,
             Err(e) => {
                 return PathSyncResult {
@@ -506,13 +881,17 @@ impl SyncEngine {
     /// Sync a single path using SFTP (fallback when rsync unavailable).
     ///
     /// Uses the ssh2 crate for SFTP transfers. Authenticates via SSH agent
-    /// or key file from SSH config.
+    /// or key file from SSH config. When the connection drops mid-transfer
+    /// the path is retried on a new connection: finished files are skipped
+    /// and the interrupted one resumes from its partial file.
     fn sync_path_sftp(
         &self,
         host: &str,
         remote_path: &str,
         dest_dir: &Path,
         remote_home: Option<&str>,
+        bwlimit: Option<u32>,
+        filter: &FileFilter,
     ) -> PathSyncResult {
         let start = Instant::now();
         let expanded_path = Self::expand_tilde_with_home(remote_path, remote_home);
@@ -530,117 +909,6 @@ impl SyncEngine {
             };
         }
 
-        // Parse host to extract user if present (user@host format)
-        let (ssh_user, ssh_host) = parse_ssh_host(host);
-
-        // Look up host in SSH config for connection details
-        // First try matching by SSH config alias (Host line), then by actual hostname
-        let ssh_config = discover_ssh_hosts();
-        let host_config = ssh_config.iter().find(|h| h.name == ssh_host).or_else(|| {
-            ssh_config
-                .iter()
-                .find(|h| h.hostname.as_deref() == Some(ssh_host))
-        });
-
-        // Determine connection parameters
-        let hostname = host_config
-            .and_then(|h| h.hostname.as_deref())
-            .unwrap_or(ssh_host);
-        let port = host_config.and_then(|h| h.port).unwrap_or(22);
-        let default_user = dotenvy::var("USER").unwrap_or_else(|_| "root".to_string());
-        let username = ssh_user
-            .map(|s| s.to_string())
-            .or_else(|| host_config.and_then(|h| h.user.clone()))
-            .unwrap_or(default_user);
-        let identity_file = host_config.and_then(|h| h.identity_file.as_deref());
-
-        tracing::debug!(
-            hostname = %hostname,
-            port,
-            username = %username,
-            identity_file = ?identity_file,
-            remote_path = %expanded_path,
-            "SFTP connection parameters"
-        );
-
-        // Connect via TCP
-        let tcp = match TcpStream::connect((hostname, port)) {
-            Ok(t) => t,
-            Err(e) => {
-                return PathSyncResult {
-                    remote_path: remote_path.to_string(),
-                    local_path,
-                    success: false,
-                    error: Some(format!(
-                        "TCP connection failed to {}:{}: {}",
-                        hostname, port, e
-                    )),
-                    duration_ms: start.elapsed().as_millis() as u64,
-                    ..Default::default()
-                };
-            }
-        };
-
-        // Set TCP timeout
-        let timeout = std::time::Duration::from_secs(self.connection_timeout);
-        let _ = tcp.set_read_timeout(Some(timeout));
-        let _ = tcp.set_write_timeout(Some(timeout));
-
-        // Create SSH session
-        let mut session = match Session::new() {
-            Ok(s) => s,
-            Err(e) => {
-                return PathSyncResult {
-                    remote_path: remote_path.to_string(),
-                    local_path,
-                    success: false,
-                    error: Some(format!("Failed to create SSH session: {}", e)),
-                    duration_ms: start.elapsed().as_millis() as u64,
-                    ..Default::default()
-                };
-            }
-        };
-
-        session.set_tcp_stream(tcp);
-
-        if let Err(e) = session.handshake() {
-            return PathSyncResult {
-                remote_path: remote_path.to_string(),
-                local_path,
-                success: false,
-                error: Some(format!("SSH handshake failed: {}", e)),
-                duration_ms: start.elapsed().as_millis() as u64,
-                ..Default::default()
-            };
-        }
-
-        // Authenticate - try agent first, then key file
-        if let Err(e) = self.authenticate_ssh(&session, &username, identity_file) {
-            return PathSyncResult {
-                remote_path: remote_path.to_string(),
-                local_path,
-                success: false,
-                error: Some(format!("SSH authentication failed: {}", e)),
-                duration_ms: start.elapsed().as_millis() as u64,
-                ..Default::default()
-            };
-        }
-
-        // Open SFTP session
-        let sftp = match session.sftp() {
-            Ok(s) => s,
-            Err(e) => {
-                return PathSyncResult {
-                    remote_path: remote_path.to_string(),
-                    local_path,
-                    success: false,
-                    error: Some(format!("Failed to open SFTP session: {}", e)),
-                    duration_ms: start.elapsed().as_millis() as u64,
-                    ..Default::default()
-                };
-            }
-        };
-
         tracing::info!(
             host = %host,
             remote_path = %expanded_path,
@@ -648,30 +916,59 @@ impl SyncEngine {
             "starting SFTP sync"
         );
 
-        // Recursively download the remote path
+        let mut throttle = Throttle::new(bwlimit);
         let mut files_transferred = 0u64;
         let mut bytes_transferred = 0u64;
+        let mut attempt = 0;
+        let error = loop {
+            attempt += 1;
+            let bytes_before = bytes_transferred;
+            let result = connect_ssh(host, self.connection_timeout).and_then(|session| {
+                let sftp = session
+                    .sftp()
+                    .map_err(|e| format!("Failed to open SFTP session: {}", e))?;
+                self.sftp_download_recursive(
+                    &sftp,
+                    Path::new(&expanded_path),
+                    &local_path,
+                    // Filters see the synced directory's name, as with rsync
+                    Path::new(remote_basename(&expanded_path)),
+                    filter,
+                    &mut files_transferred,
+                    &mut bytes_transferred,
+                    &mut throttle,
+                )
+                .map_err(|e| format!("SFTP download failed: {}", e))
+            });
+            match result {
+                Ok(()) => break None,
+                // Only a transfer that was getting somewhere is worth resuming
+                Err(e) if attempt < SFTP_ATTEMPTS && bytes_transferred > bytes_before => {
+                    tracing::warn!(
+                        host = %host,
+                        remote_path = %expanded_path,
+                        error = %e,
+                        attempt,
+                        "SFTP transfer interrupted, resuming"
+                    );
+                }
+                Err(e) => break Some(e),
+            }
+        };
 
-        if let Err(e) = self.sftp_download_recursive(
-            &sftp,
-            Path::new(&expanded_path),
-            &local_path,
-            &mut files_transferred,
-            &mut bytes_transferred,
-        ) {
+        let duration_ms = start.elapsed().as_millis() as u64;
+        if let Some(error) = error {
             return PathSyncResult {
                 remote_path: remote_path.to_string(),
                 local_path,
                 files_transferred,
                 bytes_transferred,
                 success: false,
-                error: Some(format!("SFTP download failed: {}", e)),
-                duration_ms: start.elapsed().as_millis() as u64,
+                error: Some(error),
+                duration_ms,
             };
         }
 
-        let duration_ms = start.elapsed().as_millis() as u64;
-
         tracing::info!(
             host = %host,
             remote_path = %expanded_path,
@@ -692,72 +989,21 @@ impl SyncEngine {
         }
     }
 
-    /// Authenticate SSH session using agent or key file.
-    fn authenticate_ssh(
-        &self,
-        session: &Session,
-        username: &str,
-        identity_file: Option<&str>,
-    ) -> Result<(), String> {
-        // Try SSH agent first
-        if let Ok(mut agent) = session.agent()
-            && agent.connect().is_ok()
-            && agent.list_identities().is_ok()
-        {
-            for identity in agent.identities().unwrap_or_default() {
-                if agent.userauth(username, &identity).is_ok() && session.authenticated() {
-                    tracing::debug!("Authenticated via SSH agent");
-                    return Ok(());
-                }
-            }
-        }
-
-        // Try key file if specified
-        if let Some(key_path) = identity_file {
-            let key_path_expanded = expand_tilde_local(key_path);
-            let key_path_buf = Path::new(&key_path_expanded);
-
-            if key_path_buf.exists()
-                && session
-                    .userauth_pubkey_file(username, None, key_path_buf, None)
-                    .is_ok()
-                && session.authenticated()
-            {
-                tracing::debug!(key = %key_path_buf.display(), "Authenticated via key file");
-                return Ok(());
-            }
-        }
-
-        // Try default key locations
-        if let Some(home) = dirs::home_dir() {
-            for key_name in ["id_ed25519", "id_rsa", "id_ecdsa"] {
-                let key_path = home.join(".ssh").join(key_name);
-                if key_path.exists()
-                    && session
-                        .userauth_pubkey_file(username, None, &key_path, None)
-                        .is_ok()
-                    && session.authenticated()
-                {
-                    tracing::debug!(key = %key_path.display(), "Authenticated via default key");
-                    return Ok(());
-                }
-            }
-        }
-
-        Err(format!(
-            "No valid authentication method found for user '{}'",
-            username
-        ))
-    }
-
     /// Recursively download a remote path via SFTP.
+    ///
+    /// `rel` is `remote_path` as the filter sees it; excluded directories
+    /// are not descended into.
+    #[allow(clippy::too_many_arguments)]
     fn sftp_download_recursive(
         &self,
         sftp: &Sftp,
         remote_path: &Path,
         local_path: &Path,
+        rel: &Path,
+        filter: &FileFilter,
         files_transferred: &mut u64,
         bytes_transferred: &mut u64,
+        throttle: &mut Throttle,
     ) -> Result<(), String> {
         // Check if remote path is a directory or file
         let stat = sftp
@@ -786,24 +1032,45 @@ impl SyncEngine {
                 }
 
                 let local_entry = local_path.join(file_name);
+                let rel_entry = rel.join(file_name);
 
                 if entry_stat.is_dir() {
+                    if !filter.allows_dir(&rel_entry) {
+                        continue;
+                    }
                     // Recurse into subdirectory
                     self.sftp_download_recursive(
                         sftp,
                         &entry_path,
                         &local_entry,
+                        &rel_entry,
+                        filter,
                         files_transferred,
                         bytes_transferred,
+                        throttle,
                     )?;
                 } else if entry_stat.is_file() {
+                    if !sftp_file_wanted(filter, &rel_entry, &entry_stat) {
+                        continue;
+                    }
                     // Download file
-                    self.sftp_download_file(sftp, &entry_path, &local_entry, bytes_transferred)?;
-                    *files_transferred += 1;
+                    if self.sftp_download_file(
+                        sftp,
+                        &entry_path,
+                        &entry_stat,
+                        &local_entry,
+                        bytes_transferred,
+                        throttle,
+                    )? {
+                        *files_transferred += 1;
+                    }
                 }
                 // Skip symlinks and other types for safety
             }
         } else if stat.is_file() {
+            if !sftp_file_wanted(filter, rel, &stat) {
+                return Ok(());
+            }
             // Single file - download to local path
             std::fs::create_dir_all(local_path.parent().unwrap_or(local_path))
                 .map_err(|e| format!("Failed to create parent dir: {}", e))?;
@@ -815,8 +1082,16 @@ impl SyncEngine {
                 .unwrap_or("file");
             let local_file = local_path.join(file_name);
 
-            self.sftp_download_file(sftp, remote_path, &local_file, bytes_transferred)?;
-            *files_transferred += 1;
+            if self.sftp_download_file(
+                sftp,
+                remote_path,
+                &stat,
+                &local_file,
+                bytes_transferred,
+                throttle,
+            )? {
+                *files_transferred += 1;
+            }
         } else {
             // Not a regular file or directory (symlink, socket, etc.) - skip with warning
             tracing::warn!(
@@ -829,48 +1104,652 @@ impl SyncEngine {
     }
 
     /// Download a single file via SFTP.
+    ///
+    /// Skips the file when the local copy already has the remote size and
+    /// mtime. Data goes to a partial file first; a partial left behind for the
+    /// same remote version is resumed from its length. Returns whether the
+    /// file was downloaded.
     fn sftp_download_file(
         &self,
         sftp: &Sftp,
         remote_path: &Path,
+        remote_stat: &ssh2::FileStat,
         local_path: &Path,
         bytes_transferred: &mut u64,
-    ) -> Result<(), String> {
+        throttle: &mut Throttle,
+    ) -> Result<bool, String> {
+        let remote_size = remote_stat.size.unwrap_or(0);
+        let remote_mtime = remote_stat.mtime;
+        if remote_mtime.is_some() && local_version(local_path) == Some((remote_size, remote_mtime))
+        {
+            return Ok(false);
+        }
+
+        // Resume only a partial of this remote version (it carries the remote mtime)
+        let partial = partial_path(local_path);
+        let offset = match local_version(&partial) {
+            Some((len, mtime)) if mtime == remote_mtime && len <= remote_size => len,
+            _ => 0,
+        };
+
         // Open remote file
         let mut remote_file = sftp
             .open(remote_path)
             .map_err(|e| format!("Failed to open {}: {}", remote_path.display(), e))?;
+        if offset > 0 {
+            remote_file
+                .seek(SeekFrom::Start(offset))
+                .map_err(|e| format!("Failed to seek {}: {}", remote_path.display(), e))?;
+        }
 
-        // Create local file
-        let mut local_file = std::fs::File::create(local_path)
-            .map_err(|e| format!("Failed to create {}: {}", local_path.display(), e))?;
+        let mut options = std::fs::OpenOptions::new();
+        if offset > 0 {
+            options.append(true);
+        } else {
+            options.create(true).write(true).truncate(true);
+        }
+        let mut local_file = options
+            .open(&partial)
+            .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+        let remote_modified =
+            remote_mtime.map(|secs| std::time::UNIX_EPOCH + Duration::from_secs(secs));
 
         // Transfer in chunks
         let mut buffer = [0u8; 32768]; // 32KB chunks
-        loop {
-            let bytes_read = remote_file
-                .read(&mut buffer)
-                .map_err(|e| format!("Failed to read {}: {}", remote_path.display(), e))?;
-
-            if bytes_read == 0 {
-                break;
+        let copied = loop {
+            let bytes_read = match remote_file.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(n) => n,
+                Err(e) => break Err(format!("Failed to read {}: {}", remote_path.display(), e)),
+            };
+            if let Err(e) = local_file.write_all(&buffer[..bytes_read]) {
+                break Err(format!("Failed to write {}: {}", partial.display(), e));
             }
-
-            local_file
-                .write_all(&buffer[..bytes_read])
-                .map_err(|e| format!("Failed to write {}: {}", local_path.display(), e))?;
-
             *bytes_transferred += bytes_read as u64;
+            throttle.consume(bytes_read as u64);
+        };
+        // Stamp the partial with the remote mtime so a later attempt can tell
+        // it belongs to the same version
+        if let Some(modified) = remote_modified {
+            let _ = local_file.set_modified(modified);
         }
+        copied?;
+        drop(local_file);
+
+        std::fs::rename(&partial, local_path)
+            .map_err(|e| format!("Failed to write {}: {}", local_path.display(), e))?;
 
         tracing::trace!(
             remote = %remote_path.display(),
             local = %local_path.display(),
+            resumed_at = offset,
             "downloaded file"
         );
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Sync a git source: clone on first use, then fetch and check out the
+    /// tracked branch.
+    ///
+    /// Unlike rsync syncs, the checkout mirrors the branch exactly, so files
+    /// deleted upstream disappear from it (already indexed sessions stay).
+    fn sync_git(&self, source: &SourceDefinition) -> Result<SyncReport, SyncError> {
+        let url = source.url.as_deref().ok_or(SyncError::NoUrl)?;
+        let mut report = SyncReport::new(&source.name, SyncMethod::Git);
+        let start = Instant::now();
+        let checkout = self.git_checkout_dir(&source.name);
+        std::fs::create_dir_all(self.mirror_dir(&source.name))?;
+
+        let mut result = PathSyncResult {
+            remote_path: url.to_string(),
+            local_path: checkout.clone(),
+            ..Default::default()
+        };
+        match self.git_fetch(url, source.branch.as_deref(), &checkout) {
+            Ok((before, after)) => {
+                let changed =
+                    git_changed_files(&checkout, before.as_deref(), &after, &source.paths);
+                result.files_transferred = changed.len() as u64;
+                result.bytes_transferred = changed
+                    .iter()
+                    .filter_map(|f| std::fs::metadata(checkout.join(f)).ok())
+                    .map(|m| m.len())
+                    .sum();
+                result.success = true;
+            }
+            Err(e) => result.error = Some(e),
+        }
+        result.duration_ms = start.elapsed().as_millis() as u64;
+
+        report.add_path_result(result);
+        report.total_duration_ms = start.elapsed().as_millis() as u64;
+        Ok(report)
     }
+
+    /// Clone or update `checkout`, returning the commit before (if any) and after.
+    fn git_fetch(
+        &self,
+        url: &str,
+        branch: Option<&str>,
+        checkout: &Path,
+    ) -> Result<(Option<String>, String), String> {
+        if checkout.join(".git").is_dir() {
+            let before = self.git(checkout, &["rev-parse", "HEAD"]).ok();
+            // The url may have been edited in sources.toml since the clone
+            self.git(checkout, &["remote", "set-url", "--", "origin", url])?;
+            self.git(
+                checkout,
+                &[
+                    "fetch",
+                    "--quiet",
+                    "--depth",
+                    "1",
+                    "--",
+                    "origin",
+                    branch.unwrap_or("HEAD"),
+                ],
+            )?;
+            self.git(checkout, &["reset", "--quiet", "--hard", "FETCH_HEAD"])?;
+            let after = self.git(checkout, &["rev-parse", "HEAD"])?;
+            return Ok((before, after));
+        }
+
+        // Leftovers of an interrupted clone would make git refuse the directory
+        if checkout.exists() {
+            std::fs::remove_dir_all(checkout)
+                .map_err(|e| format!("Failed to clear {}: {}", checkout.display(), e))?;
+        }
+        let parent = checkout.parent().unwrap_or(checkout);
+        let checkout_str = checkout.to_string_lossy();
+        let mut args = vec!["clone", "--quiet", "--depth", "1"];
+        if let Some(branch) = branch {
+            args.extend(["--branch", branch]);
+        }
+        args.extend(["--", url, &checkout_str]);
+        self.git(parent, &args)?;
+        let after = self.git(checkout, &["rev-parse", "HEAD"])?;
+        Ok((None, after))
+    }
+
+    /// Run a non-interactive git command in `dir`, returning trimmed stdout.
+    fn git(&self, dir: &Path, args: &[&str]) -> Result<String, String> {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(dir)
+            .arg("-c")
+            .arg("http.lowSpeedLimit=1")
+            .arg("-c")
+            .arg(format!(
+                "http.lowSpeedTime={}",
+                self.transfer_timeout.max(1)
+            ))
+            .args(args)
+            .env("GIT_TERMINAL_PROMPT", "0");
+        if std::env::var_os("GIT_SSH_COMMAND").is_none() {
+            cmd.env(
+                "GIT_SSH_COMMAND",
+                format!(
+                    "ssh -o BatchMode=yes -o ConnectTimeout={} -o StrictHostKeyChecking=accept-new",
+                    self.connection_timeout
+                ),
+            );
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| format!("Failed to execute git: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Sync an HTTP source: fetch the index at `url`, then every file it lists.
+    fn sync_http(&self, source: &SourceDefinition) -> Result<SyncReport, SyncError> {
+        let url = source.url.as_deref().ok_or(SyncError::NoUrl)?;
+        let mut report = SyncReport::new(&source.name, SyncMethod::Http);
+        let start = Instant::now();
+        let dest = self.http_mirror_dir(&source.name);
+        std::fs::create_dir_all(&dest)?;
+        let cache_path = self
+            .local_store
+            .join("remotes")
+            .join(&source.name)
+            .join("http_cache.json");
+
+        // reqwest's blocking client panics when used on an async runtime thread
+        let mut result = std::thread::scope(|s| {
+            s.spawn(|| self.fetch_http_files(url, &dest, &cache_path))
+                .join()
+                .unwrap_or_else(|_| PathSyncResult {
+                    remote_path: url.to_string(),
+                    local_path: dest.clone(),
+                    error: Some("HTTP sync panicked".into()),
+                    ..Default::default()
+                })
+        });
+        result.duration_ms = start.elapsed().as_millis() as u64;
+
+        report.add_path_result(result);
+        report.total_duration_ms = start.elapsed().as_millis() as u64;
+        Ok(report)
+    }
+
+    /// Download the index and the files it lists into `dest`.
+    ///
+    /// Files already on disk are requested with the validators of their last
+    /// download and skipped on `304 Not Modified`.
+    fn fetch_http_files(&self, url: &str, dest: &Path, cache_path: &Path) -> PathSyncResult {
+        let mut result = PathSyncResult {
+            remote_path: url.to_string(),
+            local_path: dest.to_path_buf(),
+            ..Default::default()
+        };
+        let mut cache = HttpCache::load(cache_path);
+
+        let index_url = match reqwest::Url::parse(url) {
+            Ok(u) => u,
+            Err(e) => {
+                result.error = Some(format!("Invalid url {}: {}", url, e));
+                return result;
+            }
+        };
+        let mut builder = reqwest::blocking::Client::builder()
+            .connect_timeout(Duration::from_secs(self.connection_timeout));
+        if self.transfer_timeout > 0 {
+            builder = builder.timeout(Duration::from_secs(self.transfer_timeout));
+        }
+        let client = match builder.build() {
+            Ok(c) => c,
+            Err(e) => {
+                result.error = Some(format!("Failed to create HTTP client: {}", e));
+                return result;
+            }
+        };
+
+        match http_get_if_modified(&client, &index_url, Some(&cache.index)) {
+            Ok(Some((body, validators))) => {
+                match parse_http_index(&String::from_utf8_lossy(&body)) {
+                    Ok(files) => {
+                        cache.files = files;
+                        cache.index = validators;
+                    }
+                    Err(e) => {
+                        result.error = Some(format!("Invalid index at {}: {}", url, e));
+                        return result;
+                    }
+                }
+            }
+            // Unchanged index: reuse the file list it had last time
+            Ok(None) => {}
+            Err(e) => {
+                result.error = Some(e);
+                return result;
+            }
+        }
+
+        let mut errors = Vec::new();
+        let mut validators = HashMap::new();
+        for rel in &cache.files {
+            let local = dest.join(rel);
+            let known = cache.validators.get(rel).filter(|_| local.is_file());
+            let fetched = http_file_url(&index_url, rel)
+                .and_then(|file_url| http_get_if_modified(&client, &file_url, known))
+                .and_then(|response| match response {
+                    Some((body, v)) => {
+                        write_atomic(&local, &body)?;
+                        result.files_transferred += 1;
+                        result.bytes_transferred += body.len() as u64;
+                        Ok(v)
+                    }
+                    None => Ok(known.cloned().unwrap_or_default()),
+                });
+            match fetched {
+                Ok(v) => {
+                    validators.insert(rel.clone(), v);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        // Only files still listed (and downloaded) keep validators
+        cache.validators = validators;
+
+        if let Err(e) = cache.save(cache_path) {
+            tracing::warn!(path = %cache_path.display(), "Failed to save HTTP cache: {}", e);
+        }
+
+        result.success = errors.is_empty();
+        if let Some(first) = errors.first() {
+            result.error = Some(format!(
+                "{} of {} files failed: {}",
+                errors.len(),
+                cache.files.len(),
+                first
+            ));
+        }
+        result
+    }
+}
+
+/// Files changed between two commits of a checkout (all files for a fresh
+/// clone), limited to `paths` when given.
+fn git_changed_files(
+    checkout: &Path,
+    before: Option<&str>,
+    after: &str,
+    paths: &[String],
+) -> Vec<String> {
+    if before == Some(after) {
+        return Vec::new();
+    }
+
+    let list = |args: &[&str]| -> Option<Vec<String>> {
+        let output = Command::new("git")
+            .current_dir(checkout)
+            .args(args)
+            .arg("--")
+            .args(paths)
+            .output()
+            .ok()?;
+        output.status.success().then(|| {
+            output
+                .stdout
+                .split(|b| *b == 0)
+                .filter(|name| !name.is_empty())
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .collect()
+        })
+    };
+
+    before
+        .and_then(|before| {
+            list(&[
+                "diff",
+                "--name-only",
+                "-z",
+                "--no-renames",
+                "--diff-filter=d",
+                before,
+                after,
+            ])
+        })
+        .or_else(|| list(&["ls-files", "-z"]))
+        .unwrap_or_default()
+}
+
+/// HTTP validators of a downloaded resource.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct HttpValidators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+/// What an HTTP source fetched last time (`remotes/<name>/http_cache.json`).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct HttpCache {
+    /// Validators of the index itself
+    #[serde(default)]
+    index: HttpValidators,
+    /// Files listed by the index
+    #[serde(default)]
+    files: Vec<String>,
+    /// Validators per listed file
+    #[serde(default)]
+    validators: HashMap<String, HttpValidators>,
+}
+
+impl HttpCache {
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<(), std::io::Error> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path, content.as_bytes()).map_err(std::io::Error::other)
+    }
+}
+
+/// GET `url`, sending the validators of a previous download.
+///
+/// Returns `None` when the server answers `304 Not Modified`.
+fn http_get_if_modified(
+    client: &reqwest::blocking::Client,
+    url: &reqwest::Url,
+    known: Option<&HttpValidators>,
+) -> Result<Option<(Vec<u8>, HttpValidators)>, String> {
+    use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+    let mut request = client.get(url.clone());
+    if let Some(known) = known {
+        if let Some(etag) = &known.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &known.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request
+        .send()
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let validators = HttpValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let body = response
+        .bytes()
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?;
+    Ok(Some((body.to_vec(), validators)))
+}
+
+/// URL of a file listed in the index, relative to the index's directory.
+fn http_file_url(index_url: &reqwest::Url, rel: &str) -> Result<reqwest::Url, String> {
+    let mut url = index_url
+        .join("./")
+        .map_err(|e| format!("Invalid url for {}: {}", rel, e))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Cannot resolve {} against {}", rel, index_url))?
+        .pop_if_empty()
+        .extend(rel.split('/'));
+    Ok(url)
+}
+
+/// Parse an HTTP source index into relative file paths.
+///
+/// The index is either a JSON array of paths (optionally as `{"files": [...]}`)
+/// or plain text with one path per line, where blank lines and `#` comments
+/// are ignored. Paths that would escape the mirror directory are dropped.
+pub fn parse_http_index(body: &str) -> Result<Vec<String>, String> {
+    let trimmed = body.trim_start();
+    let entries: Vec<String> = if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        let value: serde_json::Value =
+            serde_json::from_str(trimmed).map_err(|e| format!("invalid JSON: {}", e))?;
+        value
+            .get("files")
+            .unwrap_or(&value)
+            .as_array()
+            .ok_or("expected an array of file paths")?
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    } else {
+        body.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    Ok(entries
+        .into_iter()
+        .map(|entry| entry.trim_start_matches("./").to_string())
+        .filter(|entry| {
+            let safe = !entry.is_empty()
+                && !entry.contains('\\')
+                && Path::new(entry)
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_)));
+            if !safe {
+                tracing::warn!(entry = %entry, "Skipping unsafe path in HTTP source index");
+            }
+            safe
+        })
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect())
+}
+
+/// Write `bytes` to `path` through a temporary file, creating parent dirs.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let tmp = path.with_extension("part");
+    std::fs::write(&tmp, bytes).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Open an authenticated SSH session to `host` (`[user@]host`).
+///
+/// Connection details (hostname, port, user, identity file) come from
+/// ~/.ssh/config when the host is listed there. Authenticates via SSH agent
+/// or key file.
+pub fn connect_ssh(host: &str, timeout_secs: u64) -> Result<Session, String> {
+    // Parse host to extract user if present (user@host format)
+    let (ssh_user, ssh_host) = parse_ssh_host(host);
+
+    // Look up host in SSH config for connection details
+    // First try matching by SSH config alias (Host line), then by actual hostname
+    let ssh_config = discover_ssh_hosts();
+    let host_config = ssh_config.iter().find(|h| h.name == ssh_host).or_else(|| {
+        ssh_config
+            .iter()
+            .find(|h| h.hostname.as_deref() == Some(ssh_host))
+    });
+
+    // Determine connection parameters
+    let hostname = host_config
+        .and_then(|h| h.hostname.as_deref())
+        .unwrap_or(ssh_host);
+    let port = host_config.and_then(|h| h.port).unwrap_or(22);
+    let default_user = dotenvy::var("USER").unwrap_or_else(|_| "root".to_string());
+    let username = ssh_user
+        .map(|s| s.to_string())
+        .or_else(|| host_config.and_then(|h| h.user.clone()))
+        .unwrap_or(default_user);
+    let identity_file = host_config.and_then(|h| h.identity_file.as_deref());
+
+    tracing::debug!(
+        hostname = %hostname,
+        port,
+        username = %username,
+        identity_file = ?identity_file,
+        "SSH connection parameters"
+    );
+
+    // Connect via TCP
+    let tcp = TcpStream::connect((hostname, port))
+        .map_err(|e| format!("TCP connection failed to {}:{}: {}", hostname, port, e))?;
+
+    // Set TCP timeout
+    let timeout = std::time::Duration::from_secs(timeout_secs);
+    let _ = tcp.set_read_timeout(Some(timeout));
+    let _ = tcp.set_write_timeout(Some(timeout));
+
+    // Create SSH session
+    let mut session = Session::new().map_err(|e| format!("Failed to create SSH session: {}", e))?;
+    session.set_tcp_stream(tcp);
+    session
+        .handshake()
+        .map_err(|e| format!("SSH handshake failed: {}", e))?;
+
+    // Authenticate - try agent first, then key file
+    authenticate_ssh(&session, &username, identity_file)
+        .map_err(|e| format!("SSH authentication failed: {}", e))?;
+
+    Ok(session)
+}
+
+/// Authenticate SSH session using agent or key file.
+fn authenticate_ssh(
+    session: &Session,
+    username: &str,
+    identity_file: Option<&str>,
+) -> Result<(), String> {
+    // Try SSH agent first
+    if let Ok(mut agent) = session.agent()
+        && agent.connect().is_ok()
+        && agent.list_identities().is_ok()
+    {
+        for identity in agent.identities().unwrap_or_default() {
+            if agent.userauth(username, &identity).is_ok() && session.authenticated() {
+                tracing::debug!("Authenticated via SSH agent");
+                return Ok(());
+            }
+        }
+    }
+
+    // Try key file if specified
+    if let Some(key_path) = identity_file {
+        let key_path_expanded = expand_tilde_local(key_path);
+        let key_path_buf = Path::new(&key_path_expanded);
+
+        if key_path_buf.exists()
+            && session
+                .userauth_pubkey_file(username, None, key_path_buf, None)
+                .is_ok()
+            && session.authenticated()
+        {
+            tracing::debug!(key = %key_path_buf.display(), "Authenticated via key file");
+            return Ok(());
+        }
+    }
+
+    // Try default key locations
+    if let Some(home) = dirs::home_dir() {
+        for key_name in ["id_ed25519", "id_rsa", "id_ecdsa"] {
+            let key_path = home.join(".ssh").join(key_name);
+            if key_path.exists()
+                && session
+                    .userauth_pubkey_file(username, None, &key_path, None)
+                    .is_ok()
+                && session.authenticated()
+            {
+                tracing::debug!(key = %key_path.display(), "Authenticated via default key");
+                return Ok(());
+            }
+        }
+    }
+
+    Err(format!(
+        "No valid authentication method found for user '{}'",
+        username
+    ))
 }
 
 /// Parse SSH host string into (optional_user, host).
@@ -889,7 +1768,7 @@ fn parse_ssh_host(host: &str) -> (Option<&str>, &str) {
 }
 
 /// Expand tilde in local paths.
-fn expand_tilde_local(path: &str) -> String {
+pub(crate) fn expand_tilde_local(path: &str) -> String {
     if let Some(stripped) = path.strip_prefix("~/")
         && let Some(home) = dirs::home_dir()
     {
@@ -902,6 +1781,76 @@ fn expand_tilde_local(path: &str) -> String {
     path.to_string()
 }
 
+/// Hidden partial file an SFTP download of `local_path` is written to.
+fn partial_path(local_path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(local_path.file_name().unwrap_or_default());
+    name.push(PARTIAL_SUFFIX);
+    local_path.with_file_name(name)
+}
+
+/// Whether an SFTP entry passes the source's filter.
+fn sftp_file_wanted(filter: &FileFilter, rel: &Path, stat: &ssh2::FileStat) -> bool {
+    let modified = stat
+        .mtime
+        .map(|secs| std::time::UNIX_EPOCH + Duration::from_secs(secs));
+    filter.allows_file(rel, stat.size.unwrap_or(0), modified, SystemTime::now())
+}
+
+/// Directory containing a remote path (`/` at the top).
+fn remote_parent(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    match trimmed.rfind('/') {
+        Some(0) => "/",
+        Some(cut) => &trimmed[..cut],
+        None => ".",
+    }
+}
+
+/// Last component of a remote path.
+fn remote_basename(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    trimmed.rsplit('/').next().unwrap_or(trimmed)
+}
+
+/// Run `cmd`, feeding `input` to its stdin when given.
+fn run_with_input(mut cmd: Command, input: Option<&[u8]>) -> std::io::Result<std::process::Output> {
+    let Some(input) = input else {
+        return cmd.output();
+    };
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Write from a thread so a chatty child can't deadlock on a full pipe
+    let mut stdin = child.stdin.take();
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(&input);
+        }
+    });
+    let output = child.wait_with_output();
+    let _ = writer.join();
+    output
+}
+
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\"'\"'"))
+}
+
+/// Size and mtime (seconds) of a local file, as SFTP reports them.
+fn local_version(path: &Path) -> Option<(u64, Option<u64>)> {
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    Some((meta.len(), mtime))
+}
+
 /// Convert a remote path to a safe directory name.
 ///
 /// Replaces path separators and special characters with underscores.
@@ -919,7 +1868,7 @@ pub fn path_to_safe_dirname(path: &str) -> String {
 }
 
 /// Parse transfer statistics from rsync --stats output.
-fn parse_rsync_stats(output: &str) -> RsyncStats {
+pub(crate) fn parse_rsync_stats(output: &str) -> RsyncStats {
     let mut stats = RsyncStats::default();
 
     for line in output.lines() {
@@ -981,6 +1930,9 @@ pub struct SourceSyncInfo {
     pub bytes_transferred: u64,
     /// Duration of last sync in milliseconds.
     pub duration_ms: u64,
+    /// Failed syncs in a row, reset by a sync that transfers any path.
+    #[serde(default)]
+    pub consecutive_failures: u32,
 }
 
 /// Persistent sync status for all sources.
@@ -1038,6 +1990,10 @@ impl SyncStatus {
             SyncResult::Failed(errors.join("; "))
         };
 
+        let consecutive_failures = match result {
+            SyncResult::Failed(_) => self.failures(source_name) + 1,
+            _ => 0,
+        };
         self.sources.insert(
             source_name.to_string(),
             SourceSyncInfo {
@@ -1046,10 +2002,35 @@ impl SyncStatus {
                 files_synced: report.total_files(),
                 bytes_transferred: report.total_bytes(),
                 duration_ms: report.total_duration_ms,
+                consecutive_failures,
+            },
+        );
+    }
+
+    /// Record a sync that failed before any path was attempted (e.g. no SSH connection).
+    pub fn record_error(&mut self, source_name: &str, error: &str) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+
+        let consecutive_failures = self.failures(source_name) + 1;
+        self.sources.insert(
+            source_name.to_string(),
+            SourceSyncInfo {
+                last_sync: Some(now),
+                last_result: SyncResult::Failed(error.to_string()),
+                consecutive_failures,
+                ..Default::default()
             },
         );
     }
 
+    fn failures(&self, source_name: &str) -> u32 {
+        self.get(source_name)
+            .map_or(0, |info| info.consecutive_failures)
+    }
+
     /// Get sync info for a source.
     pub fn get(&self, source_name: &str) -> Option<&SourceSyncInfo> {
         self.sources.get(source_name)
@@ -1279,6 +2260,30 @@ Total transferred file size: 1,234 bytes
 
         let info = status.get("dead-host").unwrap();
         assert!(matches!(info.last_result, SyncResult::Failed(_)));
+        assert_eq!(info.consecutive_failures, 1);
+    }
+
+    #[test]
+    fn test_sync_status_counts_consecutive_failures() {
+        let mut status = SyncStatus::default();
+
+        status.record_error("laptop", "Connection timed out after 10 seconds");
+        let mut failed = SyncReport::new("laptop", SyncMethod::Rsync);
+        failed.add_path_result(PathSyncResult {
+            success: false,
+            error: Some("Host unreachable".into()),
+            ..Default::default()
+        });
+        status.update("laptop", &failed);
+        assert_eq!(status.get("laptop").unwrap().consecutive_failures, 2);
+
+        let mut ok = SyncReport::new("laptop", SyncMethod::Rsync);
+        ok.add_path_result(PathSyncResult {
+            success: true,
+            ..Default::default()
+        });
+        status.update("laptop", &ok);
+        assert_eq!(status.get("laptop").unwrap().consecutive_failures, 0);
     }
 
     #[test]
@@ -1289,6 +2294,37 @@ Total transferred file size: 1,234 bytes
 
         assert_eq!(engine.connection_timeout, 30);
         assert_eq!(engine.transfer_timeout, 600);
+        assert_eq!(engine.parallelism, DEFAULT_SYNC_PARALLELISM);
+        assert_eq!(engine.with_parallelism(0).parallelism, 1);
+    }
+
+    #[test]
+    fn test_throttle_delay() {
+        let mut throttle = Throttle::new(Some(100));
+        throttle.bytes = 200 * 1024;
+        assert_eq!(throttle.delay(Duration::ZERO), Duration::from_secs(2));
+        assert_eq!(
+            throttle.delay(Duration::from_millis(1500)),
+            Duration::from_millis(500)
+        );
+        assert_eq!(throttle.delay(Duration::from_secs(3)), Duration::ZERO);
+
+        let mut unlimited = Throttle::new(None);
+        unlimited.bytes = u64::MAX / 2;
+        assert_eq!(unlimited.delay(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_partial_path_is_hidden_sibling() {
+        assert_eq!(
+            partial_path(Path::new("/m/.codex/rollout.jsonl")),
+            PathBuf::from("/m/.codex/.rollout.jsonl.cass-partial")
+        );
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("a.jsonl");
+        assert_eq!(local_version(&file), None);
+        std::fs::write(&file, "abc").unwrap();
+        assert_eq!(local_version(&file).unwrap().0, 3);
     }
 
     #[test]
@@ -1371,4 +2407,214 @@ Total transferred file size: 1,234 bytes
         let expanded = expand_tilde_local(path);
         assert_eq!(expanded, path);
     }
+
+    #[test]
+    fn test_parse_http_index() {
+        let text = "# sessions\n./a/one.jsonl\n\nb.jsonl\n../escape.jsonl\n/abs.jsonl\nb.jsonl\n";
+        assert_eq!(
+            parse_http_index(text).unwrap(),
+            vec!["a/one.jsonl", "b.jsonl"]
+        );
+        assert_eq!(
+            parse_http_index(r#"["x.jsonl", "dir\\y.jsonl"]"#).unwrap(),
+            vec!["x.jsonl"]
+        );
+        assert_eq!(
+            parse_http_index(r#"{"files": ["z.jsonl"]}"#).unwrap(),
+            vec!["z.jsonl"]
+        );
+        assert!(parse_http_index(r#"{"files": 3}"#).is_err());
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "{:?}", status);
+    }
+
+    #[test]
+    fn test_sync_git_reports_changed_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(repo.join("sessions")).unwrap();
+        git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+        std::fs::write(repo.join("sessions/a.jsonl"), "{}\n").unwrap();
+        std::fs::write(repo.join("README.md"), "notes\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "first"]);
+
+        let engine = SyncEngine::new(&tmp.path().join("data"));
+        let mut source = SourceDefinition::git("team", repo.to_str().unwrap());
+        source.paths = vec!["sessions".into()];
+
+        let report = engine.sync_source(&source).unwrap();
+        assert!(report.all_succeeded, "{:?}", report);
+        assert_eq!(report.method, SyncMethod::Git);
+        assert_eq!(report.total_files(), 1);
+        assert_eq!(
+            engine.scan_dirs(&source),
+            vec![engine.git_checkout_dir("team").join("sessions")]
+        );
+
+        std::fs::write(repo.join("sessions/b.jsonl"), "{}\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "second"]);
+        let report = engine.sync_source(&source).unwrap();
+        assert_eq!(report.total_files(), 1);
+        assert!(
+            engine
+                .git_checkout_dir("team")
+                .join("sessions/b.jsonl")
+                .exists()
+        );
+
+        let report = engine.sync_source(&source).unwrap();
+        assert!(report.all_succeeded);
+        assert_eq!(report.total_files(), 0);
+    }
+
+    #[test]
+    fn test_sync_sources_runs_in_parallel_with_progress() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut sources = Vec::new();
+        for name in ["alpha", "beta", "gamma"] {
+            let repo = tmp.path().join(name);
+            std::fs::create_dir_all(&repo).unwrap();
+            git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+            std::fs::write(repo.join("session.jsonl"), "{}\n").unwrap();
+            git(&repo, &["add", "."]);
+            git(&repo, &["commit", "--quiet", "-m", "first"]);
+            sources.push(SourceDefinition::git(name, repo.to_str().unwrap()));
+        }
+        let mut missing = SourceDefinition::ssh("nohost", "user@nohost");
+        missing.host = None;
+        sources.push(missing);
+
+        let engine = SyncEngine::new(&tmp.path().join("data")).with_parallelism(2);
+        let events = Mutex::new(Vec::new());
+        let refs: Vec<&SourceDefinition> = sources.iter().collect();
+        let results = engine.sync_sources(&refs, |event| events.lock().unwrap().push(event));
+
+        assert_eq!(results.len(), 4);
+        for (result, name) in results.iter().zip(["alpha", "beta", "gamma"]) {
+            let report = result.as_ref().unwrap();
+            assert_eq!(report.source_name, name);
+            assert_eq!(report.total_files(), 1);
+        }
+        assert!(matches!(results[3], Err(SyncError::NoHost)));
+
+        let events = events.into_inner().unwrap();
+        for name in ["alpha", "beta", "gamma", "nohost"] {
+            let of_source: Vec<_> = events
+                .iter()
+                .filter(|e| match e {
+                    SyncProgress::SourceStarted { source }
+                    | SyncProgress::PathFinished { source, .. }
+                    | SyncProgress::SourceFinished { source, .. } => source == name,
+                })
+                .collect();
+            assert!(matches!(
+                of_source.first(),
+                Some(SyncProgress::SourceStarted { .. })
+            ));
+            assert!(matches!(
+                of_source.last(),
+                Some(SyncProgress::SourceFinished { .. })
+            ));
+        }
+        let failed = events.iter().find_map(|e| match e {
+            SyncProgress::SourceFinished {
+                source,
+                success,
+                error,
+                ..
+            } if source == "nohost" => Some((*success, error.clone())),
+            _ => None,
+        });
+        assert_eq!(failed, Some((false, Some(SyncError::NoHost.to_string()))));
+    }
+
+    /// Serve `files` over HTTP, answering 304 when If-None-Match matches
+    fn serve_files(files: Vec<(&'static str, &'static str)>) -> String {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let mut if_none_match = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("if-none-match")
+                    {
+                        if_none_match = Some(value.trim().to_string());
+                    }
+                }
+                let response = match files.iter().find(|(p, _)| *p == path) {
+                    Some((_, body)) => {
+                        let etag = format!("\"{}\"", body.len());
+                        if if_none_match.as_deref() == Some(etag.as_str()) {
+                            "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\n\r\n".to_string()
+                        } else {
+                            format!(
+                                "HTTP/1.1 200 OK\r\nETag: {}\r\nContent-Length: {}\r\n\r\n{}",
+                                etag,
+                                body.len(),
+                                body
+                            )
+                        }
+                    }
+                    None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string(),
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_sync_http_revalidates_files() {
+        let base = serve_files(vec![
+            ("/share/index.txt", "one.jsonl\nnested/two.jsonl\n"),
+            ("/share/one.jsonl", "{\"a\":1}\n"),
+            ("/share/nested/two.jsonl", "{\"b\":2}\n"),
+        ]);
+        let tmp = tempfile::TempDir::new().unwrap();
+        let engine = SyncEngine::new(tmp.path());
+        let source = SourceDefinition::http("shared", format!("{}/share/index.txt", base));
+
+        let report = engine.sync_source(&source).unwrap();
+        assert!(report.all_succeeded, "{:?}", report);
+        assert_eq!(report.method, SyncMethod::Http);
+        assert_eq!(report.total_files(), 2);
+        let dir = engine.http_mirror_dir("shared");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("nested/two.jsonl")).unwrap(),
+            "{\"b\":2}\n"
+        );
+
+        // Everything answers 304 now
+        let report = engine.sync_source(&source).unwrap();
+        assert!(report.all_succeeded, "{:?}", report);
+        assert_eq!(report.total_files(), 0);
+
+        // A removed file is downloaded again
+        std::fs::remove_file(dir.join("one.jsonl")).unwrap();
+        let report = engine.sync_source(&source).unwrap();
+        assert_eq!(report.total_files(), 1);
+    }
 }
@@ -5,7 +5,7 @@ use crate::sources::provenance::{LOCAL_SOURCE_ID, Source, SourceKind};
 use anyhow::{Context, Result, anyhow};
 use rusqlite::{Connection, OpenFlags, OptionalExtension, Transaction, params};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
@@ -173,7 +173,7 @@ pub fn cleanup_old_backups(db_path: &Path, keep_count: usize) -> Result<(), std:
 }
 
 /// Public schema version constant for external checks.
-pub const CURRENT_SCHEMA_VERSION: i64 = 6;
+pub const CURRENT_SCHEMA_VERSION: i64 = 8;
 
 /// Result of checking schema compatibility.
 #[derive(Debug, Clone)]
@@ -248,7 +248,7 @@ fn check_schema_compatibility(path: &Path) -> std::result::Result<SchemaCheck, r
     }
 }
 
-const SCHEMA_VERSION: i64 = 6;
+const SCHEMA_VERSION: i64 = 8;
 
 const MIGRATION_V1: &str = r"
 PRAGMA foreign_keys = ON;
@@ -450,6 +450,30 @@ const MIGRATION_V6: &str = r"
 CREATE INDEX IF NOT EXISTS idx_conversations_source_path ON conversations(source_path);
 ";
 
+const MIGRATION_V7: &str = r"
+-- Fingerprint sessions so copies arriving from several sources are stored once
+ALTER TABLE conversations ADD COLUMN content_hash TEXT;
+CREATE INDEX IF NOT EXISTS idx_conversations_content_hash ON conversations(agent_id, content_hash);
+
+-- Other sources a stored conversation was also seen in
+CREATE TABLE IF NOT EXISTS conversation_sources (
+    conversation_id INTEGER NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    source_id TEXT NOT NULL,
+    origin_host TEXT,
+    source_path TEXT NOT NULL,
+    seen_at INTEGER NOT NULL,
+    PRIMARY KEY (conversation_id, source_id)
+);
+CREATE INDEX IF NOT EXISTS idx_conversation_sources_source ON conversation_sources(source_id);
+";
+
+const MIGRATION_V8: &str = r"
+-- User who owns a source and the sessions indexed from it
+ALTER TABLE sources ADD COLUMN owner TEXT;
+ALTER TABLE conversations ADD COLUMN owner TEXT;
+CREATE INDEX IF NOT EXISTS idx_conversations_owner ON conversations(owner);
+";
+
 pub struct SqliteStorage {
     conn: Connection,
 }
@@ -457,6 +481,18 @@ pub struct SqliteStorage {
 pub struct InsertOutcome {
     pub conversation_id: i64,
     pub inserted_indices: Vec<i64>,
+    /// The conversation was already stored from another source, so it was
+    /// linked to that copy instead of being inserted under its own source.
+    pub linked: bool,
+}
+
+/// Workspace of a stored conversation, as listed for path mapping rewrites.
+#[derive(Debug, Clone)]
+pub struct ConversationWorkspace {
+    pub conversation_id: i64,
+    pub agent_slug: String,
+    pub workspace: Option<PathBuf>,
+    pub metadata: serde_json::Value,
 }
 
 impl SqliteStorage {
@@ -613,23 +649,35 @@ impl SqliteStorage {
         workspace_id: Option<i64>,
         conv: &Conversation,
     ) -> Result<InsertOutcome> {
-        // Check for existing conversation with same (source_id, agent_id, external_id)
-        if let Some(ext) = &conv.external_id
-            && let Some(existing) = self
-                .conn
-                .query_row(
-                    "SELECT id FROM conversations WHERE source_id = ? AND agent_id = ? AND external_id = ?",
-                    params![&conv.source_id, agent_id, ext],
-                    |row| row.get(0),
-                )
-                .optional()?
+        let content_hash = conversation_content_hash(conv);
+        let existing = match find_existing_conversation(&self.conn, agent_id, conv, &content_hash)?
         {
-            return self.append_messages(existing, conv);
+            Some(ExistingConversation::Shared(id)) => {
+                link_conversation_source(&self.conn, id, conv)?;
+                Some(ExistingConversation::Same { id, linked: true })
+            }
+            other => other,
+        };
+        match existing {
+            Some(ExistingConversation::Same { id, linked }) => {
+                let mut outcome = self.append_messages(id, conv, &content_hash)?;
+                outcome.linked = linked;
+                return Ok(outcome);
+            }
+            Some(ExistingConversation::Duplicate(id)) => {
+                link_conversation_source(&self.conn, id, conv)?;
+                return Ok(InsertOutcome {
+                    conversation_id: id,
+                    inserted_indices: Vec::new(),
+                    linked: true,
+                });
+            }
+            Some(ExistingConversation::Shared(_)) | None => {}
         }
 
         let tx = self.conn.transaction()?;
 
-        let conv_id = insert_conversation(&tx, agent_id, workspace_id, conv)?;
+        let conv_id = insert_conversation(&tx, agent_id, workspace_id, conv, &content_hash)?;
         let mut fts_entries = Vec::with_capacity(conv.messages.len());
         for msg in &conv.messages {
             let msg_id = insert_message(&tx, conv_id, msg)?;
@@ -642,6 +690,7 @@ impl SqliteStorage {
         Ok(InsertOutcome {
             conversation_id: conv_id,
             inserted_indices: conv.messages.iter().map(|m| m.idx).collect(),
+            linked: false,
         })
     }
 
@@ -649,6 +698,7 @@ impl SqliteStorage {
         &mut self,
         conversation_id: i64,
         conv: &Conversation,
+        content_hash: &str,
     ) -> Result<InsertOutcome> {
         let tx = self.conn.transaction()?;
 
@@ -682,11 +732,21 @@ impl SqliteStorage {
                 params![last_ts, conversation_id],
             )?;
         }
+        // A copy that reaches at least as far as the stored one is the latest
+        // full view of the session; one lagging behind says nothing new
+        if conv.messages.iter().map(|m| m.idx).max().unwrap_or(-1) >= cutoff {
+            tx.execute(
+                "UPDATE conversations SET content_hash = ? WHERE id = ?",
+                params![content_hash, conversation_id],
+            )?;
+        }
+        update_owner(&tx, conversation_id, conv)?;
 
         tx.commit()?;
         Ok(InsertOutcome {
             conversation_id,
             inserted_indices,
+            linked: false,
         })
     }
 
@@ -778,11 +838,70 @@ impl SqliteStorage {
         Ok(out)
     }
 
+    /// Workspaces of the conversations stored under `source_id`.
+    pub fn list_conversation_workspaces(
+        &self,
+        source_id: &str,
+    ) -> Result<Vec<ConversationWorkspace>> {
+        let mut stmt = self.conn.prepare(
+            r"SELECT c.id, a.slug, w.path, c.metadata_json
+                FROM conversations c
+                JOIN agents a ON c.agent_id = a.id
+                LEFT JOIN workspaces w ON c.workspace_id = w.id
+                WHERE c.source_id = ?
+                ORDER BY c.id",
+        )?;
+        let rows = stmt.query_map(params![source_id], |row| {
+            Ok(ConversationWorkspace {
+                conversation_id: row.get(0)?,
+                agent_slug: row.get(1)?,
+                workspace: row.get::<_, Option<String>>(2)?.map(PathBuf::from),
+                metadata: row
+                    .get::<_, Option<String>>(3)?
+                    .and_then(|s| serde_json::from_str(&s).ok())
+                    .unwrap_or_default(),
+            })
+        })?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Move conversations to the workspaces given in `updates`, replacing
+    /// their metadata. Rows without a workspace are skipped.
+    pub fn set_conversation_workspaces(&mut self, updates: &[ConversationWorkspace]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for update in updates {
+            let Some(workspace) = &update.workspace else {
+                continue;
+            };
+            let path = workspace.to_string_lossy();
+            tx.execute(
+                "INSERT INTO workspaces(path) VALUES(?) ON CONFLICT(path) DO NOTHING",
+                params![path],
+            )?;
+            tx.execute(
+                "UPDATE conversations
+                 SET workspace_id = (SELECT id FROM workspaces WHERE path = ?), metadata_json = ?
+                 WHERE id = ?",
+                params![
+                    path,
+                    serde_json::to_string(&update.metadata)?,
+                    update.conversation_id
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     pub fn list_conversations(&self, limit: i64, offset: i64) -> Result<Vec<Conversation>> {
         let mut stmt = self.conn.prepare(
             r"SELECT c.id, a.slug, w.path, c.external_id, c.title, c.source_path,
                        c.started_at, c.ended_at, c.approx_tokens, c.metadata_json,
-                       c.source_id, c.origin_host
+                       c.source_id, c.origin_host, c.owner
                 FROM conversations c
                 JOIN agents a ON c.agent_id = a.id
                 LEFT JOIN workspaces w ON c.workspace_id = w.id
@@ -812,6 +931,7 @@ impl SqliteStorage {
                     .get::<_, String>(10)
                     .unwrap_or_else(|_| "local".to_string()),
                 origin_host: row.get(11)?,
+                owner: row.get(12)?,
             })
         })?;
         let mut out = Vec::new();
@@ -910,7 +1030,7 @@ impl SqliteStorage {
     pub fn get_source(&self, id: &str) -> Result<Option<Source>> {
         self.conn
             .query_row(
-                "SELECT id, kind, host_label, machine_id, platform, config_json, created_at, updated_at
+                "SELECT id, kind, host_label, machine_id, platform, config_json, created_at, updated_at, owner
                  FROM sources WHERE id = ?",
                 params![id],
                 |row| {
@@ -926,6 +1046,7 @@ impl SqliteStorage {
                             .and_then(|s| serde_json::from_str(&s).ok()),
                         created_at: row.get(6)?,
                         updated_at: row.get(7)?,
+                        owner: row.get(8)?,
                     })
                 },
             )
@@ -936,7 +1057,7 @@ impl SqliteStorage {
     /// List all sources.
     pub fn list_sources(&self) -> Result<Vec<Source>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, kind, host_label, machine_id, platform, config_json, created_at, updated_at
+            "SELECT id, kind, host_label, machine_id, platform, config_json, created_at, updated_at, owner
              FROM sources ORDER BY id",
         )?;
         let rows = stmt.query_map([], |row| {
@@ -951,6 +1072,7 @@ impl SqliteStorage {
                 config_json: config_json_str.and_then(|s| serde_json::from_str(&s).ok()),
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
+                owner: row.get(8)?,
             })
         })?;
 
@@ -985,15 +1107,16 @@ impl SqliteStorage {
             .transpose()?;
 
         self.conn.execute(
-            "INSERT INTO sources(id, kind, host_label, machine_id, platform, config_json, created_at, updated_at)
-             VALUES(?, ?, ?, ?, ?, ?, ?, ?)
+            "INSERT INTO sources(id, kind, host_label, machine_id, platform, config_json, created_at, updated_at, owner)
+             VALUES(?, ?, ?, ?, ?, ?, ?, ?, ?)
              ON CONFLICT(id) DO UPDATE SET
                 kind = excluded.kind,
                 host_label = excluded.host_label,
                 machine_id = excluded.machine_id,
                 platform = excluded.platform,
                 config_json = excluded.config_json,
-                updated_at = excluded.updated_at",
+                updated_at = excluded.updated_at,
+                owner = excluded.owner",
             params![
                 source.id,
                 source.kind.as_str(),
@@ -1002,7 +1125,8 @@ impl SqliteStorage {
                 source.platform,
                 config_json_str,
                 source.created_at.unwrap_or(now),
-                now
+                now,
+                source.owner
             ],
         )?;
         Ok(())
@@ -1098,6 +1222,8 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             tx.execute_batch(MIGRATION_V4)?;
             tx.execute_batch(MIGRATION_V5)?;
             tx.execute_batch(MIGRATION_V6)?;
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
         }
         1 => {
             tx.execute_batch(MIGRATION_V2)?;
@@ -1105,27 +1231,47 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             tx.execute_batch(MIGRATION_V4)?;
             tx.execute_batch(MIGRATION_V5)?;
             tx.execute_batch(MIGRATION_V6)?;
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
         }
         2 => {
             tx.execute_batch(MIGRATION_V3)?;
             tx.execute_batch(MIGRATION_V4)?;
             tx.execute_batch(MIGRATION_V5)?;
             tx.execute_batch(MIGRATION_V6)?;
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
         }
         3 => {
             tx.execute_batch(MIGRATION_V4)?;
             tx.execute_batch(MIGRATION_V5)?;
             tx.execute_batch(MIGRATION_V6)?;
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
         }
         4 => {
             tx.execute_batch(MIGRATION_V5)?;
             tx.execute_batch(MIGRATION_V6)?;
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
         }
         5 => {
             tx.execute_batch(MIGRATION_V6)?;
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
+        }
+        6 => {
+            tx.execute_batch(MIGRATION_V7)?;
+            tx.execute_batch(MIGRATION_V8)?;
+        }
+        7 => {
+            tx.execute_batch(MIGRATION_V8)?;
         }
         v => return Err(anyhow!("unsupported schema version {v}")),
     }
+    if current < 7 {
+        backfill_content_hashes(&tx)?;
+    }
 
     tx.execute(
         "UPDATE meta SET value = ? WHERE key = 'schema_version'",
@@ -1136,17 +1282,212 @@ fn migrate(conn: &mut Connection) -> Result<()> {
     Ok(())
 }
 
+/// A stored conversation an incoming one resolves to.
+enum ExistingConversation {
+    /// Stored under the incoming source, or linked to it earlier: append
+    Same { id: i64, linked: bool },
+    /// The same session stored from another source, agreeing on the messages
+    /// both copies have: link it and append what is new
+    Shared(i64),
+    /// Identical content stored from another source: link it
+    Duplicate(i64),
+}
+
+/// Fingerprint of a conversation's messages (hex SHA-256).
+///
+/// Together with the agent and external id it identifies the same session
+/// seen through different sources (a local file and its synced mirror, or a
+/// home folder shared between machines).
+pub fn conversation_content_hash(conv: &Conversation) -> String {
+    messages_hash(
+        conv.messages
+            .iter()
+            .map(|msg| (msg.idx, role_str(&msg.role), msg.content.as_str())),
+    )
+}
+
+/// Hash of `(idx, role, content)` triples, as stored in the messages table.
+fn messages_hash<'a, R: AsRef<str>>(messages: impl Iterator<Item = (i64, R, &'a str)>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for (idx, role, content) in messages {
+        hasher.update(idx.to_le_bytes());
+        for field in [role.as_ref(), content] {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field.as_bytes());
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Messages of a stored conversation as `(idx, role, content)`, in order.
+fn stored_messages(conn: &Connection, conversation_id: i64) -> Result<Vec<(i64, String, String)>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT idx, role, content FROM messages WHERE conversation_id = ? ORDER BY idx",
+    )?;
+    let rows = stmt
+        .query_map(params![conversation_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Fill in `content_hash` for conversations stored before it existed.
+fn backfill_content_hashes(tx: &Transaction<'_>) -> Result<()> {
+    let ids: Vec<i64> = tx
+        .prepare("SELECT id FROM conversations WHERE content_hash IS NULL")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for id in ids {
+        let messages = stored_messages(tx, id)?;
+        let hash = messages_hash(
+            messages
+                .iter()
+                .map(|(idx, role, content)| (*idx, role.as_str(), content.as_str())),
+        );
+        tx.execute(
+            "UPDATE conversations SET content_hash = ? WHERE id = ?",
+            params![hash, id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Whether a stored conversation and `conv` agree on every message they both
+/// have, i.e. one is the other caught up to a later point.
+fn shares_message_prefix(
+    conn: &Connection,
+    conversation_id: i64,
+    conv: &Conversation,
+) -> Result<bool> {
+    let stored = stored_messages(conn, conversation_id)?;
+    let shared = stored.len().min(conv.messages.len());
+    if shared == 0 {
+        return Ok(false);
+    }
+    let stored_prefix = messages_hash(
+        stored[..shared]
+            .iter()
+            .map(|(idx, role, content)| (*idx, role.as_str(), content.as_str())),
+    );
+    let incoming_prefix = messages_hash(
+        conv.messages[..shared]
+            .iter()
+            .map(|msg| (msg.idx, role_str(&msg.role), msg.content.as_str())),
+    );
+    Ok(stored_prefix == incoming_prefix)
+}
+
+/// Find the stored conversation `conv` belongs to, if any.
+fn find_existing_conversation(
+    conn: &Connection,
+    agent_id: i64,
+    conv: &Conversation,
+    content_hash: &str,
+) -> Result<Option<ExistingConversation>> {
+    if let Some(ext) = &conv.external_id {
+        let own: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM conversations WHERE source_id = ? AND agent_id = ? AND external_id = ?",
+                params![&conv.source_id, agent_id, ext],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = own {
+            return Ok(Some(ExistingConversation::Same { id, linked: false }));
+        }
+
+        let linked: Option<i64> = conn
+            .query_row(
+                "SELECT c.id FROM conversation_sources cs
+                 JOIN conversations c ON c.id = cs.conversation_id
+                 WHERE cs.source_id = ? AND c.agent_id = ? AND c.external_id = ?",
+                params![&conv.source_id, agent_id, ext],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = linked {
+            return Ok(Some(ExistingConversation::Same { id, linked: true }));
+        }
+
+        // A session still growing differs in its full hash from one source to
+        // the next; the agent's own session id plus agreeing messages is enough
+        let mut stmt = conn.prepare_cached(
+            "SELECT id FROM conversations
+             WHERE agent_id = ? AND external_id = ? AND source_id != ?
+             ORDER BY id",
+        )?;
+        let candidates: Vec<i64> = stmt
+            .query_map(params![agent_id, ext, &conv.source_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for id in candidates {
+            if shares_message_prefix(conn, id, conv)? {
+                return Ok(Some(ExistingConversation::Shared(id)));
+            }
+        }
+    }
+
+    let duplicate: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM conversations
+             WHERE agent_id = ? AND content_hash = ? AND external_id IS ? AND source_id != ?
+             ORDER BY id LIMIT 1",
+            params![agent_id, content_hash, &conv.external_id, &conv.source_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(duplicate.map(ExistingConversation::Duplicate))
+}
+
+/// Take the owner of an incoming copy from the conversation's own source.
+///
+/// Sessions indexed before owners were tracked pick one up when rescanned,
+/// and a changed source owner follows through on the next full scan.
+fn update_owner(conn: &Connection, conversation_id: i64, conv: &Conversation) -> Result<()> {
+    if conv.owner.is_some() {
+        conn.execute(
+            "UPDATE conversations SET owner = ?1 WHERE id = ?2 AND source_id = ?3",
+            params![conv.owner, conversation_id, &conv.source_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Record that `conv`'s source also has the stored conversation `conversation_id`.
+fn link_conversation_source(
+    conn: &Connection,
+    conversation_id: i64,
+    conv: &Conversation,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO conversation_sources(
+            conversation_id, source_id, origin_host, source_path, seen_at
+        ) VALUES(?,?,?,?,?)",
+        params![
+            conversation_id,
+            &conv.source_id,
+            conv.origin_host,
+            path_to_string(&conv.source_path),
+            SqliteStorage::now_millis()
+        ],
+    )?;
+    Ok(())
+}
+
 fn insert_conversation(
     tx: &Transaction<'_>,
     agent_id: i64,
     workspace_id: Option<i64>,
     conv: &Conversation,
+    content_hash: &str,
 ) -> Result<i64> {
     tx.execute(
         "INSERT INTO conversations(
             agent_id, workspace_id, source_id, external_id, title, source_path,
-            started_at, ended_at, approx_tokens, metadata_json, origin_host
-        ) VALUES(?,?,?,?,?,?,?,?,?,?,?)",
+            started_at, ended_at, approx_tokens, metadata_json, origin_host, content_hash, owner
+        ) VALUES(?,?,?,?,?,?,?,?,?,?,?,?,?)",
         params![
             agent_id,
             workspace_id,
@@ -1158,7 +1499,9 @@ fn insert_conversation(
             conv.ended_at,
             conv.approx_tokens,
             serde_json::to_string(&conv.metadata_json)?,
-            conv.origin_host
+            conv.origin_host,
+            content_hash,
+            conv.owner
         ],
     )?;
     Ok(tx.last_insert_rowid())
@@ -1340,17 +1683,19 @@ fn insert_conversation_in_tx_batched(
     conv: &Conversation,
     fts_entries: &mut Vec<FtsEntry>,
 ) -> Result<InsertOutcome> {
-    // Check for existing conversation with same (source_id, agent_id, external_id)
-    if let Some(ext) = &conv.external_id {
-        let existing: Option<i64> = tx
-            .query_row(
-                "SELECT id FROM conversations WHERE source_id = ? AND agent_id = ? AND external_id = ?",
-                params![&conv.source_id, agent_id, ext],
-                |row| row.get(0),
-            )
-            .optional()?;
-
-        if let Some(conversation_id) = existing {
+    let content_hash = conversation_content_hash(conv);
+    let existing = match find_existing_conversation(tx, agent_id, conv, &content_hash)? {
+        Some(ExistingConversation::Shared(id)) => {
+            link_conversation_source(tx, id, conv)?;
+            Some(ExistingConversation::Same { id, linked: true })
+        }
+        other => other,
+    };
+    match existing {
+        Some(ExistingConversation::Same {
+            id: conversation_id,
+            linked,
+        }) => {
             // Append messages to existing conversation
             let max_idx: Option<i64> = tx.query_row(
                 "SELECT MAX(idx) FROM messages WHERE conversation_id = ?",
@@ -1377,16 +1722,33 @@ fn insert_conversation_in_tx_batched(
                     params![last_ts, conversation_id],
                 )?;
             }
+            if conv.messages.iter().map(|m| m.idx).max().unwrap_or(-1) >= cutoff {
+                tx.execute(
+                    "UPDATE conversations SET content_hash = ? WHERE id = ?",
+                    params![&content_hash, conversation_id],
+                )?;
+            }
+            update_owner(tx, conversation_id, conv)?;
 
             return Ok(InsertOutcome {
                 conversation_id,
                 inserted_indices,
+                linked,
+            });
+        }
+        Some(ExistingConversation::Duplicate(conversation_id)) => {
+            link_conversation_source(tx, conversation_id, conv)?;
+            return Ok(InsertOutcome {
+                conversation_id,
+                inserted_indices: Vec::new(),
+                linked: true,
             });
         }
+        Some(ExistingConversation::Shared(_)) | None => {}
     }
 
     // Insert new conversation
-    let conv_id = insert_conversation(tx, agent_id, workspace_id, conv)?;
+    let conv_id = insert_conversation(tx, agent_id, workspace_id, conv, &content_hash)?;
     for msg in &conv.messages {
         let msg_id = insert_message(tx, conv_id, msg)?;
         insert_snippets(tx, msg_id, &msg.snippets)?;
@@ -1397,6 +1759,7 @@ fn insert_conversation_in_tx_batched(
     Ok(InsertOutcome {
         conversation_id: conv_id,
         inserted_indices: conv.messages.iter().map(|m| m.idx).collect(),
+        linked: false,
     })
 }
 
@@ -1706,6 +2069,7 @@ mod tests {
             host_label: Some("test.local".into()),
             machine_id: Some("test-machine-id".into()),
             platform: None,
+            owner: None,
             config_json: None,
             created_at: Some(SqliteStorage::now_millis()),
             updated_at: None,
@@ -1729,6 +2093,7 @@ mod tests {
             host_label: Some("Original Label".into()),
             machine_id: None,
             platform: None,
+            owner: None,
             config_json: None,
             created_at: Some(SqliteStorage::now_millis()),
             updated_at: None,
@@ -1741,6 +2106,7 @@ mod tests {
             host_label: Some("Updated Label".into()),
             machine_id: None,
             platform: Some("linux".into()),
+            owner: None,
             config_json: None,
             created_at: Some(SqliteStorage::now_millis()),
             updated_at: Some(SqliteStorage::now_millis()),
@@ -1764,6 +2130,7 @@ mod tests {
             host_label: None,
             machine_id: None,
             platform: None,
+            owner: None,
             config_json: None,
             created_at: Some(SqliteStorage::now_millis()),
             updated_at: None,
@@ -1810,6 +2177,7 @@ mod tests {
             host_label: Some("server".into()),
             machine_id: None,
             platform: None,
+            owner: None,
             config_json: None,
             created_at: Some(SqliteStorage::now_millis()),
             updated_at: None,
@@ -6,6 +6,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Block, Paragraph};
 
 use crate::search::query::SearchFilters;
+use crate::search::ranking::RankingMode;
 use crate::ui::components::theme::ThemePalette;
 use crate::ui::tui;
 
@@ -18,14 +19,14 @@ pub enum BreadcrumbKind {
     None,
 }
 
-fn ranking_label(r: tui::RankingMode) -> &'static str {
+fn ranking_label(r: RankingMode) -> &'static str {
     match r {
-        tui::RankingMode::RecentHeavy => "Recent",
-        tui::RankingMode::Balanced => "Balanced",
-        tui::RankingMode::RelevanceHeavy => "Relevance",
-        tui::RankingMode::MatchQualityHeavy => "Quality",
-        tui::RankingMode::DateNewest => "Newest",
-        tui::RankingMode::DateOldest => "Oldest",
+        RankingMode::RecentHeavy => "Recent",
+        RankingMode::Balanced => "Balanced",
+        RankingMode::RelevanceHeavy => "Relevance",
+        RankingMode::MatchQualityHeavy => "Quality",
+        RankingMode::DateNewest => "Newest",
+        RankingMode::DateOldest => "Oldest",
     }
 }
 
@@ -33,7 +34,7 @@ pub fn render_breadcrumbs(
     f: &mut Frame,
     area: Rect,
     filters: &SearchFilters,
-    ranking: tui::RankingMode,
+    ranking: RankingMode,
     palette: ThemePalette,
 ) -> Vec<(Rect, BreadcrumbKind)> {
     let mut rects: Vec<(Rect, BreadcrumbKind)> = Vec::new();
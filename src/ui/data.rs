@@ -198,7 +198,8 @@ fn load_conversation_uncached(
 ) -> Result<Option<ConversationView>> {
     let mut stmt = storage.raw().prepare(
         "SELECT c.id, a.slug, w.id, w.path, w.display_name, c.external_id, c.title, c.source_path,
-                c.started_at, c.ended_at, c.approx_tokens, c.metadata_json, c.source_id, c.origin_host
+                c.started_at, c.ended_at, c.approx_tokens, c.metadata_json, c.source_id, c.origin_host,
+                c.owner
          FROM conversations c
          JOIN agents a ON c.agent_id = a.id
          LEFT JOIN workspaces w ON c.workspace_id = w.id
@@ -229,6 +230,7 @@ fn load_conversation_uncached(
                 .get::<_, String>(12)
                 .unwrap_or_else(|_| "local".to_string()),
             origin_host: row.get(13)?,
+            owner: row.get(14)?,
         };
         let workspace = row.get::<_, Option<i64>>(2)?.map(|id| Workspace {
             id: Some(id),
@@ -360,6 +362,7 @@ mod tests {
                 messages: Vec::new(),
                 source_id: "local".to_string(),
                 origin_host: None,
+                owner: None,
             },
             messages: vec![Message {
                 id: Some(1),
@@ -30,14 +30,19 @@ use syntect::parsing::SyntaxSet;
 
 use crate::default_data_dir;
 use crate::model::types::MessageRole;
-use crate::search::model_download::{DownloadProgress, ModelDownloader, ModelManifest};
-use crate::search::model_manager::{
-    SemanticAvailability, default_model_dir, load_semantic_context,
-};
+use crate::search::model_download::{DownloadProgress, ModelDownloader};
+use crate::search::model_manager::{SemanticAvailability, load_semantic_context};
+use crate::search::model_registry::active_model_manifest;
 use crate::search::query::{
-    CacheStats, QuerySuggestion, SearchClient, SearchFilters, SearchHit, SearchMode,
+    CacheStats, FusionConfig, QuerySuggestion, SearchClient, SearchFilters, SearchHit, SearchMode,
+    SimilarAnchor,
 };
+use crate::search::ranking::{RankingMode, apply_ranking_mode};
 use crate::search::tantivy::index_dir;
+use crate::search::topics::{TopicOptions, TopicsReport, build_topics};
+use crate::sources::config::SourcesConfig;
+use crate::sources::doctor::{CheckStatus, Grade, HealthReport};
+use crate::sources::sync::{SyncResult, SyncStatus};
 use crate::ui::components::help_strip;
 use crate::ui::components::palette::{self, PaletteAction, PaletteState};
 use crate::ui::components::pills::{self, Pill};
@@ -103,16 +108,6 @@ pub enum MatchMode {
     Prefix,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum RankingMode {
-    RecentHeavy,
-    Balanced,
-    RelevanceHeavy,
-    MatchQualityHeavy, // Prioritizes exact matches over wildcard/fuzzy
-    DateNewest,        // Pure newest-first (ignores relevance score)
-    DateOldest,        // Pure oldest-first (ignores relevance score)
-}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ContextWindow {
     Small,
@@ -788,6 +783,48 @@ fn contextual_empty_state(
     lines
 }
 
+/// One row of the sources panel (Alt+R).
+struct SourcePanelRow {
+    name: String,
+    kind: String,
+    /// Grade from the last `cass sources doctor` run
+    grade: Option<Grade>,
+    last_sync: Option<i64>,
+    last_result: SyncResult,
+    /// Worst check of the last doctor run, when it did not pass
+    note: Option<String>,
+}
+
+/// Configured sources with their last sync (`SyncStatus`) and doctor grade.
+fn load_source_panel_rows(data_dir: &Path) -> anyhow::Result<Vec<SourcePanelRow>> {
+    let config = SourcesConfig::load()?;
+    let status = SyncStatus::load(data_dir).unwrap_or_default();
+    let health = HealthReport::load(data_dir).unwrap_or_default();
+    Ok(config
+        .sources
+        .iter()
+        .map(|source| {
+            let info = status.get(&source.name);
+            let diagnostics = health.get(&source.name);
+            let note = diagnostics.and_then(|d| {
+                d.checks
+                    .iter()
+                    .find(|c| c.status == CheckStatus::Fail)
+                    .or_else(|| d.checks.iter().find(|c| c.status == CheckStatus::Warn))
+                    .map(|c| format!("{}: {}", c.name, c.message))
+            });
+            SourcePanelRow {
+                name: source.name.clone(),
+                kind: source.source_type.to_string(),
+                grade: diagnostics.map(|d| d.grade),
+                last_sync: info.and_then(|i| i.last_sync),
+                last_result: info.map(|i| i.last_result.clone()).unwrap_or_default(),
+                note,
+            }
+        })
+        .collect())
+}
+
 /// Formats a timestamp as a relative time string ("2h ago", "3d ago", etc.)
 /// Falls back to absolute date for timestamps older than 30 days.
 fn format_relative_time(timestamp_ms: i64) -> String {
@@ -937,7 +974,9 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
         &[
             "F11 cycle source filter: all → local → remote → all".to_string(),
             "Shift+F11 opens source filter menu (select specific sources)".to_string(),
-            "Remote sessions show [source-name] in results list".to_string(),
+            "Ctrl+F11 toggles owner filter: my sessions ↔ everyone's (shared indexes)".to_string(),
+            "Remote sessions show [source-name] in results list; other users' show @owner"
+                .to_string(),
             "Setup: cass sources setup (interactive wizard with SSH discovery)".to_string(),
             "CLI: cass sources add|list|doctor|sync|mappings".to_string(),
             "Sync: rsync over SSH (delta transfers, additive-only for safety)".to_string(),
@@ -951,6 +990,18 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
                 "{} search mode: Lexical → Semantic → Hybrid",
                 shortcuts::SEARCH_MODE
             ),
+            format!(
+                "{} more like this: semantic neighbours of the selected hit (edit query to exit)",
+                shortcuts::MORE_LIKE_THIS
+            ),
+            format!(
+                "{} topics: browse session clusters, Enter lists a topic's sessions",
+                shortcuts::TOPICS
+            ),
+            format!(
+                "{} sources: last sync and `cass sources doctor` grade per source",
+                shortcuts::SOURCES
+            ),
             format!(
                 "{} match mode: prefix (default) ⇄ standard",
                 shortcuts::MATCH_MODE
@@ -1904,14 +1955,7 @@ fn state_path_for(data_dir: &std::path::Path) -> std::path::PathBuf {
 }
 
 fn ranking_from_str(s: &str) -> RankingMode {
-    match s {
-        "recent" => RankingMode::RecentHeavy,
-        "relevance" => RankingMode::RelevanceHeavy,
-        "quality" => RankingMode::MatchQualityHeavy,
-        "newest" => RankingMode::DateNewest,
-        "oldest" => RankingMode::DateOldest,
-        _ => RankingMode::Balanced,
-    }
+    RankingMode::parse(s).unwrap_or(RankingMode::Balanced)
 }
 
 fn search_mode_from_str(s: &str) -> SearchMode {
@@ -2057,6 +2101,15 @@ fn chips_for_filters(filters: &SearchFilters, palette: ThemePalette) -> Vec<Span
             spans.push(Span::raw(" ".to_string()));
         }
     }
+    if !filters.owners.is_empty() {
+        let mut owners: Vec<_> = filters.owners.iter().cloned().collect();
+        owners.sort();
+        spans.push(Span::styled(
+            format!("[owner:{}]", owners.join("|")),
+            Style::default().fg(palette.accent),
+        ));
+        spans.push(Span::raw(" ".to_string()));
+    }
     spans
 }
 
@@ -2607,7 +2660,14 @@ pub fn run_tui(
     }
 
     let mut query = String::new();
-    let mut filters = SearchFilters::default();
+    // Shared indexes: whose sessions are "mine", and whether others are hidden by default
+    let owner_config = SourcesConfig::load().unwrap_or_default();
+    let local_owner = owner_config.local_owner();
+    let default_filters = SearchFilters {
+        owners: owner_config.default_owner().into_iter().collect(),
+        ..SearchFilters::default()
+    };
+    let mut filters = default_filters.clone();
     let mut input_mode = InputMode::Query;
     let mut input_buffer = String::new();
     let page_size: usize = 120;
@@ -2705,6 +2765,7 @@ pub fn run_tui(
     let mut bulk_action_idx: usize = 0;
     // Model download consent dialog state
     let mut show_consent_dialog = false;
+    let mut consent_model = active_model_manifest(&data_dir);
     // Model download state
     let mut download_rx: Option<mpsc::Receiver<DownloadProgress>> = None;
     let mut download_cancel: Option<Arc<AtomicBool>> = None;
@@ -2715,6 +2776,8 @@ pub fn run_tui(
     let mut cached_detail: Option<(String, ConversationView)> = None;
     let mut detail_find: Option<DetailFindState> = None;
     let mut last_query = String::new();
+    // "More like this" anchor plus the query it was started from; editing the query ends it.
+    let mut similar_anchor: Option<(SimilarAnchor, String)> = None;
     let mut needs_draw = true;
     // Load query history from persisted state, or start fresh
     let mut query_history: VecDeque<String> = persisted
@@ -2806,6 +2869,16 @@ pub fn run_tui(
     // Available source IDs discovered from index (populated on menu open)
     let mut available_source_ids: Vec<String> = Vec::new();
 
+    // Topics browser state (clusters computed lazily on first open)
+    let mut topics_menu_open = false;
+    let mut topics_menu_selection: usize = 0;
+    let mut topics_report: Option<TopicsReport> = None;
+
+    // Sources panel state (reloaded from disk on every open)
+    let mut sources_panel_open = false;
+    let mut sources_panel_selection: usize = 0;
+    let mut sources_panel_rows: Vec<SourcePanelRow> = Vec::new();
+
     // Keep a short history of indexer percentages for sparkline rendering
     let mut progress_history: std::collections::VecDeque<u8> =
         std::collections::VecDeque::with_capacity(24);
@@ -3444,6 +3517,28 @@ pub fn run_tui(
                                             .add_modifier(Modifier::ITALIC),
                                     ));
                                 }
+                                // Sessions of other users in a shared index
+                                if let Some(owner) = hit.owner.as_deref()
+                                    && local_owner.as_deref() != Some(owner)
+                                {
+                                    location_spans.push(Span::styled(
+                                        format!(" @{owner}"),
+                                        Style::default().fg(palette.accent),
+                                    ));
+                                }
+                                // Same session synced in from other sources too
+                                let also_in: Vec<&str> = hit
+                                    .seen_in
+                                    .iter()
+                                    .filter(|s| **s != hit.source_id)
+                                    .map(String::as_str)
+                                    .collect();
+                                if !also_in.is_empty() {
+                                    location_spans.push(Span::styled(
+                                        format!(" (also: {})", also_in.join(", ")),
+                                        Style::default().fg(palette.hint),
+                                    ));
+                                }
                                 let location_line = Line::from(location_spans);
 
                                 // Snippet with enhanced highlighting (multiple lines if long)
@@ -3777,6 +3872,12 @@ pub fn run_tui(
                         Span::styled("Source: ", Style::default().fg(palette.hint)),
                         Span::raw(truncate_path(&hit.source_path, 60)),
                     ]));
+                    if let Some(owner) = &hit.owner {
+                        meta_lines.push(Line::from(vec![
+                            Span::styled("Owner: ", Style::default().fg(palette.hint)),
+                            Span::raw(owner.clone()),
+                        ]));
+                    }
                     meta_lines.push(Line::from(vec![
                         Span::styled("Score: ", Style::default().fg(palette.hint)),
                         Span::raw(format!("{:.2}", hit.score)),
@@ -4385,14 +4486,19 @@ pub fn run_tui(
                         .style(Style::default().bg(palette.surface));
 
                     // Build dialog content
+                    let size_mb = consent_model.total_size().div_ceil(1_048_576);
                     let content = vec![
                         Line::from(""),
                         Line::from(Span::styled(
-                            "Semantic search requires a 23MB model download",
+                            if size_mb > 0 {
+                                format!("Semantic search requires a {size_mb}MB model download")
+                            } else {
+                                "Semantic search requires a model download".to_string()
+                            },
                             Style::default().fg(palette.fg),
                         )),
                         Line::from(Span::styled(
-                            "from HuggingFace (all-MiniLM-L6-v2).",
+                            format!("from HuggingFace ({}).", consent_model.id),
                             Style::default().fg(palette.fg),
                         )),
                         Line::from(""),
@@ -4515,6 +4621,132 @@ pub fn run_tui(
                     f.render_widget(list, area);
                 }
 
+                // Topics browser popup
+                if topics_menu_open && let Some(report) = &topics_report {
+                    let area = centered_rect(70, 60, f.area());
+                    let block = Block::default()
+                        .title(Span::styled(
+                            format!(
+                                " Topics ({} sessions) · Enter: show members · Esc: close ",
+                                report.sessions
+                            ),
+                            Style::default()
+                                .fg(palette.accent)
+                                .add_modifier(Modifier::BOLD),
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(palette.accent))
+                        .style(Style::default().bg(palette.surface));
+
+                    let items: Vec<ListItem> = report
+                        .topics
+                        .iter()
+                        .enumerate()
+                        .map(|(i, topic)| {
+                            let is_selected = i == topics_menu_selection;
+                            let style = if is_selected {
+                                Style::default()
+                                    .bg(palette.accent)
+                                    .fg(palette.bg)
+                                    .add_modifier(Modifier::BOLD)
+                            } else {
+                                Style::default().fg(palette.fg)
+                            };
+                            ListItem::new(Line::from(vec![
+                                Span::styled(if is_selected { "→ " } else { "  " }, style),
+                                Span::styled(format!("{:>4}  ", topic.size), style),
+                                Span::styled(topic.label.clone(), style),
+                            ]))
+                        })
+                        .collect();
+
+                    let mut list_state = ratatui::widgets::ListState::default()
+                        .with_selected(Some(topics_menu_selection));
+                    let list = List::new(items).block(block);
+                    f.render_widget(ratatui::widgets::Clear, area);
+                    f.render_stateful_widget(list, area, &mut list_state);
+                }
+
+                // Sources panel popup
+                if sources_panel_open {
+                    use crate::ui::components::theme::colors;
+
+                    let area = centered_rect(80, 60, f.area());
+                    let block = Block::default()
+                        .title(Span::styled(
+                            format!(
+                                " Sources ({}) · grades from `cass sources doctor` · Esc: close ",
+                                sources_panel_rows.len()
+                            ),
+                            Style::default()
+                                .fg(palette.accent)
+                                .add_modifier(Modifier::BOLD),
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(palette.accent))
+                        .style(Style::default().bg(palette.surface));
+
+                    let items: Vec<ListItem> = sources_panel_rows
+                        .iter()
+                        .enumerate()
+                        .map(|(i, row)| {
+                            let is_selected = i == sources_panel_selection;
+                            let style = if is_selected {
+                                Style::default()
+                                    .bg(palette.accent)
+                                    .fg(palette.bg)
+                                    .add_modifier(Modifier::BOLD)
+                            } else {
+                                Style::default().fg(palette.fg)
+                            };
+                            let (grade, grade_color) = match row.grade {
+                                Some(Grade::Healthy) => ("healthy", colors::STATUS_SUCCESS),
+                                Some(Grade::Degraded) => ("degraded", colors::STATUS_WARNING),
+                                Some(Grade::Failing) => ("failing", colors::STATUS_ERROR),
+                                None => ("unchecked", palette.hint),
+                            };
+                            let grade_style = if is_selected {
+                                style
+                            } else {
+                                Style::default().fg(grade_color)
+                            };
+                            let synced = match (row.last_sync, &row.last_result) {
+                                (None, _) => "never synced".to_string(),
+                                (Some(ts), SyncResult::Failed(_)) => {
+                                    format!("sync failed {}", format_relative_time(ts))
+                                }
+                                (Some(ts), _) => format!("synced {}", format_relative_time(ts)),
+                            };
+                            let mut spans = vec![
+                                Span::styled(if is_selected { "→ " } else { "  " }, style),
+                                Span::styled(format!("{:<16} ", row.name), style),
+                                Span::styled(format!("{:<8} ", row.kind), style),
+                                Span::styled(format!("{grade:<10} "), grade_style),
+                                Span::styled(format!("{synced:<20}"), style),
+                            ];
+                            if let Some(note) = &row.note {
+                                spans.push(Span::styled(
+                                    format!("  {note}"),
+                                    if is_selected {
+                                        style
+                                    } else {
+                                        Style::default().fg(palette.hint)
+                                    },
+                                ));
+                            }
+                            ListItem::new(Line::from(spans))
+                        })
+                        .collect();
+
+                    let mut list_state = ratatui::widgets::ListState::default()
+                        .with_selected(Some(sources_panel_selection));
+                    let list = List::new(items).block(block);
+                    f.render_widget(ratatui::widgets::Clear, area);
+                    f.render_stateful_widget(list, area, &mut list_state);
+                }
+
                 if palette_state.open {
                     let area = centered_rect(70, 60, f.area());
                     palette::draw_palette(f, area, &palette_state, palette);
@@ -4541,7 +4773,13 @@ pub fn run_tui(
             // Handle mouse events (skip when modal is open)
             if let Event::Mouse(mouse) = event {
                 // Ignore mouse events when help, detail, bulk, or source filter modal is open
-                if show_help || show_detail_modal || show_bulk_modal || source_filter_menu_open {
+                if show_help
+                    || show_detail_modal
+                    || show_bulk_modal
+                    || source_filter_menu_open
+                    || topics_menu_open
+                    || sources_panel_open
+                {
                     continue;
                 }
                 needs_draw = true;
@@ -4893,8 +5131,8 @@ pub fn run_tui(
                     KeyCode::Char('d' | 'D') => {
                         show_consent_dialog = false;
                         // Start model download in background
-                        let model_dir = default_model_dir(&data_dir);
-                        let manifest = ModelManifest::minilm_v2();
+                        let manifest = consent_model.clone();
+                        let model_dir = manifest.model_dir(&data_dir);
                         let total_size = manifest.total_size();
                         let total_files = manifest.files.len();
                         let downloader = ModelDownloader::new(model_dir);
@@ -5229,6 +5467,76 @@ pub fn run_tui(
                 continue;
             }
 
+            // Topics browser: handle keys when open
+            if topics_menu_open {
+                let total_items = topics_report.as_ref().map_or(0, |r| r.topics.len());
+                match key.code {
+                    KeyCode::Esc => {
+                        topics_menu_open = false;
+                        status = "Topics closed".to_string();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        topics_menu_selection = topics_menu_selection.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        topics_menu_selection =
+                            (topics_menu_selection + 1).min(total_items.saturating_sub(1));
+                    }
+                    KeyCode::Enter => {
+                        if let Some(topic) = topics_report
+                            .as_ref()
+                            .and_then(|r| r.topics.get(topics_menu_selection))
+                        {
+                            // Drill in: show the topic's sessions as the result list.
+                            topics_menu_open = false;
+                            results = topic.members.iter().map(|m| m.to_search_hit()).collect();
+                            panes = rebuild_panes_with_filter(
+                                &results,
+                                None,
+                                per_pane_limit,
+                                &mut active_pane,
+                                &mut pane_scroll_offset,
+                                None,
+                                None,
+                                MAX_VISIBLE_PANES,
+                            );
+                            pane_filter = None;
+                            selected.clear();
+                            cached_detail = None;
+                            detail_scroll = 0;
+                            dirty_since = None;
+                            status = format!(
+                                "Topic {}: {} ({} sessions) · type to search again",
+                                topic.id, topic.label, topic.size
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+                needs_draw = true;
+                continue;
+            }
+
+            // Sources panel: read-only, arrows move and Esc closes
+            if sources_panel_open {
+                match key.code {
+                    KeyCode::Esc => {
+                        sources_panel_open = false;
+                        status = "Sources closed".to_string();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        sources_panel_selection = sources_panel_selection.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        sources_panel_selection = (sources_panel_selection + 1)
+                            .min(sources_panel_rows.len().saturating_sub(1));
+                    }
+                    _ => {}
+                }
+                needs_draw = true;
+                continue;
+            }
+
             // While help is open, keys scroll the help modal and do not affect panes.
             if show_help {
                 match key.code {
@@ -5598,6 +5906,7 @@ pub fn run_tui(
                         // Check if model needs to be installed - show consent dialog
                         if semantic_availability.is_not_installed() {
                             show_consent_dialog = true;
+                            consent_model = active_model_manifest(&data_dir);
                             status = "Model not installed. Press D to download, H for hash mode, Esc to cancel.".to_string();
                         } else {
                             let reason = semantic_unavailable_message(&semantic_availability);
@@ -5616,6 +5925,86 @@ pub fn run_tui(
                 continue;
             }
 
+            // Topics browser (Alt+T): cluster sessions by embedding
+            if matches!(key.code, KeyCode::Char('t' | 'T'))
+                && key.modifiers.contains(KeyModifiers::ALT)
+            {
+                if topics_report.is_none() {
+                    match build_topics(&data_dir, &db_path, &TopicOptions::default()) {
+                        Ok(report) if !report.topics.is_empty() => topics_report = Some(report),
+                        Ok(_) => {
+                            status = "Topics: no embedded sessions to cluster".to_string();
+                            continue;
+                        }
+                        Err(err) => {
+                            status = format!("Topics unavailable: {err}");
+                            continue;
+                        }
+                    }
+                }
+                topics_menu_open = true;
+                topics_menu_selection = 0;
+                status = "Topics: ↑/↓ select, Enter show members, Esc close".to_string();
+                continue;
+            }
+
+            // Sources panel (Alt+R): sync age and doctor grade per source
+            if matches!(key.code, KeyCode::Char('r' | 'R'))
+                && key.modifiers.contains(KeyModifiers::ALT)
+            {
+                match load_source_panel_rows(&data_dir) {
+                    Ok(rows) if !rows.is_empty() => sources_panel_rows = rows,
+                    Ok(_) => {
+                        status = "Sources: none configured (cass sources add)".to_string();
+                        continue;
+                    }
+                    Err(err) => {
+                        status = format!("Sources unavailable: {err}");
+                        continue;
+                    }
+                }
+                sources_panel_open = true;
+                sources_panel_selection = 0;
+                status =
+                    "Sources: run `cass sources doctor` to refresh grades, Esc close".to_string();
+                continue;
+            }
+
+            // More like this (Alt+M): semantic neighbours of the selected hit
+            if matches!(key.code, KeyCode::Char('m' | 'M'))
+                && key.modifiers.contains(KeyModifiers::ALT)
+            {
+                let Some(hit) = active_hit(&panes, active_pane).cloned() else {
+                    status = "Select a result first for more like this".to_string();
+                    continue;
+                };
+                if let Some(client) = &search_client
+                    && !semantic_availability.is_ready()
+                {
+                    semantic_availability =
+                        initialize_semantic_context(client, &data_dir, &db_path);
+                }
+                if !semantic_availability.is_ready() {
+                    let reason = semantic_unavailable_message(&semantic_availability);
+                    status = format!("More like this needs semantic search: {reason}");
+                    continue;
+                }
+                similar_anchor = Some((
+                    SimilarAnchor {
+                        source_path: hit.source_path.clone(),
+                        line_number: hit.line_number,
+                    },
+                    query.clone(),
+                ));
+                status = format!("More like this: {}", hit.title);
+                page = 0;
+                active_pane = 0;
+                cached_detail = None;
+                detail_scroll = 0;
+                dirty_since = Some(Instant::now());
+                continue;
+            }
+
             match input_mode {
                 InputMode::Query => {
                     if key.modifiers.contains(KeyModifiers::CONTROL) {
@@ -6170,6 +6559,20 @@ pub fn run_tui(
                             }
                             needs_draw = true;
                         }
+                        // Ctrl+F11: Toggle between my sessions and everyone's (shared indexes)
+                        KeyCode::F(11) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            if !filters.owners.is_empty() {
+                                filters.owners.clear();
+                                status = "Owner: everyone".into();
+                            } else if let Some(owner) = local_owner.clone() {
+                                status = format!("Owner: mine ({owner})");
+                                filters.owners.insert(owner);
+                            } else {
+                                status = "Owner: unknown local user (set `owner` in sources.toml)"
+                                    .into();
+                            }
+                            dirty_since = Some(Instant::now());
+                        }
                         // F11: Cycle through source filters (P4.3)
                         KeyCode::F(11) => {
                             use crate::sources::provenance::SourceFilter;
@@ -6220,7 +6623,7 @@ pub fn run_tui(
                             density_mode = DensityMode::Cozy;
                             let height = terminal.size().map(|r| r.height).unwrap_or(24);
                             per_pane_limit = calculate_pane_limit(height, density_mode);
-                            filters = SearchFilters::default();
+                            filters = default_filters.clone();
                             pane_filter = None;
                             page = 0;
                             active_pane = 0;
@@ -6249,7 +6652,7 @@ pub fn run_tui(
                             needs_draw = true;
                         }
                         KeyCode::Delete if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            filters = SearchFilters::default();
+                            filters = default_filters.clone();
                             pane_filter = None;
                             page = 0;
                             status = format!(
@@ -7068,10 +7471,28 @@ pub fn run_tui(
                         let reason = semantic_unavailable_message(&semantic_availability);
                         status = format!("Semantic unavailable: {reason}. Using lexical.");
                     }
+                    // Any query edit since Alt+M drops back to a normal search
+                    if similar_anchor
+                        .as_ref()
+                        .is_some_and(|(_, started_from)| *started_from != query)
+                    {
+                        similar_anchor = None;
+                    }
                     // Track effective search mode for ranking (bead vq8v)
                     let mut effective_search_mode = SearchMode::Lexical;
-                    let search_result = match search_mode {
-                        SearchMode::Hybrid if use_semantic => {
+                    let search_result = match (search_mode, &similar_anchor) {
+                        (_, Some((anchor, _))) => client
+                            .search_similar(anchor, filters.clone(), page_size, page * page_size)
+                            .map(|hits| {
+                                effective_search_mode = SearchMode::Semantic;
+                                crate::search::query::SearchResult {
+                                    hits,
+                                    wildcard_fallback: false,
+                                    cache_stats: CacheStats::default(),
+                                    suggestions: Vec::new(),
+                                }
+                            }),
+                        (SearchMode::Hybrid, None) if use_semantic => {
                             match client.search_hybrid(
                                 &lexical_query,
                                 &semantic_query,
@@ -7079,6 +7500,7 @@ pub fn run_tui(
                                 page_size,
                                 page * page_size,
                                 SPARSE_THRESHOLD,
+                                &FusionConfig::default(),
                             ) {
                                 Ok(result) => {
                                     effective_search_mode = SearchMode::Hybrid;
@@ -7103,7 +7525,7 @@ pub fn run_tui(
                                 }
                             }
                         }
-                        SearchMode::Semantic if use_semantic => {
+                        (SearchMode::Semantic, None) if use_semantic => {
                             match client.search_semantic(
                                 &semantic_query,
                                 filters.clone(),
@@ -7177,7 +7599,8 @@ pub fn run_tui(
                             let use_recent_fallback = hits.is_empty()
                                 && page == 0
                                 && !query.trim().is_empty()
-                                && pane_filter.is_none();
+                                && pane_filter.is_none()
+                                && similar_anchor.is_none();
 
                             if hits.is_empty() && page > 0 {
                                 page = page.saturating_sub(1);
@@ -7247,120 +7670,11 @@ pub fn run_tui(
                                 needs_draw = true;
                             } else {
                                 results = hits;
-                                let max_created = results
-                                    .iter()
-                                    .filter_map(|h| h.created_at)
-                                    .max()
-                                    .unwrap_or(0)
-                                    as f32;
-                                // Handle pure date sorting modes separately
-                                if matches!(
+                                apply_ranking_mode(
+                                    &mut results,
+                                    effective_search_mode,
                                     ranking_mode,
-                                    RankingMode::DateNewest | RankingMode::DateOldest
-                                ) {
-                                    results.sort_by(|a, b| {
-                                        let ts_a = a.created_at.unwrap_or(0);
-                                        let ts_b = b.created_at.unwrap_or(0);
-                                        if matches!(ranking_mode, RankingMode::DateNewest) {
-                                            ts_b.cmp(&ts_a) // Descending (newest first)
-                                        } else {
-                                            ts_a.cmp(&ts_b) // Ascending (oldest first)
-                                        }
-                                    });
-                                } else {
-                                    // RankingMode support for all search modes (bead vq8v)
-                                    // Recency helper (shared across all modes)
-                                    let recency = |h: &SearchHit| -> f32 {
-                                        if max_created <= 0.0 {
-                                            return 0.0;
-                                        }
-                                        h.created_at.map_or(0.0, |v| v as f32 / max_created)
-                                    };
-
-                                    match effective_search_mode {
-                                        SearchMode::Lexical => {
-                                            // Lexical: BM25 score * quality_factor + alpha * recency
-                                            let alpha = match ranking_mode {
-                                                RankingMode::RecentHeavy => 1.0,
-                                                RankingMode::Balanced => 0.4,
-                                                RankingMode::RelevanceHeavy => 0.1,
-                                                RankingMode::MatchQualityHeavy => 0.2,
-                                                RankingMode::DateNewest
-                                                | RankingMode::DateOldest => unreachable!(),
-                                            };
-                                            // Per-hit quality factor based on match_type
-                                            //   Exact: 1.0, Prefix: 0.9, Suffix: 0.8,
-                                            //   Substring: 0.7, ImplicitWildcard: 0.6
-                                            let quality_factor = |h: &SearchHit| -> f32 {
-                                                h.match_type.quality_factor()
-                                            };
-                                            results.sort_by(|a, b| {
-                                                let score_a = (a.score * quality_factor(a))
-                                                    + alpha * recency(a);
-                                                let score_b = (b.score * quality_factor(b))
-                                                    + alpha * recency(b);
-                                                score_b
-                                                    .partial_cmp(&score_a)
-                                                    .unwrap_or(std::cmp::Ordering::Equal)
-                                            });
-                                        }
-                                        SearchMode::Semantic => {
-                                            // Semantic: normalize similarity [-1,1] -> [0,1]
-                                            // Then apply weighted blend (per bead vq8v spec)
-                                            let (score_weight, recency_weight) = match ranking_mode
-                                            {
-                                                RankingMode::RecentHeavy => (0.3, 0.7),
-                                                RankingMode::Balanced => (0.5, 0.5),
-                                                RankingMode::RelevanceHeavy => (0.8, 0.2),
-                                                RankingMode::MatchQualityHeavy => (0.85, 0.15),
-                                                RankingMode::DateNewest
-                                                | RankingMode::DateOldest => unreachable!(),
-                                            };
-                                            let norm_score = |h: &SearchHit| (h.score + 1.0) / 2.0;
-                                            results.sort_by(|a, b| {
-                                                let score_a = score_weight * norm_score(a)
-                                                    + recency_weight * recency(a);
-                                                let score_b = score_weight * norm_score(b)
-                                                    + recency_weight * recency(b);
-                                                score_b
-                                                    .partial_cmp(&score_a)
-                                                    .unwrap_or(std::cmp::Ordering::Equal)
-                                            });
-                                        }
-                                        SearchMode::Hybrid => {
-                                            // Hybrid: RRF scores normalized to [0,1], then weighted blend
-                                            let max_rrf = results
-                                                .iter()
-                                                .map(|h| h.score)
-                                                .fold(0.0f32, f32::max);
-                                            let (score_weight, recency_weight) = match ranking_mode
-                                            {
-                                                RankingMode::RecentHeavy => (0.3, 0.7),
-                                                RankingMode::Balanced => (0.5, 0.5),
-                                                RankingMode::RelevanceHeavy => (0.8, 0.2),
-                                                RankingMode::MatchQualityHeavy => (0.85, 0.15),
-                                                RankingMode::DateNewest
-                                                | RankingMode::DateOldest => unreachable!(),
-                                            };
-                                            let norm_score = |h: &SearchHit| {
-                                                if max_rrf > 0.0 {
-                                                    h.score / max_rrf
-                                                } else {
-                                                    0.0
-                                                }
-                                            };
-                                            results.sort_by(|a, b| {
-                                                let score_a = score_weight * norm_score(a)
-                                                    + recency_weight * recency(a);
-                                                let score_b = score_weight * norm_score(b)
-                                                    + recency_weight * recency(b);
-                                                score_b
-                                                    .partial_cmp(&score_a)
-                                                    .unwrap_or(std::cmp::Ordering::Equal)
-                                            });
-                                        }
-                                    }
-                                }
+                                );
                                 panes = rebuild_panes_with_filter(
                                     &results,
                                     pane_filter.as_deref(),
@@ -7391,6 +7705,9 @@ pub fn run_tui(
                                 } else {
                                     format!("{} results across {} agents", total_hits, panes.len())
                                 };
+                                if similar_anchor.is_some() {
+                                    status = format!("More like this: {status}");
+                                }
                                 // Query history is now saved only on explicit commit actions
                                 // (Enter on result, F8 editor, y copy) via save_query_to_history()
                                 history_cursor = None;
@@ -7399,6 +7716,7 @@ pub fn run_tui(
                         }
                         Err(err) => {
                             dirty_since = None;
+                            similar_anchor = None;
                             status = "Search error (see footer).".to_string();
                             tracing::warn!("search error: {err}");
                             results.clear();
@@ -7605,14 +7923,7 @@ pub fn run_tui(
         ),
         // Persist pane count & ranking mode (bead 46t.1)
         per_pane_limit: Some(per_pane_limit),
-        ranking_mode: Some(match ranking_mode {
-            RankingMode::RecentHeavy => "recent".into(),
-            RankingMode::RelevanceHeavy => "relevance".into(),
-            RankingMode::MatchQualityHeavy => "quality".into(),
-            RankingMode::DateNewest => "newest".into(),
-            RankingMode::DateOldest => "oldest".into(),
-            RankingMode::Balanced => "balanced".into(),
-        }),
+        ranking_mode: Some(ranking_mode.as_str().into()),
     };
     save_state(&state_path, &persisted_out);
 
@@ -7789,6 +8100,7 @@ mod tests {
             messages: Vec::new(),
             source_id: "local".to_string(),
             origin_host: None,
+            owner: None,
         };
 
         let message = Message {
@@ -7909,6 +8221,9 @@ mod tests {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         }
     }
 
@@ -571,6 +571,61 @@ fn search_error_writes_trace() {
     assert_eq!(json["contract_version"], "1");
 }
 
+#[test]
+fn search_fusion_flags_are_validated() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().to_str().unwrap();
+
+    // Out-of-range weight is a usage error before the index is opened
+    let mut cmd = base_cmd();
+    cmd.args([
+        "search",
+        "foo",
+        "--json",
+        "--semantic-weight",
+        "1.5",
+        "--data-dir",
+        data_dir,
+    ]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(contains("--semantic-weight must be between 0.0 and 1.0"));
+
+    // Fusion flags only make sense for hybrid search
+    let mut cmd = base_cmd();
+    cmd.args([
+        "search",
+        "foo",
+        "--json",
+        "--mode",
+        "lexical",
+        "--fusion",
+        "auto",
+        "--data-dir",
+        data_dir,
+    ]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(contains("only apply to hybrid search"));
+
+    let mut cmd = base_cmd();
+    cmd.args([
+        "search",
+        "foo",
+        "--json",
+        "--rrf-k",
+        "0",
+        "--data-dir",
+        data_dir,
+    ]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(contains("--rrf-k must be a positive number"));
+}
+
 // ============================================================
 // yln.5: E2E Search Tests with Fixture Data
 // ============================================================
@@ -1,6 +1,9 @@
 use coding_agent_search::encryption::{
-    Argon2Params, aes_gcm_decrypt, aes_gcm_encrypt, argon2id_hash, hkdf_expand,
+    Argon2Params, aes_gcm_decrypt, aes_gcm_encrypt, argon2id_hash, hkdf_expand, x25519_public_key,
+    x25519_shared_secret,
 };
+use coding_agent_search::pages::encrypt::derive_kek_x25519;
+use coding_agent_search::pages::recipient::{Identity, encode_recipient, parse_recipient};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -37,6 +40,32 @@ struct HkdfVector {
     expected_okm: String,
 }
 
+#[derive(Deserialize)]
+struct X25519Vector {
+    name: String,
+    secret: String,
+    public: String,
+    peer_public: String,
+    shared: String,
+}
+
+#[derive(Deserialize)]
+struct RecipientWrapVector {
+    name: String,
+    recipient_secret: String,
+    recipient: String,
+    identity: String,
+    ephemeral_secret: String,
+    ephemeral_public: String,
+    shared: String,
+    kek: String,
+    export_id: String,
+    slot_id: u8,
+    dek: String,
+    nonce: String,
+    wrapped_dek: String,
+}
+
 fn load_test_vectors<T: for<'de> Deserialize<'de>>(filename: &str) -> Vec<T> {
     let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests/vectors")
@@ -106,3 +135,105 @@ fn test_hkdf_vectors() {
         assert_eq!(result, expected, "HKDF mismatch for {}", v.name);
     }
 }
+
+#[test]
+fn test_x25519_vectors() {
+    let vectors: Vec<X25519Vector> = load_test_vectors("x25519.yaml");
+    for v in vectors {
+        let secret = hex::decode(&v.secret).unwrap();
+        let peer_public = hex::decode(&v.peer_public).unwrap();
+
+        let public = x25519_public_key(&secret).unwrap();
+        assert_eq!(
+            hex::encode(public),
+            v.public,
+            "Public key mismatch for {}",
+            v.name
+        );
+
+        let shared = x25519_shared_secret(&secret, &peer_public).unwrap();
+        assert_eq!(
+            hex::encode(shared),
+            v.shared,
+            "Shared secret mismatch for {}",
+            v.name
+        );
+    }
+}
+
+fn hex32(s: &str) -> [u8; 32] {
+    hex::decode(s).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn test_pages_recipient_wrap_vectors() {
+    let vectors: Vec<RecipientWrapVector> = load_test_vectors("pages_recipient.yaml");
+    for v in vectors {
+        let recipient_public: [u8; 32] =
+            x25519_public_key(&hex::decode(&v.recipient_secret).unwrap())
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let identity = Identity::from_bytes(hex32(&v.recipient_secret));
+        assert_eq!(
+            identity.to_secret_string(),
+            v.identity,
+            "Identity mismatch for {}",
+            v.name
+        );
+        assert_eq!(
+            encode_recipient(&recipient_public),
+            v.recipient,
+            "Recipient mismatch for {}",
+            v.name
+        );
+        assert_eq!(parse_recipient(&v.recipient).unwrap(), recipient_public);
+        assert_eq!(
+            Identity::parse(&v.identity).unwrap().public_bytes(),
+            recipient_public
+        );
+
+        // Sender side: ephemeral secret agreed with the recipient public key
+        let ephemeral_public = hex32(&v.ephemeral_public);
+        let ephemeral_secret = hex::decode(&v.ephemeral_secret).unwrap();
+        assert_eq!(
+            x25519_public_key(&ephemeral_secret).unwrap(),
+            ephemeral_public
+        );
+        let shared = x25519_shared_secret(&ephemeral_secret, &recipient_public).unwrap();
+        assert_eq!(
+            hex::encode(&shared),
+            v.shared,
+            "Shared secret mismatch for {}",
+            v.name
+        );
+
+        // Recipient side reaches the same secret from the stored ephemeral key
+        assert_eq!(
+            identity.shared_secret(&ephemeral_public).unwrap().to_vec(),
+            shared
+        );
+
+        let kek =
+            derive_kek_x25519(&hex32(&v.shared), &ephemeral_public, &recipient_public).unwrap();
+        assert_eq!(
+            hex::encode(kek.as_bytes()),
+            v.kek,
+            "KEK mismatch for {}",
+            v.name
+        );
+
+        let mut aad = hex::decode(&v.export_id).unwrap();
+        aad.push(v.slot_id);
+        let nonce = hex::decode(&v.nonce).unwrap();
+        let dek = hex::decode(&v.dek).unwrap();
+        let (ciphertext, tag) = aes_gcm_encrypt(kek.as_bytes(), &nonce, &dek, &aad).unwrap();
+        let wrapped = [ciphertext.as_slice(), tag.as_slice()].concat();
+        assert_eq!(
+            hex::encode(&wrapped),
+            v.wrapped_dek,
+            "Wrapped DEK mismatch for {}",
+            v.name
+        );
+    }
+}
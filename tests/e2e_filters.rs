@@ -899,6 +899,82 @@ fn filter_by_source_remote_empty() {
     );
 }
 
+// =============================================================================
+// Owner filter tests (--owner / hide_other_owners)
+// =============================================================================
+
+/// Test: local sessions belong to the configured owner; hide_other_owners
+/// limits searches to them unless --owner/--all-owners says otherwise
+#[test]
+fn filter_by_owner_and_hide_other_owners() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let config_home = home.join("config");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    let write_config = |owner: &str| {
+        let path = config_home.join("cass").join("sources.toml");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            path,
+            format!("owner = \"{owner}\"\nhide_other_owners = true\n"),
+        )
+        .unwrap();
+    };
+    write_config("alice");
+
+    make_codex_session_at(
+        &codex_home,
+        "2024/11/20",
+        "rollout-1.jsonl",
+        "shared ownertest",
+        1732118400000,
+    );
+
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .assert()
+        .success();
+
+    let search = |extra: &[&str]| -> Vec<serde_json::Value> {
+        let output = cargo_bin_cmd!("cass")
+            .args(["search", "ownertest", "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .args(extra)
+            .env("HOME", home)
+            .env("CODEX_HOME", &codex_home)
+            .env("XDG_CONFIG_HOME", &config_home)
+            .output()
+            .expect("search command");
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+        json.get("hits")
+            .and_then(|h| h.as_array())
+            .cloned()
+            .expect("hits array")
+    };
+
+    let hits = search(&[]);
+    assert!(!hits.is_empty(), "Owner's own sessions should be shown");
+    assert_eq!(hits[0]["owner"], "alice");
+    assert!(search(&["--owner", "bob"]).is_empty());
+
+    // As another user, alice's sessions are hidden unless asked for
+    write_config("bob");
+    assert!(search(&[]).is_empty(), "Other owners should be hidden");
+    assert!(!search(&["--all-owners"]).is_empty());
+    assert!(!search(&["--owner", "alice"]).is_empty());
+}
+
 /// Test: search --source all returns all sources (explicit)
 #[test]
 fn filter_by_source_all_explicit() {
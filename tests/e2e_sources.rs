@@ -6,6 +6,7 @@
 //! - sources remove
 //! - sources doctor (limited without actual SSH)
 //! - sources sync (dry-run only)
+//! - sources mappings (list/add/remove/test/suggest)
 //!
 //! Note: Tests that require actual SSH connectivity are marked #[ignore].
 
@@ -658,6 +659,72 @@ paths = ["~/.claude/projects"]
     }
 }
 
+/// Test: sources doctor grades a source and --fix prunes stale mirror entries.
+#[test]
+fn sources_doctor_fix_prunes_stale_mirror() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    let data_dir = tmp.path().join("data");
+    create_sources_config(
+        &config_dir,
+        r#"
+[[sources]]
+name = "ci"
+type = "push"
+token_sha256 = "0000000000000000000000000000000000000000000000000000000000000000"
+"#,
+    );
+    let mirror = data_dir.join("remotes/ci/mirror");
+    fs::create_dir_all(mirror.join("files")).unwrap();
+    fs::create_dir_all(mirror.join("git")).unwrap();
+
+    let doctor = |fix: bool| {
+        let mut cmd = cargo_bin_cmd!("cass");
+        cmd.args(["sources", "doctor", "--json"])
+            .env("XDG_CONFIG_HOME", &config_dir)
+            .env("CASS_DATA_DIR", &data_dir);
+        if fix {
+            cmd.arg("--fix");
+        }
+        let output = cmd.output().expect("sources doctor command");
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("valid JSON output");
+        json[0].clone()
+    };
+    let check = |diag: &serde_json::Value, name: &str| {
+        diag["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == name)
+            .cloned()
+            .unwrap()
+    };
+
+    // The git checkout left from an earlier source type is stale
+    let diag = doctor(false);
+    assert_eq!(diag["grade"], "degraded");
+    let mirror_check = check(&diag, "Mirror Directory");
+    assert_eq!(mirror_check["status"], "warn");
+    assert_eq!(mirror_check["repair"]["action"], "prune_mirror");
+    assert!(
+        mirror.join("git").exists(),
+        "doctor without --fix deleted data"
+    );
+
+    let diag = doctor(true);
+    assert_eq!(diag["repairs"][0]["success"], true);
+    assert_eq!(diag["grade"], "healthy");
+    assert_eq!(check(&diag, "Mirror Directory")["status"], "pass");
+    assert!(!mirror.join("git").exists());
+    assert!(mirror.join("files").exists());
+
+    // The latest results are kept for the TUI sources panel
+    let health: serde_json::Value =
+        serde_json::from_slice(&fs::read(data_dir.join("source_health.json")).unwrap()).unwrap();
+    assert_eq!(health["sources"]["ci"]["grade"], "healthy");
+}
+
 // =============================================================================
 // sources sync tests
 // =============================================================================
@@ -823,6 +890,99 @@ paths = ["~/.claude/projects"]
     }
 }
 
+/// Test: sources sync --dry-run previews the source's filter rules.
+#[test]
+fn sources_sync_dry_run_previews_filters() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    let data_dir = tmp.path().join("data");
+    create_sources_config(
+        &config_dir,
+        r#"
+[[sources]]
+name = "laptop"
+type = "ssh"
+host = "user@laptop.local"
+paths = ["~/.claude/projects", "~/.codex/sessions", "~/logs"]
+agents = ["codex"]
+include = ["*.jsonl"]
+exclude = ["debug/"]
+max_file_size = "10M"
+max_age_days = 14
+"#,
+    );
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["sources", "sync", "--json", "--dry-run"])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env("CASS_DATA_DIR", &data_dir)
+        .output()
+        .expect("sources sync --dry-run command");
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("valid JSON output");
+    let source = &json["sources"][0];
+    assert_eq!(source["status"], "dry_run");
+    assert_eq!(
+        source["paths"],
+        serde_json::json!(["~/.codex/sessions", "~/logs"])
+    );
+    assert_eq!(
+        source["skipped_paths"],
+        serde_json::json!(["~/.claude/projects"])
+    );
+    assert_eq!(source["filter"]["max_age_days"], 14);
+    assert_eq!(
+        source["rsync_args"],
+        serde_json::json!([
+            "--exclude=debug",
+            "--include=*/",
+            "--include=*.jsonl",
+            "--exclude=*",
+            "--prune-empty-dirs",
+            "--max-size=10485760"
+        ])
+    );
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["sources", "sync", "--dry-run"])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env("CASS_DATA_DIR", &data_dir)
+        .output()
+        .expect("sources sync --dry-run command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("~/.claude/projects (agent not selected)"));
+    assert!(stdout.contains("Max age: 14 days"));
+}
+
+/// Test: invalid filter rules are rejected when the config is loaded.
+#[test]
+fn sources_sync_rejects_invalid_filter() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    create_sources_config(
+        &config_dir,
+        r#"
+[[sources]]
+name = "laptop"
+type = "ssh"
+host = "user@laptop.local"
+paths = ["~/.claude/projects"]
+max_file_size = "lots"
+"#,
+    );
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["sources", "sync", "--dry-run"])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env("CASS_DATA_DIR", tmp.path().join("data"))
+        .output()
+        .expect("sources sync command");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("max_file_size"), "stderr: {stderr}");
+}
+
 // =============================================================================
 // Integration workflow tests
 // =============================================================================
@@ -1655,3 +1815,131 @@ paths = ["~/.claude/projects"]
         "Mapping should be removed, got: {stdout}"
     );
 }
+
+/// Test: mappings suggest pairs a remote workspace with the local clone of
+/// the same repository and rewrites the indexed session on --apply.
+#[test]
+fn mappings_suggest_apply_rewrites_indexed_workspaces() {
+    use coding_agent_search::model::types::{Agent, AgentKind, Conversation};
+    use coding_agent_search::sources::provenance::Source;
+    use coding_agent_search::storage::sqlite::SqliteStorage;
+    use std::path::PathBuf;
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    let data_dir = tmp.path().join("data");
+    let local_api = tmp.path().join("code").join("api");
+    fs::create_dir_all(local_api.join(".git")).unwrap();
+    fs::create_dir_all(&data_dir).unwrap();
+    create_sources_config(
+        &config_dir,
+        r#"
+[[sources]]
+name = "laptop"
+type = "ssh"
+host = "user@laptop.invalid"
+paths = ["~/.claude/projects"]
+"#,
+    );
+
+    let mut storage = SqliteStorage::open(&data_dir.join("agent_search.db")).unwrap();
+    storage.upsert_source(&Source::local()).unwrap();
+    storage
+        .upsert_source(&Source::remote("laptop", "user@laptop.invalid"))
+        .unwrap();
+    let agent_id = storage
+        .ensure_agent(&Agent {
+            id: None,
+            slug: "claude_code".into(),
+            name: "Claude Code".into(),
+            version: None,
+            kind: AgentKind::Cli,
+        })
+        .unwrap();
+    for (external_id, source_id, workspace) in [
+        ("local-1", "local", local_api.clone()),
+        ("remote-1", "laptop", PathBuf::from("/home/alice/src/api")),
+    ] {
+        let ws_id = storage.ensure_workspace(&workspace, None).unwrap();
+        storage
+            .insert_conversation_tree(
+                agent_id,
+                Some(ws_id),
+                &Conversation {
+                    id: None,
+                    agent_slug: "claude_code".into(),
+                    workspace: Some(workspace),
+                    external_id: Some(external_id.into()),
+                    title: None,
+                    source_path: PathBuf::from(format!("/logs/{external_id}.jsonl")),
+                    started_at: Some(1_700_000_000_000),
+                    ended_at: None,
+                    approx_tokens: None,
+                    metadata_json: serde_json::json!({}),
+                    messages: vec![],
+                    source_id: source_id.into(),
+                    origin_host: None,
+                    owner: None,
+                },
+            )
+            .unwrap();
+    }
+    drop(storage);
+
+    let output = cargo_bin_cmd!("cass")
+        .args([
+            "sources",
+            "mappings",
+            "suggest",
+            "laptop",
+            "--offline",
+            "--apply",
+            "--json",
+        ])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env("CASS_DATA_DIR", &data_dir)
+        .output()
+        .expect("suggest command");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    let local_code = tmp.path().join("code");
+    assert_eq!(json["suggestions"][0]["from"], "/home/alice/src");
+    assert_eq!(
+        json["suggestions"][0]["to"],
+        local_code.to_string_lossy().as_ref()
+    );
+    assert_eq!(json["suggestions"][0]["matches"][0]["reason"], "basename");
+    assert_eq!(json["rewritten"], 1);
+
+    let config = read_sources_config(&config_dir);
+    assert!(config.contains("/home/alice/src"), "config: {config}");
+
+    let storage = SqliteStorage::open(&data_dir.join("agent_search.db")).unwrap();
+    let rows = storage.list_conversation_workspaces("laptop").unwrap();
+    assert_eq!(rows[0].workspace.as_deref(), Some(local_api.as_path()));
+    assert_eq!(
+        rows[0].metadata["cass"]["workspace_original"],
+        "/home/alice/src/api"
+    );
+
+    // Nothing left to suggest once the mapping exists
+    let output = cargo_bin_cmd!("cass")
+        .args([
+            "sources",
+            "mappings",
+            "suggest",
+            "laptop",
+            "--offline",
+            "--json",
+        ])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env("CASS_DATA_DIR", &data_dir)
+        .output()
+        .expect("suggest command");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["suggestions"].as_array().unwrap().len(), 0);
+}
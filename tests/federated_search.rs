@@ -0,0 +1,198 @@
+//! `cass search --federated` against fake SSH hosts.
+//!
+//! A stub `ssh` on PATH runs the remote script locally against a second
+//! data dir, so the remote side is a real `cass search --robot-format jsonl`.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn make_codex_session(codex_home: &Path, content: &str, ts: u64) {
+    let sessions = codex_home.join("sessions/2024/11/20");
+    fs::create_dir_all(&sessions).unwrap();
+    let sample = format!(
+        r#"{{"type": "event_msg", "timestamp": {ts}, "payload": {{"type": "user_message", "message": "{content}"}}}}
+{{"type": "response_item", "timestamp": {}, "payload": {{"role": "assistant", "content": "{content}_response"}}}}
+"#,
+        ts + 1000
+    );
+    fs::write(sessions.join("rollout-1.jsonl"), sample).unwrap();
+}
+
+fn index(home: &Path, data_dir: &Path) {
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(data_dir)
+        .env("CODEX_HOME", home.join(".codex"))
+        .env("HOME", home)
+        .assert()
+        .success();
+}
+
+/// `ssh` stub: `slow` hangs, `down` refuses, anything else searches `remote_data`
+fn write_fake_ssh(bin: &Path, remote_data: &Path) {
+    fs::create_dir_all(bin).unwrap();
+    let script = format!(
+        r#"#!/bin/sh
+while [ "$1" != "--" ]; do shift; done
+shift
+case "$1" in
+  slow) exec sleep 30 ;;
+  down) echo "ssh: connect to host down port 22: Connection refused" >&2; exit 255 ;;
+esac
+CASS_DATA_DIR="{}" exec bash -s
+"#,
+        remote_data.display()
+    );
+    let ssh = bin.join("ssh");
+    fs::write(&ssh, script).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&ssh, fs::Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink(env!("CARGO_BIN_EXE_cass"), bin.join("cass")).unwrap();
+    }
+}
+
+fn write_sources(config_dir: &Path, hosts: &[(&str, &str)]) {
+    let mut toml = String::new();
+    for (name, host) in hosts {
+        toml.push_str(&format!(
+            "[[sources]]\nname = \"{name}\"\ntype = \"ssh\"\nhost = \"{host}\"\npaths = [\"~/.codex\"]\n\n"
+        ));
+    }
+    fs::create_dir_all(config_dir.join("cass")).unwrap();
+    fs::write(config_dir.join("cass/sources.toml"), toml).unwrap();
+}
+
+struct Setup {
+    _tmp: tempfile::TempDir,
+    home: PathBuf,
+    data_dir: PathBuf,
+    bin: PathBuf,
+    config_dir: PathBuf,
+}
+
+fn setup(hosts: &[(&str, &str)]) -> Setup {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path().join("home");
+    let remote_home = tmp.path().join("remote");
+    let data_dir = tmp.path().join("data");
+    let remote_data = tmp.path().join("remote_data");
+
+    make_codex_session(
+        &home.join(".codex"),
+        "deploy the local canary",
+        1732118400000,
+    );
+    make_codex_session(
+        &remote_home.join(".codex"),
+        "deploy the remote canary",
+        1732118500000,
+    );
+    index(&home, &data_dir);
+    index(&remote_home, &remote_data);
+
+    let bin = tmp.path().join("bin");
+    write_fake_ssh(&bin, &remote_data);
+    let config_dir = tmp.path().join("config");
+    write_sources(&config_dir, hosts);
+    Setup {
+        _tmp: tmp,
+        home,
+        data_dir,
+        bin,
+        config_dir,
+    }
+}
+
+fn federated_search(setup: &Setup, extra: &[&str]) -> Value {
+    let output = cargo_bin_cmd!("cass")
+        .args(["search", "canary", "--federated", "--robot", "--data-dir"])
+        .arg(&setup.data_dir)
+        .args(extra)
+        .env("HOME", &setup.home)
+        .env("XDG_CONFIG_HOME", &setup.config_dir)
+        .env(
+            "PATH",
+            format!(
+                "{}:{}",
+                setup.bin.display(),
+                std::env::var("PATH").unwrap_or_default()
+            ),
+        )
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn hits_from(json: &Value, source_id: &str) -> Vec<Value> {
+    json["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|h| h["source_id"] == source_id)
+        .cloned()
+        .collect()
+}
+
+#[cfg(unix)]
+#[test]
+fn federated_search_merges_remote_hits_with_provenance() {
+    let setup = setup(&[("builder", "dev@builder")]);
+    let json = federated_search(&setup, &[]);
+
+    assert!(!hits_from(&json, "local").is_empty());
+    let remote = hits_from(&json, "builder");
+    assert!(!remote.is_empty(), "{json:#}");
+    assert_eq!(remote[0]["origin_kind"], "ssh");
+    assert_eq!(remote[0]["origin_host"], "dev@builder");
+    assert!(
+        remote[0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("remote canary")
+    );
+
+    let hosts = json["federated"]["hosts"].as_array().unwrap();
+    assert_eq!(hosts.len(), 1);
+    assert_eq!(hosts[0]["source_id"], "builder");
+    assert_eq!(json["federated"]["partial_results"], false);
+
+    // --source narrows both the local index and the hosts that are queried
+    let json = federated_search(&setup, &["--source", "local"]);
+    assert!(hits_from(&json, "builder").is_empty());
+    assert!(json["federated"]["hosts"].as_array().unwrap().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn federated_search_returns_partial_results_when_hosts_fail() {
+    let setup = setup(&[
+        ("builder", "dev@builder"),
+        ("slow", "slow"),
+        ("down", "down"),
+    ]);
+    let json = federated_search(&setup, &["--federated-timeout", "2"]);
+
+    assert!(!hits_from(&json, "local").is_empty());
+    assert!(!hits_from(&json, "builder").is_empty());
+    assert_eq!(json["federated"]["partial_results"], true);
+
+    let hosts = json["federated"]["hosts"].as_array().unwrap();
+    let host = |id: &str| hosts.iter().find(|h| h["source_id"] == id).unwrap();
+    assert_eq!(host("builder")["timed_out"], false);
+    assert_eq!(host("slow")["timed_out"], true);
+    assert!(
+        host("down")["error"]
+            .as_str()
+            .unwrap()
+            .contains("Connection refused")
+    );
+}
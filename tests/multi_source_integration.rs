@@ -7,8 +7,9 @@ use std::path::PathBuf;
 
 use coding_agent_search::indexer::persist;
 use coding_agent_search::model::types::{Agent, AgentKind, Conversation, Message, MessageRole};
+use coding_agent_search::search::query::{SearchClient, SearchFilters};
 use coding_agent_search::search::tantivy::TantivyIndex;
-use coding_agent_search::sources::provenance::Source;
+use coding_agent_search::sources::provenance::{Source, SourceFilter};
 use coding_agent_search::storage::sqlite::SqliteStorage;
 use serde_json::json;
 use tempfile::TempDir;
@@ -59,6 +60,7 @@ fn conv_with_source(
         messages,
         source_id: source_id.to_string(),
         origin_host: origin_host.map(String::from),
+        owner: None,
     }
 }
 
@@ -1268,6 +1270,107 @@ fn dedup_within_source_not_across() {
     );
 }
 
+/// A session first stored from one source is found when filtering by another
+/// source it was also synced from
+#[test]
+fn source_filter_finds_sessions_linked_to_the_source() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let db_path = data_dir.join("linked.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open db");
+    let index_dir = data_dir.join("index");
+    std::fs::create_dir_all(&index_dir).unwrap();
+    let mut t_index = TantivyIndex::open_or_create(&index_dir).expect("create index");
+
+    storage
+        .upsert_source(&Source::local())
+        .expect("local source");
+    storage
+        .upsert_source(&Source::remote("laptop", "user@laptop.local"))
+        .expect("remote source");
+
+    let now = 1700000000i64;
+    let shared = |source_id: &str, host: Option<&str>| {
+        norm_conv_with_provenance(
+            "shared",
+            source_id,
+            host,
+            now,
+            vec![norm_msg(0, now, "linkedterm shared session")],
+        )
+    };
+    // Stored from local, then linked when the laptop mirror is indexed
+    persist::persist_conversation(&mut storage, &mut t_index, &shared("local", None)).unwrap();
+    persist::persist_conversation(
+        &mut storage,
+        &mut t_index,
+        &shared("laptop", Some("user@laptop.local")),
+    )
+    .unwrap();
+    // Other sessions of each source
+    persist::persist_conversation(
+        &mut storage,
+        &mut t_index,
+        &norm_conv_with_provenance(
+            "local-only",
+            "local",
+            None,
+            now + 1000,
+            vec![norm_msg(0, now + 1000, "linkedterm local only")],
+        ),
+    )
+    .unwrap();
+    persist::persist_conversation(
+        &mut storage,
+        &mut t_index,
+        &norm_conv_with_provenance(
+            "laptop-only",
+            "laptop",
+            Some("user@laptop.local"),
+            now + 2000,
+            vec![norm_msg(0, now + 2000, "linkedterm laptop only")],
+        ),
+    )
+    .unwrap();
+    t_index.commit().unwrap();
+    drop(storage);
+
+    let client = SearchClient::open(&index_dir, Some(&db_path))
+        .unwrap()
+        .expect("client");
+    let search = |source: &str, limit: usize, offset: usize| {
+        let filters = SearchFilters {
+            source_filter: SourceFilter::SourceId(source.to_string()),
+            ..Default::default()
+        };
+        let mut paths: Vec<String> = client
+            .search("linkedterm", filters, limit, offset)
+            .unwrap()
+            .into_iter()
+            .map(|h| h.source_path)
+            .collect();
+        paths.sort();
+        paths
+    };
+
+    assert_eq!(
+        search("laptop", 10, 0),
+        vec!["/logs/laptop-only.jsonl", "/logs/shared.jsonl"]
+    );
+    assert_eq!(
+        search("local", 10, 0),
+        vec!["/logs/local-only.jsonl", "/logs/shared.jsonl"]
+    );
+    // Paging skips only matching hits
+    let first = search("laptop", 1, 0);
+    let second = search("laptop", 1, 1);
+    assert_eq!((first.len(), second.len()), (1, 1));
+    assert_ne!(first, second);
+    assert!(search("laptop", 1, 2).is_empty());
+}
+
 /// P7.4: Verify composite key (source_id, agent_id, external_id) is unique constraint
 #[test]
 fn composite_key_unique_constraint() {
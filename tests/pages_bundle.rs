@@ -3,7 +3,9 @@
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
-    use coding_agent_search::pages::bundle::{BundleBuilder, BundleConfig, IntegrityManifest};
+    use coding_agent_search::pages::bundle::{
+        BundleBuilder, BundleConfig, IntegrityManifest, PlaintextMode,
+    };
     use coding_agent_search::pages::encrypt::EncryptionEngine;
     use std::fs;
     use std::path::Path;
@@ -173,8 +175,12 @@ mod tests {
         let integrity_content = fs::read_to_string(&integrity_path)?;
         let manifest: IntegrityManifest = serde_json::from_str(&integrity_content)?;
 
-        assert_eq!(manifest.version, 1);
+        assert_eq!(manifest.version, 2);
         assert!(!manifest.files.is_empty(), "Should have file entries");
+        assert_eq!(manifest.chunks.len(), result.chunk_count);
+        for chunk in &manifest.chunks {
+            assert_eq!(manifest.files[&chunk.path].sha256, chunk.sha256);
+        }
 
         // Verify integrity.json is not in the manifest (chicken/egg)
         assert!(!manifest.files.contains_key("integrity.json"));
@@ -430,4 +436,160 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_rebuild_after_reexport_only_changes_touched_chunks() -> Result<()> {
+        let temp = TempDir::new()?;
+        let encrypted_dir = temp.path().join("encrypted");
+        let bundle_dir = temp.path().join("bundle");
+        let input = temp.path().join("export.db");
+
+        let mut data: Vec<u8> = (0..64 * 1024u32).map(|i| (i % 253) as u8).collect();
+        fs::write(&input, &data)?;
+
+        let mut engine = EncryptionEngine::new(16 * 1024);
+        engine.add_password_slot("test-password")?;
+        let first_config = engine.encrypt_file(&input, &encrypted_dir, |_, _| {})?;
+        let first = BundleBuilder::new().build(&encrypted_dir, &bundle_dir, |_, _| {})?;
+        let first_manifest = IntegrityManifest::load(&first.site_dir)?;
+        assert_eq!(first_manifest.chunks.len(), 4);
+
+        // Touch the last chunk only and re-export under the same key
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        fs::write(&input, &data)?;
+        let engine = EncryptionEngine::from_existing(&first_config, "test-password")?;
+        engine.encrypt_file(&input, &encrypted_dir, |_, _| {})?;
+        let second = BundleBuilder::new().build(&encrypted_dir, &bundle_dir, |_, _| {})?;
+        let second_manifest = IntegrityManifest::load(&second.site_dir)?;
+
+        assert_eq!(second_manifest.chunks[..3], first_manifest.chunks[..3]);
+        assert_ne!(second_manifest.chunks[3], first_manifest.chunks[3]);
+
+        let diff = second_manifest.diff(Some(&first_manifest));
+        assert_eq!(diff.added, vec![second_manifest.chunks[3].path.clone()]);
+        assert_eq!(diff.removed, vec![first_manifest.chunks[3].path.clone()]);
+        // config.json (new chunk list) and site.json (timestamp) change too
+        assert_eq!(diff.changed, vec!["config.json", "site.json"]);
+
+        // The stale chunk is gone from the rebuilt site
+        let stale_chunk = second.site_dir.join(&first_manifest.chunks[3].path);
+        assert!(!stale_chunk.exists());
+        assert_eq!(second.chunk_count, 4);
+
+        Ok(())
+    }
+
+    /// Create a minimal export database with one conversation
+    fn setup_export_db(path: &Path) -> Result<()> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE conversations (
+                id INTEGER PRIMARY KEY,
+                agent TEXT NOT NULL,
+                workspace TEXT,
+                title TEXT,
+                source_path TEXT NOT NULL,
+                started_at INTEGER
+            );
+            CREATE TABLE messages (
+                id INTEGER PRIMARY KEY,
+                conversation_id INTEGER NOT NULL,
+                idx INTEGER NOT NULL,
+                role TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at INTEGER
+            );
+            INSERT INTO conversations VALUES
+                (7, 'claude', 'proj', 'Fix <script> tags', 'proj/1.jsonl', 1600000000000);
+            INSERT INTO messages (conversation_id, idx, role, content, created_at) VALUES
+                (7, 0, 'user', 'Why does <b>bold</b> break the parser?', 1600000000000),
+                (7, 1, 'assistant', 'Escape the tokenizer input', 1600000001000);
+            "#,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_unencrypted_viewer_bundle() -> Result<()> {
+        let temp = TempDir::new()?;
+        let database = temp.path().join("export.db");
+        let bundle_dir = temp.path().join("bundle");
+        setup_export_db(&database)?;
+
+        let result = BundleBuilder::new().build_unencrypted(
+            &database,
+            &bundle_dir,
+            PlaintextMode::Viewer,
+            |_, _| {},
+        )?;
+
+        let config: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(result.site_dir.join("config.json"))?)?;
+        assert_eq!(config["encrypted"], false);
+        assert_eq!(config["mode"], "viewer");
+
+        let db_file = config["database"]["file"].as_str().unwrap();
+        assert_eq!(
+            fs::read(result.site_dir.join(db_file))?,
+            fs::read(&database)?
+        );
+        let manifest = IntegrityManifest::load(&result.site_dir)?;
+        assert_eq!(
+            config["database"]["sha256"].as_str().unwrap(),
+            manifest.files[db_file].sha256
+        );
+
+        // Viewer assets ship; no private key material besides the fingerprint
+        assert!(result.site_dir.join("auth.js").exists());
+        assert_eq!(fs::read_dir(&result.private_dir)?.count(), 1);
+        assert_eq!(result.chunk_count, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unencrypted_static_html_bundle() -> Result<()> {
+        let temp = TempDir::new()?;
+        let database = temp.path().join("export.db");
+        let bundle_dir = temp.path().join("bundle");
+        setup_export_db(&database)?;
+
+        // A previous viewer build leaves a plaintext database behind
+        BundleBuilder::new().build_unencrypted(
+            &database,
+            &bundle_dir,
+            PlaintextMode::Viewer,
+            |_, _| {},
+        )?;
+        let result = BundleBuilder::new().title("Demo").build_unencrypted(
+            &database,
+            &bundle_dir,
+            PlaintextMode::StaticHtml,
+            |_, _| {},
+        )?;
+        let site = &result.site_dir;
+        assert!(!site.join("payload").exists());
+
+        let page = fs::read_to_string(site.join("c/7.html"))?;
+        assert!(page.contains("Fix &lt;script&gt; tags"));
+        assert!(page.contains("Why does &lt;b&gt;bold&lt;/b&gt; break the parser?"));
+        assert!(!page.contains("<b>bold"));
+
+        let index = fs::read_to_string(site.join("index.html"))?;
+        assert!(index.contains("href=\"c/7.html\""));
+        assert!(index.contains("static-search.js"));
+
+        let search: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(site.join("search-index.json"))?)?;
+        assert_eq!(search["docs"][0]["url"], "c/7.html");
+        assert_eq!(search["terms"]["tokenizer"], serde_json::json!([0]));
+        assert_eq!(search["terms"]["script"], serde_json::json!([0]));
+
+        let manifest = IntegrityManifest::load(site)?;
+        assert!(manifest.files.contains_key("c/7.html"));
+
+        Ok(())
+    }
 }
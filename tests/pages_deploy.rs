@@ -0,0 +1,283 @@
+//! Integration tests for the generic pages deployers, run against local
+//! stand-ins: a bare git repository, an in-process S3 server and (with
+//! Docker) an SSH server.
+
+mod ssh_test_helper;
+
+use anyhow::Result;
+use coding_agent_search::pages::bundle::{BundleBuilder, IntegrityManifest, PlaintextMode};
+use coding_agent_search::pages::deploy::Deployer;
+use coding_agent_search::pages::deploy_git::GitRemoteDeployer;
+use coding_agent_search::pages::deploy_s3::{S3Credentials, S3Deployer};
+use coding_agent_search::pages::deploy_ssh::SshDeployer;
+use coding_agent_search::sources::sync::SyncMethod;
+use ssh_test_helper::SshTestServer;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+/// Build an unencrypted site whose database holds `message`
+fn build_site(temp: &Path, message: &str) -> Result<PathBuf> {
+    let database = temp.join("export.db");
+    let _ = fs::remove_file(&database);
+    let conn = rusqlite::Connection::open(&database)?;
+    conn.execute_batch(
+        "CREATE TABLE conversations (id INTEGER PRIMARY KEY, agent TEXT NOT NULL,
+             workspace TEXT, title TEXT, source_path TEXT NOT NULL, started_at INTEGER);
+         CREATE TABLE messages (id INTEGER PRIMARY KEY, conversation_id INTEGER NOT NULL,
+             idx INTEGER NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL, created_at INTEGER);
+         INSERT INTO conversations VALUES (1, 'claude', 'proj', 'Demo', 'proj/1.jsonl', 0);",
+    )?;
+    conn.execute(
+        "INSERT INTO messages (conversation_id, idx, role, content) VALUES (1, 0, 'user', ?1)",
+        [message],
+    )?;
+    drop(conn);
+
+    let result = BundleBuilder::new().build_unencrypted(
+        &database,
+        &temp.join("bundle"),
+        PlaintextMode::Viewer,
+        |_, _| {},
+    )?;
+    Ok(result.site_dir)
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn git_remote_deploy_updates_branch_in_place() -> Result<()> {
+    let temp = TempDir::new()?;
+    let remote = temp.path().join("remote.git");
+    fs::create_dir_all(&remote)?;
+    git(&remote, &["init", "--bare", "-q"]);
+
+    let deployer = GitRemoteDeployer::new(remote.to_string_lossy(), "pages");
+    let site = build_site(temp.path(), "first")?;
+    let first = deployer.deploy(&site, &|_, _| {})?;
+    assert!(!first.incremental);
+    assert!(first.uploaded_files > 0);
+
+    let files = git(&remote, &["ls-tree", "-r", "--name-only", "pages"]);
+    assert!(files.lines().any(|f| f == "index.html"));
+    assert!(files.lines().any(|f| f == "integrity.json"));
+
+    // New database: only the changed files go up, on top of the old commit
+    let site = build_site(temp.path(), "second")?;
+    let second = deployer.deploy(&site, &|_, _| {})?;
+    assert!(second.incremental);
+    assert!(second.uploaded_files < first.uploaded_files);
+    assert!(second.unchanged_files > 0);
+    assert_eq!(git(&remote, &["rev-list", "--count", "pages"]).trim(), "2");
+
+    Ok(())
+}
+
+#[test]
+fn git_remote_deploy_refuses_unrelated_branch() -> Result<()> {
+    let temp = TempDir::new()?;
+    let remote = temp.path().join("remote.git");
+    let work = temp.path().join("work");
+    fs::create_dir_all(&remote)?;
+    git(&remote, &["init", "--bare", "-q"]);
+    fs::create_dir_all(&work)?;
+    git(&work, &["init", "-q", "-b", "pages"]);
+    fs::write(work.join("NOTES.txt"), "docs")?;
+    git(&work, &["add", "."]);
+    git(
+        &work,
+        &[
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@t",
+            "commit",
+            "-q",
+            "-m",
+            "docs",
+        ],
+    );
+    git(&work, &["push", "-q", &remote.to_string_lossy(), "pages"]);
+
+    let site = build_site(temp.path(), "first")?;
+    let deployer = GitRemoteDeployer::new(remote.to_string_lossy(), "pages");
+    let err = deployer.deploy(&site, &|_, _| {}).unwrap_err();
+    assert!(err.to_string().contains("does not hold a cass archive"));
+
+    deployer.force(true).deploy(&site, &|_, _| {})?;
+    let files = git(&remote, &["ls-tree", "-r", "--name-only", "pages"]);
+    assert!(!files.lines().any(|f| f == "NOTES.txt"));
+
+    Ok(())
+}
+
+/// Objects and request log of the fake S3 server
+#[derive(Default)]
+struct FakeBucket {
+    objects: BTreeMap<String, Vec<u8>>,
+    requests: Vec<(String, String, String)>,
+}
+
+/// Serve path-style GET/PUT/DELETE on a local port
+fn start_fake_s3() -> (String, Arc<Mutex<FakeBucket>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let bucket = Arc::new(Mutex::new(FakeBucket::default()));
+    let state = bucket.clone();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            let mut authorization = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                let (name, value) = line.split_once(':').unwrap();
+                match name.to_ascii_lowercase().as_str() {
+                    "content-length" => content_length = value.trim().parse().unwrap(),
+                    "authorization" => authorization = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap().to_string();
+            let path = parts.next().unwrap().to_string();
+            let mut bucket = state.lock().unwrap();
+            bucket
+                .requests
+                .push((method.clone(), path.clone(), authorization));
+            let (status, response) = match method.as_str() {
+                "GET" => match bucket.objects.get(&path) {
+                    Some(data) => ("200 OK", data.clone()),
+                    None => ("404 Not Found", Vec::new()),
+                },
+                "PUT" => {
+                    bucket.objects.insert(path, body);
+                    ("200 OK", Vec::new())
+                }
+                "DELETE" => {
+                    bucket.objects.remove(&path);
+                    ("204 No Content", Vec::new())
+                }
+                _ => ("405 Method Not Allowed", Vec::new()),
+            };
+            drop(bucket);
+            let head = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                response.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&response).unwrap();
+        }
+    });
+
+    (format!("http://{addr}"), bucket)
+}
+
+#[test]
+fn s3_deploy_uploads_signed_objects_incrementally() -> Result<()> {
+    let temp = TempDir::new()?;
+    let (endpoint, bucket) = start_fake_s3();
+    let credentials = S3Credentials {
+        access_key_id: "AKIDTEST".into(),
+        secret_access_key: "secret".into(),
+        session_token: None,
+    };
+    let deployer = S3Deployer::from_url(&format!("{endpoint}/archive/team site"), credentials)?;
+
+    let site = build_site(temp.path(), "first")?;
+    let first = deployer.deploy(&site, &|_, _| {})?;
+    assert!(!first.incremental);
+    assert_eq!(
+        first.url.as_deref(),
+        Some(format!("{endpoint}/archive/team%20site/index.html").as_str())
+    );
+
+    {
+        let bucket = bucket.lock().unwrap();
+        let manifest = IntegrityManifest::load(&site)?;
+        for rel in manifest.files.keys() {
+            let key = format!("/archive/team%20site/{rel}");
+            assert_eq!(bucket.objects[&key], fs::read(site.join(rel))?, "{rel}");
+        }
+        assert!(
+            bucket
+                .requests
+                .iter()
+                .all(|(_, _, auth)| auth.starts_with("AWS4-HMAC-SHA256 Credential=AKIDTEST/"))
+        );
+        // The manifest is written after every other object
+        let last_put = bucket
+            .requests
+            .iter()
+            .rfind(|(method, _, _)| method == "PUT")
+            .unwrap();
+        assert!(last_put.1.ends_with("/integrity.json"));
+    }
+
+    let site = build_site(temp.path(), "second")?;
+    bucket.lock().unwrap().requests.clear();
+    let second = deployer.deploy(&site, &|_, _| {})?;
+    assert!(second.incremental);
+    assert!(second.unchanged_files > 0);
+    let puts = bucket
+        .lock()
+        .unwrap()
+        .requests
+        .iter()
+        .filter(|(method, _, _)| method == "PUT")
+        .count();
+    assert_eq!(puts, second.uploaded_files + 1);
+
+    Ok(())
+}
+
+/// Integration test: rsync deploy against a real SSH server.
+#[test]
+#[ignore = "requires Docker"]
+fn ssh_rsync_deploy_to_server() -> Result<()> {
+    let server = SshTestServer::start().expect("SSH server should start");
+    let temp = TempDir::new()?;
+    let remote_path = format!("{}/www", server.remote_home());
+    let deployer = SshDeployer::new(server.ssh_target(), &remote_path)
+        .method(SyncMethod::Rsync)
+        .ssh_command(server.rsync_ssh_opts());
+
+    let site = build_site(temp.path(), "first")?;
+    let first = deployer.deploy(&site, &|_, _| {})?;
+    assert!(!first.incremental);
+    let listing = server
+        .ssh_exec(&format!("ls {remote_path}"))
+        .expect("ls should run");
+    assert!(listing.contains("index.html"));
+
+    let site = build_site(temp.path(), "second")?;
+    let second = deployer.deploy(&site, &|_, _| {})?;
+    assert!(second.incremental);
+
+    Ok(())
+}
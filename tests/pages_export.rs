@@ -395,4 +395,292 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_reexport_is_page_stable() -> Result<()> {
+        use coding_agent_search::pages::export::EXPORT_PAGE_SIZE;
+
+        let temp_dir = TempDir::new()?;
+        let source_path = temp_dir.path().join("source.db");
+        setup_source_db(&source_path)?;
+
+        let export = |name: &str| -> Result<Vec<u8>> {
+            let output_path = temp_dir.path().join(name);
+            let filter = ExportFilter {
+                agents: None,
+                workspaces: None,
+                since: None,
+                until: None,
+                path_mode: PathMode::Relative,
+            };
+            ExportEngine::new(&source_path, &output_path, filter).execute(|_, _| {}, None)?;
+            Ok(std::fs::read(&output_path)?)
+        };
+
+        let first = export("first.db")?;
+        let second = export("second.db")?;
+        assert_eq!(first.len(), second.len());
+        assert_eq!(first.len() % EXPORT_PAGE_SIZE as usize, 0);
+
+        // Only the export_meta page (which carries the timestamp) may differ
+        let changed_pages = first
+            .chunks(EXPORT_PAGE_SIZE as usize)
+            .zip(second.chunks(EXPORT_PAGE_SIZE as usize))
+            .filter(|(a, b)| a != b)
+            .count();
+        assert!(changed_pages <= 1, "{changed_pages} pages changed");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_applies_curation_and_redactions() -> Result<()> {
+        use coding_agent_search::pages::curation::{Curation, RedactRule, RedactRules};
+
+        let temp_dir = TempDir::new()?;
+        let source_path = temp_dir.path().join("source.db");
+        let output_path = temp_dir.path().join("export.db");
+        setup_source_db(&source_path)?;
+
+        let conn = Connection::open(&source_path)?;
+        conn.execute(
+            "UPDATE messages SET content = 'token sk-abcdef1234567890 here' WHERE conversation_id = 1 AND idx = 0",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO messages (conversation_id, idx, role, content, created_at)
+             VALUES (1, 2, 'tool', 'ls output', 1600000006000)",
+            [],
+        )?;
+        conn.execute(
+            r#"UPDATE conversations SET metadata_json = '{"env":{"KEY":"sk-abcdef1234567890"}}' WHERE id = 1"#,
+            [],
+        )?;
+        drop(conn);
+
+        let mut curation = Curation::default();
+        curation.set_conversation_included("/home/user/proj2/.codex/session.json", false);
+        curation.exclude_message("/home/user/proj1/.claude/1.json", 1);
+        curation.drop_tool_output = true;
+        let redactions = RedactRules::new(vec![
+            RedactRule {
+                name: None,
+                pattern: "sk-[A-Za-z0-9]{16,}".into(),
+                replacement: None,
+            },
+            RedactRule {
+                name: Some("home".into()),
+                pattern: "/home/user".into(),
+                replacement: Some("~".into()),
+            },
+        ])?;
+
+        let filter = ExportFilter {
+            agents: None,
+            workspaces: None,
+            since: None,
+            until: None,
+            path_mode: PathMode::Full,
+        };
+        let stats = ExportEngine::new(&source_path, &output_path, filter)
+            .with_curation(curation)
+            .with_redactions(redactions)
+            .execute(|_, _| {}, None)?;
+
+        assert_eq!(stats.conversations_processed, 1);
+        assert_eq!(stats.conversations_excluded, 1);
+        assert_eq!(stats.messages_processed, 1);
+        assert_eq!(stats.messages_excluded, 2);
+        assert_eq!(stats.messages_redacted, 1);
+
+        let conn = Connection::open(&output_path)?;
+        let content: String = conn.query_row("SELECT content FROM messages", [], |r| r.get(0))?;
+        assert_eq!(content, "token [REDACTED] here");
+        let message_count: i64 = conn.query_row(
+            "SELECT message_count FROM conversations WHERE id = 1",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(message_count, 1);
+        // Workspace, source path and metadata go through the same rules
+        let (workspace, path, metadata): (String, String, String) = conn.query_row(
+            "SELECT workspace, source_path, metadata_json FROM conversations WHERE id = 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )?;
+        assert_eq!(workspace, "~/proj1");
+        assert_eq!(path, "~/proj1/.claude/1.json");
+        assert_eq!(metadata, r#"{"env":{"KEY":"[REDACTED]"}}"#);
+        // Redacted text is not searchable either
+        let hits: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM messages_fts WHERE messages_fts MATCH 'abcdef1234567890'",
+            [],
+            |r| r.get(0),
+        )?;
+        assert_eq!(hits, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_messages_for_curation() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_path = temp_dir.path().join("source.db");
+        setup_source_db(&source_path)?;
+
+        let filter = ExportFilter {
+            agents: Some(vec!["claude".to_string()]),
+            workspaces: None,
+            since: None,
+            until: None,
+            path_mode: PathMode::Full,
+        };
+        let engine = ExportEngine::new(&source_path, &temp_dir.path().join("export.db"), filter);
+        let conversations = engine.list_conversations()?;
+        assert_eq!(conversations.len(), 1);
+
+        let messages = engine.list_messages(conversations[0].id)?;
+        let listed: Vec<_> = messages
+            .iter()
+            .map(|m| (m.idx, m.role.as_str(), m.content.as_str()))
+            .collect();
+        assert_eq!(listed, [(0, "user", "hello"), (1, "assistant", "world")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_includes_searchable_snippets() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_path = temp_dir.path().join("source.db");
+        let output_path = temp_dir.path().join("export.db");
+        setup_source_db(&source_path)?;
+
+        let conn = Connection::open(&source_path)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE snippets (
+                id INTEGER PRIMARY KEY,
+                message_id INTEGER NOT NULL,
+                file_path TEXT,
+                start_line INTEGER,
+                end_line INTEGER,
+                language TEXT,
+                snippet_text TEXT
+            );
+            INSERT INTO snippets (message_id, file_path, start_line, end_line, language, snippet_text)
+            VALUES (2, '/home/user/proj1/src/auth.rs', 10, 12, 'rust', 'fn verify_token(t: &str) {}');
+            "#,
+        )?;
+        drop(conn);
+
+        let filter = ExportFilter {
+            agents: None,
+            workspaces: None,
+            since: None,
+            until: None,
+            path_mode: PathMode::Relative,
+        };
+        let stats =
+            ExportEngine::new(&source_path, &output_path, filter).execute(|_, _| {}, None)?;
+        assert_eq!(stats.snippets_processed, 1);
+
+        let conn = Connection::open(&output_path)?;
+        let (path, language, start, end, message_role): (String, String, i64, i64, String) = conn
+            .query_row(
+            "SELECT s.file_path, s.language, s.start_line, s.end_line, m.role
+                 FROM snippets s JOIN messages m ON s.message_id = m.id",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+        )?;
+        assert_eq!(path, "src/auth.rs"); // PathMode applies to snippet paths
+        assert_eq!(language, "rust");
+        assert_eq!((start, end), (10, 12));
+        assert_eq!(message_role, "assistant");
+
+        // Code search finds the message by snippet text and path
+        for term in ["verify_token", "src/auth.rs"] {
+            let role: String = conn.query_row(
+                "SELECT m.role FROM messages_code_fts
+                 JOIN messages m ON messages_code_fts.rowid = m.id
+                 WHERE messages_code_fts MATCH ?",
+                [format!("\"{term}\"")],
+                |r| r.get(0),
+            )?;
+            assert_eq!(role, "assistant", "{term}");
+        }
+        let fts_count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM messages_code_fts", [], |r| r.get(0))?;
+        assert_eq!(fts_count, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_includes_quantized_vectors() -> Result<()> {
+        use coding_agent_search::pages::curation::{RedactRule, RedactRules};
+        use coding_agent_search::pages::vectors::VectorSource;
+        use coding_agent_search::search::embedder::Embedder;
+        use coding_agent_search::search::hash_embedder::HashEmbedder;
+
+        let temp_dir = TempDir::new()?;
+        let source_path = temp_dir.path().join("source.db");
+        let output_path = temp_dir.path().join("export.db");
+        setup_source_db(&source_path)?;
+
+        let filter = ExportFilter {
+            agents: None,
+            workspaces: None,
+            since: None,
+            until: None,
+            path_mode: PathMode::Relative,
+        };
+        let redactions = RedactRules::new(vec![RedactRule {
+            name: None,
+            pattern: "world".into(),
+            replacement: None,
+        }])?;
+        let stats = ExportEngine::new(&source_path, &output_path, filter)
+            .with_redactions(redactions)
+            .with_vectors(VectorSource::Hash(HashEmbedder::new(32)))
+            .execute(|_, _| {}, None)?;
+        assert_eq!(stats.vectors_exported, 3);
+
+        let conn = Connection::open(&output_path)?;
+        let meta = |key: &str| -> Result<String> {
+            Ok(
+                conn.query_row("SELECT value FROM export_meta WHERE key = ?", [key], |r| {
+                    r.get(0)
+                })?,
+            )
+        };
+        assert_eq!(meta("vector_embedder")?, "fnv1a-32");
+        assert_eq!(meta("vector_dimension")?, "32");
+        assert_eq!(meta("vector_quantization")?, "int8");
+
+        let mut stmt = conn.prepare(
+            "SELECT m.content, v.scale, v.vector FROM message_vectors v
+             JOIN messages m ON v.message_id = m.id",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, f64>(1)?,
+                r.get::<_, Vec<u8>>(2)?,
+            ))
+        })?;
+        let embedder = HashEmbedder::new(32);
+        for row in rows {
+            let (content, scale, bytes) = row?;
+            assert_eq!(bytes.len(), 32);
+            // Vectors follow the exported (redacted) text, not the source
+            let expected = embedder.embed(&content).unwrap();
+            for (value, byte) in expected.iter().zip(&bytes) {
+                let restored = scale as f32 * f32::from(*byte as i8);
+                assert!((restored - value).abs() <= scale as f32, "{content}");
+            }
+        }
+
+        Ok(())
+    }
 }
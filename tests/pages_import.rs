@@ -0,0 +1,174 @@
+//! `cass pages import` of encrypted and unencrypted bundles, from a
+//! directory and over HTTP, followed by source-filtered search.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use coding_agent_search::pages::bundle::{BundleBuilder, PlaintextMode};
+use coding_agent_search::pages::encrypt::EncryptionEngine;
+use coding_agent_search::pages::serve::PreviewServer;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Write an archive database shaped like a pages export
+fn write_export_db(path: &Path, messages: &[&str]) {
+    let _ = std::fs::remove_file(path);
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE conversations (id INTEGER PRIMARY KEY, agent TEXT NOT NULL,
+             workspace TEXT, title TEXT, source_path TEXT NOT NULL, started_at INTEGER,
+             ended_at INTEGER, message_count INTEGER, metadata_json TEXT);
+         CREATE TABLE messages (id INTEGER PRIMARY KEY, conversation_id INTEGER NOT NULL,
+             idx INTEGER NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL,
+             created_at INTEGER, attachment_refs TEXT);
+         INSERT INTO conversations VALUES
+             (1, 'claude_code', 'proj', 'Deploy notes', 'proj/session.jsonl',
+              1700000000000, 1700000060000, 2, '{}');",
+    )
+    .unwrap();
+    for (idx, content) in messages.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO messages (conversation_id, idx, role, content, created_at)
+             VALUES (1, ?1, 'user', ?2, 1700000000000 + ?1)",
+            rusqlite::params![idx as i64, content],
+        )
+        .unwrap();
+    }
+}
+
+fn build_encrypted_bundle(root: &Path, messages: &[&str]) -> PathBuf {
+    let database = root.join("export.db");
+    write_export_db(&database, messages);
+    let encrypted = root.join("encrypted");
+    let mut engine = EncryptionEngine::new(1024);
+    engine.add_password_slot("team-pw").unwrap();
+    engine
+        .encrypt_file(&database, &encrypted, |_, _| {})
+        .unwrap();
+
+    let bundle = root.join("bundle");
+    BundleBuilder::new()
+        .build(&encrypted, &bundle, |_, _| {})
+        .unwrap();
+    bundle
+}
+
+fn import(archive: &str, data_dir: &Path, password: &str) -> std::process::Output {
+    cargo_bin_cmd!("cass")
+        .args(["pages", "import", archive, "--name", "teammate", "--json"])
+        .arg("--data-dir")
+        .arg(data_dir)
+        .env("CASS_PAGES_PASSWORD", password)
+        .output()
+        .unwrap()
+}
+
+fn import_json(archive: &str, data_dir: &Path, password: &str) -> Value {
+    let output = import(archive, data_dir, password);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn search_hits(query: &str, source: &str, data_dir: &Path) -> Vec<Value> {
+    let output = cargo_bin_cmd!("cass")
+        .args(["search", query, "--source", source, "--robot", "--data-dir"])
+        .arg(data_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["hits"].as_array().cloned().unwrap_or_default()
+}
+
+#[test]
+fn pages_import_encrypted_bundle_as_source() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    let bundle = build_encrypted_bundle(tmp.path(), &["rollback the canary deployment"]);
+    let archive = bundle.to_string_lossy();
+
+    let wrong = import(&archive, &data_dir, "nope");
+    assert_eq!(wrong.status.code(), Some(9));
+
+    let summary = import_json(&archive, &data_dir, "team-pw");
+    assert_eq!(summary["source_id"], "teammate");
+    assert_eq!(summary["encrypted"], true);
+    assert_eq!(summary["conversations"], 1);
+    assert_eq!(summary["new_messages"], 1);
+    // Nothing fetched or decrypted outlives the import
+    assert_eq!(
+        std::fs::read_dir(data_dir.join("archives"))
+            .unwrap()
+            .count(),
+        0
+    );
+
+    let hits = search_hits("canary", "teammate", &data_dir);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["source_id"], "teammate");
+    assert!(search_hits("canary", "local", &data_dir).is_empty());
+
+    // A newer export of the same archive only adds what is new
+    let bundle = build_encrypted_bundle(
+        tmp.path(),
+        &["rollback the canary deployment", "canary is healthy again"],
+    );
+    let summary = import_json(&bundle.to_string_lossy(), &data_dir, "team-pw");
+    assert_eq!(summary["new_messages"], 1);
+    assert_eq!(search_hits("canary", "teammate", &data_dir).len(), 2);
+}
+
+#[test]
+fn pages_import_over_http_verifies_integrity() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    let database = tmp.path().join("export.db");
+    write_export_db(&database, &["flaky integration test quarantine"]);
+    let bundle = tmp.path().join("bundle");
+    let result = BundleBuilder::new()
+        .build_unencrypted(&database, &bundle, PlaintextMode::Viewer, |_, _| {})
+        .unwrap();
+
+    let server = PreviewServer::bind(&result.site_dir, "127.0.0.1:0").unwrap();
+    let url = format!("{}index.html", server.url().unwrap());
+    std::thread::spawn(move || server.run());
+
+    let summary = import_json(&url, &data_dir, "");
+    assert_eq!(summary["encrypted"], false);
+    assert_eq!(summary["new_messages"], 1);
+    assert_eq!(search_hits("quarantine", "teammate", &data_dir).len(), 1);
+
+    // A payload that no longer matches integrity.json is refused
+    let payload = result.site_dir.join("payload/archive.db");
+    let mut bytes = std::fs::read(&payload).unwrap();
+    bytes.push(0);
+    std::fs::write(&payload, bytes).unwrap();
+    let output = import(&url, &data_dir, "");
+    assert_eq!(output.status.code(), Some(9));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not match integrity.json"));
+}
+
+#[test]
+fn pages_import_rejects_path_like_names() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    let bundle = build_encrypted_bundle(tmp.path(), &["keep out"]);
+    // A pre-existing sibling of the staging area must survive
+    std::fs::create_dir_all(data_dir.join("keep")).unwrap();
+
+    for name in ["..", "../keep", ""] {
+        let output = cargo_bin_cmd!("cass")
+            .args(["pages", "import"])
+            .arg(&bundle)
+            .args(["--name", name, "--json", "--data-dir"])
+            .arg(&data_dir)
+            .env("CASS_PAGES_PASSWORD", "team-pw")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2), "--name {name:?}");
+    }
+    assert!(data_dir.join("keep").is_dir());
+}
@@ -0,0 +1,181 @@
+//! `cass pages keys` against a freshly encrypted archive.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use coding_agent_search::pages::bundle::BundleBuilder;
+use coding_agent_search::pages::encrypt::{DecryptionEngine, EncryptionEngine, load_config};
+use coding_agent_search::pages::recipient::Identity;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Encrypt a small file and build a bundle (site/ + private/) from it
+fn build_bundle(root: &Path) -> PathBuf {
+    let input = root.join("export.db");
+    std::fs::write(&input, b"archive database bytes").unwrap();
+    let encrypted = root.join("encrypted");
+    let mut engine = EncryptionEngine::new(1024);
+    engine.add_password_slot("owner-pw").unwrap();
+    engine.encrypt_file(&input, &encrypted, |_, _| {}).unwrap();
+
+    let bundle = root.join("bundle");
+    BundleBuilder::new()
+        .build(&encrypted, &bundle, |_, _| {})
+        .unwrap();
+    bundle
+}
+
+fn keys_json(bundle: &Path, args: &[&str], password: &str, new_password: &str) -> Value {
+    let output = cargo_bin_cmd!("cass")
+        .args(["pages", "keys", args[0]])
+        .arg(bundle)
+        .args(&args[1..])
+        .arg("--json")
+        .env("CASS_PAGES_PASSWORD", password)
+        .env("CASS_PAGES_NEW_PASSWORD", new_password)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn pages_keys_add_list_revoke() {
+    let tmp = TempDir::new().unwrap();
+    let bundle = build_bundle(tmp.path());
+    let site = bundle.join("site");
+    let integrity_before = std::fs::read_to_string(site.join("integrity.json")).unwrap();
+
+    let added = keys_json(&bundle, &["add"], "owner-pw", "colleague-pw");
+    assert_eq!(added["slot"], 1);
+    assert_eq!(added["slot_type"], "password");
+
+    let config = load_config(&site).unwrap();
+    assert!(DecryptionEngine::unlock_with_password(config, "colleague-pw").is_ok());
+    // config.json changed, so integrity.json was refreshed
+    let integrity_after = std::fs::read_to_string(site.join("integrity.json")).unwrap();
+    assert_ne!(integrity_before, integrity_after);
+
+    let listed = keys_json(&bundle, &["list"], "", "");
+    let slots = listed["slots"].as_array().unwrap();
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[1]["slot_type"], "password");
+    assert_eq!(slots[1]["kdf"], "argon2id");
+
+    keys_json(&bundle, &["revoke", "1"], "owner-pw", "");
+    let config = load_config(&site).unwrap();
+    assert!(DecryptionEngine::unlock_with_password(config, "colleague-pw").is_err());
+
+    // Wrong unlock password is rejected without touching the archive
+    cargo_bin_cmd!("cass")
+        .args(["pages", "keys", "add"])
+        .arg(&bundle)
+        .env("CASS_PAGES_PASSWORD", "nope")
+        .env("CASS_PAGES_NEW_PASSWORD", "x")
+        .assert()
+        .failure()
+        .code(9);
+    assert_eq!(load_config(&site).unwrap().key_slots.len(), 1);
+}
+
+#[test]
+fn pages_keys_rotate_with_new_recovery_secret() {
+    let tmp = TempDir::new().unwrap();
+    let bundle = build_bundle(tmp.path());
+    let site = bundle.join("site");
+    let before = load_config(&site).unwrap();
+
+    let rotated = keys_json(&bundle, &["rotate", "--recovery"], "owner-pw", "");
+    assert_eq!(rotated["slots"].as_array().unwrap().len(), 2);
+    let fingerprint = rotated["fingerprint"].as_str().unwrap();
+    let private_fingerprint =
+        std::fs::read_to_string(bundle.join("private/integrity-fingerprint.txt")).unwrap();
+    assert!(private_fingerprint.contains(fingerprint));
+
+    let after = load_config(&site).unwrap();
+    assert_ne!(after.export_id, before.export_id);
+    let recovery = rotated["recovery_secret"].as_str().unwrap();
+    let secret_file = tmp.path().join("recovery.txt");
+    std::fs::write(&secret_file, recovery).unwrap();
+
+    // The new recovery secret unlocks the rotated archive
+    let revoked = cargo_bin_cmd!("cass")
+        .args(["pages", "keys", "revoke"])
+        .arg(&bundle)
+        .args(["0", "--json", "--recovery-file"])
+        .arg(&secret_file)
+        .output()
+        .unwrap();
+    assert!(revoked.status.success());
+    assert_eq!(load_config(&site).unwrap().key_slots.len(), 1);
+}
+
+#[test]
+fn pages_keys_rotate_confirms_dropped_slots() {
+    let tmp = TempDir::new().unwrap();
+    let bundle = build_bundle(tmp.path());
+    let site = bundle.join("site");
+    keys_json(&bundle, &["add"], "owner-pw", "colleague-pw");
+    let before = load_config(&site).unwrap();
+
+    // The colleague's password cannot be re-wrapped, so rotation asks first
+    let output = cargo_bin_cmd!("cass")
+        .args(["pages", "keys", "rotate"])
+        .arg(&bundle)
+        .arg("--json")
+        .env("CASS_PAGES_PASSWORD", "owner-pw")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(9));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--yes"));
+    assert_eq!(load_config(&site).unwrap().export_id, before.export_id);
+
+    let rotated = keys_json(&bundle, &["rotate", "--yes"], "owner-pw", "");
+    assert_eq!(rotated["dropped"][0]["id"], 1);
+    assert_eq!(rotated["slots"].as_array().unwrap().len(), 1);
+    let config = load_config(&site).unwrap();
+    assert!(DecryptionEngine::unlock_with_password(config.clone(), "owner-pw").is_ok());
+    assert!(DecryptionEngine::unlock_with_password(config, "colleague-pw").is_err());
+}
+
+#[test]
+fn pages_keys_recipient_slot_unlocks_with_identity_file() {
+    let tmp = TempDir::new().unwrap();
+    let bundle = build_bundle(tmp.path());
+    let site = bundle.join("site");
+    let teammate = Identity::generate();
+    let recipient = teammate.recipient();
+
+    let added = keys_json(&bundle, &["add", "--recipient", &recipient], "owner-pw", "");
+    assert_eq!(added["slot_type"], "recipient");
+    let listed = keys_json(&bundle, &["list"], "", "");
+    assert_eq!(listed["slots"][1]["recipient"], recipient.as_str());
+    assert_eq!(listed["slots"][1]["kdf"], "x25519-hkdf-sha256");
+
+    let identity_file = tmp.path().join("key.txt");
+    let contents = format!(
+        "# public key: {recipient}\n{}\n",
+        teammate.to_secret_string()
+    );
+    std::fs::write(&identity_file, contents).unwrap();
+
+    // The identity alone is enough to edit the archive
+    let revoked = cargo_bin_cmd!("cass")
+        .args(["pages", "keys", "revoke"])
+        .arg(&bundle)
+        .args(["0", "--json", "--identity-file"])
+        .arg(&identity_file)
+        .output()
+        .unwrap();
+    assert!(
+        revoked.status.success(),
+        "{}",
+        String::from_utf8_lossy(&revoked.stderr)
+    );
+    let config = load_config(&site).unwrap();
+    assert_eq!(config.key_slots.len(), 1);
+    assert!(DecryptionEngine::unlock_with_identity(config, &teammate).is_ok());
+}
@@ -0,0 +1,223 @@
+//! `cass push` to a `cass serve-ingest` server: session files, appended
+//! tails and conversation JSONL end up searchable under the push source.
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// A running `cass serve-ingest`, killed on drop
+struct Server {
+    child: Child,
+    url: String,
+    /// Kept open so the server can keep reporting indexing runs
+    _stdout: BufReader<ChildStdout>,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn start_server(config_dir: &Path, data_dir: &Path) -> Server {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cass"))
+        .args([
+            "serve-ingest",
+            "--listen",
+            "127.0.0.1:0",
+            "--json",
+            "--data-dir",
+        ])
+        .arg(data_dir)
+        .env("XDG_CONFIG_HOME", config_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    let event: Value = serde_json::from_str(&line).unwrap();
+    Server {
+        url: event["url"].as_str().unwrap().to_string(),
+        child,
+        _stdout: stdout,
+    }
+}
+
+fn issue_token(config_dir: &Path, name: &str) -> String {
+    let output = cargo_bin_cmd!("cass")
+        .args(["serve-ingest", "--new-token", name, "--json"])
+        .env("XDG_CONFIG_HOME", config_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["token"].as_str().unwrap().to_string()
+}
+
+fn push(server: &Server, token: &str, client_dir: &Path, args: &[&str]) -> std::process::Output {
+    cargo_bin_cmd!("cass")
+        .args(["push", "--to", &server.url, "--json", "--data-dir"])
+        .arg(client_dir)
+        .args(args)
+        .env("CASS_PUSH_TOKEN", token)
+        .output()
+        .unwrap()
+}
+
+fn push_json(server: &Server, token: &str, client_dir: &Path, args: &[&str]) -> Value {
+    let output = push(server, token, client_dir, args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn search_hits(query: &str, data_dir: &Path) -> Vec<Value> {
+    let output = cargo_bin_cmd!("cass")
+        .args(["search", query, "--source", "ci", "--robot", "--data-dir"])
+        .arg(data_dir)
+        .output()
+        .unwrap();
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    json["hits"].as_array().cloned().unwrap_or_default()
+}
+
+/// Search until `count` hits show up; pushed files are indexed in the background
+fn wait_for_hits(query: &str, data_dir: &Path, count: usize) -> Vec<Value> {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    loop {
+        let hits = search_hits(query, data_dir);
+        if hits.len() >= count || Instant::now() > deadline {
+            return hits;
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn write_session(sessions: &Path) -> PathBuf {
+    let dir = sessions.join("2024/11/20");
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("rollout-1.jsonl");
+    fs::write(
+        &file,
+        r#"{"type": "event_msg", "timestamp": 1732118400000, "payload": {"type": "user_message", "message": "deploy the pushed canary"}}
+"#,
+    )
+    .unwrap();
+    file
+}
+
+#[test]
+fn pushed_session_files_are_indexed_under_the_push_source() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    let data_dir = tmp.path().join("server");
+    let client_dir = tmp.path().join("client");
+    let sessions = tmp.path().join("home/.codex/sessions");
+    let session = write_session(&sessions);
+    let sessions_arg = sessions.to_string_lossy().to_string();
+
+    let token = issue_token(&config_dir, "ci");
+    let server = start_server(&config_dir, &data_dir);
+
+    let report = push_json(&server, &token, &client_dir, &["--path", &sessions_arg]);
+    assert_eq!(report["source_id"], "ci");
+    assert_eq!(report["uploaded"], 1);
+
+    let hits = wait_for_hits("canary", &data_dir, 1);
+    assert_eq!(hits.len(), 1, "pushed session was not indexed");
+    assert_eq!(hits[0]["origin_kind"], "push");
+
+    // Nothing changed: nothing is sent
+    let report = push_json(&server, &token, &client_dir, &["--path", &sessions_arg]);
+    assert_eq!(report["unchanged"], 1);
+    assert_eq!(report["bytes"], 0);
+
+    // A grown session only sends its new tail
+    let mut content = fs::read_to_string(&session).unwrap();
+    let tail = r#"{"type": "response_item", "timestamp": 1732118401000, "payload": {"role": "assistant", "content": "canary rollout resumed"}}
+"#;
+    content.push_str(tail);
+    fs::write(&session, &content).unwrap();
+    let report = push_json(&server, &token, &client_dir, &["--path", &sessions_arg]);
+    assert_eq!(report["appended"], 1);
+    assert_eq!(report["bytes"], tail.len());
+    assert_eq!(wait_for_hits("canary", &data_dir, 2).len(), 2);
+
+    let mirrored = fs::read_dir(data_dir.join("remotes/ci/mirror/files"))
+        .unwrap()
+        .count();
+    assert_eq!(mirrored, 1);
+
+    // Unknown tokens are refused
+    let output = push(
+        &server,
+        "not-a-token",
+        &client_dir,
+        &["--path", &sessions_arg],
+    );
+    assert_eq!(output.status.code(), Some(9));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("401"));
+}
+
+#[test]
+fn pushed_conversation_jsonl_is_ingested_immediately() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    let data_dir = tmp.path().join("server");
+    let client_dir = tmp.path().join("client");
+
+    let token = issue_token(&config_dir, "ci");
+    let server = start_server(&config_dir, &data_dir);
+
+    let jsonl = tmp.path().join("conversations.jsonl");
+    let conversation = serde_json::json!({
+        "agent_slug": "codex",
+        "external_id": "ci-run-1",
+        "title": "CI run",
+        "workspace": "/ci/work",
+        "source_path": "/ci/run-1.jsonl",
+        "started_at": 1732118400000_i64,
+        "ended_at": 1732118500000_i64,
+        "metadata": {},
+        "messages": [{
+            "idx": 0,
+            "role": "user",
+            "author": null,
+            "created_at": 1732118400000_i64,
+            "content": "quarantine the flaky integration test",
+            "extra": {},
+            "snippets": []
+        }]
+    });
+    fs::write(&jsonl, format!("{conversation}\n")).unwrap();
+    let jsonl_arg = jsonl.to_string_lossy().to_string();
+
+    let receipt = push_json(&server, &token, &client_dir, &["--jsonl", &jsonl_arg]);
+    assert_eq!(receipt["conversations"], 1);
+    assert_eq!(receipt["new_messages"], 1);
+
+    let hits = search_hits("quarantine", &data_dir);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["source_id"], "ci");
+    assert_eq!(hits[0]["origin_kind"], "push");
+
+    // Pushing the same conversation again adds nothing
+    let receipt = push_json(&server, &token, &client_dir, &["--jsonl", &jsonl_arg]);
+    assert_eq!(receipt["new_messages"], 0);
+    assert_eq!(
+        fs::read_dir(data_dir.join("remotes/ci/mirror/conversations"))
+            .unwrap()
+            .count(),
+        2
+    );
+}
@@ -30,6 +30,9 @@ fn exact_hits_rank_above_wildcards_at_equal_recency_and_score() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let prefix = SearchHit {
@@ -82,6 +85,9 @@ fn recency_boost_can_outweigh_quality_when_far_newer() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let newer_suffix = SearchHit {
@@ -99,6 +105,9 @@ fn recency_boost_can_outweigh_quality_when_far_newer() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let max_created = newer_suffix.created_at.unwrap();
@@ -132,6 +141,9 @@ fn relevance_heavy_mode_prefers_quality_over_recency() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let newer_substring = SearchHit {
@@ -149,6 +161,9 @@ fn relevance_heavy_mode_prefers_quality_over_recency() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let older_score = blended_score(&older_exact, max_created, alpha);
@@ -183,6 +198,9 @@ fn match_quality_heavy_mode_balances_quality_and_recency() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let implicit = SearchHit {
@@ -229,6 +247,9 @@ fn ranking_handles_missing_created_at() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let hit_without_date = SearchHit {
@@ -246,6 +267,9 @@ fn ranking_handles_missing_created_at() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let with_date_score = blended_score(&hit_with_date, max_created, alpha);
@@ -281,6 +305,9 @@ fn ranking_handles_zero_max_created() {
         source_id: "local".into(),
         origin_kind: "local".into(),
         origin_host: None,
+        owner: None,
+        fusion: None,
+        seen_in: Vec::new(),
     };
 
     let score = blended_score(&hit, max_created, alpha);
@@ -314,6 +341,9 @@ fn all_ranking_modes_maintain_quality_ordering_at_equal_inputs() {
             source_id: "local".into(),
             origin_kind: "local".into(),
             origin_host: None,
+            owner: None,
+            fusion: None,
+            seen_in: Vec::new(),
         };
 
         let exact_score = blended_score(&base, max_created, alpha);
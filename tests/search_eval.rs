@@ -0,0 +1,132 @@
+//! `cass eval` against the fixture corpus in `tests/fixtures/eval`.
+//!
+//! The corpus is a handful of Codex sessions with judged queries; the committed
+//! `baseline.json` is the report this tree produces with the hash embedder, so any
+//! ranking change that lowers a metric fails here until the baseline is refreshed:
+//!
+//! ```text
+//! cass eval tests/fixtures/eval/judgments.json --embedder hash --save tests/fixtures/eval/baseline.json
+//! ```
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use coding_agent_search::eval::EvalReport;
+use predicates::str::contains;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/eval")
+}
+
+/// Index the fixture corpus into a fresh data dir.
+fn index_fixture_corpus(home: &Path) -> PathBuf {
+    let data_dir = home.join("cass_data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", fixture_dir().join("codex"))
+        .env("HOME", home)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .assert()
+        .success();
+    data_dir
+}
+
+#[test]
+fn eval_fixture_corpus_has_no_regression_against_baseline() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = index_fixture_corpus(tmp.path());
+
+    let output = cargo_bin_cmd!("cass")
+        .arg("eval")
+        .arg(fixture_dir().join("judgments.json"))
+        .args(["--embedder", "hash", "--json", "--max-regression", "0.0001"])
+        .arg("--baseline")
+        .arg(fixture_dir().join("baseline.json"))
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .env("HOME", tmp.path())
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "eval regressed against baseline:\n{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: Value = serde_json::from_str(&stdout).expect("valid json");
+    let runs = json["runs"].as_array().unwrap();
+    // 3 search modes x 6 ranking modes, none skipped with the hash embedder
+    assert_eq!(runs.len(), 18);
+    assert!(json.get("skipped").is_none());
+    assert!(json["diff"].get("missing_runs").is_none());
+
+    let balanced = runs
+        .iter()
+        .find(|r| r["run"] == "lexical/balanced")
+        .unwrap();
+    assert!(balanced["ndcg"].as_f64().unwrap() > 0.5);
+    assert_eq!(balanced["per_query"].as_array().unwrap().len(), 10);
+}
+
+#[test]
+fn eval_diffs_saved_reports_and_fails_on_regression() {
+    let tmp = TempDir::new().unwrap();
+    let baseline_path = fixture_dir().join("baseline.json");
+    let mut worse = EvalReport::load(&baseline_path).unwrap();
+    worse.runs[0].ndcg -= 0.1;
+    worse.runs[0].per_query[0].ndcg -= 0.5;
+    let worse_path = tmp.path().join("worse.json");
+    std::fs::write(&worse_path, serde_json::to_string(&worse).unwrap()).unwrap();
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["eval", "--json", "--max-regression", "0.05"])
+        .arg("--from-report")
+        .arg(&worse_path)
+        .arg("--baseline")
+        .arg(&baseline_path)
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("eval-regression"));
+
+    let json: Value = serde_json::from_slice(&output.stdout).expect("report still printed");
+    let first = &json["diff"]["runs"][0];
+    assert_eq!(first["run"], worse.runs[0].run.as_str());
+    assert!((first["ndcg"]["delta"].as_f64().unwrap() + 0.1).abs() < 1e-9);
+    assert_eq!(
+        first["changed_queries"][0]["query"],
+        worse.runs[0].per_query[0].query.as_str()
+    );
+
+    // Within tolerance passes
+    cargo_bin_cmd!("cass")
+        .args(["eval", "--json", "--max-regression", "0.5"])
+        .arg("--from-report")
+        .arg(&worse_path)
+        .arg("--baseline")
+        .arg(&baseline_path)
+        .env("HOME", tmp.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn eval_rejects_unknown_ranking_mode() {
+    let tmp = TempDir::new().unwrap();
+    cargo_bin_cmd!("cass")
+        .arg("eval")
+        .arg(fixture_dir().join("judgments.json"))
+        .args(["--ranking", "fastest", "--data-dir"])
+        .arg(tmp.path())
+        .env("HOME", tmp.path())
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(contains("unknown ranking mode 'fastest'"));
+}
@@ -1,7 +1,7 @@
 //! Integration tests for semantic search flows.
 //!
 //! Tests cover:
-//! - CLI models commands (status, verify, check-update)
+//! - CLI models commands (status, list, use, verify, check-update)
 //! - Search mode flags (lexical, semantic, hybrid)
 //! - Determinism tests (same query yields consistent results)
 //! - Robot output schema validation
@@ -248,6 +248,117 @@ fn test_models_help_shows_subcommands() {
     );
 }
 
+/// Test: cass models list includes built-ins and user manifests
+#[test]
+fn test_models_list_includes_user_manifest() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let data_dir = tmp.path().join("cass_data");
+    let manifests = data_dir.join("models/manifests");
+    fs::create_dir_all(&manifests).unwrap();
+    let sha = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+    fs::write(
+        manifests.join("e5.toml"),
+        format!(
+            r#"id = "e5-small-v2"
+repo = "intfloat/e5-small-v2"
+revision = "abc123"
+license = "MIT"
+dimension = 384
+
+[[files]]
+name = "onnx/model.onnx"
+sha256 = "{sha}"
+"#
+        ),
+    )
+    .unwrap();
+    fs::write(manifests.join("broken.toml"), "id = 'x'").unwrap();
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["models", "list", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env_remove("CASS_SEMANTIC_MODEL")
+        .output()
+        .expect("models list command");
+    assert!(output.status.success());
+
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["active"], "all-minilm-l6-v2");
+    let models = json["models"].as_array().unwrap();
+    let ids: Vec<&str> = models.iter().filter_map(|m| m["id"].as_str()).collect();
+    assert_eq!(ids, ["all-minilm-l6-v2", "e5-small-v2"]);
+    let e5 = models.iter().find(|m| m["id"] == "e5-small-v2").unwrap();
+    assert_eq!(e5["embedder_id"], "e5-small-v2-384");
+    assert_eq!(json["manifest_errors"].as_array().unwrap().len(), 1);
+}
+
+/// Test: cass models use switches the active model and its index path
+#[test]
+fn test_models_use_switches_active_model() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let data_dir = tmp.path().join("cass_data");
+    let manifests = data_dir.join("models/manifests");
+    fs::create_dir_all(&manifests).unwrap();
+    let sha = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+    fs::write(
+        manifests.join("bge.toml"),
+        format!(
+            r#"id = "bge-small-en-v1.5"
+repo = "BAAI/bge-small-en-v1.5"
+revision = "abc123"
+license = "MIT"
+dimension = 384
+pooling = "cls"
+embedder_id = "bge-small-384"
+
+[[files]]
+name = "onnx/model.onnx"
+sha256 = "{sha}"
+"#
+        ),
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["models", "use", "bge-small-en-v1.5", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env_remove("CASS_SEMANTIC_MODEL")
+        .output()
+        .expect("models use command");
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["active"], "bge-small-en-v1.5");
+    assert_eq!(json["installed"], false);
+    assert!(
+        json["index_path"]
+            .as_str()
+            .unwrap()
+            .ends_with("index-bge-small-384.cvvi")
+    );
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["models", "status", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env_remove("CASS_SEMANTIC_MODEL")
+        .output()
+        .expect("models status command");
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["model_id"], "bge-small-en-v1.5");
+    assert_eq!(json["active"], true);
+    assert_eq!(json["dimension"], 384);
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["models", "use", "no-such-model", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .output()
+        .expect("models use unknown");
+    assert_eq!(output.status.code(), Some(20));
+}
+
 // =============================================================================
 // Search Mode Flag Tests
 // =============================================================================
@@ -1009,6 +1120,32 @@ fn test_models_install_from_file_error() {
     );
 }
 
+/// Test: models install only knows pinned models, pointing at custom manifests for others
+#[test]
+fn test_models_install_unknown_model_points_at_manifests() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let data_dir = tmp.path().join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["models", "install", "--model", "bge-small-en-v1.5"])
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .arg("-y")
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .output()
+        .expect("models install unknown model command");
+
+    assert_eq!(output.status.code(), Some(20));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("manifests"),
+        "Error should point at the manifests dir. Got: {}",
+        stderr
+    );
+    assert!(!data_dir.join("models/bge-small-en-v1.5").exists());
+}
+
 /// Test: models install --from-file with non-existent file fails appropriately
 #[test]
 fn test_models_install_from_file_missing_file() {
@@ -1035,6 +1172,168 @@ fn test_models_install_from_file_missing_file() {
     );
 }
 
+// =============================================================================
+// Topics Command Tests
+// =============================================================================
+
+/// Test: cass topics clusters sessions from the vector index and labels them
+#[test]
+fn test_topics_command_clusters_sessions() {
+    use coding_agent_search::search::fastembed_embedder::FastEmbedder;
+    use coding_agent_search::search::vector_index::{
+        Quantization, VectorEntry, VectorIndex, vector_index_path,
+    };
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    let sessions = [
+        ("rollout-k8s-1.jsonl", "kubernetes deployment rollout"),
+        ("rollout-k8s-2.jsonl", "kubernetes rollout namespace"),
+        ("rollout-css-1.jsonl", "flexbox stylesheet layout"),
+        ("rollout-css-2.jsonl", "flexbox stylesheet gradient"),
+    ];
+    for (i, (file, content)) in sessions.iter().enumerate() {
+        make_codex_session(
+            &codex_home,
+            "2024/11/20",
+            file,
+            content,
+            1732118400000 + i as u64 * 10_000,
+        );
+    }
+
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .assert()
+        .success();
+
+    // Hand-build a vector index: k8s sessions point one way, css sessions the other.
+    let conn = rusqlite::Connection::open(data_dir.join("agent_search.db")).unwrap();
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.id, c.source_path FROM messages m
+             JOIN conversations c ON m.conversation_id = c.id",
+        )
+        .unwrap();
+    let entries: Vec<VectorEntry> = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .unwrap()
+        .map(|row| {
+            let (id, path) = row.unwrap();
+            let vector = if path.contains("k8s") {
+                vec![1.0, 0.0]
+            } else {
+                vec![0.0, 1.0]
+            };
+            VectorEntry {
+                message_id: id as u64,
+                created_at_ms: 0,
+                agent_id: 0,
+                workspace_id: 0,
+                source_id: 0,
+                role: 0,
+                chunk_idx: 0,
+                content_hash: [id as u8; 32],
+                vector,
+            }
+        })
+        .collect();
+    assert!(!entries.is_empty(), "indexing should store messages");
+    let embedder_id = FastEmbedder::embedder_id_static();
+    let index = VectorIndex::build(embedder_id, "test", 2, Quantization::F32, entries).unwrap();
+    let index_path = vector_index_path(&data_dir, embedder_id);
+    fs::create_dir_all(index_path.parent().unwrap()).unwrap();
+    index.save(&index_path).unwrap();
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["topics", "--k", "2", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("HOME", home)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .output()
+        .expect("topics command");
+    assert!(
+        output.status.success(),
+        "topics should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid topics JSON");
+    assert_eq!(json["sessions"], 4);
+    let topics = json["topics"].as_array().expect("topics array");
+    assert_eq!(topics.len(), 2);
+    for topic in topics {
+        assert_eq!(topic["size"], 2);
+        let paths: Vec<&str> = topic["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["source_path"].as_str().unwrap())
+            .collect();
+        let terms: Vec<&str> = topic["terms"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t.as_str().unwrap())
+            .collect();
+        if paths.iter().all(|p| p.contains("k8s")) {
+            assert!(terms.contains(&"kubernetes"), "terms: {terms:?}");
+        } else {
+            assert!(paths.iter().all(|p| p.contains("css")));
+            assert!(terms.contains(&"flexbox"), "terms: {terms:?}");
+        }
+    }
+}
+
+/// Test: cass topics reports semantic-unavailable without a vector index
+#[test]
+fn test_topics_command_without_vector_index() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-topics.jsonl",
+        "topics_without_index",
+        1732118400000,
+    );
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["topics", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("HOME", home)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .output()
+        .expect("topics command");
+    assert_eq!(output.status.code(), Some(15));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("semantic-unavailable"));
+}
+
 // =============================================================================
 // Introspect Tests
 // =============================================================================
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use coding_agent_search::model::types::{Agent, AgentKind, Conversation, Message, MessageRole};
 use coding_agent_search::sources::provenance::{LOCAL_SOURCE_ID, Source, SourceKind};
-use coding_agent_search::storage::sqlite::SqliteStorage;
+use coding_agent_search::storage::sqlite::{SqliteStorage, conversation_content_hash};
 
 fn sample_agent() -> Agent {
     Agent {
@@ -29,6 +29,7 @@ fn sample_conv(external_id: Option<&str>, messages: Vec<Message>) -> Conversatio
         messages,
         source_id: "local".to_string(),
         origin_host: None,
+        owner: None,
     }
 }
 
@@ -795,6 +796,7 @@ fn upsert_and_get_source() {
         host_label: Some("user@laptop.local".to_string()),
         machine_id: Some("abc123".to_string()),
         platform: Some("linux".to_string()),
+        owner: Some("alice".to_string()),
         config_json: Some(serde_json::json!({"port": 22})),
         created_at: None,
         updated_at: None,
@@ -813,6 +815,7 @@ fn upsert_and_get_source() {
     assert_eq!(retrieved.host_label, Some("user@laptop.local".to_string()));
     assert_eq!(retrieved.machine_id, Some("abc123".to_string()));
     assert_eq!(retrieved.platform, Some("linux".to_string()));
+    assert_eq!(retrieved.owner, Some("alice".to_string()));
     assert!(retrieved.config_json.is_some());
     assert!(retrieved.created_at.is_some());
     assert!(retrieved.updated_at.is_some());
@@ -831,6 +834,7 @@ fn upsert_updates_existing_source() {
         host_label: Some("old-label".to_string()),
         machine_id: None,
         platform: None,
+        owner: None,
         config_json: None,
         created_at: None,
         updated_at: None,
@@ -850,6 +854,7 @@ fn upsert_updates_existing_source() {
         host_label: Some("new-label".to_string()),
         machine_id: Some("machine-id".to_string()),
         platform: Some("macos".to_string()),
+        owner: None,
         config_json: None,
         created_at: first_created, // Preserve original created_at
         updated_at: None,
@@ -1380,6 +1385,7 @@ fn sample_conv_with_source(
         } else {
             None
         },
+        owner: None,
     }
 }
 
@@ -1878,3 +1884,170 @@ fn timeline_json_grouped_output_includes_provenance() {
     assert!(has_local, "should have local entry with kind='local'");
     assert!(has_remote, "should have remote entry with kind='ssh'");
 }
+
+// =============================================================================
+// Cross-source deduplication
+// =============================================================================
+
+fn count(storage: &SqliteStorage, sql: &str) -> i64 {
+    storage.raw().query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
+#[test]
+fn duplicate_session_from_another_source_is_linked() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let mut storage = SqliteStorage::open(&tmp.path().join("dedup.db")).expect("open");
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    for id in ["laptop", "desktop"] {
+        storage
+            .upsert_source(&Source::remote(id, format!("{id}.local")))
+            .unwrap();
+    }
+
+    let local = sample_conv_with_source("shared", "local", 100, vec![msg(0, 100), msg(1, 101)]);
+    let first = storage
+        .insert_conversation_tree(agent_id, None, &local)
+        .unwrap();
+    assert!(!first.linked);
+    assert_eq!(first.inserted_indices, vec![0, 1]);
+
+    // The synced mirror of the same session is linked, not stored again
+    let mirror = sample_conv_with_source("shared", "laptop", 100, vec![msg(0, 100), msg(1, 101)]);
+    let second = storage
+        .insert_conversation_tree(agent_id, None, &mirror)
+        .unwrap();
+    assert!(second.linked);
+    assert_eq!(second.conversation_id, first.conversation_id);
+    assert!(second.inserted_indices.is_empty());
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM conversations"), 1);
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM messages"), 2);
+    assert_eq!(
+        count(
+            &storage,
+            "SELECT COUNT(*) FROM conversation_sources WHERE source_id = 'laptop'"
+        ),
+        1
+    );
+
+    // When the linked copy grows first, its new messages extend the stored one
+    let grown = sample_conv_with_source(
+        "shared",
+        "laptop",
+        100,
+        vec![msg(0, 100), msg(1, 101), msg(2, 102)],
+    );
+    let third = storage
+        .insert_conversation_tree(agent_id, None, &grown)
+        .unwrap();
+    assert!(third.linked);
+    assert_eq!(third.conversation_id, first.conversation_id);
+    assert_eq!(third.inserted_indices, vec![2]);
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM conversations"), 1);
+
+    // Same external id but different content is a different session
+    let mut other = sample_conv_with_source("shared", "desktop", 100, vec![msg(0, 100)]);
+    other.messages[0].content = "something else".into();
+    let fourth = storage
+        .insert_conversation_tree(agent_id, None, &other)
+        .unwrap();
+    assert!(!fourth.linked);
+    assert_ne!(fourth.conversation_id, first.conversation_id);
+}
+
+#[test]
+fn growing_session_from_another_source_is_linked() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let mut storage = SqliteStorage::open(&tmp.path().join("dedup.db")).expect("open");
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    storage
+        .upsert_source(&Source::remote("laptop", "laptop.local"))
+        .unwrap();
+
+    let local = sample_conv_with_source(
+        "live",
+        "local",
+        100,
+        vec![msg(0, 100), msg(1, 101), msg(2, 102)],
+    );
+    let first = storage
+        .insert_conversation_tree(agent_id, None, &local)
+        .unwrap();
+
+    // The mirror lags behind the live file, so the full hashes differ
+    let behind = sample_conv_with_source("live", "laptop", 100, vec![msg(0, 100), msg(1, 101)]);
+    let second = storage
+        .insert_conversation_tree(agent_id, None, &behind)
+        .unwrap();
+    assert!(second.linked);
+    assert_eq!(second.conversation_id, first.conversation_id);
+    assert!(second.inserted_indices.is_empty());
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM conversations"), 1);
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM messages"), 3);
+    // A lagging copy leaves the stored fingerprint alone
+    let stored_hash: String = storage
+        .raw()
+        .query_row("SELECT content_hash FROM conversations", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(stored_hash, conversation_content_hash(&local));
+}
+
+#[test]
+fn migration_backfills_content_hashes() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("legacy.db");
+    let conv = sample_conv_with_source("old", "local", 100, vec![msg(0, 100), msg(1, 101)]);
+    {
+        let mut storage = SqliteStorage::open(&db_path).expect("open");
+        let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+        storage
+            .insert_conversation_tree(agent_id, None, &conv)
+            .unwrap();
+        // Roll the schema back to v6, before sessions were fingerprinted
+        storage
+            .raw()
+            .execute_batch(
+                "DROP INDEX idx_conversations_content_hash;
+                 ALTER TABLE conversations DROP COLUMN content_hash;
+                 DROP INDEX idx_conversations_owner;
+                 ALTER TABLE conversations DROP COLUMN owner;
+                 ALTER TABLE sources DROP COLUMN owner;
+                 UPDATE meta SET value = '6' WHERE key = 'schema_version';",
+            )
+            .unwrap();
+    }
+
+    let storage = SqliteStorage::open(&db_path).expect("migrate");
+    let stored_hash: String = storage
+        .raw()
+        .query_row("SELECT content_hash FROM conversations", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(stored_hash, conversation_content_hash(&conv));
+}
+
+#[test]
+fn duplicate_sessions_in_one_batch_are_stored_once() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let mut storage = SqliteStorage::open(&tmp.path().join("dedup.db")).expect("open");
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    for id in ["laptop", "desktop"] {
+        storage
+            .upsert_source(&Source::remote(id, format!("{id}.local")))
+            .unwrap();
+    }
+
+    let local = sample_conv_with_source("shared", "local", 100, vec![msg(0, 100)]);
+    let mirror = sample_conv_with_source("shared", "laptop", 100, vec![msg(0, 100)]);
+    let outcomes = storage
+        .insert_conversations_batched(&[(agent_id, None, &local), (agent_id, None, &mirror)])
+        .unwrap();
+
+    assert!(!outcomes[0].linked);
+    assert!(outcomes[1].linked);
+    assert_eq!(outcomes[0].conversation_id, outcomes[1].conversation_id);
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM conversations"), 1);
+    assert_eq!(count(&storage, "SELECT COUNT(*) FROM fts_messages"), 1);
+}
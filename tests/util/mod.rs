@@ -354,6 +354,7 @@ impl ConversationFixtureBuilder {
             messages,
             source_id: "local".to_string(),
             origin_host: None,
+            owner: None,
         }
     }
 }